@@ -3,7 +3,9 @@ use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 
 use crate::config::SimulationConfig;
-use crate::sweep::deterministic_drive;
+use crate::output::{write_aet_csv, RobustnessMetricRow};
+use crate::subtheory::{curve_diff_metrics, OutputTarget, PerturbedPair, SubTheory, SweepProgress};
+use crate::sweep::{deterministic_drive, SweepRunResult};
 use crate::AddError;
 
 pub const AET_PERTURBATION_STRENGTH: f64 = 0.035;
@@ -53,6 +55,86 @@ where
     run_aet_sweep_with_perturbation(config, lambda_grid, AET_PERTURBATION_STRENGTH, progress)
 }
 
+/// AET as a [`SubTheory`]: baseline and perturbed echo-slope sweeps.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AetTheory;
+
+impl SubTheory for AetTheory {
+    type Curves = PerturbedPair<AetSweep>;
+
+    fn name(&self) -> &'static str {
+        "AET"
+    }
+
+    fn is_enabled(&self, config: &SimulationConfig) -> bool {
+        config.enable_aet
+    }
+
+    fn sweep_count(&self) -> usize {
+        2
+    }
+
+    fn run(
+        &self,
+        config: &SimulationConfig,
+        lambda_grid: &[f64],
+        progress: &mut SweepProgress<'_>,
+    ) -> Result<Self::Curves, AddError> {
+        let baseline = progress.sweep("AET baseline", |report| {
+            run_aet_sweep_with_progress(config, lambda_grid, report)
+        })?;
+        let perturbed = progress.sweep("AET perturbed", |report| {
+            run_aet_sweep_perturbed_with_progress(config, lambda_grid, report)
+        })?;
+        Ok(PerturbedPair {
+            baseline,
+            perturbed,
+        })
+    }
+
+    fn write_outputs(
+        &self,
+        curves: &Self::Curves,
+        target: &OutputTarget<'_>,
+    ) -> Result<(), AddError> {
+        for (stem, sweep, is_perturbed) in [
+            ("aet_sweep", &curves.baseline, false),
+            ("aet_sweep_perturbed", &curves.perturbed, true),
+        ] {
+            for path in target.csv_paths(stem) {
+                write_aet_csv(
+                    &path,
+                    target.lambda_grid,
+                    &sweep.echo_slope,
+                    &sweep.avg_increment,
+                    target.config.steps_per_run,
+                    is_perturbed,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn robustness_metrics(
+        &self,
+        curves: &Self::Curves,
+        _lambda_grid: &[f64],
+        steps_per_run: usize,
+    ) -> Result<Vec<RobustnessMetricRow>, AddError> {
+        Ok(curve_diff_metrics(
+            "aet",
+            steps_per_run,
+            &curves.baseline.echo_slope,
+            &curves.perturbed.echo_slope,
+        ))
+    }
+
+    fn record(&self, curves: Self::Curves, run: &mut SweepRunResult) {
+        run.aet = Some(curves.baseline);
+        run.aet_perturbed = Some(curves.perturbed);
+    }
+}
+
 fn run_aet_sweep_with_perturbation<F>(
     config: &SimulationConfig,
     lambda_grid: &[f64],
@@ -68,7 +150,12 @@ where
 
     for (idx, &lambda) in lambda_grid.iter().enumerate() {
         let lambda_norm = config.normalized_lambda(lambda);
-        let drive = deterministic_drive(config.random_seed, lambda, 0xAE70_u64 + idx as u64);
+        let drive = deterministic_drive(
+            &config.drive,
+            config.random_seed,
+            lambda,
+            0xAE70_u64 + idx as u64,
+        );
         let mut rng = StdRng::seed_from_u64(config.random_seed ^ 0xA370_0000_u64 ^ idx as u64);
 
         let mut word = reduce_word(&[Symbol::A]);
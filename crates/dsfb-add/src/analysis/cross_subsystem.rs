@@ -0,0 +1,165 @@
+use crate::analysis::structural_law::{correlation, spearman_correlation};
+use crate::AddError;
+
+/// Dependence statistics between the AET echo-slope and IWLT entropy-density
+/// curves sampled on the same lambda grid.
+#[derive(Debug, Clone, Copy)]
+pub struct CrossSubsystemStats {
+    pub pearson_r: f64,
+    pub spearman_rho: f64,
+    /// Largest-magnitude Pearson correlation over the lags in `-max_lag..=max_lag`.
+    pub peak_xcorr: f64,
+    /// Lag, in lambda samples, at which `peak_xcorr` occurs. Positive means
+    /// the IWLT curve trails the AET curve.
+    pub peak_lag: isize,
+    /// `peak_lag` expressed in lambda units.
+    pub peak_lag_lambda: f64,
+    /// Histogram estimate of the mutual information, in bits.
+    pub mutual_information: f64,
+    /// Mutual information divided by `sqrt(H(aet) * H(iwlt))`, in `[0, 1]`.
+    pub normalized_mutual_information: f64,
+    pub histogram_bins: usize,
+    pub sample_count: usize,
+}
+
+/// Compute correlation, lagged cross-correlation, and mutual-information
+/// statistics between two curves over `lambda_grid`.
+///
+/// Lags are searched up to a quarter of the grid so every lagged correlation
+/// keeps at least three quarters of the samples. The mutual information uses
+/// equal-width histograms with Sturges' bin count.
+pub fn cross_subsystem_stats(
+    lambda_grid: &[f64],
+    echo_slope: &[f64],
+    entropy_density: &[f64],
+) -> Result<CrossSubsystemStats, AddError> {
+    for (context, got) in [
+        ("cross-subsystem echo slope", echo_slope.len()),
+        ("cross-subsystem entropy density", entropy_density.len()),
+    ] {
+        if got != lambda_grid.len() {
+            return Err(AddError::LengthMismatch {
+                context,
+                expected: lambda_grid.len(),
+                got,
+            });
+        }
+    }
+    let sample_count = lambda_grid.len();
+    if sample_count < 2 {
+        return Err(AddError::InvalidConfig(
+            "cross-subsystem analysis requires at least two lambda samples".to_string(),
+        ));
+    }
+
+    let max_lag = (sample_count / 4) as isize;
+    let (peak_lag, peak_xcorr) = (-max_lag..=max_lag)
+        .map(|lag| (lag, lagged_correlation(echo_slope, entropy_density, lag)))
+        .fold((0, 0.0_f64), |best, candidate| {
+            if candidate.1.abs() > best.1.abs() + 1.0e-12 {
+                candidate
+            } else {
+                best
+            }
+        });
+    let lag_span = lambda_grid[peak_lag.unsigned_abs()] - lambda_grid[0];
+    let peak_lag_lambda = if peak_lag < 0 { -lag_span } else { lag_span };
+
+    let histogram_bins = sturges_bins(sample_count);
+    let x_bins = bin_indices(echo_slope, histogram_bins);
+    let y_bins = bin_indices(entropy_density, histogram_bins);
+    let mutual_information = mutual_information_bits(&x_bins, &y_bins, histogram_bins);
+    let entropy_scale =
+        (entropy_bits(&x_bins, histogram_bins) * entropy_bits(&y_bins, histogram_bins)).sqrt();
+    let normalized_mutual_information = if entropy_scale <= f64::EPSILON {
+        0.0
+    } else {
+        (mutual_information / entropy_scale).clamp(0.0, 1.0)
+    };
+
+    Ok(CrossSubsystemStats {
+        pearson_r: correlation(echo_slope, entropy_density),
+        spearman_rho: spearman_correlation(echo_slope, entropy_density),
+        peak_xcorr,
+        peak_lag,
+        peak_lag_lambda,
+        mutual_information,
+        normalized_mutual_information,
+        histogram_bins,
+        sample_count,
+    })
+}
+
+/// Pearson correlation of `xs[i]` against `ys[i + lag]` over the overlap.
+fn lagged_correlation(xs: &[f64], ys: &[f64], lag: isize) -> f64 {
+    let shift = lag.unsigned_abs();
+    if lag >= 0 {
+        correlation(&xs[..xs.len() - shift], &ys[shift..])
+    } else {
+        correlation(&xs[shift..], &ys[..ys.len() - shift])
+    }
+}
+
+fn sturges_bins(sample_count: usize) -> usize {
+    (sample_count as f64).log2().ceil() as usize + 1
+}
+
+fn bin_indices(values: &[f64], bins: usize) -> Vec<usize> {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let width = max - min;
+
+    values
+        .iter()
+        .map(|value| {
+            if width <= f64::EPSILON {
+                0
+            } else {
+                (((value - min) / width * bins as f64) as usize).min(bins - 1)
+            }
+        })
+        .collect()
+}
+
+fn entropy_bits(indices: &[usize], bins: usize) -> f64 {
+    let mut counts = vec![0_usize; bins];
+    for &index in indices {
+        counts[index] += 1;
+    }
+    let total = indices.len() as f64;
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+fn mutual_information_bits(x_bins: &[usize], y_bins: &[usize], bins: usize) -> f64 {
+    let mut joint = vec![0_usize; bins * bins];
+    let mut x_counts = vec![0_usize; bins];
+    let mut y_counts = vec![0_usize; bins];
+    for (&x, &y) in x_bins.iter().zip(y_bins.iter()) {
+        joint[x * bins + y] += 1;
+        x_counts[x] += 1;
+        y_counts[y] += 1;
+    }
+
+    let total = x_bins.len() as f64;
+    let mut information = 0.0;
+    for x in 0..bins {
+        for y in 0..bins {
+            let count = joint[x * bins + y];
+            if count == 0 {
+                continue;
+            }
+            let p_xy = count as f64 / total;
+            let p_x = x_counts[x] as f64 / total;
+            let p_y = y_counts[y] as f64 / total;
+            information += p_xy * (p_xy / (p_x * p_y)).log2();
+        }
+    }
+    information.max(0.0)
+}
@@ -1,2 +1,3 @@
+pub mod cross_subsystem;
 pub mod rlt_phase;
 pub mod structural_law;
@@ -189,7 +189,7 @@ fn stddev(values: &[f64], mean_value: f64) -> f64 {
         .sqrt()
 }
 
-fn correlation(xs: &[f64], ys: &[f64]) -> f64 {
+pub(crate) fn correlation(xs: &[f64], ys: &[f64]) -> f64 {
     let x_mean = mean(xs);
     let y_mean = mean(ys);
     let covariance = xs
@@ -221,7 +221,7 @@ fn correlation(xs: &[f64], ys: &[f64]) -> f64 {
     }
 }
 
-fn spearman_correlation(xs: &[f64], ys: &[f64]) -> f64 {
+pub(crate) fn spearman_correlation(xs: &[f64], ys: &[f64]) -> f64 {
     let x_ranks = average_ranks(xs);
     let y_ranks = average_ranks(ys);
     correlation(&x_ranks, &y_ranks)
@@ -1,7 +1,10 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use dsfb_add::{create_timestamped_output_dir, run_sweeps_into_dir, AddError, SimulationConfig};
+use dsfb_add::{
+    create_timestamped_output_dir, run_sweeps_into_dir, AddError, GraphExportFormat,
+    SimulationConfig,
+};
 
 fn main() {
     if let Err(error) = try_main() {
@@ -16,6 +19,9 @@ fn try_main() -> Result<(), AddError> {
     if let Some(multi_steps_per_run) = cli.multi_steps_per_run {
         config.multi_steps_per_run = multi_steps_per_run;
     }
+    if let Some(format) = cli.rlt_graph_export {
+        config.rlt_graph_export = format;
+    }
     config.validate()?;
 
     let output_dir = create_timestamped_output_dir()?;
@@ -28,6 +34,7 @@ fn try_main() -> Result<(), AddError> {
 struct CliArgs {
     config_path: Option<PathBuf>,
     multi_steps_per_run: Option<Vec<usize>>,
+    rlt_graph_export: Option<GraphExportFormat>,
 }
 
 fn parse_cli<I>(args: I) -> Result<CliArgs, AddError>
@@ -37,6 +44,7 @@ where
     let mut iter = args.into_iter();
     let mut config_path = None;
     let mut multi_steps_per_run = None;
+    let mut rlt_graph_export = None;
 
     while let Some(arg) = iter.next() {
         match arg.as_str() {
@@ -52,6 +60,12 @@ where
                     .ok_or_else(|| AddError::InvalidConfig(format!("missing value for {arg}")))?;
                 multi_steps_per_run = Some(parse_multi_steps(&raw)?);
             }
+            "--rlt-graph-format" => {
+                let raw = iter.next().ok_or_else(|| {
+                    AddError::InvalidConfig("missing value for --rlt-graph-format".to_string())
+                })?;
+                rlt_graph_export = Some(GraphExportFormat::parse(&raw)?);
+            }
             "--help" | "-h" => {
                 print_help();
                 std::process::exit(0);
@@ -67,6 +81,7 @@ where
     Ok(CliArgs {
         config_path,
         multi_steps_per_run,
+        rlt_graph_export,
     })
 }
 
@@ -121,11 +136,14 @@ fn parse_multi_steps(raw: &str) -> Result<Vec<usize>, AddError> {
 
 fn print_help() {
     println!(
-        "Usage: cargo run -p dsfb-add --bin dsfb_add_sweep -- [--config path/to/config.json] [--steps-per-run-list 512,5000,10000,20000]"
+        "Usage: cargo run -p dsfb-add --bin dsfb_add_sweep -- [--config path/to/config.json] [--steps-per-run-list 512,5000,10000,20000] [--rlt-graph-format edge_list|graphml]"
     );
     println!("If config.json exists in the current directory, it is loaded automatically.");
     println!("Otherwise the built-in deterministic sweep configuration is used.");
     println!(
         "When --steps-per-run-list is provided, per-N sweep files are written with _N{{steps}} suffixes."
     );
+    println!(
+        "When --rlt-graph-format is provided, RLT example adjacency graphs are written next to the trajectory CSVs."
+    );
 }
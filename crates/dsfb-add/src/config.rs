@@ -1,3 +1,4 @@
+use dsfb::DsfbParams;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DefaultOnNull};
 
@@ -27,6 +28,211 @@ pub struct SimulationConfig {
     pub enable_rlt: bool,
     #[serde_as(as = "DefaultOnNull")]
     pub enable_iwlt: bool,
+    #[serde(default)]
+    pub rlt: RltConfig,
+    #[serde(default)]
+    pub drive: DriveConfig,
+    #[serde_as(as = "DefaultOnNull")]
+    pub rlt_graph_export: GraphExportFormat,
+}
+
+/// Optional export format for RLT example trajectory adjacency graphs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphExportFormat {
+    #[default]
+    None,
+    #[serde(alias = "edgelist", alias = "csv")]
+    EdgeList,
+    #[serde(rename = "graphml")]
+    GraphMl,
+}
+
+impl GraphExportFormat {
+    pub fn parse(raw: &str) -> Result<Self, AddError> {
+        match raw {
+            "none" => Ok(Self::None),
+            "edge_list" | "edgelist" | "csv" => Ok(Self::EdgeList),
+            "graphml" => Ok(Self::GraphMl),
+            other => Err(AddError::InvalidConfig(format!(
+                "unknown graph export format: {other} (expected none, edge_list, or graphml)"
+            ))),
+        }
+    }
+}
+
+/// Regime boundaries and step rules for the resonance lattice walk.
+///
+/// Thresholds apply to the normalized (and perturbed) lambda in `[0, 1]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RltConfig {
+    /// Upper bound of the bounded regime.
+    pub bounded_threshold: f64,
+    /// Lower bound of the expanding regime.
+    pub expanding_threshold: f64,
+    /// Transitional leash radius at `lambda = 0`.
+    pub leash_base: i32,
+    /// Leash radius added per unit of normalized lambda.
+    pub leash_growth: f64,
+    /// Transitional reset period is
+    /// `round(reset_period_base - reset_period_slope * lambda)`, clamped to
+    /// `[reset_period_min, reset_period_max]`.
+    pub reset_period_base: f64,
+    pub reset_period_slope: f64,
+    pub reset_period_min: usize,
+    pub reset_period_max: usize,
+}
+
+impl Default for RltConfig {
+    fn default() -> Self {
+        Self {
+            bounded_threshold: 0.22,
+            expanding_threshold: 0.58,
+            leash_base: 2,
+            leash_growth: 10.0,
+            reset_period_base: 16.0,
+            reset_period_slope: 10.0,
+            reset_period_min: 6,
+            reset_period_max: 16,
+        }
+    }
+}
+
+impl RltConfig {
+    pub fn validate(&self) -> Result<(), AddError> {
+        let finite = [
+            self.bounded_threshold,
+            self.expanding_threshold,
+            self.leash_growth,
+            self.reset_period_base,
+            self.reset_period_slope,
+        ];
+        if finite.iter().any(|value| !value.is_finite()) {
+            return Err(AddError::InvalidConfig(
+                "rlt thresholds and step-rule coefficients must be finite".to_string(),
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.bounded_threshold)
+            || !(0.0..=1.0).contains(&self.expanding_threshold)
+        {
+            return Err(AddError::InvalidConfig(
+                "rlt bounded_threshold and expanding_threshold must lie in [0, 1]".to_string(),
+            ));
+        }
+
+        if self.expanding_threshold < self.bounded_threshold {
+            return Err(AddError::InvalidConfig(
+                "rlt expanding_threshold must be greater than or equal to bounded_threshold"
+                    .to_string(),
+            ));
+        }
+
+        if self.leash_base < 0 || self.leash_growth < 0.0 {
+            return Err(AddError::InvalidConfig(
+                "rlt leash_base and leash_growth must be non-negative".to_string(),
+            ));
+        }
+
+        if self.reset_period_min == 0 || self.reset_period_max < self.reset_period_min {
+            return Err(AddError::InvalidConfig(
+                "rlt reset_period_min must be positive and not exceed reset_period_max".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn leash(&self, lambda_norm: f64) -> i32 {
+        self.leash_base + (lambda_norm * self.leash_growth).round() as i32
+    }
+
+    pub fn reset_period(&self, lambda_norm: f64) -> usize {
+        let period = (self.reset_period_base - self.reset_period_slope * lambda_norm).round();
+        (period.max(0.0) as usize).clamp(self.reset_period_min, self.reset_period_max)
+    }
+}
+
+/// DSFB observer run that produces the drive signal shared by the sub-theories.
+///
+/// The observer tracks two forced channels around lambda for `warmup_steps`
+/// steps; its final phase, drift, and trust split bias every sub-theory's
+/// trajectory for that lambda.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DriveConfig {
+    /// Observer gains, envelope factor, and trust softness.
+    pub observer: DsfbParams,
+    /// Observer steps taken before the drive is read out.
+    pub warmup_steps: usize,
+    /// Forcing amplitude of each of the two drive channels.
+    pub channel_amplitudes: [f64; 2],
+}
+
+impl Default for DriveConfig {
+    fn default() -> Self {
+        Self {
+            observer: DsfbParams::new(0.35, 0.08, 0.01, 0.92, 0.15),
+            warmup_steps: 24,
+            channel_amplitudes: [0.32, 0.27],
+        }
+    }
+}
+
+impl DriveConfig {
+    pub fn validate(&self) -> Result<(), AddError> {
+        let observer = &self.observer;
+        let gains = [observer.k_phi, observer.k_omega, observer.k_alpha];
+        if gains.iter().any(|gain| !gain.is_finite()) {
+            return Err(AddError::InvalidConfig(
+                "drive observer gains must be finite".to_string(),
+            ));
+        }
+
+        if !(observer.rho > 0.0 && observer.rho < 1.0) {
+            return Err(AddError::InvalidConfig(
+                "drive observer rho must lie in (0, 1)".to_string(),
+            ));
+        }
+
+        if !(observer.sigma0.is_finite() && observer.sigma0 > 0.0) {
+            return Err(AddError::InvalidConfig(
+                "drive observer sigma0 must be finite and positive".to_string(),
+            ));
+        }
+
+        // The drive has two channels, so a floor of 1/2 or more forces uniform weights
+        if !(observer.min_weight >= 0.0 && observer.min_weight < 0.5) {
+            return Err(AddError::InvalidConfig(
+                "drive observer min_weight must lie in [0, 0.5)".to_string(),
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&observer.staleness_decay) {
+            return Err(AddError::InvalidConfig(
+                "drive observer staleness_decay must lie in [0, 1]".to_string(),
+            ));
+        }
+
+        if self.warmup_steps == 0 {
+            return Err(AddError::InvalidConfig(
+                "drive warmup_steps must be greater than zero".to_string(),
+            ));
+        }
+
+        if self
+            .channel_amplitudes
+            .iter()
+            .any(|amplitude| !amplitude.is_finite() || *amplitude < 0.0)
+        {
+            return Err(AddError::InvalidConfig(
+                "drive channel_amplitudes must be finite and non-negative".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for SimulationConfig {
@@ -42,6 +248,9 @@ impl Default for SimulationConfig {
             enable_tcp: true,
             enable_rlt: true,
             enable_iwlt: true,
+            rlt: RltConfig::default(),
+            drive: DriveConfig::default(),
+            rlt_graph_export: GraphExportFormat::None,
         }
     }
 }
@@ -84,6 +293,9 @@ impl SimulationConfig {
             ));
         }
 
+        self.rlt.validate()?;
+        self.drive.validate()?;
+
         Ok(())
     }
 
@@ -3,7 +3,9 @@ use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 
 use crate::config::SimulationConfig;
-use crate::sweep::deterministic_drive;
+use crate::output::{write_iwlt_csv, RobustnessMetricRow};
+use crate::subtheory::{curve_diff_metrics, OutputTarget, PerturbedPair, SubTheory, SweepProgress};
+use crate::sweep::{deterministic_drive, SweepRunResult};
 use crate::AddError;
 
 pub const IWLT_PERTURBATION_STRENGTH: f64 = 0.03;
@@ -57,6 +59,86 @@ where
     run_iwlt_sweep_with_perturbation(config, lambda_grid, IWLT_PERTURBATION_STRENGTH, progress)
 }
 
+/// IWLT as a [`SubTheory`]: baseline and perturbed entropy-density sweeps.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IwltTheory;
+
+impl SubTheory for IwltTheory {
+    type Curves = PerturbedPair<IwltSweep>;
+
+    fn name(&self) -> &'static str {
+        "IWLT"
+    }
+
+    fn is_enabled(&self, config: &SimulationConfig) -> bool {
+        config.enable_iwlt
+    }
+
+    fn sweep_count(&self) -> usize {
+        2
+    }
+
+    fn run(
+        &self,
+        config: &SimulationConfig,
+        lambda_grid: &[f64],
+        progress: &mut SweepProgress<'_>,
+    ) -> Result<Self::Curves, AddError> {
+        let baseline = progress.sweep("IWLT baseline", |report| {
+            run_iwlt_sweep_with_progress(config, lambda_grid, report)
+        })?;
+        let perturbed = progress.sweep("IWLT perturbed", |report| {
+            run_iwlt_sweep_perturbed_with_progress(config, lambda_grid, report)
+        })?;
+        Ok(PerturbedPair {
+            baseline,
+            perturbed,
+        })
+    }
+
+    fn write_outputs(
+        &self,
+        curves: &Self::Curves,
+        target: &OutputTarget<'_>,
+    ) -> Result<(), AddError> {
+        for (stem, sweep, is_perturbed) in [
+            ("iwlt_sweep", &curves.baseline, false),
+            ("iwlt_sweep_perturbed", &curves.perturbed, true),
+        ] {
+            for path in target.csv_paths(stem) {
+                write_iwlt_csv(
+                    &path,
+                    target.lambda_grid,
+                    &sweep.entropy_density,
+                    &sweep.avg_increment,
+                    target.config.steps_per_run,
+                    is_perturbed,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn robustness_metrics(
+        &self,
+        curves: &Self::Curves,
+        _lambda_grid: &[f64],
+        steps_per_run: usize,
+    ) -> Result<Vec<RobustnessMetricRow>, AddError> {
+        Ok(curve_diff_metrics(
+            "iwlt",
+            steps_per_run,
+            &curves.baseline.entropy_density,
+            &curves.perturbed.entropy_density,
+        ))
+    }
+
+    fn record(&self, curves: Self::Curves, run: &mut SweepRunResult) {
+        run.iwlt = Some(curves.baseline);
+        run.iwlt_perturbed = Some(curves.perturbed);
+    }
+}
+
 fn run_iwlt_sweep_with_perturbation<F>(
     config: &SimulationConfig,
     lambda_grid: &[f64],
@@ -72,7 +154,12 @@ where
 
     for (idx, &lambda) in lambda_grid.iter().enumerate() {
         let lambda_norm = config.normalized_lambda(lambda);
-        let drive = deterministic_drive(config.random_seed, lambda, 0x1A17_u64 + idx as u64);
+        let drive = deterministic_drive(
+            &config.drive,
+            config.random_seed,
+            lambda,
+            0x1A17_u64 + idx as u64,
+        );
         let mut rng = StdRng::seed_from_u64(config.random_seed ^ 0x1A17_0000_u64 ^ idx as u64);
 
         let mut history: Vec<Event> = Vec::new();
@@ -4,36 +4,21 @@ pub mod config;
 pub mod iwlt;
 pub mod output;
 pub mod rlt;
+pub mod subtheory;
 pub mod sweep;
 pub mod tcp;
 
-use thiserror::Error;
-
-pub use aet::AetSweep;
-pub use config::SimulationConfig;
-pub use iwlt::IwltSweep;
+pub use aet::{AetSweep, AetTheory};
+pub use config::{DriveConfig, GraphExportFormat, RltConfig, SimulationConfig};
+pub use iwlt::{IwltSweep, IwltTheory};
 pub use output::create_timestamped_output_dir;
-pub use rlt::RltSweep;
-pub use sweep::{run_sweeps_into_dir, SweepResult};
-pub use tcp::{TcpPoint, TcpSweep};
+pub use rlt::{RltGraph, RltSweep, RltTheory};
+pub use subtheory::{default_registry, find_subtheory, RegisteredSubTheory, SubTheory};
+pub use sweep::{run_sweeps_into_dir, run_sweeps_with_registry, SweepResult};
+pub use tcp::{TcpPoint, TcpSweep, TcpTheory};
 
-#[derive(Debug, Error)]
-pub enum AddError {
-    #[error("io error: {0}")]
-    Io(#[from] std::io::Error),
-    #[error("csv error: {0}")]
-    Csv(#[from] csv::Error),
-    #[error("json error: {0}")]
-    Json(#[from] serde_json::Error),
-    #[error("invalid configuration: {0}")]
-    InvalidConfig(String),
-    #[error("{context} length mismatch: expected {expected}, got {got}")]
-    LengthMismatch {
-        context: &'static str,
-        expected: usize,
-        got: usize,
-    },
-}
+/// Error of the ADD sweeps; the workspace-wide [`dsfb_error::Error`].
+pub use dsfb_error::Error as AddError;
 
 pub fn run_all_sweeps(config: &SimulationConfig) -> Result<(), AddError> {
     let output_dir = create_timestamped_output_dir()?;
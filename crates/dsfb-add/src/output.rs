@@ -3,8 +3,19 @@ use std::path::{Path, PathBuf};
 
 use chrono::Utc;
 use csv::Writer;
-
-use crate::{rlt::RltTrajectoryPoint, AddError, TcpPoint};
+use serde::Serialize;
+
+use crate::rlt::{RltGraph, RltTrajectoryPoint};
+use crate::{AddError, GraphExportFormat, SimulationConfig, TcpPoint};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunManifest<'a> {
+    pub crate_version: &'static str,
+    pub config: &'a SimulationConfig,
+    /// Wall-clock time of the whole sweep, filled in once it completes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_runtime_s: Option<f64>,
+}
 
 #[derive(Debug, Clone)]
 pub struct PhaseBoundaryRow {
@@ -57,6 +68,21 @@ pub struct CrossLayerThresholdRow {
     pub entropy_density_star: Option<f64>,
 }
 
+#[derive(Debug, Clone)]
+pub struct CrossSubsystemRow {
+    pub steps_per_run: usize,
+    pub is_perturbed: bool,
+    pub pearson_r: f64,
+    pub spearman_rho: f64,
+    pub peak_xcorr: f64,
+    pub peak_lag: isize,
+    pub peak_lag_lambda: f64,
+    pub mutual_information: f64,
+    pub normalized_mutual_information: f64,
+    pub histogram_bins: usize,
+    pub sample_count: usize,
+}
+
 #[derive(Debug, Clone)]
 pub struct TcpPhaseAlignmentRow {
     pub steps_per_run: usize,
@@ -76,6 +102,17 @@ pub struct RobustnessMetricRow {
     pub delta: f64,
 }
 
+/// Wall-clock time of one lambda sample within one sub-theory sweep.
+#[derive(Debug, Clone)]
+pub struct SubsystemTimingRow {
+    pub steps_per_run: usize,
+    pub subsystem: String,
+    pub stage: String,
+    pub lambda_index: usize,
+    pub lambda: f64,
+    pub elapsed_s: f64,
+}
+
 pub fn repo_root_dir() -> PathBuf {
     let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     manifest_dir
@@ -102,6 +139,21 @@ pub fn create_timestamped_output_dir() -> Result<PathBuf, AddError> {
     Ok(output_dir)
 }
 
+/// Writes `manifest.json` echoing the full configuration used for the run.
+pub fn write_manifest_json(
+    path: &Path,
+    config: &SimulationConfig,
+    total_runtime_s: Option<f64>,
+) -> Result<(), AddError> {
+    let manifest = RunManifest {
+        crate_version: env!("CARGO_PKG_VERSION"),
+        config,
+        total_runtime_s,
+    };
+    fs::write(path, serde_json::to_string_pretty(&manifest)?)?;
+    Ok(())
+}
+
 fn ensure_len(context: &'static str, expected: usize, actual: usize) -> Result<(), AddError> {
     if expected == actual {
         return Ok(());
@@ -327,6 +379,104 @@ pub fn write_rlt_trajectory_csv(
     Ok(())
 }
 
+pub fn write_rlt_graph_edge_list_csv(path: &Path, graph: &RltGraph) -> Result<(), AddError> {
+    let mut writer = Writer::from_path(path)?;
+    writer.write_record([
+        "source",
+        "target",
+        "source_x",
+        "source_y",
+        "target_x",
+        "target_y",
+        "first_step",
+        "traversals",
+    ])?;
+
+    let coords: std::collections::HashMap<i64, (i32, i32)> = graph
+        .vertices
+        .iter()
+        .map(|vertex| (vertex.vertex_id, (vertex.x, vertex.y)))
+        .collect();
+
+    for edge in &graph.edges {
+        let (source_x, source_y) = coords[&edge.source];
+        let (target_x, target_y) = coords[&edge.target];
+        writer.write_record([
+            edge.source.to_string(),
+            edge.target.to_string(),
+            source_x.to_string(),
+            source_y.to_string(),
+            target_x.to_string(),
+            target_y.to_string(),
+            edge.first_step.to_string(),
+            edge.traversals.to_string(),
+        ])?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn write_rlt_graphml(path: &Path, graph: &RltGraph) -> Result<(), AddError> {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    for (id, target, name, kind) in [
+        ("x", "node", "x", "int"),
+        ("y", "node", "y", "int"),
+        ("first_visit_step", "node", "first_visit_step", "long"),
+        ("visits", "node", "visits", "long"),
+        ("first_step", "edge", "first_step", "long"),
+        ("traversals", "edge", "traversals", "long"),
+        ("lambda", "graph", "lambda", "double"),
+    ] {
+        out.push_str(&format!(
+            "  <key id=\"{id}\" for=\"{target}\" attr.name=\"{name}\" attr.type=\"{kind}\"/>\n"
+        ));
+    }
+    out.push_str("  <graph id=\"rlt\" edgedefault=\"undirected\">\n");
+    out.push_str(&format!(
+        "    <data key=\"lambda\">{}</data>\n",
+        fmt_f64(graph.lambda)
+    ));
+
+    for vertex in &graph.vertices {
+        out.push_str(&format!(
+            "    <node id=\"v{}\"><data key=\"x\">{}</data><data key=\"y\">{}</data><data key=\"first_visit_step\">{}</data><data key=\"visits\">{}</data></node>\n",
+            vertex.vertex_id, vertex.x, vertex.y, vertex.first_visit_step, vertex.visits
+        ));
+    }
+    for edge in &graph.edges {
+        out.push_str(&format!(
+            "    <edge source=\"v{}\" target=\"v{}\"><data key=\"first_step\">{}</data><data key=\"traversals\">{}</data></edge>\n",
+            edge.source, edge.target, edge.first_step, edge.traversals
+        ));
+    }
+
+    out.push_str("  </graph>\n</graphml>\n");
+    fs::write(path, out)?;
+    Ok(())
+}
+
+/// Writes `graph` next to its trajectory CSV in the requested format; a no-op
+/// for `GraphExportFormat::None`.
+pub fn write_rlt_graph(
+    dir: &Path,
+    stem: &str,
+    graph: &RltGraph,
+    format: GraphExportFormat,
+) -> Result<(), AddError> {
+    match format {
+        GraphExportFormat::None => Ok(()),
+        GraphExportFormat::EdgeList => {
+            write_rlt_graph_edge_list_csv(&dir.join(format!("{stem}.csv")), graph)
+        }
+        GraphExportFormat::GraphMl => {
+            write_rlt_graphml(&dir.join(format!("{stem}.graphml")), graph)
+        }
+    }
+}
+
 pub fn write_rlt_phase_boundary_csv(
     path: &Path,
     rows: &[PhaseBoundaryRow],
@@ -465,6 +615,42 @@ pub fn write_cross_layer_thresholds_csv(
     Ok(())
 }
 
+pub fn write_cross_subsystem_csv(path: &Path, rows: &[CrossSubsystemRow]) -> Result<(), AddError> {
+    let mut writer = Writer::from_path(path)?;
+    writer.write_record([
+        "steps_per_run",
+        "is_perturbed",
+        "pearson_r",
+        "spearman_rho",
+        "peak_xcorr",
+        "peak_lag",
+        "peak_lag_lambda",
+        "mutual_information_bits",
+        "normalized_mutual_information",
+        "histogram_bins",
+        "sample_count",
+    ])?;
+
+    for row in rows {
+        writer.write_record([
+            row.steps_per_run.to_string(),
+            row.is_perturbed.to_string(),
+            fmt_f64(row.pearson_r),
+            fmt_f64(row.spearman_rho),
+            fmt_f64(row.peak_xcorr),
+            row.peak_lag.to_string(),
+            fmt_f64(row.peak_lag_lambda),
+            fmt_f64(row.mutual_information),
+            fmt_f64(row.normalized_mutual_information),
+            row.histogram_bins.to_string(),
+            row.sample_count.to_string(),
+        ])?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
 pub fn write_tcp_phase_alignment_csv(
     path: &Path,
     rows: &[TcpPhaseAlignmentRow],
@@ -514,3 +700,29 @@ pub fn write_robustness_metrics_csv(
     writer.flush()?;
     Ok(())
 }
+
+pub fn write_timing_csv(path: &Path, rows: &[SubsystemTimingRow]) -> Result<(), AddError> {
+    let mut writer = Writer::from_path(path)?;
+    writer.write_record([
+        "steps_per_run",
+        "subsystem",
+        "stage",
+        "lambda_index",
+        "lambda",
+        "elapsed_s",
+    ])?;
+
+    for row in rows {
+        writer.write_record([
+            row.steps_per_run.to_string(),
+            row.subsystem.clone(),
+            row.stage.clone(),
+            row.lambda_index.to_string(),
+            fmt_f64(row.lambda),
+            fmt_f64(row.elapsed_s),
+        ])?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
@@ -1,9 +1,18 @@
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
 
 use serde::{Deserialize, Serialize};
 
-use crate::config::SimulationConfig;
-use crate::sweep::deterministic_drive;
+use crate::analysis::rlt_phase::analyze_rlt_phase_boundary;
+use crate::config::{RltConfig, SimulationConfig};
+use crate::output::{
+    write_rlt_csv, write_rlt_graph, write_rlt_trajectory_csv, RobustnessMetricRow,
+};
+use crate::subtheory::{
+    comparison_metric_option, curve_diff_metrics, OutputTarget, PerturbedPair, SubTheory,
+    SweepProgress,
+};
+use crate::sweep::{deterministic_drive, SweepRunResult};
 use crate::AddError;
 
 pub const RLT_EXAMPLE_STEPS: usize = 240;
@@ -42,6 +51,34 @@ pub struct RltTrajectoryPoint {
     pub distance_from_start: usize,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RltGraphVertex {
+    pub vertex_id: i64,
+    pub x: i32,
+    pub y: i32,
+    pub first_visit_step: usize,
+    pub visits: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RltGraphEdge {
+    pub source: i64,
+    pub target: i64,
+    pub first_step: usize,
+    pub traversals: usize,
+}
+
+/// Undirected adjacency graph traced out by an RLT trajectory.
+///
+/// Vertices and edges are listed in first-visit order; self-loops from
+/// repeated vertices are dropped, as in the escape-distance BFS.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RltGraph {
+    pub lambda: f64,
+    pub vertices: Vec<RltGraphVertex>,
+    pub edges: Vec<RltGraphEdge>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct Vertex {
     x: i32,
@@ -88,6 +125,139 @@ where
     run_rlt_sweep_with_perturbation(config, lambda_grid, RLT_PERTURBATION_STRENGTH, progress)
 }
 
+/// RLT as a [`SubTheory`]: baseline and perturbed transport sweeps plus
+/// representative bounded and expanding example trajectories.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RltTheory;
+
+impl SubTheory for RltTheory {
+    type Curves = PerturbedPair<RltSweep>;
+
+    fn name(&self) -> &'static str {
+        "RLT"
+    }
+
+    fn is_enabled(&self, config: &SimulationConfig) -> bool {
+        config.enable_rlt
+    }
+
+    fn sweep_count(&self) -> usize {
+        2
+    }
+
+    fn run(
+        &self,
+        config: &SimulationConfig,
+        lambda_grid: &[f64],
+        progress: &mut SweepProgress<'_>,
+    ) -> Result<Self::Curves, AddError> {
+        let baseline = progress.sweep("RLT baseline", |report| {
+            run_rlt_sweep_with_progress(config, lambda_grid, report)
+        })?;
+        let perturbed = progress.sweep("RLT perturbed", |report| {
+            run_rlt_sweep_perturbed_with_progress(config, lambda_grid, report)
+        })?;
+        Ok(PerturbedPair {
+            baseline,
+            perturbed,
+        })
+    }
+
+    fn write_outputs(
+        &self,
+        curves: &Self::Curves,
+        target: &OutputTarget<'_>,
+    ) -> Result<(), AddError> {
+        for (stem, sweep, is_perturbed) in [
+            ("rlt_sweep", &curves.baseline, false),
+            ("rlt_sweep_perturbed", &curves.perturbed, true),
+        ] {
+            for path in target.csv_paths(stem) {
+                write_rlt_csv(
+                    &path,
+                    target.lambda_grid,
+                    &sweep.escape_rate,
+                    &sweep.expansion_ratio,
+                    target.config.steps_per_run,
+                    is_perturbed,
+                )?;
+            }
+        }
+
+        for examples_dir in target.dirs("rlt_examples") {
+            fs::create_dir_all(&examples_dir)?;
+            let (bounded_idx, expanding_idx) =
+                find_representative_regime_indices(&curves.baseline.escape_rate);
+            for (kind, idx) in [
+                (RltExampleKind::Bounded, bounded_idx),
+                (RltExampleKind::Expanding, expanding_idx),
+            ] {
+                let lambda = target.lambda_grid[idx];
+                let trajectory =
+                    simulate_example_trajectory(target.config, lambda, RLT_EXAMPLE_STEPS);
+                let filename = format!("trajectory_{}_lambda_{idx:03}.csv", kind.filename_prefix());
+                write_rlt_trajectory_csv(&examples_dir.join(filename), &trajectory)?;
+                write_rlt_graph(
+                    &examples_dir,
+                    &format!("graph_{}_lambda_{idx:03}", kind.filename_prefix()),
+                    &trajectory_graph(&trajectory),
+                    target.config.rlt_graph_export,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn robustness_metrics(
+        &self,
+        curves: &Self::Curves,
+        lambda_grid: &[f64],
+        steps_per_run: usize,
+    ) -> Result<Vec<RobustnessMetricRow>, AddError> {
+        let baseline_phase = analyze_rlt_phase_boundary(
+            lambda_grid,
+            &curves.baseline.expansion_ratio,
+            &curves.baseline.escape_rate,
+        )?;
+        let perturbed_phase = analyze_rlt_phase_boundary(
+            lambda_grid,
+            &curves.perturbed.expansion_ratio,
+            &curves.perturbed.escape_rate,
+        )?;
+
+        let mut rows = curve_diff_metrics(
+            "rlt",
+            steps_per_run,
+            &curves.baseline.expansion_ratio,
+            &curves.perturbed.expansion_ratio,
+        );
+        rows.push(comparison_metric_option(
+            "lambda_star",
+            steps_per_run,
+            baseline_phase.lambda_star,
+            perturbed_phase.lambda_star,
+        ));
+        rows.push(comparison_metric_option(
+            "transition_width",
+            steps_per_run,
+            baseline_phase.transition_width,
+            perturbed_phase.transition_width,
+        ));
+        rows.push(comparison_metric_option(
+            "max_derivative",
+            steps_per_run,
+            baseline_phase.max_derivative,
+            perturbed_phase.max_derivative,
+        ));
+        Ok(rows)
+    }
+
+    fn record(&self, curves: Self::Curves, run: &mut SweepRunResult) {
+        run.rlt = Some(curves.baseline);
+        run.rlt_perturbed = Some(curves.perturbed);
+    }
+}
+
 fn run_rlt_sweep_with_perturbation<F>(
     config: &SimulationConfig,
     lambda_grid: &[f64],
@@ -151,6 +321,51 @@ pub fn simulate_example_trajectory(
     points
 }
 
+pub fn trajectory_graph(points: &[RltTrajectoryPoint]) -> RltGraph {
+    let mut graph = RltGraph {
+        lambda: points.first().map_or(0.0, |point| point.lambda),
+        ..RltGraph::default()
+    };
+    let mut vertex_index: HashMap<i64, usize> = HashMap::new();
+    let mut edge_index: HashMap<(i64, i64), usize> = HashMap::new();
+
+    for (idx, point) in points.iter().enumerate() {
+        let slot = *vertex_index.entry(point.vertex_id).or_insert_with(|| {
+            graph.vertices.push(RltGraphVertex {
+                vertex_id: point.vertex_id,
+                x: point.x,
+                y: point.y,
+                first_visit_step: point.step,
+                visits: 0,
+            });
+            graph.vertices.len() - 1
+        });
+        graph.vertices[slot].visits += 1;
+
+        if idx == 0 {
+            continue;
+        }
+        let previous = points[idx - 1].vertex_id;
+        if previous == point.vertex_id {
+            continue;
+        }
+
+        let key = (previous.min(point.vertex_id), previous.max(point.vertex_id));
+        let slot = *edge_index.entry(key).or_insert_with(|| {
+            graph.edges.push(RltGraphEdge {
+                source: previous,
+                target: point.vertex_id,
+                first_step: point.step,
+                traversals: 0,
+            });
+            graph.edges.len() - 1
+        });
+        graph.edges[slot].traversals += 1;
+    }
+
+    graph
+}
+
 pub fn find_representative_regime_indices(escape_rate: &[f64]) -> (usize, usize) {
     let bounded_idx = escape_rate
         .iter()
@@ -191,13 +406,14 @@ fn simulate_vertices_with_perturbation(
     perturbation_strength: f64,
 ) -> Vec<Vertex> {
     let lambda_norm = config.normalized_lambda(lambda);
-    let drive = deterministic_drive(config.random_seed, lambda, 0xB170_u64);
+    let drive = deterministic_drive(&config.drive, config.random_seed, lambda, 0xB170_u64);
     let mut current = Vertex { x: 0, y: 0 };
     let mut vertices = Vec::with_capacity(steps + 1);
     vertices.push(current);
 
     for step in 0..steps {
         current = resonance_step(
+            &config.rlt,
             current,
             step,
             lambda,
@@ -234,6 +450,7 @@ fn summarize_trajectory(vertices: &[Vertex], steps: usize) -> (f64, f64) {
 }
 
 fn resonance_step(
+    rules: &RltConfig,
     current: Vertex,
     step: usize,
     lambda: f64,
@@ -244,7 +461,7 @@ fn resonance_step(
     let lambda_perturbation = perturbation_strength
         * ((step as f64) * 0.0175 + lambda * 6.0 + drive.drift_bias * 2.0).sin();
     let lambda_effective = (lambda_norm + lambda_perturbation).clamp(0.0, 1.0);
-    let regime = classify_regime(lambda_effective);
+    let regime = classify_regime(rules, lambda_effective);
     let phase_bucket = (lambda_effective * 11.0).round() as i32
         + (drive.phase_bias * 5.0).round() as i32
         + (perturbation_strength * 12.0 * ((step as f64) * 0.025 + lambda * 3.0).cos()).round()
@@ -254,6 +471,7 @@ fn resonance_step(
     match regime {
         RltRegime::Bounded => bounded_step(step, phase_bucket, trust_sign),
         RltRegime::Transitional => transitional_step(
+            rules,
             current,
             step,
             lambda_effective,
@@ -271,10 +489,10 @@ fn resonance_step(
     }
 }
 
-fn classify_regime(lambda_norm: f64) -> RltRegime {
-    if lambda_norm < 0.22 {
+fn classify_regime(rules: &RltConfig, lambda_norm: f64) -> RltRegime {
+    if lambda_norm < rules.bounded_threshold {
         RltRegime::Bounded
-    } else if lambda_norm < 0.58 {
+    } else if lambda_norm < rules.expanding_threshold {
         RltRegime::Transitional
     } else {
         RltRegime::Expanding
@@ -292,6 +510,7 @@ fn bounded_step(step: usize, phase_bucket: i32, trust_sign: i32) -> Vertex {
 }
 
 fn transitional_step(
+    rules: &RltConfig,
     current: Vertex,
     step: usize,
     lambda_norm: f64,
@@ -299,8 +518,7 @@ fn transitional_step(
     trust_sign: i32,
     perturbation_strength: f64,
 ) -> Vertex {
-    let leash = 2
-        + (lambda_norm * 10.0).round() as i32
+    let leash = rules.leash(lambda_norm)
         + (perturbation_strength * 6.0 * ((step as f64) * 0.05 + lambda_norm * 4.0).sin()).round()
             as i32;
     let resonance_class = (step as i32 + phase_bucket).rem_euclid(6);
@@ -331,7 +549,7 @@ fn transitional_step(
         },
     };
 
-    let reset_period = ((16.0 - 10.0 * lambda_norm).round() as usize).clamp(6, 16);
+    let reset_period = rules.reset_period(lambda_norm);
     if step % reset_period == 0 {
         next = Vertex {
             x: phase_bucket.rem_euclid(3) - 1,
@@ -0,0 +1,323 @@
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::aet::AetTheory;
+use crate::config::SimulationConfig;
+use crate::iwlt::IwltTheory;
+use crate::output::{RobustnessMetricRow, SubsystemTimingRow};
+use crate::rlt::RltTheory;
+use crate::sweep::{ProgressTracker, SweepRunResult};
+use crate::tcp::TcpTheory;
+use crate::AddError;
+
+/// One ADD sub-theory diagnostic driven by the lambda sweep.
+///
+/// The sweep runs every enabled sub-theory once per `steps_per_run`, in
+/// registry order: [`run`](SubTheory::run) produces the curves,
+/// [`write_outputs`](SubTheory::write_outputs) exports them,
+/// [`robustness_metrics`](SubTheory::robustness_metrics) contributes rows to
+/// `robustness_metrics.csv`, and [`record`](SubTheory::record) keeps them in
+/// the returned [`SweepRunResult`].
+pub trait SubTheory {
+    /// Everything one run produces, e.g. a baseline and a perturbed sweep.
+    type Curves;
+
+    /// Short label used in progress output, e.g. `"AET"`.
+    fn name(&self) -> &'static str;
+
+    fn is_enabled(&self, config: &SimulationConfig) -> bool;
+
+    /// Number of full lambda sweeps one run performs, for overall progress.
+    fn sweep_count(&self) -> usize;
+
+    /// Runs the sub-theory over `lambda_grid` at `config.steps_per_run`.
+    fn run(
+        &self,
+        config: &SimulationConfig,
+        lambda_grid: &[f64],
+        progress: &mut SweepProgress<'_>,
+    ) -> Result<Self::Curves, AddError>;
+
+    fn write_outputs(
+        &self,
+        curves: &Self::Curves,
+        target: &OutputTarget<'_>,
+    ) -> Result<(), AddError>;
+
+    /// Baseline-versus-perturbed comparisons; empty for sub-theories without
+    /// a perturbed variant.
+    fn robustness_metrics(
+        &self,
+        curves: &Self::Curves,
+        lambda_grid: &[f64],
+        steps_per_run: usize,
+    ) -> Result<Vec<RobustnessMetricRow>, AddError>;
+
+    /// Stores the curves in the per-`N` result. The default drops them, for
+    /// sub-theories that only export files.
+    fn record(&self, curves: Self::Curves, run: &mut SweepRunResult) {
+        let _ = (curves, run);
+    }
+}
+
+/// Baseline sweep together with its perturbed counterpart.
+#[derive(Debug, Clone)]
+pub struct PerturbedPair<T> {
+    pub baseline: T,
+    pub perturbed: T,
+}
+
+/// Object-safe form of [`SubTheory`], implemented for every sub-theory so
+/// different curve types can share one registry.
+pub trait RegisteredSubTheory {
+    fn name(&self) -> &'static str;
+
+    fn is_enabled(&self, config: &SimulationConfig) -> bool;
+
+    fn sweep_count(&self) -> usize;
+
+    /// Runs the sub-theory at `target.config`, writes its outputs, records its
+    /// curves in `run`, and returns its robustness metrics.
+    fn run_into(
+        &self,
+        target: &OutputTarget<'_>,
+        progress: &mut SweepProgress<'_>,
+        run: &mut SweepRunResult,
+    ) -> Result<Vec<RobustnessMetricRow>, AddError>;
+}
+
+impl<S: SubTheory> RegisteredSubTheory for S {
+    fn name(&self) -> &'static str {
+        SubTheory::name(self)
+    }
+
+    fn is_enabled(&self, config: &SimulationConfig) -> bool {
+        SubTheory::is_enabled(self, config)
+    }
+
+    fn sweep_count(&self) -> usize {
+        SubTheory::sweep_count(self)
+    }
+
+    fn run_into(
+        &self,
+        target: &OutputTarget<'_>,
+        progress: &mut SweepProgress<'_>,
+        run: &mut SweepRunResult,
+    ) -> Result<Vec<RobustnessMetricRow>, AddError> {
+        let curves = self.run(target.config, target.lambda_grid, progress)?;
+        self.write_outputs(&curves, target)?;
+        let metrics =
+            self.robustness_metrics(&curves, target.lambda_grid, target.config.steps_per_run)?;
+        self.record(curves, run);
+        Ok(metrics)
+    }
+}
+
+/// The built-in sub-theories in run order: AET, TCP, RLT, IWLT.
+pub fn default_registry() -> Vec<Box<dyn RegisteredSubTheory>> {
+    vec![
+        Box::new(AetTheory),
+        Box::new(TcpTheory),
+        Box::new(RltTheory),
+        Box::new(IwltTheory),
+    ]
+}
+
+/// The sub-theory named `name` in `registry`, matched case-insensitively.
+pub fn find_subtheory<'a>(
+    registry: &'a [Box<dyn RegisteredSubTheory>],
+    name: &str,
+) -> Result<&'a dyn RegisteredSubTheory, AddError> {
+    registry
+        .iter()
+        .map(|theory| theory.as_ref())
+        .find(|theory| theory.name().eq_ignore_ascii_case(name))
+        .ok_or_else(|| {
+            let names: Vec<&str> = registry.iter().map(|theory| theory.name()).collect();
+            AddError::invalid_input(format!(
+                "unknown sub-theory {name}; registered: {}",
+                names.join(", ")
+            ))
+        })
+}
+
+/// Where and for which run a sub-theory writes its outputs.
+#[derive(Debug, Clone, Copy)]
+pub struct OutputTarget<'a> {
+    pub output_dir: &'a Path,
+    pub lambda_grid: &'a [f64],
+    /// Configuration of this run, with `steps_per_run` set to its `N`
+    pub config: &'a SimulationConfig,
+    /// Whether the sweep covers several `N`, so file names carry `_N<steps>`
+    pub use_step_suffix: bool,
+    /// Whether this `N` also writes the unsuffixed canonical files
+    pub is_canonical: bool,
+}
+
+impl OutputTarget<'_> {
+    /// Paths `<stem>.csv` is written to for this run.
+    pub fn csv_paths(&self, stem: &str) -> Vec<PathBuf> {
+        self.names(stem)
+            .into_iter()
+            .map(|name| self.output_dir.join(format!("{name}.csv")))
+            .collect()
+    }
+
+    /// Directories named after `stem` that this run writes into.
+    pub fn dirs(&self, stem: &str) -> Vec<PathBuf> {
+        self.names(stem)
+            .into_iter()
+            .map(|name| self.output_dir.join(name))
+            .collect()
+    }
+
+    fn names(&self, stem: &str) -> Vec<String> {
+        let mut names = Vec::new();
+        if self.use_step_suffix {
+            names.push(format!("{stem}_N{}", self.config.steps_per_run));
+            if self.is_canonical {
+                names.push(stem.to_string());
+            }
+        } else {
+            names.push(stem.to_string());
+        }
+        names
+    }
+}
+
+/// Progress reporting handed to [`SubTheory::run`].
+///
+/// Also times every lambda sample: the wall-clock time between consecutive
+/// progress reports is recorded against the sample just completed.
+pub struct SweepProgress<'a> {
+    tracker: &'a mut ProgressTracker,
+    timing: &'a mut Vec<SubsystemTimingRow>,
+    subsystem: &'static str,
+    steps_per_run: usize,
+    lambda_grid: &'a [f64],
+}
+
+impl<'a> SweepProgress<'a> {
+    pub(crate) fn new(
+        tracker: &'a mut ProgressTracker,
+        timing: &'a mut Vec<SubsystemTimingRow>,
+        subsystem: &'static str,
+        steps_per_run: usize,
+        lambda_grid: &'a [f64],
+    ) -> Self {
+        Self {
+            tracker,
+            timing,
+            subsystem,
+            steps_per_run,
+            lambda_grid,
+        }
+    }
+
+    /// Runs one full lambda sweep labelled `label` (e.g. `"AET baseline"`).
+    /// `sweep` receives a `(completed, total)` callback to report through.
+    pub fn sweep<T>(
+        &mut self,
+        label: &str,
+        sweep: impl FnOnce(&mut dyn FnMut(usize, usize)) -> Result<T, AddError>,
+    ) -> Result<T, AddError> {
+        let steps_per_run = self.steps_per_run;
+        let lambda_count = self.lambda_grid.len();
+        self.tracker.stage_start(label, steps_per_run, lambda_count);
+        let tracker = &mut *self.tracker;
+        let timing = &mut *self.timing;
+        let (subsystem, lambda_grid) = (self.subsystem, self.lambda_grid);
+        let mut last_report = Instant::now();
+        let result = sweep(&mut |completed, total| {
+            if let Some(lambda_index) = completed.checked_sub(1) {
+                timing.push(SubsystemTimingRow {
+                    steps_per_run,
+                    subsystem: subsystem.to_string(),
+                    stage: label.to_string(),
+                    lambda_index,
+                    lambda: lambda_grid.get(lambda_index).copied().unwrap_or(f64::NAN),
+                    elapsed_s: last_report.elapsed().as_secs_f64(),
+                });
+            }
+            last_report = Instant::now();
+            tracker.report(label, steps_per_run, completed, total)
+        })?;
+        self.tracker.finish_stage(lambda_count);
+        Ok(result)
+    }
+}
+
+pub fn comparison_metric(
+    metric: &str,
+    steps_per_run: usize,
+    baseline: f64,
+    perturbed: f64,
+) -> RobustnessMetricRow {
+    RobustnessMetricRow {
+        metric: metric.to_string(),
+        steps_per_run,
+        baseline,
+        perturbed,
+        delta: perturbed - baseline,
+    }
+}
+
+/// [`comparison_metric`] with missing values written as NaN.
+pub fn comparison_metric_option(
+    metric: &str,
+    steps_per_run: usize,
+    baseline: Option<f64>,
+    perturbed: Option<f64>,
+) -> RobustnessMetricRow {
+    comparison_metric(
+        metric,
+        steps_per_run,
+        baseline.unwrap_or(f64::NAN),
+        perturbed.unwrap_or(f64::NAN),
+    )
+}
+
+/// `<prefix>_curve_l2_diff` and `<prefix>_curve_max_abs_diff` between a
+/// baseline and a perturbed curve.
+pub fn curve_diff_metrics(
+    prefix: &str,
+    steps_per_run: usize,
+    baseline: &[f64],
+    perturbed: &[f64],
+) -> Vec<RobustnessMetricRow> {
+    vec![
+        comparison_metric(
+            &format!("{prefix}_curve_l2_diff"),
+            steps_per_run,
+            0.0,
+            curve_l2_diff(baseline, perturbed),
+        ),
+        comparison_metric(
+            &format!("{prefix}_curve_max_abs_diff"),
+            steps_per_run,
+            0.0,
+            curve_max_abs_diff(baseline, perturbed),
+        ),
+    ]
+}
+
+fn curve_l2_diff(baseline: &[f64], perturbed: &[f64]) -> f64 {
+    baseline
+        .iter()
+        .zip(perturbed.iter())
+        .map(|(base, perturbed_value)| {
+            let delta = perturbed_value - base;
+            delta * delta
+        })
+        .sum::<f64>()
+        .sqrt()
+}
+
+fn curve_max_abs_diff(baseline: &[f64], perturbed: &[f64]) -> f64 {
+    baseline
+        .iter()
+        .zip(perturbed.iter())
+        .map(|(base, perturbed_value)| (perturbed_value - base).abs())
+        .fold(0.0_f64, f64::max)
+}
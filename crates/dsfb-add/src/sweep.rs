@@ -1,33 +1,40 @@
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Instant;
 
-use dsfb::{DsfbObserver, DsfbParams, DsfbState};
+use dsfb::{DsfbObserver, DsfbState};
 use serde::{Deserialize, Serialize};
 
-use crate::aet::{self, AetSweep};
+use crate::aet::AetSweep;
+use crate::analysis::cross_subsystem::{cross_subsystem_stats, CrossSubsystemStats};
 use crate::analysis::rlt_phase::{analyze_rlt_phase_boundary, RltPhaseBoundary};
 use crate::analysis::structural_law::{diagnostics_from_fit, fit_with_ci, LinearFit};
-use crate::config::SimulationConfig;
-use crate::iwlt::{self, IwltSweep};
+use crate::config::{DriveConfig, SimulationConfig};
+use crate::iwlt::IwltSweep;
 use crate::output::{
-    write_aet_csv, write_cross_layer_thresholds_csv, write_diagnostics_summary_csv, write_iwlt_csv,
-    write_rlt_csv, write_rlt_phase_boundary_csv, write_rlt_trajectory_csv,
-    write_robustness_metrics_csv, write_structural_law_summary_csv, write_tcp_csv,
-    write_tcp_phase_alignment_csv, write_tcp_points_csv, CrossLayerThresholdRow,
-    DiagnosticsSummaryRow, PhaseBoundaryRow, RobustnessMetricRow, StructuralLawSummaryRow,
-    TcpPhaseAlignmentRow,
+    write_cross_layer_thresholds_csv, write_cross_subsystem_csv, write_diagnostics_summary_csv,
+    write_manifest_json, write_rlt_phase_boundary_csv, write_robustness_metrics_csv,
+    write_structural_law_summary_csv, write_tcp_phase_alignment_csv, write_timing_csv,
+    CrossLayerThresholdRow, CrossSubsystemRow, DiagnosticsSummaryRow, PhaseBoundaryRow,
+    StructuralLawSummaryRow, TcpPhaseAlignmentRow,
 };
-use crate::rlt::{self, RltExampleKind, RltSweep};
-use crate::tcp::{self, TcpSweep};
+use crate::rlt::RltSweep;
+use crate::subtheory::{
+    comparison_metric, default_registry, OutputTarget, RegisteredSubTheory, SweepProgress,
+};
+use crate::tcp::TcpSweep;
 use crate::AddError;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SweepRunResult {
     pub steps_per_run: usize,
     pub aet: Option<AetSweep>,
+    pub aet_perturbed: Option<AetSweep>,
     pub tcp: Option<TcpSweep>,
     pub rlt: Option<RltSweep>,
+    pub rlt_perturbed: Option<RltSweep>,
     pub iwlt: Option<IwltSweep>,
+    pub iwlt_perturbed: Option<IwltSweep>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,7 +48,7 @@ pub struct SweepResult {
     pub iwlt: Option<IwltSweep>,
 }
 
-struct ProgressTracker {
+pub(crate) struct ProgressTracker {
     total_units: usize,
     completed_units: usize,
     last_percent_printed: usize,
@@ -56,11 +63,17 @@ impl ProgressTracker {
         }
     }
 
-    fn stage_start(&self, label: &str, steps_per_run: usize, stage_units: usize) {
+    pub(crate) fn stage_start(&self, label: &str, steps_per_run: usize, stage_units: usize) {
         println!("[dsfb-add] Starting {label} (N={steps_per_run}, {stage_units} lambda samples)");
     }
 
-    fn report(&mut self, label: &str, steps_per_run: usize, local_done: usize, stage_units: usize) {
+    pub(crate) fn report(
+        &mut self,
+        label: &str,
+        steps_per_run: usize,
+        local_done: usize,
+        stage_units: usize,
+    ) {
         if self.total_units == 0 {
             return;
         }
@@ -76,7 +89,7 @@ impl ProgressTracker {
         }
     }
 
-    fn finish_stage(&mut self, stage_units: usize) {
+    pub(crate) fn finish_stage(&mut self, stage_units: usize) {
         self.completed_units += stage_units;
     }
 
@@ -95,22 +108,29 @@ pub(crate) struct DriveSignal {
     pub drift_bias: f64,
 }
 
-pub(crate) fn deterministic_drive(seed: u64, lambda: f64, salt: u64) -> DriveSignal {
-    let mut observer = DsfbObserver::new(DsfbParams::new(0.35, 0.08, 0.01, 0.92, 0.15), 2);
+pub(crate) fn deterministic_drive(
+    drive: &DriveConfig,
+    seed: u64,
+    lambda: f64,
+    salt: u64,
+) -> DriveSignal {
+    let mut observer = DsfbObserver::new(drive.observer, 2);
     observer.init(DsfbState::new(lambda * 0.25, 0.0, 0.0));
 
     let phase = lambda * std::f64::consts::TAU + (seed ^ salt) as f64 * 1.0e-6;
     let dt = 0.125;
 
-    for step in 0..24 {
+    let [amplitude0, amplitude1] = drive.channel_amplitudes;
+
+    for step in 0..drive.warmup_steps {
         let t = step as f64 * dt;
         let quantized0 =
             (((seed.wrapping_add(salt).wrapping_add(step as u64)) % 11) as f64 - 5.0) * 0.01;
         let quantized1 =
             (((seed ^ salt).wrapping_add((step * 3) as u64) % 13) as f64 - 6.0) * 0.008;
 
-        let channel0 = lambda + 0.32 * (phase + 1.7 * t).sin() + quantized0;
-        let channel1 = lambda + 0.27 * (phase * 0.8 + 2.3 * t).cos() + quantized1;
+        let channel0 = lambda + amplitude0 * (phase + 1.7 * t).sin() + quantized0;
+        let channel1 = lambda + amplitude1 * (phase * 0.8 + 2.3 * t).cos() + quantized1;
 
         observer.step(&[channel0, channel1], dt);
     }
@@ -126,17 +146,36 @@ pub(crate) fn deterministic_drive(seed: u64, lambda: f64, salt: u64) -> DriveSig
 pub fn run_sweeps_into_dir(
     config: &SimulationConfig,
     output_dir: &Path,
+) -> Result<SweepResult, AddError> {
+    run_sweeps_with_registry(config, output_dir, &default_registry())
+}
+
+/// [`run_sweeps_into_dir`] over an explicit set of sub-theories, run in
+/// order for every `steps_per_run`. The AET-IWLT structural law, RLT phase
+/// boundary, and TCP alignment summaries are derived from the built-in
+/// sub-theories' recorded curves whenever those are in the registry.
+pub fn run_sweeps_with_registry(
+    config: &SimulationConfig,
+    output_dir: &Path,
+    registry: &[Box<dyn RegisteredSubTheory>],
 ) -> Result<SweepResult, AddError> {
     config.validate()?;
     fs::create_dir_all(output_dir)?;
+    let started = Instant::now();
+    write_manifest_json(&output_dir.join("manifest.json"), config, None)?;
 
     let lambda_grid = config.lambda_grid();
     let sweep_steps = config.sweep_steps();
     let use_step_suffix = sweep_steps.len() > 1;
     let canonical_steps = canonical_steps(config, &sweep_steps);
     let lambda_count = lambda_grid.len();
+    let enabled: Vec<&dyn RegisteredSubTheory> = registry
+        .iter()
+        .map(|theory| theory.as_ref())
+        .filter(|theory| theory.is_enabled(config))
+        .collect();
     let mut progress = ProgressTracker::new(total_progress_units(
-        config,
+        &enabled,
         sweep_steps.len(),
         lambda_count,
     ));
@@ -144,11 +183,13 @@ pub fn run_sweeps_into_dir(
     let mut runs = Vec::with_capacity(sweep_steps.len());
     let mut phase_rows = Vec::new();
     let mut law_rows = Vec::new();
+    let mut cross_subsystem_rows = Vec::new();
     let mut scaling_rows = Vec::new();
     let mut diagnostics_rows = Vec::new();
     let mut threshold_rows = Vec::new();
     let mut tcp_alignment_rows = Vec::new();
     let mut robustness_rows = Vec::new();
+    let mut timing_rows = Vec::new();
 
     let mut canonical_aet = None;
     let mut canonical_tcp = None;
@@ -160,358 +201,53 @@ pub fn run_sweeps_into_dir(
         run_config.steps_per_run = steps_per_run;
 
         let is_canonical = steps_per_run == canonical_steps;
-        let suffix = if use_step_suffix {
-            format!("_N{steps_per_run}")
-        } else {
-            String::new()
+        let target = OutputTarget {
+            output_dir,
+            lambda_grid: &lambda_grid,
+            config: &run_config,
+            use_step_suffix,
+            is_canonical,
         };
 
-        let (aet, aet_perturbed) = if config.enable_aet {
-            progress.stage_start("AET baseline", steps_per_run, lambda_count);
-            let baseline =
-                aet::run_aet_sweep_with_progress(&run_config, &lambda_grid, |completed, total| {
-                    progress.report("AET baseline", steps_per_run, completed, total)
-                })?;
-            progress.finish_stage(lambda_count);
-
-            progress.stage_start("AET perturbed", steps_per_run, lambda_count);
-            let perturbed = aet::run_aet_sweep_perturbed_with_progress(
-                &run_config,
-                &lambda_grid,
-                |completed, total| {
-                    progress.report("AET perturbed", steps_per_run, completed, total)
-                },
-            )?;
-            progress.finish_stage(lambda_count);
-
-            write_aet_csv(
-                &output_dir.join(format!("aet_sweep{suffix}.csv")),
-                &lambda_grid,
-                &baseline.echo_slope,
-                &baseline.avg_increment,
-                steps_per_run,
-                false,
-            )?;
-            write_aet_csv(
-                &output_dir.join(format!("aet_sweep_perturbed{suffix}.csv")),
-                &lambda_grid,
-                &perturbed.echo_slope,
-                &perturbed.avg_increment,
-                steps_per_run,
-                true,
-            )?;
-
-            if use_step_suffix && is_canonical {
-                write_aet_csv(
-                    &output_dir.join("aet_sweep.csv"),
-                    &lambda_grid,
-                    &baseline.echo_slope,
-                    &baseline.avg_increment,
-                    steps_per_run,
-                    false,
-                )?;
-                write_aet_csv(
-                    &output_dir.join("aet_sweep_perturbed.csv"),
-                    &lambda_grid,
-                    &perturbed.echo_slope,
-                    &perturbed.avg_increment,
-                    steps_per_run,
-                    true,
-                )?;
-            }
-
-            robustness_rows.push(comparison_metric(
-                "aet_curve_l2_diff",
-                steps_per_run,
-                0.0,
-                curve_l2_diff(&baseline.echo_slope, &perturbed.echo_slope),
-            ));
-            robustness_rows.push(comparison_metric(
-                "aet_curve_max_abs_diff",
-                steps_per_run,
-                0.0,
-                curve_max_abs_diff(&baseline.echo_slope, &perturbed.echo_slope),
-            ));
-
-            if is_canonical {
-                canonical_aet = Some(baseline.clone());
-            }
-
-            (Some(baseline), Some(perturbed))
-        } else {
-            (None, None)
+        let mut run = SweepRunResult {
+            steps_per_run,
+            aet: None,
+            aet_perturbed: None,
+            tcp: None,
+            rlt: None,
+            rlt_perturbed: None,
+            iwlt: None,
+            iwlt_perturbed: None,
         };
-
-        let tcp = if config.enable_tcp {
-            progress.stage_start("TCP baseline", steps_per_run, lambda_count);
-            let baseline =
-                tcp::run_tcp_sweep_with_progress(&run_config, &lambda_grid, |completed, total| {
-                    progress.report("TCP baseline", steps_per_run, completed, total)
-                })?;
-            progress.finish_stage(lambda_count);
-
-            write_tcp_csv(
-                &output_dir.join(format!("tcp_sweep{suffix}.csv")),
-                &lambda_grid,
-                &baseline.betti0,
-                &baseline.betti1,
-                &baseline.l_tcp,
-                &baseline.avg_radius,
-                &baseline.max_radius,
-                &baseline.variance_radius,
+        for theory in &enabled {
+            let mut stage_progress = SweepProgress::new(
+                &mut progress,
+                &mut timing_rows,
+                theory.name(),
                 steps_per_run,
-                false,
-            )?;
-
-            if use_step_suffix && is_canonical {
-                write_tcp_csv(
-                    &output_dir.join("tcp_sweep.csv"),
-                    &lambda_grid,
-                    &baseline.betti0,
-                    &baseline.betti1,
-                    &baseline.l_tcp,
-                    &baseline.avg_radius,
-                    &baseline.max_radius,
-                    &baseline.variance_radius,
-                    steps_per_run,
-                    false,
-                )?;
-            }
-
-            for points_dir in points_dirs(output_dir, steps_per_run, use_step_suffix, is_canonical)
-            {
-                fs::create_dir_all(&points_dir)?;
-                for (idx, runs_for_lambda) in baseline.point_cloud_runs.iter().enumerate() {
-                    for (run_idx, points) in runs_for_lambda.iter().enumerate() {
-                        let filename = format!("lambda_{idx:03}_run_{run_idx:02}.csv");
-                        write_tcp_points_csv(&points_dir.join(filename), points)?;
-                    }
-                }
-            }
-
-            if is_canonical {
-                canonical_tcp = Some(baseline.clone());
-            }
-
-            Some(baseline)
-        } else {
-            None
-        };
-
-        let (rlt, rlt_perturbed, baseline_phase, perturbed_phase) = if config.enable_rlt {
-            progress.stage_start("RLT baseline", steps_per_run, lambda_count);
-            let baseline =
-                rlt::run_rlt_sweep_with_progress(&run_config, &lambda_grid, |completed, total| {
-                    progress.report("RLT baseline", steps_per_run, completed, total)
-                })?;
-            progress.finish_stage(lambda_count);
-
-            progress.stage_start("RLT perturbed", steps_per_run, lambda_count);
-            let perturbed = rlt::run_rlt_sweep_perturbed_with_progress(
-                &run_config,
-                &lambda_grid,
-                |completed, total| {
-                    progress.report("RLT perturbed", steps_per_run, completed, total)
-                },
-            )?;
-            progress.finish_stage(lambda_count);
-            let baseline_phase = analyze_rlt_phase_boundary(
                 &lambda_grid,
-                &baseline.expansion_ratio,
-                &baseline.escape_rate,
-            )?;
-            let perturbed_phase = analyze_rlt_phase_boundary(
-                &lambda_grid,
-                &perturbed.expansion_ratio,
-                &perturbed.escape_rate,
-            )?;
+            );
+            robustness_rows.extend(theory.run_into(&target, &mut stage_progress, &mut run)?);
+        }
 
-            write_rlt_csv(
-                &output_dir.join(format!("rlt_sweep{suffix}.csv")),
+        let mut baseline_phase = None;
+        if let (Some(rlt_baseline), Some(rlt_perturbed)) = (&run.rlt, &run.rlt_perturbed) {
+            let phase = analyze_rlt_phase_boundary(
                 &lambda_grid,
-                &baseline.escape_rate,
-                &baseline.expansion_ratio,
-                steps_per_run,
-                false,
+                &rlt_baseline.expansion_ratio,
+                &rlt_baseline.escape_rate,
             )?;
-            write_rlt_csv(
-                &output_dir.join(format!("rlt_sweep_perturbed{suffix}.csv")),
+            let perturbed_phase = analyze_rlt_phase_boundary(
                 &lambda_grid,
-                &perturbed.escape_rate,
-                &perturbed.expansion_ratio,
-                steps_per_run,
-                true,
+                &rlt_perturbed.expansion_ratio,
+                &rlt_perturbed.escape_rate,
             )?;
-
-            if use_step_suffix && is_canonical {
-                write_rlt_csv(
-                    &output_dir.join("rlt_sweep.csv"),
-                    &lambda_grid,
-                    &baseline.escape_rate,
-                    &baseline.expansion_ratio,
-                    steps_per_run,
-                    false,
-                )?;
-                write_rlt_csv(
-                    &output_dir.join("rlt_sweep_perturbed.csv"),
-                    &lambda_grid,
-                    &perturbed.escape_rate,
-                    &perturbed.expansion_ratio,
-                    steps_per_run,
-                    true,
-                )?;
-            }
-
-            phase_rows.push(phase_row("baseline", false, steps_per_run, baseline_phase));
+            phase_rows.push(phase_row("baseline", false, steps_per_run, phase));
             phase_rows.push(phase_row("perturbed", true, steps_per_run, perturbed_phase));
+            baseline_phase = Some(phase);
+        }
 
-            robustness_rows.push(comparison_metric(
-                "rlt_curve_l2_diff",
-                steps_per_run,
-                0.0,
-                curve_l2_diff(&baseline.expansion_ratio, &perturbed.expansion_ratio),
-            ));
-            robustness_rows.push(comparison_metric(
-                "rlt_curve_max_abs_diff",
-                steps_per_run,
-                0.0,
-                curve_max_abs_diff(&baseline.expansion_ratio, &perturbed.expansion_ratio),
-            ));
-            robustness_rows.push(comparison_metric_option(
-                "lambda_star",
-                steps_per_run,
-                baseline_phase.lambda_star,
-                perturbed_phase.lambda_star,
-            ));
-            robustness_rows.push(comparison_metric_option(
-                "transition_width",
-                steps_per_run,
-                baseline_phase.transition_width,
-                perturbed_phase.transition_width,
-            ));
-            robustness_rows.push(comparison_metric_option(
-                "max_derivative",
-                steps_per_run,
-                baseline_phase.max_derivative,
-                perturbed_phase.max_derivative,
-            ));
-
-            for examples_dir in
-                example_dirs(output_dir, steps_per_run, use_step_suffix, is_canonical)
-            {
-                fs::create_dir_all(&examples_dir)?;
-                let (bounded_idx, expanding_idx) =
-                    rlt::find_representative_regime_indices(&baseline.escape_rate);
-                for (kind, idx) in [
-                    (RltExampleKind::Bounded, bounded_idx),
-                    (RltExampleKind::Expanding, expanding_idx),
-                ] {
-                    let lambda = lambda_grid[idx];
-                    let trajectory = rlt::simulate_example_trajectory(
-                        &run_config,
-                        lambda,
-                        rlt::RLT_EXAMPLE_STEPS,
-                    );
-                    let filename =
-                        format!("trajectory_{}_lambda_{idx:03}.csv", kind.filename_prefix());
-                    write_rlt_trajectory_csv(&examples_dir.join(filename), &trajectory)?;
-                }
-            }
-
-            if is_canonical {
-                canonical_rlt = Some(baseline.clone());
-            }
-
-            (
-                Some(baseline),
-                Some(perturbed),
-                Some(baseline_phase),
-                Some(perturbed_phase),
-            )
-        } else {
-            (None, None, None, None)
-        };
-
-        let (iwlt, iwlt_perturbed) = if config.enable_iwlt {
-            progress.stage_start("IWLT baseline", steps_per_run, lambda_count);
-            let baseline = iwlt::run_iwlt_sweep_with_progress(
-                &run_config,
-                &lambda_grid,
-                |completed, total| {
-                    progress.report("IWLT baseline", steps_per_run, completed, total)
-                },
-            )?;
-            progress.finish_stage(lambda_count);
-
-            progress.stage_start("IWLT perturbed", steps_per_run, lambda_count);
-            let perturbed = iwlt::run_iwlt_sweep_perturbed_with_progress(
-                &run_config,
-                &lambda_grid,
-                |completed, total| {
-                    progress.report("IWLT perturbed", steps_per_run, completed, total)
-                },
-            )?;
-            progress.finish_stage(lambda_count);
-
-            write_iwlt_csv(
-                &output_dir.join(format!("iwlt_sweep{suffix}.csv")),
-                &lambda_grid,
-                &baseline.entropy_density,
-                &baseline.avg_increment,
-                steps_per_run,
-                false,
-            )?;
-            write_iwlt_csv(
-                &output_dir.join(format!("iwlt_sweep_perturbed{suffix}.csv")),
-                &lambda_grid,
-                &perturbed.entropy_density,
-                &perturbed.avg_increment,
-                steps_per_run,
-                true,
-            )?;
-
-            if use_step_suffix && is_canonical {
-                write_iwlt_csv(
-                    &output_dir.join("iwlt_sweep.csv"),
-                    &lambda_grid,
-                    &baseline.entropy_density,
-                    &baseline.avg_increment,
-                    steps_per_run,
-                    false,
-                )?;
-                write_iwlt_csv(
-                    &output_dir.join("iwlt_sweep_perturbed.csv"),
-                    &lambda_grid,
-                    &perturbed.entropy_density,
-                    &perturbed.avg_increment,
-                    steps_per_run,
-                    true,
-                )?;
-            }
-
-            robustness_rows.push(comparison_metric(
-                "iwlt_curve_l2_diff",
-                steps_per_run,
-                0.0,
-                curve_l2_diff(&baseline.entropy_density, &perturbed.entropy_density),
-            ));
-            robustness_rows.push(comparison_metric(
-                "iwlt_curve_max_abs_diff",
-                steps_per_run,
-                0.0,
-                curve_max_abs_diff(&baseline.entropy_density, &perturbed.entropy_density),
-            ));
-
-            if is_canonical {
-                canonical_iwlt = Some(baseline.clone());
-            }
-
-            (Some(baseline), Some(perturbed))
-        } else {
-            (None, None)
-        };
-
-        if let (Some(aet_baseline), Some(iwlt_baseline)) = (&aet, &iwlt) {
+        if let (Some(aet_baseline), Some(iwlt_baseline)) = (&run.aet, &run.iwlt) {
             let baseline_fit =
                 fit_with_ci(&aet_baseline.echo_slope, &iwlt_baseline.entropy_density)?;
             let baseline_diag = diagnostics_from_fit(
@@ -521,6 +257,15 @@ pub fn run_sweeps_into_dir(
             )?;
             let baseline_row = law_summary_row(steps_per_run, false, baseline_fit, baseline_diag);
             law_rows.push(baseline_row.clone());
+            cross_subsystem_rows.push(cross_subsystem_row(
+                steps_per_run,
+                false,
+                cross_subsystem_stats(
+                    &lambda_grid,
+                    &aet_baseline.echo_slope,
+                    &iwlt_baseline.entropy_density,
+                )?,
+            ));
             scaling_rows.push(baseline_row);
             diagnostics_rows.push(DiagnosticsSummaryRow {
                 steps_per_run,
@@ -546,7 +291,7 @@ pub fn run_sweeps_into_dir(
             }
 
             if let (Some(aet_perturbed_sweep), Some(iwlt_perturbed_sweep)) =
-                (&aet_perturbed, &iwlt_perturbed)
+                (&run.aet_perturbed, &run.iwlt_perturbed)
             {
                 let perturbed_fit = fit_with_ci(
                     &aet_perturbed_sweep.echo_slope,
@@ -563,6 +308,15 @@ pub fn run_sweeps_into_dir(
                     perturbed_fit,
                     perturbed_diag,
                 ));
+                cross_subsystem_rows.push(cross_subsystem_row(
+                    steps_per_run,
+                    true,
+                    cross_subsystem_stats(
+                        &lambda_grid,
+                        &aet_perturbed_sweep.echo_slope,
+                        &iwlt_perturbed_sweep.entropy_density,
+                    )?,
+                ));
 
                 robustness_rows.push(comparison_metric(
                     "structural_law_slope",
@@ -591,7 +345,7 @@ pub fn run_sweeps_into_dir(
             }
         }
 
-        if let (Some(tcp_baseline), Some(phase)) = (&tcp, baseline_phase) {
+        if let (Some(tcp_baseline), Some(phase)) = (&run.tcp, baseline_phase) {
             tcp_alignment_rows.push(tcp_phase_alignment_row(
                 steps_per_run,
                 phase.lambda_star,
@@ -600,16 +354,14 @@ pub fn run_sweeps_into_dir(
             ));
         }
 
-        let _ = rlt_perturbed;
-        let _ = perturbed_phase;
+        if is_canonical {
+            canonical_aet = run.aet.clone();
+            canonical_tcp = run.tcp.clone();
+            canonical_rlt = run.rlt.clone();
+            canonical_iwlt = run.iwlt.clone();
+        }
 
-        runs.push(SweepRunResult {
-            steps_per_run,
-            aet,
-            tcp,
-            rlt,
-            iwlt,
-        });
+        runs.push(run);
     }
 
     if !phase_rows.is_empty() {
@@ -624,6 +376,12 @@ pub fn run_sweeps_into_dir(
             &scaling_rows,
         )?;
     }
+    if !cross_subsystem_rows.is_empty() {
+        write_cross_subsystem_csv(
+            &output_dir.join("cross_subsystem.csv"),
+            &cross_subsystem_rows,
+        )?;
+    }
     if !diagnostics_rows.is_empty() {
         write_diagnostics_summary_csv(
             &output_dir.join("aet_iwlt_diagnostics_summary.csv"),
@@ -646,6 +404,13 @@ pub fn run_sweeps_into_dir(
         write_robustness_metrics_csv(&output_dir.join("robustness_metrics.csv"), &robustness_rows)?;
     }
 
+    write_timing_csv(&output_dir.join("timing.csv"), &timing_rows)?;
+    write_manifest_json(
+        &output_dir.join("manifest.json"),
+        config,
+        Some(started.elapsed().as_secs_f64()),
+    )?;
+
     progress.finish_all();
 
     Ok(SweepResult {
@@ -668,53 +433,14 @@ fn canonical_steps(config: &SimulationConfig, sweep_steps: &[usize]) -> usize {
 }
 
 fn total_progress_units(
-    config: &SimulationConfig,
+    enabled: &[&dyn RegisteredSubTheory],
     sweep_step_count: usize,
     lambda_count: usize,
 ) -> usize {
-    let stage_count = usize::from(config.enable_aet) * 2
-        + usize::from(config.enable_tcp)
-        + usize::from(config.enable_rlt) * 2
-        + usize::from(config.enable_iwlt) * 2;
+    let stage_count: usize = enabled.iter().map(|theory| theory.sweep_count()).sum();
     stage_count * sweep_step_count * lambda_count
 }
 
-fn points_dirs(
-    output_dir: &Path,
-    steps_per_run: usize,
-    use_step_suffix: bool,
-    is_canonical: bool,
-) -> Vec<PathBuf> {
-    let mut dirs = Vec::new();
-    if use_step_suffix {
-        dirs.push(output_dir.join(format!("tcp_points_N{steps_per_run}")));
-        if is_canonical {
-            dirs.push(output_dir.join("tcp_points"));
-        }
-    } else {
-        dirs.push(output_dir.join("tcp_points"));
-    }
-    dirs
-}
-
-fn example_dirs(
-    output_dir: &Path,
-    steps_per_run: usize,
-    use_step_suffix: bool,
-    is_canonical: bool,
-) -> Vec<PathBuf> {
-    let mut dirs = Vec::new();
-    if use_step_suffix {
-        dirs.push(output_dir.join(format!("rlt_examples_N{steps_per_run}")));
-        if is_canonical {
-            dirs.push(output_dir.join("rlt_examples"));
-        }
-    } else {
-        dirs.push(output_dir.join("rlt_examples"));
-    }
-    dirs
-}
-
 fn phase_row(
     mode: &str,
     is_perturbed: bool,
@@ -757,6 +483,26 @@ fn law_summary_row(
     }
 }
 
+fn cross_subsystem_row(
+    steps_per_run: usize,
+    is_perturbed: bool,
+    stats: CrossSubsystemStats,
+) -> CrossSubsystemRow {
+    CrossSubsystemRow {
+        steps_per_run,
+        is_perturbed,
+        pearson_r: stats.pearson_r,
+        spearman_rho: stats.spearman_rho,
+        peak_xcorr: stats.peak_xcorr,
+        peak_lag: stats.peak_lag,
+        peak_lag_lambda: stats.peak_lag_lambda,
+        mutual_information: stats.mutual_information,
+        normalized_mutual_information: stats.normalized_mutual_information,
+        histogram_bins: stats.histogram_bins,
+        sample_count: stats.sample_count,
+    }
+}
+
 fn closest_lambda_index(lambda_grid: &[f64], target: Option<f64>) -> Option<usize> {
     let target = target?;
     lambda_grid
@@ -806,58 +552,9 @@ fn tcp_phase_alignment_row(
     }
 }
 
-fn comparison_metric(
-    metric: &str,
-    steps_per_run: usize,
-    baseline: f64,
-    perturbed: f64,
-) -> RobustnessMetricRow {
-    RobustnessMetricRow {
-        metric: metric.to_string(),
-        steps_per_run,
-        baseline,
-        perturbed,
-        delta: perturbed - baseline,
-    }
-}
-
-fn comparison_metric_option(
-    metric: &str,
-    steps_per_run: usize,
-    baseline: Option<f64>,
-    perturbed: Option<f64>,
-) -> RobustnessMetricRow {
-    comparison_metric(
-        metric,
-        steps_per_run,
-        baseline.unwrap_or(f64::NAN),
-        perturbed.unwrap_or(f64::NAN),
-    )
-}
-
 fn option_diff(left: Option<f64>, right: Option<f64>) -> Option<f64> {
     match (left, right) {
         (Some(left), Some(right)) => Some(left - right),
         _ => None,
     }
 }
-
-fn curve_l2_diff(baseline: &[f64], perturbed: &[f64]) -> f64 {
-    baseline
-        .iter()
-        .zip(perturbed.iter())
-        .map(|(base, perturbed_value)| {
-            let delta = perturbed_value - base;
-            delta * delta
-        })
-        .sum::<f64>()
-        .sqrt()
-}
-
-fn curve_max_abs_diff(baseline: &[f64], perturbed: &[f64]) -> f64 {
-    baseline
-        .iter()
-        .zip(perturbed.iter())
-        .map(|(base, perturbed_value)| (perturbed_value - base).abs())
-        .fold(0.0_f64, f64::max)
-}
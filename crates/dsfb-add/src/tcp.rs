@@ -1,9 +1,12 @@
 use std::collections::{HashSet, VecDeque};
+use std::fs;
 
 use serde::{Deserialize, Serialize};
 
 use crate::config::SimulationConfig;
-use crate::sweep::deterministic_drive;
+use crate::output::{write_tcp_csv, write_tcp_points_csv, RobustnessMetricRow};
+use crate::subtheory::{OutputTarget, SubTheory, SweepProgress};
+use crate::sweep::{deterministic_drive, SweepRunResult};
 use crate::AddError;
 
 pub const NUM_TCP_RUNS_PER_LAMBDA: usize = 5;
@@ -110,6 +113,82 @@ where
     })
 }
 
+/// TCP as a [`SubTheory`]: a baseline sweep with per-lambda point clouds.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TcpTheory;
+
+impl SubTheory for TcpTheory {
+    type Curves = TcpSweep;
+
+    fn name(&self) -> &'static str {
+        "TCP"
+    }
+
+    fn is_enabled(&self, config: &SimulationConfig) -> bool {
+        config.enable_tcp
+    }
+
+    fn sweep_count(&self) -> usize {
+        1
+    }
+
+    fn run(
+        &self,
+        config: &SimulationConfig,
+        lambda_grid: &[f64],
+        progress: &mut SweepProgress<'_>,
+    ) -> Result<Self::Curves, AddError> {
+        progress.sweep("TCP baseline", |report| {
+            run_tcp_sweep_with_progress(config, lambda_grid, report)
+        })
+    }
+
+    fn write_outputs(
+        &self,
+        curves: &Self::Curves,
+        target: &OutputTarget<'_>,
+    ) -> Result<(), AddError> {
+        for path in target.csv_paths("tcp_sweep") {
+            write_tcp_csv(
+                &path,
+                target.lambda_grid,
+                &curves.betti0,
+                &curves.betti1,
+                &curves.l_tcp,
+                &curves.avg_radius,
+                &curves.max_radius,
+                &curves.variance_radius,
+                target.config.steps_per_run,
+                false,
+            )?;
+        }
+
+        for points_dir in target.dirs("tcp_points") {
+            fs::create_dir_all(&points_dir)?;
+            for (idx, runs_for_lambda) in curves.point_cloud_runs.iter().enumerate() {
+                for (run_idx, points) in runs_for_lambda.iter().enumerate() {
+                    let filename = format!("lambda_{idx:03}_run_{run_idx:02}.csv");
+                    write_tcp_points_csv(&points_dir.join(filename), points)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn robustness_metrics(
+        &self,
+        _curves: &Self::Curves,
+        _lambda_grid: &[f64],
+        _steps_per_run: usize,
+    ) -> Result<Vec<RobustnessMetricRow>, AddError> {
+        Ok(Vec::new())
+    }
+
+    fn record(&self, curves: Self::Curves, run: &mut SweepRunResult) {
+        run.tcp = Some(curves);
+    }
+}
+
 fn simulate_tcp_run(
     config: &SimulationConfig,
     lambda: f64,
@@ -119,6 +198,7 @@ fn simulate_tcp_run(
 ) -> Vec<TcpPoint> {
     let lambda_norm = config.normalized_lambda(lambda);
     let drive = deterministic_drive(
+        &config.drive,
         config.random_seed ^ ((run_idx as u64 + 1) << 20),
         lambda,
         0x7CD0_u64 + lambda_idx as u64 * 17 + run_idx as u64,
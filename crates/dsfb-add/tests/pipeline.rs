@@ -0,0 +1,291 @@
+//! End-to-end sweeps over a miniature lambda grid (20 and 40 steps per run,
+//! two seeds), checking every output file and its schema.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use dsfb_add::{
+    default_registry, find_subtheory, run_sweeps_into_dir, AddError, GraphExportFormat, RltConfig,
+    SimulationConfig,
+};
+
+const NUM_LAMBDA: usize = 8;
+const STEPS: [usize; 2] = [20, 40];
+/// Sub-theories that also write a perturbed-lambda sweep
+const PERTURBED: [&str; 3] = ["aet", "rlt", "iwlt"];
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dsfb-add-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn tiny_config(random_seed: u64) -> SimulationConfig {
+    SimulationConfig {
+        num_lambda: NUM_LAMBDA,
+        steps_per_run: STEPS[0],
+        multi_steps_per_run: STEPS.to_vec(),
+        random_seed,
+        rlt_graph_export: GraphExportFormat::EdgeList,
+        ..SimulationConfig::default()
+    }
+}
+
+fn file_names(dir: &Path) -> BTreeSet<String> {
+    fs::read_dir(dir)
+        .expect("output dir exists")
+        .map(|entry| entry.expect("dir entry").file_name().into_string().unwrap())
+        .collect()
+}
+
+fn expected_files() -> BTreeSet<String> {
+    let mut files: BTreeSet<String> = [
+        "aet_iwlt_diagnostics_summary.csv",
+        "aet_iwlt_law_summary.csv",
+        "aet_iwlt_scaling_summary.csv",
+        "cross_layer_thresholds.csv",
+        "cross_subsystem.csv",
+        "manifest.json",
+        "rlt_phase_boundary.csv",
+        "robustness_metrics.csv",
+        "tcp_phase_alignment.csv",
+        "timing.csv",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
+
+    // Per-N outputs, plus an unsuffixed copy of the first N
+    let suffixes = std::iter::once(String::new()).chain(STEPS.iter().map(|n| format!("_N{n}")));
+    for suffix in suffixes {
+        for theory in ["aet", "tcp", "rlt", "iwlt"] {
+            files.insert(format!("{theory}_sweep{suffix}.csv"));
+        }
+        for theory in PERTURBED {
+            files.insert(format!("{theory}_sweep_perturbed{suffix}.csv"));
+        }
+        files.insert(format!("rlt_examples{suffix}"));
+        files.insert(format!("tcp_points{suffix}"));
+    }
+    files
+}
+
+/// Header and data rows of a CSV file.
+fn read_csv(path: &Path) -> (Vec<String>, Vec<csv::StringRecord>) {
+    let mut reader = csv::Reader::from_path(path).expect("csv opens");
+    let header = reader
+        .headers()
+        .expect("csv header")
+        .iter()
+        .map(String::from)
+        .collect();
+    let records = reader.records().map(|r| r.expect("csv record")).collect();
+    (header, records)
+}
+
+fn check_sweep(dir: &Path, theory: &str, metrics: &[&str]) {
+    let mut expected = vec!["lambda"];
+    expected.extend_from_slice(metrics);
+    expected.extend_from_slice(&["steps_per_run", "is_perturbed"]);
+
+    for (steps, suffix) in STEPS.iter().map(|n| (*n, format!("_N{n}"))) {
+        let variants: &[bool] = if PERTURBED.contains(&theory) {
+            &[false, true]
+        } else {
+            &[false]
+        };
+        for &perturbed in variants {
+            let variant = if perturbed { "_perturbed" } else { "" };
+            let name = format!("{theory}_sweep{variant}{suffix}.csv");
+            let (header, rows) = read_csv(&dir.join(&name));
+            assert_eq!(header, expected, "header of {name}");
+            assert_eq!(rows.len(), NUM_LAMBDA, "one row per lambda in {name}");
+            for row in &rows {
+                assert_eq!(row[header.len() - 2].parse::<usize>().unwrap(), steps);
+                assert_eq!(row[header.len() - 1].parse::<bool>().unwrap(), perturbed);
+            }
+        }
+    }
+
+    let first = format!("{theory}_sweep_N{}.csv", STEPS[0]);
+    assert_eq!(
+        fs::read(dir.join(format!("{theory}_sweep.csv"))).unwrap(),
+        fs::read(dir.join(first)).unwrap(),
+        "unsuffixed {theory} sweep is the first N"
+    );
+}
+
+#[test]
+fn sweeps_write_every_output_with_its_schema() {
+    let scratch = scratch_dir("pipeline");
+    for seed in [1, 2] {
+        let dir = scratch.join(format!("seed{seed}"));
+        let result = run_sweeps_into_dir(&tiny_config(seed), &dir).expect("sweeps run");
+        assert_eq!(result.runs.len(), STEPS.len());
+        assert_eq!(result.lambda_grid.len(), NUM_LAMBDA);
+        assert_eq!(file_names(&dir), expected_files());
+
+        check_sweep(&dir, "aet", &["echo_slope", "avg_increment"]);
+        check_sweep(&dir, "iwlt", &["entropy_density", "avg_increment"]);
+        check_sweep(&dir, "rlt", &["escape_rate", "expansion_ratio"]);
+        check_sweep(
+            &dir,
+            "tcp",
+            &[
+                "betti0",
+                "betti1",
+                "l_tcp",
+                "avg_radius",
+                "max_radius",
+                "variance_radius",
+            ],
+        );
+
+        for (name, first_column) in [
+            ("aet_iwlt_diagnostics_summary.csv", "steps_per_run"),
+            ("aet_iwlt_law_summary.csv", "steps_per_run"),
+            ("cross_layer_thresholds.csv", "steps_per_run"),
+            ("cross_subsystem.csv", "steps_per_run"),
+            ("rlt_phase_boundary.csv", "steps_per_run"),
+            ("tcp_phase_alignment.csv", "steps_per_run"),
+            ("robustness_metrics.csv", "metric"),
+        ] {
+            let (header, rows) = read_csv(&dir.join(name));
+            assert_eq!(header[0], first_column, "first column of {name}");
+            assert!(!rows.is_empty(), "{name} has rows");
+        }
+
+        let examples = file_names(&dir.join("rlt_examples"));
+        assert!(examples.iter().any(|n| n.starts_with("trajectory_")));
+        assert!(examples.iter().any(|n| n.starts_with("graph_")));
+        assert!(!file_names(&dir.join("tcp_points")).is_empty());
+
+        let manifest: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.join("manifest.json")).unwrap()).unwrap();
+        assert_eq!(manifest["config"]["random_seed"], seed);
+        assert_eq!(manifest["config"]["num_lambda"], NUM_LAMBDA);
+        assert!(manifest["total_runtime_s"].as_f64().unwrap() >= 0.0);
+
+        // One row per lambda sample of every baseline and perturbed sweep
+        let (header, rows) = read_csv(&dir.join("timing.csv"));
+        assert_eq!(header[1], "subsystem");
+        assert_eq!(rows.len(), STEPS.len() * (4 + PERTURBED.len()) * NUM_LAMBDA);
+    }
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn sweeps_are_deterministic_for_a_seed() {
+    let scratch = scratch_dir("determinism");
+    let config = tiny_config(7);
+    let first = scratch.join("first");
+    let second = scratch.join("second");
+    run_sweeps_into_dir(&config, &first).expect("first sweep");
+    run_sweeps_into_dir(&config, &second).expect("second sweep");
+
+    // Wall-clock timings are the one output that varies between runs
+    for name in file_names(&first)
+        .iter()
+        .filter(|n| n.ends_with(".csv") && *n != "timing.csv")
+    {
+        assert_eq!(
+            fs::read(first.join(name)).unwrap(),
+            fs::read(second.join(name)).unwrap(),
+            "{name} differs between identical runs"
+        );
+    }
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn default_registry_lists_each_builtin_subtheory_once() {
+    let registry = default_registry();
+    let names: Vec<&str> = registry.iter().map(|theory| theory.name()).collect();
+    assert_eq!(names, ["AET", "TCP", "RLT", "IWLT"]);
+
+    for name in names {
+        let found =
+            find_subtheory(&registry, &name.to_lowercase()).expect("built-in is registered");
+        assert_eq!(found.name(), name);
+    }
+    let err = find_subtheory(&registry, "xyz")
+        .err()
+        .expect("unknown name is rejected");
+    assert!(matches!(err, AddError::InvalidInput(_)), "{err}");
+    assert!(err.to_string().contains("unknown sub-theory xyz"), "{err}");
+}
+
+#[test]
+fn drive_config_parses_partially_and_rejects_out_of_range_observers() {
+    use dsfb_add::DriveConfig;
+
+    let drive: DriveConfig = serde_json::from_str(
+        r#"{ "observer": { "k_phi": 0.3, "k_omega": 0.05, "k_alpha": 0.0, "rho": 0.9,
+             "sigma0": 0.2, "min_weight": 0.1 }, "warmup_steps": 8 }"#,
+    )
+    .expect("drive parses");
+    assert_eq!(drive.observer.k_phi, 0.3);
+    assert_eq!(drive.observer.min_weight, 0.1);
+    assert_eq!(drive.warmup_steps, 8);
+    assert_eq!(
+        drive.channel_amplitudes,
+        DriveConfig::default().channel_amplitudes
+    );
+    drive.validate().expect("drive is valid");
+    DriveConfig::default().validate().expect("default is valid");
+
+    let rejects = |edit: fn(&mut DriveConfig), field: &str| {
+        let mut drive = DriveConfig::default();
+        edit(&mut drive);
+        let err = drive.validate().expect_err(field);
+        assert!(matches!(err, AddError::InvalidConfig(_)), "{err}");
+        assert!(err.to_string().contains(field), "{err}");
+    };
+    rejects(|d| d.observer.k_phi = f64::NAN, "gains");
+    rejects(|d| d.observer.rho = 1.0, "rho");
+    rejects(|d| d.observer.sigma0 = 0.0, "sigma0");
+    rejects(|d| d.observer.min_weight = -0.1, "min_weight");
+    rejects(|d| d.observer.min_weight = 0.5, "min_weight");
+    rejects(|d| d.observer.staleness_decay = 1.5, "staleness_decay");
+    rejects(|d| d.observer.staleness_decay = f64::NAN, "staleness_decay");
+    rejects(|d| d.warmup_steps = 0, "warmup_steps");
+    rejects(|d| d.channel_amplitudes[1] = -0.1, "channel_amplitudes");
+
+    let config = SimulationConfig {
+        drive: DriveConfig {
+            warmup_steps: 0,
+            ..DriveConfig::default()
+        },
+        ..tiny_config(1)
+    };
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn graph_export_format_config_accepts_the_cli_aliases() {
+    for raw in ["none", "edge_list", "edgelist", "csv", "graphml"] {
+        let from_config: GraphExportFormat =
+            serde_json::from_str(&format!("\"{raw}\"")).expect("config value parses");
+        assert_eq!(from_config, GraphExportFormat::parse(raw).unwrap(), "{raw}");
+    }
+    assert!(serde_json::from_str::<GraphExportFormat>("\"dot\"").is_err());
+    assert!(GraphExportFormat::parse("dot").is_err());
+}
+
+#[test]
+fn rlt_config_rejects_a_shrinking_leash() {
+    RltConfig::default().validate().expect("default is valid");
+
+    for (leash_base, leash_growth) in [(-1, 10.0), (2, -0.5)] {
+        let rules = RltConfig {
+            leash_base,
+            leash_growth,
+            ..RltConfig::default()
+        };
+        let err = rules.validate().expect_err("negative leash");
+        assert!(matches!(err, AddError::InvalidConfig(_)), "{err}");
+    }
+}
@@ -0,0 +1,132 @@
+//! Disturbance and residual traces for `dsfb-ddmf`.
+//!
+//! Each trace covers one disturbance regime on a small grouped channel set:
+//! per channel, the disturbance `d[n]` and the residual `r[n] = epsilon[n] + d[n]`
+//! that drives the envelope recursion.
+
+use anyhow::{Context, Result};
+use csv::Writer;
+use serde::Serialize;
+use std::path::Path;
+
+use dsfb_ddmf::disturbances::DisturbanceKind;
+use dsfb_ddmf::envelope::WeightMapping;
+use dsfb_ddmf::sim::{run_multichannel_simulation, EpsilonModel, SimulationConfig};
+
+use crate::manifest::{DatasetEntry, DatasetFile};
+
+const TRACE_STEPS: usize = 180;
+const TRACE_GROUPS: [usize; 4] = [0, 0, 1, 1];
+
+/// Generation parameters for one disturbance trace file.
+#[derive(Debug, Clone, Serialize)]
+pub struct DdmfTraceSpec {
+    pub name: &'static str,
+    pub group_assignments: Vec<usize>,
+    pub correlated_groups: bool,
+    pub simulation: SimulationConfig,
+}
+
+/// Canonical trace set: the regimes of the HRET comparison plus slew.
+pub fn ddmf_trace_specs() -> Vec<DdmfTraceSpec> {
+    let start = TRACE_STEPS / 6;
+    let kinds = [
+        (
+            "impulse",
+            DisturbanceKind::Impulsive {
+                amplitude: 1.4,
+                start,
+                len: 7,
+            },
+        ),
+        (
+            "persistent",
+            DisturbanceKind::PersistentElevated {
+                r_nom: 0.05,
+                r_high: 0.65,
+                step_time: start,
+            },
+        ),
+        (
+            "drift",
+            DisturbanceKind::Drift {
+                b: 0.01,
+                s_max: 0.6,
+            },
+        ),
+        ("bounded", DisturbanceKind::PointwiseBounded { d: 0.1 }),
+        ("slew", DisturbanceKind::SlewRateBounded { s_max: 0.005 }),
+    ];
+
+    kinds
+        .into_iter()
+        .map(|(name, disturbance_kind)| DdmfTraceSpec {
+            name,
+            group_assignments: TRACE_GROUPS.to_vec(),
+            correlated_groups: true,
+            simulation: SimulationConfig {
+                n_steps: TRACE_STEPS,
+                rho: 0.96,
+                beta: 3.0,
+                disturbance_kind,
+                epsilon_bound: 0.05,
+                epsilon_model: EpsilonModel::Sinusoidal,
+                weight_mapping: WeightMapping::Rational,
+                w_min: 0.0,
+                envelope_cap: None,
+            },
+        })
+        .collect()
+}
+
+pub fn write_ddmf_traces(outdir: &Path) -> Result<DatasetEntry> {
+    let specs = ddmf_trace_specs();
+    let mut files = Vec::with_capacity(specs.len());
+
+    for spec in &specs {
+        let file = format!("ddmf_trace_{}.csv", spec.name);
+        let rows = write_trace_csv(&outdir.join(&file), spec)?;
+        files.push(DatasetFile::record(outdir, &file, rows)?);
+    }
+
+    Ok(DatasetEntry {
+        name: "ddmf_disturbance_traces".to_string(),
+        source: "dsfb-ddmf".to_string(),
+        description: "Per-channel disturbance d and residual r for each disturbance regime \
+                      over a two-group, four-channel set"
+            .to_string(),
+        config: serde_json::to_value(&specs)?,
+        files,
+    })
+}
+
+fn write_trace_csv(path: &Path, spec: &DdmfTraceSpec) -> Result<usize> {
+    let channels = run_multichannel_simulation(
+        &spec.simulation,
+        spec.group_assignments.len(),
+        Some(&spec.group_assignments),
+        spec.correlated_groups,
+    );
+
+    let mut writer =
+        Writer::from_path(path).with_context(|| format!("failed to create {}", path.display()))?;
+
+    let mut header = vec!["n".to_string()];
+    for k in 0..channels.len() {
+        header.push(format!("ch{k}_d"));
+        header.push(format!("ch{k}_r"));
+    }
+    writer.write_record(&header)?;
+
+    for n in 0..spec.simulation.n_steps {
+        let mut record = vec![n.to_string()];
+        for channel in &channels {
+            record.push(channel.d[n].to_string());
+            record.push(channel.r[n].to_string());
+        }
+        writer.write_record(&record)?;
+    }
+
+    writer.flush()?;
+    Ok(spec.simulation.n_steps)
+}
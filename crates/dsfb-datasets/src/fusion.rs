@@ -0,0 +1,119 @@
+//! Recorded measurement frames for `dsfb-fusion-bench`.
+//!
+//! The diagnostic model (`H`, `R`) is written once and the frames once per
+//! seed, so external tools can run their own reconstruction on exactly the
+//! inputs the benchmark methods see.
+
+use anyhow::{Context, Result};
+use csv::Writer;
+use std::path::Path;
+
+use dsfb_fusion_bench::sim::diagnostics::{build_diagnostic_model, DiagnosticModel};
+use dsfb_fusion_bench::sim::state::{generate_simulation_data, BenchConfig};
+
+use crate::manifest::{DatasetEntry, DatasetFile};
+
+/// Benchmark configuration the frames are generated from.
+pub const FUSION_BENCH_SPEC: &str = include_str!("../specs/fusion_bench.toml");
+
+pub fn fusion_bench_config() -> Result<BenchConfig> {
+    let cfg: BenchConfig =
+        toml::from_str(FUSION_BENCH_SPEC).context("failed to parse fusion-bench spec")?;
+    cfg.validate()?;
+    Ok(cfg)
+}
+
+pub fn write_fusion_bench(outdir: &Path) -> Result<DatasetEntry> {
+    let cfg = fusion_bench_config()?;
+    let model = build_diagnostic_model(&cfg)?;
+
+    let model_file = "fusion_bench_model.csv";
+    let rows = write_model_csv(&outdir.join(model_file), &model)?;
+    let mut files = vec![DatasetFile::record(outdir, model_file, rows)?];
+
+    for &seed in &cfg.seeds {
+        let file = format!("fusion_bench_frames_seed{seed}.csv");
+        let rows = write_frames_csv(&outdir.join(&file), &cfg, &model, seed)?;
+        files.push(DatasetFile::record(outdir, &file, rows)?);
+    }
+
+    Ok(DatasetEntry {
+        name: "fusion_bench_frames".to_string(),
+        source: "dsfb-fusion-bench".to_string(),
+        description: "Diagnostic model rows (H, R) and per-step true state, grouped \
+                      measurements, and corruption flag for each seed"
+            .to_string(),
+        config: serde_json::to_value(&cfg)?,
+        files,
+    })
+}
+
+fn write_model_csv(path: &Path, model: &DiagnosticModel) -> Result<usize> {
+    let mut writer =
+        Writer::from_path(path).with_context(|| format!("failed to create {}", path.display()))?;
+
+    let mut header = vec![
+        "group".to_string(),
+        "row".to_string(),
+        "bandwidth_mismatch".to_string(),
+        "r_diag".to_string(),
+    ];
+    header.extend((0..model.n).map(|c| format!("h_{c}")));
+    writer.write_record(&header)?;
+
+    let mut rows = 0;
+    for (k, group) in model.groups.iter().enumerate() {
+        for r in 0..group.dim() {
+            let mut record = vec![
+                k.to_string(),
+                r.to_string(),
+                group.bandwidth_mismatch.to_string(),
+                group.r_diag[r].to_string(),
+            ];
+            record.extend((0..model.n).map(|c| group.h[(r, c)].to_string()));
+            writer.write_record(&record)?;
+            rows += 1;
+        }
+    }
+
+    writer.flush()?;
+    Ok(rows)
+}
+
+fn write_frames_csv(
+    path: &Path,
+    cfg: &BenchConfig,
+    model: &DiagnosticModel,
+    seed: u64,
+) -> Result<usize> {
+    let data = generate_simulation_data(cfg, model, seed)?;
+    let mut writer =
+        Writer::from_path(path).with_context(|| format!("failed to create {}", path.display()))?;
+
+    let mut header = vec![
+        "step".to_string(),
+        "t".to_string(),
+        "corruption_active".to_string(),
+    ];
+    header.extend((0..cfg.n).map(|i| format!("x_{i}")));
+    for (k, &m_k) in cfg.group_dims.iter().enumerate() {
+        header.extend((0..m_k).map(|i| format!("y_g{k}_{i}")));
+    }
+    writer.write_record(&header)?;
+
+    for (step, frame) in data.measurements.iter().enumerate() {
+        let mut record = vec![
+            step.to_string(),
+            data.t[step].to_string(),
+            data.corruption_active[step].to_string(),
+        ];
+        record.extend(data.x_true[step].iter().map(f64::to_string));
+        for y in &frame.y_groups {
+            record.extend(y.iter().map(f64::to_string));
+        }
+        writer.write_record(&record)?;
+    }
+
+    writer.flush()?;
+    Ok(data.measurements.len())
+}
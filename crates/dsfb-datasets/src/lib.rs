@@ -0,0 +1,76 @@
+//! Canonical, versioned input datasets for the DSFB workspace.
+//!
+//! The generators replay each crate's own deterministic scenario and write
+//! the raw inputs (fusion-bench measurement frames, starship IMU logs, DDMF
+//! disturbance traces) as CSV, together with a manifest recording the exact
+//! configurations and the SHA-256 of every file.
+
+pub mod ddmf;
+pub mod fusion;
+pub mod manifest;
+pub mod starship;
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+
+pub use manifest::{
+    read_manifest, verify_dir, write_manifest, DatasetEntry, DatasetFile, DatasetManifest,
+    HashDivergence, DATASET_VERSION, MANIFEST_FILE,
+};
+
+/// Generates every dataset into `outdir` and writes its manifest.
+pub fn generate_all(outdir: &Path) -> Result<DatasetManifest> {
+    fs::create_dir_all(outdir)
+        .with_context(|| format!("failed to create dataset directory: {}", outdir.display()))?;
+
+    let manifest = DatasetManifest::new(vec![
+        fusion::write_fusion_bench(outdir)?,
+        starship::write_starship_imu_log(outdir)?,
+        ddmf::write_ddmf_traces(outdir)?,
+    ]);
+    write_manifest(outdir, &manifest)?;
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("dsfb-datasets-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn generation_is_deterministic_and_verifiable() {
+        let first = scratch_dir("first");
+        let second = scratch_dir("second");
+
+        let a = generate_all(&first).unwrap();
+        let b = generate_all(&second).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.files().count(), 1 + 3 + 1 + 5);
+        assert!(a.files().all(|file| file.rows > 0));
+        assert!(verify_dir(&first).unwrap().is_empty());
+
+        let tampered = &a.datasets[2].files[0].file;
+        fs::write(first.join(tampered), "n\n0\n").unwrap();
+        let missing = &a.datasets[1].files[0].file;
+        fs::remove_file(first.join(missing)).unwrap();
+
+        let divergences = verify_dir(&first).unwrap();
+        assert_eq!(divergences.len(), 2);
+        assert!(divergences
+            .iter()
+            .any(|d| &d.file == missing && d.actual.is_none()));
+        assert!(divergences
+            .iter()
+            .any(|d| &d.file == tampered && d.actual.is_some()));
+
+        let _ = fs::remove_dir_all(&first);
+        let _ = fs::remove_dir_all(&second);
+    }
+}
@@ -0,0 +1,68 @@
+use anyhow::{bail, Result};
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+use dsfb_datasets::{generate_all, verify_dir, DATASET_VERSION};
+
+#[derive(Debug, Parser)]
+#[command(name = "dsfb-datasets")]
+#[command(about = "Generate and verify the canonical DSFB benchmark input datasets")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Generate every dataset and its manifest
+    Generate {
+        /// Output directory [default: output-dsfb-datasets/v<DATASET_VERSION>]
+        #[arg(long)]
+        outdir: Option<PathBuf>,
+    },
+    /// Check every file listed in <DIR>/manifest.json against its SHA-256
+    Verify {
+        #[arg(value_name = "DIR")]
+        dir: PathBuf,
+    },
+}
+
+fn main() -> Result<()> {
+    match Cli::parse().command {
+        Command::Generate { outdir } => {
+            let outdir = outdir.unwrap_or_else(|| {
+                PathBuf::from("output-dsfb-datasets").join(format!("v{DATASET_VERSION}"))
+            });
+            let manifest = generate_all(&outdir)?;
+
+            println!(
+                "dataset version {} written to {}",
+                manifest.dataset_version,
+                outdir.display()
+            );
+            for file in manifest.files() {
+                println!("  {}  {:>6} rows  {}", file.sha256, file.rows, file.file);
+            }
+        }
+        Command::Verify { dir } => {
+            let divergences = verify_dir(&dir)?;
+            if !divergences.is_empty() {
+                for d in &divergences {
+                    println!(
+                        "MISMATCH {}: expected {}, found {}",
+                        d.file,
+                        d.expected,
+                        d.actual.as_deref().unwrap_or("<missing>")
+                    );
+                }
+                bail!("{} dataset file(s) failed verification", divergences.len());
+            }
+            println!(
+                "all dataset files match {}",
+                dir.join("manifest.json").display()
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,128 @@
+//! Dataset manifest: what was generated, from which configuration, and the
+//! SHA-256 of every file, so a published set can be checked byte for byte.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Version of the canonical dataset set. Bump it whenever a spec or a
+/// generator changes the bytes of any published file.
+pub const DATASET_VERSION: &str = "1.1.0";
+
+/// Manifest file name inside a dataset directory.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// One generated file and its content hash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatasetFile {
+    /// Path relative to the dataset directory
+    pub file: String,
+    /// Data rows, excluding the CSV header
+    pub rows: usize,
+    pub sha256: String,
+}
+
+impl DatasetFile {
+    /// Hashes `outdir/file` and records it with its row count.
+    pub fn record(outdir: &Path, file: &str, rows: usize) -> Result<Self> {
+        Ok(Self {
+            file: file.to_string(),
+            rows,
+            sha256: sha256_file(&outdir.join(file))?,
+        })
+    }
+}
+
+/// A named dataset: the crate it feeds, the exact configuration that produced
+/// it, and its files.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DatasetEntry {
+    pub name: String,
+    /// Workspace crate whose generator produced the data
+    pub source: String,
+    pub description: String,
+    pub config: serde_json::Value,
+    pub files: Vec<DatasetFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DatasetManifest {
+    pub dataset_version: String,
+    /// Generator name and version, e.g. `dsfb-datasets 0.1.0`
+    pub generator: String,
+    pub datasets: Vec<DatasetEntry>,
+}
+
+impl DatasetManifest {
+    pub fn new(datasets: Vec<DatasetEntry>) -> Self {
+        Self {
+            dataset_version: DATASET_VERSION.to_string(),
+            generator: format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+            datasets,
+        }
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = &DatasetFile> {
+        self.datasets
+            .iter()
+            .flat_map(|dataset| dataset.files.iter())
+    }
+}
+
+/// A manifest file whose hash does not match the file on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashDivergence {
+    pub file: String,
+    pub expected: String,
+    /// `None` when the file is missing
+    pub actual: Option<String>,
+}
+
+/// Lower-case hex SHA-256 of a file's bytes.
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let bytes = fs::read(path)
+        .with_context(|| format!("failed to read for hashing: {}", path.display()))?;
+    Ok(format!("{:x}", Sha256::digest(&bytes)))
+}
+
+pub fn write_manifest(outdir: &Path, manifest: &DatasetManifest) -> Result<PathBuf> {
+    let path = outdir.join(MANIFEST_FILE);
+    let json = serde_json::to_string_pretty(manifest).context("failed to serialize manifest")?;
+    fs::write(&path, json + "\n")
+        .with_context(|| format!("failed to write manifest: {}", path.display()))?;
+    Ok(path)
+}
+
+pub fn read_manifest(dir: &Path) -> Result<DatasetManifest> {
+    let path = dir.join(MANIFEST_FILE);
+    let raw = fs::read_to_string(&path)
+        .with_context(|| format!("failed to read manifest: {}", path.display()))?;
+    serde_json::from_str(&raw)
+        .with_context(|| format!("failed to parse manifest: {}", path.display()))
+}
+
+/// Re-hashes every file listed in `dir/manifest.json`.
+pub fn verify_dir(dir: &Path) -> Result<Vec<HashDivergence>> {
+    let manifest = read_manifest(dir)?;
+    let mut divergences = Vec::new();
+
+    for file in manifest.files() {
+        let path = dir.join(&file.file);
+        let actual = if path.exists() {
+            Some(sha256_file(&path)?)
+        } else {
+            None
+        };
+        if actual.as_deref() != Some(file.sha256.as_str()) {
+            divergences.push(HashDivergence {
+                file: file.file.clone(),
+                expected: file.sha256.clone(),
+                actual,
+            });
+        }
+    }
+
+    Ok(divergences)
+}
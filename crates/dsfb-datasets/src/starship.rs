@@ -0,0 +1,167 @@
+//! Raw redundant-IMU logs from the `dsfb-starship` re-entry scenario.
+//!
+//! The truth trajectory and IMU array are stepped exactly as in
+//! `dsfb_starship::run_simulation`, without the estimators, so the log holds
+//! the sensor inputs every navigation stack is fed.
+
+use anyhow::{Context, Result};
+use csv::Writer;
+use std::path::Path;
+
+use dsfb_starship::config::SimConfig;
+use dsfb_starship::physics::{
+    descent_complete, initial_truth_state, truth_step, ReentryEventState, VehicleParams,
+};
+use dsfb_starship::sensors::ImuArray;
+
+use crate::manifest::{DatasetEntry, DatasetFile};
+
+pub fn write_starship_imu_log(outdir: &Path) -> Result<DatasetEntry> {
+    let cfg = SimConfig::default();
+    cfg.validate()?;
+
+    let file = "starship_imu_log.csv";
+    let rows = write_imu_csv(&outdir.join(file), &cfg)?;
+
+    Ok(DatasetEntry {
+        name: "starship_imu_log".to_string(),
+        source: "dsfb-starship".to_string(),
+        description: "Per-step true specific force and body rate, heat-shield state, blackout \
+                      flag, and every IMU's accelerometer and gyro output"
+            .to_string(),
+        config: serde_json::to_value(&cfg)?,
+        files: vec![DatasetFile::record(outdir, file, rows)?],
+    })
+}
+
+fn write_imu_csv(path: &Path, cfg: &SimConfig) -> Result<usize> {
+    let vehicle = VehicleParams::default();
+    let mut truth = initial_truth_state(cfg, &vehicle);
+    let mut events = ReentryEventState::from_config(cfg);
+    let mut imu_array = ImuArray::from_config(cfg);
+
+    let mut writer =
+        Writer::from_path(path).with_context(|| format!("failed to create {}", path.display()))?;
+
+    let mut header: Vec<String> = [
+        "step",
+        "time_s",
+        "altitude_m",
+        "heat_shield_temp_k",
+        "blackout",
+        "true_fx_mps2",
+        "true_fy_mps2",
+        "true_fz_mps2",
+        "true_wx_rps",
+        "true_wy_rps",
+        "true_wz_rps",
+    ]
+    .map(String::from)
+    .to_vec();
+    for i in 0..cfg.imu_count {
+        header.extend(
+            [
+                "ax_mps2", "ay_mps2", "az_mps2", "gx_rps", "gy_rps", "gz_rps",
+            ]
+            .map(|axis| format!("imu{i}_{axis}")),
+        );
+    }
+    writer.write_record(&header)?;
+
+    let mut rows = 0;
+    for step_idx in 0..cfg.steps() {
+        let t_s = step_idx as f64 * cfg.dt;
+
+        let sample = truth_step(&mut truth, &vehicle, cfg, t_s, cfg.dt, &mut events);
+        let force = sample.specific_force_b_mps2;
+        let omega = truth.omega_b_rps;
+        let imu = imu_array.measure(force, omega, truth.heat_shield_temp_k, t_s, &events);
+
+        if !(truth.pos_n_m.iter().all(|v| v.is_finite())
+            && truth.vel_n_mps.iter().all(|v| v.is_finite()))
+        {
+            break;
+        }
+
+        let mut record = vec![
+            step_idx.to_string(),
+            t_s.to_string(),
+            truth.altitude_m().to_string(),
+            truth.heat_shield_temp_k.to_string(),
+            sample.blackout.to_string(),
+        ];
+        record.extend(force.iter().chain(omega.iter()).map(f64::to_string));
+        for m in &imu {
+            record.extend(
+                m.accel_b_mps2
+                    .iter()
+                    .chain(m.gyro_b_rps.iter())
+                    .map(f64::to_string),
+            );
+        }
+        writer.write_record(&record)?;
+        rows += 1;
+
+        if descent_complete(cfg, &truth, &events) {
+            break;
+        }
+    }
+
+    writer.flush()?;
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn imu_log_matches_the_simulation_it_replays() {
+        let scratch =
+            std::env::temp_dir().join(format!("dsfb-datasets-starship-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&scratch);
+        fs::create_dir_all(&scratch).unwrap();
+
+        // Tight limits so the short run saturates
+        let mut cfg = SimConfig::default();
+        cfg.t_final = 20.0 * cfg.dt;
+        cfg.imu_limits.accel_range_mps2 = 2.0;
+        cfg.imu_limits.gyro_range_rps = 0.005;
+        let log = scratch.join("imu.csv");
+        let rows = write_imu_csv(&log, &cfg).unwrap();
+        let summary = dsfb_starship::run_simulation(&cfg, &scratch.join("sim")).unwrap();
+
+        let mut reader = csv::Reader::from_path(&log).unwrap();
+        let records: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+        assert_eq!(records.len(), rows);
+        let mut telemetry = csv::Reader::from_path(&summary.outputs.csv_path).unwrap();
+        let altitude = telemetry
+            .headers()
+            .unwrap()
+            .iter()
+            .position(|h| h == "altitude_m")
+            .unwrap();
+        let first = telemetry.records().next().unwrap().unwrap();
+        assert_eq!(&records[0][2], &first[altitude]);
+
+        let value = |record: &csv::StringRecord, i: usize| record[i].parse::<f64>().unwrap();
+        let saturated: Vec<usize> = (0..cfg.imu_count)
+            .map(|imu| {
+                let base = 11 + 6 * imu;
+                records
+                    .iter()
+                    .filter(|r| {
+                        (0..3).any(|a| value(r, base + a).abs() == cfg.imu_limits.accel_range_mps2)
+                            || (3..6)
+                                .any(|g| value(r, base + g).abs() == cfg.imu_limits.gyro_range_rps)
+                    })
+                    .count()
+            })
+            .collect();
+        assert!(saturated.iter().any(|&n| n > 0));
+        assert_eq!(saturated, summary.imu_saturated_samples);
+
+        let _ = fs::remove_dir_all(&scratch);
+    }
+}
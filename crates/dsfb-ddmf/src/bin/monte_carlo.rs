@@ -5,9 +5,14 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use csv::Writer;
+use dsfb_ddmf::envelope::WeightMapping;
+use dsfb_ddmf::hret_compare::{default_hret_scenarios, run_hret_comparison, HretComparisonConfig};
 use dsfb_ddmf::monte_carlo::{
     run_monte_carlo, summarize_batch, trajectory_rows, MonteCarloConfig, DEFAULT_MONTE_CARLO_RUNS,
 };
+use dsfb_ddmf::scenarios::{scenario, SCENARIO_NAMES};
+use dsfb_ddmf::sim::{run_simulation, EpsilonModel};
+use dsfb_ddmf::weight_limits::{run_weight_limit_sweep, WeightLimitSweepConfig};
 
 #[derive(Debug, Clone)]
 struct CliConfig {
@@ -17,7 +22,16 @@ struct CliConfig {
     rho: f64,
     beta: f64,
     epsilon_bound: f64,
+    epsilon_shared_weight: Option<f64>,
     recovery_delta: f64,
+    weight_mapping: WeightMapping,
+    w_min: f64,
+    envelope_cap: Option<f64>,
+    threads: usize,
+    /// Run only this preset scenario instead of the Monte Carlo batch
+    scenario: Option<String>,
+    /// Directory the timestamped run directory is created in
+    outdir: Option<PathBuf>,
 }
 
 impl Default for CliConfig {
@@ -30,14 +44,28 @@ impl Default for CliConfig {
             rho: defaults.rho,
             beta: defaults.beta,
             epsilon_bound: defaults.epsilon_bound,
+            epsilon_shared_weight: None,
             recovery_delta: defaults.recovery_delta,
+            weight_mapping: defaults.weight_mapping,
+            w_min: defaults.w_min,
+            envelope_cap: defaults.envelope_cap,
+            threads: defaults.threads,
+            scenario: None,
+            outdir: None,
         }
     }
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
     let cli = parse_args(env::args().skip(1))?;
-    let output_dir = create_output_dir()?;
+    let output_root = cli
+        .outdir
+        .clone()
+        .unwrap_or_else(|| repo_root().join("output-dsfb-ddmf"));
+    let output_dir = create_output_dir(&output_root)?;
+    if let Some(name) = &cli.scenario {
+        return run_scenario(name, &output_dir);
+    }
     let config = MonteCarloConfig {
         n_runs: cli.runs,
         n_steps: cli.steps,
@@ -46,6 +74,10 @@ fn main() -> Result<(), Box<dyn Error>> {
         beta: cli.beta,
         epsilon_bound: cli.epsilon_bound,
         recovery_delta: cli.recovery_delta,
+        weight_mapping: cli.weight_mapping,
+        w_min: cli.w_min,
+        envelope_cap: cli.envelope_cap,
+        threads: cli.threads,
     };
     let batch = run_monte_carlo(&config);
     let summary = summarize_batch(&config, &batch);
@@ -59,6 +91,38 @@ fn main() -> Result<(), Box<dyn Error>> {
         &output_dir.join("single_run_persistent.csv"),
         &batch.example_persistent,
     )?;
+
+    let hret_defaults = HretComparisonConfig::default();
+    let hret_config = HretComparisonConfig {
+        n_steps: cli.steps,
+        rho: cli.rho,
+        beta: cli.beta,
+        epsilon_model: cli.epsilon_shared_weight.map_or(
+            hret_defaults.epsilon_model.clone(),
+            |shared_weight| EpsilonModel::Correlated {
+                shared_weight,
+                seed: cli.seed,
+            },
+        ),
+        recovery_delta: cli.recovery_delta,
+        ..hret_defaults
+    };
+    let hret_rows = run_hret_comparison(&hret_config, &default_hret_scenarios(cli.steps));
+    write_results_csv(output_dir.join("hret_comparison.csv"), &hret_rows)?;
+
+    let weight_limit_config = WeightLimitSweepConfig {
+        n_steps: cli.steps,
+        rho: cli.rho,
+        beta: cli.beta,
+        epsilon_bound: cli.epsilon_bound,
+        weight_mapping: cli.weight_mapping,
+        ..WeightLimitSweepConfig::default()
+    };
+    write_results_csv(
+        output_dir.join("weight_limit_sweep.csv"),
+        &run_weight_limit_sweep(&weight_limit_config),
+    )?;
+
     fs::write(
         output_dir.join("summary.json"),
         serde_json::to_string_pretty(&summary)?,
@@ -68,6 +132,30 @@ fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+/// Writes the trajectory and configuration of one preset scenario.
+fn run_scenario(name: &str, output_dir: &Path) -> Result<(), Box<dyn Error>> {
+    let preset = scenario(name).ok_or_else(|| {
+        format!(
+            "unknown scenario: {name} (expected one of {})",
+            SCENARIO_NAMES.join(", ")
+        )
+    })?;
+    let result = run_simulation(&preset.config);
+
+    write_trajectory_csv(
+        &output_dir.join(format!("scenario_{}.csv", preset.name)),
+        &result,
+    )?;
+    fs::write(
+        output_dir.join("scenario.json"),
+        serde_json::to_string_pretty(&preset)?,
+    )?;
+
+    println!("Scenario {}: {}", preset.name, preset.description);
+    println!("Output directory: {}", output_dir.display());
+    Ok(())
+}
+
 fn parse_args<I>(args: I) -> Result<CliConfig, Box<dyn Error>>
 where
     I: IntoIterator<Item = String>,
@@ -83,9 +171,50 @@ where
             "--rho" => cli.rho = parse_value(args.next(), "--rho")?,
             "--beta" => cli.beta = parse_value(args.next(), "--beta")?,
             "--epsilon-bound" => cli.epsilon_bound = parse_value(args.next(), "--epsilon-bound")?,
+            "--epsilon-shared-weight" => {
+                let weight: f64 = parse_value(args.next(), "--epsilon-shared-weight")?;
+                if !(0.0..=1.0).contains(&weight) {
+                    return Err("--epsilon-shared-weight must be in [0, 1]".into());
+                }
+                cli.epsilon_shared_weight = Some(weight);
+            }
             "--recovery-delta" => {
                 cli.recovery_delta = parse_value(args.next(), "--recovery-delta")?
             }
+            "--weight-mapping" => {
+                let raw = args.next().ok_or("missing value for --weight-mapping")?;
+                cli.weight_mapping = raw.parse::<WeightMapping>()?;
+            }
+            "--w-min" => {
+                let w_min: f64 = parse_value(args.next(), "--w-min")?;
+                if !(0.0..=1.0).contains(&w_min) {
+                    return Err("--w-min must be in [0, 1]".into());
+                }
+                cli.w_min = w_min;
+            }
+            "--envelope-cap" => {
+                let cap: f64 = parse_value(args.next(), "--envelope-cap")?;
+                if !(cap.is_finite() && cap >= 0.0) {
+                    return Err("--envelope-cap must be finite and >= 0".into());
+                }
+                cli.envelope_cap = Some(cap);
+            }
+            "--threads" => cli.threads = parse_value(args.next(), "--threads")?,
+            "--scenario" => {
+                let name = args.next().ok_or("missing value for --scenario")?;
+                if !SCENARIO_NAMES.contains(&name.as_str()) {
+                    return Err(format!(
+                        "unknown scenario: {name} (expected one of {})",
+                        SCENARIO_NAMES.join(", ")
+                    )
+                    .into());
+                }
+                cli.scenario = Some(name);
+            }
+            "--outdir" => {
+                let dir = args.next().ok_or("missing value for --outdir")?;
+                cli.outdir = Some(PathBuf::from(dir));
+            }
             "--help" | "-h" => {
                 print_help();
                 std::process::exit(0);
@@ -116,12 +245,29 @@ fn print_help() {
     println!("  --rho <f64>");
     println!("  --beta <f64>");
     println!("  --epsilon-bound <f64>");
+    println!(
+        "  --epsilon-shared-weight <f64>  correlated epsilon in the HRET comparison, in [0, 1]"
+    );
     println!("  --recovery-delta <f64>");
+    println!(
+        "  --weight-mapping <law>    rational (default), exponential, dead-zone:<s>, threshold:<s>"
+    );
+    println!(
+        "  --w-min <f64>             trust floor for the Monte Carlo runs, in [0, 1] (default 0)"
+    );
+    println!(
+        "  --envelope-cap <f64>      envelope saturation for the Monte Carlo runs (default none)"
+    );
+    println!("  --threads <usize>         worker threads, 0 = all cores (default)");
+    println!(
+        "  --scenario <name>         run one paper preset only: {}",
+        SCENARIO_NAMES.join(", ")
+    );
+    println!("  --outdir <dir>            parent of the timestamped run directory (default: output-dsfb-ddmf/)");
 }
 
-fn create_output_dir() -> Result<PathBuf, Box<dyn Error>> {
-    let output_root = repo_root().join("output-dsfb-ddmf");
-    fs::create_dir_all(&output_root)?;
+fn create_output_dir(output_root: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    fs::create_dir_all(output_root)?;
 
     let timestamp = timestamp_string()?;
     let output_dir = output_root.join(timestamp);
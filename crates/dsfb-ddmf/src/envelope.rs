@@ -1,4 +1,8 @@
+use std::fmt;
+use std::str::FromStr;
+
 use dsfb::TrustStats;
+use serde::{Deserialize, Serialize};
 
 /// Single-channel residual-envelope state.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -23,11 +27,19 @@ impl ResidualEnvelope {
         self.s
     }
 
+    /// Saturates the envelope at `cap`, so it never exceeds it.
+    pub fn saturate(&mut self, cap: f64) -> f64 {
+        assert!(cap.is_finite() && cap >= 0.0, "cap must be finite and >= 0");
+        self.s = self.s.min(cap);
+        self.s
+    }
+
     /// Exposes the final envelope state in the same shape as the core DSFB trust API.
     pub fn as_dsfb_stats(&self, beta: f64) -> TrustStats {
         TrustStats {
             residual_ema: self.s,
             weight: TrustWeight::weight(beta, self.s),
+            ..TrustStats::default()
         }
     }
 }
@@ -38,18 +50,126 @@ pub struct TrustWeight;
 
 impl TrustWeight {
     pub fn weight(beta: f64, s: f64) -> f64 {
-        assert!(
-            beta.is_finite() && beta > 0.0,
-            "beta must be finite and > 0"
-        );
-        assert!(s.is_finite() && s >= 0.0, "s must be finite and >= 0");
+        check_weight_inputs(beta, s);
         1.0 / (1.0 + beta * s)
     }
 }
 
+/// Envelope-to-weight law `w = f(beta, s)`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum WeightMapping {
+    /// `w = 1 / (1 + beta s)`, the `TrustWeight` law.
+    #[default]
+    Rational,
+    /// `w = exp(-beta s)`
+    Exponential,
+    /// Full trust up to the dead zone, then a linear ramp to zero:
+    /// `w = clamp(1 - beta (s - dead_zone), 0, 1)`.
+    DeadZone { dead_zone: f64 },
+    /// `w = 1` for `s <= threshold`, otherwise `0`.
+    Threshold { threshold: f64 },
+}
+
+impl WeightMapping {
+    pub fn weight(&self, beta: f64, s: f64) -> f64 {
+        match *self {
+            Self::Rational => TrustWeight::weight(beta, s),
+            Self::Exponential => {
+                check_weight_inputs(beta, s);
+                (-beta * s).exp()
+            }
+            Self::DeadZone { dead_zone } => {
+                check_weight_inputs(beta, s);
+                (1.0 - beta * (s - dead_zone).max(0.0)).clamp(0.0, 1.0)
+            }
+            Self::Threshold { threshold } => {
+                check_weight_inputs(beta, s);
+                if s <= threshold {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+
+    /// Panics if the mapping's own parameter is invalid.
+    pub fn validate(&self) {
+        match *self {
+            Self::Rational | Self::Exponential => {}
+            Self::DeadZone { dead_zone } => assert!(
+                dead_zone.is_finite() && dead_zone >= 0.0,
+                "dead_zone must be finite and >= 0"
+            ),
+            Self::Threshold { threshold } => assert!(
+                threshold.is_finite() && threshold >= 0.0,
+                "threshold must be finite and >= 0"
+            ),
+        }
+    }
+}
+
+impl fmt::Display for WeightMapping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rational => f.write_str("rational"),
+            Self::Exponential => f.write_str("exponential"),
+            Self::DeadZone { dead_zone } => write!(f, "dead-zone:{dead_zone}"),
+            Self::Threshold { threshold } => write!(f, "threshold:{threshold}"),
+        }
+    }
+}
+
+/// Parses the `Display` form: `rational`, `exponential`, `dead-zone:<s>`, or `threshold:<s>`.
+impl FromStr for WeightMapping {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let (name, value) = match raw.split_once(':') {
+            Some((name, value)) => (name, Some(value)),
+            None => (raw, None),
+        };
+        let parameter = |value: Option<&str>| -> Result<f64, String> {
+            let value = value.ok_or_else(|| format!("weight mapping '{name}' needs ':<value>'"))?;
+            let parsed: f64 = value
+                .parse()
+                .map_err(|_| format!("invalid weight mapping parameter '{value}'"))?;
+            if parsed.is_finite() && parsed >= 0.0 {
+                Ok(parsed)
+            } else {
+                Err(format!(
+                    "weight mapping parameter must be finite and >= 0, got {value}"
+                ))
+            }
+        };
+
+        match name {
+            "rational" if value.is_none() => Ok(Self::Rational),
+            "exponential" if value.is_none() => Ok(Self::Exponential),
+            "dead-zone" => Ok(Self::DeadZone {
+                dead_zone: parameter(value)?,
+            }),
+            "threshold" => Ok(Self::Threshold {
+                threshold: parameter(value)?,
+            }),
+            _ => Err(format!(
+                "unknown weight mapping '{raw}'; expected rational, exponential, dead-zone:<s>, or threshold:<s>"
+            )),
+        }
+    }
+}
+
+fn check_weight_inputs(beta: f64, s: f64) {
+    assert!(
+        beta.is_finite() && beta > 0.0,
+        "beta must be finite and > 0"
+    );
+    assert!(s.is_finite() && s >= 0.0, "s must be finite and >= 0");
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{ResidualEnvelope, TrustWeight};
+    use super::{ResidualEnvelope, TrustWeight, WeightMapping};
 
     #[test]
     fn envelope_update_matches_recursion() {
@@ -58,10 +178,52 @@ mod tests {
         assert!((s - 0.2).abs() < 1e-12);
     }
 
+    #[test]
+    fn saturated_envelope_stays_at_cap() {
+        let mut env = ResidualEnvelope::new(0.5, 0.0);
+        env.update(4.0);
+        assert_eq!(env.saturate(0.5), 0.5);
+        assert!((env.update(0.0) - 0.25).abs() < 1e-12);
+        assert_eq!(env.saturate(0.5), 0.25);
+    }
+
     #[test]
     fn trust_weight_is_monotone() {
         let w_low = TrustWeight::weight(2.0, 0.1);
         let w_high = TrustWeight::weight(2.0, 0.6);
         assert!(w_low > w_high);
     }
+
+    #[test]
+    fn weight_mappings_follow_their_laws() {
+        let beta = 2.0;
+        assert_eq!(
+            WeightMapping::Rational.weight(beta, 0.3),
+            TrustWeight::weight(beta, 0.3)
+        );
+        assert!((WeightMapping::Exponential.weight(beta, 0.3) - (-0.6_f64).exp()).abs() < 1e-12);
+
+        let dead_zone = WeightMapping::DeadZone { dead_zone: 0.1 };
+        assert_eq!(dead_zone.weight(beta, 0.05), 1.0);
+        assert!((dead_zone.weight(beta, 0.3) - 0.6).abs() < 1e-12);
+        assert_eq!(dead_zone.weight(beta, 1.0), 0.0);
+
+        let threshold = WeightMapping::Threshold { threshold: 0.2 };
+        assert_eq!(threshold.weight(beta, 0.2), 1.0);
+        assert_eq!(threshold.weight(beta, 0.21), 0.0);
+    }
+
+    #[test]
+    fn weight_mapping_round_trips_through_display() {
+        for mapping in [
+            WeightMapping::Rational,
+            WeightMapping::Exponential,
+            WeightMapping::DeadZone { dead_zone: 0.15 },
+            WeightMapping::Threshold { threshold: 0.4 },
+        ] {
+            assert_eq!(mapping.to_string().parse::<WeightMapping>(), Ok(mapping));
+        }
+        assert!("threshold".parse::<WeightMapping>().is_err());
+        assert!("dead-zone:-1".parse::<WeightMapping>().is_err());
+    }
 }
@@ -0,0 +1,320 @@
+use dsfb_hret::HretObserver;
+use serde::Serialize;
+
+use crate::disturbances::DisturbanceKind;
+use crate::envelope::{ResidualEnvelope, TrustWeight, WeightMapping};
+use crate::monte_carlo::MonteCarloConfig;
+use crate::sim::{run_multichannel_simulation, EpsilonModel, SimulationConfig};
+
+/// Identical multichannel disturbance scenarios run through independent
+/// per-channel envelopes and through an `HretObserver` with channel groups.
+#[derive(Clone, Debug)]
+pub struct HretComparisonConfig {
+    pub n_steps: usize,
+    pub rho: f64,
+    pub beta: f64,
+    pub epsilon_bound: f64,
+    pub epsilon_model: EpsilonModel,
+    pub recovery_delta: f64,
+    pub group_assignments: Vec<usize>,
+}
+
+impl Default for HretComparisonConfig {
+    fn default() -> Self {
+        let defaults = MonteCarloConfig::default();
+        Self {
+            n_steps: defaults.n_steps,
+            rho: defaults.rho,
+            beta: defaults.beta,
+            epsilon_bound: 0.05,
+            epsilon_model: EpsilonModel::Sinusoidal,
+            recovery_delta: defaults.recovery_delta,
+            group_assignments: vec![0, 0, 1, 1, 2],
+        }
+    }
+}
+
+impl HretComparisonConfig {
+    pub fn n_channels(&self) -> usize {
+        self.group_assignments.len()
+    }
+
+    pub fn n_groups(&self) -> usize {
+        self.group_assignments
+            .iter()
+            .copied()
+            .max()
+            .map_or(0, |g| g + 1)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HretScenario {
+    pub name: String,
+    pub disturbance_kind: DisturbanceKind,
+    pub correlated_groups: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HretComparisonRow {
+    pub scenario: String,
+    pub disturbance_type: String,
+    pub correlated_groups: bool,
+    pub method: String,
+    pub n_channels: usize,
+    pub n_groups: usize,
+    pub min_trust: f64,
+    pub time_to_recover: i64,
+    pub error_proxy: f64,
+}
+
+/// Per-step channel trust and fused residual for one method.
+struct MethodTrace {
+    trust: Vec<Vec<f64>>,
+    fused: Vec<f64>,
+}
+
+pub fn default_hret_scenarios(n_steps: usize) -> Vec<HretScenario> {
+    let start = (n_steps / 6).max(1);
+    let kinds = [
+        (
+            "impulse",
+            DisturbanceKind::Impulsive {
+                amplitude: 1.4,
+                start,
+                len: 7,
+            },
+        ),
+        (
+            "persistent",
+            DisturbanceKind::PersistentElevated {
+                r_nom: 0.05,
+                r_high: 0.65,
+                step_time: start,
+            },
+        ),
+        (
+            "drift",
+            DisturbanceKind::Drift {
+                b: 0.01,
+                s_max: 0.6,
+            },
+        ),
+        ("bounded", DisturbanceKind::PointwiseBounded { d: 0.1 }),
+    ];
+
+    kinds
+        .into_iter()
+        .flat_map(|(name, kind)| {
+            [true, false].into_iter().map(move |correlated_groups| {
+                let suffix = if correlated_groups {
+                    "correlated"
+                } else {
+                    "independent"
+                };
+                HretScenario {
+                    name: format!("{name}_{suffix}"),
+                    disturbance_kind: kind.clone(),
+                    correlated_groups,
+                }
+            })
+        })
+        .collect()
+}
+
+/// Runs every scenario through both methods and returns one row per
+/// (scenario, method) pair.
+pub fn run_hret_comparison(
+    config: &HretComparisonConfig,
+    scenarios: &[HretScenario],
+) -> Vec<HretComparisonRow> {
+    let mut rows = Vec::with_capacity(scenarios.len() * 2);
+
+    for scenario in scenarios {
+        let residuals = scenario_residuals(config, scenario);
+        let traces = [
+            ("independent", independent_trace(config, &residuals)),
+            ("hret", hret_trace(config, &residuals)),
+        ];
+
+        for (method, trace) in traces {
+            rows.push(HretComparisonRow {
+                scenario: scenario.name.clone(),
+                disturbance_type: scenario.disturbance_kind.disturbance_type().to_string(),
+                correlated_groups: scenario.correlated_groups,
+                method: method.to_string(),
+                n_channels: config.n_channels(),
+                n_groups: config.n_groups(),
+                min_trust: trace.trust.iter().flatten().copied().fold(1.0, f64::min),
+                time_to_recover: trust_recovery_time(
+                    &scenario.disturbance_kind,
+                    &trace.trust,
+                    config.recovery_delta,
+                ),
+                error_proxy: rms(&trace.fused),
+            });
+        }
+    }
+
+    rows
+}
+
+/// Residual traces indexed as `[step][channel]`.
+fn scenario_residuals(config: &HretComparisonConfig, scenario: &HretScenario) -> Vec<Vec<f64>> {
+    let sim_config = SimulationConfig {
+        n_steps: config.n_steps,
+        rho: config.rho,
+        beta: config.beta,
+        disturbance_kind: scenario.disturbance_kind.clone(),
+        epsilon_bound: config.epsilon_bound,
+        epsilon_model: config.epsilon_model.clone(),
+        weight_mapping: WeightMapping::Rational,
+        w_min: 0.0,
+        envelope_cap: None,
+    };
+    let channels = run_multichannel_simulation(
+        &sim_config,
+        config.n_channels(),
+        Some(&config.group_assignments),
+        scenario.correlated_groups,
+    );
+
+    (0..config.n_steps)
+        .map(|n| channels.iter().map(|channel| channel.r[n]).collect())
+        .collect()
+}
+
+fn independent_trace(config: &HretComparisonConfig, residuals: &[Vec<f64>]) -> MethodTrace {
+    let mut envelopes = vec![ResidualEnvelope::new(config.rho, 0.0); config.n_channels()];
+    let mut trust = Vec::with_capacity(residuals.len());
+    let mut fused = Vec::with_capacity(residuals.len());
+
+    for r in residuals {
+        let w: Vec<f64> = envelopes
+            .iter_mut()
+            .zip(r)
+            .map(|(envelope, &r_k)| TrustWeight::weight(config.beta, envelope.update(r_k)))
+            .collect();
+        fused.push(convex_fuse(&w, r));
+        trust.push(w);
+    }
+
+    MethodTrace { trust, fused }
+}
+
+fn hret_trace(config: &HretComparisonConfig, residuals: &[Vec<f64>]) -> MethodTrace {
+    let m = config.n_channels();
+    let g = config.n_groups();
+    let mut observer = HretObserver::new(
+        m,
+        g,
+        config.group_assignments.clone(),
+        config.rho,
+        vec![config.rho; g],
+        vec![config.beta; m],
+        vec![config.beta; g],
+        vec![vec![1.0; m]],
+        None,
+    )
+    .expect("HRET comparison configuration must be valid");
+
+    let mut trust = Vec::with_capacity(residuals.len());
+    let mut fused = Vec::with_capacity(residuals.len());
+
+    for r in residuals {
+        let (delta_x, _weights, s_k, s_g) = observer
+            .update(r.clone())
+            .expect("simulated residuals must be finite");
+        // Effective (unnormalized) hierarchical trust: w_k * w_g(group(k)).
+        let w = s_k
+            .iter()
+            .zip(&config.group_assignments)
+            .map(|(&s, &group)| {
+                TrustWeight::weight(config.beta, s) * TrustWeight::weight(config.beta, s_g[group])
+            })
+            .collect();
+        fused.push(delta_x[0]);
+        trust.push(w);
+    }
+
+    MethodTrace { trust, fused }
+}
+
+fn convex_fuse(weights: &[f64], residuals: &[f64]) -> f64 {
+    let sum: f64 = weights.iter().sum();
+    if sum <= 0.0 {
+        return residuals.iter().sum::<f64>() / residuals.len() as f64;
+    }
+    weights
+        .iter()
+        .zip(residuals)
+        .map(|(w, r)| w * r)
+        .sum::<f64>()
+        / sum
+}
+
+fn rms(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    (values.iter().map(|v| v * v).sum::<f64>() / values.len() as f64).sqrt()
+}
+
+/// Steps after the disturbance window until the minimum channel trust returns
+/// to within `delta` (relative) of its pre-disturbance level, or `-1` for
+/// non-recoverable regimes or no recovery within the run.
+fn trust_recovery_time(kind: &DisturbanceKind, trust: &[Vec<f64>], delta: f64) -> i64 {
+    let (DisturbanceKind::Impulsive { start, .. }, Some(end)) =
+        (kind, kind.recovery_search_start())
+    else {
+        return -1;
+    };
+    let min_at = |n: usize| trust[n].iter().copied().fold(1.0, f64::min);
+    let baseline = if *start == 0 || *start > trust.len() {
+        1.0
+    } else {
+        min_at(start - 1)
+    };
+
+    (end..trust.len())
+        .find(|&n| min_at(n) >= (1.0 - delta) * baseline)
+        .map(|n| (n - end) as i64)
+        .unwrap_or(-1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{default_hret_scenarios, run_hret_comparison, HretComparisonConfig};
+
+    #[test]
+    fn comparison_emits_one_row_per_scenario_and_method() {
+        let config = HretComparisonConfig {
+            n_steps: 64,
+            ..HretComparisonConfig::default()
+        };
+        let scenarios = default_hret_scenarios(config.n_steps);
+        let rows = run_hret_comparison(&config, &scenarios);
+
+        assert_eq!(rows.len(), scenarios.len() * 2);
+        assert!(rows
+            .iter()
+            .all(|row| row.min_trust > 0.0 && row.min_trust <= 1.0));
+        assert!(rows.iter().all(|row| row.error_proxy.is_finite()));
+    }
+
+    #[test]
+    fn hierarchy_lowers_trust_under_correlated_group_fault() {
+        let config = HretComparisonConfig {
+            n_steps: 96,
+            ..HretComparisonConfig::default()
+        };
+        let scenarios = default_hret_scenarios(config.n_steps);
+        let rows = run_hret_comparison(&config, &scenarios[..1]);
+
+        let independent = &rows[0];
+        let hret = &rows[1];
+        assert_eq!(independent.method, "independent");
+        assert_eq!(hret.method, "hret");
+        assert!(hret.min_trust < independent.min_trust);
+    }
+}
@@ -5,16 +5,27 @@
 
 pub mod disturbances;
 pub mod envelope;
+pub mod hret_compare;
 pub mod monte_carlo;
+pub mod scenarios;
 pub mod sim;
+pub mod weight_limits;
 
 pub use disturbances::{build_disturbance, Disturbance, DisturbanceKind};
-pub use envelope::{ResidualEnvelope, TrustWeight};
+pub use envelope::{ResidualEnvelope, TrustWeight, WeightMapping};
+pub use hret_compare::{
+    default_hret_scenarios, run_hret_comparison, HretComparisonConfig, HretComparisonRow,
+    HretScenario,
+};
 pub use monte_carlo::{
     example_impulse_result, example_persistent_result, run_monte_carlo, MonteCarloBatch,
     MonteCarloConfig, MonteCarloRunRecord, MonteCarloSummary, TrajectoryRow,
 };
+pub use scenarios::{scenario, scenarios, Scenario, SCENARIO_NAMES};
 pub use sim::{
-    run_multichannel_simulation, run_simulation, run_simulation_with_s0, SimulationConfig,
-    SimulationResult,
+    run_multichannel_simulation, run_simulation, run_simulation_with_s0, EpsilonModel,
+    SimulationConfig, SimulationResult,
+};
+pub use weight_limits::{
+    error_contribution, run_weight_limit_sweep, WeightLimitRow, WeightLimitSweepConfig,
 };
@@ -2,10 +2,12 @@ use std::collections::BTreeMap;
 
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
 use serde::Serialize;
 
 use crate::disturbances::DisturbanceKind;
-use crate::sim::{run_simulation_with_s0, SimulationConfig, SimulationResult};
+use crate::envelope::WeightMapping;
+use crate::sim::{run_simulation_with_s0, EpsilonModel, SimulationConfig, SimulationResult};
 
 pub const DEFAULT_MONTE_CARLO_RUNS: usize = 360;
 
@@ -18,6 +20,14 @@ pub struct MonteCarloConfig {
     pub beta: f64,
     pub epsilon_bound: f64,
     pub recovery_delta: f64,
+    pub weight_mapping: WeightMapping,
+    /// Trust-weight floor applied at every step; 0 disables it
+    pub w_min: f64,
+    /// Envelope saturation level; `None` leaves the envelope uncapped
+    pub envelope_cap: Option<f64>,
+    /// Worker threads for the runs; 0 uses every available core and 1 runs
+    /// serially. Results do not depend on it.
+    pub threads: usize,
 }
 
 impl Default for MonteCarloConfig {
@@ -30,6 +40,27 @@ impl Default for MonteCarloConfig {
             beta: 3.0,
             epsilon_bound: 0.0,
             recovery_delta: 0.03,
+            weight_mapping: WeightMapping::Rational,
+            w_min: 0.0,
+            envelope_cap: None,
+            threads: 0,
+        }
+    }
+}
+
+impl MonteCarloConfig {
+    /// Single-channel simulation of `disturbance_kind` with this batch's parameters.
+    pub(crate) fn simulation_config(&self, disturbance_kind: DisturbanceKind) -> SimulationConfig {
+        SimulationConfig {
+            n_steps: self.n_steps,
+            rho: self.rho,
+            beta: self.beta,
+            disturbance_kind,
+            epsilon_bound: self.epsilon_bound,
+            epsilon_model: EpsilonModel::Sinusoidal,
+            weight_mapping: self.weight_mapping,
+            w_min: self.w_min,
+            envelope_cap: self.envelope_cap,
         }
     }
 }
@@ -52,6 +83,7 @@ pub struct MonteCarloRunRecord {
     pub max_envelope: f64,
     pub min_trust: f64,
     pub time_to_recover: i64,
+    pub weight_mapping: String,
 }
 
 #[derive(Clone, Debug)]
@@ -70,6 +102,9 @@ pub struct MonteCarloSummary {
     pub beta: f64,
     pub epsilon_bound: f64,
     pub recovery_delta: f64,
+    pub weight_mapping: String,
+    pub w_min: f64,
+    pub envelope_cap: Option<f64>,
     pub mean_max_envelope: f64,
     pub min_observed_trust: f64,
     pub regime_counts: BTreeMap<String, usize>,
@@ -85,48 +120,70 @@ pub struct TrajectoryRow {
 }
 
 pub fn run_monte_carlo(config: &MonteCarloConfig) -> MonteCarloBatch {
+    // Every run's disturbance and initial envelope are drawn up front from the
+    // master stream, in run order, so the runs themselves can execute in any
+    // order without changing their results.
     let mut rng = StdRng::seed_from_u64(config.seed);
-    let mut records = Vec::with_capacity(config.n_runs);
-
-    for run_id in 0..config.n_runs {
-        let disturbance_kind = sample_disturbance(&mut rng, config.n_steps);
-        let s0 = rng.gen_range(0.0..0.25);
-        let sim_config = SimulationConfig {
-            n_steps: config.n_steps,
-            rho: config.rho,
-            beta: config.beta,
-            disturbance_kind: disturbance_kind.clone(),
-            epsilon_bound: config.epsilon_bound,
-        };
-        let result = run_simulation_with_s0(&sim_config, s0);
-        let (d, b, s, impulse_start, impulse_len) = disturbance_kind.monte_carlo_columns();
-
-        records.push(MonteCarloRunRecord {
-            run_id,
-            regime_label: disturbance_kind.regime_label().to_string(),
-            disturbance_type: disturbance_kind.disturbance_type().to_string(),
-            admissible: disturbance_kind.is_admissible(),
-            d,
-            b,
-            s,
-            impulse_start,
-            impulse_len,
-            s0,
-            max_envelope: result.s.iter().copied().fold(0.0, f64::max),
-            min_trust: result.w.iter().copied().fold(1.0, f64::min),
-            time_to_recover: time_to_recover(
-                &disturbance_kind,
-                &result.s,
-                config.epsilon_bound,
-                config.recovery_delta,
-            ),
-        });
-    }
+    let plans: Vec<(DisturbanceKind, f64)> = (0..config.n_runs)
+        .map(|_| {
+            let disturbance_kind = sample_disturbance(&mut rng, config.n_steps);
+            let s0 = rng.gen_range(0.0..0.25);
+            (disturbance_kind, s0)
+        })
+        .collect();
+
+    let run = |(run_id, (disturbance_kind, s0)): (usize, &(DisturbanceKind, f64))| {
+        run_record(config, run_id, disturbance_kind, *s0)
+    };
+    let records = if config.threads == 1 {
+        plans.iter().enumerate().map(run).collect()
+    } else {
+        match rayon::ThreadPoolBuilder::new()
+            .num_threads(config.threads)
+            .build()
+        {
+            Ok(pool) => pool.install(|| plans.par_iter().enumerate().map(run).collect()),
+            Err(_) => plans.iter().enumerate().map(run).collect(),
+        }
+    };
 
     MonteCarloBatch {
         records,
-        example_impulse: example_impulse_result(config.n_steps, config.rho, config.beta),
-        example_persistent: example_persistent_result(config.n_steps, config.rho, config.beta),
+        example_impulse: example_result(config, example_impulse_kind()),
+        example_persistent: example_result(config, example_persistent_kind()),
+    }
+}
+
+fn run_record(
+    config: &MonteCarloConfig,
+    run_id: usize,
+    disturbance_kind: &DisturbanceKind,
+    s0: f64,
+) -> MonteCarloRunRecord {
+    let sim_config = config.simulation_config(disturbance_kind.clone());
+    let result = run_simulation_with_s0(&sim_config, s0);
+    let (d, b, s, impulse_start, impulse_len) = disturbance_kind.monte_carlo_columns();
+
+    MonteCarloRunRecord {
+        run_id,
+        regime_label: disturbance_kind.regime_label().to_string(),
+        disturbance_type: disturbance_kind.disturbance_type().to_string(),
+        admissible: disturbance_kind.is_admissible(),
+        d,
+        b,
+        s,
+        impulse_start,
+        impulse_len,
+        s0,
+        max_envelope: result.s.iter().copied().fold(0.0, f64::max),
+        min_trust: result.w.iter().copied().fold(1.0, f64::min),
+        time_to_recover: time_to_recover(
+            disturbance_kind,
+            &result.s,
+            config.epsilon_bound,
+            config.recovery_delta,
+        ),
+        weight_mapping: config.weight_mapping.to_string(),
     }
 }
 
@@ -157,6 +214,9 @@ pub fn summarize_batch(config: &MonteCarloConfig, batch: &MonteCarloBatch) -> Mo
         beta: config.beta,
         epsilon_bound: config.epsilon_bound,
         recovery_delta: config.recovery_delta,
+        weight_mapping: config.weight_mapping.to_string(),
+        w_min: config.w_min,
+        envelope_cap: config.envelope_cap,
         mean_max_envelope,
         min_observed_trust,
         regime_counts,
@@ -164,33 +224,55 @@ pub fn summarize_batch(config: &MonteCarloConfig, batch: &MonteCarloBatch) -> Mo
 }
 
 pub fn example_impulse_result(n_steps: usize, rho: f64, beta: f64) -> SimulationResult {
-    let config = SimulationConfig {
-        n_steps,
-        rho,
-        beta,
-        disturbance_kind: DisturbanceKind::Impulsive {
-            amplitude: 1.4,
-            start: 24,
-            len: 7,
+    example_result(
+        &MonteCarloConfig {
+            n_steps,
+            rho,
+            beta,
+            ..MonteCarloConfig::default()
         },
-        epsilon_bound: 0.0,
-    };
-    run_simulation_with_s0(&config, 0.0)
+        example_impulse_kind(),
+    )
 }
 
 pub fn example_persistent_result(n_steps: usize, rho: f64, beta: f64) -> SimulationResult {
-    let config = SimulationConfig {
-        n_steps,
-        rho,
-        beta,
-        disturbance_kind: DisturbanceKind::PersistentElevated {
-            r_nom: 0.05,
-            r_high: 0.65,
-            step_time: 24,
+    example_result(
+        &MonteCarloConfig {
+            n_steps,
+            rho,
+            beta,
+            ..MonteCarloConfig::default()
         },
+        example_persistent_kind(),
+    )
+}
+
+pub(crate) fn example_impulse_kind() -> DisturbanceKind {
+    DisturbanceKind::Impulsive {
+        amplitude: 1.4,
+        start: 24,
+        len: 7,
+    }
+}
+
+pub(crate) fn example_persistent_kind() -> DisturbanceKind {
+    DisturbanceKind::PersistentElevated {
+        r_nom: 0.05,
+        r_high: 0.65,
+        step_time: 24,
+    }
+}
+
+/// The example trajectories are noise-free (`epsilon_bound = 0`).
+fn example_result(
+    config: &MonteCarloConfig,
+    disturbance_kind: DisturbanceKind,
+) -> SimulationResult {
+    let sim_config = SimulationConfig {
         epsilon_bound: 0.0,
+        ..config.simulation_config(disturbance_kind)
     };
-    run_simulation_with_s0(&config, 0.0)
+    run_simulation_with_s0(&sim_config, 0.0)
 }
 
 pub fn trajectory_rows(result: &SimulationResult) -> Vec<TrajectoryRow> {
@@ -272,6 +354,7 @@ mod tests {
         DEFAULT_MONTE_CARLO_RUNS,
     };
     use crate::disturbances::DisturbanceKind;
+    use crate::envelope::WeightMapping;
 
     #[test]
     fn monte_carlo_is_reproducible() {
@@ -285,6 +368,31 @@ mod tests {
         assert_eq!(a.records[0].regime_label, b.records[0].regime_label);
     }
 
+    #[test]
+    fn parallel_runs_match_serial_runs() {
+        let serial = MonteCarloConfig {
+            n_runs: 24,
+            threads: 1,
+            ..MonteCarloConfig::default()
+        };
+        let parallel = MonteCarloConfig {
+            threads: 4,
+            ..serial.clone()
+        };
+        let a = run_monte_carlo(&serial);
+        let b = run_monte_carlo(&parallel);
+
+        assert_eq!(a.records.len(), b.records.len());
+        for (ra, rb) in a.records.iter().zip(&b.records) {
+            assert_eq!(ra.run_id, rb.run_id);
+            assert_eq!(ra.disturbance_type, rb.disturbance_type);
+            assert_eq!(ra.s0, rb.s0);
+            assert_eq!(ra.max_envelope, rb.max_envelope);
+            assert_eq!(ra.min_trust, rb.min_trust);
+            assert_eq!(ra.time_to_recover, rb.time_to_recover);
+        }
+    }
+
     #[test]
     fn summary_counts_all_runs() {
         let config = MonteCarloConfig {
@@ -339,4 +447,25 @@ mod tests {
         assert!(batch.records.iter().any(|record| record.admissible));
         assert!(batch.records.iter().any(|record| !record.admissible));
     }
+
+    #[test]
+    fn weight_mapping_keeps_disturbances_and_is_recorded() {
+        let rational = MonteCarloConfig {
+            n_runs: 16,
+            ..MonteCarloConfig::default()
+        };
+        let threshold = MonteCarloConfig {
+            weight_mapping: WeightMapping::Threshold { threshold: 0.1 },
+            ..rational.clone()
+        };
+        let a = run_monte_carlo(&rational);
+        let b = run_monte_carlo(&threshold);
+
+        for (ra, rb) in a.records.iter().zip(&b.records) {
+            assert_eq!(ra.disturbance_type, rb.disturbance_type);
+            assert_eq!(ra.max_envelope, rb.max_envelope);
+            assert_eq!(rb.weight_mapping, "threshold:0.1");
+        }
+        assert!(b.records.iter().any(|record| record.min_trust == 0.0));
+    }
 }
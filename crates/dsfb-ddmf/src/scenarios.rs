@@ -0,0 +1,96 @@
+//! Named preset scenarios from the paper.
+//!
+//! Each preset is a complete single-channel [`SimulationConfig`], so the
+//! paper's figures can be reproduced by name instead of copying parameter
+//! values from the text. All presets use the default Monte Carlo parameters
+//! (`rho = 0.96`, `beta = 3`, 180 steps, rational weight law) and are
+//! noise-free (`epsilon_bound = 0`), like the example trajectories.
+
+use serde::Serialize;
+
+use crate::disturbances::DisturbanceKind;
+use crate::monte_carlo::{example_impulse_kind, example_persistent_kind, MonteCarloConfig};
+use crate::sim::SimulationConfig;
+
+/// Names accepted by [`scenario`], in presentation order.
+pub const SCENARIO_NAMES: [&str; 4] = [
+    "nominal",
+    "impulse-recovery",
+    "persistent-step",
+    "worst-case-drift",
+];
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Scenario {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub config: SimulationConfig,
+}
+
+/// Preset scenario by name, or `None` for an unknown name.
+pub fn scenario(name: &str) -> Option<Scenario> {
+    let (description, disturbance_kind) = match name {
+        "nominal" => (
+            "bounded residual well inside the nominal regime",
+            DisturbanceKind::PointwiseBounded { d: 0.1 },
+        ),
+        "impulse-recovery" => (
+            "7-step impulse of 1.4 at step 24, then recovery of trust",
+            example_impulse_kind(),
+        ),
+        "persistent-step" => (
+            "residual steps from 0.05 to 0.65 at step 24 and stays elevated",
+            example_persistent_kind(),
+        ),
+        "worst-case-drift" => (
+            "steepest drift of the Monte Carlo draw, 0.03 per step, saturating at 0.85",
+            DisturbanceKind::Drift {
+                b: 0.03,
+                s_max: 0.85,
+            },
+        ),
+        _ => return None,
+    };
+
+    let name = SCENARIO_NAMES.into_iter().find(|&known| known == name)?;
+    Some(Scenario {
+        name,
+        description,
+        config: SimulationConfig {
+            epsilon_bound: 0.0,
+            ..MonteCarloConfig::default().simulation_config(disturbance_kind)
+        },
+    })
+}
+
+/// Every preset scenario, in [`SCENARIO_NAMES`] order.
+pub fn scenarios() -> Vec<Scenario> {
+    SCENARIO_NAMES.into_iter().filter_map(scenario).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{scenario, scenarios, SCENARIO_NAMES};
+    use crate::monte_carlo::example_impulse_result;
+    use crate::sim::run_simulation;
+
+    #[test]
+    fn every_name_resolves_and_unknown_names_do_not() {
+        let all = scenarios();
+        assert_eq!(all.len(), SCENARIO_NAMES.len());
+        assert!(all
+            .iter()
+            .zip(SCENARIO_NAMES)
+            .all(|(s, name)| s.name == name));
+        assert!(scenario("impulse").is_none());
+    }
+
+    #[test]
+    fn impulse_recovery_matches_the_example_trajectory() {
+        let preset = scenario("impulse-recovery").expect("preset should exist");
+        let result = run_simulation(&preset.config);
+        let example = example_impulse_result(180, 0.96, 3.0);
+        assert_eq!(result.s, example.s);
+        assert_eq!(result.w, example.w);
+    }
+}
@@ -1,8 +1,10 @@
 use dsfb::TrustStats;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 
 use crate::disturbances::{build_disturbance, DisturbanceKind};
-use crate::envelope::{ResidualEnvelope, TrustWeight};
+use crate::envelope::{ResidualEnvelope, WeightMapping};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SimulationConfig {
@@ -11,6 +13,43 @@ pub struct SimulationConfig {
     pub beta: f64,
     pub disturbance_kind: DisturbanceKind,
     pub epsilon_bound: f64,
+    #[serde(default)]
+    pub epsilon_model: EpsilonModel,
+    /// Envelope-to-weight law; `Rational` (`1 / (1 + beta s)`) by default.
+    #[serde(default)]
+    pub weight_mapping: WeightMapping,
+    /// Per-step floor on the trust weight, in `[0, 1]`; 0 disables it.
+    #[serde(default)]
+    pub w_min: f64,
+    /// Saturation level of the envelope state; `None` leaves it uncapped.
+    #[serde(default)]
+    pub envelope_cap: Option<f64>,
+}
+
+/// How the bounded nuisance term `epsilon_k[n]` is generated.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum EpsilonModel {
+    /// Deterministic per-channel sinusoids with channel-dependent phase.
+    #[default]
+    Sinusoidal,
+    /// Seeded stochastic epsilon sharing a latent factor across channels:
+    /// `epsilon_k[n] = bound * (w * z[n] + (1 - w) * u_k[n])` with `z` and
+    /// `u_k` uniform on `[-1, 1]`, so `|epsilon_k[n]| <= bound` still holds.
+    Correlated { shared_weight: f64, seed: u64 },
+}
+
+impl EpsilonModel {
+    /// Cross-channel correlation of epsilon implied by the model.
+    pub fn cross_channel_correlation(&self) -> f64 {
+        match *self {
+            Self::Sinusoidal => f64::NAN,
+            Self::Correlated { shared_weight, .. } => {
+                let w = shared_weight;
+                let idio = 1.0 - w;
+                w * w / (w * w + idio * idio)
+            }
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -34,6 +73,7 @@ impl SimulationResult {
         TrustStats {
             residual_ema: *self.s.last().unwrap_or(&0.0),
             weight: *self.w.last().unwrap_or(&1.0),
+            ..TrustStats::default()
         }
     }
 }
@@ -95,6 +135,24 @@ fn simulate_channel(
         config.epsilon_bound.is_finite() && config.epsilon_bound >= 0.0,
         "epsilon_bound must be finite and >= 0",
     );
+    if let EpsilonModel::Correlated { shared_weight, .. } = config.epsilon_model {
+        assert!(
+            (0.0..=1.0).contains(&shared_weight),
+            "shared_weight must be in [0, 1]",
+        );
+    }
+
+    config.weight_mapping.validate();
+    assert!(
+        (0.0..=1.0).contains(&config.w_min),
+        "w_min must be in [0, 1]"
+    );
+    if let Some(cap) = config.envelope_cap {
+        assert!(
+            cap.is_finite() && cap >= 0.0,
+            "envelope_cap must be finite and >= 0"
+        );
+    }
 
     let mut envelope = ResidualEnvelope::new(config.rho, s0);
     let mut disturbance = build_disturbance(disturbance_kind);
@@ -107,12 +165,19 @@ fn simulate_channel(
         d: Vec::with_capacity(config.n_steps),
     };
 
-    for n in 0..config.n_steps {
+    let epsilon = epsilon_sequence(config, channel_key);
+
+    for (n, &epsilon) in epsilon.iter().enumerate() {
         let d = disturbance.next(n);
-        let epsilon = epsilon_at(n, config.epsilon_bound, channel_key);
         let r = epsilon + d;
-        let s = envelope.update(r);
-        let w = TrustWeight::weight(config.beta, s);
+        let mut s = envelope.update(r);
+        if let Some(cap) = config.envelope_cap {
+            s = envelope.saturate(cap);
+        }
+        let w = config
+            .weight_mapping
+            .weight(config.beta, s)
+            .max(config.w_min);
 
         result.d.push(d);
         result.r.push(r);
@@ -123,6 +188,32 @@ fn simulate_channel(
     result
 }
 
+fn epsilon_sequence(config: &SimulationConfig, channel_key: usize) -> Vec<f64> {
+    match config.epsilon_model {
+        EpsilonModel::Sinusoidal => (0..config.n_steps)
+            .map(|n| epsilon_at(n, config.epsilon_bound, channel_key))
+            .collect(),
+        EpsilonModel::Correlated {
+            shared_weight,
+            seed,
+        } => {
+            // Every channel replays the same shared stream; the idiosyncratic
+            // stream is keyed by channel so results do not depend on call order.
+            let mut shared = StdRng::seed_from_u64(seed);
+            let mut own = StdRng::seed_from_u64(
+                seed ^ 0x9e37_79b9_7f4a_7c15_u64.wrapping_mul(channel_key as u64 + 1),
+            );
+            (0..config.n_steps)
+                .map(|_| {
+                    let z: f64 = shared.gen_range(-1.0..=1.0);
+                    let u: f64 = own.gen_range(-1.0..=1.0);
+                    config.epsilon_bound * (shared_weight * z + (1.0 - shared_weight) * u)
+                })
+                .collect()
+        }
+    }
+}
+
 fn epsilon_at(n: usize, epsilon_bound: f64, channel_key: usize) -> f64 {
     if epsilon_bound == 0.0 {
         return 0.0;
@@ -136,8 +227,9 @@ fn epsilon_at(n: usize, epsilon_bound: f64, channel_key: usize) -> f64 {
 
 #[cfg(test)]
 mod tests {
-    use super::{run_multichannel_simulation, run_simulation, SimulationConfig};
+    use super::{run_multichannel_simulation, run_simulation, EpsilonModel, SimulationConfig};
     use crate::disturbances::DisturbanceKind;
+    use crate::envelope::WeightMapping;
 
     #[test]
     fn pointwise_simulation_reaches_plateau() {
@@ -147,6 +239,10 @@ mod tests {
             beta: 2.0,
             disturbance_kind: DisturbanceKind::PointwiseBounded { d: 0.4 },
             epsilon_bound: 0.0,
+            epsilon_model: EpsilonModel::Sinusoidal,
+            weight_mapping: WeightMapping::Rational,
+            w_min: 0.0,
+            envelope_cap: None,
         };
 
         let result = run_simulation(&config);
@@ -154,6 +250,37 @@ mod tests {
         assert!(final_s > 0.35 && final_s < 0.41);
     }
 
+    #[test]
+    fn weight_floor_and_envelope_cap_bound_trust_loss() {
+        let base = SimulationConfig {
+            n_steps: 120,
+            rho: 0.9,
+            beta: 3.0,
+            disturbance_kind: DisturbanceKind::PointwiseBounded { d: 2.0 },
+            epsilon_bound: 0.0,
+            epsilon_model: EpsilonModel::Sinusoidal,
+            weight_mapping: WeightMapping::Rational,
+            w_min: 0.0,
+            envelope_cap: None,
+        };
+        let floored = run_simulation(&SimulationConfig {
+            w_min: 0.3,
+            ..base.clone()
+        });
+        assert!(floored.w.iter().all(|&w| w >= 0.3));
+        assert!(floored.w.contains(&0.3));
+
+        let capped = run_simulation(&SimulationConfig {
+            envelope_cap: Some(0.5),
+            ..base.clone()
+        });
+        let uncapped = run_simulation(&base);
+        assert!(capped.s.iter().all(|&s| s <= 0.5));
+        assert_eq!(capped.r, uncapped.r);
+        assert!((capped.w.last().unwrap() - 1.0 / 2.5).abs() < 1e-12);
+        assert!(uncapped.w.last().unwrap() < capped.w.last().unwrap());
+    }
+
     #[test]
     fn multichannel_group_correlation_reuses_disturbance() {
         let config = SimulationConfig {
@@ -166,10 +293,51 @@ mod tests {
                 step_time: 4,
             },
             epsilon_bound: 0.0,
+            epsilon_model: EpsilonModel::Sinusoidal,
+            weight_mapping: WeightMapping::Rational,
+            w_min: 0.0,
+            envelope_cap: None,
         };
 
         let results = run_multichannel_simulation(&config, 3, Some(&[0, 0, 1]), true);
         assert_eq!(results[0].d, results[1].d);
         assert_ne!(results[0].d, results[2].d);
     }
+
+    #[test]
+    fn correlated_epsilon_is_bounded_deterministic_and_shared() {
+        let model = EpsilonModel::Correlated {
+            shared_weight: 0.7,
+            seed: 11,
+        };
+        let config = SimulationConfig {
+            n_steps: 4000,
+            rho: 0.9,
+            beta: 3.0,
+            disturbance_kind: DisturbanceKind::PointwiseBounded { d: 0.0 },
+            epsilon_bound: 0.05,
+            epsilon_model: model.clone(),
+            weight_mapping: WeightMapping::Rational,
+            w_min: 0.0,
+            envelope_cap: None,
+        };
+
+        let first = run_multichannel_simulation(&config, 2, None, false);
+        let second = run_multichannel_simulation(&config, 2, None, false);
+        assert_eq!(first[0].r, second[0].r);
+        assert_ne!(first[0].r, first[1].r);
+        assert!(first
+            .iter()
+            .flat_map(|channel| channel.r.iter())
+            .all(|r| r.abs() <= 0.05));
+
+        let mean = |x: &[f64]| x.iter().sum::<f64>() / x.len() as f64;
+        let (a, b) = (&first[0].r, &first[1].r);
+        let (ma, mb) = (mean(a), mean(b));
+        let cov: f64 = a.iter().zip(b).map(|(x, y)| (x - ma) * (y - mb)).sum();
+        let va: f64 = a.iter().map(|x| (x - ma).powi(2)).sum();
+        let vb: f64 = b.iter().map(|y| (y - mb).powi(2)).sum();
+        let corr = cov / (va * vb).sqrt();
+        assert!((corr - model.cross_channel_correlation()).abs() < 0.05);
+    }
 }
@@ -0,0 +1,193 @@
+//! Weight-floor and envelope-cap experiments.
+//!
+//! A trust floor `w_min` or an envelope cap keeps a channel from ever being
+//! fully discarded: its weight can never drop below
+//! `weight_floor = max(w_min, w(beta, cap))`. The price is that a faulted
+//! channel keeps leaking its residual into the fused estimate. This sweep
+//! measures that leak for growing disturbance amplitudes.
+//!
+//! The error contribution of a channel with weight `w` and residual `r` is
+//! taken against one healthy, fully trusted reference channel with zero
+//! residual: the convex fusion of the two is off by `w |r| / (1 + w)`.
+
+use serde::Serialize;
+
+use crate::disturbances::DisturbanceKind;
+use crate::envelope::WeightMapping;
+use crate::sim::{run_simulation, EpsilonModel, SimulationConfig};
+
+#[derive(Clone, Debug)]
+pub struct WeightLimitSweepConfig {
+    pub n_steps: usize,
+    pub rho: f64,
+    pub beta: f64,
+    pub epsilon_bound: f64,
+    pub weight_mapping: WeightMapping,
+    pub w_min_values: Vec<f64>,
+    /// Envelope caps to sweep; `None` is the uncapped reference
+    pub envelope_cap_values: Vec<Option<f64>>,
+    /// Impulse and persistent-fault amplitudes
+    pub amplitudes: Vec<f64>,
+}
+
+impl Default for WeightLimitSweepConfig {
+    fn default() -> Self {
+        Self {
+            n_steps: 180,
+            rho: 0.96,
+            beta: 3.0,
+            epsilon_bound: 0.0,
+            weight_mapping: WeightMapping::Rational,
+            w_min_values: vec![0.0, 0.05, 0.1, 0.2],
+            envelope_cap_values: vec![None, Some(0.25), Some(0.5), Some(1.0)],
+            amplitudes: vec![0.5, 1.0, 2.0, 4.0, 8.0],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct WeightLimitRow {
+    pub w_min: f64,
+    /// Empty when the envelope is uncapped
+    pub envelope_cap: Option<f64>,
+    pub disturbance_type: String,
+    pub amplitude: f64,
+    /// Lowest weight the floor and cap allow
+    pub weight_floor: f64,
+    pub max_envelope: f64,
+    pub min_trust: f64,
+    /// Largest per-step error contribution over the run
+    pub max_error_contribution: f64,
+    /// Error contribution at the last step
+    pub final_error_contribution: f64,
+    /// Contribution of a residual of size `amplitude` at `weight_floor`: what a
+    /// persistent fault settles to once the floor or cap binds
+    pub floor_leak: f64,
+}
+
+/// Error a channel of weight `w` and residual `r` adds to a fusion with one
+/// fully trusted, zero-residual reference channel.
+pub fn error_contribution(w: f64, r: f64) -> f64 {
+    w * r.abs() / (1.0 + w)
+}
+
+/// Runs an impulsive and a persistent fault at every amplitude for every
+/// floor and cap combination, in that nesting order.
+pub fn run_weight_limit_sweep(config: &WeightLimitSweepConfig) -> Vec<WeightLimitRow> {
+    let mut rows = Vec::new();
+
+    for &w_min in &config.w_min_values {
+        for &envelope_cap in &config.envelope_cap_values {
+            let weight_floor = envelope_cap
+                .map_or(0.0, |cap| config.weight_mapping.weight(config.beta, cap))
+                .max(w_min);
+
+            for &amplitude in &config.amplitudes {
+                for disturbance_kind in fault_kinds(config.n_steps, amplitude) {
+                    let sim_config = SimulationConfig {
+                        n_steps: config.n_steps,
+                        rho: config.rho,
+                        beta: config.beta,
+                        disturbance_kind: disturbance_kind.clone(),
+                        epsilon_bound: config.epsilon_bound,
+                        epsilon_model: EpsilonModel::Sinusoidal,
+                        weight_mapping: config.weight_mapping,
+                        w_min,
+                        envelope_cap,
+                    };
+                    let result = run_simulation(&sim_config);
+                    let contributions: Vec<f64> = result
+                        .w
+                        .iter()
+                        .zip(&result.r)
+                        .map(|(&w, &r)| error_contribution(w, r))
+                        .collect();
+
+                    rows.push(WeightLimitRow {
+                        w_min,
+                        envelope_cap,
+                        disturbance_type: disturbance_kind.disturbance_type().to_string(),
+                        amplitude,
+                        weight_floor,
+                        max_envelope: result.s.iter().copied().fold(0.0, f64::max),
+                        min_trust: result.w.iter().copied().fold(1.0, f64::min),
+                        max_error_contribution: contributions.iter().copied().fold(0.0, f64::max),
+                        final_error_contribution: contributions.last().copied().unwrap_or(0.0),
+                        floor_leak: error_contribution(weight_floor, amplitude),
+                    });
+                }
+            }
+        }
+    }
+
+    rows
+}
+
+/// An impulse of `n_steps / 6` steps and a persistent step, both of size
+/// `amplitude` and starting a quarter into the run.
+fn fault_kinds(n_steps: usize, amplitude: f64) -> [DisturbanceKind; 2] {
+    let start = n_steps / 4;
+    [
+        DisturbanceKind::Impulsive {
+            amplitude,
+            start,
+            len: (n_steps / 6).max(1),
+        },
+        DisturbanceKind::PersistentElevated {
+            r_nom: 0.05,
+            r_high: amplitude,
+            step_time: start,
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run_weight_limit_sweep, WeightLimitSweepConfig};
+
+    #[test]
+    fn sweep_covers_every_combination_and_respects_the_floor() {
+        let config = WeightLimitSweepConfig::default();
+        let rows = run_weight_limit_sweep(&config);
+        assert_eq!(
+            rows.len(),
+            config.w_min_values.len()
+                * config.envelope_cap_values.len()
+                * config.amplitudes.len()
+                * 2
+        );
+        assert!(rows.iter().all(|row| row.min_trust >= row.weight_floor));
+    }
+
+    #[test]
+    fn capped_channel_leaks_in_proportion_to_the_fault() {
+        let config = WeightLimitSweepConfig {
+            w_min_values: vec![0.0],
+            envelope_cap_values: vec![None, Some(0.5)],
+            amplitudes: vec![2.0, 8.0],
+            ..WeightLimitSweepConfig::default()
+        };
+        let rows = run_weight_limit_sweep(&config);
+        let persistent = |cap: Option<f64>, amplitude: f64| {
+            rows.iter()
+                .find(|row| {
+                    row.envelope_cap == cap
+                        && row.amplitude == amplitude
+                        && row.disturbance_type == "persistent_elevated"
+                })
+                .expect("row should exist")
+        };
+
+        // Uncapped, trust keeps falling as the fault grows, so the leak saturates.
+        let uncapped = persistent(None, 8.0);
+        assert_eq!(uncapped.weight_floor, 0.0);
+        assert!(uncapped.final_error_contribution < 0.5);
+
+        // Capped, the weight is pinned at the floor and the leak scales with the fault.
+        let small = persistent(Some(0.5), 2.0);
+        let large = persistent(Some(0.5), 8.0);
+        assert!((large.final_error_contribution - large.floor_leak).abs() < 1e-9);
+        assert!(large.final_error_contribution > 3.5 * small.final_error_contribution);
+        assert!(large.final_error_contribution > uncapped.final_error_contribution);
+    }
+}
@@ -0,0 +1,177 @@
+//! End-to-end runs of the `monte_carlo` CLI on a miniature configuration,
+//! checking every output file and its schema.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use dsfb_ddmf::envelope::TrustWeight;
+use dsfb_ddmf::monte_carlo::MonteCarloConfig;
+use serde_json::Value;
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dsfb-ddmf-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+/// Runs the CLI with `args` under `outdir` and returns the run directory.
+fn run_cli(outdir: &Path, args: &[&str]) -> PathBuf {
+    let status = Command::new(env!("CARGO_BIN_EXE_monte_carlo"))
+        .args(args)
+        .arg("--outdir")
+        .arg(outdir)
+        .status()
+        .expect("monte_carlo starts");
+    assert!(status.success(), "monte_carlo {args:?} failed");
+
+    let runs: Vec<PathBuf> = fs::read_dir(outdir)
+        .expect("outdir exists")
+        .map(|entry| entry.expect("dir entry").path())
+        .collect();
+    assert_eq!(runs.len(), 1, "expected one run directory in {outdir:?}");
+    runs.into_iter().next().unwrap()
+}
+
+fn file_names(dir: &Path) -> BTreeSet<String> {
+    fs::read_dir(dir)
+        .expect("run dir exists")
+        .map(|entry| entry.expect("dir entry").file_name().into_string().unwrap())
+        .collect()
+}
+
+fn read_csv(path: &Path) -> (Vec<String>, Vec<csv::StringRecord>) {
+    let mut reader = csv::Reader::from_path(path).expect("csv opens");
+    let header = reader
+        .headers()
+        .expect("csv header")
+        .iter()
+        .map(String::from)
+        .collect();
+    let records = reader.records().map(|r| r.expect("csv record")).collect();
+    (header, records)
+}
+
+fn assert_header(path: &Path, expected: &[&str]) -> Vec<csv::StringRecord> {
+    let (header, records) = read_csv(path);
+    assert_eq!(header, expected, "header of {}", path.display());
+    records
+}
+
+const TRAJECTORY_COLUMNS: [&str; 5] = ["n", "r", "d", "s", "w"];
+
+#[test]
+fn monte_carlo_run_writes_every_output_with_its_schema() {
+    let outdir = scratch_dir("pipeline");
+    let run = run_cli(&outdir, &["--runs", "2", "--steps", "20", "--threads", "1"]);
+
+    let expected: BTreeSet<String> = [
+        "hret_comparison.csv",
+        "results.csv",
+        "single_run_impulse.csv",
+        "single_run_persistent.csv",
+        "summary.json",
+        "weight_limit_sweep.csv",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
+    assert_eq!(file_names(&run), expected);
+
+    let results = assert_header(
+        &run.join("results.csv"),
+        &[
+            "run_id",
+            "regime_label",
+            "disturbance_type",
+            "admissible",
+            "D",
+            "B",
+            "S",
+            "impulse_start",
+            "impulse_len",
+            "s0",
+            "max_envelope",
+            "min_trust",
+            "time_to_recover",
+            "weight_mapping",
+        ],
+    );
+    assert_eq!(results.len(), 2);
+
+    assert_header(
+        &run.join("hret_comparison.csv"),
+        &[
+            "scenario",
+            "disturbance_type",
+            "correlated_groups",
+            "method",
+            "n_channels",
+            "n_groups",
+            "min_trust",
+            "time_to_recover",
+            "error_proxy",
+        ],
+    );
+    assert_header(
+        &run.join("weight_limit_sweep.csv"),
+        &[
+            "w_min",
+            "envelope_cap",
+            "disturbance_type",
+            "amplitude",
+            "weight_floor",
+            "max_envelope",
+            "min_trust",
+            "max_error_contribution",
+            "final_error_contribution",
+            "floor_leak",
+        ],
+    );
+
+    // Trajectory trust follows the rational law of its own envelope column.
+    let beta = MonteCarloConfig::default().beta;
+    for file in ["single_run_impulse.csv", "single_run_persistent.csv"] {
+        let rows = assert_header(&run.join(file), &TRAJECTORY_COLUMNS);
+        assert_eq!(rows.len(), 20, "{file} has one row per step");
+        for row in &rows {
+            let s: f64 = row[3].parse().unwrap();
+            let w: f64 = row[4].parse().unwrap();
+            assert!((w - TrustWeight::weight(beta, s)).abs() < 1e-12);
+        }
+    }
+
+    let summary: Value =
+        serde_json::from_str(&fs::read_to_string(run.join("summary.json")).unwrap()).unwrap();
+    assert_eq!(summary["n_runs"], 2);
+    assert_eq!(summary["n_steps"], 20);
+    for key in ["mean_max_envelope", "min_observed_trust", "regime_counts"] {
+        assert!(summary.get(key).is_some(), "summary.json lacks {key}");
+    }
+
+    let _ = fs::remove_dir_all(&outdir);
+}
+
+#[test]
+fn scenario_run_writes_trajectory_and_preset() {
+    let outdir = scratch_dir("scenario");
+    let run = run_cli(&outdir, &["--scenario", "impulse-recovery"]);
+
+    let expected: BTreeSet<String> = ["scenario.json", "scenario_impulse-recovery.csv"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    assert_eq!(file_names(&run), expected);
+
+    let rows = assert_header(
+        &run.join("scenario_impulse-recovery.csv"),
+        &TRAJECTORY_COLUMNS,
+    );
+    assert!(!rows.is_empty());
+    let preset: Value =
+        serde_json::from_str(&fs::read_to_string(run.join("scenario.json")).unwrap()).unwrap();
+    assert_eq!(preset["name"], "impulse-recovery");
+
+    let _ = fs::remove_dir_all(&outdir);
+}
@@ -0,0 +1,103 @@
+//! Cross-checks that the residual envelope and trust laws shared by `dsfb`,
+//! `dsfb-ddmf`, and `dsfb-hret` still agree, so a change to one definition
+//! cannot silently shift the outputs of the others.
+
+use dsfb::trust::calculate_trust_weights;
+use dsfb_ddmf::{ResidualEnvelope, TrustWeight};
+use dsfb_hret::HretObserver;
+
+const RHO: f64 = 0.9;
+const SIGMA0: f64 = 0.25;
+
+/// Two channels: a quiet one and one hit by an impulse mid-run.
+fn residuals() -> Vec<[f64; 2]> {
+    (0..20)
+        .map(|n| {
+            let quiet = 0.05 * ((n as f64) * 0.7).sin();
+            let noisy = if (8..12).contains(&n) { 1.5 } else { -0.1 };
+            [quiet, noisy]
+        })
+        .collect()
+}
+
+fn hret_observer() -> HretObserver {
+    HretObserver::new(
+        2,
+        1,
+        vec![0, 0],
+        RHO,
+        vec![RHO],
+        vec![1.0 / SIGMA0; 2],
+        vec![0.0],
+        vec![vec![1.0, 1.0]],
+        None,
+    )
+    .expect("valid observer")
+}
+
+#[test]
+fn residual_envelopes_agree_across_crates() {
+    let mut dsfb_ema = [0.0; 2];
+    let mut ddmf = [ResidualEnvelope::new(RHO, 0.0); 2];
+    let mut hret = hret_observer();
+
+    for r in residuals() {
+        calculate_trust_weights(&r, &mut dsfb_ema, RHO, SIGMA0);
+        let (_, _, hret_s, _) = hret.update(r.to_vec()).expect("hret update");
+        for k in 0..2 {
+            let ddmf_s = ddmf[k].update(r[k]);
+            assert!(
+                (dsfb_ema[k] - ddmf_s).abs() < 1e-12,
+                "dsfb vs ddmf, channel {k}"
+            );
+            assert!(
+                (hret_s[k] - ddmf_s).abs() < 1e-12,
+                "hret vs ddmf, channel {k}"
+            );
+        }
+    }
+}
+
+#[test]
+fn normalized_trust_weights_agree_across_crates() {
+    // 1/(sigma0 + s) and 1/(1 + s/sigma0) differ only by the factor sigma0,
+    // which normalization removes.
+    let beta = 1.0 / SIGMA0;
+    let mut dsfb_ema = [0.0; 2];
+    let mut ddmf = [ResidualEnvelope::new(RHO, 0.0); 2];
+    let mut hret = hret_observer();
+
+    for r in residuals() {
+        let dsfb_w = calculate_trust_weights(&r, &mut dsfb_ema, RHO, SIGMA0);
+        let (_, hret_w, _, _) = hret.update(r.to_vec()).expect("hret update");
+
+        let raw: Vec<f64> = (0..2)
+            .map(|k| TrustWeight::weight(beta, ddmf[k].update(r[k])))
+            .collect();
+        let total: f64 = raw.iter().sum();
+        for k in 0..2 {
+            let ddmf_w = raw[k] / total;
+            assert!(
+                (dsfb_w[k] - ddmf_w).abs() < 1e-12,
+                "dsfb vs ddmf, channel {k}"
+            );
+            assert!(
+                (hret_w[k] - ddmf_w).abs() < 1e-12,
+                "hret vs ddmf, channel {k}"
+            );
+        }
+    }
+}
+
+#[test]
+fn ddmf_envelope_exports_dsfb_trust_stats() {
+    let beta = 1.0 / SIGMA0;
+    let mut envelope = ResidualEnvelope::new(RHO, 0.0);
+    for r in residuals() {
+        envelope.update(r[1]);
+    }
+    let stats = envelope.as_dsfb_stats(beta);
+    assert_eq!(stats.residual_ema, envelope.s);
+    assert_eq!(stats.weight, TrustWeight::weight(beta, envelope.s));
+    assert_eq!(stats.bias, 0.0);
+}
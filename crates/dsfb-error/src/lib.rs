@@ -0,0 +1,132 @@
+//! Shared error type of the DSFB workspace libraries.
+//!
+//! Library APIs across the workspace return [`Error`], so a program that
+//! composes several crates handles one matchable type instead of a mix of
+//! crate-specific errors:
+//!
+//! - `dsfb-hret` and `dsfb-add` return it directly; their `HretError` and
+//!   `AddError` names are re-exports of [`Error`].
+//! - The `no_std` core `dsfb` keeps its allocation-free `DsfbError` and
+//!   converts into [`Error`] with `From` when its `std` feature is on.
+//! - Application crates built on `anyhow` accept [`Error`] through `?`, and
+//!   their errors are wrapped with [`Error::other`]. `dsfb-fusion-bench` and
+//!   `dsfb-starship` still return `anyhow::Result` from their library APIs.
+//!
+//! ```
+//! use dsfb_error::{Error, Result};
+//!
+//! fn channel(index: usize, channels: usize) -> Result<usize> {
+//!     if index >= channels {
+//!         return Err(Error::IndexOutOfRange {
+//!             context: "channel",
+//!             index,
+//!             len: channels,
+//!         });
+//!     }
+//!     Ok(index)
+//! }
+//!
+//! assert!(matches!(channel(3, 2), Err(Error::IndexOutOfRange { .. })));
+//! ```
+
+use std::error::Error as StdError;
+
+/// Result with [`Error`] as the default error type.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Error returned by the workspace library APIs.
+///
+/// New variants may be added, so matches need a wildcard arm.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// An argument failed validation; the message names the argument.
+    #[error("{0}")]
+    InvalidInput(String),
+    /// A configuration is inconsistent or out of range.
+    #[error("invalid configuration: {0}")]
+    InvalidConfig(String),
+    /// A sequence has a different length than required.
+    #[error("{context} length mismatch: expected {expected}, got {got}")]
+    LengthMismatch {
+        /// What was measured, e.g. `"measurements"`
+        context: &'static str,
+        expected: usize,
+        got: usize,
+    },
+    /// An index is not below the length of what it indexes.
+    #[error("{context} index {index} out of range 0..{len}")]
+    IndexOutOfRange {
+        /// What was indexed, e.g. `"channel"`
+        context: &'static str,
+        index: usize,
+        len: usize,
+    },
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("csv error: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    /// An error from outside the workspace libraries.
+    #[error(transparent)]
+    Other(Box<dyn StdError + Send + Sync>),
+}
+
+impl Error {
+    /// An [`Error::InvalidInput`] with `message`.
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::InvalidInput(message.into())
+    }
+
+    /// An [`Error::InvalidConfig`] with `message`.
+    pub fn invalid_config(message: impl Into<String>) -> Self {
+        Self::InvalidConfig(message.into())
+    }
+
+    /// Wraps any other error, including an `anyhow::Error`, as
+    /// [`Error::Other`].
+    pub fn other(error: impl Into<Box<dyn StdError + Send + Sync>>) -> Self {
+        Self::Other(error.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_match_the_crate_errors_they_replace() {
+        assert_eq!(
+            Error::LengthMismatch {
+                context: "residuals",
+                expected: 3,
+                got: 2
+            }
+            .to_string(),
+            "residuals length mismatch: expected 3, got 2"
+        );
+        assert_eq!(
+            Error::invalid_input("rho must lie in [0, 1)").to_string(),
+            "rho must lie in [0, 1)"
+        );
+        assert_eq!(
+            Error::invalid_config("steps must be > 0").to_string(),
+            "invalid configuration: steps must be > 0"
+        );
+    }
+
+    #[test]
+    fn conversions_keep_the_source_error() {
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "missing.csv");
+        let error = Error::from(io);
+        assert!(matches!(&error, Error::Io(e) if e.kind() == std::io::ErrorKind::NotFound));
+
+        let json = serde_json::from_str::<u32>("x").unwrap_err();
+        assert!(matches!(Error::from(json), Error::Json(_)));
+
+        let other = Error::other("external failure");
+        assert_eq!(other.to_string(), "external failure");
+        assert!(other.source().is_none());
+    }
+}
@@ -0,0 +1,169 @@
+//! Combining the outputs of several run directories, selected by tag.
+//!
+//! Runs are selected by the `tags` recorded in their `manifest.json`. The
+//! selected runs' `summary.csv`, `summary_sweep.csv`, and `heatmap.csv` are
+//! concatenated into same-named files with a leading `run_dir` column, so a
+//! campaign can be queried without encoding scenarios in directory names.
+
+use anyhow::{bail, Context, Result};
+use csv::{ReaderBuilder, StringRecord, WriterBuilder};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::io::{check_record_schema, read_manifest_json, OUTPUT_SCHEMA_VERSION};
+
+/// Per-run CSVs concatenated by [`aggregate_runs`].
+pub const AGGREGATED_FILES: [&str; 3] = ["summary.csv", "summary_sweep.csv", "heatmap.csv"];
+
+/// Tag-based run selection.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagFilter {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+impl TagFilter {
+    /// A run matches when it carries at least one `include` tag (any run, if
+    /// `include` is empty) and no `exclude` tag.
+    pub fn matches(&self, tags: &[String]) -> bool {
+        let included = self.include.is_empty() || self.include.iter().any(|t| tags.contains(t));
+        included && !self.exclude.iter().any(|t| tags.contains(t))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AggregatedRun {
+    pub run_dir: PathBuf,
+    pub mode: String,
+    pub tags: Vec<String>,
+}
+
+/// Contents of `aggregate_manifest.json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct AggregateManifest {
+    pub schema_version: String,
+    pub include_tags: Vec<String>,
+    pub exclude_tags: Vec<String>,
+    /// Runs whose outputs were combined, in argument order.
+    pub runs: Vec<AggregatedRun>,
+    /// Runs rejected by the tag filter.
+    pub skipped: Vec<PathBuf>,
+    /// Row count of every combined file written, keyed by file name.
+    pub files: BTreeMap<String, usize>,
+}
+
+/// Combines the outputs of the runs in `run_dirs` that match `filter` into
+/// `outdir` and writes `aggregate_manifest.json` there.
+///
+/// Every run must carry the current output schema version, in its manifest
+/// and in every row of the combined files, and a file must have the same
+/// header in every selected run that wrote it.
+pub fn aggregate_runs(
+    run_dirs: &[PathBuf],
+    filter: &TagFilter,
+    outdir: &Path,
+) -> Result<AggregateManifest> {
+    let mut runs = Vec::new();
+    let mut skipped = Vec::new();
+    for run_dir in run_dirs {
+        let manifest = read_manifest_json(run_dir)?;
+        if manifest.schema_version != OUTPUT_SCHEMA_VERSION {
+            bail!(
+                "{} has schema_version {}, expected {}",
+                run_dir.display(),
+                manifest.schema_version,
+                OUTPUT_SCHEMA_VERSION
+            );
+        }
+        if filter.matches(&manifest.tags) {
+            runs.push(AggregatedRun {
+                run_dir: run_dir.clone(),
+                mode: manifest.mode,
+                tags: manifest.tags,
+            });
+        } else {
+            skipped.push(run_dir.clone());
+        }
+    }
+
+    let mut files = BTreeMap::new();
+    for file in AGGREGATED_FILES {
+        if let Some(rows) = concat_csv(&runs, file, &outdir.join(file))? {
+            files.insert(file.to_string(), rows);
+        }
+    }
+
+    let manifest = AggregateManifest {
+        schema_version: OUTPUT_SCHEMA_VERSION.to_string(),
+        include_tags: filter.include.clone(),
+        exclude_tags: filter.exclude.clone(),
+        runs,
+        skipped,
+        files,
+    };
+    let path = outdir.join("aggregate_manifest.json");
+    let payload = serde_json::to_string_pretty(&manifest)
+        .context("failed to serialize aggregate manifest")?;
+    fs::write(&path, payload)
+        .with_context(|| format!("failed to write aggregate manifest: {}", path.display()))?;
+
+    Ok(manifest)
+}
+
+/// Concatenates `file` from every run that has it into `out`, prefixing each
+/// record with its run directory. Returns the row count, or `None` when no
+/// run wrote the file.
+fn concat_csv(runs: &[AggregatedRun], file: &str, out: &Path) -> Result<Option<usize>> {
+    let mut header: Option<(StringRecord, &Path)> = None;
+    let mut records = Vec::<StringRecord>::new();
+
+    for run in runs {
+        let path = run.run_dir.join(file);
+        if !path.exists() {
+            continue;
+        }
+        let mut reader = ReaderBuilder::new()
+            .has_headers(true)
+            .from_path(&path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+        let run_header = reader.headers()?.clone();
+        match &header {
+            Some((expected, first)) if *expected != run_header => bail!(
+                "{file} header in {} differs from {}",
+                run.run_dir.display(),
+                first.display()
+            ),
+            Some(_) => {}
+            None => header = Some((run_header.clone(), &run.run_dir)),
+        }
+
+        let run_dir = run.run_dir.display().to_string();
+        for record in reader.records() {
+            let record = record.with_context(|| format!("failed to read {}", path.display()))?;
+            check_record_schema(&run_header, &record, &path)?;
+            let mut row = StringRecord::from(vec![run_dir.as_str()]);
+            row.extend(record.iter());
+            records.push(row);
+        }
+    }
+
+    let Some((header, _)) = header else {
+        return Ok(None);
+    };
+
+    let mut wtr = WriterBuilder::new()
+        .has_headers(false)
+        .from_path(out)
+        .with_context(|| format!("failed to open {} for writing", out.display()))?;
+    let mut out_header = StringRecord::from(vec!["run_dir"]);
+    out_header.extend(header.iter());
+    wtr.write_record(&out_header)?;
+    for record in &records {
+        wtr.write_record(record)?;
+    }
+    wtr.flush()?;
+
+    Ok(Some(records.len()))
+}
@@ -0,0 +1,132 @@
+//! Parameter recommendations extracted from sweep heatmap cells.
+//!
+//! Cells are grouped by noise vector and method. Per group and objective, the
+//! best cell is the one with the lowest objective value, the first in
+//! ascending (alpha, beta) order on ties. The marginal curves profile the
+//! objective along one axis: for every alpha, the best cell over all betas,
+//! and for every beta, the best cell over all alphas. Cells without a value
+//! for the objective are ignored.
+
+use crate::io::{BestParamsRow, HeatmapRow, MarginalRow};
+use crate::sim::state::SweepObjective;
+
+/// Value of `objective` in a heatmap cell.
+pub fn objective_value(objective: SweepObjective, row: &HeatmapRow) -> Option<f64> {
+    match objective {
+        SweepObjective::PeakErr => Some(row.peak_err),
+        SweepObjective::RmsErr => Some(row.rms_err),
+        SweepObjective::FalseDownweightRate => row.false_downweight_rate,
+        SweepObjective::WorstPeakErr => Some(row.worst_peak_err),
+        SweepObjective::WorstRmsErr => Some(row.worst_rms_err),
+    }
+}
+
+/// Best cell per noise vector, method, and objective, in first-appearance
+/// order of noise vector and method, then objective order.
+pub fn best_params_rows(
+    heatmap: &[HeatmapRow],
+    objectives: &[SweepObjective],
+) -> Vec<BestParamsRow> {
+    let mut rows = Vec::new();
+    for cells in cell_groups(heatmap) {
+        for &objective in objectives {
+            let scored = scored_cells(&cells, objective);
+            let Some((best, value)) = best_cell(&scored) else {
+                continue;
+            };
+            rows.push(BestParamsRow {
+                noise_ratio: best.noise_ratio,
+                noise_std: best.noise_std.clone(),
+                method: best.method.clone(),
+                objective: objective.name().to_string(),
+                alpha: best.alpha,
+                beta: best.beta,
+                objective_value: value,
+                peak_err: best.peak_err,
+                rms_err: best.rms_err,
+                false_downweight_rate: best.false_downweight_rate,
+                cells: scored.len(),
+                tags: best.tags.clone(),
+            });
+        }
+    }
+    rows
+}
+
+/// Marginal curves per noise vector, method, and objective: the alpha curve
+/// in ascending alpha, then the beta curve in ascending beta.
+pub fn marginal_rows(heatmap: &[HeatmapRow], objectives: &[SweepObjective]) -> Vec<MarginalRow> {
+    let mut rows = Vec::new();
+    for cells in cell_groups(heatmap) {
+        for &objective in objectives {
+            let scored = scored_cells(&cells, objective);
+            for (axis, key) in [
+                ("alpha", (|c| c.alpha) as fn(&HeatmapRow) -> f64),
+                ("beta", |c| c.beta),
+            ] {
+                let mut values: Vec<f64> = scored.iter().map(|(c, _)| key(c)).collect();
+                values.sort_by(|a, b| a.total_cmp(b));
+                values.dedup();
+                for value in values {
+                    let slice: Vec<(&HeatmapRow, f64)> = scored
+                        .iter()
+                        .copied()
+                        .filter(|(c, _)| key(c) == value)
+                        .collect();
+                    let Some((best, objective_value)) = best_cell(&slice) else {
+                        continue;
+                    };
+                    rows.push(MarginalRow {
+                        noise_ratio: best.noise_ratio,
+                        noise_std: best.noise_std.clone(),
+                        method: best.method.clone(),
+                        objective: objective.name().to_string(),
+                        axis,
+                        alpha: best.alpha,
+                        beta: best.beta,
+                        objective_value,
+                        tags: best.tags.clone(),
+                    });
+                }
+            }
+        }
+    }
+    rows
+}
+
+/// Cells grouped by noise vector and method, each group in ascending
+/// (alpha, beta) order.
+fn cell_groups(heatmap: &[HeatmapRow]) -> Vec<Vec<&HeatmapRow>> {
+    let mut groups: Vec<Vec<&HeatmapRow>> = Vec::new();
+    for row in heatmap {
+        match groups
+            .iter_mut()
+            .find(|g| g[0].noise_std == row.noise_std && g[0].method == row.method)
+        {
+            Some(group) => group.push(row),
+            None => groups.push(vec![row]),
+        }
+    }
+    for group in &mut groups {
+        group.sort_by(|a, b| a.alpha.total_cmp(&b.alpha).then(a.beta.total_cmp(&b.beta)));
+    }
+    groups
+}
+
+fn scored_cells<'a>(
+    cells: &[&'a HeatmapRow],
+    objective: SweepObjective,
+) -> Vec<(&'a HeatmapRow, f64)> {
+    cells
+        .iter()
+        .filter_map(|c| objective_value(objective, c).map(|v| (*c, v)))
+        .collect()
+}
+
+/// Lowest-valued cell; the first one on ties.
+fn best_cell<'a>(scored: &[(&'a HeatmapRow, f64)]) -> Option<(&'a HeatmapRow, f64)> {
+    scored
+        .iter()
+        .copied()
+        .reduce(|best, cell| if cell.1 < best.1 { cell } else { best })
+}
@@ -0,0 +1,172 @@
+//! Checkpoints that let an interrupted sweep be resumed.
+//!
+//! A sweep appends one JSON line per finished (noise, alpha, beta, seed,
+//! method) run to `sweep_checkpoint.jsonl` in its run directory, after a
+//! first line holding the resolved config, methods, and `emit_tables`.
+//! `--resume <run_dir>` reads that header back, reuses every recorded run,
+//! and computes only the missing ones. The final outputs are written in grid
+//! order as in an uninterrupted sweep, and the checkpoint is removed once
+//! they are complete.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::io::SummaryRow;
+use crate::sim::state::BenchConfig;
+
+pub const CHECKPOINT_FILE: &str = "sweep_checkpoint.jsonl";
+
+/// First line of a checkpoint: what the sweep runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointHeader {
+    pub config: BenchConfig,
+    pub methods: Vec<String>,
+    pub emit_tables: bool,
+}
+
+/// One finished run of one method on one sweep cell and seed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointEntry {
+    pub noise_std: Vec<f64>,
+    pub summary: SummaryRow,
+}
+
+/// Bit patterns of noise, alpha, beta, the seed, and the method name.
+type RunKey = (Vec<u64>, u64, u64, u64, String);
+
+fn run_key(noise_std: &[f64], alpha: f64, beta: f64, seed: u64, method: &str) -> RunKey {
+    (
+        noise_std.iter().map(|v| v.to_bits()).collect(),
+        alpha.to_bits(),
+        beta.to_bits(),
+        seed,
+        method.to_string(),
+    )
+}
+
+/// Checkpoint file of a sweep, shared by its worker threads.
+#[derive(Debug)]
+pub struct SweepCheckpoint {
+    path: PathBuf,
+    completed: HashMap<RunKey, SummaryRow>,
+    file: Mutex<File>,
+}
+
+/// Reads the header of the checkpoint in `run_dir`.
+pub fn read_checkpoint_header(run_dir: &Path) -> Result<CheckpointHeader> {
+    let path = run_dir.join(CHECKPOINT_FILE);
+    let text = fs::read_to_string(&path)
+        .with_context(|| format!("no sweep checkpoint to resume at {}", path.display()))?;
+    let first = text.lines().next().unwrap_or_default();
+    serde_json::from_str(first)
+        .with_context(|| format!("invalid checkpoint header in {}", path.display()))
+}
+
+impl SweepCheckpoint {
+    /// Opens the checkpoint in `run_dir`, loading its finished runs, or starts
+    /// a new one for `header`.
+    ///
+    /// An existing checkpoint must have been written for the same config,
+    /// methods, and `emit_tables`. A final line cut short by an interruption
+    /// is dropped.
+    pub fn open(run_dir: &Path, header: &CheckpointHeader) -> Result<Self> {
+        let path = run_dir.join(CHECKPOINT_FILE);
+        let mut completed = HashMap::new();
+        // Rewritten without any cut-off line so new entries start on their own line
+        let mut contents = serde_json::to_string(header)?;
+        contents.push('\n');
+
+        if path.exists() {
+            let text = fs::read_to_string(&path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            let mut lines = text.lines();
+            let stored: CheckpointHeader =
+                serde_json::from_str(lines.next().unwrap_or_default())
+                    .with_context(|| format!("invalid checkpoint header in {}", path.display()))?;
+            if serde_json::to_value(&stored)? != serde_json::to_value(header)? {
+                bail!(
+                    "{} was written for a different config or method list",
+                    path.display()
+                );
+            }
+
+            let lines: Vec<&str> = lines.collect();
+            for (idx, line) in lines.iter().enumerate() {
+                let entry: CheckpointEntry = match serde_json::from_str(line) {
+                    Ok(entry) => entry,
+                    Err(_) if idx + 1 == lines.len() => break,
+                    Err(err) => {
+                        return Err(err).with_context(|| {
+                            format!("invalid entry on line {} of {}", idx + 2, path.display())
+                        })
+                    }
+                };
+                let row = &entry.summary;
+                let (Some(alpha), Some(beta)) = (row.alpha, row.beta) else {
+                    bail!("checkpoint entry on line {} has no alpha/beta", idx + 2);
+                };
+                completed.insert(
+                    run_key(&entry.noise_std, alpha, beta, row.seed, &row.method),
+                    entry.summary,
+                );
+                contents.push_str(line);
+                contents.push('\n');
+            }
+        }
+
+        fs::write(&path, contents)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        let file = OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+
+        Ok(Self {
+            path,
+            completed,
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Number of runs recorded before this sweep started.
+    pub fn completed_runs(&self) -> usize {
+        self.completed.len()
+    }
+
+    /// Summary of a run recorded before this sweep started.
+    pub fn completed(
+        &self,
+        noise_std: &[f64],
+        alpha: f64,
+        beta: f64,
+        seed: u64,
+        method: &str,
+    ) -> Option<&SummaryRow> {
+        self.completed
+            .get(&run_key(noise_std, alpha, beta, seed, method))
+    }
+
+    /// Appends a finished run.
+    pub fn record(&self, noise_std: &[f64], summary: &SummaryRow) -> Result<()> {
+        let mut line = serde_json::to_string(&CheckpointEntry {
+            noise_std: noise_std.to_vec(),
+            summary: summary.clone(),
+        })?;
+        line.push('\n');
+        let mut file = self.file.lock().expect("checkpoint lock poisoned");
+        file.write_all(line.as_bytes())
+            .with_context(|| format!("failed to append to {}", self.path.display()))
+    }
+
+    /// Removes the checkpoint once the sweep's outputs are complete.
+    pub fn finish(self) -> Result<()> {
+        drop(self.file);
+        fs::remove_file(&self.path)
+            .with_context(|| format!("failed to remove {}", self.path.display()))
+    }
+}
@@ -0,0 +1,200 @@
+//! Degenerate and edge-case scenario pack.
+//!
+//! Each scenario perturbs the base configuration into a corner of the problem
+//! space (zero-noise group, single group, every group corrupted at once,
+//! rank-deficient H) and every `ReconstructionMethod` is checked against the
+//! qualitative behaviour expected of it there. Checks are reported as
+//! pass/fail rather than aborting, so the pack doubles as a conformance suite
+//! for method implementations.
+
+use anyhow::Result;
+
+use crate::methods::ReconstructionMethod;
+use crate::metrics::FALSE_DOWNWEIGHT_THRESHOLD;
+use crate::sim::diagnostics::{build_diagnostic_model, DiagnosticModel};
+use crate::sim::faults::apply_impulse_corruption;
+use crate::sim::state::{generate_simulation_data, BenchConfig, SimulationData};
+
+/// Fraction of the run, at the end, over which fault recovery is judged.
+pub const RECOVERY_WINDOW_FRACTION: f64 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeCase {
+    /// Group 0 has zero measurement noise (`r_diag = 0`).
+    ZeroNoiseGroup,
+    /// A single diagnostic group (`K = 1`) with more rows than states.
+    SingleGroup,
+    /// The impulse corrupts channel 0 of every group simultaneously.
+    AllGroupsCorrupted,
+    /// State 0 is unobserved by every group, so the stacked H loses rank.
+    RankDeficientH,
+}
+
+impl EdgeCase {
+    pub const ALL: [EdgeCase; 4] = [
+        EdgeCase::ZeroNoiseGroup,
+        EdgeCase::SingleGroup,
+        EdgeCase::AllGroupsCorrupted,
+        EdgeCase::RankDeficientH,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EdgeCase::ZeroNoiseGroup => "zero_noise_group",
+            EdgeCase::SingleGroup => "single_group",
+            EdgeCase::AllGroupsCorrupted => "all_groups_corrupted",
+            EdgeCase::RankDeficientH => "rank_deficient_h",
+        }
+    }
+}
+
+/// A fully built scenario: resolved config, model, and simulated data.
+#[derive(Debug, Clone)]
+pub struct EdgeCaseScenario {
+    pub case: EdgeCase,
+    pub cfg: BenchConfig,
+    pub model: DiagnosticModel,
+    pub data: SimulationData,
+}
+
+#[derive(Debug, Clone)]
+pub struct EdgeCaseCheck {
+    pub check: &'static str,
+    pub passed: bool,
+    pub value: f64,
+}
+
+/// Builds `case` from `base` for one seed.
+///
+/// The scenarios deliberately leave the range accepted by
+/// `BenchConfig::validate` (e.g. zero noise), so the config is not re-validated.
+pub fn build_edge_case(base: &BenchConfig, case: EdgeCase, seed: u64) -> Result<EdgeCaseScenario> {
+    let mut cfg = base.clone();
+
+    match case {
+        EdgeCase::ZeroNoiseGroup => {
+            cfg.noise_std[0] = 0.0;
+        }
+        EdgeCase::SingleGroup => {
+            cfg.group_dims = vec![cfg.n + 2];
+            cfg.noise_std = vec![cfg.noise_std[0]];
+            cfg.noise_correlation = cfg.noise_correlation.map(|rhos| vec![rhos[0]]);
+            cfg.bandwidth_groups.clear();
+            cfg.corruption_group = 0;
+            cfg.corruption_channel = 0;
+            // Fault and drift targets refer to the base group layout.
+            cfg.faults.clear();
+            cfg.model_drift.clear();
+        }
+        EdgeCase::AllGroupsCorrupted => {
+            // Corruption is injected per group below.
+            cfg.corruption_amplitude = 0.0;
+        }
+        EdgeCase::RankDeficientH => {}
+    }
+
+    let mut model = build_diagnostic_model(&cfg)?;
+    if case == EdgeCase::RankDeficientH {
+        for group in &mut model.groups {
+            group.h.column_mut(0).fill(0.0);
+        }
+    }
+
+    let mut data = generate_simulation_data(&cfg, &model, seed)?;
+    if case == EdgeCase::AllGroupsCorrupted {
+        let mut impulse_cfg = base.clone();
+        impulse_cfg.corruption_channel = 0;
+        for (step, frame) in data.measurements.iter_mut().enumerate() {
+            for k in 0..model.groups.len() {
+                impulse_cfg.corruption_group = k;
+                let applied = apply_impulse_corruption(&impulse_cfg, frame, step);
+                data.corruption_active[step] |= applied;
+                data.group_corrupted[step][k] |= applied;
+            }
+        }
+    }
+
+    Ok(EdgeCaseScenario {
+        case,
+        cfg,
+        model,
+        data,
+    })
+}
+
+/// Runs `method` over the scenario and evaluates the expected behaviour.
+///
+/// - `finite_estimates`: every state estimate is finite (value: non-finite steps)
+/// - `weights_in_unit_interval`: group weights stay in `[0, 1]` (value: violations)
+/// - `weights_recover`: after the fault clears, the mean weight of every group
+///   over the final `RECOVERY_WINDOW_FRACTION` of the run is at least
+///   `FALSE_DOWNWEIGHT_THRESHOLD` (value: smallest such mean)
+///
+/// The weight checks apply only to methods that report group weights, and
+/// `weights_recover` only to those whose weights adapt during the run; a fixed
+/// prior weighting (e.g. `cov_inflate`) has nothing to recover.
+pub fn run_edge_case(
+    scenario: &EdgeCaseScenario,
+    method: &mut dyn ReconstructionMethod,
+) -> Vec<EdgeCaseCheck> {
+    let steps = scenario.data.t.len();
+    let window_start =
+        steps - ((steps as f64 * RECOVERY_WINDOW_FRACTION).ceil() as usize).clamp(1, steps);
+    let k = scenario.model.groups.len();
+
+    method.reset(&scenario.cfg, &scenario.model);
+
+    let mut non_finite = 0usize;
+    let mut out_of_range = 0usize;
+    let mut window_sums = vec![0.0; k];
+    let mut first_weights: Option<Vec<f64>> = None;
+    let mut adaptive = false;
+
+    for step in 0..steps {
+        let out = method.estimate(&scenario.model, &scenario.data.measurements[step].y_groups);
+        if out.x_hat.iter().any(|v| !v.is_finite()) {
+            non_finite += 1;
+        }
+        if let Some(weights) = &out.group_weights {
+            out_of_range += weights.iter().filter(|w| !(0.0..=1.0).contains(*w)).count();
+            match &first_weights {
+                Some(first) => adaptive |= first != weights,
+                None => first_weights = Some(weights.clone()),
+            }
+            if step >= window_start {
+                for (sum, &w) in window_sums.iter_mut().zip(weights) {
+                    *sum += w;
+                }
+            }
+        }
+    }
+
+    let mut checks = vec![EdgeCaseCheck {
+        check: "finite_estimates",
+        passed: non_finite == 0,
+        value: non_finite as f64,
+    }];
+
+    if method.has_weights() {
+        let window_len = (steps - window_start) as f64;
+        let min_recovered = window_sums
+            .iter()
+            .map(|sum| sum / window_len)
+            .fold(f64::INFINITY, f64::min);
+
+        checks.push(EdgeCaseCheck {
+            check: "weights_in_unit_interval",
+            passed: out_of_range == 0,
+            value: out_of_range as f64,
+        });
+        if adaptive {
+            checks.push(EdgeCaseCheck {
+                check: "weights_recover",
+                passed: min_recovered >= FALSE_DOWNWEIGHT_THRESHOLD,
+                value: min_recovered,
+            });
+        }
+    }
+
+    checks
+}
@@ -1,12 +1,20 @@
-use anyhow::{Context, Result};
-use csv::WriterBuilder;
-use serde::Serialize;
+use anyhow::{bail, Context, Result};
+use csv::{StringRecord, WriterBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-pub const OUTPUT_SCHEMA_VERSION: &str = "1.0.0";
+use crate::sim::state::{BenchConfig, TAG_SEPARATOR};
+use crate::stats::StatsTestRow;
+use crate::timing::SampleStats;
 
-#[derive(Debug, Clone)]
+/// Layout of the output CSVs and manifest. Bumped whenever a column is
+/// added, removed, reordered, or changes meaning, so readers of run
+/// directories can reject outputs of another layout.
+pub const OUTPUT_SCHEMA_VERSION: &str = "2.0.0";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SummaryRow {
     pub method: String,
     pub seed: u64,
@@ -16,21 +24,86 @@ pub struct SummaryRow {
     pub peak_err: f64,
     pub rms_err: f64,
     pub false_downweight_rate: Option<f64>,
+    /// Fraction of corrupted (step, group) pairs downweighted; `None` without
+    /// weights or corruption
+    pub true_downweight_rate: Option<f64>,
     pub baseline_wls_us: f64,
     pub overhead_us: f64,
     pub total_us: f64,
     pub alpha: Option<f64>,
     pub beta: Option<f64>,
+    pub noise_ratio: f64,
+    /// Iterative-solver statistics; `None` for closed-form methods
+    pub mean_iterations: Option<f64>,
+    pub max_iterations: Option<usize>,
+    pub nonconvergence_rate: Option<f64>,
+    /// Fitted time constants (s) of the corrupted group's weight after fault
+    /// onset and after clearance; `None` without weights or a clear response
+    pub downweight_tau_s: Option<f64>,
+    pub recovery_tau_s: Option<f64>,
+    /// Mean time to first downweight after each corruption onset, and back
+    /// after its end, and the weight ROC AUC; `None` without weights
+    pub detection_latency_s: Option<f64>,
+    pub recovery_latency_s: Option<f64>,
+    pub detection_auc: Option<f64>,
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct HeatmapRow {
     pub alpha: f64,
     pub beta: f64,
+    /// Largest over smallest group `noise_std` of the sweep cell
+    pub noise_ratio: f64,
+    pub noise_std: Vec<f64>,
+    pub method: String,
+    pub peak_err: f64,
+    pub rms_err: f64,
+    pub false_downweight_rate: Option<f64>,
+    /// Sample standard deviations across the cell's seeds; zero for one seed
+    pub peak_err_std: f64,
+    pub rms_err_std: f64,
+    pub false_downweight_rate_std: Option<f64>,
+    /// Seed with the largest `rms_err`, and its metrics
+    pub worst_seed: u64,
+    pub worst_peak_err: f64,
+    pub worst_rms_err: f64,
+    pub tags: Vec<String>,
+}
+
+/// Lowest-objective (alpha, beta) cell of one method and noise vector.
+#[derive(Debug, Clone)]
+pub struct BestParamsRow {
+    pub noise_ratio: f64,
+    pub noise_std: Vec<f64>,
     pub method: String,
+    pub objective: String,
+    pub alpha: f64,
+    pub beta: f64,
+    pub objective_value: f64,
+    /// Heatmap metrics of the chosen cell
     pub peak_err: f64,
     pub rms_err: f64,
     pub false_downweight_rate: Option<f64>,
+    /// Cells that had a value for the objective
+    pub cells: usize,
+    pub tags: Vec<String>,
+}
+
+/// One point of a marginal curve: the best cell along `axis` at one value of
+/// that parameter, with the other parameter free.
+#[derive(Debug, Clone)]
+pub struct MarginalRow {
+    pub noise_ratio: f64,
+    pub noise_std: Vec<f64>,
+    pub method: String,
+    pub objective: String,
+    /// `alpha` or `beta`, the parameter held fixed
+    pub axis: &'static str,
+    pub alpha: f64,
+    pub beta: f64,
+    pub objective_value: f64,
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -41,19 +114,207 @@ pub struct TrajectoryRow {
     pub weights: Option<Vec<f64>>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone)]
+pub struct StateErrorRow {
+    pub t: f64,
+    pub method: String,
+    pub seed: u64,
+    pub errors: Vec<f64>,
+}
+
+/// Difference between a method's estimate and the equal-weight estimate at
+/// one step.
+#[derive(Debug, Clone)]
+pub struct EqualDeltaRow {
+    pub t: f64,
+    pub method: String,
+    pub seed: u64,
+    pub delta_norm: f64,
+    /// One of `+`, `-`, or `0` per state, in state order.
+    pub delta_signs: String,
+}
+
+/// Per-group NIS of the equal-weight solve at one step.
+#[derive(Debug, Clone)]
+pub struct GroupNisRow {
+    pub t: f64,
+    pub seed: u64,
+    pub corruption_active: bool,
+    pub nis: Vec<f64>,
+}
+
+/// One channel corrupted by a `[[faults]]` entry at one step.
+#[derive(Debug, Clone)]
+pub struct FaultLabelRow {
+    pub t: f64,
+    pub seed: u64,
+    /// Index of the entry in `faults`.
+    pub fault: usize,
+    pub kind: &'static str,
+    pub group: usize,
+    pub channel: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct StateSummaryRow {
+    pub method: String,
+    pub seed: u64,
+    pub state: usize,
+    pub peak_err: f64,
+    pub rms_err: f64,
+}
+
+/// Downweight rates of one group for one method and seed, scored against the
+/// group's own fault labels.
+#[derive(Debug, Clone)]
+pub struct GroupDownweightRow {
+    pub method: String,
+    pub seed: u64,
+    pub group: usize,
+    pub clean_steps: usize,
+    pub false_downweight_rate: Option<f64>,
+    pub corrupted_steps: usize,
+    pub true_downweight_rate: Option<f64>,
+    pub episodes: usize,
+    pub detected_episodes: usize,
+    pub detection_latency_s: Option<f64>,
+    pub recovery_latency_s: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ErrorCdfRow {
+    pub method: String,
+    pub probability: f64,
+    /// Error norm at `probability` of the empirical CDF
+    pub quantile: f64,
+    /// Per-step error norms pooled over seeds
+    pub samples: usize,
+}
+
+/// Equal-weight WLS solved through the normal equations and as a stacked QR
+/// problem, for one seed.
+/// Per-call timing distribution of one method from `--bench-solver`.
+#[derive(Debug, Clone)]
+pub struct SolverBenchRow {
+    pub method: String,
+    pub n: usize,
+    pub k: usize,
+    pub m: usize,
+    pub warmup: usize,
+    pub batch: usize,
+    pub stats: SampleStats,
+}
+
+#[derive(Debug, Clone)]
+pub struct SolverComparisonRow {
+    pub seed: u64,
+    pub n: usize,
+    pub k: usize,
+    pub m: usize,
+    /// Mean solve time per step of each path
+    pub normal_eq_us: f64,
+    pub stacked_qr_us: f64,
+    pub normal_eq_rms_err: f64,
+    pub stacked_qr_rms_err: f64,
+    /// Largest absolute difference between the two solutions over all steps
+    pub max_solution_diff: f64,
+    /// Largest `max |R_ii| / min |R_ii|` of the whitened stack over all steps
+    pub max_qr_cond_estimate: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct EdgeCaseRow {
+    pub scenario: String,
+    pub method: String,
+    pub seed: u64,
+    pub check: String,
+    pub passed: bool,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct NullSummaryRow {
+    pub method: String,
+    pub seed: u64,
+    pub min_weight: f64,
+    pub min_group_weights: Vec<f64>,
+    pub excursion_steps: usize,
+    pub excursion_events: usize,
+    pub longest_excursion: usize,
+    pub excursion_rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Manifest {
     pub schema_version: String,
     pub mode: String,
     pub methods: Vec<String>,
     pub seeds: Vec<u64>,
     pub note: String,
+    /// Config `tags` of the run.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Fully resolved config (after CLI overrides) the run executed with.
+    #[serde(default)]
+    pub config: Option<BenchConfig>,
+    /// Preprocessing stages applied to the frames, in order; empty when none.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub preprocessing: Vec<String>,
+    #[serde(default)]
+    pub emit_tables: bool,
+    /// Output CSV hashes (timing columns excluded), keyed by file name.
+    #[serde(default)]
+    pub output_hashes: BTreeMap<String, String>,
+    /// Run directory this run re-executed, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rerun_of: Option<PathBuf>,
+}
+
+impl Manifest {
+    pub fn new(
+        mode: &str,
+        note: &str,
+        cfg: &BenchConfig,
+        methods: &[String],
+        emit_tables: bool,
+    ) -> Self {
+        Self {
+            schema_version: OUTPUT_SCHEMA_VERSION.to_string(),
+            mode: mode.to_string(),
+            methods: methods.to_vec(),
+            seeds: cfg.seeds.clone(),
+            note: note.to_string(),
+            tags: cfg.tags.clone(),
+            config: Some(cfg.clone()),
+            preprocessing: cfg
+                .preprocess
+                .as_ref()
+                .map(|pre| pre.stages())
+                .unwrap_or_default(),
+            emit_tables,
+            output_hashes: BTreeMap::new(),
+            rerun_of: None,
+        }
+    }
 }
 
 fn fmt_f64(v: f64) -> String {
     format!("{v:.10}")
 }
 
+/// Tags joined into a single CSV field.
+pub fn fmt_tags(tags: &[String]) -> String {
+    tags.join(&TAG_SEPARATOR.to_string())
+}
+
+fn fmt_noise_std(noise_std: &[f64]) -> String {
+    noise_std
+        .iter()
+        .map(|&s| fmt_f64(s))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
 fn fmt_opt(v: Option<f64>) -> String {
     match v {
         Some(x) => fmt_f64(x),
@@ -81,11 +342,22 @@ pub fn write_summary_csv(path: &Path, rows: &[SummaryRow]) -> Result<()> {
         "peak_err",
         "rms_err",
         "false_downweight_rate",
+        "true_downweight_rate",
         "baseline_wls_us",
         "overhead_us",
         "total_us",
         "alpha",
         "beta",
+        "noise_ratio",
+        "mean_iterations",
+        "max_iterations",
+        "nonconvergence_rate",
+        "downweight_tau_s",
+        "recovery_tau_s",
+        "detection_latency_s",
+        "recovery_latency_s",
+        "detection_auc",
+        "tags",
         "schema_version",
     ])?;
 
@@ -99,11 +371,23 @@ pub fn write_summary_csv(path: &Path, rows: &[SummaryRow]) -> Result<()> {
             &fmt_f64(row.peak_err),
             &fmt_f64(row.rms_err),
             &fmt_opt(row.false_downweight_rate),
+            &fmt_opt(row.true_downweight_rate),
             &fmt_f64(row.baseline_wls_us),
             &fmt_f64(row.overhead_us),
             &fmt_f64(row.total_us),
             &fmt_opt(row.alpha),
             &fmt_opt(row.beta),
+            &fmt_f64(row.noise_ratio),
+            &fmt_opt(row.mean_iterations),
+            &row.max_iterations
+                .map_or_else(|| "NA".to_string(), |v| v.to_string()),
+            &fmt_opt(row.nonconvergence_rate),
+            &fmt_opt(row.downweight_tau_s),
+            &fmt_opt(row.recovery_tau_s),
+            &fmt_opt(row.detection_latency_s),
+            &fmt_opt(row.recovery_latency_s),
+            &fmt_opt(row.detection_auc),
+            &fmt_tags(&row.tags),
             OUTPUT_SCHEMA_VERSION,
         ])?;
     }
@@ -121,10 +405,19 @@ pub fn write_heatmap_csv(path: &Path, rows: &[HeatmapRow]) -> Result<()> {
     wtr.write_record([
         "alpha",
         "beta",
+        "noise_ratio",
+        "noise_std",
         "method",
         "peak_err",
         "rms_err",
         "false_downweight_rate",
+        "peak_err_std",
+        "rms_err_std",
+        "false_downweight_rate_std",
+        "worst_seed",
+        "worst_peak_err",
+        "worst_rms_err",
+        "tags",
         "schema_version",
     ])?;
 
@@ -132,10 +425,111 @@ pub fn write_heatmap_csv(path: &Path, rows: &[HeatmapRow]) -> Result<()> {
         wtr.write_record([
             &fmt_f64(row.alpha),
             &fmt_f64(row.beta),
+            &fmt_f64(row.noise_ratio),
+            &fmt_noise_std(&row.noise_std),
             row.method.as_str(),
             &fmt_f64(row.peak_err),
             &fmt_f64(row.rms_err),
             &fmt_opt(row.false_downweight_rate),
+            &fmt_f64(row.peak_err_std),
+            &fmt_f64(row.rms_err_std),
+            &fmt_opt(row.false_downweight_rate_std),
+            &row.worst_seed.to_string(),
+            &fmt_f64(row.worst_peak_err),
+            &fmt_f64(row.worst_rms_err),
+            &fmt_tags(&row.tags),
+            OUTPUT_SCHEMA_VERSION,
+        ])?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+pub fn write_best_params_csv(path: &Path, rows: &[BestParamsRow]) -> Result<()> {
+    let mut wtr = WriterBuilder::new()
+        .has_headers(false)
+        .from_path(path)
+        .with_context(|| {
+            format!(
+                "failed to open best_params.csv for writing: {}",
+                path.display()
+            )
+        })?;
+
+    wtr.write_record([
+        "noise_ratio",
+        "noise_std",
+        "method",
+        "objective",
+        "alpha",
+        "beta",
+        "objective_value",
+        "peak_err",
+        "rms_err",
+        "false_downweight_rate",
+        "cells",
+        "tags",
+        "schema_version",
+    ])?;
+
+    for row in rows {
+        wtr.write_record([
+            &fmt_f64(row.noise_ratio),
+            &fmt_noise_std(&row.noise_std),
+            row.method.as_str(),
+            row.objective.as_str(),
+            &fmt_f64(row.alpha),
+            &fmt_f64(row.beta),
+            &fmt_f64(row.objective_value),
+            &fmt_f64(row.peak_err),
+            &fmt_f64(row.rms_err),
+            &fmt_opt(row.false_downweight_rate),
+            &row.cells.to_string(),
+            &fmt_tags(&row.tags),
+            OUTPUT_SCHEMA_VERSION,
+        ])?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+pub fn write_marginals_csv(path: &Path, rows: &[MarginalRow]) -> Result<()> {
+    let mut wtr = WriterBuilder::new()
+        .has_headers(false)
+        .from_path(path)
+        .with_context(|| {
+            format!(
+                "failed to open marginals.csv for writing: {}",
+                path.display()
+            )
+        })?;
+
+    wtr.write_record([
+        "noise_ratio",
+        "noise_std",
+        "method",
+        "objective",
+        "axis",
+        "alpha",
+        "beta",
+        "objective_value",
+        "tags",
+        "schema_version",
+    ])?;
+
+    for row in rows {
+        wtr.write_record([
+            &fmt_f64(row.noise_ratio),
+            &fmt_noise_std(&row.noise_std),
+            row.method.as_str(),
+            row.objective.as_str(),
+            row.axis,
+            &fmt_f64(row.alpha),
+            &fmt_f64(row.beta),
+            &fmt_f64(row.objective_value),
+            &fmt_tags(&row.tags),
             OUTPUT_SCHEMA_VERSION,
         ])?;
     }
@@ -189,6 +583,504 @@ pub fn write_trajectories_csv(path: &Path, rows: &[TrajectoryRow], k: usize) ->
     Ok(())
 }
 
+pub fn write_null_summary_csv(path: &Path, rows: &[NullSummaryRow], k: usize) -> Result<()> {
+    let mut wtr = WriterBuilder::new()
+        .has_headers(false)
+        .from_path(path)
+        .with_context(|| {
+            format!(
+                "failed to open null_summary.csv for writing: {}",
+                path.display()
+            )
+        })?;
+
+    let mut header = vec![
+        "method".to_string(),
+        "seed".to_string(),
+        "min_weight".to_string(),
+    ];
+    for i in 0..k {
+        header.push(format!("min_w_{i}"));
+    }
+    header.extend(
+        [
+            "excursion_steps",
+            "excursion_events",
+            "longest_excursion",
+            "excursion_rate",
+            "schema_version",
+        ]
+        .map(String::from),
+    );
+    wtr.write_record(&header)?;
+
+    for row in rows {
+        let mut record = vec![
+            row.method.clone(),
+            row.seed.to_string(),
+            fmt_f64(row.min_weight),
+        ];
+        for i in 0..k {
+            record.push(fmt_opt(row.min_group_weights.get(i).copied()));
+        }
+        record.push(row.excursion_steps.to_string());
+        record.push(row.excursion_events.to_string());
+        record.push(row.longest_excursion.to_string());
+        record.push(fmt_f64(row.excursion_rate));
+        record.push(OUTPUT_SCHEMA_VERSION.to_string());
+        wtr.write_record(&record)?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+pub fn write_state_errors_csv(path: &Path, rows: &[StateErrorRow], dims: &[usize]) -> Result<()> {
+    let mut wtr = WriterBuilder::new()
+        .has_headers(false)
+        .from_path(path)
+        .with_context(|| {
+            format!(
+                "failed to open state_errors.csv for writing: {}",
+                path.display()
+            )
+        })?;
+
+    let mut header = vec!["t".to_string(), "method".to_string(), "seed".to_string()];
+    for d in dims {
+        header.push(format!("err_x{d}"));
+    }
+    header.push("schema_version".to_string());
+    wtr.write_record(&header)?;
+
+    for row in rows {
+        let mut record = vec![fmt_f64(row.t), row.method.clone(), row.seed.to_string()];
+        record.extend(row.errors.iter().map(|&e| fmt_f64(e)));
+        record.push(OUTPUT_SCHEMA_VERSION.to_string());
+        wtr.write_record(&record)?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+pub fn write_equal_deltas_csv(path: &Path, rows: &[EqualDeltaRow]) -> Result<()> {
+    let mut wtr = WriterBuilder::new()
+        .has_headers(false)
+        .from_path(path)
+        .with_context(|| {
+            format!(
+                "failed to open equal_deltas.csv for writing: {}",
+                path.display()
+            )
+        })?;
+
+    wtr.write_record([
+        "t",
+        "method",
+        "seed",
+        "delta_norm",
+        "delta_signs",
+        "schema_version",
+    ])?;
+
+    for row in rows {
+        wtr.write_record([
+            &fmt_f64(row.t),
+            row.method.as_str(),
+            &row.seed.to_string(),
+            &fmt_f64(row.delta_norm),
+            row.delta_signs.as_str(),
+            OUTPUT_SCHEMA_VERSION,
+        ])?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+pub fn write_group_nis_csv(path: &Path, rows: &[GroupNisRow], k: usize) -> Result<()> {
+    let mut wtr = WriterBuilder::new()
+        .has_headers(false)
+        .from_path(path)
+        .with_context(|| {
+            format!(
+                "failed to open group_nis.csv for writing: {}",
+                path.display()
+            )
+        })?;
+
+    let mut header = vec![
+        "t".to_string(),
+        "seed".to_string(),
+        "corruption_active".to_string(),
+    ];
+    for i in 0..k {
+        header.push(format!("nis_{i}"));
+    }
+    header.push("schema_version".to_string());
+    wtr.write_record(&header)?;
+
+    for row in rows {
+        let mut record = vec![
+            fmt_f64(row.t),
+            row.seed.to_string(),
+            u8::from(row.corruption_active).to_string(),
+        ];
+        for i in 0..k {
+            match row.nis.get(i) {
+                Some(&nis) => record.push(fmt_f64(nis)),
+                None => record.push("NA".to_string()),
+            }
+        }
+        record.push(OUTPUT_SCHEMA_VERSION.to_string());
+        wtr.write_record(&record)?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+pub fn write_fault_labels_csv(path: &Path, rows: &[FaultLabelRow]) -> Result<()> {
+    let mut wtr = WriterBuilder::new()
+        .has_headers(false)
+        .from_path(path)
+        .with_context(|| {
+            format!(
+                "failed to open fault_labels.csv for writing: {}",
+                path.display()
+            )
+        })?;
+
+    wtr.write_record([
+        "t",
+        "seed",
+        "fault",
+        "kind",
+        "group",
+        "channel",
+        "schema_version",
+    ])?;
+
+    for row in rows {
+        wtr.write_record([
+            &fmt_f64(row.t),
+            &row.seed.to_string(),
+            &row.fault.to_string(),
+            row.kind,
+            &row.group.to_string(),
+            &row.channel.to_string(),
+            OUTPUT_SCHEMA_VERSION,
+        ])?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+pub fn write_state_summary_csv(path: &Path, rows: &[StateSummaryRow]) -> Result<()> {
+    let mut wtr = WriterBuilder::new()
+        .has_headers(false)
+        .from_path(path)
+        .with_context(|| {
+            format!(
+                "failed to open state_summary.csv for writing: {}",
+                path.display()
+            )
+        })?;
+
+    wtr.write_record([
+        "method",
+        "seed",
+        "state",
+        "peak_err",
+        "rms_err",
+        "schema_version",
+    ])?;
+
+    for row in rows {
+        wtr.write_record([
+            row.method.as_str(),
+            &row.seed.to_string(),
+            &row.state.to_string(),
+            &fmt_f64(row.peak_err),
+            &fmt_f64(row.rms_err),
+            OUTPUT_SCHEMA_VERSION,
+        ])?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+pub fn write_group_downweight_csv(path: &Path, rows: &[GroupDownweightRow]) -> Result<()> {
+    let mut wtr = WriterBuilder::new()
+        .has_headers(false)
+        .from_path(path)
+        .with_context(|| {
+            format!(
+                "failed to open group_downweight.csv for writing: {}",
+                path.display()
+            )
+        })?;
+
+    wtr.write_record([
+        "method",
+        "seed",
+        "group",
+        "clean_steps",
+        "false_downweight_rate",
+        "corrupted_steps",
+        "true_downweight_rate",
+        "episodes",
+        "detected_episodes",
+        "detection_latency_s",
+        "recovery_latency_s",
+        "schema_version",
+    ])?;
+
+    for row in rows {
+        wtr.write_record([
+            row.method.as_str(),
+            &row.seed.to_string(),
+            &row.group.to_string(),
+            &row.clean_steps.to_string(),
+            &fmt_opt(row.false_downweight_rate),
+            &row.corrupted_steps.to_string(),
+            &fmt_opt(row.true_downweight_rate),
+            &row.episodes.to_string(),
+            &row.detected_episodes.to_string(),
+            &fmt_opt(row.detection_latency_s),
+            &fmt_opt(row.recovery_latency_s),
+            OUTPUT_SCHEMA_VERSION,
+        ])?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+pub fn write_error_cdf_csv(path: &Path, rows: &[ErrorCdfRow]) -> Result<()> {
+    let mut wtr = WriterBuilder::new()
+        .has_headers(false)
+        .from_path(path)
+        .with_context(|| {
+            format!(
+                "failed to open error_cdf.csv for writing: {}",
+                path.display()
+            )
+        })?;
+
+    wtr.write_record([
+        "method",
+        "probability",
+        "quantile",
+        "samples",
+        "schema_version",
+    ])?;
+
+    for row in rows {
+        wtr.write_record([
+            row.method.as_str(),
+            &fmt_f64(row.probability),
+            &fmt_f64(row.quantile),
+            &row.samples.to_string(),
+            OUTPUT_SCHEMA_VERSION,
+        ])?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+pub fn write_solver_bench_csv(path: &Path, rows: &[SolverBenchRow]) -> Result<()> {
+    let mut wtr = WriterBuilder::new()
+        .has_headers(false)
+        .from_path(path)
+        .with_context(|| {
+            format!(
+                "failed to open solver_bench.csv for writing: {}",
+                path.display()
+            )
+        })?;
+
+    wtr.write_record([
+        "method",
+        "n",
+        "K",
+        "M",
+        "warmup",
+        "batch",
+        "samples_kept",
+        "outliers",
+        "mean_us",
+        "std_us",
+        "min_us",
+        "p50_us",
+        "p95_us",
+        "p99_us",
+        "max_us",
+        "schema_version",
+    ])?;
+
+    for row in rows {
+        let s = &row.stats;
+        wtr.write_record([
+            row.method.as_str(),
+            &row.n.to_string(),
+            &row.k.to_string(),
+            &row.m.to_string(),
+            &row.warmup.to_string(),
+            &row.batch.to_string(),
+            &s.samples.to_string(),
+            &s.outliers.to_string(),
+            &fmt_f64(s.mean_us),
+            &fmt_f64(s.std_us),
+            &fmt_f64(s.min_us),
+            &fmt_f64(s.p50_us),
+            &fmt_f64(s.p95_us),
+            &fmt_f64(s.p99_us),
+            &fmt_f64(s.max_us),
+            OUTPUT_SCHEMA_VERSION,
+        ])?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+pub fn write_solver_comparison_csv(path: &Path, rows: &[SolverComparisonRow]) -> Result<()> {
+    let mut wtr = WriterBuilder::new()
+        .has_headers(false)
+        .from_path(path)
+        .with_context(|| {
+            format!(
+                "failed to open solver_comparison.csv for writing: {}",
+                path.display()
+            )
+        })?;
+
+    wtr.write_record([
+        "seed",
+        "n",
+        "K",
+        "M",
+        "normal_eq_us",
+        "stacked_qr_us",
+        "normal_eq_rms_err",
+        "stacked_qr_rms_err",
+        "max_solution_diff",
+        "max_qr_cond_estimate",
+        "schema_version",
+    ])?;
+
+    for row in rows {
+        wtr.write_record([
+            &row.seed.to_string(),
+            &row.n.to_string(),
+            &row.k.to_string(),
+            &row.m.to_string(),
+            &fmt_f64(row.normal_eq_us),
+            &fmt_f64(row.stacked_qr_us),
+            &fmt_f64(row.normal_eq_rms_err),
+            &fmt_f64(row.stacked_qr_rms_err),
+            &fmt_f64(row.max_solution_diff),
+            &fmt_f64(row.max_qr_cond_estimate),
+            OUTPUT_SCHEMA_VERSION,
+        ])?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+pub fn write_edge_cases_csv(path: &Path, rows: &[EdgeCaseRow]) -> Result<()> {
+    let mut wtr = WriterBuilder::new()
+        .has_headers(false)
+        .from_path(path)
+        .with_context(|| {
+            format!(
+                "failed to open edge_cases.csv for writing: {}",
+                path.display()
+            )
+        })?;
+
+    wtr.write_record([
+        "scenario",
+        "method",
+        "seed",
+        "check",
+        "result",
+        "value",
+        "schema_version",
+    ])?;
+
+    for row in rows {
+        wtr.write_record([
+            row.scenario.as_str(),
+            row.method.as_str(),
+            &row.seed.to_string(),
+            row.check.as_str(),
+            if row.passed { "pass" } else { "fail" },
+            &fmt_f64(row.value),
+            OUTPUT_SCHEMA_VERSION,
+        ])?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+pub fn write_stats_tests_csv(path: &Path, rows: &[StatsTestRow]) -> Result<()> {
+    let mut wtr = WriterBuilder::new()
+        .has_headers(false)
+        .from_path(path)
+        .with_context(|| {
+            format!(
+                "failed to open stats_tests.csv for writing: {}",
+                path.display()
+            )
+        })?;
+
+    wtr.write_record([
+        "method",
+        "reference",
+        "metric",
+        "test",
+        "n_pairs",
+        "n_nonzero",
+        "median_diff",
+        "w_plus",
+        "w_minus",
+        "p_value",
+        "p_value_method",
+        "effect_size_rank_biserial",
+        "schema_version",
+    ])?;
+
+    for row in rows {
+        let test = row.test.as_ref();
+        wtr.write_record([
+            row.method.as_str(),
+            row.reference.as_str(),
+            row.metric.as_str(),
+            "wilcoxon_signed_rank",
+            &row.n_pairs.to_string(),
+            &test.map_or(0, |t| t.n_nonzero).to_string(),
+            &fmt_opt(row.median_diff),
+            &fmt_opt(test.map(|t| t.w_plus)),
+            &fmt_opt(test.map(|t| t.w_minus)),
+            &fmt_opt(test.map(|t| t.p_value)),
+            test.map_or("NA", |t| if t.exact { "exact" } else { "normal" }),
+            &fmt_opt(test.map(|t| t.rank_biserial)),
+            OUTPUT_SCHEMA_VERSION,
+        ])?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
 pub fn write_manifest_json(outdir: &Path, manifest: &Manifest) -> Result<PathBuf> {
     let path = outdir.join("manifest.json");
     let payload = serde_json::to_string_pretty(manifest).context("failed to serialize manifest")?;
@@ -196,3 +1088,33 @@ pub fn write_manifest_json(outdir: &Path, manifest: &Manifest) -> Result<PathBuf
         .with_context(|| format!("failed to write manifest: {}", path.display()))?;
     Ok(path)
 }
+
+/// Checks that the `schema_version` column of `record`, read from `path`
+/// under `header`, is [`OUTPUT_SCHEMA_VERSION`].
+pub fn check_record_schema(
+    header: &StringRecord,
+    record: &StringRecord,
+    path: &Path,
+) -> Result<()> {
+    let version = header
+        .iter()
+        .position(|h| h == "schema_version")
+        .and_then(|i| record.get(i));
+    if version != Some(OUTPUT_SCHEMA_VERSION) {
+        bail!(
+            "{} has a row with schema_version {}, expected {}",
+            path.display(),
+            version.unwrap_or("<missing>"),
+            OUTPUT_SCHEMA_VERSION
+        );
+    }
+    Ok(())
+}
+
+pub fn read_manifest_json(run_dir: &Path) -> Result<Manifest> {
+    let path = run_dir.join("manifest.json");
+    let raw = fs::read_to_string(&path)
+        .with_context(|| format!("failed to read manifest: {}", path.display()))?;
+    serde_json::from_str(&raw)
+        .with_context(|| format!("failed to parse manifest: {}", path.display()))
+}
@@ -1,14 +1,28 @@
 //! Deterministic synthetic benchmarking crate for DSFB fusion diagnostics.
 //!
 //! This library exposes the simulation, method, metric, timing, and output
-//! modules used by the `dsfb-fusion-bench` CLI binary.
+//! modules used by the `dsfb-fusion-bench` CLI binary, and in [`runner`] the
+//! run orchestration the binary wraps.
 
+pub mod aggregate;
+pub mod best_params;
+pub mod checkpoint;
+pub mod edge_cases;
 pub mod io;
 pub mod methods;
 pub mod metrics;
+pub mod perf;
+pub mod repro;
+pub mod runner;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
 pub mod sim {
     pub mod diagnostics;
     pub mod faults;
+    pub mod migrate;
+    pub mod preprocess;
     pub mod state;
 }
+pub mod stats;
+pub mod tables;
 pub mod timing;
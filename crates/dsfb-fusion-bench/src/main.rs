@@ -3,22 +3,16 @@ use clap::Parser;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use dsfb_fusion_bench::aggregate::{aggregate_runs, TagFilter};
+use dsfb_fusion_bench::checkpoint::{read_checkpoint_header, CHECKPOINT_FILE};
 use dsfb_fusion_bench::io::{
-    ensure_outdir, write_heatmap_csv, write_manifest_json, write_summary_csv,
-    write_trajectories_csv, HeatmapRow, Manifest, SummaryRow, TrajectoryRow, OUTPUT_SCHEMA_VERSION,
+    ensure_outdir, read_manifest_json, EdgeCaseRow, OUTPUT_SCHEMA_VERSION,
 };
-use dsfb_fusion_bench::methods::cov_inflate::CovInflateMethod;
-use dsfb_fusion_bench::methods::dsfb::DsfbAdaptiveMethod;
-use dsfb_fusion_bench::methods::equal::EqualMethod;
-use dsfb_fusion_bench::methods::irls_huber::IrlsHuberMethod;
-use dsfb_fusion_bench::methods::nis_gating::{NisGatingMethod, NisMode};
-use dsfb_fusion_bench::methods::{
-    canonical_method_list, solve_group_weighted_wls, ReconstructionMethod, METHOD_ORDER,
-};
-use dsfb_fusion_bench::metrics::{MethodMetrics, MetricsAccumulator};
-use dsfb_fusion_bench::sim::diagnostics::{build_diagnostic_model, DiagnosticModel};
-use dsfb_fusion_bench::sim::state::{generate_simulation_data, BenchConfig, SimulationData};
-use dsfb_fusion_bench::timing::TimingAccumulator;
+use dsfb_fusion_bench::perf::{compare_timings, read_summary_timings, PerfThresholds};
+use dsfb_fusion_bench::repro::{compare_output_hashes, output_hashes};
+use dsfb_fusion_bench::runner::{parse_methods, BenchRunner, RunMode, RunResults};
+use dsfb_fusion_bench::sim::migrate::CONFIG_SCHEMA_VERSION;
+use dsfb_fusion_bench::sim::state::BenchConfig;
 
 #[derive(Debug, Parser)]
 #[command(name = "dsfb-fusion-bench")]
@@ -39,19 +33,73 @@ struct Cli {
     #[arg(long, default_value_t = false)]
     run_sweep: bool,
 
+    /// Run the weighted methods with the corruption impulse and faults removed, for false-alarm rates
+    #[arg(long, default_value_t = false)]
+    run_null: bool,
+
+    /// Run the degenerate/edge-case scenario pack and report pass/fail checks
+    #[arg(long, default_value_t = false)]
+    run_edge_cases: bool,
+
+    /// Print the fully resolved config, after includes, schema upgrades, --seed, and --methods, then exit
+    #[arg(long, default_value_t = false)]
+    print_config: bool,
+
+    /// Micro-benchmark each method's per-step estimate against the equal-weight WLS solve
+    #[arg(long, default_value_t = false)]
+    bench_solver: bool,
+
     #[arg(long)]
     methods: Option<String>,
-}
 
-#[derive(Debug, Clone)]
-struct MethodRunResult {
-    summary: SummaryRow,
-    metrics: MethodMetrics,
-    trajectories: Vec<TrajectoryRow>,
+    /// Also write the mean ± std summary per method as tables.tex and tables.md
+    #[arg(long, default_value_t = false)]
+    emit_tables: bool,
+
+    /// Worker threads for --run-sweep grid points and seeds (0 uses all cores)
+    #[arg(long, default_value_t = 1)]
+    jobs: usize,
+
+    /// Re-execute the run recorded in <RUN_DIR>/manifest.json and verify its output hashes
+    #[arg(long, value_name = "RUN_DIR")]
+    rerun: Option<PathBuf>,
+
+    /// Finish the interrupted --run-sweep in <RUN_DIR> from its sweep_checkpoint.jsonl
+    #[arg(long, value_name = "RUN_DIR")]
+    resume: Option<PathBuf>,
+
+    /// Fail if timings regress against this previous summary.csv (or run directory)
+    #[arg(long, value_name = "FILE")]
+    perf_baseline: Option<PathBuf>,
+
+    /// Allowed baseline_wls_us regression against --perf-baseline, in percent
+    #[arg(long, default_value_t = 25.0)]
+    perf_max_wls_regression_pct: f64,
+
+    /// Allowed overhead_us regression against --perf-baseline, in percent
+    #[arg(long, default_value_t = 25.0)]
+    perf_max_overhead_regression_pct: f64,
+
+    /// Combine the summary and heatmap CSVs of these run directories
+    #[arg(long, value_name = "RUN_DIR", num_args = 1..)]
+    aggregate: Vec<PathBuf>,
+
+    /// With --aggregate, keep only runs carrying this tag (repeatable; any match)
+    #[arg(long = "include-tag", value_name = "TAG")]
+    include_tags: Vec<String>,
+
+    /// With --aggregate, drop runs carrying this tag (repeatable)
+    #[arg(long = "exclude-tag", value_name = "TAG")]
+    exclude_tags: Vec<String>,
+
+    /// Also record the run's manifest, summary, heatmap, and trajectories in this SQLite database
+    #[cfg(feature = "sqlite")]
+    #[arg(long, value_name = "FILE")]
+    sqlite: Option<PathBuf>,
 }
 
-fn resolve_default_config_path(run_default: bool) -> PathBuf {
-    let file = if run_default {
+fn resolve_default_config_path(use_default: bool) -> PathBuf {
+    let file = if use_default {
         "default.toml"
     } else {
         "sweep.toml"
@@ -105,320 +153,303 @@ fn resolve_run_output_dir(base_outdir: &Path) -> Result<PathBuf> {
     Ok(candidate)
 }
 
-fn parse_methods(cli_methods: Option<&str>, cfg: &BenchConfig) -> Result<Vec<String>> {
-    let requested: Vec<String> = if let Some(raw) = cli_methods {
-        raw.split(',')
-            .map(|m| m.trim().to_lowercase())
-            .filter(|m| !m.is_empty())
-            .collect()
-    } else if !cfg.methods.is_empty() {
-        cfg.methods.iter().map(|m| m.to_lowercase()).collect()
-    } else {
-        METHOD_ORDER.iter().map(|m| m.to_string()).collect()
-    };
-
-    if requested.is_empty() {
-        bail!("methods list cannot be empty");
-    }
-
-    for m in &requested {
-        if !METHOD_ORDER.contains(&m.as_str()) {
-            bail!(
-                "unknown method '{m}'. valid methods: {}",
-                METHOD_ORDER.join(",")
+/// Prints the console summary of `results`.
+fn report(results: &RunResults) {
+    match results {
+        RunResults::Default(results) => {
+            for row in &results.solver_comparison {
+                println!(
+                    "seed {}: normal equations {:.3} us, stacked QR {:.3} us, rms_err {:.6} vs {:.6}, max |dx| {:.3e}, cond(R) >= {:.3e}",
+                    row.seed,
+                    row.normal_eq_us,
+                    row.stacked_qr_us,
+                    row.normal_eq_rms_err,
+                    row.stacked_qr_rms_err,
+                    row.max_solution_diff,
+                    row.max_qr_cond_estimate
+                );
+            }
+        }
+        RunResults::Sweep(results) => {
+            if results.resumed_runs > 0 {
+                println!(
+                    "resumed sweep: {} run(s) reused from {CHECKPOINT_FILE}",
+                    results.resumed_runs
+                );
+            }
+        }
+        RunResults::Null(_) => {}
+        RunResults::EdgeCases(rows) => {
+            let failed: Vec<&EdgeCaseRow> = rows.iter().filter(|r| !r.passed).collect();
+            println!(
+                "edge cases: {}/{} checks passed",
+                rows.len() - failed.len(),
+                rows.len()
             );
+            for row in failed {
+                println!(
+                    "FAIL {} {} seed={} {} (value {})",
+                    row.scenario, row.method, row.seed, row.check, row.value
+                );
+            }
+        }
+        RunResults::BenchSolver(rows) => {
+            for row in rows {
+                let s = &row.stats;
+                println!(
+                    "{}: p50 {:.3} us, p95 {:.3} us, p99 {:.3} us, mean {:.3} +/- {:.3} us ({} samples, {} outliers rejected)",
+                    row.method, s.p50_us, s.p95_us, s.p99_us, s.mean_us, s.std_us, s.samples, s.outliers
+                );
+            }
         }
     }
-
-    Ok(canonical_method_list(&requested))
 }
 
-fn build_method(name: &str) -> Result<Box<dyn ReconstructionMethod>> {
-    let method: Box<dyn ReconstructionMethod> = match name {
-        "equal" => Box::new(EqualMethod),
-        "cov_inflate" => Box::new(CovInflateMethod::new()),
-        "irls_huber" => Box::new(IrlsHuberMethod::new()),
-        "nis_hard" => Box::new(NisGatingMethod::new(NisMode::Hard)),
-        "nis_soft" => Box::new(NisGatingMethod::new(NisMode::Soft)),
-        "dsfb" => Box::new(DsfbAdaptiveMethod::new()),
-        _ => bail!("unsupported method: {name}"),
+fn rerun(run_dir: &Path, base_outdir: &Path, jobs: usize) -> Result<()> {
+    let original = read_manifest_json(run_dir)?;
+    if original.schema_version != OUTPUT_SCHEMA_VERSION {
+        bail!(
+            "manifest schema_version {} does not match output schema {}",
+            original.schema_version,
+            OUTPUT_SCHEMA_VERSION
+        );
+    }
+    let cfg = original.config.clone().with_context(|| {
+        format!(
+            "manifest in {} does not embed the resolved config; rerun requires a manifest written by this version",
+            run_dir.display()
+        )
+    })?;
+
+    // Manifests without recorded hashes are verified against the files on disk.
+    let expected = if original.output_hashes.is_empty() {
+        output_hashes(run_dir)?
+    } else {
+        original.output_hashes.clone()
     };
-    Ok(method)
-}
-
-fn baseline_wls_us(model: &DiagnosticModel, data: &SimulationData) -> f64 {
-    let mut acc = TimingAccumulator::default();
-    let weights = vec![1.0; model.groups.len()];
 
-    for frame in &data.measurements {
-        let (_x, solve_time) = solve_group_weighted_wls(model, &frame.y_groups, &weights);
-        acc.observe(solve_time, solve_time);
-    }
+    let run_outdir = resolve_run_output_dir(base_outdir)?;
+    let (manifest, results) = BenchRunner::new(cfg)?
+        .with_methods(&original.methods)?
+        .with_jobs(jobs)
+        .execute(
+            RunMode::from_name(&original.mode)?,
+            &run_outdir,
+            original.emit_tables,
+            Some(run_dir),
+        )?;
+    report(&results);
 
-    acc.avg_solve_us()
-}
+    println!("wrote outputs to {}", run_outdir.display());
 
-fn run_method(
-    method_name: &str,
-    cfg: &BenchConfig,
-    model: &DiagnosticModel,
-    data: &SimulationData,
-    seed: u64,
-    baseline_us: f64,
-    alpha_beta: Option<(f64, f64)>,
-    keep_trajectories: bool,
-) -> Result<MethodRunResult> {
-    let mut method = build_method(method_name)?;
-    method.reset(cfg, model);
-
-    let mut metrics_acc = MetricsAccumulator::new(method.has_weights());
-    let mut timing_acc = TimingAccumulator::default();
-    let mut trajectories = Vec::with_capacity(data.t.len());
-
-    for step in 0..data.t.len() {
-        let out = method.estimate(model, &data.measurements[step].y_groups);
-        let err_norm = (&out.x_hat - &data.x_true[step]).norm();
-
-        metrics_acc.observe(
-            err_norm,
-            out.group_weights.as_deref(),
-            data.corruption_active[step],
+    let divergences = compare_output_hashes(&expected, &manifest.output_hashes);
+    if divergences.is_empty() {
+        println!(
+            "reproduced {} output file(s) from {} bit-for-bit (timing and tag columns excluded)",
+            expected.len(),
+            run_dir.display()
         );
-        timing_acc.observe(out.solve_time, out.total_time);
-
-        if keep_trajectories {
-            trajectories.push(TrajectoryRow {
-                t: data.t[step],
-                method: method.name().to_string(),
-                err_norm,
-                weights: out.group_weights,
-            });
-        }
+        return Ok(());
     }
 
-    let metrics = metrics_acc.finalize();
-    let total_us = timing_acc.avg_total_us();
-    let overhead_us = (total_us - baseline_us).max(0.0);
-
-    let summary = SummaryRow {
-        method: method.name().to_string(),
-        seed,
-        n: cfg.n,
-        k: cfg.group_count(),
-        m: cfg.total_measurements(),
-        peak_err: metrics.peak_err,
-        rms_err: metrics.rms_err,
-        false_downweight_rate: metrics.false_downweight_rate,
-        baseline_wls_us: baseline_us,
-        overhead_us,
-        total_us,
-        alpha: alpha_beta.map(|v| v.0),
-        beta: alpha_beta.map(|v| v.1),
-    };
+    for d in &divergences {
+        println!(
+            "DIVERGED {}: expected {} got {}",
+            d.file,
+            d.expected.as_deref().unwrap_or("<missing>"),
+            d.actual.as_deref().unwrap_or("<missing>")
+        );
+    }
+    bail!(
+        "rerun of {} diverged in {} output file(s)",
+        run_dir.display(),
+        divergences.len()
+    );
+}
 
-    Ok(MethodRunResult {
-        summary,
-        metrics,
-        trajectories,
-    })
+/// Finishes the sweep checkpointed in `run_dir`, writing its outputs there.
+fn resume(run_dir: &Path, jobs: usize) -> Result<()> {
+    let header = read_checkpoint_header(run_dir)?;
+    let (_, results) = BenchRunner::new(header.config)?
+        .with_methods(&header.methods)?
+        .with_jobs(jobs)
+        .execute(RunMode::Sweep, run_dir, header.emit_tables, None)?;
+    report(&results);
+    println!("resumed sweep in {}", run_dir.display());
+    Ok(())
 }
 
-fn run_default(cfg: &BenchConfig, methods: &[String], outdir: &Path) -> Result<()> {
-    let model = build_diagnostic_model(cfg)?;
-
-    let mut summary_rows = Vec::<SummaryRow>::new();
-    let mut trajectory_rows = Vec::<TrajectoryRow>::new();
-
-    let mut seeds = cfg.seeds.clone();
-    seeds.sort_unstable();
-
-    for seed in seeds {
-        let data = generate_simulation_data(cfg, &model, seed)?;
-        let baseline_us = baseline_wls_us(&model, &data);
-
-        for method_name in methods {
-            let result = run_method(
-                method_name,
-                cfg,
-                &model,
-                &data,
-                seed,
-                baseline_us,
-                Some((cfg.dsfb_alpha, cfg.dsfb_beta)),
-                true,
-            )?;
-            summary_rows.push(result.summary);
-            trajectory_rows.extend(result.trajectories);
-        }
+/// Compares the run's `summary.csv` timings with `baseline` and fails on regression.
+fn check_perf_baseline(baseline: &Path, outdir: &Path, thresholds: PerfThresholds) -> Result<()> {
+    let baseline_path = if baseline.is_dir() {
+        baseline.join("summary.csv")
+    } else {
+        baseline.to_path_buf()
+    };
+    let expected = read_summary_timings(&baseline_path)?;
+    let current = read_summary_timings(&outdir.join("summary.csv"))?;
+    let comparisons = compare_timings(&expected, &current, thresholds);
+
+    for c in &comparisons {
+        let change = c
+            .change_pct
+            .map_or_else(|| "n/a".to_string(), |pct| format!("{pct:+.1}%"));
+        println!(
+            "{} {} {}: baseline {:.3} us, current {:.3} us ({change}, limit +{:.1}%)",
+            if c.regressed { "REGRESSED" } else { "ok" },
+            c.method,
+            c.metric,
+            c.baseline_us,
+            c.current_us,
+            c.limit_pct
+        );
     }
 
-    let summary_path = outdir.join("summary.csv");
-    let heatmap_path = outdir.join("heatmap.csv");
-    let traj_path = outdir.join("trajectories.csv");
-    let sim_path = outdir.join("sim-dsfb-fusion-bench.csv");
-
-    write_summary_csv(&summary_path, &summary_rows)?;
-    write_heatmap_csv(&heatmap_path, &[])?;
-    write_trajectories_csv(&traj_path, &trajectory_rows, cfg.group_count())?;
-    write_trajectories_csv(&sim_path, &trajectory_rows, cfg.group_count())?;
-
-    write_manifest_json(
-        outdir,
-        &Manifest {
-            schema_version: OUTPUT_SCHEMA_VERSION.to_string(),
-            mode: "default".to_string(),
-            methods: methods.to_vec(),
-            seeds: cfg.seeds.clone(),
-            note: "Deterministic synthetic benchmark outputs".to_string(),
-        },
-    )?;
-
+    let regressed = comparisons.iter().filter(|c| c.regressed).count();
+    if regressed > 0 {
+        bail!(
+            "{regressed} timing(s) regressed against {}",
+            baseline_path.display()
+        );
+    }
     Ok(())
 }
 
-#[derive(Debug, Default, Clone)]
-struct HeatAgg {
-    peak_sum: f64,
-    rms_sum: f64,
-    false_sum: f64,
-    false_count: usize,
-    count: usize,
+fn aggregate(cli: &Cli) -> Result<()> {
+    let filter = TagFilter {
+        include: cli.include_tags.clone(),
+        exclude: cli.exclude_tags.clone(),
+    };
+    let run_outdir = resolve_run_output_dir(&cli.outdir)?;
+    let manifest = aggregate_runs(&cli.aggregate, &filter, &run_outdir)?;
+
+    println!(
+        "aggregated {} of {} runs into {}",
+        manifest.runs.len(),
+        manifest.runs.len() + manifest.skipped.len(),
+        run_outdir.display()
+    );
+    for (file, rows) in &manifest.files {
+        println!("{file}: {rows} rows");
+    }
+    Ok(())
 }
 
-fn run_sweep(cfg: &BenchConfig, methods: &[String], outdir: &Path) -> Result<()> {
-    let alpha_values = cfg
-        .alpha_values
-        .clone()
-        .context("sweep requires alpha_values in config")?;
-    let beta_values = cfg
-        .beta_values
-        .clone()
-        .context("sweep requires beta_values in config")?;
-
-    if alpha_values.is_empty() || beta_values.is_empty() {
-        bail!("alpha_values and beta_values must be non-empty for sweep");
+/// Records `run_dir` in the `--sqlite` database and reports the row counts.
+#[cfg(feature = "sqlite")]
+fn record_sqlite(db_path: &Path, run_dir: &Path) -> Result<()> {
+    let record = dsfb_fusion_bench::sqlite::record_run(db_path, run_dir)?;
+    println!("recorded run {} in {}", record.run_id, db_path.display());
+    for (table, rows) in &record.tables {
+        println!("{table}: {rows} rows");
     }
+    Ok(())
+}
 
-    let mut alphas = alpha_values;
-    let mut betas = beta_values;
-    alphas.sort_by(|a, b| a.total_cmp(b));
-    betas.sort_by(|a, b| a.total_cmp(b));
-
-    let mut seeds = cfg.seeds.clone();
-    seeds.sort_unstable();
-
-    let mut summary_rows = Vec::<SummaryRow>::new();
-    let mut heatmap_rows = Vec::<HeatmapRow>::new();
-
-    for alpha in &alphas {
-        for beta in &betas {
-            let mut cfg_ab = cfg.clone();
-            cfg_ab.dsfb_alpha = *alpha;
-            cfg_ab.dsfb_beta = *beta;
-
-            let model = build_diagnostic_model(&cfg_ab)?;
-            let mut aggs = vec![HeatAgg::default(); methods.len()];
-
-            for seed in &seeds {
-                let data = generate_simulation_data(&cfg_ab, &model, *seed)?;
-                let baseline_us = baseline_wls_us(&model, &data);
-
-                for (idx, method_name) in methods.iter().enumerate() {
-                    let result = run_method(
-                        method_name,
-                        &cfg_ab,
-                        &model,
-                        &data,
-                        *seed,
-                        baseline_us,
-                        Some((*alpha, *beta)),
-                        false,
-                    )?;
-
-                    summary_rows.push(result.summary.clone());
-
-                    aggs[idx].peak_sum += result.metrics.peak_err;
-                    aggs[idx].rms_sum += result.metrics.rms_err;
-                    if let Some(v) = result.metrics.false_downweight_rate {
-                        aggs[idx].false_sum += v;
-                        aggs[idx].false_count += 1;
-                    }
-                    aggs[idx].count += 1;
-                }
-            }
+fn main() -> Result<()> {
+    let cli = Cli::parse();
 
-            for (idx, method_name) in methods.iter().enumerate() {
-                let agg = &aggs[idx];
-                if agg.count == 0 {
-                    continue;
-                }
-                heatmap_rows.push(HeatmapRow {
-                    alpha: *alpha,
-                    beta: *beta,
-                    method: method_name.clone(),
-                    peak_err: agg.peak_sum / agg.count as f64,
-                    rms_err: agg.rms_sum / agg.count as f64,
-                    false_downweight_rate: if agg.false_count > 0 {
-                        Some(agg.false_sum / agg.false_count as f64)
-                    } else {
-                        None
-                    },
-                });
-            }
-        }
+    #[cfg(feature = "sqlite")]
+    if cli.sqlite.is_some()
+        && (!cli.aggregate.is_empty() || cli.rerun.is_some() || cli.resume.is_some())
+    {
+        bail!("--sqlite records --run-* outputs; do not combine it with --aggregate, --rerun, or --resume");
     }
 
-    let summary_path = outdir.join("summary_sweep.csv");
-    let heatmap_path = outdir.join("heatmap.csv");
-    let default_summary_path = outdir.join("summary.csv");
-    let traj_path = outdir.join("trajectories.csv");
-    let sim_path = outdir.join("sim-dsfb-fusion-bench.csv");
-
-    write_summary_csv(&summary_path, &summary_rows)?;
-    if !default_summary_path.exists() {
-        write_summary_csv(&default_summary_path, &summary_rows)?;
+    if cli.print_config
+        && (!cli.aggregate.is_empty() || cli.rerun.is_some() || cli.resume.is_some())
+    {
+        bail!("--print-config resolves a config file; do not combine it with --aggregate, --rerun, or --resume");
     }
-    write_heatmap_csv(&heatmap_path, &heatmap_rows)?;
-    if !traj_path.exists() {
-        write_trajectories_csv(&traj_path, &[], cfg.group_count())?;
+    if !(cli.include_tags.is_empty() && cli.exclude_tags.is_empty()) && cli.aggregate.is_empty() {
+        bail!("--include-tag and --exclude-tag require --aggregate");
     }
-    if !sim_path.exists() {
-        write_trajectories_csv(&sim_path, &[], cfg.group_count())?;
+    if !cli.aggregate.is_empty() {
+        if cli.run_default
+            || cli.run_sweep
+            || cli.run_null
+            || cli.run_edge_cases
+            || cli.bench_solver
+            || cli.rerun.is_some()
+            || cli.resume.is_some()
+            || cli.perf_baseline.is_some()
+        {
+            bail!("--aggregate combines existing runs; do not combine it with --run-*, --bench-solver, --rerun, --resume, or --perf-baseline");
+        }
+        return aggregate(&cli);
     }
 
-    write_manifest_json(
-        outdir,
-        &Manifest {
-            schema_version: OUTPUT_SCHEMA_VERSION.to_string(),
-            mode: "sweep".to_string(),
-            methods: methods.to_vec(),
-            seeds: cfg.seeds.clone(),
-            note: "Deterministic synthetic benchmark outputs with alpha/beta sweep".to_string(),
-        },
-    )?;
-
-    Ok(())
-}
+    if let Some(run_dir) = &cli.rerun {
+        if cli.run_default
+            || cli.run_sweep
+            || cli.run_null
+            || cli.run_edge_cases
+            || cli.bench_solver
+            || cli.config.is_some()
+        {
+            bail!("--rerun takes its mode and config from the manifest; do not combine it with --run-*, --bench-solver, or --config");
+        }
+        if cli.seed.is_some() || cli.methods.is_some() || cli.emit_tables {
+            bail!("--rerun takes seeds, methods, and table options from the manifest");
+        }
+        if cli.perf_baseline.is_some() {
+            bail!("--perf-baseline cannot be combined with --rerun");
+        }
+        if cli.resume.is_some() {
+            bail!("--rerun and --resume are mutually exclusive");
+        }
+        return rerun(run_dir, &cli.outdir, cli.jobs);
+    }
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+    if let Some(run_dir) = &cli.resume {
+        if cli.run_default
+            || cli.run_sweep
+            || cli.run_null
+            || cli.run_edge_cases
+            || cli.bench_solver
+            || cli.config.is_some()
+        {
+            bail!("--resume takes its config from the checkpoint; do not combine it with --run-*, --bench-solver, or --config");
+        }
+        if cli.seed.is_some() || cli.methods.is_some() || cli.emit_tables {
+            bail!("--resume takes seeds, methods, and table options from the checkpoint");
+        }
+        if cli.perf_baseline.is_some() {
+            bail!("--perf-baseline cannot be combined with --resume");
+        }
+        return resume(run_dir, cli.jobs);
+    }
 
-    if cli.run_default == cli.run_sweep {
-        bail!("choose exactly one of --run-default or --run-sweep");
+    let modes = [
+        cli.run_default,
+        cli.run_sweep,
+        cli.run_null,
+        cli.run_edge_cases,
+        cli.bench_solver,
+    ];
+    let mode_count = modes.iter().filter(|&&m| m).count();
+    if mode_count > 1 || (mode_count == 0 && !cli.print_config) {
+        bail!("choose exactly one of --run-default, --run-sweep, --run-null, --run-edge-cases, or --bench-solver");
+    }
+    if cli.perf_baseline.is_some() && !(cli.run_default || cli.run_sweep) {
+        bail!("--perf-baseline requires --run-default or --run-sweep");
+    }
+    if !(cli.perf_max_wls_regression_pct >= 0.0 && cli.perf_max_overhead_regression_pct >= 0.0) {
+        bail!("--perf-max-*-regression-pct must be non-negative");
     }
 
     let config_path = if let Some(path) = cli.config.clone() {
         path
     } else {
-        resolve_default_config_path(cli.run_default)
+        resolve_default_config_path(!cli.run_sweep)
     };
 
     let mut cfg = BenchConfig::from_toml_file(&config_path)?;
-    if cfg.schema_version != OUTPUT_SCHEMA_VERSION {
+    if cfg.schema_version != CONFIG_SCHEMA_VERSION {
         bail!(
-            "config schema_version {} does not match output schema {}",
+            "config schema_version {} does not match config schema {}",
             cfg.schema_version,
-            OUTPUT_SCHEMA_VERSION
+            CONFIG_SCHEMA_VERSION
         );
     }
 
@@ -427,14 +458,49 @@ fn main() -> Result<()> {
     }
 
     let methods = parse_methods(cli.methods.as_deref(), &cfg)?;
+    if cli.print_config {
+        cfg.methods = methods;
+        print!(
+            "{}",
+            toml::to_string(&cfg).context("failed to serialize the resolved config")?
+        );
+        return Ok(());
+    }
     let run_outdir = resolve_run_output_dir(&cli.outdir)?;
 
-    if cli.run_default {
-        run_default(&cfg, &methods, &run_outdir)?;
+    let mode = if cli.run_default {
+        RunMode::Default
+    } else if cli.run_null {
+        RunMode::Null
+    } else if cli.run_edge_cases {
+        RunMode::EdgeCases
+    } else if cli.bench_solver {
+        RunMode::BenchSolver
     } else {
-        run_sweep(&cfg, &methods, &run_outdir)?;
-    }
+        RunMode::Sweep
+    };
+    let (_, results) = BenchRunner::new(cfg)?
+        .with_methods(&methods)?
+        .with_jobs(cli.jobs)
+        .execute(mode, &run_outdir, cli.emit_tables, None)?;
+    report(&results);
 
     println!("wrote outputs to {}", run_outdir.display());
+
+    #[cfg(feature = "sqlite")]
+    if let Some(db_path) = &cli.sqlite {
+        record_sqlite(db_path, &run_outdir)?;
+    }
+
+    if let Some(baseline) = &cli.perf_baseline {
+        check_perf_baseline(
+            baseline,
+            &run_outdir,
+            PerfThresholds {
+                baseline_wls_pct: cli.perf_max_wls_regression_pct,
+                overhead_pct: cli.perf_max_overhead_regression_pct,
+            },
+        )?;
+    }
     Ok(())
 }
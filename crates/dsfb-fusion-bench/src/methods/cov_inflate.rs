@@ -43,6 +43,7 @@ impl ReconstructionMethod for CovInflateMethod {
             group_weights: Some(self.weights.clone()),
             solve_time,
             total_time: total_t0.elapsed(),
+            solver: None,
         }
     }
 }
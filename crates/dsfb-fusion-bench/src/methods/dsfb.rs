@@ -65,6 +65,7 @@ impl ReconstructionMethod for DsfbAdaptiveMethod {
             group_weights: Some(weights),
             solve_time: solve_0 + solve_1,
             total_time: total_t0.elapsed(),
+            solver: None,
         }
     }
 }
@@ -26,6 +26,7 @@ impl ReconstructionMethod for EqualMethod {
             group_weights: None,
             solve_time,
             total_time: total_t0.elapsed(),
+            solver: None,
         }
     }
 }
@@ -4,7 +4,7 @@ use nalgebra::DVector;
 
 use crate::methods::{
     solve_group_weighted_wls, solve_measurement_weighted_wls, MethodStepResult,
-    ReconstructionMethod,
+    ReconstructionMethod, SolverIterations,
 };
 use crate::sim::diagnostics::DiagnosticModel;
 use crate::sim::state::BenchConfig;
@@ -46,16 +46,19 @@ impl ReconstructionMethod for IrlsHuberMethod {
         let (mut x_hat, mut solve_time) =
             solve_group_weighted_wls(model, y_groups, &vec![1.0; model.groups.len()]);
 
+        let mut solver = SolverIterations {
+            iterations: 0,
+            converged: false,
+        };
         for _ in 0..self.max_iter {
             let mut measurement_weights: Vec<Vec<f64>> = Vec::with_capacity(model.groups.len());
 
             for (k, group) in model.groups.iter().enumerate() {
-                let residual = &y_groups[k] - &group.h * &x_hat;
+                // Whitened, so correlated groups are reweighted per whitened row
+                let z = group.whiten(&group.residual(&y_groups[k], &x_hat));
                 let mut w_k = vec![1.0; group.dim()];
                 for i in 0..group.dim() {
-                    let sigma = group.r_diag[i].sqrt().max(1e-12);
-                    let z = residual[i] / sigma;
-                    let abs_z = z.abs();
+                    let abs_z = z[i].abs();
                     w_k[i] = if abs_z <= self.delta {
                         1.0
                     } else {
@@ -70,9 +73,11 @@ impl ReconstructionMethod for IrlsHuberMethod {
                 solve_measurement_weighted_wls(model, y_groups, &measurement_weights);
             solve_time += this_solve;
             x_hat = new_x;
+            solver.iterations += 1;
 
             let dx = (&x_hat - prev).norm();
             if dx < self.tol {
+                solver.converged = true;
                 break;
             }
         }
@@ -82,6 +87,7 @@ impl ReconstructionMethod for IrlsHuberMethod {
             group_weights: None,
             solve_time,
             total_time: total_t0.elapsed(),
+            solver: Some(solver),
         }
     }
 }
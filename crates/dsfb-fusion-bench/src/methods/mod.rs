@@ -3,29 +3,46 @@ use std::time::{Duration, Instant};
 use nalgebra::{DMatrix, DVector};
 
 use crate::sim::diagnostics::DiagnosticModel;
-use crate::sim::state::BenchConfig;
+use crate::sim::state::{BenchConfig, WlsSummation};
 
 pub mod cov_inflate;
 pub mod dsfb;
+pub mod dsfb_core;
 pub mod equal;
 pub mod irls_huber;
 pub mod nis_gating;
+pub mod ukf;
+pub mod vb_adaptive_r;
 
-pub const METHOD_ORDER: [&str; 6] = [
+pub const METHOD_ORDER: [&str; 9] = [
     "equal",
     "cov_inflate",
     "irls_huber",
     "nis_hard",
     "nis_soft",
     "dsfb",
+    "dsfb_core",
+    "ukf",
+    "vb_adaptive_r",
 ];
 
+/// Outcome of an iterative solver for one step.
+#[derive(Debug, Clone, Copy)]
+pub struct SolverIterations {
+    /// Reweighting iterations run after the initial solve
+    pub iterations: usize,
+    /// Whether the update norm fell below the tolerance within `max_iter`
+    pub converged: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct MethodStepResult {
     pub x_hat: DVector<f64>,
     pub group_weights: Option<Vec<f64>>,
     pub solve_time: Duration,
     pub total_time: Duration,
+    /// `None` for closed-form methods
+    pub solver: Option<SolverIterations>,
 }
 
 pub trait ReconstructionMethod {
@@ -45,17 +62,210 @@ fn solve_normal_equation(normal: DMatrix<f64>, rhs: DVector<f64>) -> DVector<f64
     DVector::<f64>::zeros(rhs.nrows())
 }
 
+/// Normal equations `(H^T W H) x = H^T W y`, accumulated one measurement row
+/// at a time with the model's summation mode.
+struct NormalEquations {
+    normal: DMatrix<f64>,
+    rhs: DVector<f64>,
+    /// Neumaier error terms; only used with [`WlsSummation::Compensated`]
+    normal_err: DMatrix<f64>,
+    rhs_err: DVector<f64>,
+    summation: WlsSummation,
+}
+
+impl NormalEquations {
+    fn new(n: usize, summation: WlsSummation) -> Self {
+        let (err_rows, err_cols) = match summation {
+            WlsSummation::Naive => (0, 0),
+            WlsSummation::Compensated => (n, n),
+        };
+        Self {
+            normal: DMatrix::<f64>::identity(n, n) * 1e-9,
+            rhs: DVector::<f64>::zeros(n),
+            normal_err: DMatrix::<f64>::zeros(err_rows, err_cols),
+            rhs_err: DVector::<f64>::zeros(err_rows),
+            summation,
+        }
+    }
+
+    fn add_row(&mut self, row: &[f64], inv_var: f64, yi: f64) {
+        match self.summation {
+            WlsSummation::Naive => {
+                for (a, &ha) in row.iter().enumerate() {
+                    self.rhs[a] += inv_var * ha * yi;
+                    for (b, &hb) in row.iter().enumerate() {
+                        self.normal[(a, b)] += inv_var * ha * hb;
+                    }
+                }
+            }
+            WlsSummation::Compensated => {
+                for (a, &ha) in row.iter().enumerate() {
+                    neumaier_add(&mut self.rhs[a], &mut self.rhs_err[a], inv_var * ha * yi);
+                    for (b, &hb) in row.iter().enumerate() {
+                        neumaier_add(
+                            &mut self.normal[(a, b)],
+                            &mut self.normal_err[(a, b)],
+                            inv_var * ha * hb,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn solve(self) -> DVector<f64> {
+        match self.summation {
+            WlsSummation::Naive => solve_normal_equation(self.normal, self.rhs),
+            WlsSummation::Compensated => {
+                solve_normal_equation(self.normal + self.normal_err, self.rhs + self.rhs_err)
+            }
+        }
+    }
+}
+
+/// Adds `term` to `sum`, collecting the rounding error in `err` (Neumaier).
+fn neumaier_add(sum: &mut f64, err: &mut f64, term: f64) {
+    let next = *sum + term;
+    if sum.abs() >= term.abs() {
+        *err += (*sum - next) + term;
+    } else {
+        *err += (term - next) + *sum;
+    }
+    *sum = next;
+}
+
+/// Gauss-Newton iteration cap of the WLS solvers on models with nonlinear
+/// groups.
+const GAUSS_NEWTON_MAX_ITER: usize = 20;
+/// Gauss-Newton stops once an update is smaller than this, relative to
+/// `1 + |x|`.
+const GAUSS_NEWTON_TOL: f64 = 1e-10;
+
+/// Assembles and solves the WLS normal equations with measurement `i` of group
+/// `k` weighted by `weight(k, i)`; rows with weight 0 are left out.
+///
+/// Nonlinear groups are linearized at `x_lin` as `J x = y - h(x_lin) + J x_lin`
+/// with `J` the Jacobian at `x_lin`, or left out when `x_lin` is `None`.
+/// Groups with correlated noise are whitened by `L^-1` first, so their weights
+/// apply to whitened rows with unit variance.
+fn solve_linearized(
+    model: &DiagnosticModel,
+    y_groups: &[DVector<f64>],
+    weight: &impl Fn(usize, usize) -> f64,
+    x_lin: Option<&DVector<f64>>,
+) -> DVector<f64> {
+    let mut equations = NormalEquations::new(model.n, model.summation);
+    let mut row = vec![0.0; model.n];
+
+    for (k, group) in model.groups.iter().enumerate() {
+        let y = &y_groups[k];
+        let mut linearized = match (group.is_nonlinear(), x_lin) {
+            (false, _) => None,
+            (true, None) => continue,
+            (true, Some(x)) => {
+                let jac = group.jacobian(x);
+                let target = group.residual(y, x) + &jac * x;
+                Some((jac, target))
+            }
+        };
+        if let Some(whitening) = &group.whitening {
+            let (h, target) = linearized.unwrap_or_else(|| (group.h.clone(), y.clone()));
+            linearized = Some((whitening * h, whitening * target));
+        }
+
+        for i in 0..group.dim() {
+            let w = weight(k, i).max(0.0);
+            if w <= 0.0 {
+                continue;
+            }
+
+            let var = if group.is_correlated() {
+                1.0
+            } else {
+                group.r_diag[i].max(1e-12)
+            };
+            let (h_row, yi) = match &linearized {
+                Some((jac, target)) => (jac.row(i), target[i]),
+                None => (group.h.row(i), y[i]),
+            };
+            row.iter_mut()
+                .zip(h_row.iter())
+                .for_each(|(dst, &h)| *dst = h);
+            equations.add_row(&row, w / var, yi);
+        }
+    }
+
+    equations.solve()
+}
+
+/// WLS estimate with per-measurement weights `weight(k, i)`.
+///
+/// A linear model is solved once. With nonlinear groups, the solve is
+/// Gauss-Newton: it starts from the estimate of the linear groups alone and
+/// relinearizes the nonlinear groups at each new estimate until the update
+/// falls below [`GAUSS_NEWTON_TOL`] or [`GAUSS_NEWTON_MAX_ITER`] is reached.
+fn solve_wls(
+    model: &DiagnosticModel,
+    y_groups: &[DVector<f64>],
+    weight: impl Fn(usize, usize) -> f64,
+) -> DVector<f64> {
+    let mut x = solve_linearized(model, y_groups, &weight, None);
+    if !model.groups.iter().any(|g| g.is_nonlinear()) {
+        return x;
+    }
+
+    for _ in 0..GAUSS_NEWTON_MAX_ITER {
+        let next = solve_linearized(model, y_groups, &weight, Some(&x));
+        if next.iter().any(|v| !v.is_finite()) {
+            break;
+        }
+        let step = (&next - &x).norm();
+        x = next;
+        if step <= GAUSS_NEWTON_TOL * (1.0 + x.norm()) {
+            break;
+        }
+    }
+    x
+}
+
 pub fn solve_group_weighted_wls(
     model: &DiagnosticModel,
     y_groups: &[DVector<f64>],
     group_weights: &[f64],
 ) -> (DVector<f64>, Duration) {
     let t0 = Instant::now();
-    let n = model.n;
+    let x = solve_wls(model, y_groups, |k, _| group_weights[k]);
+    (x, t0.elapsed())
+}
 
-    let mut normal = DMatrix::<f64>::identity(n, n) * 1e-9;
-    let mut rhs = DVector::<f64>::zeros(n);
+/// Same problem as [`solve_group_weighted_wls`], solved as one stacked
+/// least-squares system: every row is whitened by `sqrt(w_k / r_i)` (by
+/// `sqrt(w_k) L^-1` for a group with correlated noise), the same
+/// `1e-9` ridge is appended as `sqrt(1e-9) I` rows, and the stack is solved by
+/// Householder QR without forming `H^T W H`.
+///
+/// Linear groups only; configs with `nonlinear_groups` cannot enable
+/// `compare_stacked_qr`.
+///
+/// Also returns `max |R_ii| / min |R_ii|`, a cheap lower bound on the
+/// condition number of the whitened system; the normal equations square it.
+pub fn solve_group_weighted_stacked_qr(
+    model: &DiagnosticModel,
+    y_groups: &[DVector<f64>],
+    group_weights: &[f64],
+) -> (DVector<f64>, Duration, f64) {
+    let t0 = Instant::now();
+    let rows: usize = model
+        .groups
+        .iter()
+        .zip(group_weights)
+        .filter(|(_, &gw)| gw > 0.0)
+        .map(|(group, _)| group.dim())
+        .sum();
+    let mut a = DMatrix::<f64>::zeros(rows + model.n, model.n);
+    let mut b = DVector::<f64>::zeros(rows + model.n);
 
+    let mut r = 0;
     for (k, group) in model.groups.iter().enumerate() {
         let gw = group_weights[k].max(0.0);
         if gw <= 0.0 {
@@ -63,24 +273,34 @@ pub fn solve_group_weighted_wls(
         }
 
         let y = &y_groups[k];
+        if let Some(whitening) = &group.whitening {
+            let scale = gw.sqrt();
+            a.rows_mut(r, group.dim())
+                .copy_from(&(whitening * &group.h * scale));
+            b.rows_mut(r, group.dim())
+                .copy_from(&(whitening * y * scale));
+            r += group.dim();
+            continue;
+        }
         for i in 0..group.dim() {
-            let var = group.r_diag[i].max(1e-12);
-            let inv_var = gw / var;
-            let row = group.h.row(i);
-            let yi = y[i];
-
-            for a in 0..n {
-                let ha = row[a];
-                rhs[a] += inv_var * ha * yi;
-                for b in 0..n {
-                    normal[(a, b)] += inv_var * ha * row[b];
-                }
-            }
+            let scale = (gw / group.r_diag[i].max(1e-12)).sqrt();
+            a.row_mut(r).copy_from(&(group.h.row(i) * scale));
+            b[r] = scale * y[i];
+            r += 1;
         }
     }
+    a.view_mut((rows, 0), (model.n, model.n))
+        .fill_diagonal(1e-9_f64.sqrt());
 
-    let x = solve_normal_equation(normal, rhs);
-    (x, t0.elapsed())
+    let qr = a.qr();
+    qr.q_tr_mul(&mut b);
+    let r_factor = qr.r();
+    let diag = r_factor.diagonal().abs();
+    let cond_estimate = diag.max() / diag.min();
+    let x = r_factor
+        .solve_upper_triangular(&b.rows(0, model.n))
+        .unwrap_or_else(|| DVector::<f64>::zeros(model.n));
+    (x, t0.elapsed(), cond_estimate)
 }
 
 pub fn solve_measurement_weighted_wls(
@@ -89,35 +309,7 @@ pub fn solve_measurement_weighted_wls(
     measurement_weights: &[Vec<f64>],
 ) -> (DVector<f64>, Duration) {
     let t0 = Instant::now();
-    let n = model.n;
-
-    let mut normal = DMatrix::<f64>::identity(n, n) * 1e-9;
-    let mut rhs = DVector::<f64>::zeros(n);
-
-    for (k, group) in model.groups.iter().enumerate() {
-        let y = &y_groups[k];
-        for i in 0..group.dim() {
-            let mw = measurement_weights[k][i].max(0.0);
-            if mw <= 0.0 {
-                continue;
-            }
-
-            let var = group.r_diag[i].max(1e-12);
-            let inv_var = mw / var;
-            let row = group.h.row(i);
-            let yi = y[i];
-
-            for a in 0..n {
-                let ha = row[a];
-                rhs[a] += inv_var * ha * yi;
-                for b in 0..n {
-                    normal[(a, b)] += inv_var * ha * row[b];
-                }
-            }
-        }
-    }
-
-    let x = solve_normal_equation(normal, rhs);
+    let x = solve_wls(model, y_groups, |k, i| measurement_weights[k][i]);
     (x, t0.elapsed())
 }
 
@@ -129,12 +321,17 @@ pub fn compute_group_nis(
     let mut nis = Vec::with_capacity(model.groups.len());
 
     for (k, group) in model.groups.iter().enumerate() {
-        let residual = &y_groups[k] - &group.h * x_hat;
-        let mut sum = 0.0;
-        for i in 0..group.dim() {
-            let var = group.r_diag[i].max(1e-12);
-            sum += residual[i] * residual[i] / var;
-        }
+        let residual = group.residual(&y_groups[k], x_hat);
+        let sum = if group.is_correlated() {
+            group.whiten(&residual).norm_squared()
+        } else {
+            let mut sum = 0.0;
+            for i in 0..group.dim() {
+                let var = group.r_diag[i].max(1e-12);
+                sum += residual[i] * residual[i] / var;
+            }
+            sum
+        };
         nis.push(sum / group.dim() as f64);
     }
 
@@ -150,3 +347,85 @@ pub fn canonical_method_list(raw: &[String]) -> Vec<String> {
     }
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::diagnostics::DiagnosticGroup;
+
+    #[test]
+    fn neumaier_add_recovers_cancelled_terms() {
+        // Naive summation loses both 1.0 terms to the 1e100 magnitudes
+        let terms = [1.0, 1e100, 1.0, -1e100];
+        assert_eq!(terms.iter().sum::<f64>(), 0.0);
+
+        let (mut sum, mut err) = (0.0, 0.0);
+        for term in terms {
+            neumaier_add(&mut sum, &mut err, term);
+        }
+        assert_eq!(sum, 0.0);
+        assert_eq!(sum + err, 2.0);
+    }
+
+    fn group(h: DMatrix<f64>, r_diag: DVector<f64>) -> DiagnosticGroup {
+        DiagnosticGroup {
+            h,
+            r_diag,
+            noise_chol: None,
+            whitening: None,
+            bandwidth_mismatch: false,
+            nonlinear: None,
+        }
+    }
+
+    #[test]
+    fn stacked_qr_matches_the_normal_equations() {
+        // Group 0 observes x directly with unit noise; group 1 observes
+        // x0 + x1 with variance 0.5 at weight 0.5, so w / r = 1. The normal
+        // equations are [[2, 1], [1, 2]] x = [1 + 4, 2 + 4], x = [4/3, 7/3]
+        let model = DiagnosticModel {
+            n: 2,
+            groups: vec![
+                group(DMatrix::identity(2, 2), DVector::from_vec(vec![1.0, 1.0])),
+                group(
+                    DMatrix::from_row_slice(1, 2, &[1.0, 1.0]),
+                    DVector::from_vec(vec![0.5]),
+                ),
+            ],
+            summation: WlsSummation::Naive,
+        };
+        let y_groups = [
+            DVector::from_vec(vec![1.0, 2.0]),
+            DVector::from_vec(vec![4.0]),
+        ];
+        let expected = DVector::from_vec(vec![4.0 / 3.0, 7.0 / 3.0]);
+
+        let (normal, _) = solve_group_weighted_wls(&model, &y_groups, &[1.0, 0.5]);
+        let (qr, _, cond) = solve_group_weighted_stacked_qr(&model, &y_groups, &[1.0, 0.5]);
+        assert!((&normal - &expected).amax() < 1e-8, "{normal}");
+        assert!((&qr - &expected).amax() < 1e-8, "{qr}");
+        assert!(cond >= 1.0);
+
+        // A zero-weighted group is left out of both
+        let (normal, _) = solve_group_weighted_wls(&model, &y_groups, &[1.0, 0.0]);
+        let (qr, _, _) = solve_group_weighted_stacked_qr(&model, &y_groups, &[1.0, 0.0]);
+        let direct = DVector::from_vec(vec![1.0, 2.0]);
+        assert!((&normal - &direct).amax() < 1e-8, "{normal}");
+        assert!((&qr - &direct).amax() < 1e-8, "{qr}");
+    }
+
+    #[test]
+    fn compensated_normal_equations_keep_small_rows() {
+        // Three unit rows observing 1e16, 1, and -1e16: the exact rhs is 1
+        // against a normal matrix of 3 (plus the 1e-9 regularization)
+        let solve = |summation| {
+            let mut equations = NormalEquations::new(1, summation);
+            for y in [1e16, 1.0, -1e16] {
+                equations.add_row(&[1.0], 1.0, y);
+            }
+            equations.solve()[0]
+        };
+        assert_eq!(solve(WlsSummation::Naive), 0.0);
+        assert!((solve(WlsSummation::Compensated) - 1.0 / (3.0 + 1e-9)).abs() < 1e-15);
+    }
+}
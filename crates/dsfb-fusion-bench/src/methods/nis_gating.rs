@@ -78,6 +78,7 @@ impl ReconstructionMethod for NisGatingMethod {
             group_weights: Some(weights),
             solve_time: solve_0 + solve_1,
             total_time: total_t0.elapsed(),
+            solver: None,
         }
     }
 }
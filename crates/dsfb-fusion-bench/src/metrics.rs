@@ -1,8 +1,43 @@
+use crate::methods::SolverIterations;
+
+/// Group weights below this value count as a downweight.
+pub const FALSE_DOWNWEIGHT_THRESHOLD: f64 = 0.9;
+
 #[derive(Debug, Clone)]
 pub struct MethodMetrics {
     pub peak_err: f64,
     pub rms_err: f64,
+    /// Fraction of clean (step, group) pairs with a weight below
+    /// [`FALSE_DOWNWEIGHT_THRESHOLD`], pooled over groups
     pub false_downweight_rate: Option<f64>,
+    /// Fraction of corrupted (step, group) pairs below the threshold;
+    /// `None` without weights or corrupted pairs
+    pub true_downweight_rate: Option<f64>,
+    /// Per-group counts and rates; empty without weights
+    pub groups: Vec<GroupDownweightRates>,
+}
+
+/// Downweight counts of one group against its per-step fault labels.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct GroupDownweightRates {
+    pub clean_steps: usize,
+    pub false_downweights: usize,
+    pub corrupted_steps: usize,
+    pub true_downweights: usize,
+}
+
+impl GroupDownweightRates {
+    pub fn false_downweight_rate(&self) -> Option<f64> {
+        rate(self.false_downweights, self.clean_steps)
+    }
+
+    pub fn true_downweight_rate(&self) -> Option<f64> {
+        rate(self.true_downweights, self.corrupted_steps)
+    }
+}
+
+fn rate(count: usize, total: usize) -> Option<f64> {
+    (total > 0).then(|| count as f64 / total as f64)
 }
 
 #[derive(Debug, Default, Clone)]
@@ -10,8 +45,7 @@ pub struct MetricsAccumulator {
     peak_err: f64,
     sum_sq: f64,
     count: usize,
-    false_downweight_count: usize,
-    false_downweight_total: usize,
+    groups: Vec<GroupDownweightRates>,
     expects_weights: bool,
 }
 
@@ -23,24 +57,32 @@ impl MetricsAccumulator {
         }
     }
 
-    pub fn observe(
-        &mut self,
-        err_norm: f64,
-        group_weights: Option<&[f64]>,
-        corruption_active: bool,
-    ) {
+    /// Records one step. `corrupted` holds the step's per-group fault labels:
+    /// a weight below the threshold is a true downweight on a corrupted group
+    /// and a false one on a clean group.
+    pub fn observe(&mut self, err_norm: f64, group_weights: Option<&[f64]>, corrupted: &[bool]) {
         self.peak_err = self.peak_err.max(err_norm);
         self.sum_sq += err_norm * err_norm;
         self.count += 1;
 
-        if self.expects_weights && !corruption_active {
-            if let Some(weights) = group_weights {
-                for &w in weights {
-                    self.false_downweight_total += 1;
-                    if w < 0.9 {
-                        self.false_downweight_count += 1;
-                    }
-                }
+        if !self.expects_weights {
+            return;
+        }
+        let Some(weights) = group_weights else {
+            return;
+        };
+        if self.groups.len() < weights.len() {
+            self.groups
+                .resize(weights.len(), GroupDownweightRates::default());
+        }
+        for ((&w, &is_corrupted), counts) in weights.iter().zip(corrupted).zip(&mut self.groups) {
+            let downweighted = w < FALSE_DOWNWEIGHT_THRESHOLD;
+            if is_corrupted {
+                counts.corrupted_steps += 1;
+                counts.true_downweights += usize::from(downweighted);
+            } else {
+                counts.clean_steps += 1;
+                counts.false_downweights += usize::from(downweighted);
             }
         }
     }
@@ -52,20 +94,532 @@ impl MetricsAccumulator {
             0.0
         };
 
-        let false_downweight_rate = if self.expects_weights {
-            if self.false_downweight_total > 0 {
-                Some(self.false_downweight_count as f64 / self.false_downweight_total as f64)
-            } else {
-                Some(0.0)
-            }
+        let pooled = self
+            .groups
+            .iter()
+            .fold(GroupDownweightRates::default(), |acc, g| {
+                GroupDownweightRates {
+                    clean_steps: acc.clean_steps + g.clean_steps,
+                    false_downweights: acc.false_downweights + g.false_downweights,
+                    corrupted_steps: acc.corrupted_steps + g.corrupted_steps,
+                    true_downweights: acc.true_downweights + g.true_downweights,
+                }
+            });
+        let (false_downweight_rate, true_downweight_rate) = if self.expects_weights {
+            (
+                Some(pooled.false_downweight_rate().unwrap_or(0.0)),
+                pooled.true_downweight_rate(),
+            )
         } else {
-            None
+            (None, None)
         };
 
         MethodMetrics {
             peak_err: self.peak_err,
             rms_err,
             false_downweight_rate,
+            true_downweight_rate,
+            groups: self.groups.clone(),
+        }
+    }
+}
+
+/// Smallest weight change across a fault transition that gets a time constant.
+pub const MIN_WEIGHT_RESPONSE: f64 = 1e-3;
+
+/// Samples closer to the settled weight than this fraction of the total
+/// change end the fit; below it the response is dominated by noise.
+pub const WEIGHT_RESPONSE_SETTLED_FRACTION: f64 = 0.05;
+
+/// Fitted time constants, in seconds, of the corrupted group's weight after
+/// fault onset and after fault clearance.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WeightResponse {
+    pub downweight_tau: Option<f64>,
+    pub recovery_tau: Option<f64>,
+}
+
+/// Fits `w(t) = w_inf + (w_0 - w_inf) exp(-t / tau)` to the weight
+/// trajectory `values`, sampled every `dt` starting one step after the
+/// transition, with `w_0 = start` and `w_inf` the mean of the last third of
+/// `values`.
+///
+/// `log((w - w_inf) / (w_0 - w_inf))` is regressed on `t` through the origin,
+/// over the samples before the response first comes within
+/// [`WEIGHT_RESPONSE_SETTLED_FRACTION`] of `w_inf`. `None` when the weight
+/// moves less than [`MIN_WEIGHT_RESPONSE`], settles within one step (a hard
+/// gate), or the fit does not decay.
+pub fn fit_time_constant(start: f64, values: &[f64], dt: f64) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let tail = &values[values.len() - values.len().div_ceil(3)..];
+    let settled = tail.iter().sum::<f64>() / tail.len() as f64;
+    let amplitude = start - settled;
+    if amplitude.abs() < MIN_WEIGHT_RESPONSE {
+        return None;
+    }
+
+    let mut sum_tt = 0.0;
+    let mut sum_tl = 0.0;
+    for (i, &w) in values.iter().enumerate() {
+        let ratio = (w - settled) / amplitude;
+        if ratio <= WEIGHT_RESPONSE_SETTLED_FRACTION {
+            break;
+        }
+        if ratio >= 1.0 {
+            continue;
+        }
+        let t = (i + 1) as f64 * dt;
+        sum_tt += t * t;
+        sum_tl += t * ratio.ln();
+    }
+
+    (sum_tl < 0.0).then(|| -sum_tt / sum_tl)
+}
+
+/// Weight response of one group over a run: the down-weighting fit covers
+/// the first stretch of `corruption_active` steps and the recovery fit the
+/// steps after it. `weights` holds the group's weight at every step and
+/// `corruption_active` the same group's labels (see
+/// [`SimulationData::group_active`](crate::sim::state::SimulationData::group_active)).
+pub fn weight_response(weights: &[f64], corruption_active: &[bool], dt: f64) -> WeightResponse {
+    let Some(onset) = corruption_active.iter().position(|&active| active) else {
+        return WeightResponse::default();
+    };
+    let clearance = corruption_active[onset..]
+        .iter()
+        .position(|&active| !active)
+        .map_or(weights.len(), |len| onset + len);
+
+    let before_onset = onset.checked_sub(1).map_or(1.0, |i| weights[i]);
+    let downweight_tau = fit_time_constant(before_onset, &weights[onset..clearance], dt);
+    let recovery_tau = if clearance < weights.len() {
+        fit_time_constant(weights[clearance - 1], &weights[clearance..], dt)
+    } else {
+        None
+    };
+
+    WeightResponse {
+        downweight_tau,
+        recovery_tau,
+    }
+}
+
+/// How quickly and how cleanly a method's group weights flag corrupted groups.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DetectionMetrics {
+    /// Mean seconds from an episode's onset to the group's first weight below
+    /// [`FALSE_DOWNWEIGHT_THRESHOLD`], over the detected episodes
+    pub detection_latency: Option<f64>,
+    /// Mean seconds from a detected episode's end to the group's first weight
+    /// back at or above the threshold, over the episodes that recover in the run
+    pub recovery_latency: Option<f64>,
+    /// Area under the ROC curve of `1 - weight` as a score for "corrupted",
+    /// over every step and group
+    pub auc: Option<f64>,
+    /// The same latencies for each group's own episodes, in group order
+    pub groups: Vec<GroupDetection>,
+}
+
+/// Detection of one group's corruption episodes.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GroupDetection {
+    pub episodes: usize,
+    pub detected_episodes: usize,
+    pub detection_latency: Option<f64>,
+    pub recovery_latency: Option<f64>,
+}
+
+/// Detection metrics of a run. `weights[step][k]` is group `k`'s weight and
+/// `corrupted[step][k]` whether the group is corrupted at that step; each
+/// maximal run of corrupted steps of one group is an episode.
+pub fn detection_metrics(
+    weights: &[Vec<f64>],
+    corrupted: &[Vec<bool>],
+    dt: f64,
+) -> DetectionMetrics {
+    let groups = corrupted.first().map_or(0, Vec::len);
+    let mut detection = Vec::new();
+    let mut recovery = Vec::new();
+    let mut per_group = Vec::with_capacity(groups);
+
+    for k in 0..groups {
+        let mut episodes = 0;
+        let mut group_detection = Vec::new();
+        let mut group_recovery = Vec::new();
+        let mut step = 0;
+        while step < corrupted.len() {
+            if !corrupted[step][k] {
+                step += 1;
+                continue;
+            }
+            let onset = step;
+            while step < corrupted.len() && corrupted[step][k] {
+                step += 1;
+            }
+            let clearance = step;
+            episodes += 1;
+
+            let Some(first_low) =
+                (onset..clearance).find(|&s| weights[s][k] < FALSE_DOWNWEIGHT_THRESHOLD)
+            else {
+                continue;
+            };
+            group_detection.push((first_low - onset) as f64 * dt);
+            if let Some(first_high) =
+                (clearance..weights.len()).find(|&s| weights[s][k] >= FALSE_DOWNWEIGHT_THRESHOLD)
+            {
+                group_recovery.push((first_high - clearance) as f64 * dt);
+            }
+        }
+
+        per_group.push(GroupDetection {
+            episodes,
+            detected_episodes: group_detection.len(),
+            detection_latency: mean(&group_detection),
+            recovery_latency: mean(&group_recovery),
+        });
+        detection.extend(group_detection);
+        recovery.extend(group_recovery);
+    }
+
+    DetectionMetrics {
+        detection_latency: mean(&detection),
+        recovery_latency: mean(&recovery),
+        auc: weight_auc(weights, corrupted),
+        groups: per_group,
+    }
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// Mann-Whitney estimate of the ROC AUC: the probability that a corrupted
+/// (step, group) has a lower weight than a clean one, counting ties as half.
+/// `None` unless both classes occur.
+fn weight_auc(weights: &[Vec<f64>], corrupted: &[Vec<bool>]) -> Option<f64> {
+    let mut samples: Vec<(f64, bool)> = weights
+        .iter()
+        .zip(corrupted)
+        .flat_map(|(w, c)| w.iter().copied().zip(c.iter().copied()))
+        .collect();
+    let positives = samples.iter().filter(|(_, c)| *c).count();
+    let negatives = samples.len() - positives;
+    if positives == 0 || negatives == 0 {
+        return None;
+    }
+
+    // Rank by descending weight, so rank grows with the score `1 - weight`
+    samples.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let mut positive_rank_sum = 0.0;
+    let mut start = 0;
+    while start < samples.len() {
+        let end = start
+            + samples[start..]
+                .iter()
+                .take_while(|(w, _)| *w == samples[start].0)
+                .count();
+        let mid_rank = (start + end + 1) as f64 / 2.0;
+        let tied_positives = samples[start..end].iter().filter(|(_, c)| *c).count();
+        positive_rank_sum += mid_rank * tied_positives as f64;
+        start = end;
+    }
+
+    let p = positives as f64;
+    Some((positive_rank_sum - p * (p + 1.0) / 2.0) / (p * negatives as f64))
+}
+
+/// Iteration statistics of an iterative solver over one run.
+#[derive(Debug, Clone, Copy)]
+pub struct SolverMetrics {
+    pub mean_iterations: f64,
+    pub max_iterations: usize,
+    /// Fraction of steps that hit `max_iter` without meeting the tolerance
+    pub nonconvergence_rate: f64,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct SolverAccumulator {
+    steps: usize,
+    iterations_sum: usize,
+    max_iterations: usize,
+    nonconverged: usize,
+}
+
+impl SolverAccumulator {
+    pub fn observe(&mut self, solver: SolverIterations) {
+        self.steps += 1;
+        self.iterations_sum += solver.iterations;
+        self.max_iterations = self.max_iterations.max(solver.iterations);
+        if !solver.converged {
+            self.nonconverged += 1;
+        }
+    }
+
+    /// `None` when no step reported solver iterations.
+    pub fn finalize(&self) -> Option<SolverMetrics> {
+        (self.steps > 0).then(|| SolverMetrics {
+            mean_iterations: self.iterations_sum as f64 / self.steps as f64,
+            max_iterations: self.max_iterations,
+            nonconvergence_rate: self.nonconverged as f64 / self.steps as f64,
+        })
+    }
+}
+
+/// Error metrics for a single state dimension.
+#[derive(Debug, Clone)]
+pub struct StateErrorMetrics {
+    pub state: usize,
+    pub peak_err: f64,
+    pub rms_err: f64,
+}
+
+/// Empirical quantile of ascending, non-empty `sorted`: the smallest sample
+/// `x` whose empirical CDF `F(x)` is at least `p`.
+pub fn empirical_quantile(sorted: &[f64], p: f64) -> f64 {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Per-dimension peak and RMS error over a run for a selected set of states.
+#[derive(Debug, Default, Clone)]
+pub struct StateErrorAccumulator {
+    dims: Vec<usize>,
+    peak_err: Vec<f64>,
+    sum_sq: Vec<f64>,
+    count: usize,
+}
+
+impl StateErrorAccumulator {
+    pub fn new(dims: &[usize]) -> Self {
+        Self {
+            dims: dims.to_vec(),
+            peak_err: vec![0.0; dims.len()],
+            sum_sq: vec![0.0; dims.len()],
+            count: 0,
         }
     }
+
+    /// Records one step and returns the signed errors of the selected states.
+    pub fn observe(&mut self, err: &[f64]) -> Vec<f64> {
+        let selected: Vec<f64> = self.dims.iter().map(|&d| err[d]).collect();
+        for (i, &e) in selected.iter().enumerate() {
+            self.peak_err[i] = self.peak_err[i].max(e.abs());
+            self.sum_sq[i] += e * e;
+        }
+        self.count += 1;
+        selected
+    }
+
+    pub fn finalize(&self) -> Vec<StateErrorMetrics> {
+        self.dims
+            .iter()
+            .enumerate()
+            .map(|(i, &state)| StateErrorMetrics {
+                state,
+                peak_err: self.peak_err[i],
+                rms_err: if self.count > 0 {
+                    (self.sum_sq[i] / self.count as f64).sqrt()
+                } else {
+                    0.0
+                },
+            })
+            .collect()
+    }
+}
+
+/// Group-weight behaviour on fault-free data.
+#[derive(Debug, Clone)]
+pub struct NullMetrics {
+    pub steps: usize,
+    pub min_group_weights: Vec<f64>,
+    pub excursion_steps: usize,
+    pub excursion_events: usize,
+    pub longest_excursion: usize,
+}
+
+impl NullMetrics {
+    pub fn min_weight(&self) -> f64 {
+        self.min_group_weights
+            .iter()
+            .copied()
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    pub fn excursion_rate(&self) -> f64 {
+        if self.steps == 0 {
+            0.0
+        } else {
+            self.excursion_steps as f64 / self.steps as f64
+        }
+    }
+}
+
+/// Tracks minimum group weights and sub-threshold excursions over a run.
+///
+/// A step is an excursion when any group weight is below
+/// `FALSE_DOWNWEIGHT_THRESHOLD`; consecutive excursion steps form one event.
+#[derive(Debug, Default, Clone)]
+pub struct NullAccumulator {
+    steps: usize,
+    min_group_weights: Vec<f64>,
+    excursion_steps: usize,
+    excursion_events: usize,
+    current_excursion: usize,
+    longest_excursion: usize,
+}
+
+impl NullAccumulator {
+    pub fn observe(&mut self, group_weights: &[f64]) {
+        if self.min_group_weights.len() < group_weights.len() {
+            self.min_group_weights
+                .resize(group_weights.len(), f64::INFINITY);
+        }
+        for (min_w, &w) in self.min_group_weights.iter_mut().zip(group_weights) {
+            *min_w = min_w.min(w);
+        }
+        self.steps += 1;
+
+        if group_weights
+            .iter()
+            .any(|&w| w < FALSE_DOWNWEIGHT_THRESHOLD)
+        {
+            if self.current_excursion == 0 {
+                self.excursion_events += 1;
+            }
+            self.excursion_steps += 1;
+            self.current_excursion += 1;
+            self.longest_excursion = self.longest_excursion.max(self.current_excursion);
+        } else {
+            self.current_excursion = 0;
+        }
+    }
+
+    pub fn finalize(&self) -> NullMetrics {
+        NullMetrics {
+            steps: self.steps,
+            min_group_weights: self.min_group_weights.clone(),
+            excursion_steps: self.excursion_steps,
+            excursion_events: self.excursion_events,
+            longest_excursion: self.longest_excursion,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auc(weights: &[[f64; 2]], corrupted: &[[bool; 2]]) -> Option<f64> {
+        let weights: Vec<Vec<f64>> = weights.iter().map(|w| w.to_vec()).collect();
+        let corrupted: Vec<Vec<bool>> = corrupted.iter().map(|c| c.to_vec()).collect();
+        weight_auc(&weights, &corrupted)
+    }
+
+    #[test]
+    fn weight_auc_is_one_for_perfect_separation() {
+        let corrupted = [[true, false], [true, false]];
+        assert_eq!(auc(&[[0.1, 1.0], [0.2, 0.9]], &corrupted), Some(1.0));
+        assert_eq!(auc(&[[1.0, 0.1], [0.9, 0.2]], &corrupted), Some(0.0));
+    }
+
+    #[test]
+    fn weight_auc_counts_ties_as_half() {
+        // Corrupted {0.5, 0.8} against clean {0.5, 1.0}: of the four pairs,
+        // 0.5 < 1.0 and 0.8 < 1.0 count 1, 0.5 = 0.5 counts 1/2, and
+        // 0.8 > 0.5 counts 0, so the AUC is 2.5 / 4
+        let weights = [[0.5, 0.5], [0.8, 1.0]];
+        let corrupted = [[true, false], [true, false]];
+        assert_eq!(auc(&weights, &corrupted), Some(0.625));
+
+        let equal = [[0.7, 0.7], [0.7, 0.7]];
+        assert_eq!(auc(&equal, &[[true, false], [false, false]]), Some(0.5));
+    }
+
+    #[test]
+    fn weight_auc_needs_both_classes() {
+        assert_eq!(auc(&[[0.5, 0.9]], &[[false, false]]), None);
+        assert_eq!(auc(&[[0.5, 0.9]], &[[true, true]]), None);
+    }
+
+    #[test]
+    fn empirical_quantile_is_the_smallest_sample_reaching_p() {
+        let sorted = [1.0, 2.0, 3.0, 4.0];
+        // F(1) = 0.25, F(2) = 0.5, F(3) = 0.75, F(4) = 1
+        let quantiles: Vec<f64> = [0.0, 0.25, 0.26, 0.5, 0.75, 0.9, 1.0]
+            .iter()
+            .map(|&p| empirical_quantile(&sorted, p))
+            .collect();
+        assert_eq!(quantiles, [1.0, 1.0, 2.0, 2.0, 3.0, 4.0, 4.0]);
+        assert_eq!(empirical_quantile(&[7.5], 0.5), 7.5);
+    }
+
+    /// `count` samples of `settled + (start - settled) exp(-t / tau)` at
+    /// `t = 0.1, 0.2, ...`, then `hold` samples of exactly `settled`.
+    fn exponential(start: f64, settled: f64, tau: f64, count: usize, hold: usize) -> Vec<f64> {
+        (1..=count)
+            .map(|i| settled + (start - settled) * (-(i as f64) * 0.1 / tau).exp())
+            .chain(std::iter::repeat(settled).take(hold))
+            .collect()
+    }
+
+    #[test]
+    fn fit_time_constant_recovers_an_exact_exponential() {
+        // At tau = 0.5 the 15th sample (t = 1.5) is exp(-3) < 5% of the way,
+        // which ends the fit; the last third is all at the settled weight
+        let values = exponential(1.0, 0.2, 0.5, 15, 15);
+        let tau = fit_time_constant(1.0, &values, 0.1).expect("decaying response");
+        assert!((tau - 0.5).abs() < 1e-9, "{tau}");
+    }
+
+    #[test]
+    fn fit_time_constant_skips_small_moves_and_hard_gates() {
+        assert_eq!(fit_time_constant(1.0, &[0.9995; 6], 0.1), None);
+        // Settled by the first sample: nothing left to fit
+        assert_eq!(fit_time_constant(1.0, &[0.0; 6], 0.1), None);
+        assert_eq!(fit_time_constant(1.0, &[], 0.1), None);
+    }
+
+    #[test]
+    fn weight_response_fits_onset_and_clearance() {
+        let mut weights = vec![1.0; 2];
+        weights.extend(exponential(1.0, 0.2, 0.5, 15, 15));
+        weights.extend(exponential(0.2, 1.0, 0.3, 10, 20));
+        let mut active = vec![false; 2];
+        active.extend([true; 30]);
+        active.extend([false; 30]);
+
+        let response = weight_response(&weights, &active, 0.1);
+        assert!((response.downweight_tau.unwrap() - 0.5).abs() < 1e-9);
+        assert!((response.recovery_tau.unwrap() - 0.3).abs() < 1e-9);
+
+        // Without a corrupted step there is nothing to fit
+        assert_eq!(
+            weight_response(&weights, &[false; 62], 0.1),
+            WeightResponse::default()
+        );
+    }
+
+    #[test]
+    fn detection_metrics_reports_each_groups_episodes() {
+        // Group 0 is corrupted on steps 1-2 and 4; group 1 never is
+        let weights: Vec<Vec<f64>> = [1.0, 1.0, 0.5, 1.0, 0.5, 1.0]
+            .iter()
+            .map(|&w| vec![w, 1.0])
+            .collect();
+        let corrupted: Vec<Vec<bool>> = [false, true, true, false, true, false]
+            .iter()
+            .map(|&c| vec![c, false])
+            .collect();
+        let metrics = detection_metrics(&weights, &corrupted, 0.1);
+
+        let group = metrics.groups[0];
+        assert_eq!((group.episodes, group.detected_episodes), (2, 2));
+        assert!((group.detection_latency.unwrap() - 0.05).abs() < 1e-12);
+        assert_eq!(group.recovery_latency, Some(0.0));
+        assert_eq!(metrics.groups[1], GroupDetection::default());
+        assert_eq!(metrics.detection_latency, group.detection_latency);
+    }
 }
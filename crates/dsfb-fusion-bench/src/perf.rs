@@ -0,0 +1,146 @@
+//! Run-to-run performance regression gate.
+//!
+//! Mean `baseline_wls_us` and `overhead_us` per method are read from a previous
+//! `summary.csv` and compared with the current run. A timing regresses when it
+//! exceeds the baseline by more than the configured percentage and by more
+//! than `PERF_NOISE_FLOOR_US`; the floor keeps sub-microsecond jitter on near-zero
+//! overheads from failing the gate.
+
+use anyhow::{bail, Context, Result};
+use csv::ReaderBuilder;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Absolute slack, in microseconds, below which a slowdown is never a regression.
+pub const PERF_NOISE_FLOOR_US: f64 = 0.5;
+
+pub const PERF_METRICS: [&str; 2] = ["baseline_wls_us", "overhead_us"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MethodTiming {
+    pub baseline_wls_us: f64,
+    pub overhead_us: f64,
+}
+
+impl MethodTiming {
+    fn get(&self, metric: &str) -> f64 {
+        match metric {
+            "baseline_wls_us" => self.baseline_wls_us,
+            _ => self.overhead_us,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PerfThresholds {
+    pub baseline_wls_pct: f64,
+    pub overhead_pct: f64,
+}
+
+impl PerfThresholds {
+    fn get(&self, metric: &str) -> f64 {
+        match metric {
+            "baseline_wls_us" => self.baseline_wls_pct,
+            _ => self.overhead_pct,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PerfComparison {
+    pub method: String,
+    pub metric: &'static str,
+    pub baseline_us: f64,
+    pub current_us: f64,
+    /// Relative change in percent; `None` when the baseline timing is zero.
+    pub change_pct: Option<f64>,
+    pub limit_pct: f64,
+    pub regressed: bool,
+}
+
+/// Mean timings per method from a `summary.csv`, averaged over seeds and sweep cells.
+pub fn read_summary_timings(path: &Path) -> Result<BTreeMap<String, MethodTiming>> {
+    let mut reader = ReaderBuilder::new()
+        .has_headers(true)
+        .from_path(path)
+        .with_context(|| format!("failed to open summary for timings: {}", path.display()))?;
+
+    let headers = reader.headers()?.clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|h| h == name)
+            .with_context(|| format!("{} has no '{name}' column", path.display()))
+    };
+    let method_col = column("method")?;
+    let wls_col = column("baseline_wls_us")?;
+    let overhead_col = column("overhead_us")?;
+
+    let mut sums = BTreeMap::<String, (f64, f64, usize)>::new();
+    for record in reader.records() {
+        let record =
+            record.with_context(|| format!("failed to read summary: {}", path.display()))?;
+        let parse = |idx: usize| -> Result<f64> {
+            let raw = record.get(idx).unwrap_or("");
+            raw.parse()
+                .with_context(|| format!("invalid timing '{raw}' in {}", path.display()))
+        };
+        let entry = sums
+            .entry(record.get(method_col).unwrap_or("").to_string())
+            .or_default();
+        entry.0 += parse(wls_col)?;
+        entry.1 += parse(overhead_col)?;
+        entry.2 += 1;
+    }
+
+    if sums.is_empty() {
+        bail!("{} contains no timing rows", path.display());
+    }
+
+    Ok(sums
+        .into_iter()
+        .map(|(method, (wls, overhead, count))| {
+            let count = count as f64;
+            (
+                method,
+                MethodTiming {
+                    baseline_wls_us: wls / count,
+                    overhead_us: overhead / count,
+                },
+            )
+        })
+        .collect())
+}
+
+/// Compares every method present in both runs. Methods missing from the
+/// baseline are not gated.
+pub fn compare_timings(
+    baseline: &BTreeMap<String, MethodTiming>,
+    current: &BTreeMap<String, MethodTiming>,
+    thresholds: PerfThresholds,
+) -> Vec<PerfComparison> {
+    let mut out = Vec::new();
+    for (method, cur) in current {
+        let Some(base) = baseline.get(method) else {
+            continue;
+        };
+        for metric in PERF_METRICS {
+            let baseline_us = base.get(metric);
+            let current_us = cur.get(metric);
+            let limit_pct = thresholds.get(metric);
+            let change_pct =
+                (baseline_us > 0.0).then(|| (current_us - baseline_us) / baseline_us * 100.0);
+            let over_limit = change_pct.map_or(true, |pct| pct > limit_pct);
+            out.push(PerfComparison {
+                method: method.clone(),
+                metric,
+                baseline_us,
+                current_us,
+                change_pct,
+                limit_pct,
+                regressed: over_limit && current_us - baseline_us > PERF_NOISE_FLOOR_US,
+            });
+        }
+    }
+    out
+}
@@ -0,0 +1,125 @@
+//! Output hashing for reproducible run re-execution.
+//!
+//! Every CSV in a run directory is hashed (64-bit FNV-1a) after dropping the
+//! wall-clock timing columns, which are the only non-deterministic values the
+//! benchmark writes, and the `tags` labels, which are not results. A rerun
+//! reproduces a run when every hash matches.
+
+use anyhow::{Context, Result};
+use csv::{ReaderBuilder, StringRecord};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Columns holding wall-clock timings, or counts derived from them, excluded
+/// from output hashes.
+pub const TIMING_COLUMNS: [&str; 14] = [
+    "baseline_wls_us",
+    "overhead_us",
+    "total_us",
+    "normal_eq_us",
+    "stacked_qr_us",
+    "samples_kept",
+    "outliers",
+    "mean_us",
+    "std_us",
+    "min_us",
+    "p50_us",
+    "p95_us",
+    "p99_us",
+    "max_us",
+];
+
+/// Label columns, excluded from output hashes so retagging keeps them stable.
+pub const LABEL_COLUMNS: [&str; 1] = ["tags"];
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashDivergence {
+    pub file: String,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+}
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(hash, |h, &b| (h ^ u64::from(b)).wrapping_mul(FNV_PRIME))
+}
+
+/// Hashes a CSV file with the timing and label columns removed from every record.
+pub fn hash_csv(path: &Path) -> Result<String> {
+    let mut reader = ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_path(path)
+        .with_context(|| format!("failed to open CSV for hashing: {}", path.display()))?;
+
+    let mut keep = Vec::<usize>::new();
+    let mut hash = FNV_OFFSET;
+    let mut record = StringRecord::new();
+    let mut first = true;
+
+    while reader
+        .read_record(&mut record)
+        .with_context(|| format!("failed to read CSV for hashing: {}", path.display()))?
+    {
+        if first {
+            keep = record
+                .iter()
+                .enumerate()
+                .filter(|(_, name)| !TIMING_COLUMNS.contains(name) && !LABEL_COLUMNS.contains(name))
+                .map(|(idx, _)| idx)
+                .collect();
+            first = false;
+        }
+        for &idx in &keep {
+            hash = fnv1a(hash, record.get(idx).unwrap_or("").as_bytes());
+            hash = fnv1a(hash, b",");
+        }
+        hash = fnv1a(hash, b"\n");
+    }
+
+    Ok(format!("{hash:016x}"))
+}
+
+/// Hashes every `*.csv` directly inside `outdir`, keyed by file name.
+pub fn output_hashes(outdir: &Path) -> Result<BTreeMap<String, String>> {
+    let mut hashes = BTreeMap::new();
+    let entries = fs::read_dir(outdir)
+        .with_context(|| format!("failed to list run directory: {}", outdir.display()))?;
+
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("csv") {
+            continue;
+        }
+        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+            hashes.insert(name.to_string(), hash_csv(&path)?);
+        }
+    }
+
+    Ok(hashes)
+}
+
+/// Files whose hashes differ or that are present in only one of the runs.
+pub fn compare_output_hashes(
+    expected: &BTreeMap<String, String>,
+    actual: &BTreeMap<String, String>,
+) -> Vec<HashDivergence> {
+    let mut files: Vec<&String> = expected.keys().chain(actual.keys()).collect();
+    files.sort();
+    files.dedup();
+
+    files
+        .into_iter()
+        .filter(|file| expected.get(*file) != actual.get(*file))
+        .map(|file| HashDivergence {
+            file: file.clone(),
+            expected: expected.get(file).cloned(),
+            actual: actual.get(file).cloned(),
+        })
+        .collect()
+}
@@ -0,0 +1,1224 @@
+//! Programmatic entry point for benchmark runs.
+//!
+//! [`BenchRunner`] holds a validated config and method list. Its `run_*`
+//! methods return the rows of a run in memory without touching the
+//! filesystem, and [`BenchRunner::execute`] writes the same output directory
+//! and manifest as the `dsfb-fusion-bench` CLI, which is a thin wrapper over it.
+
+use anyhow::{bail, Context, Result};
+use nalgebra::DVector;
+use rayon::prelude::*;
+use std::path::Path;
+
+use crate::best_params::{best_params_rows, marginal_rows};
+use crate::checkpoint::{CheckpointHeader, SweepCheckpoint};
+use crate::edge_cases::{build_edge_case, run_edge_case, EdgeCase};
+use crate::io::{
+    write_best_params_csv, write_edge_cases_csv, write_equal_deltas_csv, write_error_cdf_csv,
+    write_fault_labels_csv, write_group_downweight_csv, write_group_nis_csv, write_heatmap_csv,
+    write_manifest_json, write_marginals_csv, write_null_summary_csv, write_solver_bench_csv,
+    write_solver_comparison_csv, write_state_errors_csv, write_state_summary_csv,
+    write_stats_tests_csv, write_summary_csv, write_trajectories_csv, BestParamsRow, EdgeCaseRow,
+    EqualDeltaRow, ErrorCdfRow, FaultLabelRow, GroupDownweightRow, GroupNisRow, HeatmapRow,
+    Manifest, MarginalRow, NullSummaryRow, SolverBenchRow, SolverComparisonRow, StateErrorRow,
+    StateSummaryRow, SummaryRow, TrajectoryRow,
+};
+use crate::methods::cov_inflate::CovInflateMethod;
+use crate::methods::dsfb::DsfbAdaptiveMethod;
+use crate::methods::dsfb_core::DsfbCoreMethod;
+use crate::methods::equal::EqualMethod;
+use crate::methods::irls_huber::IrlsHuberMethod;
+use crate::methods::nis_gating::{NisGatingMethod, NisMode};
+use crate::methods::ukf::UkfMethod;
+use crate::methods::vb_adaptive_r::VbAdaptiveRMethod;
+use crate::methods::{
+    canonical_method_list, compute_group_nis, solve_group_weighted_stacked_qr,
+    solve_group_weighted_wls, ReconstructionMethod, METHOD_ORDER,
+};
+use crate::metrics::{
+    detection_metrics, empirical_quantile, weight_response, DetectionMetrics, MetricsAccumulator,
+    NullAccumulator, SolverAccumulator, StateErrorAccumulator, WeightResponse,
+};
+use crate::repro::output_hashes;
+use crate::sim::diagnostics::{build_diagnostic_model, DiagnosticModel};
+use crate::sim::preprocess::{preprocess_data, preprocessed_model};
+use crate::sim::state::{
+    generate_null_simulation_data, generate_simulation_data, BenchConfig, SimulationData,
+};
+use crate::stats::{build_stats_rows, StatsTestRow, STATS_REFERENCE_METHOD};
+use crate::tables::{mean_std, write_tables};
+use crate::timing::{bench, bench_with_setup, BenchSettings, SampleStats, TimingAccumulator};
+
+/// Relative tolerance below which an `equal_deltas.csv` sign entry is `0`.
+const EQUAL_DELTA_ZERO_TOL: f64 = 1e-12;
+
+/// Kind of run, recorded as the manifest `mode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunMode {
+    Default,
+    Sweep,
+    Null,
+    EdgeCases,
+    BenchSolver,
+}
+
+impl RunMode {
+    pub const ALL: [RunMode; 5] = [
+        RunMode::Default,
+        RunMode::Sweep,
+        RunMode::Null,
+        RunMode::EdgeCases,
+        RunMode::BenchSolver,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RunMode::Default => "default",
+            RunMode::Sweep => "sweep",
+            RunMode::Null => "null",
+            RunMode::EdgeCases => "edge_cases",
+            RunMode::BenchSolver => "bench_solver",
+        }
+    }
+
+    /// Parses a manifest `mode`.
+    pub fn from_name(name: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.name() == name)
+            .with_context(|| format!("unsupported run mode: {name}"))
+    }
+
+    fn note(self) -> &'static str {
+        match self {
+            RunMode::Default => "Deterministic synthetic benchmark outputs",
+            RunMode::Sweep => "Deterministic synthetic benchmark outputs with alpha/beta sweep",
+            RunMode::Null => {
+                "Fault-free runs characterizing the false-alarm floor of weighted methods"
+            }
+            RunMode::EdgeCases => {
+                "Degenerate/edge-case scenario pack with qualitative pass/fail checks"
+            }
+            RunMode::BenchSolver => {
+                "Per-call micro-benchmark of the equal-weight WLS solve and each method"
+            }
+        }
+    }
+}
+
+/// Rows of a `--run-default` run, one entry per file it writes.
+///
+/// Row vectors of optional outputs are empty when the config disables them.
+#[derive(Debug, Clone, Default)]
+pub struct DefaultResults {
+    pub summary: Vec<SummaryRow>,
+    pub stats_tests: Vec<StatsTestRow>,
+    pub trajectories: Vec<TrajectoryRow>,
+    pub group_downweights: Vec<GroupDownweightRow>,
+    pub state_errors: Vec<StateErrorRow>,
+    pub state_summary: Vec<StateSummaryRow>,
+    pub equal_deltas: Vec<EqualDeltaRow>,
+    pub group_nis: Vec<GroupNisRow>,
+    pub fault_labels: Vec<FaultLabelRow>,
+    pub error_cdf: Vec<ErrorCdfRow>,
+    pub solver_comparison: Vec<SolverComparisonRow>,
+}
+
+/// Rows of a `--run-sweep` run.
+#[derive(Debug, Clone, Default)]
+pub struct SweepResults {
+    pub summary: Vec<SummaryRow>,
+    pub heatmap: Vec<HeatmapRow>,
+    pub best_params: Vec<BestParamsRow>,
+    pub marginals: Vec<MarginalRow>,
+    /// Number of noise/alpha/beta grid points.
+    pub cells: usize,
+    /// Number of seeds run at every grid point.
+    pub seeds: usize,
+    /// Method runs taken from a checkpoint instead of being recomputed.
+    pub resumed_runs: usize,
+}
+
+/// Rows of a `--run-null` run.
+#[derive(Debug, Clone, Default)]
+pub struct NullResults {
+    pub summary: Vec<NullSummaryRow>,
+    pub trajectories: Vec<TrajectoryRow>,
+}
+
+/// In-memory results of one run, by mode.
+#[derive(Debug, Clone)]
+pub enum RunResults {
+    Default(DefaultResults),
+    Sweep(SweepResults),
+    Null(NullResults),
+    EdgeCases(Vec<EdgeCaseRow>),
+    BenchSolver(Vec<SolverBenchRow>),
+}
+
+#[derive(Debug, Clone)]
+struct MethodRunResult {
+    summary: SummaryRow,
+    trajectories: Vec<TrajectoryRow>,
+    state_errors: Vec<StateErrorRow>,
+    state_summary: Vec<StateSummaryRow>,
+    equal_deltas: Vec<EqualDeltaRow>,
+    group_downweights: Vec<GroupDownweightRow>,
+}
+
+/// Resolves the methods to run from a comma-separated list, falling back to
+/// `cfg.methods` and then to every method, in canonical order.
+pub fn parse_methods(list: Option<&str>, cfg: &BenchConfig) -> Result<Vec<String>> {
+    let requested: Vec<String> = if let Some(raw) = list {
+        raw.split(',')
+            .map(|m| m.trim().to_lowercase())
+            .filter(|m| !m.is_empty())
+            .collect()
+    } else if !cfg.methods.is_empty() {
+        cfg.methods.iter().map(|m| m.to_lowercase()).collect()
+    } else {
+        METHOD_ORDER.iter().map(|m| m.to_string()).collect()
+    };
+    validated_methods(requested)
+}
+
+fn validated_methods(requested: Vec<String>) -> Result<Vec<String>> {
+    if requested.is_empty() {
+        bail!("methods list cannot be empty");
+    }
+
+    for m in &requested {
+        if !METHOD_ORDER.contains(&m.as_str()) {
+            bail!(
+                "unknown method '{m}'. valid methods: {}",
+                METHOD_ORDER.join(",")
+            );
+        }
+    }
+
+    Ok(canonical_method_list(&requested))
+}
+
+/// Instantiates the method registered under `name` in `METHOD_ORDER`.
+pub fn build_method(name: &str) -> Result<Box<dyn ReconstructionMethod>> {
+    let method: Box<dyn ReconstructionMethod> = match name {
+        "equal" => Box::new(EqualMethod),
+        "cov_inflate" => Box::new(CovInflateMethod::new()),
+        "irls_huber" => Box::new(IrlsHuberMethod::new()),
+        "nis_hard" => Box::new(NisGatingMethod::new(NisMode::Hard)),
+        "nis_soft" => Box::new(NisGatingMethod::new(NisMode::Soft)),
+        "dsfb" => Box::new(DsfbAdaptiveMethod::new()),
+        "dsfb_core" => Box::new(DsfbCoreMethod::new()),
+        "ukf" => Box::new(UkfMethod::new()),
+        "vb_adaptive_r" => Box::new(VbAdaptiveRMethod::new()),
+        _ => bail!("unsupported method: {name}"),
+    };
+    Ok(method)
+}
+
+fn baseline_wls_us(model: &DiagnosticModel, data: &SimulationData) -> f64 {
+    let mut acc = TimingAccumulator::default();
+    let weights = vec![1.0; model.groups.len()];
+
+    for frame in &data.measurements {
+        let (_x, solve_time) = solve_group_weighted_wls(model, &frame.y_groups, &weights);
+        acc.observe(solve_time, solve_time);
+    }
+
+    acc.avg_solve_us()
+}
+
+/// Equal-weight WLS estimate at every step, the reference for
+/// `equal_deltas.csv` and `group_nis.csv`.
+fn equal_estimates(model: &DiagnosticModel, data: &SimulationData) -> Vec<DVector<f64>> {
+    let weights = vec![1.0; model.groups.len()];
+    data.measurements
+        .iter()
+        .map(|frame| solve_group_weighted_wls(model, &frame.y_groups, &weights).0)
+        .collect()
+}
+
+/// Equal-weight WLS through the normal equations and as a stacked QR solve,
+/// compared step by step for `solver_comparison.csv`.
+fn solver_comparison_row(
+    cfg: &BenchConfig,
+    model: &DiagnosticModel,
+    data: &SimulationData,
+    seed: u64,
+) -> SolverComparisonRow {
+    let weights = vec![1.0; model.groups.len()];
+    let mut normal_timing = TimingAccumulator::default();
+    let mut qr_timing = TimingAccumulator::default();
+    let mut normal_sq_err = 0.0;
+    let mut qr_sq_err = 0.0;
+    let mut max_solution_diff = 0.0_f64;
+    let mut max_qr_cond_estimate = 0.0_f64;
+
+    for (frame, x_true) in data.measurements.iter().zip(&data.x_true) {
+        let (x_normal, normal_time) = solve_group_weighted_wls(model, &frame.y_groups, &weights);
+        let (x_qr, qr_time, cond_estimate) =
+            solve_group_weighted_stacked_qr(model, &frame.y_groups, &weights);
+        normal_timing.observe(normal_time, normal_time);
+        qr_timing.observe(qr_time, qr_time);
+        normal_sq_err += (&x_normal - x_true).norm_squared();
+        qr_sq_err += (&x_qr - x_true).norm_squared();
+        max_solution_diff = max_solution_diff.max((&x_qr - &x_normal).amax());
+        max_qr_cond_estimate = max_qr_cond_estimate.max(cond_estimate);
+    }
+
+    let steps = data.measurements.len().max(1) as f64;
+    SolverComparisonRow {
+        seed,
+        n: cfg.n,
+        k: cfg.group_count(),
+        m: cfg.total_measurements(),
+        normal_eq_us: normal_timing.avg_solve_us(),
+        stacked_qr_us: qr_timing.avg_solve_us(),
+        normal_eq_rms_err: (normal_sq_err / steps).sqrt(),
+        stacked_qr_rms_err: (qr_sq_err / steps).sqrt(),
+        max_solution_diff,
+        max_qr_cond_estimate,
+    }
+}
+
+/// Empirical error-norm quantiles of every method, pooled over all steps and
+/// seeds in `trajectories`, for `error_cdf.csv`.
+fn error_cdf_rows(
+    trajectories: &[TrajectoryRow],
+    methods: &[String],
+    probabilities: &[f64],
+) -> Vec<ErrorCdfRow> {
+    let mut rows = Vec::new();
+    for method in methods {
+        let mut errors: Vec<f64> = trajectories
+            .iter()
+            .filter(|row| row.method == *method)
+            .map(|row| row.err_norm)
+            .collect();
+        if errors.is_empty() {
+            continue;
+        }
+        errors.sort_by(f64::total_cmp);
+        rows.extend(probabilities.iter().map(|&probability| ErrorCdfRow {
+            method: method.clone(),
+            probability,
+            quantile: empirical_quantile(&errors, probability),
+            samples: errors.len(),
+        }));
+    }
+    rows
+}
+
+/// Sign of every entry of `delta`, with entries within rounding of zero
+/// relative to `reference` reported as `0`.
+fn delta_signs(delta: &DVector<f64>, reference: &DVector<f64>) -> String {
+    delta
+        .iter()
+        .zip(reference.iter())
+        .map(|(&d, &r)| {
+            if d.abs() <= EQUAL_DELTA_ZERO_TOL * (1.0 + r.abs()) {
+                '0'
+            } else if d > 0.0 {
+                '+'
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_method(
+    method_name: &str,
+    cfg: &BenchConfig,
+    model: &DiagnosticModel,
+    data: &SimulationData,
+    seed: u64,
+    baseline_us: f64,
+    alpha_beta: Option<(f64, f64)>,
+    keep_trajectories: bool,
+    equal_x: Option<&[DVector<f64>]>,
+) -> Result<MethodRunResult> {
+    let mut method = build_method(method_name)?;
+    method.reset(cfg, model);
+
+    let mut metrics_acc = MetricsAccumulator::new(method.has_weights());
+    let mut timing_acc = TimingAccumulator::default();
+    let mut solver_acc = SolverAccumulator::default();
+    let mut trajectories = Vec::with_capacity(data.t.len());
+    let mut state_acc = cfg
+        .state_error_dims
+        .as_deref()
+        .map(StateErrorAccumulator::new);
+    let mut state_errors = Vec::new();
+    let mut equal_deltas = Vec::new();
+    let mut weight_history = Vec::with_capacity(data.t.len());
+
+    for step in 0..data.t.len() {
+        let out = method.estimate(model, &data.measurements[step].y_groups);
+        let err = &out.x_hat - &data.x_true[step];
+        let err_norm = err.norm();
+
+        if let Some(equal_x) = equal_x {
+            let delta = &out.x_hat - &equal_x[step];
+            equal_deltas.push(EqualDeltaRow {
+                t: data.t[step],
+                method: method.name().to_string(),
+                seed,
+                delta_norm: delta.norm(),
+                delta_signs: delta_signs(&delta, &equal_x[step]),
+            });
+        }
+
+        if let Some(acc) = state_acc.as_mut() {
+            let errors = acc.observe(err.as_slice());
+            if keep_trajectories {
+                state_errors.push(StateErrorRow {
+                    t: data.t[step],
+                    method: method.name().to_string(),
+                    seed,
+                    errors,
+                });
+            }
+        }
+
+        metrics_acc.observe(
+            err_norm,
+            out.group_weights.as_deref(),
+            &data.group_corrupted[step],
+        );
+        if let Some(weights) = &out.group_weights {
+            weight_history.push(weights.clone());
+        }
+        timing_acc.observe(out.solve_time, out.total_time);
+        if let Some(solver) = out.solver {
+            solver_acc.observe(solver);
+        }
+
+        if keep_trajectories {
+            trajectories.push(TrajectoryRow {
+                t: data.t[step],
+                method: method.name().to_string(),
+                err_norm,
+                weights: out.group_weights,
+            });
+        }
+    }
+
+    let metrics = metrics_acc.finalize();
+    let solver = solver_acc.finalize();
+    let total_us = timing_acc.avg_total_us();
+    let overhead_us = (total_us - baseline_us).max(0.0);
+    let (response, detection) = if weight_history.len() == data.t.len() {
+        let corrupted_weights: Vec<f64> = weight_history
+            .iter()
+            .map(|weights| weights[cfg.corruption_group])
+            .collect();
+        (
+            weight_response(
+                &corrupted_weights,
+                &data.group_active(cfg.corruption_group),
+                cfg.dt,
+            ),
+            detection_metrics(&weight_history, &data.group_corrupted, cfg.dt),
+        )
+    } else {
+        (WeightResponse::default(), DetectionMetrics::default())
+    };
+
+    let summary = SummaryRow {
+        method: method.name().to_string(),
+        seed,
+        n: cfg.n,
+        k: cfg.group_count(),
+        m: cfg.total_measurements(),
+        peak_err: metrics.peak_err,
+        rms_err: metrics.rms_err,
+        false_downweight_rate: metrics.false_downweight_rate,
+        true_downweight_rate: metrics.true_downweight_rate,
+        baseline_wls_us: baseline_us,
+        overhead_us,
+        total_us,
+        alpha: alpha_beta.map(|v| v.0),
+        beta: alpha_beta.map(|v| v.1),
+        noise_ratio: cfg.noise_ratio(),
+        mean_iterations: solver.map(|s| s.mean_iterations),
+        max_iterations: solver.map(|s| s.max_iterations),
+        nonconvergence_rate: solver.map(|s| s.nonconvergence_rate),
+        downweight_tau_s: response.downweight_tau,
+        recovery_tau_s: response.recovery_tau,
+        detection_latency_s: detection.detection_latency,
+        recovery_latency_s: detection.recovery_latency,
+        detection_auc: detection.auc,
+        tags: cfg.tags.clone(),
+    };
+
+    let state_summary = state_acc
+        .map(|acc| {
+            acc.finalize()
+                .into_iter()
+                .map(|m| StateSummaryRow {
+                    method: method.name().to_string(),
+                    seed,
+                    state: m.state,
+                    peak_err: m.peak_err,
+                    rms_err: m.rms_err,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let group_downweights = metrics
+        .groups
+        .iter()
+        .enumerate()
+        .map(|(group, rates)| {
+            let detected = detection.groups.get(group).copied().unwrap_or_default();
+            GroupDownweightRow {
+                method: method.name().to_string(),
+                seed,
+                group,
+                clean_steps: rates.clean_steps,
+                false_downweight_rate: rates.false_downweight_rate(),
+                corrupted_steps: rates.corrupted_steps,
+                true_downweight_rate: rates.true_downweight_rate(),
+                episodes: detected.episodes,
+                detected_episodes: detected.detected_episodes,
+                detection_latency_s: detected.detection_latency,
+                recovery_latency_s: detected.recovery_latency,
+            }
+        })
+        .collect();
+
+    Ok(MethodRunResult {
+        summary,
+        trajectories,
+        state_errors,
+        state_summary,
+        equal_deltas,
+        group_downweights,
+    })
+}
+
+/// Per-seed metrics of one heatmap cell, in seed order.
+#[derive(Debug, Default, Clone)]
+struct HeatAgg {
+    seeds: Vec<u64>,
+    peak: Vec<f64>,
+    rms: Vec<f64>,
+    false_rates: Vec<f64>,
+}
+
+impl HeatAgg {
+    fn push(&mut self, row: &SummaryRow) {
+        self.seeds.push(row.seed);
+        self.peak.push(row.peak_err);
+        self.rms.push(row.rms_err);
+        if let Some(v) = row.false_downweight_rate {
+            self.false_rates.push(v);
+        }
+    }
+
+    /// Index of the seed with the largest `rms_err`; the first one on ties.
+    fn worst(&self) -> Option<usize> {
+        (0..self.rms.len()).reduce(|worst, i| {
+            if self.rms[i] > self.rms[worst] {
+                i
+            } else {
+                worst
+            }
+        })
+    }
+}
+
+/// One noise/alpha/beta grid point of a sweep.
+struct SweepCell {
+    cfg: BenchConfig,
+    sim_model: DiagnosticModel,
+    model: DiagnosticModel,
+    alpha: f64,
+    beta: f64,
+}
+
+/// Maps `f` over `items` on `jobs` worker threads (0 uses all cores),
+/// returning the results in input order.
+fn parallel_map<T: Sync, R: Send>(
+    jobs: usize,
+    items: &[T],
+    f: impl Fn(&T) -> R + Sync + Send,
+) -> Result<Vec<R>> {
+    if jobs == 1 {
+        return Ok(items.iter().map(f).collect());
+    }
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .context("failed to start the --jobs thread pool")?;
+    Ok(pool.install(|| items.par_iter().map(f).collect()))
+}
+
+/// Runs benchmarks for one config and method list.
+///
+/// ```no_run
+/// # fn main() -> anyhow::Result<()> {
+/// use dsfb_fusion_bench::runner::BenchRunner;
+/// use dsfb_fusion_bench::sim::state::BenchConfig;
+///
+/// let cfg = BenchConfig::from_toml_file("configs/default.toml".as_ref())?;
+/// let runner = BenchRunner::new(cfg)?.with_methods(&["equal", "dsfb"])?;
+/// let results = runner.run_default()?;
+/// for row in &results.summary {
+///     println!("{} seed {}: rms_err {:.4}", row.method, row.seed, row.rms_err);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct BenchRunner {
+    cfg: BenchConfig,
+    methods: Vec<String>,
+    jobs: usize,
+}
+
+impl BenchRunner {
+    /// Validates `cfg` and runs its `methods` (every method when empty) on
+    /// one thread.
+    pub fn new(cfg: BenchConfig) -> Result<Self> {
+        cfg.validate()?;
+        let methods = parse_methods(None, &cfg)?;
+        Ok(Self {
+            cfg,
+            methods,
+            jobs: 1,
+        })
+    }
+
+    /// Replaces the method list; names are checked and put in canonical order.
+    pub fn with_methods<S: AsRef<str>>(mut self, methods: &[S]) -> Result<Self> {
+        self.methods = validated_methods(
+            methods
+                .iter()
+                .map(|m| m.as_ref().trim().to_lowercase())
+                .collect(),
+        )?;
+        Ok(self)
+    }
+
+    /// Worker threads for sweep grid points and seeds (0 uses all cores).
+    pub fn with_jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs;
+        self
+    }
+
+    pub fn config(&self) -> &BenchConfig {
+        &self.cfg
+    }
+
+    pub fn methods(&self) -> &[String] {
+        &self.methods
+    }
+
+    /// Runs `mode` in memory.
+    pub fn run(&self, mode: RunMode) -> Result<RunResults> {
+        Ok(match mode {
+            RunMode::Default => RunResults::Default(self.run_default()?),
+            RunMode::Sweep => RunResults::Sweep(self.run_sweep()?),
+            RunMode::Null => RunResults::Null(self.run_null()?),
+            RunMode::EdgeCases => RunResults::EdgeCases(self.run_edge_cases()?),
+            RunMode::BenchSolver => RunResults::BenchSolver(self.run_bench_solver()?),
+        })
+    }
+
+    /// Runs `mode` into `outdir` and writes the manifest with output hashes.
+    ///
+    /// A sweep checkpoints every method run in `outdir` and resumes from a
+    /// checkpoint already there; `emit_tables` applies to default and sweep
+    /// runs only.
+    pub fn execute(
+        &self,
+        mode: RunMode,
+        outdir: &Path,
+        emit_tables: bool,
+        rerun_of: Option<&Path>,
+    ) -> Result<(Manifest, RunResults)> {
+        let emit_tables = emit_tables && matches!(mode, RunMode::Default | RunMode::Sweep);
+        let results = match mode {
+            RunMode::Sweep => {
+                let checkpoint = SweepCheckpoint::open(
+                    outdir,
+                    &CheckpointHeader {
+                        config: self.cfg.clone(),
+                        methods: self.methods.clone(),
+                        emit_tables,
+                    },
+                )?;
+                let results = self.sweep(Some(&checkpoint))?;
+                self.write_sweep(&results, outdir, emit_tables)?;
+                checkpoint.finish()?;
+                RunResults::Sweep(results)
+            }
+            mode => {
+                let results = self.run(mode)?;
+                self.write(&results, outdir, emit_tables)?;
+                results
+            }
+        };
+
+        let mut manifest = Manifest::new(
+            mode.name(),
+            mode.note(),
+            &self.cfg,
+            &self.methods,
+            emit_tables,
+        );
+        if mode == RunMode::EdgeCases {
+            // Edge-case frames are not preprocessed: a zero-noise group cannot be normalized.
+            manifest.preprocessing.clear();
+        }
+        manifest.output_hashes = output_hashes(outdir)?;
+        manifest.rerun_of = rerun_of.map(Path::to_path_buf);
+        write_manifest_json(outdir, &manifest)?;
+        Ok((manifest, results))
+    }
+
+    /// Writes the output files of `results` into `outdir`.
+    pub fn write(&self, results: &RunResults, outdir: &Path, emit_tables: bool) -> Result<()> {
+        let cfg = &self.cfg;
+        match results {
+            RunResults::Default(results) => self.write_default(results, outdir, emit_tables),
+            RunResults::Sweep(results) => self.write_sweep(results, outdir, emit_tables),
+            RunResults::Null(results) => {
+                write_null_summary_csv(
+                    &outdir.join("null_summary.csv"),
+                    &results.summary,
+                    cfg.group_count(),
+                )?;
+                write_trajectories_csv(
+                    &outdir.join("null_trajectories.csv"),
+                    &results.trajectories,
+                    cfg.group_count(),
+                )
+            }
+            RunResults::EdgeCases(rows) => {
+                write_edge_cases_csv(&outdir.join("edge_cases.csv"), rows)
+            }
+            RunResults::BenchSolver(rows) => {
+                write_solver_bench_csv(&outdir.join("solver_bench.csv"), rows)
+            }
+        }
+    }
+
+    pub fn run_default(&self) -> Result<DefaultResults> {
+        let cfg = &self.cfg;
+        let sim_model = build_diagnostic_model(cfg)?;
+        let model = preprocessed_model(cfg, &sim_model);
+        let mut results = DefaultResults::default();
+
+        let mut seeds = cfg.seeds.clone();
+        seeds.sort_unstable();
+
+        for seed in seeds {
+            let mut data = generate_simulation_data(cfg, &sim_model, seed)?;
+            for (step, labels) in data.fault_labels.iter().enumerate() {
+                results
+                    .fault_labels
+                    .extend(labels.iter().map(|label| FaultLabelRow {
+                        t: data.t[step],
+                        seed,
+                        fault: label.fault,
+                        kind: cfg.faults[label.fault].kind.name(),
+                        group: label.group,
+                        channel: label.channel,
+                    }));
+            }
+            preprocess_data(cfg, &mut data);
+            let baseline_us = baseline_wls_us(&model, &data);
+            let equal_x =
+                (cfg.equal_deltas || cfg.group_nis).then(|| equal_estimates(&model, &data));
+            if let Some(equal_x) = equal_x.as_ref().filter(|_| cfg.group_nis) {
+                for (step, (frame, x_eq)) in data.measurements.iter().zip(equal_x).enumerate() {
+                    results.group_nis.push(GroupNisRow {
+                        t: data.t[step],
+                        seed,
+                        corruption_active: data.corruption_active[step],
+                        nis: compute_group_nis(&model, &frame.y_groups, x_eq),
+                    });
+                }
+            }
+            if cfg.compare_stacked_qr {
+                results
+                    .solver_comparison
+                    .push(solver_comparison_row(cfg, &model, &data, seed));
+            }
+
+            for method_name in &self.methods {
+                let result = run_method(
+                    method_name,
+                    cfg,
+                    &model,
+                    &data,
+                    seed,
+                    baseline_us,
+                    Some((cfg.dsfb_alpha, cfg.dsfb_beta)),
+                    true,
+                    equal_x.as_deref().filter(|_| cfg.equal_deltas),
+                )?;
+                results.summary.push(result.summary);
+                results.trajectories.extend(result.trajectories);
+                results.state_errors.extend(result.state_errors);
+                results.state_summary.extend(result.state_summary);
+                results.equal_deltas.extend(result.equal_deltas);
+                results.group_downweights.extend(result.group_downweights);
+            }
+        }
+
+        results.stats_tests = build_stats_rows(&results.summary, STATS_REFERENCE_METHOD);
+        if let Some(probabilities) = &cfg.error_cdf_probabilities {
+            results.error_cdf = error_cdf_rows(&results.trajectories, &self.methods, probabilities);
+        }
+        Ok(results)
+    }
+
+    fn write_default(
+        &self,
+        results: &DefaultResults,
+        outdir: &Path,
+        emit_tables: bool,
+    ) -> Result<()> {
+        let cfg = &self.cfg;
+        write_summary_csv(&outdir.join("summary.csv"), &results.summary)?;
+        write_stats_tests_csv(&outdir.join("stats_tests.csv"), &results.stats_tests)?;
+        write_heatmap_csv(&outdir.join("heatmap.csv"), &[])?;
+        write_trajectories_csv(
+            &outdir.join("trajectories.csv"),
+            &results.trajectories,
+            cfg.group_count(),
+        )?;
+        write_trajectories_csv(
+            &outdir.join("sim-dsfb-fusion-bench.csv"),
+            &results.trajectories,
+            cfg.group_count(),
+        )?;
+        write_group_downweight_csv(
+            &outdir.join("group_downweight.csv"),
+            &results.group_downweights,
+        )?;
+        if let Some(dims) = &cfg.state_error_dims {
+            write_state_errors_csv(
+                &outdir.join("state_errors.csv"),
+                &results.state_errors,
+                dims,
+            )?;
+            write_state_summary_csv(&outdir.join("state_summary.csv"), &results.state_summary)?;
+        }
+        if cfg.equal_deltas {
+            write_equal_deltas_csv(&outdir.join("equal_deltas.csv"), &results.equal_deltas)?;
+        }
+        if cfg.group_nis {
+            write_group_nis_csv(
+                &outdir.join("group_nis.csv"),
+                &results.group_nis,
+                cfg.group_count(),
+            )?;
+        }
+        if !cfg.faults.is_empty() {
+            write_fault_labels_csv(&outdir.join("fault_labels.csv"), &results.fault_labels)?;
+        }
+        if cfg.error_cdf_probabilities.is_some() {
+            write_error_cdf_csv(&outdir.join("error_cdf.csv"), &results.error_cdf)?;
+        }
+        if cfg.compare_stacked_qr {
+            write_solver_comparison_csv(
+                &outdir.join("solver_comparison.csv"),
+                &results.solver_comparison,
+            )?;
+        }
+
+        if emit_tables {
+            let caption = format!(
+                "DSFB fusion benchmark, mean and standard deviation across {} seed(s)",
+                cfg.seeds.len()
+            );
+            write_tables(outdir, &results.summary, &caption)?;
+        }
+        Ok(())
+    }
+
+    /// Runs the noise/alpha/beta grid without a checkpoint.
+    pub fn run_sweep(&self) -> Result<SweepResults> {
+        self.sweep(None)
+    }
+
+    fn sweep(&self, checkpoint: Option<&SweepCheckpoint>) -> Result<SweepResults> {
+        let cfg = &self.cfg;
+        let methods = &self.methods;
+        let alpha_values = cfg
+            .alpha_values
+            .clone()
+            .context("sweep requires alpha_values in config")?;
+        let beta_values = cfg
+            .beta_values
+            .clone()
+            .context("sweep requires beta_values in config")?;
+
+        if alpha_values.is_empty() || beta_values.is_empty() {
+            bail!("alpha_values and beta_values must be non-empty for sweep");
+        }
+
+        let mut alphas = alpha_values;
+        let mut betas = beta_values;
+        alphas.sort_by(|a, b| a.total_cmp(b));
+        betas.sort_by(|a, b| a.total_cmp(b));
+
+        let mut seeds = cfg.seeds.clone();
+        seeds.sort_unstable();
+
+        let noise_sets = cfg
+            .noise_std_values
+            .clone()
+            .unwrap_or_else(|| vec![cfg.noise_std.clone()]);
+
+        let mut cells = Vec::with_capacity(noise_sets.len() * alphas.len() * betas.len());
+        for noise_std in &noise_sets {
+            for &alpha in &alphas {
+                for &beta in &betas {
+                    let mut cfg_ab = cfg.clone();
+                    cfg_ab.noise_std = noise_std.clone();
+                    cfg_ab.dsfb_alpha = alpha;
+                    cfg_ab.dsfb_beta = beta;
+
+                    let sim_model = build_diagnostic_model(&cfg_ab)?;
+                    let model = preprocessed_model(&cfg_ab, &sim_model);
+                    cells.push(SweepCell {
+                        cfg: cfg_ab,
+                        sim_model,
+                        model,
+                        alpha,
+                        beta,
+                    });
+                }
+            }
+        }
+
+        // Every (cell, seed) pair is independent; results come back in grid
+        // order, so the outputs do not depend on `jobs`.
+        let work: Vec<(&SweepCell, u64)> = cells
+            .iter()
+            .flat_map(|cell| seeds.iter().map(move |&seed| (cell, seed)))
+            .collect();
+        let results = parallel_map(self.jobs, &work, |&(cell, seed)| {
+            let done: Vec<Option<SummaryRow>> = methods
+                .iter()
+                .map(|method_name| {
+                    checkpoint
+                        .and_then(|checkpoint| {
+                            checkpoint.completed(
+                                &cell.cfg.noise_std,
+                                cell.alpha,
+                                cell.beta,
+                                seed,
+                                method_name,
+                            )
+                        })
+                        .cloned()
+                })
+                .collect();
+            if done.iter().all(Option::is_some) {
+                return Ok(done.into_iter().flatten().collect());
+            }
+
+            let mut data = generate_simulation_data(&cell.cfg, &cell.sim_model, seed)?;
+            preprocess_data(&cell.cfg, &mut data);
+            let baseline_us = baseline_wls_us(&cell.model, &data);
+
+            methods
+                .iter()
+                .zip(done)
+                .map(|(method_name, done)| {
+                    if let Some(summary) = done {
+                        return Ok(summary);
+                    }
+                    let result = run_method(
+                        method_name,
+                        &cell.cfg,
+                        &cell.model,
+                        &data,
+                        seed,
+                        baseline_us,
+                        Some((cell.alpha, cell.beta)),
+                        false,
+                        None,
+                    )?;
+                    if let Some(checkpoint) = checkpoint {
+                        checkpoint.record(&cell.cfg.noise_std, &result.summary)?;
+                    }
+                    Ok(result.summary)
+                })
+                .collect::<Result<Vec<_>>>()
+        })?;
+
+        let mut summary_rows = Vec::<SummaryRow>::new();
+        let mut heatmap_rows = Vec::<HeatmapRow>::new();
+        let mut results = results.into_iter();
+
+        for cell in &cells {
+            let mut aggs = vec![HeatAgg::default(); methods.len()];
+            for _ in &seeds {
+                let runs = results.next().expect("one result per cell and seed")?;
+                for (agg, summary) in aggs.iter_mut().zip(runs) {
+                    agg.push(&summary);
+                    summary_rows.push(summary);
+                }
+            }
+
+            for (idx, method_name) in methods.iter().enumerate() {
+                let agg = &aggs[idx];
+                let (Some(peak), Some(rms), Some(worst)) =
+                    (mean_std(&agg.peak), mean_std(&agg.rms), agg.worst())
+                else {
+                    continue;
+                };
+                let false_rate = mean_std(&agg.false_rates);
+                heatmap_rows.push(HeatmapRow {
+                    alpha: cell.alpha,
+                    beta: cell.beta,
+                    noise_ratio: cell.cfg.noise_ratio(),
+                    noise_std: cell.cfg.noise_std.clone(),
+                    method: method_name.clone(),
+                    peak_err: peak.mean,
+                    rms_err: rms.mean,
+                    false_downweight_rate: false_rate.map(|v| v.mean),
+                    peak_err_std: peak.std,
+                    rms_err_std: rms.std,
+                    false_downweight_rate_std: false_rate.map(|v| v.std),
+                    worst_seed: agg.seeds[worst],
+                    worst_peak_err: agg.peak[worst],
+                    worst_rms_err: agg.rms[worst],
+                    tags: cfg.tags.clone(),
+                });
+            }
+        }
+
+        let (best_params, marginals) = match &cfg.sweep_objectives {
+            Some(objectives) => (
+                best_params_rows(&heatmap_rows, objectives),
+                marginal_rows(&heatmap_rows, objectives),
+            ),
+            None => (Vec::new(), Vec::new()),
+        };
+
+        Ok(SweepResults {
+            summary: summary_rows,
+            heatmap: heatmap_rows,
+            best_params,
+            marginals,
+            cells: cells.len(),
+            seeds: seeds.len(),
+            resumed_runs: checkpoint.map_or(0, SweepCheckpoint::completed_runs),
+        })
+    }
+
+    fn write_sweep(&self, results: &SweepResults, outdir: &Path, emit_tables: bool) -> Result<()> {
+        let cfg = &self.cfg;
+        let default_summary_path = outdir.join("summary.csv");
+        let traj_path = outdir.join("trajectories.csv");
+        let sim_path = outdir.join("sim-dsfb-fusion-bench.csv");
+
+        write_summary_csv(&outdir.join("summary_sweep.csv"), &results.summary)?;
+        if !default_summary_path.exists() {
+            write_summary_csv(&default_summary_path, &results.summary)?;
+        }
+        write_heatmap_csv(&outdir.join("heatmap.csv"), &results.heatmap)?;
+        if cfg.sweep_objectives.is_some() {
+            write_best_params_csv(&outdir.join("best_params.csv"), &results.best_params)?;
+            write_marginals_csv(&outdir.join("marginals.csv"), &results.marginals)?;
+        }
+        if !traj_path.exists() {
+            write_trajectories_csv(&traj_path, &[], cfg.group_count())?;
+        }
+        if !sim_path.exists() {
+            write_trajectories_csv(&sim_path, &[], cfg.group_count())?;
+        }
+
+        if emit_tables {
+            let caption = format!(
+                "DSFB fusion benchmark sweep, mean and standard deviation pooled across {} noise/alpha/beta cell(s) and {} seed(s)",
+                results.cells, results.seeds
+            );
+            write_tables(outdir, &results.summary, &caption)?;
+        }
+        Ok(())
+    }
+
+    /// Runs the weighted methods on fault-free data; unweighted methods are skipped.
+    pub fn run_null(&self) -> Result<NullResults> {
+        let cfg = &self.cfg;
+        let sim_model = build_diagnostic_model(cfg)?;
+        let model = preprocessed_model(cfg, &sim_model);
+        let mut results = NullResults::default();
+
+        let mut seeds = cfg.seeds.clone();
+        seeds.sort_unstable();
+
+        for seed in seeds {
+            let mut data = generate_null_simulation_data(cfg, &sim_model, seed)?;
+            preprocess_data(cfg, &mut data);
+
+            for method_name in &self.methods {
+                let mut method = build_method(method_name)?;
+                if !method.has_weights() {
+                    continue;
+                }
+                method.reset(cfg, &model);
+
+                let mut null_acc = NullAccumulator::default();
+                for step in 0..data.t.len() {
+                    let out = method.estimate(&model, &data.measurements[step].y_groups);
+                    let err_norm = (&out.x_hat - &data.x_true[step]).norm();
+                    if let Some(weights) = &out.group_weights {
+                        null_acc.observe(weights);
+                    }
+                    results.trajectories.push(TrajectoryRow {
+                        t: data.t[step],
+                        method: method.name().to_string(),
+                        err_norm,
+                        weights: out.group_weights,
+                    });
+                }
+
+                let metrics = null_acc.finalize();
+                results.summary.push(NullSummaryRow {
+                    method: method.name().to_string(),
+                    seed,
+                    min_weight: metrics.min_weight(),
+                    min_group_weights: metrics.min_group_weights.clone(),
+                    excursion_steps: metrics.excursion_steps,
+                    excursion_events: metrics.excursion_events,
+                    longest_excursion: metrics.longest_excursion,
+                    excursion_rate: metrics.excursion_rate(),
+                });
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Runs every method through the edge-case scenario pack; one row per check.
+    pub fn run_edge_cases(&self) -> Result<Vec<EdgeCaseRow>> {
+        let mut rows = Vec::<EdgeCaseRow>::new();
+
+        let mut seeds = self.cfg.seeds.clone();
+        seeds.sort_unstable();
+
+        for case in EdgeCase::ALL {
+            for &seed in &seeds {
+                let scenario = build_edge_case(&self.cfg, case, seed)?;
+                for method_name in &self.methods {
+                    let mut method = build_method(method_name)?;
+                    for check in run_edge_case(&scenario, method.as_mut()) {
+                        rows.push(EdgeCaseRow {
+                            scenario: case.name().to_string(),
+                            method: method.name().to_string(),
+                            seed,
+                            check: check.check.to_string(),
+                            passed: check.passed,
+                            value: check.value,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(rows)
+    }
+
+    /// Micro-benchmarks the equal-weight WLS solve and every method's `estimate`
+    /// on the frames of the first seed, stepping through them in order.
+    ///
+    /// Stateful methods carry their state from call to call and are reset when
+    /// the frames wrap around, as at the start of a run.
+    pub fn run_bench_solver(&self) -> Result<Vec<SolverBenchRow>> {
+        let cfg = &self.cfg;
+        let sim_model = build_diagnostic_model(cfg)?;
+        let model = preprocessed_model(cfg, &sim_model);
+        let seed = cfg
+            .seeds
+            .iter()
+            .copied()
+            .min()
+            .expect("validated non-empty seeds");
+        let mut data = generate_simulation_data(cfg, &sim_model, seed)?;
+        preprocess_data(cfg, &mut data);
+        let frames = &data.measurements;
+
+        let settings = BenchSettings {
+            warmup: cfg.bench_warmup,
+            samples: cfg.bench_samples,
+            batch: cfg.bench_batch,
+        };
+        let row = |method: &str, stats: Option<SampleStats>| SolverBenchRow {
+            method: method.to_string(),
+            n: cfg.n,
+            k: cfg.group_count(),
+            m: cfg.total_measurements(),
+            warmup: settings.warmup,
+            batch: settings.batch,
+            stats: stats.expect("validated bench_samples > 0"),
+        };
+
+        let mut rows = Vec::<SolverBenchRow>::new();
+        let equal_weights = vec![1.0; model.groups.len()];
+        let mut step = 0;
+        let stats = bench(settings, || {
+            std::hint::black_box(solve_group_weighted_wls(
+                &model,
+                &frames[step].y_groups,
+                &equal_weights,
+            ));
+            step = (step + 1) % frames.len();
+        });
+        rows.push(row("baseline_wls", stats));
+
+        for method_name in &self.methods {
+            let mut method = build_method(method_name)?;
+            method.reset(cfg, &model);
+            // Rewind before a run of calls that would pass the last frame, so
+            // the reset stays outside the timed batch
+            let stats = bench_with_setup(
+                settings,
+                &mut (method, 0usize),
+                |(method, step), calls| {
+                    if *step + calls > frames.len() {
+                        *step = 0;
+                        method.reset(cfg, &model);
+                    }
+                },
+                |(method, step)| {
+                    std::hint::black_box(method.estimate(&model, &frames[*step].y_groups));
+                    *step += 1;
+                },
+            );
+            rows.push(row(method_name, stats));
+        }
+
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delta_signs_treat_rounding_relative_to_the_reference_as_zero() {
+        let reference = DVector::from_vec(vec![0.0, 1e6, 1.0, -2.0]);
+        // 1e-13 is below the tolerance at a zero reference, and 1e-7 is below
+        // it at 1e6; the last two entries are well clear of it
+        let delta = DVector::from_vec(vec![1e-13, -1e-7, 1e-3, -1e-3]);
+        assert_eq!(delta_signs(&delta, &reference), "00+-");
+
+        let at_tolerance = DVector::from_vec(vec![EQUAL_DELTA_ZERO_TOL]);
+        let reference = DVector::from_vec(vec![0.0]);
+        assert_eq!(delta_signs(&at_tolerance, &reference), "0");
+        assert_eq!(delta_signs(&(at_tolerance * 3.0), &reference), "+");
+    }
+}
@@ -1,29 +1,376 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use nalgebra::{DMatrix, DVector};
 use rand::distributions::{Distribution as RandDistribution, Uniform};
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 use rand_distr::Normal;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
 
-use crate::sim::state::BenchConfig;
+use crate::sim::state::{BenchConfig, WlsSummation};
+
+/// Offset from `matrix_seed` of the stream placing nonlinear landmarks, so
+/// linear groups keep the same `H` whether or not nonlinear groups are set.
+const LANDMARK_SEED_OFFSET: u64 = 0x4c41_4e44_4d41_524b;
+/// Distance of every landmark from the origin of its state plane.
+const LANDMARK_RADIUS: f64 = 1.0;
+/// Relative step of the central-difference Jacobian.
+const NUMERIC_JACOBIAN_STEP: f64 = 1e-6;
+
+/// Measurement function of a nonlinear group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NonlinearKind {
+    /// Distance from a landmark to the point `(x_p, x_q)`.
+    Range,
+    /// Angle of the point `(x_p, x_q)` seen from a landmark, in radians.
+    Bearing,
+}
+
+/// How the Jacobian of a nonlinear group is evaluated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JacobianMode {
+    /// Closed-form derivatives of the measurement function.
+    #[default]
+    Analytic,
+    /// Central differences of the measurement function.
+    Numeric,
+}
+
+/// A `[[nonlinear_groups]]` entry: replaces the linear `H` of `group` with a
+/// range or bearing measurement per channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NonlinearGroupSpec {
+    pub group: usize,
+    pub kind: NonlinearKind,
+    #[serde(default)]
+    pub jacobian: JacobianMode,
+}
+
+/// Channel `i` of a nonlinear group observes the state pair `(x_p, x_q)`
+/// from the landmark `anchor`.
+#[derive(Debug, Clone, Copy)]
+pub struct Landmark {
+    pub p: usize,
+    pub q: usize,
+    pub anchor: [f64; 2],
+}
+
+#[derive(Debug, Clone)]
+pub struct NonlinearMeasurement {
+    pub kind: NonlinearKind,
+    pub jacobian: JacobianMode,
+    pub landmarks: Vec<Landmark>,
+    /// Factor applied to every prediction, changed by normalization.
+    pub scale: f64,
+}
+
+impl NonlinearMeasurement {
+    fn channel(&self, landmark: &Landmark, x: &DVector<f64>) -> f64 {
+        let dx = x[landmark.p] - landmark.anchor[0];
+        let dy = x[landmark.q] - landmark.anchor[1];
+        let value = match self.kind {
+            NonlinearKind::Range => dx.hypot(dy),
+            NonlinearKind::Bearing => dy.atan2(dx),
+        };
+        self.scale * value
+    }
+
+    fn predict(&self, x: &DVector<f64>) -> DVector<f64> {
+        DVector::from_iterator(
+            self.landmarks.len(),
+            self.landmarks.iter().map(|l| self.channel(l, x)),
+        )
+    }
+
+    fn jacobian(&self, n: usize, x: &DVector<f64>) -> DMatrix<f64> {
+        let mut jac = DMatrix::<f64>::zeros(self.landmarks.len(), n);
+        for (i, landmark) in self.landmarks.iter().enumerate() {
+            let (dp, dq) = match self.jacobian {
+                JacobianMode::Analytic => {
+                    let dx = x[landmark.p] - landmark.anchor[0];
+                    let dy = x[landmark.q] - landmark.anchor[1];
+                    let r2 = (dx * dx + dy * dy).max(1e-12);
+                    match self.kind {
+                        NonlinearKind::Range => (dx / r2.sqrt(), dy / r2.sqrt()),
+                        NonlinearKind::Bearing => (-dy / r2, dx / r2),
+                    }
+                }
+                JacobianMode::Numeric => {
+                    let partial = |col: usize| {
+                        let step = NUMERIC_JACOBIAN_STEP * (1.0 + x[col].abs());
+                        let mut shifted = x.clone();
+                        shifted[col] = x[col] + step;
+                        let plus = self.channel(landmark, &shifted);
+                        shifted[col] = x[col] - step;
+                        let minus = self.channel(landmark, &shifted);
+                        self.difference(plus, minus) / (2.0 * step * self.scale)
+                    };
+                    (partial(landmark.p), partial(landmark.q))
+                }
+            };
+            jac[(i, landmark.p)] += self.scale * dp;
+            jac[(i, landmark.q)] += self.scale * dq;
+        }
+        jac
+    }
+
+    /// `a - b` for two predictions, wrapped to `(-pi, pi]` for bearings.
+    fn difference(&self, a: f64, b: f64) -> f64 {
+        match self.kind {
+            NonlinearKind::Range => a - b,
+            NonlinearKind::Bearing => wrap_angle((a - b) / self.scale) * self.scale,
+        }
+    }
+}
+
+fn wrap_angle(angle: f64) -> f64 {
+    let wrapped = (angle + PI).rem_euclid(2.0 * PI) - PI;
+    if wrapped == -PI {
+        PI
+    } else {
+        wrapped
+    }
+}
+
+/// Offset from `matrix_seed` of the matrices `mixing` drifts move toward.
+const DRIFT_SEED_OFFSET: u64 = 0x4452_4946_5448;
+
+/// How a `[[model_drift]]` entry changes the true model of its group.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DriftKind {
+    /// Measurement gain scaled toward `factor`: `H` of a linear group, the
+    /// prediction of a nonlinear one.
+    Gain { factor: f64 },
+    /// Noise standard deviation scaled toward `factor`, a degrading sensor.
+    Noise { factor: f64 },
+    /// `H` interpolated toward an independently drawn matrix `H'`, reaching
+    /// `(1 - amount) H + amount H'`; linear groups only.
+    Mixing { amount: f64 },
+}
+
+/// One `[[model_drift]]` config entry: the true measurement model of `group`
+/// drifts from step `start`, ramping linearly over `ramp_steps` steps (a step
+/// change when 0) and holding afterwards. The methods keep estimating with the
+/// nominal model, so the drift is a model mismatch they must absorb.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelDriftSpec {
+    #[serde(flatten)]
+    pub kind: DriftKind,
+    pub group: usize,
+    pub start: usize,
+    #[serde(default)]
+    pub ramp_steps: usize,
+}
+
+impl ModelDriftSpec {
+    /// Fraction of the drift reached at `step`: 0 before `start`, 1 once the
+    /// ramp is complete.
+    pub fn progress(&self, step: usize) -> f64 {
+        if step < self.start {
+            0.0
+        } else if self.ramp_steps == 0 {
+            1.0
+        } else {
+            ((step - self.start + 1) as f64 / self.ramp_steps as f64).min(1.0)
+        }
+    }
+}
+
+/// Multiplier of the noise standard deviation of group `k` at `step` from
+/// the `noise` drifts in `drifts`.
+pub fn noise_drift_factor(drifts: &[ModelDriftSpec], k: usize, step: usize) -> f64 {
+    drifts
+        .iter()
+        .filter(|spec| spec.group == k)
+        .map(|spec| match spec.kind {
+            DriftKind::Noise { factor } => 1.0 + spec.progress(step) * (factor - 1.0),
+            _ => 1.0,
+        })
+        .product()
+}
+
+/// True measurement model over a run with `[[model_drift]]` entries.
+#[derive(Debug, Clone)]
+pub struct DriftSchedule {
+    drifts: Vec<ModelDriftSpec>,
+    /// `H'` of each group with a `mixing` drift, drawn from its own stream.
+    mixing_targets: Vec<Option<DMatrix<f64>>>,
+}
+
+impl DriftSchedule {
+    pub fn new(cfg: &BenchConfig) -> Result<Self> {
+        let mut mixing_targets = vec![None; cfg.group_count()];
+        if cfg
+            .model_drift
+            .iter()
+            .any(|spec| matches!(spec.kind, DriftKind::Mixing { .. }))
+        {
+            let mut target_cfg = cfg.clone();
+            target_cfg.matrix_seed ^= DRIFT_SEED_OFFSET;
+            target_cfg.nonlinear_groups.clear();
+            target_cfg.noise_correlation = None;
+            let target = build_diagnostic_model(&target_cfg)?;
+            for spec in &cfg.model_drift {
+                if matches!(spec.kind, DriftKind::Mixing { .. }) {
+                    mixing_targets[spec.group] = Some(target.groups[spec.group].h.clone());
+                }
+            }
+        }
+        Ok(Self {
+            drifts: cfg.model_drift.clone(),
+            mixing_targets,
+        })
+    }
+
+    /// Model generating the measurements of `step`, with the `gain` and
+    /// `mixing` drifts applied in config order; `None` while it is nominal.
+    /// `noise` drifts act in [`generate_measurements`].
+    pub fn model_at(&self, model: &DiagnosticModel, step: usize) -> Option<DiagnosticModel> {
+        let mut drifted: Option<DiagnosticModel> = None;
+        for spec in &self.drifts {
+            let progress = spec.progress(step);
+            if progress == 0.0 || matches!(spec.kind, DriftKind::Noise { .. }) {
+                continue;
+            }
+            let group = &mut drifted.get_or_insert_with(|| model.clone()).groups[spec.group];
+            match spec.kind {
+                DriftKind::Gain { factor } => {
+                    let gain = 1.0 + progress * (factor - 1.0);
+                    group.h *= gain;
+                    if let Some(nl) = &mut group.nonlinear {
+                        nl.scale *= gain;
+                    }
+                }
+                DriftKind::Mixing { amount } => {
+                    let lambda = progress * amount;
+                    let target = self.mixing_targets[spec.group]
+                        .as_ref()
+                        .expect("mixing target drawn for every mixing drift");
+                    group.h = &group.h * (1.0 - lambda) + target * lambda;
+                }
+                DriftKind::Noise { .. } => {}
+            }
+        }
+        drifted
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct DiagnosticGroup {
+    /// Measurement matrix of a linear group; for a nonlinear group, its
+    /// Jacobian at `x = 0`.
     pub h: DMatrix<f64>,
+    /// Noise variance of each channel, the diagonal of the noise covariance.
     pub r_diag: DVector<f64>,
+    /// Lower Cholesky factor `L` of the full noise covariance `L L^T`, for a
+    /// group with correlated noise; `None` when the noise is `diag(r_diag)`.
+    pub noise_chol: Option<DMatrix<f64>>,
+    /// `L^-1`, which whitens the residuals of a correlated group.
+    pub whitening: Option<DMatrix<f64>>,
     pub bandwidth_mismatch: bool,
+    /// Range or bearing measurement replacing `h`, from `[[nonlinear_groups]]`.
+    pub nonlinear: Option<NonlinearMeasurement>,
 }
 
 impl DiagnosticGroup {
     pub fn dim(&self) -> usize {
         self.h.nrows()
     }
+
+    pub fn is_nonlinear(&self) -> bool {
+        self.nonlinear.is_some()
+    }
+
+    pub fn is_correlated(&self) -> bool {
+        self.whitening.is_some()
+    }
+
+    /// Sets a full noise covariance, keeping `r_diag` as its diagonal.
+    pub fn set_noise_covariance(&mut self, cov: &DMatrix<f64>) -> Result<()> {
+        let Some(chol) = cov.clone().cholesky() else {
+            bail!("noise covariance is not positive definite");
+        };
+        let l = chol.l();
+        let whitening = l
+            .clone()
+            .solve_lower_triangular(&DMatrix::<f64>::identity(l.nrows(), l.nrows()))
+            .context("noise covariance factor is singular")?;
+        self.r_diag = cov.diagonal();
+        self.noise_chol = Some(l);
+        self.whitening = Some(whitening);
+        Ok(())
+    }
+
+    /// Scales the noise standard deviation by `factor`, keeping correlations.
+    pub fn scale_noise(&mut self, factor: f64) {
+        self.r_diag *= factor * factor;
+        if let Some(l) = &mut self.noise_chol {
+            *l *= factor;
+        }
+        if let Some(w) = &mut self.whitening {
+            *w /= factor;
+        }
+    }
+
+    /// Full noise covariance, `diag(r_diag)` unless the noise is correlated.
+    pub fn noise_covariance(&self) -> DMatrix<f64> {
+        match &self.noise_chol {
+            Some(l) => l * l.transpose(),
+            None => DMatrix::from_diagonal(&self.r_diag),
+        }
+    }
+
+    /// Residual scaled to unit noise: `L^-1 v` for a correlated group,
+    /// otherwise each channel divided by its standard deviation.
+    pub fn whiten(&self, v: &DVector<f64>) -> DVector<f64> {
+        match &self.whitening {
+            Some(w) => w * v,
+            None => v.zip_map(&self.r_diag, |vi, r| vi / r.sqrt().max(1e-12)),
+        }
+    }
+
+    /// Noise-free measurement `h(x)`.
+    pub fn predict(&self, x: &DVector<f64>) -> DVector<f64> {
+        match &self.nonlinear {
+            Some(nl) => nl.predict(x),
+            None => &self.h * x,
+        }
+    }
+
+    /// Jacobian of `h` at `x`; `h` itself for a linear group.
+    pub fn jacobian(&self, x: &DVector<f64>) -> DMatrix<f64> {
+        match &self.nonlinear {
+            Some(nl) => nl.jacobian(x.len(), x),
+            None => self.h.clone(),
+        }
+    }
+
+    /// `y - y_pred`, with bearing differences wrapped to `(-pi, pi]`.
+    pub fn innovation(&self, y: &DVector<f64>, y_pred: &DVector<f64>) -> DVector<f64> {
+        match &self.nonlinear {
+            Some(nl) => y.zip_map(y_pred, |a, b| nl.difference(a, b)),
+            None => y - y_pred,
+        }
+    }
+
+    /// Measurement residual `y - h(x)`.
+    pub fn residual(&self, y: &DVector<f64>, x: &DVector<f64>) -> DVector<f64> {
+        match &self.nonlinear {
+            Some(_) => self.innovation(y, &self.predict(x)),
+            None => y - &self.h * x,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct DiagnosticModel {
     pub n: usize,
     pub groups: Vec<DiagnosticGroup>,
+    /// Accumulation used by the WLS solvers on this model.
+    pub summation: WlsSummation,
 }
 
 #[derive(Debug, Clone)]
@@ -35,6 +382,9 @@ pub fn build_diagnostic_model(cfg: &BenchConfig) -> Result<DiagnosticModel> {
     let mut rng = ChaCha8Rng::seed_from_u64(cfg.matrix_seed);
     let uniform = Uniform::new(-0.45_f64, 0.45_f64);
 
+    let mut landmark_rng = ChaCha8Rng::seed_from_u64(cfg.matrix_seed ^ LANDMARK_SEED_OFFSET);
+    let bearing = Uniform::new(-PI, PI);
+
     let mut groups = Vec::with_capacity(cfg.group_count());
     let mut running_offset = 0usize;
 
@@ -58,24 +408,71 @@ pub fn build_diagnostic_model(cfg: &BenchConfig) -> Result<DiagnosticModel> {
         for i in 0..m_k {
             r_diag[i] = sigma * sigma;
         }
+        let rho = cfg.noise_correlation.as_ref().map_or(0.0, |c| c[k]);
+
+        // The linear rows are drawn either way, keeping the matrix stream of
+        // later groups unchanged.
+        let nonlinear = cfg
+            .nonlinear_groups
+            .iter()
+            .find(|spec| spec.group == k)
+            .map(|spec| {
+                let landmarks = (0..m_k)
+                    .map(|r| {
+                        let p = (running_offset + r) % cfg.n;
+                        let angle = bearing.sample(&mut landmark_rng);
+                        Landmark {
+                            p,
+                            q: (p + 1) % cfg.n,
+                            anchor: [LANDMARK_RADIUS * angle.cos(), LANDMARK_RADIUS * angle.sin()],
+                        }
+                    })
+                    .collect();
+                NonlinearMeasurement {
+                    kind: spec.kind,
+                    jacobian: spec.jacobian,
+                    landmarks,
+                    scale: 1.0,
+                }
+            });
+        if let Some(nl) = &nonlinear {
+            h = nl.jacobian(cfg.n, &DVector::<f64>::zeros(cfg.n));
+        }
 
         let mismatch = cfg.bandwidth_groups.contains(&k);
-        groups.push(DiagnosticGroup {
+        let mut group = DiagnosticGroup {
             h,
             r_diag,
+            noise_chol: None,
+            whitening: None,
             bandwidth_mismatch: mismatch,
-        });
+            nonlinear,
+        };
+        // Zero noise (an edge case) has no Cholesky factor and stays diagonal
+        if rho != 0.0 && sigma > 0.0 {
+            let cov = DMatrix::<f64>::from_fn(m_k, m_k, |i, j| {
+                sigma * sigma * rho.powi(i.abs_diff(j) as i32)
+            });
+            group
+                .set_noise_covariance(&cov)
+                .with_context(|| format!("invalid noise covariance for group {k}"))?;
+        }
+        groups.push(group);
         running_offset += m_k;
     }
 
-    Ok(DiagnosticModel { n: cfg.n, groups })
+    Ok(DiagnosticModel {
+        n: cfg.n,
+        groups,
+        summation: cfg.wls_summation,
+    })
 }
 
 pub fn generate_measurements(
     cfg: &BenchConfig,
     model: &DiagnosticModel,
     x_true: &DVector<f64>,
-    _step: usize,
+    step: usize,
     low_pass_state: &mut [Option<DVector<f64>>],
     rng: &mut impl Rng,
 ) -> Result<MeasurementFrame> {
@@ -88,7 +485,7 @@ pub fn generate_measurements(
     let mut y_groups = Vec::with_capacity(model.groups.len());
 
     for (k, group) in model.groups.iter().enumerate() {
-        let ideal = &group.h * x_true;
+        let ideal = group.predict(x_true);
         let mut base = ideal.clone();
 
         if group.bandwidth_mismatch {
@@ -106,13 +503,22 @@ pub fn generate_measurements(
             }
         }
 
-        let sigma = cfg.noise_std[k];
+        let sigma = cfg.noise_std[k] * noise_drift_factor(&cfg.model_drift, k, step);
         let noise_dist = Normal::new(0.0, sigma)
             .with_context(|| format!("failed to create measurement noise for group {k}"))?;
 
         let mut y = base;
-        for i in 0..group.dim() {
-            y[i] += noise_dist.sample(rng);
+        match &group.noise_chol {
+            // The same draws as uncorrelated noise, mixed by the correlation factor
+            Some(l) => {
+                let z = DVector::<f64>::from_fn(group.dim(), |_, _| noise_dist.sample(rng));
+                y += l * z / cfg.noise_std[k];
+            }
+            None => {
+                for i in 0..group.dim() {
+                    y[i] += noise_dist.sample(rng);
+                }
+            }
         }
         y_groups.push(y);
     }
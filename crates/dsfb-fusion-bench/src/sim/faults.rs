@@ -1,3 +1,9 @@
+use anyhow::{bail, Result};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use rand_distr::{Distribution, StandardNormal};
+use serde::{Deserialize, Serialize};
+
 use crate::sim::diagnostics::MeasurementFrame;
 use crate::sim::state::BenchConfig;
 
@@ -15,14 +21,243 @@ pub fn apply_impulse_corruption(
     let local = (step - start) as f64;
     let duration = cfg.corruption_duration as f64;
 
-    // Smooth pulse envelope sampled at bin centers so a 1-step window
-    // still receives full corruption amplitude.
-    let phase = std::f64::consts::PI * ((local + 0.5) / duration);
-    let envelope = phase.sin().abs();
-
     let group = cfg.corruption_group;
     let channel = cfg.corruption_channel;
-    frame.y_groups[group][channel] += cfg.corruption_amplitude * envelope;
+    frame.y_groups[group][channel] += cfg.corruption_amplitude * pulse_envelope(local, duration);
 
     true
 }
+
+/// Smooth pulse envelope sampled at bin centers so a 1-step window still
+/// receives full corruption amplitude.
+fn pulse_envelope(local: f64, duration: f64) -> f64 {
+    let phase = std::f64::consts::PI * ((local + 0.5) / duration);
+    phase.sin().abs()
+}
+
+/// Effect of one `[[faults]]` entry on each targeted channel.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FaultKind {
+    /// Constant `offset` added to the reading.
+    BiasStep { offset: f64 },
+    /// Offset growing by `rate` per second, reaching `rate * dt` at the first
+    /// faulty step.
+    Ramp { rate: f64 },
+    /// The channel reads zero.
+    Dropout,
+    /// The channel reads `value`, or holds its last reading before the
+    /// window when `value` is unset.
+    StuckAt {
+        #[serde(default)]
+        value: Option<f64>,
+    },
+    /// Measurement noise variance multiplied by `factor` (>= 1), drawn from
+    /// a fault-only random stream so the nominal noise is unchanged.
+    VarianceInflation { factor: f64 },
+    /// The half-sine pulse of the `corruption_*` impulse with peak
+    /// `amplitude`; requires a `duration`.
+    Impulse { amplitude: f64 },
+}
+
+impl FaultKind {
+    /// Name written to the `kind` column of `fault_labels.csv`.
+    pub fn name(self) -> &'static str {
+        match self {
+            FaultKind::BiasStep { .. } => "bias_step",
+            FaultKind::Ramp { .. } => "ramp",
+            FaultKind::Dropout => "dropout",
+            FaultKind::StuckAt { .. } => "stuck_at",
+            FaultKind::VarianceInflation { .. } => "variance_inflation",
+            FaultKind::Impulse { .. } => "impulse",
+        }
+    }
+}
+
+/// One `[[faults]]` config entry: a fault on some channels of a group over a
+/// window of steps. Entries may overlap in time and channels; they are
+/// applied in config order, after the `corruption_*` impulse.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FaultSpec {
+    #[serde(flatten)]
+    pub kind: FaultKind,
+    pub group: usize,
+    /// Channels of `group` to corrupt; every channel of the group when unset.
+    #[serde(default)]
+    pub channels: Option<Vec<usize>>,
+    /// First faulty step.
+    pub start: usize,
+    /// Number of faulty steps; the fault lasts to the end of the run when unset.
+    #[serde(default)]
+    pub duration: Option<usize>,
+}
+
+impl FaultSpec {
+    pub fn is_active(&self, step: usize) -> bool {
+        step >= self.start && self.duration.map_or(true, |d| step - self.start < d)
+    }
+
+    /// Targeted channel indices within a group of `group_dim` channels.
+    pub fn target_channels(&self, group_dim: usize) -> Vec<usize> {
+        self.channels
+            .clone()
+            .unwrap_or_else(|| (0..group_dim).collect())
+    }
+
+    /// Checks entry `index` of `faults` against the group layout and run length.
+    pub fn validate(&self, index: usize, group_dims: &[usize], steps: usize) -> Result<()> {
+        let Some(&dim) = group_dims.get(self.group) else {
+            bail!("faults[{index}].group index out of range");
+        };
+        if let Some(channels) = &self.channels {
+            if channels.is_empty() {
+                bail!("faults[{index}].channels must be non-empty when set");
+            }
+            if channels.iter().any(|&c| c >= dim) {
+                bail!(
+                    "faults[{index}].channels entries must be < group_dims[{}]",
+                    self.group
+                );
+            }
+            if channels
+                .iter()
+                .enumerate()
+                .any(|(i, c)| channels[..i].contains(c))
+            {
+                bail!("faults[{index}].channels entries must be unique");
+            }
+        }
+        if self.start >= steps {
+            bail!("faults[{index}].start must be < steps");
+        }
+        if self.duration == Some(0) {
+            bail!("faults[{index}].duration must be > 0");
+        }
+        let finite = match self.kind {
+            FaultKind::BiasStep { offset } => offset.is_finite(),
+            FaultKind::Ramp { rate } => rate.is_finite(),
+            FaultKind::Dropout | FaultKind::StuckAt { value: None } => true,
+            FaultKind::StuckAt { value: Some(value) } => value.is_finite(),
+            FaultKind::VarianceInflation { factor } => {
+                if factor.is_nan() || factor < 1.0 {
+                    bail!("faults[{index}].factor must be >= 1");
+                }
+                factor.is_finite()
+            }
+            FaultKind::Impulse { amplitude } => {
+                if self.duration.is_none() {
+                    bail!("faults[{index}]: impulse faults require a duration");
+                }
+                amplitude.is_finite()
+            }
+        };
+        if !finite {
+            bail!("faults[{index}] parameters must be finite");
+        }
+        Ok(())
+    }
+}
+
+/// Ground truth for one corrupted channel at one step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FaultLabel {
+    /// Index of the entry in `faults`.
+    pub fault: usize,
+    pub group: usize,
+    pub channel: usize,
+}
+
+/// Per-group ground truth at `step`: the `corruption_group` while the impulse
+/// window is active with a non-zero amplitude, and every group a `[[faults]]`
+/// entry touches according to the step's `labels`.
+pub fn corrupted_groups(cfg: &BenchConfig, step: usize, labels: &[FaultLabel]) -> Vec<bool> {
+    let mut corrupted = vec![false; cfg.group_count()];
+    let impulse_end = cfg.corruption_start + cfg.corruption_duration;
+    if cfg.corruption_amplitude != 0.0 && (cfg.corruption_start..impulse_end).contains(&step) {
+        corrupted[cfg.corruption_group] = true;
+    }
+    for label in labels {
+        corrupted[label.group] = true;
+    }
+    corrupted
+}
+
+/// Offset of the fault-injection random stream from the run seed.
+const FAULT_SEED_OFFSET: u64 = 0x6661_756c_7473;
+
+/// Applies the configured `[[faults]]` to successive measurement frames.
+#[derive(Debug, Clone)]
+pub struct FaultInjector {
+    faults: Vec<FaultSpec>,
+    noise_std: Vec<f64>,
+    dt: f64,
+    /// Stream for variance inflation, separate from the measurement noise.
+    rng: ChaCha8Rng,
+    /// Last pre-window reading of each channel of each group, per fault.
+    held: Vec<Vec<Option<f64>>>,
+}
+
+impl FaultInjector {
+    pub fn new(cfg: &BenchConfig, seed: u64) -> Self {
+        Self {
+            faults: cfg.faults.clone(),
+            noise_std: cfg.noise_std.clone(),
+            dt: cfg.dt,
+            rng: ChaCha8Rng::seed_from_u64(seed.wrapping_add(FAULT_SEED_OFFSET)),
+            held: cfg
+                .faults
+                .iter()
+                .map(|fault| vec![None; cfg.group_dims[fault.group]])
+                .collect(),
+        }
+    }
+
+    /// Corrupts `frame`, the measurements of `step`, and returns a label for
+    /// every channel an active fault touched.
+    pub fn apply(&mut self, frame: &mut MeasurementFrame, step: usize) -> Vec<FaultLabel> {
+        let mut labels = Vec::new();
+        for (index, fault) in self.faults.iter().enumerate() {
+            let y = &mut frame.y_groups[fault.group];
+            let held = &mut self.held[index];
+            let channels = fault.target_channels(y.len());
+
+            if !fault.is_active(step) {
+                if step < fault.start {
+                    for &c in &channels {
+                        held[c] = Some(y[c]);
+                    }
+                }
+                continue;
+            }
+
+            let local = (step - fault.start) as f64;
+            for &c in &channels {
+                match fault.kind {
+                    FaultKind::BiasStep { offset } => y[c] += offset,
+                    FaultKind::Ramp { rate } => y[c] += rate * (local + 1.0) * self.dt,
+                    FaultKind::Dropout => y[c] = 0.0,
+                    FaultKind::StuckAt { value } => {
+                        let stuck = value.or(held[c]).unwrap_or(y[c]);
+                        held[c] = Some(stuck);
+                        y[c] = stuck;
+                    }
+                    FaultKind::VarianceInflation { factor } => {
+                        let extra_std = self.noise_std[fault.group] * (factor - 1.0).sqrt();
+                        let z: f64 = StandardNormal.sample(&mut self.rng);
+                        y[c] += extra_std * z;
+                    }
+                    FaultKind::Impulse { amplitude } => {
+                        let duration = fault.duration.unwrap_or(1) as f64;
+                        y[c] += amplitude * pulse_envelope(local, duration);
+                    }
+                }
+                labels.push(FaultLabel {
+                    fault: index,
+                    group: fault.group,
+                    channel: c,
+                });
+            }
+        }
+        labels
+    }
+}
@@ -0,0 +1,111 @@
+//! Upgrades of config files written against an older schema.
+//!
+//! Every config file may declare the `schema_version` it was written for.
+//! Before its `extends` chain is merged, a file that declares an older
+//! version is upgraded one [`Migration`] at a time until it reaches
+//! [`CONFIG_SCHEMA_VERSION`]. Renamed keys are moved to their new names, and
+//! keys that became required get the value older configs implied. Every change
+//! is reported as a warning so the file can be updated. Files that do not
+//! declare a version are taken to be current.
+//!
+//! The config schema is versioned apart from the output layout
+//! (`OUTPUT_SCHEMA_VERSION`): it changes only when config keys are renamed or
+//! become required, not when output columns do. `1.0.0` is the first schema,
+//! so [`MIGRATIONS`] is empty. A config schema bump adds its step here and
+//! keeps older configs loading.
+
+use anyhow::{bail, Context, Result};
+use toml::{Table, Value};
+
+/// Config key holding the schema a file was written for.
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// Current config schema, the version every loaded config is upgraded to.
+pub const CONFIG_SCHEMA_VERSION: &str = "1.0.0";
+
+/// One schema upgrade, from `from` to `to`.
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub from: &'static str,
+    pub to: &'static str,
+    /// Top-level keys renamed by this schema, as `(old, new)`
+    pub renamed: &'static [(&'static str, &'static str)],
+    /// Keys that became required, with the TOML value older configs implied
+    pub defaults: &'static [(&'static str, &'static str)],
+}
+
+/// Upgrade steps, oldest first.
+pub const MIGRATIONS: &[Migration] = &[];
+
+/// Upgrades `table` from its declared `schema_version` to the current schema
+/// with [`MIGRATIONS`], returning one warning per change.
+pub fn migrate_config(table: &mut Table) -> Result<Vec<String>> {
+    apply_migrations(MIGRATIONS, CONFIG_SCHEMA_VERSION, table)
+}
+
+/// Upgrades `table` to `current` by chaining the steps of `migrations`.
+///
+/// Fails when no chain leads from the declared version to `current`, or when
+/// a file sets both the old and the new name of a renamed key.
+pub fn apply_migrations(
+    migrations: &[Migration],
+    current: &str,
+    table: &mut Table,
+) -> Result<Vec<String>> {
+    let declared = match table.get(SCHEMA_VERSION_KEY) {
+        None => return Ok(Vec::new()),
+        Some(Value::String(version)) => version.clone(),
+        Some(_) => bail!("`{SCHEMA_VERSION_KEY}` must be a string"),
+    };
+
+    let mut warnings = Vec::new();
+    let mut version = declared.clone();
+    while version != current {
+        let Some(step) = migrations.iter().find(|m| m.from == version) else {
+            let known: Vec<&str> = migrations.iter().map(|m| m.from).collect();
+            bail!(
+                "config schema_version {version} cannot be upgraded to {current} (known older schemas: {})",
+                if known.is_empty() { "none".to_string() } else { known.join(", ") }
+            );
+        };
+
+        for &(old, new) in step.renamed {
+            let Some(value) = table.remove(old) else {
+                continue;
+            };
+            if table.contains_key(new) {
+                bail!("config sets both `{old}` and `{new}`; `{old}` was renamed to `{new}` in schema {}", step.to);
+            }
+            table.insert(new.to_string(), value);
+            warnings.push(format!(
+                "`{old}` was renamed to `{new}` in schema {}",
+                step.to
+            ));
+        }
+        for &(key, raw) in step.defaults {
+            if table.contains_key(key) {
+                continue;
+            }
+            let value = toml::from_str::<Table>(&format!("value = {raw}"))
+                .ok()
+                .and_then(|mut t| t.remove("value"))
+                .with_context(|| {
+                    format!("invalid default for `{key}` in migration to {}", step.to)
+                })?;
+            table.insert(key.to_string(), value);
+            warnings.push(format!(
+                "`{key}` is required since schema {}; using {raw}, the value schema {} implied",
+                step.to, step.from
+            ));
+        }
+        version = step.to.to_string();
+    }
+
+    if version != declared {
+        table.insert(SCHEMA_VERSION_KEY.to_string(), Value::String(version));
+        warnings.push(format!(
+            "upgraded from schema_version {declared} to {current}; update the file to silence these warnings"
+        ));
+    }
+    Ok(warnings)
+}
@@ -0,0 +1,135 @@
+use std::collections::VecDeque;
+
+use crate::sim::diagnostics::DiagnosticModel;
+use crate::sim::state::{BenchConfig, PreprocessConfig, SimulationData};
+
+/// Causal mean over the last `window` pushed samples.
+struct RollingMean {
+    window: usize,
+    samples: VecDeque<f64>,
+}
+
+impl RollingMean {
+    fn new(window: usize) -> Self {
+        Self {
+            window,
+            samples: VecDeque::with_capacity(window),
+        }
+    }
+
+    fn push(&mut self, value: f64) {
+        if self.samples.len() == self.window {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(value);
+    }
+
+    fn mean(&self) -> Option<f64> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        Some(self.samples.iter().sum::<f64>() / self.samples.len() as f64)
+    }
+}
+
+/// Model the methods estimate with: with `normalize`, every group's rows of
+/// `H` (or its nonlinear measurement function) are divided by its
+/// `noise_std` and its noise variance becomes 1 (a correlated group keeps its
+/// correlations).
+/// Measurements are generated from the unmodified model.
+pub fn preprocessed_model(cfg: &BenchConfig, model: &DiagnosticModel) -> DiagnosticModel {
+    let mut model = model.clone();
+    if cfg.preprocess.as_ref().is_some_and(|pre| pre.normalize) {
+        for (group, &sigma) in model.groups.iter_mut().zip(&cfg.noise_std) {
+            group.h /= sigma;
+            if let Some(nl) = &mut group.nonlinear {
+                nl.scale /= sigma;
+            }
+            if group.is_correlated() {
+                group.scale_noise(1.0 / sigma);
+            } else {
+                group.r_diag.fill(1.0);
+            }
+        }
+    }
+    model
+}
+
+/// Applies the configured preprocessing to every frame of `data` in place.
+///
+/// Detrending also removes the same rolling mean from `x_true`, so errors are
+/// measured against the truth the detrended measurements describe. Clipping
+/// bounds are in units of the group's `noise_std`, taken before normalization.
+pub fn preprocess_data(cfg: &BenchConfig, data: &mut SimulationData) {
+    let Some(pre) = &cfg.preprocess else {
+        return;
+    };
+    if let Some(clip_sigma) = pre.clip_sigma {
+        clip_outliers(pre, clip_sigma, &cfg.noise_std, data);
+    }
+    if pre.detrend {
+        detrend(pre, data);
+    }
+    if pre.normalize {
+        for frame in &mut data.measurements {
+            for (y, &sigma) in frame.y_groups.iter_mut().zip(&cfg.noise_std) {
+                *y /= sigma;
+            }
+        }
+    }
+}
+
+fn clip_outliers(
+    pre: &PreprocessConfig,
+    clip_sigma: f64,
+    noise_std: &[f64],
+    data: &mut SimulationData,
+) {
+    let Some(first) = data.measurements.first() else {
+        return;
+    };
+    let mut history: Vec<Vec<RollingMean>> = first
+        .y_groups
+        .iter()
+        .map(|y| (0..y.len()).map(|_| RollingMean::new(pre.window)).collect())
+        .collect();
+
+    for frame in &mut data.measurements {
+        for (k, y) in frame.y_groups.iter_mut().enumerate() {
+            let limit = clip_sigma * noise_std[k];
+            for (i, value) in y.iter_mut().enumerate() {
+                if let Some(mean) = history[k][i].mean() {
+                    *value = value.clamp(mean - limit, mean + limit);
+                }
+                history[k][i].push(*value);
+            }
+        }
+    }
+}
+
+fn detrend(pre: &PreprocessConfig, data: &mut SimulationData) {
+    let Some(first) = data.measurements.first() else {
+        return;
+    };
+    let mut y_history: Vec<Vec<RollingMean>> = first
+        .y_groups
+        .iter()
+        .map(|y| (0..y.len()).map(|_| RollingMean::new(pre.window)).collect())
+        .collect();
+    let mut x_history: Vec<RollingMean> = (0..data.x_true[0].len())
+        .map(|_| RollingMean::new(pre.window))
+        .collect();
+
+    for (frame, x) in data.measurements.iter_mut().zip(&mut data.x_true) {
+        for (k, y) in frame.y_groups.iter_mut().enumerate() {
+            for (i, value) in y.iter_mut().enumerate() {
+                y_history[k][i].push(*value);
+                *value -= y_history[k][i].mean().unwrap_or(0.0);
+            }
+        }
+        for (i, value) in x.iter_mut().enumerate() {
+            x_history[i].push(*value);
+            *value -= x_history[i].mean().unwrap_or(0.0);
+        }
+    }
+}
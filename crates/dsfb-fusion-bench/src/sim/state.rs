@@ -5,10 +5,17 @@ use rand_chacha::ChaCha8Rng;
 use rand_distr::{Distribution, Normal};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
 
-use crate::sim::diagnostics::{generate_measurements, DiagnosticModel, MeasurementFrame};
-use crate::sim::faults::apply_impulse_corruption;
+use crate::sim::diagnostics::{
+    generate_measurements, DiagnosticModel, DriftKind, DriftSchedule, MeasurementFrame,
+    ModelDriftSpec, NonlinearGroupSpec,
+};
+use crate::sim::faults::{
+    apply_impulse_corruption, corrupted_groups, FaultInjector, FaultLabel, FaultSpec,
+};
+use crate::sim::migrate::migrate_config;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BenchConfig {
@@ -18,6 +25,11 @@ pub struct BenchConfig {
     pub n: usize,
     pub group_dims: Vec<usize>,
     pub noise_std: Vec<f64>,
+    /// Per-group correlation `rho` between the noise of a group's channels:
+    /// channels `i` and `j` have covariance `noise_std^2 rho^|i - j|`. The
+    /// noise of each group is independent when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub noise_correlation: Option<Vec<f64>>,
     pub process_noise_std: f64,
     pub bandwidth_groups: Vec<usize>,
     pub bandwidth_tau: f64,
@@ -35,21 +47,215 @@ pub struct BenchConfig {
     pub dsfb_alpha: f64,
     pub dsfb_beta: f64,
     pub dsfb_w_min: f64,
+    /// Forgetting factor applied each step to the inverse-gamma noise-scale
+    /// posteriors of `vb_adaptive_r`; the memory is about `1 / (1 - rho)` steps.
+    #[serde(default = "default_vb_forgetting")]
+    pub vb_forgetting: f64,
+    /// Variational fixed-point iterations of `vb_adaptive_r` per step.
+    #[serde(default = "default_vb_iterations")]
+    pub vb_iterations: usize,
+    /// `--bench-solver` schedule: untimed warmup calls per method, timed
+    /// samples, and consecutive calls per sample.
+    #[serde(default = "default_bench_warmup")]
+    pub bench_warmup: usize,
+    #[serde(default = "default_bench_samples")]
+    pub bench_samples: usize,
+    #[serde(default = "default_bench_batch")]
+    pub bench_batch: usize,
     pub matrix_seed: u64,
     pub seeds: Vec<u64>,
     pub methods: Vec<String>,
     pub alpha_values: Option<Vec<f64>>,
     pub beta_values: Option<Vec<f64>>,
+    /// Per-group `noise_std` vectors swept in sweep mode, as the outermost
+    /// axis; the sweep uses `noise_std` alone when unset.
+    pub noise_std_values: Option<Vec<Vec<f64>>>,
+    /// State dimensions exported to `state_errors.csv` and `state_summary.csv`;
+    /// per-state outputs are skipped when unset.
+    pub state_error_dims: Option<Vec<usize>>,
+    /// Probabilities at which each method's empirical CDF of per-step error
+    /// norms, pooled over seeds, is written to `error_cdf.csv` (default mode
+    /// only); the file is skipped when unset.
+    pub error_cdf_probabilities: Option<Vec<f64>>,
+    /// Heatmap metrics, each minimized to pick every method's best
+    /// (alpha, beta) cell for `best_params.csv` and to profile the 1-D
+    /// marginal curves in `marginals.csv` (sweep mode only); both files are
+    /// skipped when unset.
+    pub sweep_objectives: Option<Vec<SweepObjective>>,
+    /// Export each method's per-step deviation from the equal-weight estimate
+    /// to `equal_deltas.csv` (default mode only).
+    #[serde(default)]
+    pub equal_deltas: bool,
+    /// Export the per-group NIS of the equal-weight solve at every step to
+    /// `group_nis.csv` (default mode only). This is the stream `nis_hard`,
+    /// `nis_soft` and `dsfb` score, so alternative thresholds can be studied
+    /// offline without rerunning the simulation.
+    #[serde(default)]
+    pub group_nis: bool,
+    /// Also solve the equal-weight WLS problem as one stacked, whitened QR
+    /// least-squares system and compare it with the normal-equation solve in
+    /// `solver_comparison.csv` (default mode only).
+    #[serde(default)]
+    pub compare_stacked_qr: bool,
+    /// Accumulation used when assembling the WLS normal equations.
+    #[serde(default)]
+    pub wls_summation: WlsSummation,
+    /// Preprocessing applied to every frame before the methods see it; frames
+    /// reach the methods unchanged when unset.
+    #[serde(default)]
+    pub preprocess: Option<PreprocessConfig>,
+    /// Free-form labels copied into the manifest, summary, and heatmap rows,
+    /// used to select runs when aggregating.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Faults injected on top of the `corruption_*` impulse, from
+    /// `[[faults]]` tables; see [`FaultSpec`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub faults: Vec<FaultSpec>,
+    /// Groups measured by a range or bearing function of the state instead of
+    /// a fixed `H`, from `[[nonlinear_groups]]` tables; the WLS solvers then
+    /// iterate Gauss-Newton. See [`NonlinearGroupSpec`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub nonlinear_groups: Vec<NonlinearGroupSpec>,
+    /// Slow changes of the true measurement model over the run, from
+    /// `[[model_drift]]` tables; the methods keep the nominal model. See
+    /// [`ModelDriftSpec`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub model_drift: Vec<ModelDriftSpec>,
+}
+
+/// How the WLS normal equations are accumulated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WlsSummation {
+    /// Plain running sums.
+    #[default]
+    Naive,
+    /// Neumaier-compensated sums: every entry carries a running error term,
+    /// so the result depends far less on accumulation order and rounding.
+    Compensated,
+}
+
+/// Heatmap metric minimized when extracting the best sweep parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SweepObjective {
+    /// Mean `peak_err` across seeds.
+    PeakErr,
+    /// Mean `rms_err` across seeds.
+    RmsErr,
+    /// Mean `false_downweight_rate` across seeds; cells without one are skipped.
+    FalseDownweightRate,
+    /// `worst_peak_err`, the peak error of the worst seed.
+    WorstPeakErr,
+    /// `worst_rms_err`, the RMS error of the worst seed.
+    WorstRmsErr,
+}
+
+impl SweepObjective {
+    /// Name written to the `objective` column, matching the heatmap column.
+    pub fn name(self) -> &'static str {
+        match self {
+            SweepObjective::PeakErr => "peak_err",
+            SweepObjective::RmsErr => "rms_err",
+            SweepObjective::FalseDownweightRate => "false_downweight_rate",
+            SweepObjective::WorstPeakErr => "worst_peak_err",
+            SweepObjective::WorstRmsErr => "worst_rms_err",
+        }
+    }
+}
+
+/// Per-channel preprocessing stages, applied in field order: clip, then
+/// detrend, then normalize.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PreprocessConfig {
+    /// Length in steps of the causal rolling mean used by clipping and detrending.
+    #[serde(default = "default_preprocess_window")]
+    pub window: usize,
+    /// Clip each channel to its rolling mean of the preceding `window`
+    /// (already clipped) samples, plus or minus this many times the group's
+    /// `noise_std`.
+    #[serde(default)]
+    pub clip_sigma: Option<f64>,
+    /// Subtract the rolling mean of the last `window` samples from every channel.
+    #[serde(default)]
+    pub detrend: bool,
+    /// Divide every group's measurements and rows of `H` by its `noise_std`,
+    /// so the methods see unit-variance noise.
+    #[serde(default)]
+    pub normalize: bool,
+}
+
+fn default_preprocess_window() -> usize {
+    25
+}
+
+fn default_vb_forgetting() -> f64 {
+    0.95
+}
+
+fn default_vb_iterations() -> usize {
+    3
+}
+
+fn default_bench_warmup() -> usize {
+    200
+}
+
+fn default_bench_samples() -> usize {
+    500
+}
+
+fn default_bench_batch() -> usize {
+    10
+}
+
+impl PreprocessConfig {
+    /// Manifest descriptions of the enabled stages, in application order.
+    pub fn stages(&self) -> Vec<String> {
+        let mut stages = Vec::new();
+        if let Some(sigma) = self.clip_sigma {
+            stages.push(format!("clip(sigma={sigma}, window={})", self.window));
+        }
+        if self.detrend {
+            stages.push(format!("detrend(window={})", self.window));
+        }
+        if self.normalize {
+            stages.push("normalize(noise_std)".to_string());
+        }
+        stages
+    }
 }
 
 impl BenchConfig {
+    /// Loads a config file, resolving `extends` includes.
+    ///
+    /// A file with `extends = "<path>"` (relative to the including file) starts
+    /// from the fully resolved base config; every key it sets replaces the
+    /// base value, and nested tables merge key by key. Chains of includes are
+    /// allowed; cycles are rejected.
+    ///
+    /// Each file written against an older `schema_version` is upgraded first;
+    /// the upgrade warnings are printed to stderr.
     pub fn from_toml_file(path: &Path) -> Result<Self> {
-        let raw = fs::read_to_string(path)
-            .with_context(|| format!("failed to read config file: {}", path.display()))?;
-        let cfg: BenchConfig = toml::from_str(&raw)
+        let (cfg, warnings) = Self::from_toml_file_with_warnings(path)?;
+        for warning in warnings {
+            eprintln!("warning: {warning}");
+        }
+        Ok(cfg)
+    }
+
+    /// Same as [`BenchConfig::from_toml_file`], returning the schema upgrade
+    /// warnings instead of printing them.
+    pub fn from_toml_file_with_warnings(path: &Path) -> Result<(Self, Vec<String>)> {
+        let mut warnings = Vec::new();
+        let table = load_config_table(path, &mut Vec::new(), &mut warnings)?;
+        let cfg: BenchConfig = table
+            .try_into()
             .with_context(|| format!("failed to parse TOML config: {}", path.display()))?;
         cfg.validate()?;
-        Ok(cfg)
+        Ok((cfg, warnings))
     }
 
     pub fn validate(&self) -> Result<()> {
@@ -74,6 +280,14 @@ impl BenchConfig {
         if self.noise_std.iter().any(|&s| s <= 0.0) {
             bail!("all noise_std entries must be > 0");
         }
+        if let Some(rhos) = &self.noise_correlation {
+            if rhos.len() != self.group_dims.len() {
+                bail!("noise_correlation length must equal group_dims length");
+            }
+            if rhos.iter().any(|rho| rho.is_nan() || rho.abs() >= 1.0) {
+                bail!("all noise_correlation entries must be in (-1, 1)");
+            }
+        }
         if self.corruption_group >= self.group_dims.len() {
             bail!("corruption_group index out of range");
         }
@@ -95,12 +309,142 @@ impl BenchConfig {
         if self.dsfb_beta <= 0.0 || self.dsfb_beta > 1.0 {
             bail!("dsfb_beta must be in (0, 1]");
         }
+        if !(self.vb_forgetting > 0.0 && self.vb_forgetting <= 1.0) {
+            bail!("vb_forgetting must be in (0, 1]");
+        }
+        if self.vb_iterations == 0 {
+            bail!("vb_iterations must be > 0");
+        }
+        if self.bench_samples == 0 || self.bench_batch == 0 {
+            bail!("bench_samples and bench_batch must be > 0");
+        }
+        if self.bench_batch > self.steps {
+            bail!("bench_batch must be <= steps");
+        }
         if self.bandwidth_tau < 0.0 {
             bail!("bandwidth_tau must be >= 0");
         }
         if self.seeds.is_empty() {
             bail!("seeds must be non-empty");
         }
+        if let Some(sets) = &self.noise_std_values {
+            if sets.is_empty() {
+                bail!("noise_std_values must be non-empty when set");
+            }
+            for (i, set) in sets.iter().enumerate() {
+                if set.len() != self.group_dims.len() {
+                    bail!("noise_std_values[{i}] length must equal group_dims length");
+                }
+                if set.iter().any(|&s| s <= 0.0) {
+                    bail!("all noise_std_values[{i}] entries must be > 0");
+                }
+            }
+        }
+        for tag in &self.tags {
+            if tag.trim().is_empty() || tag.trim() != tag {
+                bail!("tags must be non-empty and have no surrounding whitespace");
+            }
+            if tag.contains(TAG_SEPARATOR) {
+                bail!("tags must not contain '{TAG_SEPARATOR}'");
+            }
+        }
+        if let Some(probs) = &self.error_cdf_probabilities {
+            if probs.is_empty() {
+                bail!("error_cdf_probabilities must be non-empty when set");
+            }
+            if probs.iter().any(|p| !(0.0..=1.0).contains(p)) {
+                bail!("error_cdf_probabilities entries must be in [0, 1]");
+            }
+        }
+        if let Some(objectives) = &self.sweep_objectives {
+            if objectives.is_empty() {
+                bail!("sweep_objectives must be non-empty when set");
+            }
+            if objectives
+                .iter()
+                .enumerate()
+                .any(|(i, o)| objectives[..i].contains(o))
+            {
+                bail!("sweep_objectives entries must be unique");
+            }
+        }
+        if let Some(pre) = &self.preprocess {
+            if pre.window < 2 {
+                bail!("preprocess.window must be >= 2");
+            }
+            if let Some(sigma) = pre.clip_sigma {
+                if !sigma.is_finite() || sigma <= 0.0 {
+                    bail!("preprocess.clip_sigma must be finite and > 0");
+                }
+            }
+        }
+        for (i, fault) in self.faults.iter().enumerate() {
+            fault.validate(i, &self.group_dims, self.steps)?;
+        }
+        if !self.nonlinear_groups.is_empty() {
+            if self.n < 2 {
+                bail!("nonlinear_groups need n >= 2");
+            }
+            if self.compare_stacked_qr {
+                bail!("compare_stacked_qr does not support nonlinear_groups");
+            }
+            // Range and bearing channels are not additive in the state, and
+            // bearings wrap, so a rolling mean of them is meaningless.
+            if let Some(pre) = &self.preprocess {
+                if pre.clip_sigma.is_some() || pre.detrend {
+                    bail!("preprocess clip_sigma and detrend do not support nonlinear_groups");
+                }
+            }
+        }
+        for (i, spec) in self.nonlinear_groups.iter().enumerate() {
+            if spec.group >= self.group_dims.len() {
+                bail!("nonlinear_groups[{i}].group index out of range");
+            }
+            if self.nonlinear_groups[..i]
+                .iter()
+                .any(|other| other.group == spec.group)
+            {
+                bail!("nonlinear_groups[{i}].group appears more than once");
+            }
+        }
+        for (i, spec) in self.model_drift.iter().enumerate() {
+            if spec.group >= self.group_dims.len() {
+                bail!("model_drift[{i}].group index out of range");
+            }
+            if spec.start >= self.steps {
+                bail!("model_drift[{i}].start must be < steps");
+            }
+            match spec.kind {
+                DriftKind::Gain { factor } | DriftKind::Noise { factor } => {
+                    if !factor.is_finite() || factor <= 0.0 {
+                        bail!("model_drift[{i}].factor must be finite and > 0");
+                    }
+                }
+                DriftKind::Mixing { amount } => {
+                    if !(0.0..=1.0).contains(&amount) {
+                        bail!("model_drift[{i}].amount must be in [0, 1]");
+                    }
+                    if self
+                        .nonlinear_groups
+                        .iter()
+                        .any(|nl| nl.group == spec.group)
+                    {
+                        bail!("model_drift[{i}] mixes the H of a nonlinear group");
+                    }
+                }
+            }
+        }
+        if let Some(dims) = &self.state_error_dims {
+            if dims.is_empty() {
+                bail!("state_error_dims must be non-empty when set");
+            }
+            if dims.iter().any(|&d| d >= self.n) {
+                bail!("state_error_dims entries must be < n");
+            }
+            if dims.iter().enumerate().any(|(i, d)| dims[..i].contains(d)) {
+                bail!("state_error_dims entries must be unique");
+            }
+        }
         Ok(())
     }
 
@@ -111,6 +455,88 @@ impl BenchConfig {
     pub fn group_count(&self) -> usize {
         self.group_dims.len()
     }
+
+    /// Noise heterogeneity ratio between groups: largest over smallest `noise_std`.
+    pub fn noise_ratio(&self) -> f64 {
+        let max = self.noise_std.iter().copied().fold(f64::MIN, f64::max);
+        let min = self.noise_std.iter().copied().fold(f64::MAX, f64::min);
+        max / min
+    }
+}
+
+/// Separator between tags in the `tags` output column.
+pub const TAG_SEPARATOR: char = ';';
+
+/// Config key naming the base file a config inherits from.
+pub const EXTENDS_KEY: &str = "extends";
+
+/// Reads a config file into a TOML table with its `extends` chain merged in.
+///
+/// `chain` holds the canonical paths of the files currently being resolved.
+/// Schema upgrade warnings are appended to `warnings`, prefixed with the file.
+fn load_config_table(
+    path: &Path,
+    chain: &mut Vec<PathBuf>,
+    warnings: &mut Vec<String>,
+) -> Result<Table> {
+    let canonical = path
+        .canonicalize()
+        .with_context(|| format!("failed to read config file: {}", path.display()))?;
+    if let Some(start) = chain.iter().position(|seen| *seen == canonical) {
+        let cycle: Vec<String> = chain[start..]
+            .iter()
+            .chain(std::iter::once(&canonical))
+            .map(|p| p.display().to_string())
+            .collect();
+        bail!("config extends cycle: {}", cycle.join(" -> "));
+    }
+
+    let raw = fs::read_to_string(&canonical)
+        .with_context(|| format!("failed to read config file: {}", path.display()))?;
+    let mut table: Table = toml::from_str(&raw)
+        .with_context(|| format!("failed to parse TOML config: {}", path.display()))?;
+    let upgrades = migrate_config(&mut table)
+        .with_context(|| format!("failed to upgrade config: {}", path.display()))?;
+    warnings.extend(
+        upgrades
+            .into_iter()
+            .map(|w| format!("{}: {w}", path.display())),
+    );
+
+    let Some(extends) = table.remove(EXTENDS_KEY) else {
+        return Ok(table);
+    };
+    let Value::String(base) = extends else {
+        bail!(
+            "`{EXTENDS_KEY}` must be a file path string in {}",
+            path.display()
+        );
+    };
+
+    let base_path = canonical
+        .parent()
+        .map_or_else(|| PathBuf::from(&base), |dir| dir.join(&base));
+    chain.push(canonical);
+    let mut merged = load_config_table(&base_path, chain, warnings)
+        .with_context(|| format!("failed to resolve `{EXTENDS_KEY}` in {}", path.display()))?;
+    chain.pop();
+
+    merge_tables(&mut merged, table);
+    Ok(merged)
+}
+
+/// Overlays `overrides` onto `base`; tables merge recursively, other values replace.
+fn merge_tables(base: &mut Table, overrides: Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base_table)), Value::Table(override_table)) => {
+                merge_tables(base_table, override_table);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -118,10 +544,31 @@ pub struct SimulationData {
     pub t: Vec<f64>,
     pub x_true: Vec<DVector<f64>>,
     pub measurements: Vec<MeasurementFrame>,
+    /// Whether the impulse or any `[[faults]]` entry corrupts the step, in
+    /// any group. Only the pooled exports (`group_nis.csv`, the dataset
+    /// export) use it; the metrics score each group against its own column of
+    /// `group_corrupted`.
     pub corruption_active: Vec<bool>,
+    /// Per-step, per-group ground truth: whether the impulse or a
+    /// `[[faults]]` entry corrupts the group at the step.
+    pub group_corrupted: Vec<Vec<bool>>,
+    /// Channels corrupted by `[[faults]]` entries at each step.
+    pub fault_labels: Vec<Vec<FaultLabel>>,
+}
+
+impl SimulationData {
+    /// Per-step ground truth of one group, the column `group` of
+    /// `group_corrupted`.
+    pub fn group_active(&self, group: usize) -> Vec<bool> {
+        self.group_corrupted
+            .iter()
+            .map(|groups| groups[group])
+            .collect()
+    }
 }
 
-fn build_dynamics_matrix(n: usize, dt: f64) -> DMatrix<f64> {
+/// Transition matrix `A` of the simulated state, `x' = A x + u(t) + w`.
+pub fn build_dynamics_matrix(n: usize, dt: f64) -> DMatrix<f64> {
     let mut a = DMatrix::<f64>::identity(n, n);
     for i in 0..n {
         let coupling = 0.015 * dt;
@@ -136,7 +583,8 @@ fn build_dynamics_matrix(n: usize, dt: f64) -> DMatrix<f64> {
     a
 }
 
-fn deterministic_drive(n: usize, t: f64, dt: f64) -> DVector<f64> {
+/// Known input `u(t)` added to the state on the step starting at time `t`.
+pub fn deterministic_drive(n: usize, t: f64, dt: f64) -> DVector<f64> {
     let mut u = DVector::<f64>::zeros(n);
     for i in 0..n {
         let f1 = 0.07 * (i as f64 + 1.0);
@@ -163,17 +611,27 @@ pub fn generate_simulation_data(
     let mut x_true = Vec::with_capacity(cfg.steps);
     let mut frames = Vec::with_capacity(cfg.steps);
     let mut corruption_flags = Vec::with_capacity(cfg.steps);
+    let mut fault_labels = Vec::with_capacity(cfg.steps);
+    let mut group_corrupted = Vec::with_capacity(cfg.steps);
+    let mut injector = FaultInjector::new(cfg, seed);
+    let schedule = DriftSchedule::new(cfg)?;
 
     for step in 0..cfg.steps {
         let t = step as f64 * cfg.dt;
 
-        let mut frame = generate_measurements(cfg, model, &x, step, &mut low_pass_state, &mut rng)?;
-        let corrupted = apply_impulse_corruption(cfg, &mut frame, step);
+        let drifted = schedule.model_at(model, step);
+        let truth = drifted.as_ref().unwrap_or(model);
+        let mut frame = generate_measurements(cfg, truth, &x, step, &mut low_pass_state, &mut rng)?;
+        apply_impulse_corruption(cfg, &mut frame, step);
+        let labels = injector.apply(&mut frame, step);
 
         t_vec.push(t);
         x_true.push(x.clone());
         frames.push(frame);
-        corruption_flags.push(corrupted);
+        let corrupted = corrupted_groups(cfg, step, &labels);
+        corruption_flags.push(corrupted.contains(&true));
+        group_corrupted.push(corrupted);
+        fault_labels.push(labels);
 
         let mut next_x = &a * &x + deterministic_drive(cfg.n, t, cfg.dt);
         for i in 0..cfg.n {
@@ -187,5 +645,27 @@ pub fn generate_simulation_data(
         x_true,
         measurements: frames,
         corruption_active: corruption_flags,
+        group_corrupted,
+        fault_labels,
     })
 }
+
+/// Generates the same trajectory and noise as `generate_simulation_data` with
+/// the corruption impulse and every `[[faults]]` entry removed, for
+/// false-alarm characterization.
+pub fn generate_null_simulation_data(
+    cfg: &BenchConfig,
+    model: &DiagnosticModel,
+    seed: u64,
+) -> Result<SimulationData> {
+    let mut null_cfg = cfg.clone();
+    null_cfg.corruption_amplitude = 0.0;
+    null_cfg.faults.clear();
+
+    let mut data = generate_simulation_data(&null_cfg, model, seed)?;
+    data.corruption_active.fill(false);
+    for corrupted in &mut data.group_corrupted {
+        corrupted.fill(false);
+    }
+    Ok(data)
+}
@@ -0,0 +1,290 @@
+//! Recording run outputs in a single SQLite database (`sqlite` feature).
+//!
+//! Campaigns with hundreds of run directories are easier to query from one
+//! database than from loose CSV files. [`record_run`] loads a finished run's
+//! `manifest.json` into the `runs` table and its summary, heatmap, and
+//! trajectory CSVs into same-named tables, every row keyed by the run's
+//! `run_id`. Table columns follow the CSV headers; columns a later run adds
+//! (e.g. weight columns for more groups) are appended, and earlier rows read
+//! them as NULL. Numeric fields are stored as INTEGER or REAL, `NA` and empty
+//! fields as NULL.
+//!
+//! The database layout is versioned through `PRAGMA user_version`
+//! ([`SQLITE_SCHEMA_VERSION`]); a database with another version is rejected.
+
+use anyhow::{bail, Context, Result};
+use csv::ReaderBuilder;
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Transaction};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::io::{check_record_schema, fmt_tags, read_manifest_json, OUTPUT_SCHEMA_VERSION};
+
+/// Layout version of the database, stored as `PRAGMA user_version`.
+pub const SQLITE_SCHEMA_VERSION: i64 = 1;
+
+/// A run CSV loaded by [`record_run`], with the columns its table is indexed on.
+#[derive(Debug, Clone, Copy)]
+pub struct SqliteTable {
+    pub file: &'static str,
+    pub table: &'static str,
+    pub index: &'static [&'static str],
+}
+
+/// Run CSVs recorded in the database; files a run did not write are skipped.
+pub const SQLITE_TABLES: [SqliteTable; 4] = [
+    SqliteTable {
+        file: "summary.csv",
+        table: "summary",
+        index: &["method", "seed"],
+    },
+    SqliteTable {
+        file: "summary_sweep.csv",
+        table: "summary_sweep",
+        index: &["method", "alpha", "beta"],
+    },
+    SqliteTable {
+        file: "heatmap.csv",
+        table: "heatmap",
+        index: &["method", "alpha", "beta"],
+    },
+    SqliteTable {
+        file: "trajectories.csv",
+        table: "trajectories",
+        index: &["method", "t"],
+    },
+];
+
+/// What [`record_run`] wrote for one run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqliteRecord {
+    pub run_id: i64,
+    /// Rows inserted per table, keyed by table name.
+    pub tables: BTreeMap<String, usize>,
+}
+
+/// Records the outputs of `run_dir` in the database at `db_path`, creating it
+/// if needed.
+///
+/// A run directory recorded before, under any path that resolves to it, is
+/// replaced, so recording is idempotent.
+/// The run must carry the current output schema version, in its manifest and
+/// in every recorded row, and so must every run already in the database.
+/// Everything is written in one transaction.
+pub fn record_run(db_path: &Path, run_dir: &Path) -> Result<SqliteRecord> {
+    let manifest = read_manifest_json(run_dir)?;
+    if manifest.schema_version != OUTPUT_SCHEMA_VERSION {
+        bail!(
+            "{} has schema_version {}, expected {}",
+            run_dir.display(),
+            manifest.schema_version,
+            OUTPUT_SCHEMA_VERSION
+        );
+    }
+    let manifest_json = fs::read_to_string(run_dir.join("manifest.json"))
+        .with_context(|| format!("failed to read manifest in {}", run_dir.display()))?;
+
+    let mut conn = open_database(db_path)?;
+    let tx = conn.transaction()?;
+    let other_schema: Option<String> = tx
+        .query_row(
+            "SELECT schema_version FROM runs WHERE schema_version <> ?1 LIMIT 1",
+            params![OUTPUT_SCHEMA_VERSION],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if let Some(version) = other_schema {
+        bail!(
+            "{} holds runs with schema_version {version}; record schema {} runs in a new database",
+            db_path.display(),
+            OUTPUT_SCHEMA_VERSION
+        );
+    }
+    // Key runs by their canonical path, so another spelling of the same
+    // directory replaces its earlier record instead of duplicating it
+    let run_dir_key = fs::canonicalize(run_dir)
+        .with_context(|| format!("failed to resolve {}", run_dir.display()))?
+        .display()
+        .to_string();
+
+    let previous: Option<i64> = tx
+        .query_row(
+            "SELECT run_id FROM runs WHERE run_dir = ?1",
+            params![run_dir_key],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if let Some(run_id) = previous {
+        for table in SQLITE_TABLES {
+            if table_exists(&tx, table.table)? {
+                tx.execute(
+                    &format!("DELETE FROM {} WHERE run_id = ?1", quote(table.table)),
+                    params![run_id],
+                )?;
+            }
+        }
+        tx.execute("DELETE FROM runs WHERE run_id = ?1", params![run_id])?;
+    }
+
+    tx.execute(
+        "INSERT INTO runs (run_dir, mode, schema_version, tags, note, manifest_json) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            run_dir_key,
+            manifest.mode,
+            manifest.schema_version,
+            fmt_tags(&manifest.tags),
+            manifest.note,
+            manifest_json
+        ],
+    )?;
+    let run_id = tx.last_insert_rowid();
+
+    let mut tables = BTreeMap::new();
+    for table in SQLITE_TABLES {
+        let path = run_dir.join(table.file);
+        if path.exists() {
+            let rows = load_csv(&tx, &table, &path, run_id)?;
+            tables.insert(table.table.to_string(), rows);
+        }
+    }
+
+    tx.commit()
+        .with_context(|| format!("failed to commit to {}", db_path.display()))?;
+    Ok(SqliteRecord { run_id, tables })
+}
+
+/// Opens `db_path`, creating the `runs` table in a new database and
+/// rejecting one with a different layout version.
+fn open_database(db_path: &Path) -> Result<Connection> {
+    let conn = Connection::open(db_path)
+        .with_context(|| format!("failed to open database: {}", db_path.display()))?;
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    match version {
+        0 => {
+            conn.execute_batch(&format!(
+                "CREATE TABLE IF NOT EXISTS runs (
+                     run_id INTEGER PRIMARY KEY AUTOINCREMENT,
+                     run_dir TEXT NOT NULL UNIQUE,
+                     mode TEXT NOT NULL,
+                     schema_version TEXT NOT NULL,
+                     tags TEXT NOT NULL,
+                     note TEXT NOT NULL,
+                     manifest_json TEXT NOT NULL
+                 );
+                 CREATE INDEX IF NOT EXISTS idx_runs_mode ON runs (mode);
+                 PRAGMA user_version = {SQLITE_SCHEMA_VERSION};"
+            ))?;
+        }
+        SQLITE_SCHEMA_VERSION => {}
+        other => bail!(
+            "{} has database schema version {other}, expected {SQLITE_SCHEMA_VERSION}",
+            db_path.display()
+        ),
+    }
+    Ok(conn)
+}
+
+/// Inserts every record of `path` into `table.table`, creating the table,
+/// its missing columns, and its indexes as needed. Returns the row count.
+fn load_csv(tx: &Transaction<'_>, table: &SqliteTable, path: &Path, run_id: i64) -> Result<usize> {
+    let mut reader = ReaderBuilder::new()
+        .has_headers(true)
+        .from_path(path)
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    let header_record = reader.headers()?.clone();
+    let header: Vec<String> = header_record.iter().map(str::to_string).collect();
+
+    let name = quote(table.table);
+    tx.execute(
+        &format!(
+            "CREATE TABLE IF NOT EXISTS {name} (run_id INTEGER NOT NULL REFERENCES runs (run_id))"
+        ),
+        [],
+    )?;
+    let existing = table_columns(tx, table.table)?;
+    for column in header.iter().filter(|c| !existing.contains(*c)) {
+        tx.execute(
+            &format!("ALTER TABLE {name} ADD COLUMN {}", quote(column)),
+            [],
+        )?;
+    }
+
+    let mut index_columns = vec!["run_id"];
+    index_columns.extend(
+        table
+            .index
+            .iter()
+            .filter(|c| header.iter().any(|h| h == *c)),
+    );
+    tx.execute(
+        &format!(
+            "CREATE INDEX IF NOT EXISTS {} ON {name} ({})",
+            quote(&format!("idx_{}_{}", table.table, index_columns.join("_"))),
+            index_columns
+                .iter()
+                .map(|c| quote(c))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        [],
+    )?;
+
+    let columns: Vec<String> = std::iter::once("run_id")
+        .chain(header.iter().map(String::as_str))
+        .map(quote)
+        .collect();
+    let placeholders = vec!["?"; columns.len()].join(", ");
+    let mut insert = tx.prepare(&format!(
+        "INSERT INTO {name} ({}) VALUES ({placeholders})",
+        columns.join(", ")
+    ))?;
+
+    let mut rows = 0;
+    for record in reader.records() {
+        let record = record.with_context(|| format!("failed to read {}", path.display()))?;
+        check_record_schema(&header_record, &record, path)?;
+        let values = std::iter::once(Value::Integer(run_id)).chain(record.iter().map(sql_value));
+        insert.execute(params_from_iter(values))?;
+        rows += 1;
+    }
+    Ok(rows)
+}
+
+fn table_exists(tx: &Transaction<'_>, table: &str) -> Result<bool> {
+    let count: i64 = tx.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        params![table],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+fn table_columns(tx: &Transaction<'_>, table: &str) -> Result<Vec<String>> {
+    let mut stmt = tx.prepare(&format!("PRAGMA table_info({})", quote(table)))?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(columns)
+}
+
+/// SQL value of a CSV field: NULL for `NA` or empty, INTEGER or REAL when
+/// numeric, TEXT otherwise.
+fn sql_value(field: &str) -> Value {
+    if field.is_empty() || field == "NA" {
+        Value::Null
+    } else if let Ok(v) = field.parse::<i64>() {
+        Value::Integer(v)
+    } else if let Ok(v) = field.parse::<f64>() {
+        Value::Real(v)
+    } else {
+        Value::Text(field.to_string())
+    }
+}
+
+/// Quotes an SQL identifier.
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
@@ -0,0 +1,183 @@
+//! Paired method-vs-method statistical tests across seeds.
+//!
+//! Each method's `rms_err` is compared against a reference method (`dsfb`) with
+//! the Wilcoxon signed-rank test on differences paired by seed, over the rows
+//! of the default run. Zero differences are dropped and tied absolute
+//! differences get mid-ranks. The p-value is exact for up to
+//! `EXACT_MAX_PAIRS` non-zero pairs and uses the tie-corrected normal
+//! approximation with continuity correction above that.
+
+use crate::io::SummaryRow;
+
+pub const STATS_REFERENCE_METHOD: &str = "dsfb";
+
+const EXACT_MAX_PAIRS: usize = 25;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WilcoxonResult {
+    pub n_nonzero: usize,
+    pub w_plus: f64,
+    pub w_minus: f64,
+    pub p_value: f64,
+    pub exact: bool,
+    /// Matched-pairs rank-biserial correlation `(W+ - W-) / (W+ + W-)`.
+    pub rank_biserial: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct StatsTestRow {
+    pub method: String,
+    pub reference: String,
+    pub metric: String,
+    pub n_pairs: usize,
+    pub median_diff: Option<f64>,
+    pub test: Option<WilcoxonResult>,
+}
+
+/// Two-sided Wilcoxon signed-rank test on paired differences.
+///
+/// Returns `None` when every difference is zero.
+pub fn wilcoxon_signed_rank(diffs: &[f64]) -> Option<WilcoxonResult> {
+    let mut nonzero: Vec<f64> = diffs.iter().copied().filter(|d| *d != 0.0).collect();
+    if nonzero.is_empty() {
+        return None;
+    }
+    nonzero.sort_by(|a, b| a.abs().total_cmp(&b.abs()));
+    let n = nonzero.len();
+
+    // Doubled mid-ranks keep tied ranks integral for the exact distribution.
+    let mut ranks2 = vec![0usize; n];
+    let mut tie_term = 0.0;
+    let mut i = 0;
+    while i < n {
+        let mut j = i + 1;
+        while j < n && nonzero[j].abs() == nonzero[i].abs() {
+            j += 1;
+        }
+        let t = (j - i) as f64;
+        tie_term += t * t * t - t;
+        for rank in ranks2.iter_mut().take(j).skip(i) {
+            *rank = i + j + 1;
+        }
+        i = j;
+    }
+
+    let w_plus2: usize = nonzero
+        .iter()
+        .zip(&ranks2)
+        .filter(|(d, _)| **d > 0.0)
+        .map(|(_, r)| *r)
+        .sum();
+    let total2: usize = ranks2.iter().sum();
+    let w_plus = w_plus2 as f64 / 2.0;
+    let w_minus = (total2 - w_plus2) as f64 / 2.0;
+
+    let (p_value, exact) = if n <= EXACT_MAX_PAIRS {
+        (exact_p_value(&ranks2, w_plus2), true)
+    } else {
+        let nf = n as f64;
+        let mean = nf * (nf + 1.0) / 4.0;
+        let var = nf * (nf + 1.0) * (2.0 * nf + 1.0) / 24.0 - tie_term / 48.0;
+        let p = if var > 0.0 {
+            let z = ((w_plus - mean).abs() - 0.5).max(0.0) / var.sqrt();
+            2.0 * (1.0 - std_normal_cdf(z))
+        } else {
+            1.0
+        };
+        (p, false)
+    };
+
+    Some(WilcoxonResult {
+        n_nonzero: n,
+        w_plus,
+        w_minus,
+        p_value: p_value.clamp(0.0, 1.0),
+        exact,
+        rank_biserial: (w_plus - w_minus) / (w_plus + w_minus),
+    })
+}
+
+/// Exact two-sided p-value from the null distribution of the doubled W+.
+fn exact_p_value(ranks2: &[usize], w_plus2: usize) -> f64 {
+    let total: usize = ranks2.iter().sum();
+    let mut counts = vec![0.0_f64; total + 1];
+    counts[0] = 1.0;
+    for &r in ranks2 {
+        for w in (r..=total).rev() {
+            counts[w] += counts[w - r];
+        }
+    }
+    let all: f64 = counts.iter().sum();
+    let lower: f64 = counts[..=w_plus2].iter().sum::<f64>() / all;
+    let upper: f64 = counts[w_plus2..].iter().sum::<f64>() / all;
+    (2.0 * lower.min(upper)).min(1.0)
+}
+
+/// Standard normal CDF via the Abramowitz-Stegun 7.1.26 erf approximation.
+fn std_normal_cdf(z: f64) -> f64 {
+    let x = z.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.327_591_1 * x);
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let erf = 1.0 - poly * (-x * x).exp();
+    if z >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
+fn median(values: &mut [f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    Some(if values.len() % 2 == 0 {
+        0.5 * (values[mid - 1] + values[mid])
+    } else {
+        values[mid]
+    })
+}
+
+/// Compares every method's `rms_err` against `reference`, pairing rows by
+/// seed. `rows` must hold at most one row per method and seed, as the default
+/// run does. Differences are `method - reference`, so a positive
+/// rank-biserial effect means the reference had lower error.
+pub fn build_stats_rows(rows: &[SummaryRow], reference: &str) -> Vec<StatsTestRow> {
+    let reference_rows: Vec<&SummaryRow> = rows.iter().filter(|r| r.method == reference).collect();
+
+    let mut methods = Vec::<String>::new();
+    for row in rows {
+        if row.method != reference && !methods.contains(&row.method) {
+            methods.push(row.method.clone());
+        }
+    }
+
+    methods
+        .into_iter()
+        .map(|method| {
+            let mut diffs: Vec<f64> = rows
+                .iter()
+                .filter(|r| r.method == method)
+                .filter_map(|r| {
+                    reference_rows
+                        .iter()
+                        .find(|b| b.seed == r.seed)
+                        .map(|b| r.rms_err - b.rms_err)
+                })
+                .collect();
+            let test = wilcoxon_signed_rank(&diffs);
+            StatsTestRow {
+                method,
+                reference: reference.to_string(),
+                metric: "rms_err".to_string(),
+                n_pairs: diffs.len(),
+                median_diff: median(&mut diffs),
+                test,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,205 @@
+//! Paper-ready LaTeX and Markdown tables built from in-memory summary rows.
+//!
+//! Each table has one row per method and one column per metric, formatted as
+//! `mean ± std` across seeds. The best (lowest) mean in each column is bolded.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::io::SummaryRow;
+
+/// A metric column: header label and accessor into a summary row.
+struct MetricColumn {
+    label: &'static str,
+    latex_label: &'static str,
+    value: fn(&SummaryRow) -> Option<f64>,
+}
+
+const METRIC_COLUMNS: [MetricColumn; 4] = [
+    MetricColumn {
+        label: "peak_err",
+        latex_label: "Peak error",
+        value: |row| Some(row.peak_err),
+    },
+    MetricColumn {
+        label: "rms_err",
+        latex_label: "RMS error",
+        value: |row| Some(row.rms_err),
+    },
+    MetricColumn {
+        label: "false_downweight_rate",
+        latex_label: "False downweight rate",
+        value: |row| row.false_downweight_rate,
+    },
+    MetricColumn {
+        label: "overhead_us",
+        latex_label: "Overhead ($\\mu$s)",
+        value: |row| Some(row.overhead_us),
+    },
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeanStd {
+    pub mean: f64,
+    pub std: f64,
+    pub count: usize,
+}
+
+/// Aggregated metrics for one method across seeds.
+#[derive(Debug, Clone)]
+pub struct TableRow {
+    pub method: String,
+    pub cells: Vec<Option<MeanStd>>,
+}
+
+/// Mean and sample standard deviation; `std` is zero for a single sample.
+pub fn mean_std(values: &[f64]) -> Option<MeanStd> {
+    if values.is_empty() {
+        return None;
+    }
+    let count = values.len();
+    let mean = values.iter().sum::<f64>() / count as f64;
+    let std = if count > 1 {
+        let ss: f64 = values.iter().map(|v| (v - mean) * (v - mean)).sum();
+        (ss / (count - 1) as f64).sqrt()
+    } else {
+        0.0
+    };
+    Some(MeanStd { mean, std, count })
+}
+
+/// Groups summary rows by method (in first-appearance order) and aggregates
+/// every metric column across the rows of that method.
+pub fn build_table_rows(rows: &[SummaryRow]) -> Vec<TableRow> {
+    let mut methods = Vec::<String>::new();
+    for row in rows {
+        if !methods.contains(&row.method) {
+            methods.push(row.method.clone());
+        }
+    }
+
+    methods
+        .into_iter()
+        .map(|method| {
+            let cells = METRIC_COLUMNS
+                .iter()
+                .map(|col| {
+                    let values: Vec<f64> = rows
+                        .iter()
+                        .filter(|row| row.method == method)
+                        .filter_map(|row| (col.value)(row))
+                        .collect();
+                    mean_std(&values)
+                })
+                .collect();
+            TableRow { method, cells }
+        })
+        .collect()
+}
+
+/// Index of the row with the lowest mean in each metric column.
+fn best_rows(rows: &[TableRow]) -> Vec<Option<usize>> {
+    (0..METRIC_COLUMNS.len())
+        .map(|col| {
+            rows.iter()
+                .enumerate()
+                .filter_map(|(idx, row)| row.cells[col].map(|cell| (idx, cell.mean)))
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(idx, _)| idx)
+        })
+        .collect()
+}
+
+fn fmt_cell(cell: MeanStd) -> (String, String) {
+    (format!("{:.4}", cell.mean), format!("{:.4}", cell.std))
+}
+
+pub fn render_markdown(rows: &[TableRow], caption: &str) -> String {
+    let best = best_rows(rows);
+    let mut out = format!("{caption}\n\n| method |");
+    for col in &METRIC_COLUMNS {
+        out.push_str(&format!(" {} |", col.label));
+    }
+    out.push_str("\n|---|");
+    for _ in &METRIC_COLUMNS {
+        out.push_str("---|");
+    }
+    out.push('\n');
+
+    for (row_idx, row) in rows.iter().enumerate() {
+        out.push_str(&format!("| {} |", row.method));
+        for (col_idx, cell) in row.cells.iter().enumerate() {
+            let text = match cell {
+                Some(cell) => {
+                    let (mean, std) = fmt_cell(*cell);
+                    if best[col_idx] == Some(row_idx) {
+                        format!("**{mean} ± {std}**")
+                    } else {
+                        format!("{mean} ± {std}")
+                    }
+                }
+                None => "NA".to_string(),
+            };
+            out.push_str(&format!(" {text} |"));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn latex_escape(s: &str) -> String {
+    s.replace('_', "\\_")
+}
+
+pub fn render_latex(rows: &[TableRow], caption: &str) -> String {
+    let best = best_rows(rows);
+    let mut out = String::new();
+    out.push_str("\\begin{table}[t]\n\\centering\n");
+    out.push_str(&format!("\\caption{{{}}}\n", latex_escape(caption)));
+    out.push_str(&format!(
+        "\\begin{{tabular}}{{l{}}}\n\\hline\n",
+        "c".repeat(METRIC_COLUMNS.len())
+    ));
+    out.push_str("Method");
+    for col in &METRIC_COLUMNS {
+        out.push_str(&format!(" & {}", col.latex_label));
+    }
+    out.push_str(" \\\\\n\\hline\n");
+
+    for (row_idx, row) in rows.iter().enumerate() {
+        out.push_str(&format!("\\texttt{{{}}}", latex_escape(&row.method)));
+        for (col_idx, cell) in row.cells.iter().enumerate() {
+            let text = match cell {
+                Some(cell) => {
+                    let (mean, std) = fmt_cell(*cell);
+                    if best[col_idx] == Some(row_idx) {
+                        format!("$\\mathbf{{{mean} \\pm {std}}}$")
+                    } else {
+                        format!("${mean} \\pm {std}$")
+                    }
+                }
+                None => "--".to_string(),
+            };
+            out.push_str(&format!(" & {text}"));
+        }
+        out.push_str(" \\\\\n");
+    }
+
+    out.push_str("\\hline\n\\end{tabular}\n\\end{table}\n");
+    out
+}
+
+/// Writes `tables.tex` and `tables.md` into `outdir` and returns their paths.
+pub fn write_tables(outdir: &Path, rows: &[SummaryRow], caption: &str) -> Result<Vec<PathBuf>> {
+    let table_rows = build_table_rows(rows);
+
+    let tex_path = outdir.join("tables.tex");
+    let md_path = outdir.join("tables.md");
+    fs::write(&tex_path, render_latex(&table_rows, caption))
+        .with_context(|| format!("failed to write LaTeX tables: {}", tex_path.display()))?;
+    fs::write(&md_path, render_markdown(&table_rows, caption))
+        .with_context(|| format!("failed to write Markdown tables: {}", md_path.display()))?;
+
+    Ok(vec![tex_path, md_path])
+}
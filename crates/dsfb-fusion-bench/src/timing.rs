@@ -1,4 +1,6 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+use crate::metrics::empirical_quantile;
 
 #[derive(Debug, Default, Clone)]
 pub struct TimingAccumulator {
@@ -28,3 +30,157 @@ impl TimingAccumulator {
         (self.total_time.as_secs_f64() * 1e6) / self.steps as f64
     }
 }
+
+/// Severe-outlier fence: samples more than this many interquartile ranges
+/// outside the quartiles are rejected, as in Criterion's outlier
+/// classification.
+pub const OUTLIER_IQR_FENCE: f64 = 3.0;
+
+/// Schedule of a micro-benchmark: `warmup` untimed calls, then `samples`
+/// timed batches of `batch` consecutive calls each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchSettings {
+    pub warmup: usize,
+    pub samples: usize,
+    pub batch: usize,
+}
+
+/// Distribution of per-call times, in microseconds, after outlier rejection.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SampleStats {
+    /// Samples kept after rejection
+    pub samples: usize,
+    pub outliers: usize,
+    pub mean_us: f64,
+    pub std_us: f64,
+    pub min_us: f64,
+    pub p50_us: f64,
+    pub p95_us: f64,
+    pub p99_us: f64,
+    pub max_us: f64,
+}
+
+/// Statistics of `samples_us` once samples beyond [`OUTLIER_IQR_FENCE`]
+/// interquartile ranges from the quartiles are dropped. Percentiles are
+/// empirical quantiles of the kept samples. `None` when there are no samples.
+pub fn sample_stats(samples_us: &[f64]) -> Option<SampleStats> {
+    if samples_us.is_empty() {
+        return None;
+    }
+    let mut sorted = samples_us.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let q1 = empirical_quantile(&sorted, 0.25);
+    let q3 = empirical_quantile(&sorted, 0.75);
+    let fence = OUTLIER_IQR_FENCE * (q3 - q1);
+    let kept: Vec<f64> = sorted
+        .iter()
+        .copied()
+        .filter(|&v| v >= q1 - fence && v <= q3 + fence)
+        .collect();
+
+    let count = kept.len();
+    let mean_us = kept.iter().sum::<f64>() / count as f64;
+    let std_us = if count > 1 {
+        let ss: f64 = kept.iter().map(|v| (v - mean_us) * (v - mean_us)).sum();
+        (ss / (count - 1) as f64).sqrt()
+    } else {
+        0.0
+    };
+    Some(SampleStats {
+        samples: count,
+        outliers: sorted.len() - count,
+        mean_us,
+        std_us,
+        min_us: kept[0],
+        p50_us: empirical_quantile(&kept, 0.50),
+        p95_us: empirical_quantile(&kept, 0.95),
+        p99_us: empirical_quantile(&kept, 0.99),
+        max_us: kept[count - 1],
+    })
+}
+
+/// Times `call` on the `settings` schedule and returns per-call statistics.
+/// Batching amortizes the clock reads over calls too short to time singly.
+pub fn bench(settings: BenchSettings, mut call: impl FnMut()) -> Option<SampleStats> {
+    bench_with_setup(settings, &mut (), |_, _| {}, |_| call())
+}
+
+/// [`bench()`] over a mutable `state`. Before each warmup call and each timed
+/// batch, `setup(state, calls)` runs outside the timer with the number of
+/// calls about to be made, so per-run housekeeping such as resetting a
+/// stateful method is not charged to the calls.
+pub fn bench_with_setup<S>(
+    settings: BenchSettings,
+    state: &mut S,
+    mut setup: impl FnMut(&mut S, usize),
+    mut call: impl FnMut(&mut S),
+) -> Option<SampleStats> {
+    for _ in 0..settings.warmup {
+        setup(state, 1);
+        call(state);
+    }
+    let batch = settings.batch.max(1);
+    let samples_us: Vec<f64> = (0..settings.samples)
+        .map(|_| {
+            setup(state, batch);
+            let t0 = Instant::now();
+            for _ in 0..batch {
+                call(state);
+            }
+            t0.elapsed().as_secs_f64() * 1e6 / batch as f64
+        })
+        .collect();
+    sample_stats(&samples_us)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_stats_drops_samples_beyond_the_iqr_fence() {
+        // Quartiles of the nine samples are 3 and 7, so the fence is
+        // 3 * (7 - 3) = 12 and keeps [-9, 19]
+        let samples = [4.0, 1.0, 100.0, 2.0, 8.0, 3.0, 7.0, 5.0, 6.0];
+        let stats = sample_stats(&samples).expect("non-empty samples");
+        assert_eq!((stats.samples, stats.outliers), (8, 1));
+        assert_eq!(stats.mean_us, 4.5);
+        // Squared deviations of 1..=8 from 4.5 sum to 42
+        assert!((stats.std_us - (42.0_f64 / 7.0).sqrt()).abs() < 1e-12);
+        assert_eq!((stats.min_us, stats.max_us), (1.0, 8.0));
+        assert_eq!(stats.p50_us, 4.0);
+        assert_eq!(stats.p95_us, 8.0);
+        assert_eq!(stats.p99_us, 8.0);
+
+        let mut on_fence = samples;
+        on_fence[2] = 19.0;
+        let stats = sample_stats(&on_fence).expect("non-empty samples");
+        assert_eq!((stats.samples, stats.outliers, stats.max_us), (9, 0, 19.0));
+    }
+
+    #[test]
+    fn sample_stats_of_no_samples_is_none() {
+        assert_eq!(sample_stats(&[]), None);
+    }
+
+    #[test]
+    fn bench_with_setup_runs_setup_before_each_batch() {
+        let settings = BenchSettings {
+            warmup: 2,
+            samples: 3,
+            batch: 4,
+        };
+        let mut log = Vec::new();
+        bench_with_setup(
+            settings,
+            &mut log,
+            |log, calls| log.push(calls),
+            |log| log.push(0),
+        );
+        let mut expected = vec![1, 0, 1, 0];
+        for _ in 0..3 {
+            expected.extend([4, 0, 0, 0, 0]);
+        }
+        assert_eq!(log, expected);
+    }
+}
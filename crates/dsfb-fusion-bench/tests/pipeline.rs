@@ -0,0 +1,1370 @@
+//! End-to-end runs of the `dsfb-fusion-bench` binary on a miniature config
+//! (20 steps, 2 seeds), checking every output file and its schema.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use dsfb_fusion_bench::io::OUTPUT_SCHEMA_VERSION;
+use serde_json::Value;
+
+const METHODS: usize = 6;
+const SEEDS: usize = 2;
+const STEPS: usize = 20;
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dsfb-fusion-bench-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("scratch dir");
+    dir
+}
+
+/// Writes the miniature config into `dir`, extending the shipped default.
+fn tiny_config(dir: &Path) -> PathBuf {
+    let default = Path::new(env!("CARGO_MANIFEST_DIR")).join("configs/default.toml");
+    let path = dir.join("tiny.toml");
+    fs::write(
+        &path,
+        format!(
+            "extends = {:?}\n\
+             steps = {STEPS}\n\
+             corruption_start = 8\n\
+             corruption_duration = 4\n\
+             seeds = [1, 2]\n\
+             state_error_dims = [0, 1]\n\
+             equal_deltas = true\n\
+             group_nis = true\n\
+             error_cdf_probabilities = [0.5, 1.0]\n\
+             compare_stacked_qr = true\n\
+             alpha_values = [0.8, 1.2]\n\
+             beta_values = [0.08, 0.12]\n\
+             sweep_objectives = [\"rms_err\"]\n",
+            default.display().to_string()
+        ),
+    )
+    .expect("write config");
+    path
+}
+
+/// Runs the binary with `args` into a fresh `outdir` and returns the run directory.
+fn run_bench(outdir: &Path, args: &[&str]) -> PathBuf {
+    let status = Command::new(env!("CARGO_BIN_EXE_dsfb-fusion-bench"))
+        .args(args)
+        .arg("--outdir")
+        .arg(outdir)
+        .status()
+        .expect("dsfb-fusion-bench starts");
+    assert!(status.success(), "dsfb-fusion-bench {args:?} failed");
+
+    let runs: Vec<PathBuf> = fs::read_dir(outdir)
+        .expect("outdir exists")
+        .map(|entry| entry.expect("dir entry").path())
+        .collect();
+    assert_eq!(runs.len(), 1, "expected one run directory in {outdir:?}");
+    runs.into_iter().next().unwrap()
+}
+
+fn file_names(dir: &Path) -> BTreeSet<String> {
+    fs::read_dir(dir)
+        .expect("run dir exists")
+        .map(|entry| entry.expect("dir entry").file_name().into_string().unwrap())
+        .collect()
+}
+
+fn names(files: &[&str]) -> BTreeSet<String> {
+    files.iter().map(|f| f.to_string()).collect()
+}
+
+/// Checks that every CSV in `run` ends with a `schema_version` column set to
+/// the output schema version, and returns the number of data rows per file.
+fn check_csv_schemas(run: &Path) -> Vec<(String, usize)> {
+    let mut rows = Vec::new();
+    for name in file_names(run).into_iter().filter(|n| n.ends_with(".csv")) {
+        let mut reader = csv::Reader::from_path(run.join(&name)).expect("csv opens");
+        let header = reader.headers().expect("csv header").clone();
+        let last = header.len() - 1;
+        assert_eq!(&header[last], "schema_version", "{name}");
+        let records: Vec<csv::StringRecord> =
+            reader.records().map(|r| r.expect("csv record")).collect();
+        for record in &records {
+            assert_eq!(record.len(), header.len(), "{name} row width");
+            assert_eq!(
+                &record[last], OUTPUT_SCHEMA_VERSION,
+                "{name} schema_version"
+            );
+        }
+        rows.push((name, records.len()));
+    }
+    rows
+}
+
+fn row_count(rows: &[(String, usize)], name: &str) -> usize {
+    rows.iter()
+        .find(|(n, _)| n == name)
+        .unwrap_or_else(|| panic!("{name} missing"))
+        .1
+}
+
+fn manifest(run: &Path) -> Value {
+    serde_json::from_str(&fs::read_to_string(run.join("manifest.json")).unwrap()).unwrap()
+}
+
+#[test]
+fn default_run_writes_every_output_and_reruns_identically() {
+    let scratch = scratch_dir("default");
+    let config = tiny_config(&scratch);
+    let config = config.to_str().unwrap();
+    let run = run_bench(
+        &scratch.join("out"),
+        &["--config", config, "--run-default", "--emit-tables"],
+    );
+
+    assert_eq!(
+        file_names(&run),
+        names(&[
+            "equal_deltas.csv",
+            "error_cdf.csv",
+            "group_downweight.csv",
+            "group_nis.csv",
+            "heatmap.csv",
+            "manifest.json",
+            "sim-dsfb-fusion-bench.csv",
+            "solver_comparison.csv",
+            "state_errors.csv",
+            "state_summary.csv",
+            "stats_tests.csv",
+            "summary.csv",
+            "tables.md",
+            "tables.tex",
+            "trajectories.csv",
+        ])
+    );
+
+    let rows = check_csv_schemas(&run);
+    assert_eq!(row_count(&rows, "summary.csv"), METHODS * SEEDS);
+    assert_eq!(
+        row_count(&rows, "trajectories.csv"),
+        METHODS * SEEDS * STEPS
+    );
+    assert_eq!(row_count(&rows, "group_nis.csv"), SEEDS * STEPS);
+    assert_eq!(row_count(&rows, "heatmap.csv"), 0);
+
+    // One row per group for each of the 4 weighted methods; only group 2 is
+    // corrupted, by the impulse on steps 8-11
+    assert_eq!(row_count(&rows, "group_downweight.csv"), 4 * SEEDS * 4);
+    let mut reader = csv::Reader::from_path(run.join("group_downweight.csv")).expect("csv opens");
+    for row in reader.records() {
+        let row = row.expect("csv record");
+        let count = |i: usize| row[i].parse::<usize>().expect("step count");
+        let corrupted = if &row[2] == "2" { 4 } else { 0 };
+        assert_eq!(count(5), corrupted, "{row:?}");
+        assert_eq!(count(3) + count(5), STEPS, "{row:?}");
+        assert_eq!(&row[6] == "NA", corrupted == 0, "{row:?}");
+        assert_eq!(count(7), usize::from(corrupted > 0), "{row:?}");
+        assert!(count(8) <= count(7), "{row:?}");
+        assert_eq!(&row[9] == "NA", count(8) == 0, "{row:?}");
+    }
+
+    let manifest = manifest(&run);
+    assert_eq!(manifest["mode"], "default");
+    assert!(manifest["output_hashes"].is_object());
+
+    let rerun = Command::new(env!("CARGO_BIN_EXE_dsfb-fusion-bench"))
+        .arg("--rerun")
+        .arg(&run)
+        .arg("--outdir")
+        .arg(scratch.join("rerun"))
+        .status()
+        .expect("dsfb-fusion-bench starts");
+    assert!(rerun.success(), "rerun did not reproduce the output hashes");
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn sweep_run_writes_grid_and_best_parameters() {
+    let scratch = scratch_dir("sweep");
+    let config = tiny_config(&scratch);
+    let config = config.to_str().unwrap();
+    let run = run_bench(
+        &scratch.join("out"),
+        &[
+            "--config",
+            config,
+            "--run-sweep",
+            "--emit-tables",
+            "--jobs",
+            "3",
+        ],
+    );
+
+    assert_eq!(
+        file_names(&run),
+        names(&[
+            "best_params.csv",
+            "heatmap.csv",
+            "manifest.json",
+            "marginals.csv",
+            "sim-dsfb-fusion-bench.csv",
+            "summary.csv",
+            "summary_sweep.csv",
+            "tables.md",
+            "tables.tex",
+            "trajectories.csv",
+        ])
+    );
+
+    // Two alpha values by two beta values
+    let cells = 4;
+    let rows = check_csv_schemas(&run);
+    assert_eq!(
+        row_count(&rows, "summary_sweep.csv"),
+        cells * METHODS * SEEDS
+    );
+    assert_eq!(row_count(&rows, "heatmap.csv"), cells * METHODS);
+    assert!(row_count(&rows, "best_params.csv") > 0);
+    assert_eq!(manifest(&run)["mode"], "sweep");
+
+    // A serial rerun reproduces the parallel outputs
+    let rerun = Command::new(env!("CARGO_BIN_EXE_dsfb-fusion-bench"))
+        .arg("--rerun")
+        .arg(&run)
+        .arg("--outdir")
+        .arg(scratch.join("rerun"))
+        .status()
+        .expect("dsfb-fusion-bench starts");
+    assert!(rerun.success(), "serial rerun diverged from --jobs 3");
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn null_and_edge_case_runs_write_their_outputs() {
+    let scratch = scratch_dir("null");
+    let config = tiny_config(&scratch);
+    let config = config.to_str().unwrap();
+
+    let null = run_bench(&scratch.join("null"), &["--config", config, "--run-null"]);
+    assert_eq!(
+        file_names(&null),
+        names(&["manifest.json", "null_summary.csv", "null_trajectories.csv"])
+    );
+    check_csv_schemas(&null);
+
+    let edge = run_bench(&scratch.join("edge"), &["--run-edge-cases"]);
+    assert_eq!(
+        file_names(&edge),
+        names(&["edge_cases.csv", "manifest.json"])
+    );
+    assert!(row_count(&check_csv_schemas(&edge), "edge_cases.csv") > 0);
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn fault_scenarios_record_ground_truth_labels() {
+    let scratch = scratch_dir("faults");
+    let tiny = tiny_config(&scratch);
+    let config = scratch.join("faults.toml");
+    let faults = "\n\
+        [[faults]]\n\
+        kind = \"bias_step\"\n\
+        offset = 0.5\n\
+        group = 0\n\
+        channels = [1, 3]\n\
+        start = 2\n\
+        duration = 5\n\
+        [[faults]]\n\
+        kind = \"variance_inflation\"\n\
+        factor = 4.0\n\
+        group = 3\n\
+        start = 5\n\
+        duration = 3\n\
+        [[faults]]\n\
+        kind = \"dropout\"\n\
+        group = 2\n\
+        channels = [0]\n\
+        start = 10\n\
+        duration = 2\n\
+        [[faults]]\n\
+        kind = \"ramp\"\n\
+        rate = 2.0\n\
+        group = 0\n\
+        channels = [0]\n\
+        start = 12\n\
+        duration = 4\n\
+        [[faults]]\n\
+        kind = \"stuck_at\"\n\
+        group = 1\n\
+        channels = [0]\n\
+        start = 15\n";
+    fs::write(
+        &config,
+        format!("extends = {:?}\n{faults}", tiny.display().to_string()),
+    )
+    .expect("write config");
+    let run = run_bench(
+        &scratch.join("out"),
+        &["--config", config.to_str().unwrap(), "--run-default"],
+    );
+
+    // Labelled channel-steps per seed: 2x5 bias, 5x3 variance, 1x2 dropout,
+    // 1x4 ramp, and 1x5 stuck-at through the last step
+    let rows = check_csv_schemas(&run);
+    assert_eq!(row_count(&rows, "fault_labels.csv"), SEEDS * 36);
+
+    // With the impulse on steps 8-11 the faults cover steps 2-19
+    let mut reader = csv::Reader::from_path(run.join("group_nis.csv")).expect("csv opens");
+    let active = reader
+        .records()
+        .filter(|r| &r.as_ref().expect("csv record")[2] == "1")
+        .count();
+    assert_eq!(active, SEEDS * 18);
+
+    let invalid = scratch.join("invalid.toml");
+    fs::write(
+        &invalid,
+        format!(
+            "extends = {:?}\n[[faults]]\nkind = \"dropout\"\ngroup = 9\nstart = 0\n",
+            tiny.display().to_string()
+        ),
+    )
+    .expect("write config");
+    let status = Command::new(env!("CARGO_BIN_EXE_dsfb-fusion-bench"))
+        .args(["--config", invalid.to_str().unwrap(), "--run-default"])
+        .arg("--outdir")
+        .arg(scratch.join("invalid-out"))
+        .status()
+        .expect("dsfb-fusion-bench starts");
+    assert!(!status.success(), "out-of-range fault group was accepted");
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn weight_response_ignores_faults_on_other_groups() {
+    use dsfb_fusion_bench::metrics::{weight_response, WeightResponse};
+    use dsfb_fusion_bench::sim::diagnostics::build_diagnostic_model;
+    use dsfb_fusion_bench::sim::state::{generate_simulation_data, BenchConfig};
+
+    let scratch = scratch_dir("other-group-fault");
+    let tiny = tiny_config(&scratch);
+    let config = scratch.join("other-group.toml");
+    // No impulse, and a fault on group 0 only; corruption_group stays 2
+    fs::write(
+        &config,
+        format!(
+            "extends = {:?}\n\
+             corruption_amplitude = 0.0\n\
+             [[faults]]\n\
+             kind = \"bias_step\"\n\
+             offset = 5.0\n\
+             group = 0\n\
+             start = 5\n\
+             duration = 8\n",
+            tiny.display().to_string()
+        ),
+    )
+    .expect("write config");
+    let cfg = BenchConfig::from_toml_file(&config).expect("config loads");
+    assert_ne!(cfg.corruption_group, 0);
+    let model = build_diagnostic_model(&cfg).expect("model builds");
+    let data = generate_simulation_data(&cfg, &model, 1).expect("data generates");
+
+    // A weight that dips over the fault window would be scored as a response
+    // against the group 0 labels
+    let dipping: Vec<f64> = (0..STEPS)
+        .map(|step| match step {
+            5..13 => 0.5_f64.powi(step as i32 - 4),
+            _ => 1.0,
+        })
+        .collect();
+    assert!(weight_response(&dipping, &data.group_active(0), cfg.dt)
+        .downweight_tau
+        .is_some());
+
+    let active = data.group_active(cfg.corruption_group);
+    assert!(active.iter().all(|&a| !a));
+    assert_eq!(
+        weight_response(&dipping, &active, cfg.dt),
+        WeightResponse::default()
+    );
+
+    // End to end, no method reports a response for the corruption group
+    let run = run_bench(
+        &scratch.join("out"),
+        &["--config", config.to_str().unwrap(), "--run-default"],
+    );
+    let mut reader = csv::Reader::from_path(run.join("summary.csv")).expect("csv opens");
+    let header = reader.headers().expect("csv header").clone();
+    let column = |name: &str| header.iter().position(|h| h == name).unwrap();
+    let (downweight, recovery) = (column("downweight_tau_s"), column("recovery_tau_s"));
+    for record in reader.records() {
+        let record = record.expect("csv record");
+        assert_eq!(&record[downweight], "NA", "{record:?}");
+        assert_eq!(&record[recovery], "NA", "{record:?}");
+    }
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn resumed_sweep_reuses_checkpointed_runs() {
+    use dsfb_fusion_bench::checkpoint::{CheckpointHeader, SweepCheckpoint, CHECKPOINT_FILE};
+    use dsfb_fusion_bench::io::SummaryRow;
+
+    let scratch = scratch_dir("resume");
+    let config = tiny_config(&scratch);
+    let full = run_bench(
+        &scratch.join("out"),
+        &[
+            "--config",
+            config.to_str().unwrap(),
+            "--run-sweep",
+            "--methods",
+            "equal,dsfb",
+        ],
+    );
+    assert!(!full.join(CHECKPOINT_FILE).exists());
+
+    // Interrupted after one run, with a second entry cut off mid-line
+    let original = manifest(&full);
+    let header = CheckpointHeader {
+        config: serde_json::from_value(original["config"].clone()).unwrap(),
+        methods: vec!["equal".to_string(), "dsfb".to_string()],
+        emit_tables: false,
+    };
+    let run = scratch.join("interrupted");
+    fs::create_dir_all(&run).unwrap();
+    let checkpoint = SweepCheckpoint::open(&run, &header).unwrap();
+    let noise_std = header.config.noise_std.clone();
+    checkpoint
+        .record(
+            &noise_std,
+            &SummaryRow {
+                method: "equal".to_string(),
+                seed: 1,
+                n: 0,
+                k: 0,
+                m: 0,
+                peak_err: 123.0,
+                rms_err: 123.0,
+                false_downweight_rate: None,
+                true_downweight_rate: None,
+                baseline_wls_us: 0.0,
+                overhead_us: 0.0,
+                total_us: 0.0,
+                alpha: Some(0.8),
+                beta: Some(0.08),
+                noise_ratio: 1.0,
+                mean_iterations: None,
+                max_iterations: None,
+                nonconvergence_rate: None,
+                downweight_tau_s: None,
+                recovery_tau_s: None,
+                detection_latency_s: None,
+                recovery_latency_s: None,
+                detection_auc: None,
+                tags: Vec::new(),
+            },
+        )
+        .unwrap();
+    drop(checkpoint);
+    let mut contents = fs::read_to_string(run.join(CHECKPOINT_FILE)).unwrap();
+    contents.push_str("{\"noise_std\":[0.04");
+    fs::write(run.join(CHECKPOINT_FILE), contents).unwrap();
+
+    let status = Command::new(env!("CARGO_BIN_EXE_dsfb-fusion-bench"))
+        .arg("--resume")
+        .arg(&run)
+        .args(["--jobs", "2"])
+        .status()
+        .expect("dsfb-fusion-bench starts");
+    assert!(status.success(), "--resume failed");
+    assert!(!run.join(CHECKPOINT_FILE).exists());
+
+    // The recorded run is reused as is; every other run is computed
+    let rows = check_csv_schemas(&run);
+    assert_eq!(row_count(&rows, "summary_sweep.csv"), 4 * 2 * SEEDS);
+    assert_eq!(row_count(&rows, "heatmap.csv"), 4 * 2);
+    let mut reader = csv::Reader::from_path(run.join("summary_sweep.csv")).expect("csv opens");
+    let sentinels = reader
+        .records()
+        .map(|r| r.expect("csv record"))
+        .filter(|r| r[6].parse::<f64>().expect("rms_err") == 123.0)
+        .count();
+    assert_eq!(sentinels, 1);
+    assert_eq!(manifest(&run)["mode"], "sweep");
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn bench_solver_reports_timing_percentiles() {
+    let scratch = scratch_dir("bench-solver");
+    let tiny = tiny_config(&scratch);
+    let config = scratch.join("bench.toml");
+    fs::write(
+        &config,
+        format!(
+            "extends = {:?}\nbench_warmup = 5\nbench_samples = 40\nbench_batch = 2\n",
+            tiny.display().to_string()
+        ),
+    )
+    .expect("write config");
+    let run = run_bench(
+        &scratch.join("out"),
+        &[
+            "--config",
+            config.to_str().unwrap(),
+            "--bench-solver",
+            "--methods",
+            "dsfb,equal",
+        ],
+    );
+
+    assert_eq!(
+        file_names(&run),
+        names(&["manifest.json", "solver_bench.csv"])
+    );
+    let rows = check_csv_schemas(&run);
+    assert_eq!(row_count(&rows, "solver_bench.csv"), 3);
+    let mut reader = csv::Reader::from_path(run.join("solver_bench.csv")).expect("csv opens");
+    let header = reader.headers().expect("csv header").clone();
+    let column = |name: &str| header.iter().position(|h| h == name).expect(name);
+    let records: Vec<csv::StringRecord> =
+        reader.records().map(|r| r.expect("csv record")).collect();
+    let methods: Vec<&str> = records.iter().map(|r| &r[0]).collect();
+    assert_eq!(methods, ["baseline_wls", "equal", "dsfb"]);
+    for record in &records {
+        let value = |name: &str| record[column(name)].parse::<f64>().expect(name);
+        assert_eq!(value("samples_kept") + value("outliers"), 40.0);
+        assert!(value("min_us") <= value("p50_us"));
+        assert!(value("p50_us") <= value("p95_us"));
+        assert!(value("p95_us") <= value("p99_us"));
+        assert!(value("p99_us") <= value("max_us"));
+    }
+    assert_eq!(manifest(&run)["mode"], "bench_solver");
+
+    // Timings and outlier counts are excluded from the hashes
+    let rerun = Command::new(env!("CARGO_BIN_EXE_dsfb-fusion-bench"))
+        .arg("--rerun")
+        .arg(&run)
+        .arg("--outdir")
+        .arg(scratch.join("rerun"))
+        .status()
+        .expect("dsfb-fusion-bench starts");
+    assert!(rerun.success(), "bench rerun diverged");
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn print_config_dumps_the_resolved_config() {
+    use dsfb_fusion_bench::sim::state::BenchConfig;
+
+    let scratch = scratch_dir("print-config");
+    let tiny = tiny_config(&scratch);
+    let config = scratch.join("extended.toml");
+    fs::write(
+        &config,
+        format!(
+            "extends = {:?}\n\
+             [preprocess]\n\
+             detrend = true\n\
+             [[faults]]\n\
+             kind = \"stuck_at\"\n\
+             group = 1\n\
+             start = 3\n",
+            tiny.display().to_string()
+        ),
+    )
+    .expect("write config");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_dsfb-fusion-bench"))
+        .args(["--config", config.to_str().unwrap(), "--print-config"])
+        .args(["--seed", "7", "--methods", "dsfb,equal"])
+        .arg("--outdir")
+        .arg(scratch.join("out"))
+        .output()
+        .expect("dsfb-fusion-bench starts");
+    assert!(output.status.success(), "--print-config failed");
+    assert!(
+        !scratch.join("out").exists(),
+        "--print-config wrote outputs"
+    );
+
+    // The dump is a standalone config: no includes, overrides applied
+    let printed = String::from_utf8(output.stdout).expect("utf-8 config");
+    assert!(!printed.contains("extends"));
+    let dumped = scratch.join("dumped.toml");
+    fs::write(&dumped, &printed).expect("write dump");
+    let cfg = BenchConfig::from_toml_file(&dumped).expect("dump loads");
+    assert_eq!(cfg.steps, STEPS);
+    assert_eq!(cfg.seeds, [7]);
+    assert_eq!(cfg.methods, ["equal", "dsfb"]);
+    assert_eq!(cfg.faults.len(), 1);
+    assert!(cfg.preprocess.is_some_and(|pre| pre.detrend));
+
+    let future = scratch.join("future.toml");
+    fs::write(
+        &future,
+        format!(
+            "extends = {:?}\nschema_version = \"9.0.0\"\n",
+            tiny.display().to_string()
+        ),
+    )
+    .expect("write config");
+    let error = BenchConfig::from_toml_file(&future).unwrap_err();
+    assert!(
+        format!("{error:#}").contains("schema_version 9.0.0 cannot be upgraded"),
+        "{error:#}"
+    );
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn config_migrations_rename_keys_and_fill_defaults() {
+    use dsfb_fusion_bench::sim::migrate::{apply_migrations, Migration};
+
+    let migrations = [
+        Migration {
+            from: "0.8.0",
+            to: "0.9.0",
+            renamed: &[("dsfb_floor", "dsfb_w_min")],
+            defaults: &[],
+        },
+        Migration {
+            from: "0.9.0",
+            to: "1.0.0",
+            renamed: &[],
+            defaults: &[("wls_summation", "\"naive\""), ("tags", "[]")],
+        },
+    ];
+    let mut table: toml::Table =
+        toml::from_str("schema_version = \"0.8.0\"\ndsfb_floor = 0.2\ntags = [\"a\"]\n").unwrap();
+    let warnings = apply_migrations(&migrations, "1.0.0", &mut table).expect("migrates");
+
+    assert_eq!(table["schema_version"].as_str(), Some("1.0.0"));
+    assert_eq!(table["dsfb_w_min"].as_float(), Some(0.2));
+    assert!(!table.contains_key("dsfb_floor"));
+    assert_eq!(table["wls_summation"].as_str(), Some("naive"));
+    // A key the file sets keeps its value
+    assert_eq!(table["tags"].as_array().map(Vec::len), Some(1));
+    // The rename, the default, and the upgrade itself
+    assert_eq!(warnings.len(), 3, "{warnings:?}");
+
+    let mut conflicting: toml::Table =
+        toml::from_str("schema_version = \"0.8.0\"\ndsfb_floor = 0.2\ndsfb_w_min = 0.1\n").unwrap();
+    assert!(apply_migrations(&migrations, "1.0.0", &mut conflicting).is_err());
+
+    let mut current: toml::Table = toml::from_str("schema_version = \"1.0.0\"\n").unwrap();
+    assert!(apply_migrations(&migrations, "1.0.0", &mut current)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn dsfb_core_adapter_runs_beside_dsfb() {
+    let scratch = scratch_dir("dsfb-core");
+    let config = tiny_config(&scratch);
+    let run = run_bench(
+        &scratch.join("out"),
+        &[
+            "--config",
+            config.to_str().unwrap(),
+            "--run-default",
+            "--methods",
+            "dsfb_core,dsfb",
+        ],
+    );
+
+    let mut reader = csv::Reader::from_path(run.join("summary.csv")).expect("csv opens");
+    let rows: Vec<csv::StringRecord> = reader.records().map(|r| r.expect("csv record")).collect();
+    let methods: Vec<&str> = rows.iter().map(|r| &r[0]).collect();
+    assert_eq!(methods, ["dsfb", "dsfb_core", "dsfb", "dsfb_core"]);
+    for row in &rows {
+        let rms: f64 = row[6].parse().expect("rms_err");
+        assert!(rms.is_finite() && rms > 0.0, "{row:?}");
+    }
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn ukf_baseline_filters_across_steps() {
+    let scratch = scratch_dir("ukf");
+    let config = tiny_config(&scratch);
+    let run = run_bench(
+        &scratch.join("out"),
+        &[
+            "--config",
+            config.to_str().unwrap(),
+            "--run-default",
+            "--methods",
+            "ukf,equal",
+        ],
+    );
+
+    let mut reader = csv::Reader::from_path(run.join("summary.csv")).expect("csv opens");
+    let rows: Vec<csv::StringRecord> = reader.records().map(|r| r.expect("csv record")).collect();
+    let methods: Vec<&str> = rows.iter().map(|r| &r[0]).collect();
+    assert_eq!(methods, ["equal", "ukf", "equal", "ukf"]);
+    for pair in rows.chunks(2) {
+        let rms = |row: &csv::StringRecord| row[6].parse::<f64>().expect("rms_err");
+        assert!(rms(&pair[1]).is_finite(), "{:?}", pair[1]);
+        // The filter has the true dynamics, so it beats the snapshot solve
+        assert!(rms(&pair[1]) < rms(&pair[0]), "{pair:?}");
+        assert_eq!(&pair[1][7], "NA", "ukf reports no group weights");
+    }
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn nonlinear_groups_solve_by_gauss_newton() {
+    let scratch = scratch_dir("nonlinear");
+    let tiny = tiny_config(&scratch);
+    let rms_by_jacobian = |jacobian: &str| {
+        let config = scratch.join(format!("{jacobian}.toml"));
+        fs::write(
+            &config,
+            format!(
+                "extends = {:?}\n\
+                 compare_stacked_qr = false\n\
+                 [[nonlinear_groups]]\n\
+                 group = 0\n\
+                 kind = \"range\"\n\
+                 jacobian = {jacobian:?}\n\
+                 [[nonlinear_groups]]\n\
+                 group = 3\n\
+                 kind = \"bearing\"\n\
+                 jacobian = {jacobian:?}\n",
+                tiny.display().to_string()
+            ),
+        )
+        .expect("write config");
+        let run = run_bench(
+            &scratch.join(jacobian),
+            &[
+                "--config",
+                config.to_str().unwrap(),
+                "--run-default",
+                "--methods",
+                "equal,irls_huber,nis_soft,ukf,vb_adaptive_r",
+            ],
+        );
+        let mut reader = csv::Reader::from_path(run.join("summary.csv")).expect("csv opens");
+        reader
+            .records()
+            .map(|r| r.expect("csv record")[6].parse::<f64>().expect("rms_err"))
+            .collect::<Vec<f64>>()
+    };
+
+    let analytic = rms_by_jacobian("analytic");
+    let numeric = rms_by_jacobian("numeric");
+    assert_eq!(analytic.len(), 5 * SEEDS);
+    for (a, n) in analytic.iter().zip(&numeric) {
+        assert!(a.is_finite() && *a < 0.5, "rms_err {a}");
+        // Central differences agree with the closed-form Jacobian
+        assert!((a - n).abs() < 1e-6, "analytic {a} vs numeric {n}");
+    }
+
+    // Rolling-mean preprocessing would mix range/bearing samples; normalize scales them
+    let preprocessed = |stage: &str| {
+        let config = scratch.join("preprocessed.toml");
+        fs::write(
+            &config,
+            format!(
+                "extends = {:?}\n\
+                 compare_stacked_qr = false\n\
+                 [[nonlinear_groups]]\n\
+                 group = 3\n\
+                 kind = \"bearing\"\n\
+                 [preprocess]\n\
+                 {stage}\n",
+                tiny.display().to_string()
+            ),
+        )
+        .expect("write config");
+        dsfb_fusion_bench::sim::state::BenchConfig::from_toml_file(&config)
+    };
+    for stage in ["detrend = true", "clip_sigma = 3.0"] {
+        let err = preprocessed(stage).expect_err(stage).to_string();
+        assert!(err.contains("nonlinear_groups"), "{err}");
+    }
+    assert!(preprocessed("normalize = true").is_ok());
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn correlated_noise_is_whitened() {
+    let scratch = scratch_dir("correlated");
+    let tiny = tiny_config(&scratch);
+    let config = scratch.join("correlated.toml");
+    fs::write(
+        &config,
+        format!(
+            "extends = {:?}\n\
+             steps = 200\n\
+             corruption_amplitude = 0.0\n\
+             noise_correlation = [0.8, 0.8, 0.0, -0.5]\n",
+            tiny.display().to_string()
+        ),
+    )
+    .expect("write config");
+    let run = run_bench(
+        &scratch.join("out"),
+        &[
+            "--config",
+            config.to_str().unwrap(),
+            "--run-default",
+            "--methods",
+            "equal,irls_huber,nis_soft,ukf,vb_adaptive_r",
+        ],
+    );
+
+    let mut reader = csv::Reader::from_path(run.join("summary.csv")).expect("csv opens");
+    for row in reader.records() {
+        let row = row.expect("csv record");
+        let rms = row[6].parse::<f64>().expect("rms_err");
+        assert!(rms.is_finite() && rms < 0.2, "{row:?}");
+    }
+
+    // Whitened residuals keep every group's mean NIS near its uncorrelated
+    // level, below 1 by the degrees of freedom the fit absorbs
+    let mut reader = csv::Reader::from_path(run.join("group_nis.csv")).expect("csv opens");
+    let rows: Vec<csv::StringRecord> = reader.records().map(|r| r.expect("csv record")).collect();
+    for k in 0..4 {
+        let mean = rows
+            .iter()
+            .map(|r| r[3 + k].parse::<f64>().expect("nis"))
+            .sum::<f64>()
+            / rows.len() as f64;
+        assert!((0.4..1.0).contains(&mean), "group {k} mean NIS {mean}");
+    }
+
+    let invalid = scratch.join("invalid.toml");
+    fs::write(
+        &invalid,
+        format!(
+            "extends = {:?}\nnoise_correlation = [1.0, 0.0, 0.0, 0.0]\n",
+            tiny.display().to_string()
+        ),
+    )
+    .expect("write config");
+    let status = Command::new(env!("CARGO_BIN_EXE_dsfb-fusion-bench"))
+        .args(["--config", invalid.to_str().unwrap(), "--run-default"])
+        .arg("--outdir")
+        .arg(scratch.join("invalid-out"))
+        .status()
+        .expect("dsfb-fusion-bench starts");
+    assert!(!status.success(), "singular noise correlation was accepted");
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn model_drift_changes_the_truth_from_its_start() {
+    let scratch = scratch_dir("drift");
+    let tiny = tiny_config(&scratch);
+    let drifted = scratch.join("drift.toml");
+    fs::write(
+        &drifted,
+        format!(
+            "extends = {:?}\n\
+             [[model_drift]]\n\
+             kind = \"gain\"\n\
+             factor = 3.0\n\
+             group = 1\n\
+             start = 10\n\
+             [[model_drift]]\n\
+             kind = \"noise\"\n\
+             factor = 4.0\n\
+             group = 0\n\
+             start = 10\n\
+             ramp_steps = 5\n\
+             [[model_drift]]\n\
+             kind = \"mixing\"\n\
+             amount = 0.5\n\
+             group = 3\n\
+             start = 12\n",
+            tiny.display().to_string()
+        ),
+    )
+    .expect("write config");
+    let err_norms = |config: &Path, name: &str| {
+        let run = run_bench(
+            &scratch.join(name),
+            &[
+                "--config",
+                config.to_str().unwrap(),
+                "--run-default",
+                "--methods",
+                "equal",
+                "--seed",
+                "1",
+            ],
+        );
+        let mut reader = csv::Reader::from_path(run.join("trajectories.csv")).expect("csv opens");
+        reader
+            .records()
+            .map(|r| r.expect("csv record")[2].parse::<f64>().expect("err_norm"))
+            .collect::<Vec<f64>>()
+    };
+
+    let nominal = err_norms(&tiny, "nominal");
+    let drift = err_norms(&drifted, "drift");
+    assert_eq!(nominal.len(), STEPS);
+    // The measurements only change once the drift starts
+    assert_eq!(nominal[..10], drift[..10]);
+    // Compared after the impulse on steps 8-11
+    let mean = |v: &[f64]| v.iter().sum::<f64>() / v.len() as f64;
+    assert!(
+        mean(&drift[12..]) > 1.5 * mean(&nominal[12..]),
+        "{drift:?} vs {nominal:?}"
+    );
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn vb_adaptive_r_downweights_the_corrupted_group() {
+    let scratch = scratch_dir("vb");
+    let config = tiny_config(&scratch);
+    let run = run_bench(
+        &scratch.join("out"),
+        &[
+            "--config",
+            config.to_str().unwrap(),
+            "--run-default",
+            "--methods",
+            "vb_adaptive_r",
+            "--seed",
+            "1",
+        ],
+    );
+
+    // Group 2 is corrupted on steps 8..12
+    let mut reader = csv::Reader::from_path(run.join("trajectories.csv")).expect("csv opens");
+    let header = reader.headers().expect("csv header").clone();
+    let w2 = header.iter().position(|h| h == "w_2").expect("w_2 column");
+    let weights: Vec<f64> = reader
+        .records()
+        .map(|r| r.expect("csv record")[w2].parse().expect("w_2"))
+        .collect();
+    assert_eq!(weights.len(), STEPS);
+    assert!(weights.iter().all(|w| (0.0..=1.0).contains(w)));
+    let before = weights[7];
+    let during = weights[8..12].iter().copied().fold(f64::INFINITY, f64::min);
+    assert!(during < 0.5 * before, "before {before}, during {during}");
+
+    // Flagged on the onset step, still low at the end of the run
+    let mut reader = csv::Reader::from_path(run.join("summary.csv")).expect("csv opens");
+    let header = reader.headers().expect("csv header").clone();
+    let column = |name: &str| header.iter().position(|h| h == name).expect(name);
+    let row = reader
+        .records()
+        .next()
+        .expect("summary row")
+        .expect("csv record");
+    assert_eq!(&row[column("detection_latency_s")], "0.0000000000");
+    assert_eq!(&row[column("recovery_latency_s")], "NA");
+    let auc: f64 = row[column("detection_auc")].parse().expect("detection_auc");
+    assert!(auc > 0.9 && auc <= 1.0, "auc {auc}");
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn bench_runner_runs_in_memory_and_matches_the_cli() {
+    use dsfb_fusion_bench::runner::{BenchRunner, RunMode, RunResults};
+    use dsfb_fusion_bench::sim::state::BenchConfig;
+
+    let scratch = scratch_dir("runner");
+    let config = tiny_config(&scratch);
+    let cfg = BenchConfig::from_toml_file(&config).expect("config loads");
+    let runner = BenchRunner::new(cfg)
+        .unwrap()
+        .with_methods(&["dsfb", "Equal"])
+        .unwrap();
+    assert_eq!(runner.methods(), ["equal", "dsfb"]);
+    assert!(runner.clone().with_methods(&["kalman"]).is_err());
+
+    // In memory: nothing is written
+    let results = runner.run_default().unwrap();
+    assert_eq!(results.summary.len(), 2 * SEEDS);
+    assert_eq!(results.trajectories.len(), 2 * SEEDS * STEPS);
+    assert_eq!(
+        results.group_downweights.len(),
+        SEEDS * runner.config().group_count()
+    );
+    assert_eq!(results.solver_comparison.len(), SEEDS);
+    // Equal-weight deltas: zero for `equal` by construction, one sign per state
+    let n = runner.config().n;
+    assert_eq!(results.equal_deltas.len(), 2 * SEEDS * STEPS);
+    for row in &results.equal_deltas {
+        assert_eq!(row.delta_signs.len(), n, "{row:?}");
+        if row.method == "equal" {
+            assert_eq!(row.delta_norm, 0.0, "{row:?}");
+            assert_eq!(row.delta_signs, "0".repeat(n), "{row:?}");
+        }
+    }
+    assert!(results
+        .equal_deltas
+        .iter()
+        .any(|row| row.method == "dsfb" && row.delta_norm > 0.0));
+    assert_eq!(results.error_cdf.len(), 2 * 2);
+    assert!(results.fault_labels.is_empty());
+    assert!(!scratch.join("lib").exists());
+
+    let sweep = runner.run_sweep().unwrap();
+    assert_eq!(
+        (sweep.cells, sweep.seeds, sweep.resumed_runs),
+        (4, SEEDS, 0)
+    );
+    assert_eq!(sweep.heatmap.len(), 4 * 2);
+
+    // Written out, the run is the one the CLI produces
+    let cli = run_bench(
+        &scratch.join("cli"),
+        &[
+            "--config",
+            config.to_str().unwrap(),
+            "--run-default",
+            "--methods",
+            "equal,dsfb",
+        ],
+    );
+    let lib = scratch.join("lib");
+    fs::create_dir_all(&lib).unwrap();
+    let (written, executed) = runner.execute(RunMode::Default, &lib, false, None).unwrap();
+    let RunResults::Default(executed) = executed else {
+        panic!("default run returned {executed:?}");
+    };
+    assert_eq!(executed.summary.len(), results.summary.len());
+    assert_eq!(file_names(&lib), file_names(&cli));
+    assert_eq!(
+        serde_json::to_value(&written.output_hashes).unwrap(),
+        manifest(&cli)["output_hashes"]
+    );
+    assert_eq!(manifest(&lib)["mode"], "default");
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sqlite_sink_records_runs_in_one_database() {
+    let scratch = scratch_dir("sqlite");
+    let config = tiny_config(&scratch);
+    let config = config.to_str().unwrap();
+    let db = scratch.join("results.db");
+    let db_arg = db.to_str().unwrap();
+
+    run_bench(
+        &scratch.join("default"),
+        &["--config", config, "--run-default", "--sqlite", db_arg],
+    );
+    let sweep = run_bench(
+        &scratch.join("sweep"),
+        &["--config", config, "--run-sweep", "--sqlite", db_arg],
+    );
+
+    let conn = rusqlite::Connection::open(&db).expect("database exists");
+    let count = |sql: &str| -> i64 { conn.query_row(sql, [], |row| row.get(0)).unwrap() };
+    assert_eq!(count("PRAGMA user_version"), 1);
+    assert_eq!(count("SELECT COUNT(*) FROM runs"), 2);
+    assert_eq!(
+        count("SELECT COUNT(*) FROM summary JOIN runs USING (run_id) WHERE mode = 'default'"),
+        (METHODS * SEEDS) as i64
+    );
+    assert_eq!(
+        count("SELECT COUNT(*) FROM trajectories"),
+        (METHODS * SEEDS * STEPS) as i64
+    );
+    assert_eq!(
+        count("SELECT COUNT(*) FROM heatmap WHERE method = 'dsfb' AND alpha = 0.8"),
+        2
+    );
+
+    // Recording a run again replaces it
+    let record = dsfb_fusion_bench::sqlite::record_run(&db, &sweep).expect("re-record sweep");
+    assert_eq!(record.tables["heatmap"], 4 * METHODS);
+    assert_eq!(count("SELECT COUNT(*) FROM runs"), 2);
+    assert_eq!(count("SELECT COUNT(*) FROM heatmap"), (4 * METHODS) as i64);
+
+    // ...also when the same directory is named by another path
+    let respelled = sweep
+        .join("..")
+        .join(sweep.file_name().expect("run dir name"));
+    dsfb_fusion_bench::sqlite::record_run(&db, &respelled).expect("re-record via another path");
+    assert_eq!(count("SELECT COUNT(*) FROM runs"), 2);
+
+    // Rows of another output layout are rejected, in the run or in the database
+    let stale = scratch.join("stale");
+    fs::create_dir_all(&stale).unwrap();
+    for entry in fs::read_dir(&sweep).unwrap() {
+        let path = entry.unwrap().path();
+        fs::copy(&path, stale.join(path.file_name().unwrap())).unwrap();
+    }
+    let summary = fs::read_to_string(stale.join("summary.csv")).unwrap();
+    fs::write(
+        stale.join("summary.csv"),
+        summary.replace(&format!(",{OUTPUT_SCHEMA_VERSION}\n"), ",1.0.0\n"),
+    )
+    .unwrap();
+    let err = dsfb_fusion_bench::sqlite::record_run(&db, &stale).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("schema_version 1.0.0"),
+        "{err:#}"
+    );
+    assert_eq!(count("SELECT COUNT(*) FROM runs"), 2);
+
+    conn.execute("UPDATE runs SET schema_version = '1.0.0'", [])
+        .unwrap();
+    let err = dsfb_fusion_bench::sqlite::record_run(&db, &sweep).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("holds runs with schema_version 1.0.0"),
+        "{err:#}"
+    );
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn tag_filter_keeps_any_included_tag_without_an_excluded_one() {
+    use dsfb_fusion_bench::aggregate::TagFilter;
+
+    let tags = |list: &[&str]| -> Vec<String> { list.iter().map(|t| t.to_string()).collect() };
+    let filter = TagFilter {
+        include: tags(&["ablation", "high-noise"]),
+        exclude: tags(&["flaky"]),
+    };
+    assert!(filter.matches(&tags(&["ablation"])));
+    assert!(filter.matches(&tags(&["baseline", "high-noise"])));
+    assert!(!filter.matches(&tags(&["baseline"])));
+    assert!(!filter.matches(&tags(&["ablation", "flaky"])));
+    assert!(!filter.matches(&[]));
+
+    // Without include tags every run matches unless excluded
+    let open = TagFilter {
+        include: Vec::new(),
+        exclude: tags(&["flaky"]),
+    };
+    assert!(open.matches(&[]));
+    assert!(open.matches(&tags(&["baseline"])));
+    assert!(!open.matches(&tags(&["flaky"])));
+    assert!(TagFilter::default().matches(&tags(&["anything"])));
+}
+
+#[test]
+fn aggregate_selects_runs_by_tag_and_rejects_other_schema_versions() {
+    let scratch = scratch_dir("aggregate");
+    let config = tiny_config(&scratch);
+    let tagged_run = |name: &str, tags: &str| {
+        let path = scratch.join(format!("{name}.toml"));
+        fs::write(
+            &path,
+            format!(
+                "extends = {:?}\ntags = [{tags}]\n",
+                config.display().to_string()
+            ),
+        )
+        .unwrap();
+        run_bench(
+            &scratch.join(name),
+            &["--config", path.to_str().unwrap(), "--run-default"],
+        )
+    };
+    let ablation = tagged_run("ablation", "\"ablation\", \"high-noise\"");
+    let baseline = tagged_run("baseline", "\"baseline\"");
+    let flaky = tagged_run("flaky", "\"ablation\", \"flaky\"");
+
+    let combined = run_bench(
+        &scratch.join("combined"),
+        &[
+            "--aggregate",
+            ablation.to_str().unwrap(),
+            baseline.to_str().unwrap(),
+            flaky.to_str().unwrap(),
+            "--include-tag",
+            "ablation",
+            "--exclude-tag",
+            "flaky",
+        ],
+    );
+    let aggregate: Value = serde_json::from_str(
+        &fs::read_to_string(combined.join("aggregate_manifest.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(aggregate["runs"].as_array().unwrap().len(), 1);
+    assert_eq!(aggregate["runs"][0]["run_dir"], ablation.to_str().unwrap());
+    assert_eq!(aggregate["skipped"].as_array().unwrap().len(), 2);
+    assert_eq!(aggregate["files"]["summary.csv"], METHODS * SEEDS);
+    let mut reader = csv::Reader::from_path(combined.join("summary.csv")).unwrap();
+    assert_eq!(&reader.headers().unwrap()[0], "run_dir");
+    for row in reader.records() {
+        assert_eq!(&row.unwrap()[0], ablation.to_str().unwrap());
+    }
+
+    // A tag flag needs --aggregate
+    let status = Command::new(env!("CARGO_BIN_EXE_dsfb-fusion-bench"))
+        .args(["--include-tag", "ablation", "--outdir"])
+        .arg(scratch.join("unused"))
+        .status()
+        .unwrap();
+    assert!(!status.success());
+
+    // A run of another schema version is rejected, by its manifest or by a
+    // row, even when the tag filter would skip it
+    let copy_run = |name: &str| {
+        let copy = scratch.join(name);
+        fs::create_dir_all(&copy).unwrap();
+        for entry in fs::read_dir(&baseline).unwrap() {
+            let path = entry.unwrap().path();
+            fs::copy(&path, copy.join(path.file_name().unwrap())).unwrap();
+        }
+        copy
+    };
+    let aggregate_err = |runs: &[&Path]| {
+        let runs: Vec<PathBuf> = runs.iter().map(|r| r.to_path_buf()).collect();
+        let filter = dsfb_fusion_bench::aggregate::TagFilter::default();
+        let err = dsfb_fusion_bench::aggregate::aggregate_runs(&runs, &filter, &scratch)
+            .expect_err("mixed schema versions are rejected");
+        format!("{err:#}")
+    };
+
+    let old_manifest = copy_run("old-manifest");
+    let manifest = fs::read_to_string(old_manifest.join("manifest.json")).unwrap();
+    fs::write(
+        old_manifest.join("manifest.json"),
+        manifest.replace(
+            &format!("\"schema_version\": \"{OUTPUT_SCHEMA_VERSION}\""),
+            "\"schema_version\": \"1.0.0\"",
+        ),
+    )
+    .unwrap();
+    let err = aggregate_err(&[&ablation, &old_manifest]);
+    assert!(err.contains("has schema_version 1.0.0"), "{err}");
+
+    let old_rows = copy_run("old-rows");
+    let summary = fs::read_to_string(old_rows.join("summary.csv")).unwrap();
+    fs::write(
+        old_rows.join("summary.csv"),
+        summary.replace(&format!(",{OUTPUT_SCHEMA_VERSION}\n"), ",1.0.0\n"),
+    )
+    .unwrap();
+    let err = aggregate_err(&[&ablation, &old_rows]);
+    assert!(err.contains("has a row with schema_version 1.0.0"), "{err}");
+    assert!(!scratch.join("aggregate_manifest.json").exists());
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn edge_case_scenarios_build_their_corner_of_the_problem() {
+    use dsfb_fusion_bench::edge_cases::{build_edge_case, run_edge_case, EdgeCase};
+    use dsfb_fusion_bench::runner::build_method;
+    use dsfb_fusion_bench::sim::state::BenchConfig;
+
+    let scratch = scratch_dir("edge-cases");
+    let cfg = BenchConfig::from_toml_file(&tiny_config(&scratch)).expect("config loads");
+    let build = |case| build_edge_case(&cfg, case, 1).expect("scenario builds");
+
+    let zero_noise = build(EdgeCase::ZeroNoiseGroup);
+    assert!(zero_noise.model.groups[0].r_diag.iter().all(|&r| r == 0.0));
+    assert!(zero_noise.model.groups[1].r_diag.iter().all(|&r| r > 0.0));
+
+    let single = build(EdgeCase::SingleGroup);
+    assert_eq!(single.model.groups.len(), 1);
+    assert_eq!(single.model.groups[0].h.nrows(), cfg.n + 2);
+
+    let all = build(EdgeCase::AllGroupsCorrupted);
+    assert!(all
+        .data
+        .group_corrupted
+        .iter()
+        .any(|groups| groups.iter().all(|&c| c)));
+
+    let rank_deficient = build(EdgeCase::RankDeficientH);
+    for group in &rank_deficient.model.groups {
+        assert!(group.h.column(0).iter().all(|&h| h == 0.0));
+    }
+
+    // Methods without group weights are only checked for finite estimates
+    let checks = |method: &str, scenario| {
+        let mut method = build_method(method).unwrap();
+        run_edge_case(scenario, method.as_mut())
+            .into_iter()
+            .map(|c| c.check)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(checks("equal", &single), ["finite_estimates"]);
+    assert_eq!(
+        checks("dsfb", &all),
+        [
+            "finite_estimates",
+            "weights_in_unit_interval",
+            "weights_recover"
+        ]
+    );
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn compare_timings_gates_on_percent_and_noise_floor() {
+    use dsfb_fusion_bench::perf::{compare_timings, MethodTiming, PerfThresholds};
+    use std::collections::BTreeMap;
+
+    let timing = |baseline_wls_us, overhead_us| MethodTiming {
+        baseline_wls_us,
+        overhead_us,
+    };
+    let baseline = BTreeMap::from([
+        ("dsfb".to_string(), timing(10.0, 0.0)),
+        ("equal".to_string(), timing(1.0, 0.0)),
+    ]);
+    let current = BTreeMap::from([
+        ("dsfb".to_string(), timing(13.0, 2.0)),
+        ("equal".to_string(), timing(1.4, 0.3)),
+        ("irls_huber".to_string(), timing(100.0, 100.0)),
+    ]);
+    let thresholds = PerfThresholds {
+        baseline_wls_pct: 25.0,
+        overhead_pct: 50.0,
+    };
+    let comparisons = compare_timings(&baseline, &current, thresholds);
+    let find = |method: &str, metric: &str| {
+        comparisons
+            .iter()
+            .find(|c| c.method == method && c.metric == metric)
+            .unwrap_or_else(|| panic!("no {method} {metric} comparison"))
+    };
+
+    // Methods missing from the baseline are not gated
+    assert_eq!(comparisons.len(), 4);
+    assert!(comparisons.iter().all(|c| c.method != "irls_huber"));
+
+    // 30% over a 25% limit and 3 us over the baseline
+    let wls = find("dsfb", "baseline_wls_us");
+    assert!((wls.change_pct.unwrap() - 30.0).abs() < 1e-9);
+    assert!(wls.regressed);
+
+    // 40% over, but within the 0.5 us noise floor
+    let wls = find("equal", "baseline_wls_us");
+    assert!((wls.change_pct.unwrap() - 40.0).abs() < 1e-9);
+    assert!(!wls.regressed);
+
+    // A zero baseline has no relative change; only the noise floor decides
+    let overhead = find("dsfb", "overhead_us");
+    assert_eq!(overhead.change_pct, None);
+    assert!(overhead.regressed);
+    let overhead = find("equal", "overhead_us");
+    assert_eq!(overhead.change_pct, None);
+    assert!(!overhead.regressed);
+}
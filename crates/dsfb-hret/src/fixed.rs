@@ -0,0 +1,225 @@
+//! Fixed-capacity HRET observer for hard real-time loops.
+
+use crate::instrument::{UpdateProfile, UpdateProfiler};
+use crate::{
+    validate_forgetting_factor, validate_forgetting_factors, validate_len,
+    validate_non_negative_finite, validate_positive, validate_positive_finite, HretError,
+    WEIGHT_SUM_EPS,
+};
+
+/// Result of a single [`HretObserverFixed`] update; the fused correction is
+/// written to the caller's buffer instead.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedUpdate<const M: usize, const G: usize> {
+    /// Normalized channel weights
+    pub weights: [f64; M],
+    /// Channel envelopes `s_k`
+    pub s_k: [f64; M],
+    /// Group envelopes `s_g`
+    pub s_g: [f64; G],
+}
+
+/// HRET observer with `M` channels and `G` groups fixed at compile time.
+///
+/// Computes the same envelopes, weights, and correction as
+/// [`HretObserver`](crate::HretObserver), but all per-update state lives in
+/// arrays and the correction is written into a caller-provided slice, so a
+/// successful [`update`](Self::update) never touches the heap. Only the
+/// construction of the gain matrix and error messages allocate.
+#[derive(Clone, Debug)]
+pub struct HretObserverFixed<const M: usize, const G: usize> {
+    group_mapping: [usize; M],
+    group_sizes: [usize; G],
+    rho: f64,
+    rho_g: [f64; G],
+    beta_k: [f64; M],
+    beta_g: [f64; G],
+    s_k: [f64; M],
+    s_g: [f64; G],
+    /// Gain matrix rows, shape `(p, M)`
+    k_k: Vec<[f64; M]>,
+    sigma_k: Option<[f64; M]>,
+    profiler: Option<UpdateProfiler>,
+}
+
+impl<const M: usize, const G: usize> HretObserverFixed<M, G> {
+    /// Constructs a new observer; the arguments match
+    /// [`HretObserver::new`](crate::HretObserver::new) with the channel and
+    /// group counts taken from `M` and `G`.
+    pub fn new(
+        group_mapping: [usize; M],
+        rho: f64,
+        rho_g: [f64; G],
+        beta_k: [f64; M],
+        beta_g: [f64; G],
+        k_k: Vec<[f64; M]>,
+        sigma_k: Option<[f64; M]>,
+    ) -> Result<Self, HretError> {
+        validate_positive("m", M)?;
+        validate_positive("g", G)?;
+        validate_forgetting_factor("rho", rho)?;
+        validate_forgetting_factors("rho_g", &rho_g)?;
+        validate_non_negative_finite("beta_k", &beta_k)?;
+        validate_non_negative_finite("beta_g", &beta_g)?;
+        if let Some(sigma_k) = &sigma_k {
+            validate_positive_finite("sigma_k", sigma_k)?;
+        }
+
+        let mut group_sizes = [0; G];
+        for (channel_idx, &group_idx) in group_mapping.iter().enumerate() {
+            if group_idx >= G {
+                return Err(HretError::invalid_input(format!(
+                    "group_mapping[{channel_idx}] = {group_idx} is out of range 0..{G}",
+                )));
+            }
+            group_sizes[group_idx] += 1;
+        }
+
+        if k_k.is_empty() {
+            return Err(HretError::invalid_input(
+                "k_k must contain at least one gain row",
+            ));
+        }
+        for (row_idx, row) in k_k.iter().enumerate() {
+            if let Some((col_idx, value)) = row.iter().enumerate().find(|(_, v)| !v.is_finite()) {
+                return Err(HretError::invalid_input(format!(
+                    "k_k[{row_idx}][{col_idx}] must be finite (got {value})",
+                )));
+            }
+        }
+
+        Ok(Self {
+            group_mapping,
+            group_sizes,
+            rho,
+            rho_g,
+            beta_k,
+            beta_g,
+            s_k: [0.0; M],
+            s_g: [0.0; G],
+            k_k,
+            sigma_k,
+            profiler: None,
+        })
+    }
+
+    /// Applies one HRET update and writes the fused correction to `delta_x`,
+    /// which must have length `p`.
+    ///
+    /// On error the envelopes are left unchanged.
+    pub fn update(
+        &mut self,
+        residuals: &[f64; M],
+        delta_x: &mut [f64],
+    ) -> Result<FixedUpdate<M, G>, HretError> {
+        let mut profiler = self.profiler.take();
+        let start = profiler.as_ref().map(UpdateProfiler::start);
+        let result = self.apply(residuals, delta_x);
+        if let (Some(profiler), Some(start)) = (&mut profiler, start) {
+            profiler.finish(start);
+        }
+        self.profiler = profiler;
+        result
+    }
+
+    fn apply(
+        &mut self,
+        residuals: &[f64; M],
+        delta_x: &mut [f64],
+    ) -> Result<FixedUpdate<M, G>, HretError> {
+        validate_len("delta_x", self.k_k.len(), delta_x.len())?;
+        if let Some((idx, value)) = residuals.iter().enumerate().find(|(_, r)| !r.is_finite()) {
+            return Err(HretError::invalid_input(format!(
+                "residuals[{idx}] must be finite; got {value}",
+            )));
+        }
+
+        let abs_r: [f64; M] = std::array::from_fn(|i| match &self.sigma_k {
+            Some(sigma_k) => (residuals[i] / sigma_k[i]).abs(),
+            None => residuals[i].abs(),
+        });
+
+        // Channel envelopes (eq. 8)
+        let rho = self.rho;
+        for (s, &r) in self.s_k.iter_mut().zip(&abs_r) {
+            *s = rho * *s + (1.0 - rho) * r;
+        }
+
+        // Group envelopes (eq. 11)
+        let mut group_sums = [0.0; G];
+        for (&group_idx, &r) in self.group_mapping.iter().zip(&abs_r) {
+            group_sums[group_idx] += r;
+        }
+        for (j, s) in self.s_g.iter_mut().enumerate() {
+            if self.group_sizes[j] == 0 {
+                continue;
+            }
+            let avg_abs_r = group_sums[j] / self.group_sizes[j] as f64;
+            *s = self.rho_g[j] * *s + (1.0 - self.rho_g[j]) * avg_abs_r;
+        }
+
+        // Trusts and hierarchical composition (eq. 9, 12, 14-15)
+        let w_g: [f64; G] = std::array::from_fn(|j| 1.0 / (1.0 + self.beta_g[j] * self.s_g[j]));
+        let mut weights: [f64; M] = std::array::from_fn(|i| {
+            let w_k = 1.0 / (1.0 + self.beta_k[i] * self.s_k[i]);
+            w_k * w_g[self.group_mapping[i]]
+        });
+        let sum_hat: f64 = weights.iter().sum();
+        for w in &mut weights {
+            *w = if sum_hat > WEIGHT_SUM_EPS {
+                *w / sum_hat
+            } else {
+                1.0 / M as f64
+            };
+        }
+
+        // Fusion correction (eq. 19): Delta_x = K * (tilde_w ⊙ r)
+        for (out, row) in delta_x.iter_mut().zip(&self.k_k) {
+            *out = (0..M).map(|i| row[i] * weights[i] * residuals[i]).sum();
+        }
+
+        Ok(FixedUpdate {
+            weights,
+            s_k: self.s_k,
+            s_g: self.s_g,
+        })
+    }
+
+    /// Resets the stored channel and group envelope state to zero.
+    pub fn reset_envelopes(&mut self) {
+        self.s_k = [0.0; M];
+        self.s_g = [0.0; G];
+    }
+
+    /// Starts recording per-update latency and allocations, discarding any
+    /// earlier profile.
+    pub fn enable_profiling(&mut self) {
+        self.profiler = Some(UpdateProfiler::default());
+    }
+
+    /// Stops recording and drops the collected profile.
+    pub fn disable_profiling(&mut self) {
+        self.profiler = None;
+    }
+
+    /// Statistics of the updates since [`enable_profiling`](Self::enable_profiling),
+    /// or `None` while profiling is off.
+    pub fn update_profile(&self) -> Option<UpdateProfile> {
+        self.profiler.as_ref().map(UpdateProfiler::profile)
+    }
+
+    /// Returns the current channel envelopes `s_k`.
+    pub fn channel_envelopes(&self) -> [f64; M] {
+        self.s_k
+    }
+
+    /// Returns the current group envelopes `s_g`.
+    pub fn group_envelopes(&self) -> [f64; G] {
+        self.s_g
+    }
+
+    /// Returns the correction dimension `p`.
+    pub fn correction_dim(&self) -> usize {
+        self.k_k.len()
+    }
+}
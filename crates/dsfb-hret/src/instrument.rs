@@ -0,0 +1,141 @@
+//! Opt-in update-time instrumentation.
+//!
+//! [`UpdateProfiler`] records the wall-clock latency of every observer update
+//! and, when [`CountingAllocator`] is the program's global allocator, the
+//! number of heap allocations the update made. Enable it on an observer with
+//! [`HretObserver::enable_profiling`](crate::HretObserver::enable_profiling)
+//! or [`HretObserverFixed::enable_profiling`](crate::HretObserverFixed::enable_profiling).
+//!
+//! ```rust,ignore
+//! #[global_allocator]
+//! static ALLOC: dsfb_hret::CountingAllocator = dsfb_hret::CountingAllocator;
+//! ```
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use pyo3::prelude::*;
+
+thread_local! {
+    static THREAD_ALLOCATIONS: Cell<u64> = const { Cell::new(0) };
+}
+
+static COUNTING_INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// System allocator that counts allocations per thread.
+///
+/// Allocations, zeroed allocations, and reallocations each count once;
+/// deallocations are not counted.
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn record() {
+        COUNTING_INSTALLED.store(true, Ordering::Relaxed);
+        let _ = THREAD_ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        Self::record();
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        Self::record();
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        Self::record();
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+/// Allocations made so far on the current thread, or `None` when
+/// [`CountingAllocator`] is not the global allocator.
+pub fn thread_allocations() -> Option<u64> {
+    if !COUNTING_INSTALLED.load(Ordering::Relaxed) {
+        return None;
+    }
+    THREAD_ALLOCATIONS.try_with(Cell::get).ok()
+}
+
+/// Latency and allocation statistics over the profiled updates.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[pyclass(get_all)]
+pub struct UpdateProfile {
+    /// Updates measured since profiling was enabled, failed ones included
+    pub updates: u64,
+    pub last_latency_ns: u64,
+    pub max_latency_ns: u64,
+    pub mean_latency_ns: f64,
+    /// Allocations of the most recent update; `None` without [`CountingAllocator`]
+    pub last_allocations: Option<u64>,
+    /// Most allocations in any single update
+    pub max_allocations: Option<u64>,
+    pub total_allocations: Option<u64>,
+}
+
+#[pymethods]
+impl UpdateProfile {
+    fn __repr__(&self) -> String {
+        format!(
+            "UpdateProfile(updates={}, max_latency_ns={}, mean_latency_ns={:.1}, max_allocations={:?})",
+            self.updates, self.max_latency_ns, self.mean_latency_ns, self.max_allocations
+        )
+    }
+}
+
+/// Accumulates an [`UpdateProfile`] from bracketed update calls.
+#[derive(Debug, Clone, Default)]
+pub struct UpdateProfiler {
+    profile: UpdateProfile,
+    total_latency: Duration,
+}
+
+/// Start of one profiled update, returned by [`UpdateProfiler::start`].
+#[derive(Debug, Clone, Copy)]
+pub struct UpdateStart {
+    at: Instant,
+    allocations: Option<u64>,
+}
+
+impl UpdateProfiler {
+    pub fn start(&self) -> UpdateStart {
+        UpdateStart {
+            allocations: thread_allocations(),
+            at: Instant::now(),
+        }
+    }
+
+    pub fn finish(&mut self, start: UpdateStart) {
+        let latency = start.at.elapsed();
+        let allocations = start
+            .allocations
+            .zip(thread_allocations())
+            .map(|(before, after)| after - before);
+
+        let profile = &mut self.profile;
+        self.total_latency += latency;
+        profile.updates += 1;
+        profile.last_latency_ns = u64::try_from(latency.as_nanos()).unwrap_or(u64::MAX);
+        profile.max_latency_ns = profile.max_latency_ns.max(profile.last_latency_ns);
+        profile.mean_latency_ns = self.total_latency.as_nanos() as f64 / profile.updates as f64;
+        profile.last_allocations = allocations;
+        if let Some(allocations) = allocations {
+            profile.max_allocations = Some(profile.max_allocations.unwrap_or(0).max(allocations));
+            profile.total_allocations = Some(profile.total_allocations.unwrap_or(0) + allocations);
+        }
+    }
+
+    pub fn profile(&self) -> UpdateProfile {
+        self.profile
+    }
+}
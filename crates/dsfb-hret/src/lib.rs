@@ -20,6 +20,7 @@
 //!         vec![1.0, 0.5, 0.5],
 //!         vec![0.0, 1.0, 0.0],
 //!     ],
+//!     None,
 //! )
 //! .unwrap();
 //!
@@ -32,12 +33,25 @@
 //!
 #![allow(clippy::useless_conversion)] // False positive from PyO3-generated PyResult signature.
 
+mod fixed;
+pub mod instrument;
+
+use std::collections::VecDeque;
+
 use ndarray::{Array1, Array2};
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 
+pub use fixed::{FixedUpdate, HretObserverFixed};
+use instrument::UpdateProfiler;
+pub use instrument::{CountingAllocator, UpdateProfile};
+
 const WEIGHT_SUM_EPS: f64 = 1e-12;
 
+/// Number of updates covered by [`HretObserver::weight_stats`] unless changed
+/// with [`HretObserver::set_weight_stats_window`].
+pub const DEFAULT_WEIGHT_STATS_WINDOW: usize = 256;
+
 /// Result of a single HRET update.
 ///
 /// The tuple components are, in order:
@@ -47,27 +61,242 @@ const WEIGHT_SUM_EPS: f64 = 1e-12;
 /// 4. group envelopes `s_g`
 pub type HretUpdate = (Vec<f64>, Vec<f64>, Vec<f64>, Vec<f64>);
 
-/// Error returned when HRET inputs fail validation.
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct HretError {
-    message: String,
+/// Result of a single HRET update against several gain matrices.
+///
+/// Same as [`HretUpdate`], except that the first component holds one
+/// correction per gain matrix, in the order the gains were given.
+pub type HretMultiUpdate = (Vec<Vec<f64>>, Vec<f64>, Vec<f64>, Vec<f64>);
+
+/// Error returned when HRET inputs fail validation; the workspace-wide
+/// [`dsfb_error::Error`].
+///
+/// Length checks return [`LengthMismatch`](dsfb_error::Error::LengthMismatch),
+/// an unknown channel [`IndexOutOfRange`](dsfb_error::Error::IndexOutOfRange),
+/// and every other check [`InvalidInput`](dsfb_error::Error::InvalidInput).
+/// Unlike the 0.1 `HretError` struct it is not `Clone` or `PartialEq`;
+/// compare errors with `matches!` or by their message.
+pub use dsfb_error::Error as HretError;
+
+/// Rule for carrying group envelope state across a channel-to-group remapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GroupCarryOver {
+    /// Zero every group envelope, as if the groups were freshly constructed.
+    Reset,
+    /// Set each group envelope to the mean of the previous group envelopes of its
+    /// newly assigned channels. Groups left without channels are zeroed.
+    Average,
+    /// Keep each group envelope attached to its group index.
+    #[default]
+    Inherit,
 }
 
-impl HretError {
-    fn new(message: impl Into<String>) -> Self {
-        Self {
-            message: message.into(),
+impl GroupCarryOver {
+    /// Parses a carry-over rule from `"reset"`, `"average"`, or `"inherit"`.
+    pub fn parse(value: &str) -> Result<Self, HretError> {
+        match value.to_ascii_lowercase().as_str() {
+            "reset" => Ok(Self::Reset),
+            "average" => Ok(Self::Average),
+            "inherit" => Ok(Self::Inherit),
+            other => Err(HretError::invalid_input(format!(
+                "carry_over must be one of reset, average, inherit (got {other})",
+            ))),
         }
     }
 }
 
-impl std::fmt::Display for HretError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.message)
+/// Rule for turning envelope state into a fault-suspicion score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SuspicionScoring {
+    /// The raw envelope (`s_k` or `s_g`).
+    Envelope,
+    /// One minus the unnormalized trust, `1 - 1 / (1 + beta * s)`, in `[0, 1)`.
+    #[default]
+    Distrust,
+    /// The envelope over the mean envelope of its peers (channels or groups);
+    /// `1.0` is typical, and every score is `0` while all envelopes are zero.
+    Relative,
+}
+
+impl SuspicionScoring {
+    /// Parses a scoring rule from `"envelope"`, `"distrust"`, or `"relative"`.
+    pub fn parse(value: &str) -> Result<Self, HretError> {
+        match value.to_ascii_lowercase().as_str() {
+            "envelope" => Ok(Self::Envelope),
+            "distrust" => Ok(Self::Distrust),
+            "relative" => Ok(Self::Relative),
+            other => Err(HretError::invalid_input(format!(
+                "scoring must be one of envelope, distrust, relative (got {other})",
+            ))),
+        }
     }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Envelope => "envelope",
+            Self::Distrust => "distrust",
+            Self::Relative => "relative",
+        }
+    }
+
+    fn scores(self, envelopes: &Array1<f64>, trusts: &Array1<f64>) -> Vec<f64> {
+        match self {
+            Self::Envelope => envelopes.to_vec(),
+            Self::Distrust => trusts.iter().map(|&w| 1.0 - w).collect(),
+            Self::Relative => {
+                let mean = envelopes.mean().unwrap_or(0.0);
+                envelopes
+                    .iter()
+                    .map(|&s| if mean > 0.0 { s / mean } else { 0.0 })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Suspicion entry for one channel in a [`FaultReport`].
+#[derive(Debug, Clone, PartialEq)]
+#[pyclass(get_all)]
+pub struct ChannelSuspicion {
+    pub channel: usize,
+    pub group: usize,
+    pub score: f64,
+    /// Channel envelope `s_k`
+    pub envelope: f64,
+    /// Unnormalized channel trust `1 / (1 + beta_k * s_k)`
+    pub trust: f64,
+    /// Normalized hierarchical fusion weight
+    pub weight: f64,
 }
 
-impl std::error::Error for HretError {}
+/// Suspicion entry for one group in a [`FaultReport`].
+#[derive(Debug, Clone, PartialEq)]
+#[pyclass(get_all)]
+pub struct GroupSuspicion {
+    pub group: usize,
+    pub score: f64,
+    /// Group envelope `s_g`
+    pub envelope: f64,
+    /// Unnormalized group trust `1 / (1 + beta_g * s_g)`
+    pub trust: f64,
+    /// Channels currently mapped to the group
+    pub channels: Vec<usize>,
+}
+
+/// Channels and groups ranked from most to least suspect.
+///
+/// Ties are broken by index, so the ranking is deterministic.
+#[derive(Debug, Clone, PartialEq)]
+#[pyclass(get_all)]
+pub struct FaultReport {
+    /// Name of the [`SuspicionScoring`] rule that produced the scores
+    pub scoring: String,
+    pub channels: Vec<ChannelSuspicion>,
+    pub groups: Vec<GroupSuspicion>,
+}
+
+#[pymethods]
+impl FaultReport {
+    fn __repr__(&self) -> String {
+        format!(
+            "FaultReport(scoring={}, top_channel={:?}, top_group={:?})",
+            self.scoring,
+            self.channels.first().map(|c| c.channel),
+            self.groups.first().map(|g| g.group)
+        )
+    }
+}
+
+/// Summary of one channel's normalized weight over the recent update window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[pyclass(get_all)]
+pub struct WeightStats {
+    /// Updates in the window (at most the window length)
+    pub count: usize,
+    pub mean: f64,
+    /// Population variance over the window
+    pub variance: f64,
+    pub min: f64,
+    /// Weight from the most recent update
+    pub last: f64,
+}
+
+#[pymethods]
+impl WeightStats {
+    fn __repr__(&self) -> String {
+        format!(
+            "WeightStats(count={}, mean={:.6}, variance={:.3e}, min={:.6})",
+            self.count, self.mean, self.variance, self.min
+        )
+    }
+}
+
+/// Sliding-window mean, variance, and minimum of one channel's weight.
+///
+/// Mean and variance are updated in O(1) per sample with Welford's recurrence
+/// extended to removals; the minimum is kept with a monotonic deque.
+#[derive(Clone, Debug)]
+struct WeightWindow {
+    len: usize,
+    values: VecDeque<f64>,
+    mean: f64,
+    m2: f64,
+    /// Candidate minima as `(sequence number, value)`, increasing in both
+    minima: VecDeque<(u64, f64)>,
+    seq: u64,
+}
+
+impl WeightWindow {
+    fn new(len: usize) -> Self {
+        Self {
+            len,
+            values: VecDeque::with_capacity(len),
+            mean: 0.0,
+            m2: 0.0,
+            minima: VecDeque::new(),
+            seq: 0,
+        }
+    }
+
+    fn push(&mut self, x: f64) {
+        if self.values.len() == self.len {
+            let old = self.values.pop_front().unwrap_or(x);
+            let old_mean = self.mean;
+            self.mean += (x - old) / self.len as f64;
+            self.m2 = (self.m2 + (x - old) * (x - self.mean + old - old_mean)).max(0.0);
+        } else {
+            let n = self.values.len() as f64 + 1.0;
+            let delta = x - self.mean;
+            self.mean += delta / n;
+            self.m2 += delta * (x - self.mean);
+        }
+        self.values.push_back(x);
+
+        while self.minima.back().is_some_and(|&(_, v)| v >= x) {
+            self.minima.pop_back();
+        }
+        self.minima.push_back((self.seq, x));
+        while self
+            .minima
+            .front()
+            .is_some_and(|&(seq, _)| seq + self.len as u64 <= self.seq)
+        {
+            self.minima.pop_front();
+        }
+        self.seq += 1;
+    }
+
+    fn stats(&self) -> Option<WeightStats> {
+        let last = *self.values.back()?;
+        let count = self.values.len();
+        Some(WeightStats {
+            count,
+            mean: self.mean,
+            variance: self.m2 / count as f64,
+            min: self.minima.front().map_or(last, |&(_, v)| v),
+            last,
+        })
+    }
+}
 
 #[derive(Clone, Debug)]
 #[pyclass]
@@ -87,6 +316,11 @@ pub struct HretObserver {
     s_k: Array1<f64>,
     s_g: Array1<f64>,
     k_k: Array2<f64>,
+    sigma_k: Option<Array1<f64>>,
+    weight_windows: Vec<WeightWindow>,
+    /// Normalized weights of the latest update, carried over by masked channels
+    last_weights: Array1<f64>,
+    profiler: Option<UpdateProfiler>,
 }
 
 impl HretObserver {
@@ -94,6 +328,11 @@ impl HretObserver {
     ///
     /// `k_k` is the fusion gain matrix with shape `(p, m)`, where `m` is the number
     /// of channels and `p` is the correction dimension.
+    ///
+    /// `sigma_k` optionally gives each channel's expected noise scale. When set,
+    /// residuals are divided by `sigma_k` before the channel and group envelope
+    /// updates, so channels in different units can share `beta` values. The fused
+    /// correction still uses the raw residuals.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         m: usize,
@@ -104,6 +343,7 @@ impl HretObserver {
         beta_k: Vec<f64>,
         beta_g: Vec<f64>,
         k_k: Vec<Vec<f64>>,
+        sigma_k: Option<Vec<f64>>,
     ) -> Result<Self, HretError> {
         validate_positive("m", m)?;
         validate_positive("g", g)?;
@@ -115,40 +355,13 @@ impl HretObserver {
         validate_forgetting_factors("rho_g", &rho_g)?;
         validate_non_negative_finite("beta_k", &beta_k)?;
         validate_non_negative_finite("beta_g", &beta_g)?;
-
-        let mut group_indices = vec![Vec::new(); g];
-        for (channel_idx, &group_idx) in group_mapping.iter().enumerate() {
-            if group_idx >= g {
-                return Err(HretError::new(format!(
-                    "group_mapping[{channel_idx}] = {group_idx} is out of range 0..{g}",
-                )));
-            }
-            group_indices[group_idx].push(channel_idx);
+        if let Some(sigma_k) = &sigma_k {
+            validate_len("sigma_k", m, sigma_k.len())?;
+            validate_positive_finite("sigma_k", sigma_k)?;
         }
 
-        if k_k.is_empty() {
-            return Err(HretError::new("k_k must contain at least one gain row"));
-        }
-
-        let p = k_k.len();
-        let mut k_k_flat = Vec::with_capacity(p * m);
-        for (row_idx, row) in k_k.into_iter().enumerate() {
-            validate_len(&format!("k_k[{row_idx}]"), m, row.len())?;
-            for (col_idx, value) in row.into_iter().enumerate() {
-                if !value.is_finite() {
-                    return Err(HretError::new(format!(
-                        "k_k[{row_idx}][{col_idx}] must be finite (got {value})",
-                    )));
-                }
-                k_k_flat.push(value);
-            }
-        }
-
-        let k_k = Array2::from_shape_vec((p, m), k_k_flat).map_err(|e| {
-            HretError::new(format!(
-                "failed to build gain matrix with shape ({p}, {m}): {e}",
-            ))
-        })?;
+        let group_indices = build_group_indices(&group_mapping, g)?;
+        let k_k = build_gain_matrix("k_k", &k_k, m)?;
 
         Ok(Self {
             m,
@@ -162,6 +375,10 @@ impl HretObserver {
             s_k: Array1::zeros(m),
             s_g: Array1::zeros(g),
             k_k,
+            sigma_k: sigma_k.map(Array1::from),
+            weight_windows: vec![WeightWindow::new(DEFAULT_WEIGHT_STATS_WINDOW); m],
+            last_weights: Array1::from_elem(m, 1.0 / m as f64),
+            profiler: None,
         })
     }
 
@@ -170,26 +387,197 @@ impl HretObserver {
     /// Returns the fused correction, normalized channel weights, updated channel
     /// envelopes, and updated group envelopes.
     pub fn update(&mut self, residuals: Vec<f64>) -> Result<HretUpdate, HretError> {
+        self.fuse(residuals, None)
+    }
+
+    /// Applies one HRET update in which only the channels with `valid[i]` set
+    /// carry a measurement.
+    ///
+    /// A masked channel is skipped rather than fed a placeholder residual: its
+    /// envelope is frozen, it is left out of its group's envelope average, and
+    /// its normalized weight is carried over from the previous update. The
+    /// valid channels share the remaining weight in proportion to their trust.
+    /// Masked residuals may hold any value, including NaN, and do not
+    /// contribute to the correction. A group with no valid channel keeps its
+    /// envelope.
+    pub fn update_masked(
+        &mut self,
+        residuals: Vec<f64>,
+        valid: &[bool],
+    ) -> Result<HretUpdate, HretError> {
+        self.fuse(residuals, Some(valid))
+    }
+
+    /// Shared body of [`update`](Self::update) and
+    /// [`update_masked`](Self::update_masked).
+    fn fuse(
+        &mut self,
+        residuals: Vec<f64>,
+        valid: Option<&[bool]>,
+    ) -> Result<HretUpdate, HretError> {
+        self.profiled(|obs| {
+            let (r_arr, tilde_w_k) = obs.apply_update(residuals, valid)?;
+
+            // Fusion correction (eq. 19): Delta_x = K * (tilde_w ⊙ r)
+            let delta_x = obs.k_k.dot(&(&tilde_w_k * &r_arr));
+
+            Ok((
+                delta_x.to_vec(),
+                tilde_w_k.to_vec(),
+                obs.s_k.to_vec(),
+                obs.s_g.to_vec(),
+            ))
+        })
+    }
+
+    /// Applies one HRET update and fuses the weighted residuals through each
+    /// of `gains` instead of the constructor's gain matrix.
+    ///
+    /// Each gain matrix has shape `(p_i, m)`, so estimator blocks with their
+    /// own correction dimensions (for example position and velocity) share one
+    /// set of envelopes and trust weights rather than each running a separate
+    /// observer on the same residuals. The envelopes advance once per call.
+    /// All gains are validated before any state changes.
+    pub fn update_multi(
+        &mut self,
+        residuals: Vec<f64>,
+        gains: &[Vec<Vec<f64>>],
+    ) -> Result<HretMultiUpdate, HretError> {
+        if gains.is_empty() {
+            return Err(HretError::invalid_input(
+                "gains must contain at least one gain matrix",
+            ));
+        }
+        let gains = gains
+            .iter()
+            .enumerate()
+            .map(|(idx, gain)| build_gain_matrix(&format!("gains[{idx}]"), gain, self.m))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.profiled(|obs| {
+            let (r_arr, tilde_w_k) = obs.apply_update(residuals, None)?;
+
+            let weighted_r = &tilde_w_k * &r_arr;
+            let delta_xs = gains
+                .iter()
+                .map(|gain| gain.dot(&weighted_r).to_vec())
+                .collect();
+
+            Ok((
+                delta_xs,
+                tilde_w_k.to_vec(),
+                obs.s_k.to_vec(),
+                obs.s_g.to_vec(),
+            ))
+        })
+    }
+
+    /// Runs `update` under the profiler, if one is enabled.
+    fn profiled<R>(&mut self, update: impl FnOnce(&mut Self) -> R) -> R {
+        let mut profiler = self.profiler.take();
+        let start = profiler.as_ref().map(UpdateProfiler::start);
+        let result = update(self);
+        if let (Some(profiler), Some(start)) = (&mut profiler, start) {
+            profiler.finish(start);
+        }
+        self.profiler = profiler;
+        result
+    }
+
+    /// Advances the envelopes and weight statistics with the residuals of the
+    /// `valid` channels (all channels when `None`), returning the residuals,
+    /// zeroed where masked, and the normalized weights to fuse them with.
+    fn apply_update(
+        &mut self,
+        mut residuals: Vec<f64>,
+        valid: Option<&[bool]>,
+    ) -> Result<(Array1<f64>, Array1<f64>), HretError> {
         validate_len("residuals", self.m, residuals.len())?;
+        if let Some(valid) = valid {
+            validate_len("valid", self.m, valid.len())?;
+        }
+        let is_valid = |i: usize| valid.map_or(true, |valid| valid[i]);
+        for (i, r) in residuals.iter_mut().enumerate() {
+            if !is_valid(i) {
+                *r = 0.0;
+            }
+        }
         validate_finite("residuals", &residuals)?;
 
         let r_arr = Array1::from(residuals);
+        let abs_r = match &self.sigma_k {
+            Some(sigma_k) => (&r_arr / sigma_k).mapv(f64::abs),
+            None => r_arr.mapv(f64::abs),
+        };
 
-        // Channel envelopes (eq. 8)
-        self.s_k = self.rho * &self.s_k + (1.0 - self.rho) * r_arr.mapv(f64::abs);
+        // Channel envelopes (eq. 8); masked channels keep theirs
+        for (i, (s, &r)) in self.s_k.iter_mut().zip(&abs_r).enumerate() {
+            if is_valid(i) {
+                *s = self.rho * *s + (1.0 - self.rho) * r;
+            }
+        }
 
-        // Group envelopes (eq. 11)
+        // Group envelopes (eq. 11) over the valid channels of each group
         for (group_idx, channels) in self.group_indices.iter().enumerate() {
-            if channels.is_empty() {
+            let (count, sum) = channels
+                .iter()
+                .filter(|&&i| is_valid(i))
+                .fold((0usize, 0.0), |(count, sum), &i| {
+                    (count + 1, sum + abs_r[i])
+                });
+            if count == 0 {
                 continue;
             }
 
-            let avg_abs_r =
-                channels.iter().map(|&i| r_arr[i].abs()).sum::<f64>() / channels.len() as f64;
+            let avg_abs_r = sum / count as f64;
             self.s_g[group_idx] = self.rho_g[group_idx] * self.s_g[group_idx]
                 + (1.0 - self.rho_g[group_idx]) * avg_abs_r;
         }
 
+        let (_, _, mut tilde_w_k) = self.trust_weights();
+        if let Some(valid) = valid.filter(|valid| valid.contains(&false)) {
+            self.carry_over_weights(&mut tilde_w_k, valid);
+        }
+
+        debug_assert!(tilde_w_k.iter().all(|&w| w >= -1e-12));
+        debug_assert!((tilde_w_k.sum() - 1.0).abs() < 1e-8);
+
+        for (window, &w) in self.weight_windows.iter_mut().zip(tilde_w_k.iter()) {
+            window.push(w);
+        }
+        self.last_weights.assign(&tilde_w_k);
+
+        Ok((r_arr, tilde_w_k))
+    }
+
+    /// Gives each masked channel its previous weight and rescales the valid
+    /// channels' weights in `tilde_w_k` to share the rest.
+    fn carry_over_weights(&self, tilde_w_k: &mut Array1<f64>, valid: &[bool]) {
+        let carried: f64 = (0..self.m)
+            .filter(|&i| !valid[i])
+            .map(|i| self.last_weights[i])
+            .sum();
+        let valid_count = valid.iter().filter(|&&v| v).count();
+        let valid_sum: f64 = (0..self.m)
+            .filter(|&i| valid[i])
+            .map(|i| tilde_w_k[i])
+            .sum();
+        let remaining = (1.0 - carried).max(0.0);
+
+        for (i, w) in tilde_w_k.iter_mut().enumerate() {
+            *w = if !valid[i] {
+                self.last_weights[i]
+            } else if valid_sum > WEIGHT_SUM_EPS {
+                remaining * *w / valid_sum
+            } else {
+                remaining / valid_count as f64
+            };
+        }
+    }
+
+    /// Channel trusts, group trusts, and normalized hierarchical weights for the
+    /// current envelopes.
+    fn trust_weights(&self) -> (Array1<f64>, Array1<f64>, Array1<f64>) {
         // Trusts (eq. 9, 12)
         let w_k =
             Array1::from_iter((0..self.m).map(|i| 1.0 / (1.0 + self.beta_k[i] * self.s_k[i])));
@@ -207,25 +595,160 @@ impl HretObserver {
             Array1::from_elem(self.m, 1.0 / self.m as f64)
         };
 
-        // Fusion correction (eq. 19): Delta_x = K * (tilde_w ⊙ r)
-        let weighted_r = &tilde_w_k * &r_arr;
-        let delta_x = self.k_k.dot(&weighted_r);
+        (w_k, w_g, tilde_w_k)
+    }
 
-        debug_assert!(tilde_w_k.iter().all(|&w| w >= -1e-12));
-        debug_assert!((tilde_w_k.sum() - 1.0).abs() < 1e-8);
+    /// Ranks channels and groups by fault suspicion from the current envelopes.
+    ///
+    /// Channels and groups are scored independently with `scoring`; a fault
+    /// confined to one channel shows up at the top of `channels`, while a
+    /// correlated disturbance across a group shows up at the top of `groups`.
+    pub fn fault_report(&self, scoring: SuspicionScoring) -> FaultReport {
+        let (w_k, w_g, tilde_w_k) = self.trust_weights();
+        let channel_scores = scoring.scores(&self.s_k, &w_k);
+        let group_scores = scoring.scores(&self.s_g, &w_g);
 
-        Ok((
-            delta_x.to_vec(),
-            tilde_w_k.to_vec(),
-            self.s_k.to_vec(),
-            self.s_g.to_vec(),
-        ))
+        let mut channels: Vec<ChannelSuspicion> = (0..self.m)
+            .map(|i| ChannelSuspicion {
+                channel: i,
+                group: self.group_mapping[i],
+                score: channel_scores[i],
+                envelope: self.s_k[i],
+                trust: w_k[i],
+                weight: tilde_w_k[i],
+            })
+            .collect();
+        channels.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.channel.cmp(&b.channel)));
+
+        let mut groups: Vec<GroupSuspicion> = (0..self.g)
+            .map(|i| GroupSuspicion {
+                group: i,
+                score: group_scores[i],
+                envelope: self.s_g[i],
+                trust: w_g[i],
+                channels: self.group_indices[i].clone(),
+            })
+            .collect();
+        groups.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.group.cmp(&b.group)));
+
+        FaultReport {
+            scoring: scoring.name().to_string(),
+            channels,
+            groups,
+        }
+    }
+
+    /// Mean, variance, and minimum of `channel`'s normalized weight over the
+    /// last [`weight_stats_window`](Self::weight_stats_window) updates, or
+    /// `None` before the first update.
+    ///
+    /// The statistics are maintained incrementally on every update, so long
+    /// runs can be trended without logging each weight.
+    pub fn weight_stats(&self, channel: usize) -> Result<Option<WeightStats>, HretError> {
+        let window = self
+            .weight_windows
+            .get(channel)
+            .ok_or(HretError::IndexOutOfRange {
+                context: "channel",
+                index: channel,
+                len: self.m,
+            })?;
+        Ok(window.stats())
     }
 
-    /// Resets the stored channel and group envelope state to zero.
+    /// Returns the number of updates covered by [`weight_stats`](Self::weight_stats).
+    pub fn weight_stats_window(&self) -> usize {
+        self.weight_windows[0].len
+    }
+
+    /// Sets the number of updates covered by [`weight_stats`](Self::weight_stats)
+    /// and clears the collected statistics.
+    pub fn set_weight_stats_window(&mut self, window: usize) -> Result<(), HretError> {
+        validate_positive("window", window)?;
+        self.weight_windows = vec![WeightWindow::new(window); self.m];
+        Ok(())
+    }
+
+    /// Resets the stored channel and group envelope state to zero, and the
+    /// weights carried over by masked channels to uniform.
     pub fn reset_envelopes(&mut self) {
         self.s_k.fill(0.0);
         self.s_g.fill(0.0);
+        self.last_weights.fill(1.0 / self.m as f64);
+    }
+
+    /// Starts recording per-update latency and allocations, discarding any
+    /// earlier profile. Allocations are only counted when
+    /// [`CountingAllocator`] is the global allocator.
+    ///
+    /// For loops with a hard latency budget, [`HretObserverFixed`] performs
+    /// the same update without heap allocation.
+    pub fn enable_profiling(&mut self) {
+        self.profiler = Some(UpdateProfiler::default());
+    }
+
+    /// Stops recording and drops the collected profile.
+    pub fn disable_profiling(&mut self) {
+        self.profiler = None;
+    }
+
+    /// Statistics of the updates since [`enable_profiling`](Self::enable_profiling),
+    /// or `None` while profiling is off.
+    pub fn update_profile(&self) -> Option<UpdateProfile> {
+        self.profiler.as_ref().map(UpdateProfiler::profile)
+    }
+
+    /// Remaps channels to groups without rebuilding the observer.
+    ///
+    /// Channel envelopes `s_k` are always preserved because they belong to the
+    /// channels themselves. Group envelopes `s_g` follow the `carry_over` rule.
+    /// The group count is fixed, so every entry must lie in `0..g`. On error the
+    /// observer is left unchanged.
+    pub fn set_group_mapping(
+        &mut self,
+        group_mapping: Vec<usize>,
+        carry_over: GroupCarryOver,
+    ) -> Result<(), HretError> {
+        validate_len("group_mapping", self.m, group_mapping.len())?;
+        let group_indices = build_group_indices(&group_mapping, self.g)?;
+
+        match carry_over {
+            GroupCarryOver::Reset => self.s_g.fill(0.0),
+            GroupCarryOver::Average => {
+                let previous = self.s_g.clone();
+                for (group_idx, channels) in group_indices.iter().enumerate() {
+                    self.s_g[group_idx] = if channels.is_empty() {
+                        0.0
+                    } else {
+                        channels
+                            .iter()
+                            .map(|&i| previous[self.group_mapping[i]])
+                            .sum::<f64>()
+                            / channels.len() as f64
+                    };
+                }
+            }
+            GroupCarryOver::Inherit => {}
+        }
+
+        self.group_mapping = Array1::from(group_mapping);
+        self.group_indices = group_indices;
+        Ok(())
+    }
+
+    /// Returns the current channel envelopes `s_k`.
+    pub fn channel_envelopes(&self) -> Vec<f64> {
+        self.s_k.to_vec()
+    }
+
+    /// Returns the current group envelopes `s_g`.
+    pub fn group_envelopes(&self) -> Vec<f64> {
+        self.s_g.to_vec()
+    }
+
+    /// Returns the per-channel noise scales, if residual normalization is enabled.
+    pub fn channel_sigmas(&self) -> Option<Vec<f64>> {
+        self.sigma_k.as_ref().map(Array1::to_vec)
     }
 
     /// Returns the configured number of residual channels.
@@ -247,7 +770,7 @@ impl HretObserver {
 #[pymethods]
 impl HretObserver {
     #[new]
-    #[pyo3(signature = (m, g, group_mapping, rho, rho_g, beta_k, beta_g, k_k))]
+    #[pyo3(signature = (m, g, group_mapping, rho, rho_g, beta_k, beta_g, k_k, sigma_k = None))]
     #[allow(clippy::too_many_arguments)]
     fn py_new(
         m: usize,
@@ -258,15 +781,38 @@ impl HretObserver {
         beta_k: Vec<f64>,
         beta_g: Vec<f64>,
         k_k: Vec<Vec<f64>>,
+        sigma_k: Option<Vec<f64>>,
     ) -> PyResult<Self> {
-        Self::new(m, g, group_mapping, rho, rho_g, beta_k, beta_g, k_k)
-            .map_err(|error| PyValueError::new_err(error.to_string()))
+        Self::new(
+            m,
+            g,
+            group_mapping,
+            rho,
+            rho_g,
+            beta_k,
+            beta_g,
+            k_k,
+            sigma_k,
+        )
+        .map_err(|error| PyValueError::new_err(error.to_string()))
     }
 
     #[pyo3(name = "update")]
+    #[pyo3(signature = (residuals, valid = None))]
     #[allow(clippy::useless_conversion)]
-    fn py_update(&mut self, residuals: Vec<f64>) -> PyResult<HretUpdate> {
-        self.update(residuals)
+    fn py_update(&mut self, residuals: Vec<f64>, valid: Option<Vec<bool>>) -> PyResult<HretUpdate> {
+        self.fuse(residuals, valid.as_deref())
+            .map_err(|error| PyValueError::new_err(error.to_string()))
+    }
+
+    #[pyo3(name = "update_multi")]
+    #[allow(clippy::useless_conversion)]
+    fn py_update_multi(
+        &mut self,
+        residuals: Vec<f64>,
+        gains: Vec<Vec<Vec<f64>>>,
+    ) -> PyResult<HretMultiUpdate> {
+        self.update_multi(residuals, &gains)
             .map_err(|error| PyValueError::new_err(error.to_string()))
     }
 
@@ -275,6 +821,58 @@ impl HretObserver {
         self.reset_envelopes();
     }
 
+    #[pyo3(name = "set_group_mapping")]
+    #[pyo3(signature = (group_mapping, carry_over = "inherit"))]
+    fn py_set_group_mapping(
+        &mut self,
+        group_mapping: Vec<usize>,
+        carry_over: &str,
+    ) -> PyResult<()> {
+        GroupCarryOver::parse(carry_over)
+            .and_then(|rule| self.set_group_mapping(group_mapping, rule))
+            .map_err(|error| PyValueError::new_err(error.to_string()))
+    }
+
+    #[pyo3(name = "fault_report")]
+    #[pyo3(signature = (scoring = "distrust"))]
+    fn py_fault_report(&self, scoring: &str) -> PyResult<FaultReport> {
+        SuspicionScoring::parse(scoring)
+            .map(|rule| self.fault_report(rule))
+            .map_err(|error| PyValueError::new_err(error.to_string()))
+    }
+
+    #[pyo3(name = "weight_stats")]
+    fn py_weight_stats(&self, channel: usize) -> PyResult<Option<WeightStats>> {
+        self.weight_stats(channel)
+            .map_err(|error| PyValueError::new_err(error.to_string()))
+    }
+
+    #[pyo3(name = "set_weight_stats_window")]
+    fn py_set_weight_stats_window(&mut self, window: usize) -> PyResult<()> {
+        self.set_weight_stats_window(window)
+            .map_err(|error| PyValueError::new_err(error.to_string()))
+    }
+
+    #[pyo3(name = "enable_profiling")]
+    fn py_enable_profiling(&mut self) {
+        self.enable_profiling();
+    }
+
+    #[pyo3(name = "disable_profiling")]
+    fn py_disable_profiling(&mut self) {
+        self.disable_profiling();
+    }
+
+    #[pyo3(name = "update_profile")]
+    fn py_update_profile(&self) -> Option<UpdateProfile> {
+        self.update_profile()
+    }
+
+    #[getter(weight_stats_window)]
+    fn py_weight_stats_window(&self) -> usize {
+        self.weight_stats_window()
+    }
+
     #[getter]
     fn m(&self) -> usize {
         self.channel_count()
@@ -290,6 +888,11 @@ impl HretObserver {
         self.group_mapping_vec()
     }
 
+    #[getter]
+    fn sigma_k(&self) -> Option<Vec<f64>> {
+        self.channel_sigmas()
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "HretObserver(m={}, g={}, p={})",
@@ -300,25 +903,76 @@ impl HretObserver {
     }
 }
 
+fn build_group_indices(group_mapping: &[usize], g: usize) -> Result<Vec<Vec<usize>>, HretError> {
+    let mut group_indices = vec![Vec::new(); g];
+    for (channel_idx, &group_idx) in group_mapping.iter().enumerate() {
+        if group_idx >= g {
+            return Err(HretError::invalid_input(format!(
+                "group_mapping[{channel_idx}] = {group_idx} is out of range 0..{g}",
+            )));
+        }
+        group_indices[group_idx].push(channel_idx);
+    }
+    Ok(group_indices)
+}
+
+/// Validates a `(p, m)` gain matrix given as rows and packs it into an array.
+fn build_gain_matrix(field: &str, rows: &[Vec<f64>], m: usize) -> Result<Array2<f64>, HretError> {
+    if rows.is_empty() {
+        return Err(HretError::invalid_input(format!(
+            "{field} must contain at least one gain row"
+        )));
+    }
+
+    let p = rows.len();
+    let mut flat = Vec::with_capacity(p * m);
+    for (row_idx, row) in rows.iter().enumerate() {
+        if row.len() != m {
+            return Err(HretError::invalid_input(format!(
+                "{field}[{row_idx}] length mismatch: expected {m}, got {}",
+                row.len()
+            )));
+        }
+        for (col_idx, &value) in row.iter().enumerate() {
+            if !value.is_finite() {
+                return Err(HretError::invalid_input(format!(
+                    "{field}[{row_idx}][{col_idx}] must be finite (got {value})",
+                )));
+            }
+            flat.push(value);
+        }
+    }
+
+    Array2::from_shape_vec((p, m), flat).map_err(|e| {
+        HretError::invalid_input(format!(
+            "failed to build gain matrix with shape ({p}, {m}): {e}",
+        ))
+    })
+}
+
 fn validate_positive(field: &str, value: usize) -> Result<(), HretError> {
     if value == 0 {
-        return Err(HretError::new(format!("{field} must be > 0 (got 0)")));
+        return Err(HretError::invalid_input(format!(
+            "{field} must be > 0 (got 0)"
+        )));
     }
     Ok(())
 }
 
-fn validate_len(field: &str, expected: usize, got: usize) -> Result<(), HretError> {
+fn validate_len(field: &'static str, expected: usize, got: usize) -> Result<(), HretError> {
     if expected != got {
-        return Err(HretError::new(format!(
-            "{field} length mismatch: expected {expected}, got {got}",
-        )));
+        return Err(HretError::LengthMismatch {
+            context: field,
+            expected,
+            got,
+        });
     }
     Ok(())
 }
 
 fn validate_forgetting_factor(field: &str, value: f64) -> Result<(), HretError> {
     if !value.is_finite() || value <= 0.0 || value >= 1.0 {
-        return Err(HretError::new(format!(
+        return Err(HretError::invalid_input(format!(
             "{field} must be finite and in (0, 1); got {value}",
         )));
     }
@@ -328,7 +982,7 @@ fn validate_forgetting_factor(field: &str, value: f64) -> Result<(), HretError>
 fn validate_forgetting_factors(field: &str, values: &[f64]) -> Result<(), HretError> {
     for (idx, value) in values.iter().copied().enumerate() {
         if !value.is_finite() || value <= 0.0 || value >= 1.0 {
-            return Err(HretError::new(format!(
+            return Err(HretError::invalid_input(format!(
                 "{field}[{idx}] must be finite and in (0, 1); got {value}",
             )));
         }
@@ -339,7 +993,7 @@ fn validate_forgetting_factors(field: &str, values: &[f64]) -> Result<(), HretEr
 fn validate_non_negative_finite(field: &str, values: &[f64]) -> Result<(), HretError> {
     for (idx, value) in values.iter().copied().enumerate() {
         if !value.is_finite() || value < 0.0 {
-            return Err(HretError::new(format!(
+            return Err(HretError::invalid_input(format!(
                 "{field}[{idx}] must be finite and >= 0; got {value}",
             )));
         }
@@ -347,10 +1001,21 @@ fn validate_non_negative_finite(field: &str, values: &[f64]) -> Result<(), HretE
     Ok(())
 }
 
+fn validate_positive_finite(field: &str, values: &[f64]) -> Result<(), HretError> {
+    for (idx, value) in values.iter().copied().enumerate() {
+        if !value.is_finite() || value <= 0.0 {
+            return Err(HretError::invalid_input(format!(
+                "{field}[{idx}] must be finite and > 0; got {value}",
+            )));
+        }
+    }
+    Ok(())
+}
+
 fn validate_finite(field: &str, values: &[f64]) -> Result<(), HretError> {
     for (idx, value) in values.iter().copied().enumerate() {
         if !value.is_finite() {
-            return Err(HretError::new(format!(
+            return Err(HretError::invalid_input(format!(
                 "{field}[{idx}] must be finite; got {value}",
             )));
         }
@@ -361,6 +1026,11 @@ fn validate_finite(field: &str, values: &[f64]) -> Result<(), HretError> {
 #[pymodule]
 fn dsfb_hret(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<HretObserver>()?;
+    m.add_class::<FaultReport>()?;
+    m.add_class::<ChannelSuspicion>()?;
+    m.add_class::<GroupSuspicion>()?;
+    m.add_class::<WeightStats>()?;
+    m.add_class::<UpdateProfile>()?;
     Ok(())
 }
 
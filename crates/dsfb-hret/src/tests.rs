@@ -1,4 +1,9 @@
-use super::HretObserver;
+use super::{
+    CountingAllocator, GroupCarryOver, HretError, HretObserver, HretObserverFixed, SuspicionScoring,
+};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 fn make_observer() -> HretObserver {
     HretObserver::new(
@@ -10,6 +15,7 @@ fn make_observer() -> HretObserver {
         vec![1.0, 1.0],
         vec![1.0, 1.0],
         vec![vec![1.0, 1.0]],
+        None,
     )
     .expect("observer construction should succeed")
 }
@@ -53,6 +59,7 @@ fn constructor_rejects_invalid_group_mapping_length() {
         vec![1.0, 1.0],
         vec![1.0],
         vec![vec![1.0, 1.0]],
+        None,
     )
     .expect_err("constructor should reject invalid mapping length");
 
@@ -70,6 +77,7 @@ fn constructor_rejects_out_of_range_group_indices() {
         vec![1.0, 1.0],
         vec![1.0],
         vec![vec![1.0, 1.0]],
+        None,
     )
     .expect_err("constructor should reject out-of-range group index");
 
@@ -87,6 +95,7 @@ fn constructor_rejects_invalid_forgetting_factor() {
         vec![1.0, 1.0],
         vec![1.0],
         vec![vec![1.0, 1.0]],
+        None,
     )
     .expect_err("constructor should reject rho outside (0, 1)");
 
@@ -104,6 +113,7 @@ fn constructor_rejects_empty_gain_matrix() {
         vec![1.0, 1.0],
         vec![1.0],
         vec![],
+        None,
     )
     .expect_err("constructor should reject empty gain matrix");
 
@@ -121,6 +131,7 @@ fn constructor_rejects_non_finite_gains() {
         vec![1.0, 1.0],
         vec![1.0],
         vec![vec![1.0, f64::INFINITY]],
+        None,
     )
     .expect_err("constructor should reject non-finite gains");
 
@@ -137,6 +148,103 @@ fn update_rejects_non_finite_residuals() {
     assert!(error.to_string().contains("residuals"));
 }
 
+#[test]
+fn update_masked_freezes_masked_channel_and_carries_its_weight() {
+    let mut obs = HretObserver::new(
+        3,
+        2,
+        vec![0, 0, 1],
+        0.5,
+        vec![0.5, 0.5],
+        vec![1.0, 1.0, 1.0],
+        vec![1.0, 1.0],
+        vec![vec![1.0, 1.0, 1.0]],
+        None,
+    )
+    .expect("observer construction should succeed");
+    let (_, weights_before, s_k_before, _) = obs.update(vec![0.2, 0.4, 0.6]).unwrap();
+
+    let (delta_x, weights, s_k, s_g) = obs
+        .update_masked(vec![0.2, f64::NAN, 0.6], &[true, false, true])
+        .expect("masked residuals need not be finite");
+
+    assert_eq!(s_k[1], s_k_before[1]);
+    assert_eq!(weights[1], weights_before[1]);
+    assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    // Group 0 averages only channel 0: 0.5 * 0.15 + 0.5 * 0.2
+    assert!((s_g[0] - 0.175).abs() < 1e-12);
+    assert!((delta_x[0] - (0.2 * weights[0] + 0.6 * weights[2])).abs() < 1e-12);
+}
+
+#[test]
+fn update_masked_with_all_channels_valid_matches_update() {
+    let mut plain = make_observer();
+    let mut masked = make_observer();
+
+    for residuals in [vec![1.0, 0.2], vec![0.4, -2.0], vec![0.1, 0.1]] {
+        assert_eq!(
+            masked
+                .update_masked(residuals.clone(), &[true, true])
+                .unwrap(),
+            plain.update(residuals).unwrap()
+        );
+    }
+}
+
+#[test]
+fn update_masked_rejects_bad_masks_and_non_finite_valid_residuals() {
+    let mut obs = make_observer();
+    let error = obs
+        .update_masked(vec![0.1, 0.2], &[true])
+        .expect_err("update_masked should reject a short mask");
+    assert!(error.to_string().contains("valid"));
+
+    let error = obs
+        .update_masked(vec![0.1, f64::NAN], &[false, true])
+        .expect_err("update_masked should reject NaN on a valid channel");
+    assert!(error.to_string().contains("residuals[1]"));
+    assert!(obs.channel_envelopes().iter().all(|&s| s == 0.0));
+}
+
+#[test]
+fn update_multi_shares_one_trust_hierarchy_across_gains() {
+    let mut single = make_observer();
+    let mut multi = make_observer();
+    let gains = vec![vec![vec![1.0, 1.0]], vec![vec![2.0, 0.0], vec![0.0, -1.0]]];
+
+    for residuals in [vec![1.0, 0.2], vec![0.4, -2.0], vec![0.1, 0.1]] {
+        let (delta_x, weights, s_k, s_g) = single.update(residuals.clone()).unwrap();
+        let (delta_xs, multi_weights, multi_s_k, multi_s_g) =
+            multi.update_multi(residuals.clone(), &gains).unwrap();
+
+        assert_eq!(delta_xs.len(), 2);
+        assert_eq!(delta_xs[0], delta_x);
+        assert!((delta_xs[1][0] - 2.0 * weights[0] * residuals[0]).abs() < 1e-12);
+        assert!((delta_xs[1][1] + weights[1] * residuals[1]).abs() < 1e-12);
+        assert_eq!(multi_weights, weights);
+        assert_eq!(multi_s_k, s_k);
+        assert_eq!(multi_s_g, s_g);
+    }
+}
+
+#[test]
+fn update_multi_rejects_invalid_gains_without_side_effects() {
+    let mut obs = make_observer();
+    let _ = obs.update(vec![1.0, 0.5]).expect("update should succeed");
+    let s_k = obs.channel_envelopes();
+
+    let error = obs
+        .update_multi(vec![0.3, 0.3], &[])
+        .expect_err("update_multi should reject an empty gain list");
+    assert!(error.to_string().contains("gain matrix"));
+
+    let error = obs
+        .update_multi(vec![0.3, 0.3], &[vec![vec![1.0, 1.0]], vec![vec![1.0]]])
+        .expect_err("update_multi should reject a gain with the wrong width");
+    assert!(error.to_string().contains("gains[1][0]"));
+    assert_eq!(obs.channel_envelopes(), s_k);
+}
+
 #[test]
 fn update_uses_uniform_weights_when_trusts_underflow() {
     let mut obs = HretObserver::new(
@@ -148,6 +256,7 @@ fn update_uses_uniform_weights_when_trusts_underflow() {
         vec![1e308, 1e308],
         vec![1e308],
         vec![vec![1.0, 1.0]],
+        None,
     )
     .expect("constructor should succeed");
 
@@ -159,3 +268,331 @@ fn update_uses_uniform_weights_when_trusts_underflow() {
     assert!((weights[1] - 0.5).abs() < 1e-12);
     assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-12);
 }
+
+#[test]
+fn set_group_mapping_preserves_channel_envelopes() {
+    let mut obs = make_observer();
+    let (_, _, s_k_before, _) = obs.update(vec![1.0, 0.5]).expect("update should succeed");
+
+    obs.set_group_mapping(vec![1, 1], GroupCarryOver::Reset)
+        .expect("remap should succeed");
+
+    assert_eq!(obs.group_mapping_vec(), vec![1, 1]);
+    assert_eq!(obs.channel_envelopes(), s_k_before);
+    assert!(obs.group_envelopes().iter().all(|&x| x == 0.0));
+}
+
+#[test]
+fn set_group_mapping_carry_over_rules() {
+    let mut obs = make_observer();
+    let _ = obs.update(vec![1.0, 0.5]).expect("update should succeed");
+    let s_g = obs.group_envelopes();
+    assert!((s_g[0] - 0.5).abs() < 1e-12);
+    assert!((s_g[1] - 0.25).abs() < 1e-12);
+
+    let mut inherit = obs.clone();
+    inherit
+        .set_group_mapping(vec![1, 0], GroupCarryOver::Inherit)
+        .expect("remap should succeed");
+    assert_eq!(inherit.group_envelopes(), s_g);
+
+    let mut average = obs.clone();
+    average
+        .set_group_mapping(vec![0, 0], GroupCarryOver::Average)
+        .expect("remap should succeed");
+    let averaged = average.group_envelopes();
+    assert!((averaged[0] - 0.375).abs() < 1e-12);
+    assert!(averaged[1].abs() < 1e-12);
+}
+
+#[test]
+fn set_group_mapping_rejects_invalid_mapping_without_side_effects() {
+    let mut obs = make_observer();
+    let _ = obs.update(vec![1.0, 0.5]).expect("update should succeed");
+    let s_g = obs.group_envelopes();
+
+    let error = obs
+        .set_group_mapping(vec![0, 2], GroupCarryOver::Reset)
+        .expect_err("remap should reject out-of-range group index");
+    assert!(error.to_string().contains("out of range"));
+    assert_eq!(obs.group_mapping_vec(), vec![0, 1]);
+    assert_eq!(obs.group_envelopes(), s_g);
+
+    assert!(GroupCarryOver::parse("median").is_err());
+}
+
+#[test]
+fn sigma_normalization_equalizes_envelopes_across_scales() {
+    let mut obs = HretObserver::new(
+        2,
+        1,
+        vec![0, 0],
+        0.5,
+        vec![0.5],
+        vec![1.0, 1.0],
+        vec![1.0],
+        vec![vec![1.0, 1.0]],
+        Some(vec![1000.0, 0.5]),
+    )
+    .expect("observer construction should succeed");
+
+    let (delta_x, weights, s_k, s_g) = obs
+        .update(vec![2000.0, -1.0])
+        .expect("update should succeed");
+
+    assert!((s_k[0] - 1.0).abs() < 1e-12);
+    assert!((s_k[1] - 1.0).abs() < 1e-12);
+    assert!((s_g[0] - 1.0).abs() < 1e-12);
+    assert!((weights[0] - weights[1]).abs() < 1e-12);
+    // The correction uses raw residuals.
+    assert!((delta_x[0] - 999.5).abs() < 1e-9);
+    assert_eq!(obs.channel_sigmas(), Some(vec![1000.0, 0.5]));
+}
+
+#[test]
+fn constructor_rejects_non_positive_sigma() {
+    for sigma in [0.0, -1.0, f64::NAN] {
+        let error = HretObserver::new(
+            2,
+            1,
+            vec![0, 0],
+            0.95,
+            vec![0.9],
+            vec![1.0, 1.0],
+            vec![1.0],
+            vec![vec![1.0, 1.0]],
+            Some(vec![1.0, sigma]),
+        )
+        .expect_err("constructor should reject non-positive sigma");
+
+        assert!(error.to_string().contains("sigma_k[1]"));
+    }
+}
+
+#[test]
+fn constructor_rejects_sigma_length_mismatch() {
+    let error = HretObserver::new(
+        2,
+        1,
+        vec![0, 0],
+        0.95,
+        vec![0.9],
+        vec![1.0, 1.0],
+        vec![1.0],
+        vec![vec![1.0, 1.0]],
+        Some(vec![1.0]),
+    )
+    .expect_err("constructor should reject sigma_k length mismatch");
+
+    assert!(error.to_string().contains("sigma_k"));
+    assert!(matches!(
+        error,
+        HretError::LengthMismatch {
+            context: "sigma_k",
+            expected: 2,
+            got: 1
+        }
+    ));
+}
+
+#[test]
+fn fault_report_ranks_faulty_channel_and_group_first() {
+    let mut obs = HretObserver::new(
+        3,
+        2,
+        vec![0, 0, 1],
+        0.5,
+        vec![0.5, 0.5],
+        vec![1.0, 1.0, 1.0],
+        vec![1.0, 1.0],
+        vec![vec![1.0, 1.0, 1.0]],
+        None,
+    )
+    .expect("observer construction should succeed");
+    for _ in 0..5 {
+        let _ = obs
+            .update(vec![0.05, 2.0, 0.1])
+            .expect("update should succeed");
+    }
+
+    let report = obs.fault_report(SuspicionScoring::Distrust);
+    assert_eq!(report.scoring, "distrust");
+    let ranked: Vec<usize> = report.channels.iter().map(|c| c.channel).collect();
+    assert_eq!(ranked, vec![1, 2, 0]);
+    assert_eq!(report.channels[0].group, 0);
+    assert!(report.channels.windows(2).all(|w| w[0].score >= w[1].score));
+    assert!(report
+        .channels
+        .iter()
+        .all(|c| (c.score - (1.0 - c.trust)).abs() < 1e-12));
+    let weight_sum: f64 = report.channels.iter().map(|c| c.weight).sum();
+    assert!((weight_sum - 1.0).abs() < 1e-12);
+
+    assert_eq!(report.groups[0].group, 0);
+    assert_eq!(report.groups[0].channels, vec![0, 1]);
+}
+
+#[test]
+fn fault_report_scoring_rules() {
+    let mut obs = make_observer();
+    let _ = obs.update(vec![3.0, 1.0]).expect("update should succeed");
+
+    let envelope = obs.fault_report(SuspicionScoring::Envelope);
+    assert!((envelope.channels[0].score - 1.5).abs() < 1e-12);
+    assert!((envelope.channels[1].score - 0.5).abs() < 1e-12);
+
+    let relative = obs.fault_report(SuspicionScoring::Relative);
+    assert_eq!(relative.channels[0].channel, 0);
+    assert!((relative.channels[0].score - 1.5).abs() < 1e-12);
+    assert!((relative.channels[1].score - 0.5).abs() < 1e-12);
+
+    obs.reset_envelopes();
+    let idle = obs.fault_report(SuspicionScoring::Relative);
+    assert!(idle.channels.iter().all(|c| c.score == 0.0));
+    let ranked: Vec<usize> = idle.channels.iter().map(|c| c.channel).collect();
+    assert_eq!(ranked, vec![0, 1]);
+
+    assert_eq!(
+        SuspicionScoring::parse("Relative").ok(),
+        Some(SuspicionScoring::Relative)
+    );
+    assert!(SuspicionScoring::parse("ratio").is_err());
+}
+
+#[test]
+fn weight_stats_track_sliding_window() {
+    let mut obs = make_observer();
+    assert_eq!(obs.weight_stats(0).expect("channel 0 exists"), None);
+    obs.set_weight_stats_window(4)
+        .expect("positive window should be accepted");
+
+    let mut history = Vec::new();
+    for step in 0..11 {
+        let fault = if step % 3 == 0 { 2.0 } else { 0.1 };
+        let (_, weights, _, _) = obs.update(vec![0.1, fault]).expect("update should succeed");
+        history.push(weights[1]);
+    }
+
+    let recent = &history[history.len() - 4..];
+    let mean = recent.iter().sum::<f64>() / 4.0;
+    let variance = recent.iter().map(|w| (w - mean).powi(2)).sum::<f64>() / 4.0;
+    let min = recent.iter().copied().fold(f64::INFINITY, f64::min);
+
+    let stats = obs
+        .weight_stats(1)
+        .expect("channel 1 exists")
+        .expect("stats after updates");
+    assert_eq!(stats.count, 4);
+    assert!((stats.mean - mean).abs() < 1e-12);
+    assert!((stats.variance - variance).abs() < 1e-12);
+    assert_eq!(stats.min, min);
+    assert_eq!(stats.last, history[history.len() - 1]);
+
+    assert!(matches!(
+        obs.weight_stats(2),
+        Err(HretError::IndexOutOfRange {
+            context: "channel",
+            index: 2,
+            len: 2
+        })
+    ));
+    assert!(obs.set_weight_stats_window(0).is_err());
+}
+
+fn make_fixed_pair() -> (HretObserver, HretObserverFixed<3, 2>) {
+    let gains = [[1.0, 0.5, 0.5], [0.0, 1.0, -0.25]];
+    let dynamic = HretObserver::new(
+        3,
+        2,
+        vec![0, 0, 1],
+        0.9,
+        vec![0.8, 0.85],
+        vec![1.5, 1.0, 2.0],
+        vec![1.0, 0.5],
+        gains.iter().map(|row| row.to_vec()).collect(),
+        Some(vec![0.1, 0.2, 0.5]),
+    )
+    .expect("observer construction should succeed");
+    let fixed = HretObserverFixed::new(
+        [0, 0, 1],
+        0.9,
+        [0.8, 0.85],
+        [1.5, 1.0, 2.0],
+        [1.0, 0.5],
+        gains.to_vec(),
+        Some([0.1, 0.2, 0.5]),
+    )
+    .expect("fixed observer construction should succeed");
+    (dynamic, fixed)
+}
+
+#[test]
+fn fixed_observer_matches_dynamic_observer() {
+    let (mut dynamic, mut fixed) = make_fixed_pair();
+    let mut delta_x = [0.0; 2];
+
+    for step in 0..50 {
+        let t = step as f64;
+        let residuals = [0.05 * t.sin(), 0.3 * (0.2 * t).cos(), 0.1 + 0.02 * t];
+        let (expected_dx, expected_w, expected_sk, expected_sg) = dynamic
+            .update(residuals.to_vec())
+            .expect("update should succeed");
+        let out = fixed
+            .update(&residuals, &mut delta_x)
+            .expect("fixed update should succeed");
+
+        let close = |a: &[f64], b: &[f64]| a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-12);
+        assert!(close(&delta_x, &expected_dx));
+        assert!(close(&out.weights, &expected_w));
+        assert!(close(&out.s_k, &expected_sk));
+        assert!(close(&out.s_g, &expected_sg));
+    }
+}
+
+#[test]
+fn fixed_observer_update_does_not_allocate() {
+    let (mut dynamic, mut fixed) = make_fixed_pair();
+    assert!(fixed.update_profile().is_none());
+    dynamic.enable_profiling();
+    fixed.enable_profiling();
+
+    let mut delta_x = [0.0; 2];
+    for _ in 0..20 {
+        dynamic
+            .update(vec![0.1, -0.2, 0.3])
+            .expect("update should succeed");
+        fixed
+            .update(&[0.1, -0.2, 0.3], &mut delta_x)
+            .expect("fixed update should succeed");
+    }
+
+    let fixed_profile = fixed.update_profile().expect("profiling is enabled");
+    assert_eq!(fixed_profile.updates, 20);
+    assert_eq!(fixed_profile.max_allocations, Some(0));
+    assert!(fixed_profile.max_latency_ns >= fixed_profile.last_latency_ns);
+
+    let dynamic_profile = dynamic.update_profile().expect("profiling is enabled");
+    assert_eq!(dynamic_profile.updates, 20);
+    assert!(dynamic_profile.last_allocations.is_some_and(|n| n > 0));
+}
+
+#[test]
+fn fixed_observer_rejects_bad_inputs() {
+    let error = HretObserverFixed::<2, 1>::new(
+        [0, 1],
+        0.9,
+        [0.9],
+        [1.0, 1.0],
+        [1.0],
+        vec![[1.0, 1.0]],
+        None,
+    )
+    .expect_err("constructor should reject out-of-range group index");
+    assert!(error.to_string().contains("group_mapping[1]"));
+
+    let (_, mut fixed) = make_fixed_pair();
+    let mut wrong_len = [0.0; 3];
+    assert!(fixed.update(&[0.1, 0.1, 0.1], &mut wrong_len).is_err());
+    assert!(fixed.update(&[f64::NAN, 0.1, 0.1], &mut [0.0; 2]).is_err());
+    assert_eq!(fixed.channel_envelopes(), [0.0; 3]);
+}
@@ -0,0 +1,152 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::Args;
+
+/// Parameters of the correlated group fault experiment.
+///
+/// Missing fields in a JSON file fall back to the defaults below, which
+/// reproduce the original hard-coded experiment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct ExperimentConfig {
+    /// Channel indices of each group; together they must cover `0..k` once.
+    /// The fault is injected into the first group.
+    pub groups: Vec<Vec<usize>>,
+    /// Envelope forgetting factor
+    pub rho: f64,
+    /// Channel trust sensitivity
+    pub beta: f64,
+    /// Group trust sensitivity
+    pub beta_g: f64,
+    pub fault_amp: f64,
+    pub fault_start: usize,
+    pub fault_duration: usize,
+    pub process_noise_std: f64,
+    pub meas_noise_std: f64,
+}
+
+impl Default for ExperimentConfig {
+    fn default() -> Self {
+        Self {
+            groups: vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7]],
+            rho: 0.95,
+            beta: 4.0,
+            beta_g: 4.0,
+            fault_amp: 2.0,
+            fault_start: 200,
+            fault_duration: 40,
+            process_noise_std: 0.01,
+            meas_noise_std: 0.05,
+        }
+    }
+}
+
+/// Quantities derived from an `ExperimentConfig`, exported for provenance.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct DerivedConstants {
+    pub k_channels: usize,
+    pub fault_end: usize,
+    /// Envelope time constant `1 / (1 - rho)` in steps
+    pub envelope_time_constant: f64,
+}
+
+/// Resolved configuration written as `experiment_config.json` in the run directory.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct ResolvedExperiment<'a> {
+    pub seed: u64,
+    pub time_steps: usize,
+    pub config: &'a ExperimentConfig,
+    pub derived: DerivedConstants,
+}
+
+impl ExperimentConfig {
+    /// Defaults, or the JSON file from `--experiment-config`, with CLI overrides applied.
+    pub fn resolve(args: &Args) -> Result<Self> {
+        let mut cfg = match &args.experiment_config {
+            Some(path) => Self::from_json(path)?,
+            None => Self::default(),
+        };
+
+        if let Some(rho) = args.rho {
+            cfg.rho = rho;
+        }
+        if let Some(beta) = args.beta {
+            cfg.beta = beta;
+        }
+        if let Some(beta_g) = args.beta_g {
+            cfg.beta_g = beta_g;
+        }
+        if let Some(fault_amp) = args.fault_amp {
+            cfg.fault_amp = fault_amp;
+        }
+        if let Some(fault_start) = args.fault_start {
+            cfg.fault_start = fault_start;
+        }
+        if let Some(fault_duration) = args.fault_duration {
+            cfg.fault_duration = fault_duration;
+        }
+
+        cfg.validate()?;
+        Ok(cfg)
+    }
+
+    pub fn from_json(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read experiment config: {}", path.display()))?;
+        serde_json::from_str(&text)
+            .with_context(|| format!("failed to parse experiment config: {}", path.display()))
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.groups.is_empty() || self.groups.iter().any(|g| g.is_empty()) {
+            bail!("groups must be non-empty and contain no empty group");
+        }
+        let k = self.k_channels();
+        let mut seen = vec![false; k];
+        for &channel in self.groups.iter().flatten() {
+            if channel >= k || seen[channel] {
+                bail!("groups must cover channels 0..{} exactly once", k);
+            }
+            seen[channel] = true;
+        }
+        if !(0.0..1.0).contains(&self.rho) {
+            bail!("rho must be in [0, 1), got {}", self.rho);
+        }
+        if self.beta < 0.0 || self.beta_g < 0.0 {
+            bail!("beta and beta_g must be non-negative");
+        }
+        if self.process_noise_std < 0.0 || self.meas_noise_std < 0.0 {
+            bail!("noise standard deviations must be non-negative");
+        }
+        Ok(())
+    }
+
+    pub fn k_channels(&self) -> usize {
+        self.groups.iter().map(Vec::len).sum()
+    }
+
+    pub fn fault_end(&self) -> usize {
+        self.fault_start + self.fault_duration
+    }
+
+    pub fn derived(&self) -> DerivedConstants {
+        DerivedConstants {
+            k_channels: self.k_channels(),
+            fault_end: self.fault_end(),
+            envelope_time_constant: 1.0 / (1.0 - self.rho),
+        }
+    }
+
+    pub fn write_resolved(&self, args: &Args, path: &Path) -> Result<()> {
+        let resolved = ResolvedExperiment {
+            seed: args.seed,
+            time_steps: args.time_steps,
+            config: self,
+            derived: self.derived(),
+        };
+        let json = serde_json::to_string_pretty(&resolved)?;
+        std::fs::write(path, json).with_context(|| format!("failed to write {}", path.display()))
+    }
+}
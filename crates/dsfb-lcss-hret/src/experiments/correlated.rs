@@ -4,29 +4,35 @@ use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 use rand_distr::{Distribution, Normal};
 
+use super::config::ExperimentConfig;
 use crate::{create_run_dir, Args};
 
 pub(crate) fn run_correlated(args: &Args) -> Result<()> {
-    let k_channels = 8;
-    let group0 = [0usize, 1, 2, 3];
-    let group1 = [4usize, 5, 6, 7];
-    let groups = [&group0[..], &group1[..]];
+    let cfg = ExperimentConfig::resolve(args)?;
 
-    let rho = 0.95;
-    let beta = 4.0;
-    let beta_g = 4.0;
+    let k_channels = cfg.k_channels();
+    let groups = &cfg.groups;
+    let group0 = &groups[0];
 
-    let fault_amp = 2.0;
-    let fault_start = 200usize;
-    let fault_end = fault_start + 40;
+    let rho = cfg.rho;
+    let beta = cfg.beta;
+    let beta_g = cfg.beta_g;
+
+    let fault_amp = cfg.fault_amp;
+    let fault_start = cfg.fault_start;
+    let fault_end = cfg.fault_end();
 
     let mut rng = ChaCha8Rng::seed_from_u64(args.seed);
-    let process_noise = Normal::new(0.0, 0.01)?;
-    let meas_noise = Normal::new(0.0, 0.05)?;
+    let process_noise = Normal::new(0.0, cfg.process_noise_std)?;
+    let meas_noise = Normal::new(0.0, cfg.meas_noise_std)?;
 
     let run_dir = create_run_dir(&args.output)?;
     println!("  Output: {:?}", run_dir);
 
+    let config_path = run_dir.join("experiment_config.json");
+    cfg.write_resolved(args, &config_path)?;
+    println!("  Written: {:?}", config_path);
+
     let error_path = run_dir.join("group_error_comparison.csv");
     let mut error_wtr = Writer::from_path(&error_path)?;
     error_wtr.write_record(&["time", "error_channel_only", "error_hierarchical"])?;
@@ -86,7 +92,7 @@ pub(crate) fn run_correlated(args: &Args) -> Result<()> {
         let mut group_weights = vec![0.0f64; groups.len()];
         for (g_idx, group) in groups.iter().enumerate() {
             let mut mean_abs = 0.0;
-            for k in *group {
+            for k in group {
                 mean_abs += residuals_hier[*k];
             }
             mean_abs /= group.len() as f64;
@@ -96,7 +102,7 @@ pub(crate) fn run_correlated(args: &Args) -> Result<()> {
 
         let mut weights_hier = vec![0.0f64; k_channels];
         for (g_idx, group) in groups.iter().enumerate() {
-            for k in *group {
+            for k in group {
                 let channel_weight = 1.0 / (1.0 + beta * envelope_hier[*k]);
                 weights_hier[*k] = channel_weight * group_weights[g_idx];
             }
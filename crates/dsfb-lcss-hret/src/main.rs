@@ -35,6 +35,34 @@ pub(crate) struct Args {
     /// Run correlated group fault experiment
     #[arg(long)]
     run_correlated: bool,
+
+    /// JSON file with the correlated experiment configuration
+    #[arg(long)]
+    experiment_config: Option<PathBuf>,
+
+    /// Override the envelope forgetting factor rho
+    #[arg(long)]
+    rho: Option<f64>,
+
+    /// Override the channel trust sensitivity beta
+    #[arg(long)]
+    beta: Option<f64>,
+
+    /// Override the group trust sensitivity beta_g
+    #[arg(long)]
+    beta_g: Option<f64>,
+
+    /// Override the fault amplitude
+    #[arg(long)]
+    fault_amp: Option<f64>,
+
+    /// Override the fault start step
+    #[arg(long)]
+    fault_start: Option<usize>,
+
+    /// Override the fault duration in steps
+    #[arg(long)]
+    fault_duration: Option<usize>,
 }
 
 fn main() -> Result<()> {
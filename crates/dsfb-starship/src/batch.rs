@@ -0,0 +1,182 @@
+//! Batch runs over a directory of configurations with a combined leaderboard.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use anyhow::Context;
+use serde::Serialize;
+
+use crate::config::SimConfig;
+use crate::output::{MethodMetrics, Summary};
+use crate::{create_timestamped_run_dir, resolve_output_base_dir, run_simulation};
+
+/// One named configuration of a batch.
+#[derive(Debug, Clone)]
+pub struct BatchEntry {
+    /// Config file stem; also the per-config output subdirectory
+    pub name: String,
+    pub config: SimConfig,
+}
+
+/// One method's result for one configuration, ranked within that configuration.
+#[derive(Debug, Clone, Serialize)]
+pub struct LeaderboardRow {
+    pub config: String,
+    pub method: String,
+    /// 1 for the lowest position RMSE within the configuration
+    pub rank: usize,
+    pub rmse_position_m: f64,
+    pub rmse_velocity_mps: f64,
+    pub rmse_attitude_deg: f64,
+    pub final_position_error_m: f64,
+    pub max_position_error_m: f64,
+    /// Empty when the trajectory never entered blackout
+    pub blackout_rmse_position_m: Option<f64>,
+    pub blackout_duration_s: f64,
+    pub run_dir: PathBuf,
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchSummary {
+    pub output_dir: PathBuf,
+    pub leaderboard_path: PathBuf,
+    pub leaderboard: Vec<LeaderboardRow>,
+    /// Per-configuration summaries in entry order
+    pub runs: Vec<(String, Summary)>,
+}
+
+/// Loads every `*.json` SimConfig in `dir`, sorted by file name.
+///
+/// Omitted fields take their defaults. Every config is validated before any
+/// run starts, so one bad file does not waste a partial batch.
+pub fn load_config_dir(dir: &Path) -> anyhow::Result<Vec<BatchEntry>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("failed to list config directory {}", dir.display()))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<_, _>>()?;
+    paths.retain(|path| path.extension().and_then(|e| e.to_str()) == Some("json"));
+    paths.sort();
+    anyhow::ensure!(
+        !paths.is_empty(),
+        "no *.json config files in {}",
+        dir.display()
+    );
+
+    paths
+        .iter()
+        .map(|path| {
+            let raw = fs::read_to_string(path)
+                .with_context(|| format!("failed to read config {}", path.display()))?;
+            let config: SimConfig = serde_json::from_str(&raw)
+                .with_context(|| format!("failed to parse config {}", path.display()))?;
+            config
+                .validate()
+                .with_context(|| format!("invalid config {}", path.display()))?;
+            let name = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("config")
+                .to_string();
+            Ok(BatchEntry { name, config })
+        })
+        .collect()
+}
+
+/// Runs every entry and writes `leaderboard.csv` into a fresh timestamped
+/// directory under `output_dir`.
+///
+/// Each configuration gets its own run directory under `<batch>/<name>/`.
+/// `jobs` is the number of worker threads (`0` uses all available cores,
+/// `1` runs sequentially); results do not depend on it.
+pub fn run_batch(
+    entries: &[BatchEntry],
+    output_dir: &Path,
+    jobs: usize,
+) -> anyhow::Result<BatchSummary> {
+    let batch_dir = create_timestamped_run_dir(&resolve_output_base_dir(output_dir))?;
+    let jobs = match jobs {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+    .min(entries.len())
+    .max(1);
+
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<anyhow::Result<Summary>>>> =
+        Mutex::new((0..entries.len()).map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| loop {
+                let idx = next.fetch_add(1, Ordering::Relaxed);
+                let Some(entry) = entries.get(idx) else {
+                    break;
+                };
+                let result = run_simulation(&entry.config, &batch_dir.join(&entry.name))
+                    .with_context(|| format!("config {} failed", entry.name));
+                results.lock().expect("batch results lock poisoned")[idx] = Some(result);
+            });
+        }
+    });
+
+    let runs = entries
+        .iter()
+        .zip(results.into_inner().expect("batch results lock poisoned"))
+        .map(|(entry, result)| {
+            let summary = result.expect("every batch entry is run")?;
+            Ok((entry.name.clone(), summary))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let leaderboard: Vec<LeaderboardRow> = runs
+        .iter()
+        .flat_map(|(name, summary)| leaderboard_rows(name, summary))
+        .collect();
+
+    let leaderboard_path = batch_dir.join("leaderboard.csv");
+    let mut writer = csv::Writer::from_path(&leaderboard_path)
+        .with_context(|| format!("failed to open CSV path {}", leaderboard_path.display()))?;
+    for row in &leaderboard {
+        writer.serialize(row)?;
+    }
+    writer.flush()?;
+
+    Ok(BatchSummary {
+        output_dir: batch_dir,
+        leaderboard_path,
+        leaderboard,
+        runs,
+    })
+}
+
+/// Ranks the navigation stacks of one run by whole-trajectory position RMSE.
+fn leaderboard_rows(name: &str, summary: &Summary) -> Vec<LeaderboardRow> {
+    let mut methods: Vec<(&str, &MethodMetrics)> = vec![
+        ("dsfb", &summary.dsfb),
+        ("ekf", &summary.ekf),
+        ("ekf_inflated", &summary.ekf_inflated),
+        ("inertial", &summary.inertial),
+    ];
+    methods.sort_by(|a, b| a.1.rmse_position_m.total_cmp(&b.1.rmse_position_m));
+
+    methods
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (method, metrics))| LeaderboardRow {
+            config: name.to_string(),
+            method: method.to_string(),
+            rank: idx + 1,
+            rmse_position_m: metrics.rmse_position_m,
+            rmse_velocity_mps: metrics.rmse_velocity_mps,
+            rmse_attitude_deg: metrics.rmse_attitude_deg,
+            final_position_error_m: metrics.final_position_error_m,
+            max_position_error_m: metrics.max_position_error_m,
+            blackout_rmse_position_m: metrics.blackout.as_ref().map(|b| b.rmse_position_m),
+            blackout_duration_s: summary.blackout_duration_s,
+            run_dir: summary.outputs.output_dir.clone(),
+        })
+        .collect()
+}
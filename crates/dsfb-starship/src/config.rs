@@ -1,14 +1,119 @@
+use std::fmt;
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
 
+/// Image backend used for the summary plots.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlotBackend {
+    /// Rasterized 1280x720 PNG
+    #[default]
+    Png,
+    /// Anti-aliased vector SVG
+    Svg,
+}
+
+impl PlotBackend {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Svg => "svg",
+        }
+    }
+}
+
+impl FromStr for PlotBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "png" => Ok(Self::Png),
+            "svg" => Ok(Self::Svg),
+            other => Err(format!(
+                "unknown plot backend '{other}' (expected png or svg)"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for PlotBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+/// Distribution of the fusion task's release offsets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JitterDistribution {
+    /// Perfect tick: every update is released exactly on the `dt` grid
+    #[default]
+    None,
+    /// Uniform on `[-magnitude_s, magnitude_s]`
+    Uniform,
+    /// Normal with standard deviation `magnitude_s`, truncated at three sigma
+    Gaussian,
+}
+
+impl FromStr for JitterDistribution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "uniform" => Ok(Self::Uniform),
+            "gaussian" => Ok(Self::Gaussian),
+            other => Err(format!(
+                "unknown jitter distribution '{other}' (expected none, uniform or gaussian)"
+            )),
+        }
+    }
+}
+
+fn default_gnss_count() -> usize {
+    3
+}
+
+fn default_imu_thermal_degradation() -> f64 {
+    1.0
+}
+
+fn default_ekf_blackout_q_inflation() -> f64 {
+    25.0
+}
+
+fn default_plot_max_points() -> usize {
+    2_000
+}
+
 /// Runtime configuration for the Starship re-entry DSFB demonstration.
+///
+/// Deserializing fills omitted fields from [`SimConfig::default`].
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SimConfig {
-    /// Fixed integration step [s]
+    /// Fixed integration and fusion step [s]
     pub dt: f64,
+    /// Telemetry (CSV and plot) sample period [s]; `None` writes every step
+    #[serde(default)]
+    pub output_dt: Option<f64>,
     /// Final simulation time [s]
     pub t_final: f64,
     /// Number of redundant IMU channels
     pub imu_count: usize,
+    /// Gain on heat-shield-temperature-driven IMU noise and bias growth; 0 disables it
+    #[serde(default = "default_imu_thermal_degradation")]
+    pub imu_thermal_degradation: f64,
+    /// Saturation and quantization of every IMU channel's output
+    #[serde(default)]
+    pub imu_limits: ImuLimitsConfig,
+    /// Process-noise multiplier of the blackout-aware EKF while in blackout
+    #[serde(default = "default_ekf_blackout_q_inflation")]
+    pub ekf_blackout_q_inflation: f64,
+    /// Number of independent GNSS receivers; receiver 1 carries a ramp spoof
+    #[serde(default = "default_gnss_count")]
+    pub gnss_count: usize,
     /// RNG seed for reproducibility
     pub seed: u64,
     /// Altitude where blackout starts [m]
@@ -29,14 +134,435 @@ pub struct SimConfig {
     pub slew_threshold_gyro: f64,
     /// Penalty scale when slew threshold is exceeded
     pub slew_penalty_gain: f64,
+    /// Image backend for the summary plots
+    #[serde(default)]
+    pub plot_backend: PlotBackend,
+    /// Maximum points per plotted series (LTTB downsampling); 0 plots every sample
+    #[serde(default = "default_plot_max_points")]
+    pub plot_max_points: usize,
+    /// Add truth and per-method quaternion and roll/pitch/yaw columns to the CSV
+    #[serde(default)]
+    pub export_attitude: bool,
+    /// Scheduling jitter of the DSFB fusion updates
+    #[serde(default)]
+    pub fusion_jitter: FusionJitterConfig,
+    /// Heat-shield tile-loss event model
+    pub tile_loss: TileLossConfig,
+    /// Flip maneuver and landing burn below the re-entry cutoff
+    pub landing: LandingConfig,
+    /// Navigation errors the estimators start from
+    pub initial_error: InitialErrorConfig,
+    /// Bounds of the post-run physics sanity checks
+    pub sanity: SanityConfig,
+}
+
+/// Navigation error added to the truth state to seed an estimator.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SeedError {
+    /// Position offset, north/east/down [m]
+    pub pos_m: [f64; 3],
+    /// Velocity offset, north/east/down [m/s]
+    pub vel_mps: [f64; 3],
+    /// Roll/pitch/yaw offset [deg]
+    pub att_deg: [f64; 3],
+}
+
+impl Default for SeedError {
+    fn default() -> Self {
+        Self {
+            pos_m: [45.0, -30.0, 80.0],
+            vel_mps: [-2.5, 1.8, -1.2],
+            att_deg: [0.3, -0.5, 0.2],
+        }
+    }
+}
+
+impl SeedError {
+    /// Every component multiplied by `scale`.
+    pub fn scaled(&self, scale: f64) -> Self {
+        Self {
+            pos_m: self.pos_m.map(|v| v * scale),
+            vel_mps: self.vel_mps.map(|v| v * scale),
+            att_deg: self.att_deg.map(|v| v * scale),
+        }
+    }
+
+    fn is_finite(&self) -> bool {
+        self.pos_m
+            .iter()
+            .chain(&self.vel_mps)
+            .chain(&self.att_deg)
+            .all(|v| v.is_finite())
+    }
+}
+
+/// Initial navigation errors of the inertial, EKF and DSFB estimators.
+///
+/// Each estimator starts from `base` times its own scale. The default scales
+/// (1.00, 1.12, 0.86) seed every method differently; `identical` seeds all
+/// three with `base` unscaled, so no method is favoured by its start.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InitialErrorConfig {
+    pub base: SeedError,
+    pub inertial_scale: f64,
+    pub ekf_scale: f64,
+    pub dsfb_scale: f64,
+    /// Ignore the per-method scales and seed every estimator with `base`
+    pub identical: bool,
+}
+
+impl Default for InitialErrorConfig {
+    fn default() -> Self {
+        Self {
+            base: SeedError::default(),
+            inertial_scale: 1.00,
+            ekf_scale: 1.12,
+            dsfb_scale: 0.86,
+            identical: false,
+        }
+    }
+}
+
+impl InitialErrorConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.base.is_finite(),
+            "initial_error.base components must be finite"
+        );
+        anyhow::ensure!(
+            [self.inertial_scale, self.ekf_scale, self.dsfb_scale]
+                .iter()
+                .all(|s| s.is_finite() && *s >= 0.0),
+            "initial_error scales must be finite and >= 0"
+        );
+        Ok(())
+    }
+
+    /// Scales applied to `base` for the inertial, EKF and DSFB estimators.
+    pub fn scales(&self) -> [f64; 3] {
+        if self.identical {
+            [1.0; 3]
+        } else {
+            [self.inertial_scale, self.ekf_scale, self.dsfb_scale]
+        }
+    }
+
+    /// The seed error each estimator starts from.
+    pub fn resolve(&self) -> InitialErrors {
+        let [inertial, ekf, dsfb] = self.scales().map(|scale| self.base.scaled(scale));
+        InitialErrors {
+            inertial,
+            ekf,
+            dsfb,
+        }
+    }
+}
+
+/// Seed errors the estimators of a run started from.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct InitialErrors {
+    pub inertial: SeedError,
+    pub ekf: SeedError,
+    pub dsfb: SeedError,
+}
+
+/// Output range and resolution of the IMU channels.
+///
+/// Each axis is rounded to a multiple of its LSB and then clamped to
+/// `[-range, range]`, so the estimators only ever see what a real
+/// sensor could report. An LSB of 0 disables quantization.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImuLimitsConfig {
+    /// Accelerometer full-scale range per axis [m/s^2]
+    pub accel_range_mps2: f64,
+    /// Gyro full-scale range per axis [rad/s]
+    pub gyro_range_rps: f64,
+    /// Accelerometer output resolution [m/s^2]
+    pub accel_lsb_mps2: f64,
+    /// Gyro output resolution [rad/s]
+    pub gyro_lsb_rps: f64,
+}
+
+impl Default for ImuLimitsConfig {
+    fn default() -> Self {
+        Self {
+            accel_range_mps2: 60.0,
+            gyro_range_rps: 0.8,
+            accel_lsb_mps2: 0.0,
+            gyro_lsb_rps: 0.0,
+        }
+    }
+}
+
+impl ImuLimitsConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.accel_range_mps2.is_finite() && self.accel_range_mps2 > 0.0,
+            "imu_limits.accel_range_mps2 must be finite and > 0"
+        );
+        anyhow::ensure!(
+            self.gyro_range_rps.is_finite() && self.gyro_range_rps > 0.0,
+            "imu_limits.gyro_range_rps must be finite and > 0"
+        );
+        anyhow::ensure!(
+            self.accel_lsb_mps2.is_finite()
+                && (0.0..self.accel_range_mps2).contains(&self.accel_lsb_mps2),
+            "imu_limits.accel_lsb_mps2 must be in [0, accel_range_mps2)"
+        );
+        anyhow::ensure!(
+            self.gyro_lsb_rps.is_finite()
+                && (0.0..self.gyro_range_rps).contains(&self.gyro_lsb_rps),
+            "imu_limits.gyro_lsb_rps must be in [0, gyro_range_rps)"
+        );
+        Ok(())
+    }
+}
+
+/// Flight-computer scheduling jitter on the DSFB fusion updates.
+///
+/// Update `k` is released at `k * dt + e_k`, with each offset `e_k` drawn
+/// independently, and the fusion layer is handed the measured interval
+/// `dt + e_k - e_(k-1)`. Truth, IMU sampling and navigation propagation stay
+/// on the fixed `dt` grid, so only the trust fusion sees the jitter. The
+/// default distribution `none` keeps the perfect tick.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FusionJitterConfig {
+    pub distribution: JitterDistribution,
+    /// Half-width (uniform) or standard deviation (gaussian) of each offset [s]
+    pub magnitude_s: f64,
+    /// Seed of the offset draws; `None` derives it from `SimConfig::seed`
+    pub seed: Option<u64>,
+}
+
+impl FusionJitterConfig {
+    /// Whether any update can leave the `dt` grid.
+    pub fn enabled(&self) -> bool {
+        self.distribution != JitterDistribution::None && self.magnitude_s > 0.0
+    }
+
+    /// Largest possible release offset [s].
+    pub fn max_offset_s(&self) -> f64 {
+        match self.distribution {
+            JitterDistribution::None => 0.0,
+            JitterDistribution::Uniform => self.magnitude_s,
+            JitterDistribution::Gaussian => 3.0 * self.magnitude_s,
+        }
+    }
+
+    /// Seed of the offset draws: `seed`, or one derived from the run seed.
+    pub fn effective_seed(&self, sim_seed: u64) -> u64 {
+        self.seed.unwrap_or(sim_seed ^ 0x5C4E_D11E_u64)
+    }
+
+    /// Checks that every interval stays positive for the fusion step `dt`.
+    pub fn validate(&self, dt: f64) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.magnitude_s.is_finite() && self.magnitude_s >= 0.0,
+            "fusion_jitter.magnitude_s must be finite and >= 0"
+        );
+        anyhow::ensure!(
+            self.max_offset_s() < 0.5 * dt,
+            "fusion_jitter offsets (up to {} s) must stay below dt / 2",
+            self.max_offset_s()
+        );
+        Ok(())
+    }
+}
+
+/// Terminal descent: belly-flop to vertical flip and landing burn.
+///
+/// Disabled, the run ends at the 18 km re-entry cutoff. Enabled, the vehicle
+/// falls belly-first from the blackout lower bound to `flip_altitude_m`, where
+/// the engines light at minimum throttle and it rotates to vertical over
+/// `flip_duration_s`; a throttled landing burn then brings it to touchdown.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LandingConfig {
+    pub enabled: bool,
+    /// Altitude at which the flip starts [m]
+    pub flip_altitude_m: f64,
+    /// Duration of the rotation to vertical [s]
+    pub flip_duration_s: f64,
+    /// Maximum landing thrust [N]
+    pub max_thrust_n: f64,
+    /// Lowest throttle setting while the engines run, as a fraction of `max_thrust_n`
+    pub min_throttle: f64,
+    /// Largest thrust tilt from vertical during the landing burn [deg]
+    pub max_tilt_deg: f64,
+}
+
+impl Default for LandingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            flip_altitude_m: 1_000.0,
+            flip_duration_s: 6.0,
+            max_thrust_n: 4.6e6,
+            min_throttle: 0.2,
+            max_tilt_deg: 15.0,
+        }
+    }
+}
+
+impl LandingConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.flip_altitude_m.is_finite() && self.flip_altitude_m > 0.0,
+            "landing.flip_altitude_m must be finite and > 0"
+        );
+        anyhow::ensure!(
+            self.flip_duration_s.is_finite() && self.flip_duration_s > 0.0,
+            "landing.flip_duration_s must be finite and > 0"
+        );
+        anyhow::ensure!(
+            self.max_thrust_n.is_finite() && self.max_thrust_n > 0.0,
+            "landing.max_thrust_n must be finite and > 0"
+        );
+        anyhow::ensure!(
+            (0.0..=1.0).contains(&self.min_throttle),
+            "landing.min_throttle must be in [0, 1]"
+        );
+        anyhow::ensure!(
+            self.max_tilt_deg > 0.0 && self.max_tilt_deg < 90.0,
+            "landing.max_tilt_deg must be in (0, 90)"
+        );
+        Ok(())
+    }
+}
+
+/// Seeded heat-shield tile-loss events.
+///
+/// Each event draws its onset uniformly from
+/// `[onset_s, onset_s + onset_spread_s]` and its severity uniformly from
+/// `[severity_min, severity_max]`. Severity scales the asymmetric side-force,
+/// roll and yaw aero terms; the severities of all started events add up. The
+/// defaults reproduce a single full-severity loss at 320 s.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TileLossConfig {
+    /// Number of tile-loss events; 0 disables tile loss
+    pub count: usize,
+    /// Earliest onset [s]
+    pub onset_s: f64,
+    /// Width of the uniform onset window [s]; 0 fixes every onset at `onset_s`
+    pub onset_spread_s: f64,
+    /// Smallest per-event severity (1 is the nominal asymmetry)
+    pub severity_min: f64,
+    /// Largest per-event severity
+    pub severity_max: f64,
+    /// Seed of the event draws; `None` derives it from `SimConfig::seed`
+    pub seed: Option<u64>,
+}
+
+impl Default for TileLossConfig {
+    fn default() -> Self {
+        Self {
+            count: 1,
+            onset_s: 320.0,
+            onset_spread_s: 0.0,
+            severity_min: 1.0,
+            severity_max: 1.0,
+            seed: None,
+        }
+    }
+}
+
+impl TileLossConfig {
+    /// Seed of the event draws: `seed`, or one derived from the run seed.
+    pub fn effective_seed(&self, sim_seed: u64) -> u64 {
+        self.seed.unwrap_or(sim_seed ^ 0x711E_1055_u64)
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.onset_s.is_finite() && self.onset_s >= 0.0,
+            "tile_loss.onset_s must be finite and >= 0"
+        );
+        anyhow::ensure!(
+            self.onset_spread_s.is_finite() && self.onset_spread_s >= 0.0,
+            "tile_loss.onset_spread_s must be finite and >= 0"
+        );
+        anyhow::ensure!(
+            self.severity_min.is_finite()
+                && self.severity_min >= 0.0
+                && self.severity_max.is_finite()
+                && self.severity_max >= self.severity_min,
+            "tile_loss severities must satisfy 0 <= severity_min <= severity_max"
+        );
+        Ok(())
+    }
+}
+
+/// Bounds checked on the truth trajectory after every run.
+///
+/// Samples outside them are reported as `sanity_violations` in the summary.
+/// The defaults are plausibility limits that the nominal trajectories stay
+/// within; tighten them to a specific vehicle's envelope.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SanityConfig {
+    /// Largest dynamic pressure [Pa]
+    pub max_dynamic_pressure_pa: f64,
+    /// Largest stagnation heat flux [W/m^2]
+    pub max_heat_flux_w_m2: f64,
+    /// Smallest heat-shield temperature [K]
+    pub min_heat_shield_temp_k: f64,
+    /// Largest heat-shield temperature [K]
+    pub max_heat_shield_temp_k: f64,
+    /// Largest climb above the lowest altitude reached so far in an unguided
+    /// segment (entry phase outside the blackout band) [m]
+    pub max_altitude_rise_m: f64,
+}
+
+impl Default for SanityConfig {
+    fn default() -> Self {
+        Self {
+            max_dynamic_pressure_pa: 4.0e6,
+            max_heat_flux_w_m2: 2.0e7,
+            min_heat_shield_temp_k: 150.0,
+            max_heat_shield_temp_k: 2_000.0,
+            max_altitude_rise_m: 250.0,
+        }
+    }
+}
+
+impl SanityConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.max_dynamic_pressure_pa > 0.0,
+            "sanity.max_dynamic_pressure_pa must be > 0"
+        );
+        anyhow::ensure!(
+            self.max_heat_flux_w_m2 > 0.0,
+            "sanity.max_heat_flux_w_m2 must be > 0"
+        );
+        anyhow::ensure!(
+            self.min_heat_shield_temp_k >= 0.0
+                && self.max_heat_shield_temp_k > self.min_heat_shield_temp_k,
+            "sanity heat-shield temperatures must satisfy 0 <= min < max"
+        );
+        anyhow::ensure!(
+            self.max_altitude_rise_m >= 0.0,
+            "sanity.max_altitude_rise_m must be >= 0"
+        );
+        Ok(())
+    }
 }
 
 impl Default for SimConfig {
     fn default() -> Self {
         Self {
             dt: 0.2,
+            output_dt: None,
             t_final: 900.0,
             imu_count: 3,
+            imu_thermal_degradation: default_imu_thermal_degradation(),
+            imu_limits: ImuLimitsConfig::default(),
+            ekf_blackout_q_inflation: default_ekf_blackout_q_inflation(),
+            gnss_count: default_gnss_count(),
             seed: 17,
             blackout_upper_m: 80_000.0,
             blackout_lower_m: 40_000.0,
@@ -47,24 +573,111 @@ impl Default for SimConfig {
             slew_threshold_accel: 32.0,
             slew_threshold_gyro: 1.4,
             slew_penalty_gain: 0.75,
+            plot_backend: PlotBackend::Png,
+            plot_max_points: default_plot_max_points(),
+            export_attitude: false,
+            fusion_jitter: FusionJitterConfig::default(),
+            tile_loss: TileLossConfig::default(),
+            landing: LandingConfig::default(),
+            initial_error: InitialErrorConfig::default(),
+            sanity: SanityConfig::default(),
         }
     }
 }
 
+/// Gyro slew threshold per unit of accel slew threshold [(rad/s^2) / (m/s^3)]
+const GYRO_PER_ACCEL_SLEW_THRESHOLD: f64 = 0.055;
+/// Floor on the derived gyro slew threshold [rad/s^2]
+const MIN_GYRO_SLEW_THRESHOLD: f64 = 0.15;
+
 impl SimConfig {
+    /// Sets the accel slew threshold [m/s^3] and derives the gyro threshold
+    /// [rad/s^2] from it, so the two channel families stay consistently scaled.
+    pub fn set_slew_threshold_accel(&mut self, threshold_mps3: f64) {
+        self.slew_threshold_accel = threshold_mps3;
+        self.slew_threshold_gyro =
+            (GYRO_PER_ACCEL_SLEW_THRESHOLD * threshold_mps3).max(MIN_GYRO_SLEW_THRESHOLD);
+    }
+
     pub fn validate(&self) -> anyhow::Result<()> {
         anyhow::ensure!(self.dt > 0.0, "dt must be > 0");
         anyhow::ensure!(self.t_final > self.dt, "t_final must be > dt");
+        if let Some(output_dt) = self.output_dt {
+            anyhow::ensure!(
+                output_dt.is_finite() && output_dt >= self.dt,
+                "output_dt must be finite and >= dt"
+            );
+        }
         anyhow::ensure!(self.imu_count >= 2, "imu_count must be at least 2");
+        anyhow::ensure!(
+            self.imu_thermal_degradation.is_finite() && self.imu_thermal_degradation >= 0.0,
+            "imu_thermal_degradation must be finite and >= 0"
+        );
+        anyhow::ensure!(
+            self.ekf_blackout_q_inflation.is_finite() && self.ekf_blackout_q_inflation >= 1.0,
+            "ekf_blackout_q_inflation must be finite and >= 1"
+        );
+        anyhow::ensure!(self.gnss_count >= 1, "gnss_count must be at least 1");
         anyhow::ensure!(
             self.blackout_upper_m > self.blackout_lower_m,
             "blackout_upper_m must be larger than blackout_lower_m"
         );
         anyhow::ensure!(self.rho > 0.0 && self.rho < 1.0, "rho must be in (0, 1)");
+        self.imu_limits.validate()?;
+        self.fusion_jitter.validate(self.dt)?;
+        self.tile_loss.validate()?;
+        self.landing.validate()?;
+        self.initial_error.validate()?;
+        self.sanity.validate()?;
         Ok(())
     }
 
     pub fn steps(&self) -> usize {
         (self.t_final / self.dt).ceil() as usize
     }
+
+    /// Number of integration steps per telemetry sample.
+    pub fn output_stride(&self) -> usize {
+        self.output_dt
+            .map(|output_dt| (output_dt / self.dt).round().max(1.0) as usize)
+            .unwrap_or(1)
+    }
+
+    /// This configuration with every implicit choice made explicit, so it
+    /// reproduces the same run even if a default changes later.
+    pub fn resolved(&self) -> ResolvedConfig {
+        let mut config = self.clone();
+        config.tile_loss.seed = Some(self.tile_loss.effective_seed(self.seed));
+        config.fusion_jitter.seed = Some(self.fusion_jitter.effective_seed(self.seed));
+        ResolvedConfig {
+            derived: DerivedValues {
+                steps: config.steps(),
+                output_stride: config.output_stride(),
+                initial_errors: config.initial_error.resolve(),
+            },
+            config,
+        }
+    }
+}
+
+/// A [`SimConfig`] as flown, written to `config.json` in every run directory.
+///
+/// The config fields are stored flat, so the file also loads as a plain
+/// `SimConfig` (e.g. in a `--configs` directory); `derived` is informational.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedConfig {
+    #[serde(flatten)]
+    pub config: SimConfig,
+    pub derived: DerivedValues,
+}
+
+/// Values the run computes from its configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DerivedValues {
+    /// Integration steps up to `t_final`
+    pub steps: usize,
+    /// Integration steps per telemetry sample
+    pub output_stride: usize,
+    /// Seed error each estimator starts from
+    pub initial_errors: InitialErrors,
 }
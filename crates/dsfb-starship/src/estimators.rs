@@ -2,9 +2,9 @@ use nalgebra::{SMatrix, SVector, UnitQuaternion, Vector3};
 
 use dsfb::{DsfbObserver, DsfbParams, DsfbState};
 
-use crate::config::SimConfig;
+use crate::config::{SeedError, SimConfig};
 use crate::physics::{gravity_mps2, TruthState};
-use crate::sensors::ImuMeasurement;
+use crate::sensors::{GnssFix, ImuMeasurement};
 
 #[derive(Debug, Clone)]
 pub struct NavState {
@@ -15,13 +15,21 @@ pub struct NavState {
 }
 
 impl NavState {
-    pub fn from_truth_with_seed_error(truth: &TruthState, seed_scale: f64) -> Self {
-        let pos_err = Vector3::new(45.0 * seed_scale, -30.0 * seed_scale, 80.0 * seed_scale);
-        let vel_err = Vector3::new(-2.5 * seed_scale, 1.8 * seed_scale, -1.2 * seed_scale);
+    /// Truth perturbed by `seed_error` times `seed_scale`.
+    pub fn from_truth_with_seed_error(
+        truth: &TruthState,
+        seed_error: &SeedError,
+        seed_scale: f64,
+    ) -> Self {
+        let [px, py, pz] = seed_error.pos_m;
+        let [vx, vy, vz] = seed_error.vel_mps;
+        let [roll, pitch, yaw] = seed_error.att_deg;
+        let pos_err = Vector3::new(px * seed_scale, py * seed_scale, pz * seed_scale);
+        let vel_err = Vector3::new(vx * seed_scale, vy * seed_scale, vz * seed_scale);
         let att_err = UnitQuaternion::from_euler_angles(
-            0.3_f64.to_radians() * seed_scale,
-            -0.5_f64.to_radians() * seed_scale,
-            0.2_f64.to_radians() * seed_scale,
+            roll.to_radians() * seed_scale,
+            pitch.to_radians() * seed_scale,
+            yaw.to_radians() * seed_scale,
         );
 
         Self {
@@ -33,17 +41,6 @@ impl NavState {
     }
 
     pub fn propagate(&mut self, specific_force_b_mps2: Vector3<f64>, gyro_b_rps: Vector3<f64>, dt_s: f64) {
-        let gyro_b_rps = Vector3::new(
-            gyro_b_rps.x.clamp(-0.8, 0.8),
-            gyro_b_rps.y.clamp(-0.8, 0.8),
-            gyro_b_rps.z.clamp(-0.8, 0.8),
-        );
-        let specific_force_b_mps2 = Vector3::new(
-            specific_force_b_mps2.x.clamp(-60.0, 60.0),
-            specific_force_b_mps2.y.clamp(-60.0, 60.0),
-            specific_force_b_mps2.z.clamp(-60.0, 60.0),
-        );
-
         let dq = UnitQuaternion::from_scaled_axis(gyro_b_rps * dt_s);
         self.q_bn *= dq;
 
@@ -79,11 +76,14 @@ impl NavState {
 type Mat6 = SMatrix<f64, 6, 6>;
 type Vec6 = SVector<f64, 6>;
 
+#[derive(Debug, Clone)]
 pub struct SimpleEkf {
     pub nav: NavState,
     p: Mat6,
     q_diag: Vec6,
     r_diag: Vec6,
+    /// Multiplier on the process noise of the next propagation steps
+    q_scale: f64,
 }
 
 impl SimpleEkf {
@@ -93,9 +93,16 @@ impl SimpleEkf {
             p: Mat6::identity() * 35.0,
             q_diag: Vec6::new(0.04, 0.04, 0.04, 0.55, 0.55, 0.55),
             r_diag: Vec6::new(25.0, 25.0, 36.0, 4.0, 4.0, 5.0),
+            q_scale: 1.0,
         }
     }
 
+    /// Scales the process noise added by subsequent propagation steps, e.g. to
+    /// inflate the covariance while no aiding is available; `1.0` is nominal.
+    pub fn set_process_noise_scale(&mut self, scale: f64) {
+        self.q_scale = scale;
+    }
+
     pub fn propagate(&mut self, specific_force_b_mps2: Vector3<f64>, gyro_b_rps: Vector3<f64>, dt_s: f64) {
         self.nav.propagate(specific_force_b_mps2, gyro_b_rps, dt_s);
 
@@ -106,7 +113,7 @@ impl SimpleEkf {
 
         let mut q = Mat6::zeros();
         for i in 0..6 {
-            q[(i, i)] = self.q_diag[i] * dt_s;
+            q[(i, i)] = self.q_diag[i] * self.q_scale * dt_s;
         }
 
         self.p = a * self.p * a.transpose() + q;
@@ -149,6 +156,7 @@ impl SimpleEkf {
 
 struct AxisFusion {
     observer: DsfbObserver,
+    sigma0: f64,
     prev_samples: Vec<f64>,
     slew_threshold: f64,
     penalty_gain: f64,
@@ -160,6 +168,7 @@ impl AxisFusion {
     fn new(params: DsfbParams, channels: usize, slew_threshold: f64, penalty_gain: f64) -> Self {
         Self {
             observer: DsfbObserver::new(params, channels),
+            sigma0: params.sigma0,
             prev_samples: vec![0.0; channels],
             slew_threshold,
             penalty_gain,
@@ -210,7 +219,8 @@ impl AxisFusion {
             fused
         } else {
             let mean = adjusted.iter().copied().sum::<f64>() / adjusted.len() as f64;
-            self.observer.init(DsfbState::new(mean, 0.0, 0.0));
+            self.observer
+                .reinit_with(DsfbState::new(mean, 0.0, 0.0), true);
             mean
         }
     }
@@ -222,6 +232,11 @@ impl AxisFusion {
     fn increment(&self, channel: usize) -> f64 {
         self.last_increments[channel]
     }
+
+    /// Unnormalized trust `sigma0 / (sigma0 + s_k)` from the channel's own envelope.
+    fn raw_trust(&self, channel: usize) -> f64 {
+        self.sigma0 / (self.sigma0 + self.observer.ema_residual(channel))
+    }
 }
 
 pub struct DsfbFusionLayer {
@@ -312,22 +327,27 @@ impl DsfbFusionLayer {
 
         let mut trust_weights = vec![0.0; self.channels];
         let mut residual_increments = vec![0.0; self.channels];
+        let mut raw_trust = vec![0.0; self.channels];
 
         for ch in 0..self.channels {
             let mut w_sum = 0.0;
             let mut inc_sum = 0.0;
+            let mut raw_sum = 0.0;
 
             for axis in &self.accel_axes {
                 w_sum += axis.weight(ch);
                 inc_sum += axis.increment(ch);
+                raw_sum += axis.raw_trust(ch);
             }
             for axis in &self.gyro_axes {
                 w_sum += axis.weight(ch);
                 inc_sum += axis.increment(ch);
+                raw_sum += axis.raw_trust(ch);
             }
 
             trust_weights[ch] = w_sum / 6.0;
             residual_increments[ch] = inc_sum / 6.0;
+            raw_trust[ch] = raw_sum / 6.0;
         }
 
         DsfbFusionOutput {
@@ -335,6 +355,7 @@ impl DsfbFusionLayer {
             fused_gyro_b_rps: fused_gyro,
             trust_weights,
             residual_increments,
+            raw_trust,
         }
     }
 }
@@ -344,6 +365,123 @@ pub struct DsfbFusionOutput {
     pub fused_gyro_b_rps: Vector3<f64>,
     pub trust_weights: Vec<f64>,
     pub residual_increments: Vec<f64>,
+    /// Per-channel unnormalized trust, unaffected by the other channels
+    pub raw_trust: Vec<f64>,
+}
+
+/// Trust layer over redundant GNSS receivers.
+///
+/// Each receiver's deviation from the cross-receiver median is fed, per
+/// position and velocity axis, to a DSFB observer. The observer trust weights
+/// then form the aiding fix as a weighted combination of the receivers, so a
+/// receiver that drifts away from the others (e.g. a ramp spoof) loses its
+/// share of the aiding. Deviations are taken against the median rather than
+/// the navigation solution so that inertial drift (e.g. after blackout) is
+/// common-mode and does not mask receiver faults.
+pub struct GnssTrustLayer {
+    pos_axes: [DsfbObserver; 3],
+    vel_axes: [DsfbObserver; 3],
+    receivers: usize,
+}
+
+impl GnssTrustLayer {
+    pub fn new(cfg: &SimConfig) -> Self {
+        // GNSS fixes arrive at 1 Hz, so the envelope forgets faster than the IMU layer's.
+        let rho = 0.85;
+        let pos_params = DsfbParams::new(0.5, 0.1, 0.01, rho, 5.0);
+        let vel_params = DsfbParams::new(0.5, 0.1, 0.01, rho, 0.6);
+        let receivers = cfg.gnss_count;
+
+        Self {
+            pos_axes: [
+                DsfbObserver::new(pos_params, receivers),
+                DsfbObserver::new(pos_params, receivers),
+                DsfbObserver::new(pos_params, receivers),
+            ],
+            vel_axes: [
+                DsfbObserver::new(vel_params, receivers),
+                DsfbObserver::new(vel_params, receivers),
+                DsfbObserver::new(vel_params, receivers),
+            ],
+            receivers,
+        }
+    }
+
+    pub fn fuse(&mut self, fixes: &[GnssFix], dt_s: f64) -> GnssFusionOutput {
+        let mut pos_n_m = Vector3::zeros();
+        let mut vel_n_mps = Vector3::zeros();
+
+        for axis in 0..3 {
+            let pos: Vec<f64> = fixes.iter().map(|f| f.pos_n_m[axis]).collect();
+            let vel: Vec<f64> = fixes.iter().map(|f| f.vel_n_mps[axis]).collect();
+            let pos_ref = median(&pos);
+            let vel_ref = median(&vel);
+
+            let pos_dev: Vec<f64> = pos.iter().map(|p| p - pos_ref).collect();
+            let vel_dev: Vec<f64> = vel.iter().map(|v| v - vel_ref).collect();
+            self.pos_axes[axis].step(&pos_dev, dt_s);
+            self.vel_axes[axis].step(&vel_dev, dt_s);
+
+            for (rx, fix) in fixes.iter().enumerate() {
+                pos_n_m[axis] += self.pos_axes[axis].trust_weight(rx) * fix.pos_n_m[axis];
+                vel_n_mps[axis] += self.vel_axes[axis].trust_weight(rx) * fix.vel_n_mps[axis];
+            }
+        }
+
+        GnssFusionOutput {
+            pos_n_m,
+            vel_n_mps,
+            trust_weights: self.trust_weights(),
+        }
+    }
+
+    /// Per-receiver trust averaged over the six position and velocity axes.
+    pub fn trust_weights(&self) -> Vec<f64> {
+        (0..self.receivers)
+            .map(|rx| {
+                let w_sum: f64 = self
+                    .pos_axes
+                    .iter()
+                    .chain(self.vel_axes.iter())
+                    .map(|axis| axis.trust_weight(rx))
+                    .sum();
+                w_sum / 6.0
+            })
+            .collect()
+    }
+}
+
+pub struct GnssFusionOutput {
+    pub pos_n_m: Vector3<f64>,
+    pub vel_n_mps: Vector3<f64>,
+    pub trust_weights: Vec<f64>,
+}
+
+fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let mid = sorted.len() / 2;
+    if sorted.len() % 2 == 0 {
+        0.5 * (sorted[mid - 1] + sorted[mid])
+    } else {
+        sorted[mid]
+    }
+}
+
+pub fn mean_gnss_fix(fixes: &[GnssFix]) -> GnssFix {
+    let n = fixes.len() as f64;
+
+    let mut pos = Vector3::zeros();
+    let mut vel = Vector3::zeros();
+    for f in fixes {
+        pos += f.pos_n_m;
+        vel += f.vel_n_mps;
+    }
+
+    GnssFix {
+        pos_n_m: pos / n,
+        vel_n_mps: vel / n,
+    }
 }
 
 pub fn mean_measurement(measurements: &[ImuMeasurement]) -> ImuMeasurement {
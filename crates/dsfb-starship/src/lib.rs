@@ -1,7 +1,10 @@
+pub mod batch;
 pub mod config;
 pub mod estimators;
 pub mod output;
 pub mod physics;
+pub mod replay;
+pub mod sanity;
 pub mod sensors;
 
 use std::fs;
@@ -10,18 +13,25 @@ use std::path::{Path, PathBuf};
 use anyhow::Context;
 use chrono::Utc;
 use nalgebra::Vector3;
-use pyo3::exceptions::PyRuntimeError;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
 use pyo3::types::PyModule;
-use rand::{Rng, SeedableRng};
-use rand_chacha::ChaCha8Rng;
-use rand_distr::StandardNormal;
 
 use crate::config::SimConfig;
-use crate::estimators::{mean_measurement, DsfbFusionLayer, NavState, SimpleEkf};
-use crate::output::{make_plots, write_csv, write_summary, MethodMetrics, OutputFiles, SimRecord, Summary};
-use crate::physics::{initial_truth_state, truth_step, ReentryEventState, VehicleParams};
-use crate::sensors::ImuArray;
+use crate::estimators::{
+    mean_gnss_fix, mean_measurement, DsfbFusionLayer, GnssTrustLayer, NavState, SimpleEkf,
+};
+use crate::output::{
+    make_plots_with_summary, write_config, write_csv, write_summary, AttitudeRecord,
+    BlackoutMetrics, MethodMetrics, OutputFiles, SimRecord, Summary, ThermalTrustMetrics,
+    CONFIG_FILE, SUMMARY_FILE,
+};
+use crate::physics::{
+    descent_complete, initial_truth_state, truth_step, DescentPhase, ReentryEventState,
+    VehicleParams,
+};
+use crate::sanity::SanityMonitor;
+use crate::sensors::{FusionClock, GnssArray, ImuArray};
 
 pub fn run_simulation(cfg: &SimConfig, output_dir: &Path) -> anyhow::Result<Summary> {
     cfg.validate()?;
@@ -30,17 +40,27 @@ pub fn run_simulation(cfg: &SimConfig, output_dir: &Path) -> anyhow::Result<Summ
 
     let vehicle = VehicleParams::default();
     let mut truth = initial_truth_state(cfg, &vehicle);
-    let mut events = ReentryEventState::default();
-    let mut imu_array = ImuArray::new(cfg.seed, cfg.imu_count);
-
-    let mut inertial = NavState::from_truth_with_seed_error(&truth, 1.00);
-    let mut ekf = SimpleEkf::new(NavState::from_truth_with_seed_error(&truth, 1.12));
-    let mut dsfb_nav = NavState::from_truth_with_seed_error(&truth, 0.86);
+    let mut events = ReentryEventState::from_config(cfg);
+    let mut imu_array = ImuArray::from_config(cfg);
+
+    let seed_error = &cfg.initial_error.base;
+    let [inertial_scale, ekf_scale, dsfb_scale] = cfg.initial_error.scales();
+    let mut inertial = NavState::from_truth_with_seed_error(&truth, seed_error, inertial_scale);
+    let mut ekf = SimpleEkf::new(NavState::from_truth_with_seed_error(
+        &truth, seed_error, ekf_scale,
+    ));
+    // Same filter and start as `ekf`, with its process noise inflated while in blackout.
+    let mut ekf_inflated = ekf.clone();
+    let mut dsfb_nav = NavState::from_truth_with_seed_error(&truth, seed_error, dsfb_scale);
     let mut dsfb_fusion = DsfbFusionLayer::new(cfg);
-
-    let mut gnss_rng = ChaCha8Rng::seed_from_u64(cfg.seed ^ 0xCAB00D1E_u64);
+    let mut fusion_clock = FusionClock::new(&cfg.fusion_jitter, cfg.seed, cfg.dt);
+    let mut gnss_array = GnssArray::new(cfg.seed, cfg.gnss_count);
+    let mut gnss_trust = GnssTrustLayer::new(cfg);
+    let gnss_stride = (1.0 / cfg.dt).round().max(1.0) as usize;
+    let mut gnss_trust_weights = vec![1.0 / cfg.gnss_count as f64; cfg.gnss_count];
 
     let mut records = Vec::with_capacity(cfg.steps());
+    let mut sanity = SanityMonitor::new(&cfg.sanity);
 
     let mut blackout_start: Option<f64> = None;
     let mut blackout_end: Option<f64> = None;
@@ -50,7 +70,7 @@ pub fn run_simulation(cfg: &SimConfig, output_dir: &Path) -> anyhow::Result<Summ
 
         let truth_sample = truth_step(&mut truth, &vehicle, cfg, t_s, cfg.dt, &mut events);
         let imu_measurements = imu_array.measure(
-            truth_sample.aero.specific_force_b_mps2,
+            truth_sample.specific_force_b_mps2,
             truth.omega_b_rps,
             truth.heat_shield_temp_k,
             t_s,
@@ -65,20 +85,36 @@ pub fn run_simulation(cfg: &SimConfig, output_dir: &Path) -> anyhow::Result<Summ
         // Simple EKF baseline: average IMU propagation + GNSS update when not in blackout.
         let mean_imu = mean_measurement(&imu_measurements);
         ekf.propagate(mean_imu.accel_b_mps2, mean_imu.gyro_b_rps, cfg.dt);
+        ekf_inflated.set_process_noise_scale(if truth_sample.blackout {
+            cfg.ekf_blackout_q_inflation
+        } else {
+            1.0
+        });
+        ekf_inflated.propagate(mean_imu.accel_b_mps2, mean_imu.gyro_b_rps, cfg.dt);
 
-        // DSFB fusion over redundant IMUs.
-        let dsfb_out = dsfb_fusion.fuse(&imu_measurements, cfg.dt);
+        // DSFB fusion over redundant IMUs, stepped with the interval the
+        // fusion task measured (exactly dt without scheduling jitter).
+        let dsfb_out = dsfb_fusion.fuse(&imu_measurements, fusion_clock.next_interval());
         dsfb_nav.propagate(dsfb_out.fused_accel_b_mps2, dsfb_out.fused_gyro_b_rps, cfg.dt);
 
         if !finite_nav(&truth.pos_n_m, &truth.vel_n_mps)
             || !finite_nav(&inertial.pos_n_m, &inertial.vel_n_mps)
             || !finite_nav(&ekf.nav.pos_n_m, &ekf.nav.vel_n_mps)
+            || !finite_nav(&ekf_inflated.nav.pos_n_m, &ekf_inflated.nav.vel_n_mps)
             || !finite_nav(&dsfb_nav.pos_n_m, &dsfb_nav.vel_n_mps)
         {
             break;
         }
 
         let is_blackout = truth_sample.blackout;
+        sanity.observe(
+            t_s,
+            truth.altitude_m(),
+            truth_sample.aero.dynamic_pressure_pa,
+            truth_sample.heat_flux_w_m2,
+            truth.heat_shield_temp_k,
+            is_blackout || events.descent_phase != DescentPhase::Entry,
+        );
         if is_blackout {
             if blackout_start.is_none() {
                 blackout_start = Some(t_s);
@@ -87,25 +123,19 @@ pub fn run_simulation(cfg: &SimConfig, output_dir: &Path) -> anyhow::Result<Summ
             blackout_end = Some(t_s);
         }
 
-        // GNSS aiding outside blackout at 1 Hz.
-        if !is_blackout && step_idx % (1.0 / cfg.dt).round().max(1.0) as usize == 0 {
-            let gnss_pos = truth.pos_n_m
-                + Vector3::new(
-                    gaussian(&mut gnss_rng, 5.5),
-                    gaussian(&mut gnss_rng, 5.5),
-                    gaussian(&mut gnss_rng, 7.0),
-                );
-            let gnss_vel = truth.vel_n_mps
-                + Vector3::new(
-                    gaussian(&mut gnss_rng, 0.75),
-                    gaussian(&mut gnss_rng, 0.75),
-                    gaussian(&mut gnss_rng, 0.90),
-                );
-
-            ekf.update_gnss(gnss_pos, gnss_vel);
-
-            dsfb_nav.pos_n_m = dsfb_nav.pos_n_m * 0.75 + gnss_pos * 0.25;
-            dsfb_nav.vel_n_mps = dsfb_nav.vel_n_mps * 0.70 + gnss_vel * 0.30;
+        // GNSS aiding outside blackout at 1 Hz. The EKF takes the receiver
+        // average; DSFB aiding uses the trust-weighted receiver combination.
+        if !is_blackout && step_idx % gnss_stride == 0 {
+            let fixes = gnss_array.measure(truth.pos_n_m, truth.vel_n_mps, t_s);
+
+            let mean_fix = mean_gnss_fix(&fixes);
+            ekf.update_gnss(mean_fix.pos_n_m, mean_fix.vel_n_mps);
+            ekf_inflated.update_gnss(mean_fix.pos_n_m, mean_fix.vel_n_mps);
+
+            let gnss_out = gnss_trust.fuse(&fixes, gnss_stride as f64 * cfg.dt);
+            dsfb_nav.pos_n_m = dsfb_nav.pos_n_m * 0.75 + gnss_out.pos_n_m * 0.25;
+            dsfb_nav.vel_n_mps = dsfb_nav.vel_n_mps * 0.70 + gnss_out.vel_n_mps * 0.30;
+            gnss_trust_weights = gnss_out.trust_weights;
         }
 
         let trust_imu0 = *dsfb_out.trust_weights.first().unwrap_or(&0.0);
@@ -136,6 +166,9 @@ pub fn run_simulation(cfg: &SimConfig, output_dir: &Path) -> anyhow::Result<Summ
             ekf_x_km: ekf.nav.pos_n_m.x / 1_000.0,
             ekf_y_km: ekf.nav.pos_n_m.y / 1_000.0,
             ekf_z_km: ekf.nav.pos_n_m.z / 1_000.0,
+            ekf_inflated_x_km: ekf_inflated.nav.pos_n_m.x / 1_000.0,
+            ekf_inflated_y_km: ekf_inflated.nav.pos_n_m.y / 1_000.0,
+            ekf_inflated_z_km: ekf_inflated.nav.pos_n_m.z / 1_000.0,
             dsfb_x_km: dsfb_nav.pos_n_m.x / 1_000.0,
             dsfb_y_km: dsfb_nav.pos_n_m.y / 1_000.0,
             dsfb_z_km: dsfb_nav.pos_n_m.z / 1_000.0,
@@ -146,6 +179,9 @@ pub fn run_simulation(cfg: &SimConfig, output_dir: &Path) -> anyhow::Result<Summ
             ekf_pos_err_m: ekf.nav.position_error_m(&truth),
             ekf_vel_err_mps: ekf.nav.velocity_error_mps(&truth),
             ekf_att_err_deg: ekf.nav.attitude_error_deg(&truth),
+            ekf_inflated_pos_err_m: ekf_inflated.nav.position_error_m(&truth),
+            ekf_inflated_vel_err_mps: ekf_inflated.nav.velocity_error_mps(&truth),
+            ekf_inflated_att_err_deg: ekf_inflated.nav.attitude_error_deg(&truth),
             dsfb_pos_err_m: dsfb_nav.position_error_m(&truth),
             dsfb_vel_err_mps: dsfb_nav.velocity_error_mps(&truth),
             dsfb_att_err_deg: dsfb_nav.attitude_error_deg(&truth),
@@ -156,9 +192,16 @@ pub fn run_simulation(cfg: &SimConfig, output_dir: &Path) -> anyhow::Result<Summ
             dsfb_resid_inc_imu0: resid_imu0,
             dsfb_resid_inc_imu1: resid_imu1,
             dsfb_resid_inc_imu2: resid_imu2,
+            dsfb_raw_trust_imu: dsfb_out.raw_trust.clone(),
+
+            dsfb_trust_gnss: gnss_trust_weights.clone(),
+
+            attitude: cfg.export_attitude.then(|| {
+                AttitudeRecord::new(&truth.q_bn, &inertial.q_bn, &ekf.nav.q_bn, &dsfb_nav.q_bn)
+            }),
         });
 
-        if truth.altitude_m() <= 18_000.0 {
+        if descent_complete(cfg, &truth, &events) {
             break;
         }
     }
@@ -169,13 +212,17 @@ pub fn run_simulation(cfg: &SimConfig, output_dir: &Path) -> anyhow::Result<Summ
         0.0
     };
 
+    let plot_ext = cfg.plot_backend.extension();
     let files = OutputFiles {
         output_dir: output_dir.clone(),
         csv_path: output_dir.join("starship_timeseries.csv"),
-        summary_path: output_dir.join("starship_summary.json"),
-        plot_altitude_path: output_dir.join("plot_altitude.png"),
-        plot_error_path: output_dir.join("plot_position_error_log.png"),
-        plot_trust_path: output_dir.join("plot_dsfb_trust.png"),
+        summary_path: output_dir.join(SUMMARY_FILE),
+        config_path: output_dir.join(CONFIG_FILE),
+        plot_altitude_path: output_dir.join(format!("plot_altitude.{plot_ext}")),
+        plot_error_path: output_dir.join(format!("plot_position_error_log.{plot_ext}")),
+        plot_trust_path: output_dir.join(format!("plot_dsfb_trust.{plot_ext}")),
+        plot_blackout_path: blackout_start
+            .map(|_| output_dir.join(format!("plot_blackout_divergence.{plot_ext}"))),
     };
 
     let inertial_metrics = compute_metrics(
@@ -190,6 +237,12 @@ pub fn run_simulation(cfg: &SimConfig, output_dir: &Path) -> anyhow::Result<Summ
         |r| r.ekf_vel_err_mps,
         |r| r.ekf_att_err_deg,
     );
+    let ekf_inflated_metrics = compute_metrics(
+        &records,
+        |r| r.ekf_inflated_pos_err_m,
+        |r| r.ekf_inflated_vel_err_mps,
+        |r| r.ekf_inflated_att_err_deg,
+    );
     let dsfb_metrics = compute_metrics(
         &records,
         |r| r.dsfb_pos_err_m,
@@ -197,21 +250,38 @@ pub fn run_simulation(cfg: &SimConfig, output_dir: &Path) -> anyhow::Result<Summ
         |r| r.dsfb_att_err_deg,
     );
 
+    // Metrics use the full-rate records; telemetry is decimated to output_dt.
+    let output_records: Vec<SimRecord> = records
+        .iter()
+        .step_by(cfg.output_stride())
+        .cloned()
+        .collect();
+
     let summary = Summary {
         config: cfg.clone(),
         samples: records.len(),
+        output_samples: output_records.len(),
         blackout_start_s: blackout_start,
         blackout_end_s: blackout_end,
         blackout_duration_s,
+        tile_loss_events: events.tile_loss_events.clone(),
+        landing: cfg.landing.enabled.then_some(events.landing),
+        initial_errors: cfg.initial_error.resolve(),
+        imu_saturated_samples: imu_array.saturated_counts().to_vec(),
         inertial: inertial_metrics,
         ekf: ekf_metrics,
+        ekf_inflated: ekf_inflated_metrics,
         dsfb: dsfb_metrics,
+        thermal: compute_thermal_trust_metrics(&records, cfg.imu_count),
+        fusion_jitter: fusion_clock.metrics(),
+        sanity_violations: sanity.finish(),
         outputs: files.clone(),
     };
 
-    write_csv(&files.csv_path, &records)?;
+    write_config(&files.config_path, &cfg.resolved())?;
+    write_csv(&files.csv_path, &output_records)?;
     write_summary(&files.summary_path, &summary)?;
-    make_plots(&records, &files)?;
+    make_plots_with_summary(&output_records, &summary)?;
 
     Ok(summary)
 }
@@ -262,12 +332,116 @@ fn compute_metrics(
         rmse_attitude_deg: (att_sq / n).sqrt(),
         final_position_error_m: final_pos,
         max_position_error_m: max_pos,
+        blackout: compute_blackout_metrics(records, &pos_fn, &vel_fn, &att_fn),
+    }
+}
+
+fn compute_blackout_metrics(
+    records: &[SimRecord],
+    pos_fn: impl Fn(&SimRecord) -> f64,
+    vel_fn: impl Fn(&SimRecord) -> f64,
+    att_fn: impl Fn(&SimRecord) -> f64,
+) -> Option<BlackoutMetrics> {
+    let samples: Vec<(f64, f64, f64, f64)> = records
+        .iter()
+        .filter(|r| r.blackout)
+        .map(|r| (r.time_s, pos_fn(r), vel_fn(r), att_fn(r)))
+        .filter(|(_, p, v, a)| p.is_finite() && v.is_finite() && a.is_finite())
+        .collect();
+    if samples.is_empty() {
+        return None;
+    }
+
+    let n = samples.len() as f64;
+    let rms = |f: fn(&(f64, f64, f64, f64)) -> f64| {
+        (samples.iter().map(|s| f(s) * f(s)).sum::<f64>() / n).sqrt()
+    };
+
+    // Least-squares slope of position error against time.
+    let t_mean = samples.iter().map(|s| s.0).sum::<f64>() / n;
+    let p_mean = samples.iter().map(|s| s.1).sum::<f64>() / n;
+    let (cov, var) = samples.iter().fold((0.0, 0.0), |(cov, var), s| {
+        let dt = s.0 - t_mean;
+        (cov + dt * (s.1 - p_mean), var + dt * dt)
+    });
+    let growth = if var > 0.0 { cov / var } else { 0.0 };
+
+    // Error accrued over the first contiguous blackout interval, the one
+    // bounded by `blackout_start_s` and `blackout_end_s`.
+    let first_interval: Vec<(f64, f64, f64, f64)> = records
+        .iter()
+        .skip_while(|r| !r.blackout)
+        .take_while(|r| r.blackout)
+        .map(|r| (r.time_s, pos_fn(r), vel_fn(r), att_fn(r)))
+        .filter(|(_, p, v, a)| p.is_finite() && v.is_finite() && a.is_finite())
+        .collect();
+    let (entry, exit) = match (first_interval.first(), first_interval.last()) {
+        (Some(entry), Some(exit)) => (*entry, *exit),
+        _ => (samples[0], samples[samples.len() - 1]),
+    };
+    let accrued = exit.1 - entry.1;
+    let span = exit.0 - entry.0;
+
+    Some(BlackoutMetrics {
+        samples: samples.len(),
+        rmse_position_m: rms(|s| s.1),
+        rmse_velocity_mps: rms(|s| s.2),
+        rmse_attitude_deg: rms(|s| s.3),
+        max_position_error_m: samples.iter().map(|s| s.1).fold(0.0, f64::max),
+        position_error_growth_mps: growth,
+        position_error_accrued_m: accrued,
+        position_divergence_rate_mps: if span > 0.0 { accrued / span } else { 0.0 },
+        velocity_error_at_exit_mps: exit.2,
+        attitude_error_at_exit_deg: exit.3,
+    })
+}
+
+fn compute_thermal_trust_metrics(records: &[SimRecord], imu_count: usize) -> ThermalTrustMetrics {
+    let temps: Vec<f64> = records.iter().map(|r| r.heat_shield_temp_k).collect();
+    let peak = records
+        .iter()
+        .max_by(|a, b| a.heat_shield_temp_k.total_cmp(&b.heat_shield_temp_k));
+    let cold = &records[..records.len().div_ceil(10)];
+
+    ThermalTrustMetrics {
+        peak_heat_shield_temp_k: peak.map_or(0.0, |r| r.heat_shield_temp_k),
+        peak_temp_time_s: peak.map_or(0.0, |r| r.time_s),
+        trust_temp_correlation_imu: (0..imu_count)
+            .map(|k| {
+                let trust: Vec<f64> = records.iter().map(|r| r.dsfb_raw_trust_imu[k]).collect();
+                pearson(&temps, &trust)
+            })
+            .collect(),
+        trust_cold_imu: (0..imu_count)
+            .map(|k| {
+                cold.iter().map(|r| r.dsfb_raw_trust_imu[k]).sum::<f64>() / cold.len().max(1) as f64
+            })
+            .collect(),
+        trust_at_peak_temp_imu: (0..imu_count)
+            .map(|k| peak.map_or(0.0, |r| r.dsfb_raw_trust_imu[k]))
+            .collect(),
     }
 }
 
-fn gaussian(rng: &mut ChaCha8Rng, sigma: f64) -> f64 {
-    let z: f64 = rng.sample(StandardNormal);
-    sigma * z
+/// Pearson correlation; 0 when either series is constant.
+fn pearson(x: &[f64], y: &[f64]) -> f64 {
+    let n = x.len().min(y.len()) as f64;
+    if n < 2.0 {
+        return 0.0;
+    }
+    let mx = x.iter().sum::<f64>() / n;
+    let my = y.iter().sum::<f64>() / n;
+    let (mut cov, mut vx, mut vy) = (0.0, 0.0, 0.0);
+    for (a, b) in x.iter().zip(y) {
+        cov += (a - mx) * (b - my);
+        vx += (a - mx) * (a - mx);
+        vy += (b - my) * (b - my);
+    }
+    if vx > 0.0 && vy > 0.0 {
+        cov / (vx * vy).sqrt()
+    } else {
+        0.0
+    }
 }
 
 fn finite_nav(pos: &Vector3<f64>, vel: &Vector3<f64>) -> bool {
@@ -286,7 +460,7 @@ pub fn default_output_base_dir() -> PathBuf {
     workspace_root_dir().join("output-dsfb-starship")
 }
 
-fn resolve_output_base_dir(requested: &Path) -> PathBuf {
+pub(crate) fn resolve_output_base_dir(requested: &Path) -> PathBuf {
     if requested.is_absolute() {
         requested.to_path_buf()
     } else {
@@ -294,7 +468,7 @@ fn resolve_output_base_dir(requested: &Path) -> PathBuf {
     }
 }
 
-fn create_timestamped_run_dir(base_dir: &Path) -> anyhow::Result<PathBuf> {
+pub(crate) fn create_timestamped_run_dir(base_dir: &Path) -> anyhow::Result<PathBuf> {
     fs::create_dir_all(base_dir)
         .with_context(|| format!("failed to create output base directory {}", base_dir.display()))?;
 
@@ -317,7 +491,8 @@ fn create_timestamped_run_dir(base_dir: &Path) -> anyhow::Result<PathBuf> {
 }
 
 #[pyfunction]
-#[pyo3(signature = (output_dir=None, dt=None, t_final=None, rho=None, slew_threshold=None, seed=None))]
+#[pyo3(signature = (output_dir=None, dt=None, t_final=None, rho=None, slew_threshold=None, seed=None, output_dt=None, plot_backend=None, plot_max_points=None, gnss_count=None))]
+#[allow(clippy::too_many_arguments)]
 fn run_starship_simulation(
     output_dir: Option<String>,
     dt: Option<f64>,
@@ -325,12 +500,19 @@ fn run_starship_simulation(
     rho: Option<f64>,
     slew_threshold: Option<f64>,
     seed: Option<u64>,
+    output_dt: Option<f64>,
+    plot_backend: Option<String>,
+    plot_max_points: Option<usize>,
+    gnss_count: Option<usize>,
 ) -> PyResult<String> {
     let mut cfg = SimConfig::default();
 
     if let Some(v) = dt {
         cfg.dt = v;
     }
+    if output_dt.is_some() {
+        cfg.output_dt = output_dt;
+    }
     if let Some(v) = t_final {
         cfg.t_final = v;
     }
@@ -338,12 +520,20 @@ fn run_starship_simulation(
         cfg.rho = v;
     }
     if let Some(v) = slew_threshold {
-        cfg.slew_threshold_accel = v;
-        cfg.slew_threshold_gyro = (v * 0.055).max(0.15);
+        cfg.set_slew_threshold_accel(v);
     }
     if let Some(v) = seed {
         cfg.seed = v;
     }
+    if let Some(v) = plot_backend {
+        cfg.plot_backend = v.parse().map_err(PyValueError::new_err)?;
+    }
+    if let Some(v) = plot_max_points {
+        cfg.plot_max_points = v;
+    }
+    if let Some(v) = gnss_count {
+        cfg.gnss_count = v;
+    }
 
     let out = output_dir
         .map(PathBuf::from)
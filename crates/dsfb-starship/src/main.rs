@@ -1,8 +1,12 @@
 use std::path::PathBuf;
 
+use anyhow::Context;
 use clap::Parser;
-use dsfb_starship::config::SimConfig;
+use dsfb_starship::batch::{load_config_dir, run_batch};
+use dsfb_starship::config::{JitterDistribution, PlotBackend, SimConfig};
+use dsfb_starship::replay::replay_run;
 use dsfb_starship::run_simulation;
+use dsfb_starship::sanity::SanityViolation;
 
 #[derive(Debug, Parser)]
 #[command(author, version, about = "Starship 6-DoF re-entry DSFB demonstration")]
@@ -11,10 +15,14 @@ struct Cli {
     #[arg(long, default_value = "output-dsfb-starship")]
     output: PathBuf,
 
-    /// Integration step in seconds
+    /// Integration step in seconds (e.g. 0.02 for a 50 Hz fusion loop)
     #[arg(long)]
     dt: Option<f64>,
 
+    /// Telemetry output period in seconds (e.g. 0.2 for 5 Hz CSV output)
+    #[arg(long)]
+    output_dt: Option<f64>,
+
     /// Final simulation time in seconds
     #[arg(long)]
     t_final: Option<f64>,
@@ -30,15 +38,89 @@ struct Cli {
     /// Random seed
     #[arg(long)]
     seed: Option<u64>,
-}
 
-fn main() -> anyhow::Result<()> {
-    let cli = Cli::parse();
+    /// Gain on heating-driven IMU noise and bias growth (0 disables)
+    #[arg(long)]
+    imu_thermal_degradation: Option<f64>,
 
-    let mut cfg = SimConfig::default();
+    /// IMU accelerometer full-scale range per axis [m/s^2]
+    #[arg(long)]
+    accel_range: Option<f64>,
+
+    /// IMU gyro full-scale range per axis [rad/s]
+    #[arg(long)]
+    gyro_range: Option<f64>,
+
+    /// IMU accelerometer output resolution [m/s^2] (0 disables quantization)
+    #[arg(long)]
+    accel_lsb: Option<f64>,
+
+    /// IMU gyro output resolution [rad/s] (0 disables quantization)
+    #[arg(long)]
+    gyro_lsb: Option<f64>,
+
+    /// Number of GNSS receivers fused as separate trust channels
+    #[arg(long)]
+    gnss_count: Option<usize>,
+
+    /// Scheduling jitter on the DSFB fusion updates: none, uniform or gaussian
+    #[arg(long)]
+    fusion_jitter: Option<JitterDistribution>,
+
+    /// Fusion jitter magnitude [s]: uniform half-width or gaussian standard deviation
+    #[arg(long)]
+    fusion_jitter_s: Option<f64>,
+
+    /// Plot image backend: png or svg
+    #[arg(long)]
+    plot_backend: Option<PlotBackend>,
+
+    /// Maximum points per plotted series (0 disables downsampling)
+    #[arg(long)]
+    plot_max_points: Option<usize>,
+
+    /// Write truth and estimated quaternion and roll/pitch/yaw columns to the CSV
+    #[arg(long, default_value_t = false)]
+    export_attitude: bool,
+
+    /// Continue past the re-entry cutoff through the flip and landing burn to touchdown
+    #[arg(long, default_value_t = false)]
+    landing: bool,
+
+    /// Seed every estimator with the same initial navigation error
+    #[arg(long, default_value_t = false)]
+    identical_seed_errors: bool,
+
+    /// Exit with an error when any run fails a physics sanity check
+    #[arg(long, default_value_t = false)]
+    fail_on_sanity: bool,
+
+    /// Run every *.json SimConfig in this directory and write a combined leaderboard
+    #[arg(long, value_name = "DIR")]
+    configs: Option<PathBuf>,
+
+    /// Worker threads for --configs (0 uses all cores)
+    #[arg(long, default_value_t = 1)]
+    jobs: usize,
+
+    /// Rerun the configuration recorded in this run directory and check the
+    /// new summary against its starship_summary.json (other config flags are ignored)
+    #[arg(long, value_name = "RUN_DIR", conflicts_with = "configs")]
+    replay_config: Option<PathBuf>,
+
+    /// Relative tolerance for --replay-config summary values (absolute below 1)
+    #[arg(long, default_value_t = 1e-6)]
+    replay_tolerance: f64,
+}
+
+/// Applies the command-line overrides on top of `cfg`.
+fn apply_overrides(cli: &Cli, cfg: &mut SimConfig) {
     if let Some(v) = cli.dt {
         cfg.dt = v;
     }
+    if cli.output_dt.is_some() {
+        cfg.output_dt = cli.output_dt;
+    }
     if let Some(v) = cli.t_final {
         cfg.t_final = v;
     }
@@ -46,18 +128,70 @@ fn main() -> anyhow::Result<()> {
         cfg.rho = v;
     }
     if let Some(v) = cli.slew_threshold {
-        cfg.slew_threshold_accel = v;
-        cfg.slew_threshold_gyro = (0.055 * v).max(0.15);
+        cfg.set_slew_threshold_accel(v);
     }
     if let Some(v) = cli.seed {
         cfg.seed = v;
     }
+    if let Some(v) = cli.imu_thermal_degradation {
+        cfg.imu_thermal_degradation = v;
+    }
+    if let Some(v) = cli.accel_range {
+        cfg.imu_limits.accel_range_mps2 = v;
+    }
+    if let Some(v) = cli.gyro_range {
+        cfg.imu_limits.gyro_range_rps = v;
+    }
+    if let Some(v) = cli.accel_lsb {
+        cfg.imu_limits.accel_lsb_mps2 = v;
+    }
+    if let Some(v) = cli.gyro_lsb {
+        cfg.imu_limits.gyro_lsb_rps = v;
+    }
+    if let Some(v) = cli.gnss_count {
+        cfg.gnss_count = v;
+    }
+    if let Some(v) = cli.fusion_jitter {
+        cfg.fusion_jitter.distribution = v;
+    }
+    if let Some(v) = cli.fusion_jitter_s {
+        cfg.fusion_jitter.magnitude_s = v;
+    }
+    if let Some(v) = cli.plot_backend {
+        cfg.plot_backend = v;
+    }
+    if let Some(v) = cli.plot_max_points {
+        cfg.plot_max_points = v;
+    }
+    if cli.export_attitude {
+        cfg.export_attitude = true;
+    }
+    if cli.landing {
+        cfg.landing.enabled = true;
+    }
+    if cli.identical_seed_errors {
+        cfg.initial_error.identical = true;
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    if let Some(dir) = &cli.configs {
+        return run_config_dir(&cli, dir);
+    }
+    if let Some(run_dir) = &cli.replay_config {
+        return replay(&cli, run_dir);
+    }
+
+    let mut cfg = SimConfig::default();
+    apply_overrides(&cli, &mut cfg);
 
     let summary = run_simulation(&cfg, &cli.output)?;
 
     println!(
-        "Simulation complete. Samples: {} | Blackout: {:.1} s",
-        summary.samples, summary.blackout_duration_s
+        "Simulation complete. Samples: {} (written: {}) | Blackout: {:.1} s",
+        summary.samples, summary.output_samples, summary.blackout_duration_s
     );
     println!("Run directory: {}", summary.outputs.output_dir.display());
     println!("CSV: {}", summary.outputs.csv_path.display());
@@ -65,6 +199,15 @@ fn main() -> anyhow::Result<()> {
     println!("Altitude plot: {}", summary.outputs.plot_altitude_path.display());
     println!("Error plot: {}", summary.outputs.plot_error_path.display());
     println!("Trust plot: {}", summary.outputs.plot_trust_path.display());
+    if let Some(path) = &summary.outputs.plot_blackout_path {
+        println!("Blackout plot: {}", path.display());
+    }
+    if let Some(jitter) = &summary.fusion_jitter {
+        println!(
+            "Fusion jitter: intervals {:.4}-{:.4} s | RMS deviation from dt {:.4} s",
+            jitter.min_interval_s, jitter.max_interval_s, jitter.rms_interval_deviation_s
+        );
+    }
 
     println!(
         "DSFB RMSE pos/vel/att: {:.2} m | {:.3} m/s | {:.3} deg",
@@ -72,6 +215,141 @@ fn main() -> anyhow::Result<()> {
         summary.dsfb.rmse_velocity_mps,
         summary.dsfb.rmse_attitude_deg
     );
+    for (name, metrics) in [
+        ("Inertial", &summary.inertial),
+        ("EKF", &summary.ekf),
+        ("EKF (blackout inflation)", &summary.ekf_inflated),
+        ("DSFB", &summary.dsfb),
+    ] {
+        if let Some(blackout) = &metrics.blackout {
+            println!(
+                "{name} blackout RMSE pos: {:.2} m | max: {:.2} m | growth: {:.3} m/s",
+                blackout.rmse_position_m,
+                blackout.max_position_error_m,
+                blackout.position_error_growth_mps
+            );
+            println!(
+                "{name} blackout divergence: {:.3} m/s ({:.1} m accrued) | exit vel/att error: {:.3} m/s | {:.3} deg",
+                blackout.position_divergence_rate_mps,
+                blackout.position_error_accrued_m,
+                blackout.velocity_error_at_exit_mps,
+                blackout.attitude_error_at_exit_deg
+            );
+        }
+    }
+
+    if let Some(landing) = &summary.landing {
+        match (landing.touchdown_s, landing.touchdown_speed_mps) {
+            (Some(t), Some(v)) => println!("Touchdown at {t:.1} s, {v:.2} m/s"),
+            _ => println!("No touchdown before t_final = {:.0} s", cfg.t_final),
+        }
+    }
+
+    println!(
+        "Thermal: peak {:.1} K at {:.1} s | trust/temperature correlation per IMU: {}",
+        summary.thermal.peak_heat_shield_temp_k,
+        summary.thermal.peak_temp_time_s,
+        summary
+            .thermal
+            .trust_temp_correlation_imu
+            .iter()
+            .map(|c| format!("{c:+.3}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    );
+
+    print_sanity_violations(&summary.sanity_violations);
+    anyhow::ensure!(
+        !cli.fail_on_sanity || summary.sanity_violations.is_empty(),
+        "run failed {} physics sanity check(s)",
+        summary.sanity_violations.len()
+    );
+
+    Ok(())
+}
+
+fn print_sanity_violations(violations: &[SanityViolation]) {
+    for v in violations {
+        println!(
+            "Sanity violation: {:?} in {} sample(s) from {:.1} s to {:.1} s | worst {:.4e} vs limit {:.4e}",
+            v.check, v.samples, v.first_time_s, v.last_time_s, v.worst_value, v.limit
+        );
+    }
+}
+
+fn run_config_dir(cli: &Cli, dir: &std::path::Path) -> anyhow::Result<()> {
+    let mut entries = load_config_dir(dir)?;
+    for entry in &mut entries {
+        apply_overrides(cli, &mut entry.config);
+        entry
+            .config
+            .validate()
+            .with_context(|| format!("invalid config {} after overrides", entry.name))?;
+    }
+
+    let batch = run_batch(&entries, &cli.output, cli.jobs)?;
+
+    println!(
+        "Batch complete: {} configs | Run directory: {}",
+        batch.runs.len(),
+        batch.output_dir.display()
+    );
+    println!("Leaderboard: {}", batch.leaderboard_path.display());
+    println!(
+        "{:<24} {:>4} {:<9} {:>14} {:>14}",
+        "config", "rank", "method", "rmse_pos_m", "blackout_pos_m"
+    );
+    for row in &batch.leaderboard {
+        let blackout = row
+            .blackout_rmse_position_m
+            .map_or_else(|| "-".to_string(), |v| format!("{v:.2}"));
+        println!(
+            "{:<24} {:>4} {:<9} {:>14.2} {:>14}",
+            row.config, row.rank, row.method, row.rmse_position_m, blackout
+        );
+    }
+
+    let mut insane = 0;
+    for (name, summary) in &batch.runs {
+        if !summary.sanity_violations.is_empty() {
+            println!("{name}:");
+            print_sanity_violations(&summary.sanity_violations);
+            insane += 1;
+        }
+    }
+    anyhow::ensure!(
+        !cli.fail_on_sanity || insane == 0,
+        "{insane} of {} configs failed physics sanity checks",
+        batch.runs.len()
+    );
+
+    Ok(())
+}
+
+fn replay(cli: &Cli, run_dir: &std::path::Path) -> anyhow::Result<()> {
+    let report = replay_run(run_dir, &cli.output, cli.replay_tolerance)?;
+
+    println!("Replayed: {}", report.source_dir.display());
+    println!(
+        "Run directory: {}",
+        report.summary.outputs.output_dir.display()
+    );
+    for mismatch in &report.mismatches {
+        println!(
+            "  {}: stored {} | replayed {}",
+            mismatch.path, mismatch.stored, mismatch.replayed
+        );
+    }
+    anyhow::ensure!(
+        report.matches(),
+        "replay differs from the stored summary in {} value(s) (tolerance {:e})",
+        report.mismatches.len(),
+        cli.replay_tolerance
+    );
+    println!(
+        "Summary reproduced within tolerance {:e}",
+        cli.replay_tolerance
+    );
 
     Ok(())
 }
@@ -2,10 +2,19 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 use anyhow::Context;
+use nalgebra::UnitQuaternion;
+use plotters::coord::Shift;
 use plotters::prelude::*;
 use serde::Serialize;
 
-use crate::config::SimConfig;
+use crate::config::{InitialErrors, PlotBackend, ResolvedConfig, SimConfig};
+use crate::physics::{LandingTimeline, TileLossEvent};
+use crate::sanity::SanityViolation;
+
+/// File name of the run summary inside a run directory
+pub const SUMMARY_FILE: &str = "starship_summary.json";
+/// File name of the resolved run configuration inside a run directory
+pub const CONFIG_FILE: &str = "config.json";
 
 #[derive(Debug, Clone, Serialize)]
 pub struct SimRecord {
@@ -28,6 +37,9 @@ pub struct SimRecord {
     pub ekf_x_km: f64,
     pub ekf_y_km: f64,
     pub ekf_z_km: f64,
+    pub ekf_inflated_x_km: f64,
+    pub ekf_inflated_y_km: f64,
+    pub ekf_inflated_z_km: f64,
     pub dsfb_x_km: f64,
     pub dsfb_y_km: f64,
     pub dsfb_z_km: f64,
@@ -38,6 +50,9 @@ pub struct SimRecord {
     pub ekf_pos_err_m: f64,
     pub ekf_vel_err_mps: f64,
     pub ekf_att_err_deg: f64,
+    pub ekf_inflated_pos_err_m: f64,
+    pub ekf_inflated_vel_err_mps: f64,
+    pub ekf_inflated_att_err_deg: f64,
     pub dsfb_pos_err_m: f64,
     pub dsfb_vel_err_mps: f64,
     pub dsfb_att_err_deg: f64,
@@ -48,6 +63,104 @@ pub struct SimRecord {
     pub dsfb_resid_inc_imu0: f64,
     pub dsfb_resid_inc_imu1: f64,
     pub dsfb_resid_inc_imu2: f64,
+    /// Unnormalized trust of each IMU, written as one `dsfb_raw_trust_imu<k>`
+    /// column per IMU after the residual columns
+    #[serde(skip)]
+    pub dsfb_raw_trust_imu: Vec<f64>,
+
+    /// Trust of each GNSS receiver, written as one `dsfb_trust_gnss<k>` column
+    /// per receiver after the IMU columns
+    #[serde(skip)]
+    pub dsfb_trust_gnss: Vec<f64>,
+
+    /// Attitude columns, appended to the CSV only when `export_attitude` is set
+    #[serde(skip)]
+    pub attitude: Option<AttitudeRecord>,
+}
+
+/// Truth and estimated attitude of `q_bn` as scalar-first quaternion
+/// components and roll/pitch/yaw (nalgebra `euler_angles` convention).
+#[derive(Debug, Clone, Serialize)]
+pub struct AttitudeRecord {
+    pub truth_qw: f64,
+    pub truth_qx: f64,
+    pub truth_qy: f64,
+    pub truth_qz: f64,
+    pub truth_roll_deg: f64,
+    pub truth_pitch_deg: f64,
+    pub truth_yaw_deg: f64,
+
+    pub inertial_qw: f64,
+    pub inertial_qx: f64,
+    pub inertial_qy: f64,
+    pub inertial_qz: f64,
+    pub inertial_roll_deg: f64,
+    pub inertial_pitch_deg: f64,
+    pub inertial_yaw_deg: f64,
+
+    pub ekf_qw: f64,
+    pub ekf_qx: f64,
+    pub ekf_qy: f64,
+    pub ekf_qz: f64,
+    pub ekf_roll_deg: f64,
+    pub ekf_pitch_deg: f64,
+    pub ekf_yaw_deg: f64,
+
+    pub dsfb_qw: f64,
+    pub dsfb_qx: f64,
+    pub dsfb_qy: f64,
+    pub dsfb_qz: f64,
+    pub dsfb_roll_deg: f64,
+    pub dsfb_pitch_deg: f64,
+    pub dsfb_yaw_deg: f64,
+}
+
+impl AttitudeRecord {
+    pub fn new(
+        truth: &UnitQuaternion<f64>,
+        inertial: &UnitQuaternion<f64>,
+        ekf: &UnitQuaternion<f64>,
+        dsfb: &UnitQuaternion<f64>,
+    ) -> Self {
+        let (truth_roll, truth_pitch, truth_yaw) = truth.euler_angles();
+        let (inertial_roll, inertial_pitch, inertial_yaw) = inertial.euler_angles();
+        let (ekf_roll, ekf_pitch, ekf_yaw) = ekf.euler_angles();
+        let (dsfb_roll, dsfb_pitch, dsfb_yaw) = dsfb.euler_angles();
+
+        Self {
+            truth_qw: truth.w,
+            truth_qx: truth.i,
+            truth_qy: truth.j,
+            truth_qz: truth.k,
+            truth_roll_deg: truth_roll.to_degrees(),
+            truth_pitch_deg: truth_pitch.to_degrees(),
+            truth_yaw_deg: truth_yaw.to_degrees(),
+
+            inertial_qw: inertial.w,
+            inertial_qx: inertial.i,
+            inertial_qy: inertial.j,
+            inertial_qz: inertial.k,
+            inertial_roll_deg: inertial_roll.to_degrees(),
+            inertial_pitch_deg: inertial_pitch.to_degrees(),
+            inertial_yaw_deg: inertial_yaw.to_degrees(),
+
+            ekf_qw: ekf.w,
+            ekf_qx: ekf.i,
+            ekf_qy: ekf.j,
+            ekf_qz: ekf.k,
+            ekf_roll_deg: ekf_roll.to_degrees(),
+            ekf_pitch_deg: ekf_pitch.to_degrees(),
+            ekf_yaw_deg: ekf_yaw.to_degrees(),
+
+            dsfb_qw: dsfb.w,
+            dsfb_qx: dsfb.i,
+            dsfb_qy: dsfb.j,
+            dsfb_qz: dsfb.k,
+            dsfb_roll_deg: dsfb_roll.to_degrees(),
+            dsfb_pitch_deg: dsfb_pitch.to_degrees(),
+            dsfb_yaw_deg: dsfb_yaw.to_degrees(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -57,18 +170,84 @@ pub struct MethodMetrics {
     pub rmse_attitude_deg: f64,
     pub final_position_error_m: f64,
     pub max_position_error_m: f64,
+    /// Metrics restricted to blackout samples; `None` if no blackout occurred
+    pub blackout: Option<BlackoutMetrics>,
+}
+
+/// Relation between the heating profile and DSFB IMU trust.
+///
+/// Uses the unnormalized per-channel trust (`dsfb_raw_trust_imu`), since the
+/// normalized weights of healthy channels rise whenever another channel faults.
+#[derive(Debug, Clone, Serialize)]
+pub struct ThermalTrustMetrics {
+    pub peak_heat_shield_temp_k: f64,
+    pub peak_temp_time_s: f64,
+    /// Pearson correlation of each IMU's trust with heat-shield temperature;
+    /// negative values mean trust declines as the vehicle heats
+    pub trust_temp_correlation_imu: Vec<f64>,
+    /// Mean trust of each IMU before heating (first 10% of samples) and at peak temperature
+    pub trust_cold_imu: Vec<f64>,
+    pub trust_at_peak_temp_imu: Vec<f64>,
+}
+
+/// Intervals the DSFB fusion layer was stepped with under scheduling jitter.
+#[derive(Debug, Clone, Serialize)]
+pub struct FusionJitterMetrics {
+    pub updates: usize,
+    pub min_interval_s: f64,
+    pub max_interval_s: f64,
+    /// RMS difference between the realized intervals and `dt`
+    pub rms_interval_deviation_s: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BlackoutMetrics {
+    pub samples: usize,
+    pub rmse_position_m: f64,
+    pub rmse_velocity_mps: f64,
+    pub rmse_attitude_deg: f64,
+    pub max_position_error_m: f64,
+    /// Least-squares slope of position error over blackout time [m/s]
+    pub position_error_growth_mps: f64,
+    /// Position error at blackout exit minus at blackout entry, over the
+    /// first blackout interval [m]
+    pub position_error_accrued_m: f64,
+    /// `position_error_accrued_m` per second of blackout [m/s]
+    pub position_divergence_rate_mps: f64,
+    /// Velocity error at blackout exit [m/s]
+    pub velocity_error_at_exit_mps: f64,
+    /// Attitude error at blackout exit [deg]
+    pub attitude_error_at_exit_deg: f64,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct Summary {
     pub config: SimConfig,
+    /// Full-rate samples used for the metrics
     pub samples: usize,
+    /// Decimated samples written to the CSV and plots
+    pub output_samples: usize,
     pub blackout_start_s: Option<f64>,
     pub blackout_end_s: Option<f64>,
     pub blackout_duration_s: f64,
+    /// Tile-loss schedule the run was flown with
+    pub tile_loss_events: Vec<TileLossEvent>,
+    /// Flip, landing-burn and touchdown times; `None` when landing is disabled
+    pub landing: Option<LandingTimeline>,
+    /// Seed error each estimator started from
+    pub initial_errors: InitialErrors,
+    /// Per IMU channel, readings with at least one axis at its range limit
+    pub imu_saturated_samples: Vec<usize>,
     pub inertial: MethodMetrics,
     pub ekf: MethodMetrics,
+    /// EKF with process noise inflated during blackout
+    pub ekf_inflated: MethodMetrics,
     pub dsfb: MethodMetrics,
+    pub thermal: ThermalTrustMetrics,
+    /// Realized fusion-update intervals; `None` without scheduling jitter
+    pub fusion_jitter: Option<FusionJitterMetrics>,
+    /// Physics sanity checks the truth trajectory failed; empty for a sane run
+    pub sanity_violations: Vec<SanityViolation>,
     pub outputs: OutputFiles,
 }
 
@@ -77,9 +256,13 @@ pub struct OutputFiles {
     pub output_dir: PathBuf,
     pub csv_path: PathBuf,
     pub summary_path: PathBuf,
+    /// Resolved configuration of the run, for `--replay-config`
+    pub config_path: PathBuf,
     pub plot_altitude_path: PathBuf,
     pub plot_error_path: PathBuf,
     pub plot_trust_path: PathBuf,
+    /// Position error around the blackout window; `None` without a blackout
+    pub plot_blackout_path: Option<PathBuf>,
 }
 
 pub fn write_csv(path: &Path, records: &[SimRecord]) -> anyhow::Result<()> {
@@ -87,17 +270,47 @@ pub fn write_csv(path: &Path, records: &[SimRecord]) -> anyhow::Result<()> {
         fs::create_dir_all(parent)?;
     }
 
-    let mut writer = csv::Writer::from_path(path)
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_path(path)
         .with_context(|| format!("failed to open CSV path {}", path.display()))?;
 
+    if let Some(first) = records.first() {
+        let mut header = struct_headers(first)?;
+        for k in 0..first.dsfb_raw_trust_imu.len() {
+            header.push_field(&format!("dsfb_raw_trust_imu{k}"));
+        }
+        for k in 0..first.dsfb_trust_gnss.len() {
+            header.push_field(&format!("dsfb_trust_gnss{k}"));
+        }
+        if let Some(attitude) = &first.attitude {
+            header.extend(struct_headers(attitude)?.iter());
+        }
+        writer.write_record(&header)?;
+    }
+
     for record in records {
-        writer.serialize(record)?;
+        let channels = (&record.dsfb_raw_trust_imu, &record.dsfb_trust_gnss);
+        match &record.attitude {
+            Some(attitude) => writer.serialize((record, channels, attitude))?,
+            None => writer.serialize((record, channels))?,
+        }
     }
 
     writer.flush()?;
     Ok(())
 }
 
+/// Column names serde derives for one row struct
+fn struct_headers<T: Serialize>(row: &T) -> anyhow::Result<csv::StringRecord> {
+    let mut probe = csv::Writer::from_writer(Vec::new());
+    probe.serialize(row)?;
+    let bytes = probe.into_inner().map_err(|e| e.into_error())?;
+    Ok(csv::Reader::from_reader(bytes.as_slice())
+        .headers()?
+        .clone())
+}
+
 pub fn write_summary(path: &Path, summary: &Summary) -> anyhow::Result<()> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)?;
@@ -108,19 +321,159 @@ pub fn write_summary(path: &Path, summary: &Summary) -> anyhow::Result<()> {
     Ok(())
 }
 
-pub fn make_plots(records: &[SimRecord], files: &OutputFiles) -> anyhow::Result<()> {
-    plot_altitude(records, &files.plot_altitude_path)?;
-    plot_position_error(records, &files.plot_error_path)?;
-    plot_trust(records, &files.plot_trust_path)?;
+pub fn write_config(path: &Path, config: &ResolvedConfig) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let data = serde_json::to_string_pretty(config)?;
+    fs::write(path, data)?;
     Ok(())
 }
 
-fn plot_altitude(records: &[SimRecord], path: &Path) -> anyhow::Result<()> {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)?;
+/// Writes the altitude, position error, and trust plots of `records` into
+/// `files`. The blackout divergence plot needs the run's metrics and is only
+/// written by [`make_plots_with_summary`].
+pub fn make_plots(
+    records: &[SimRecord],
+    files: &OutputFiles,
+    cfg: &SimConfig,
+) -> anyhow::Result<()> {
+    plot_with_backend(records, files, cfg, None)
+}
+
+/// [`make_plots`] for a finished run, adding the blackout divergence plot
+/// when the trajectory enters blackout.
+pub fn make_plots_with_summary(records: &[SimRecord], summary: &Summary) -> anyhow::Result<()> {
+    plot_with_backend(records, &summary.outputs, &summary.config, Some(summary))
+}
+
+fn plot_with_backend(
+    records: &[SimRecord],
+    files: &OutputFiles,
+    cfg: &SimConfig,
+    summary: Option<&Summary>,
+) -> anyhow::Result<()> {
+    fs::create_dir_all(&files.output_dir)?;
+
+    let max_points = cfg.plot_max_points;
+    match cfg.plot_backend {
+        PlotBackend::Png => render_plots(records, files, summary, max_points, |path| {
+            BitMapBackend::new(path, PLOT_SIZE)
+        }),
+        PlotBackend::Svg => render_plots(records, files, summary, max_points, |path| {
+            SVGBackend::new(path, PLOT_SIZE)
+        }),
+    }
+}
+
+const PLOT_SIZE: (u32, u32) = (1280, 720);
+
+/// Reads one plotted value out of a record
+type RecordValue = fn(&SimRecord) -> f64;
+
+fn render_plots<'a, DB, F>(
+    records: &[SimRecord],
+    files: &'a OutputFiles,
+    summary: Option<&Summary>,
+    max_points: usize,
+    backend: F,
+) -> anyhow::Result<()>
+where
+    DB: DrawingBackend + 'a,
+    DB::ErrorType: 'static,
+    F: Fn(&'a Path) -> DB,
+{
+    plot_altitude(
+        backend(&files.plot_altitude_path).into_drawing_area(),
+        records,
+        max_points,
+    )?;
+    plot_position_error(
+        backend(&files.plot_error_path).into_drawing_area(),
+        records,
+        max_points,
+    )?;
+    plot_trust(
+        backend(&files.plot_trust_path).into_drawing_area(),
+        records,
+        max_points,
+    )?;
+    if let (Some(path), Some(summary)) = (&files.plot_blackout_path, summary) {
+        plot_blackout_divergence(
+            backend(path).into_drawing_area(),
+            records,
+            summary,
+            max_points,
+        )?;
+    }
+    Ok(())
+}
+
+/// Extracts `(time, value)` points for one series, downsampled to at most
+/// `max_points` with largest-triangle-three-buckets (LTTB).
+fn series(
+    records: &[SimRecord],
+    value: impl Fn(&SimRecord) -> f64,
+    max_points: usize,
+) -> Vec<(f64, f64)> {
+    let points: Vec<(f64, f64)> = records.iter().map(|r| (r.time_s, value(r))).collect();
+    lttb(&points, max_points)
+}
+
+/// Largest-triangle-three-buckets downsampling; keeps the first and last points
+/// and, per bucket, the point forming the largest triangle with its neighbours.
+fn lttb(points: &[(f64, f64)], max_points: usize) -> Vec<(f64, f64)> {
+    if max_points < 3 || points.len() <= max_points {
+        return points.to_vec();
     }
 
-    let root = BitMapBackend::new(path, (1280, 720)).into_drawing_area();
+    let bucket = (points.len() - 2) as f64 / (max_points - 2) as f64;
+    let mut out = Vec::with_capacity(max_points);
+    out.push(points[0]);
+    let mut anchor = points[0];
+
+    for i in 0..max_points - 2 {
+        let start = (i as f64 * bucket) as usize + 1;
+        let end = (((i + 1) as f64 * bucket) as usize + 1).min(points.len() - 1);
+
+        let next_start = end;
+        let next_end = (((i + 2) as f64 * bucket) as usize + 1).min(points.len());
+        let next = &points[next_start..next_end.max(next_start + 1)];
+        let n = next.len() as f64;
+        let avg = (
+            next.iter().map(|p| p.0).sum::<f64>() / n,
+            next.iter().map(|p| p.1).sum::<f64>() / n,
+        );
+
+        let chosen = points[start..end.max(start + 1)]
+            .iter()
+            .copied()
+            .max_by(|a, b| {
+                let area = |p: (f64, f64)| {
+                    ((anchor.0 - avg.0) * (p.1 - anchor.1) - (anchor.0 - p.0) * (avg.1 - anchor.1))
+                        .abs()
+                };
+                area(*a).total_cmp(&area(*b))
+            })
+            .unwrap_or(points[start]);
+        out.push(chosen);
+        anchor = chosen;
+    }
+
+    out.push(points[points.len() - 1]);
+    out
+}
+
+fn plot_altitude<DB>(
+    root: DrawingArea<DB, Shift>,
+    records: &[SimRecord],
+    max_points: usize,
+) -> anyhow::Result<()>
+where
+    DB: DrawingBackend,
+    DB::ErrorType: 'static,
+{
     root.fill(&WHITE)?;
 
     let max_time = records.last().map(|r| r.time_s).unwrap_or(1.0);
@@ -144,7 +497,7 @@ fn plot_altitude(records: &[SimRecord], path: &Path) -> anyhow::Result<()> {
         .draw()?;
 
     chart.draw_series(LineSeries::new(
-        records.iter().map(|r| (r.time_s, r.altitude_m)),
+        series(records, |r| r.altitude_m, max_points),
         &BLUE,
     ))?;
 
@@ -152,12 +505,15 @@ fn plot_altitude(records: &[SimRecord], path: &Path) -> anyhow::Result<()> {
     Ok(())
 }
 
-fn plot_position_error(records: &[SimRecord], path: &Path) -> anyhow::Result<()> {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)?;
-    }
-
-    let root = BitMapBackend::new(path, (1280, 720)).into_drawing_area();
+fn plot_position_error<DB>(
+    root: DrawingArea<DB, Shift>,
+    records: &[SimRecord],
+    max_points: usize,
+) -> anyhow::Result<()>
+where
+    DB: DrawingBackend,
+    DB::ErrorType: 'static,
+{
     root.fill(&WHITE)?;
 
     let max_time = records.last().map(|r| r.time_s).unwrap_or(1.0);
@@ -166,6 +522,7 @@ fn plot_position_error(records: &[SimRecord], path: &Path) -> anyhow::Result<()>
         .map(|r| {
             r.inertial_pos_err_m
                 .max(r.ekf_pos_err_m)
+                .max(r.ekf_inflated_pos_err_m)
                 .max(r.dsfb_pos_err_m)
                 .max(1.0)
         })
@@ -189,7 +546,7 @@ fn plot_position_error(records: &[SimRecord], path: &Path) -> anyhow::Result<()>
 
     chart
         .draw_series(LineSeries::new(
-            records.iter().map(|r| (r.time_s, r.inertial_pos_err_m.max(1.0))),
+            series(records, |r| r.inertial_pos_err_m.max(1.0), max_points),
             &RED,
         ))?
         .label("Pure Inertial")
@@ -197,7 +554,7 @@ fn plot_position_error(records: &[SimRecord], path: &Path) -> anyhow::Result<()>
 
     chart
         .draw_series(LineSeries::new(
-            records.iter().map(|r| (r.time_s, r.ekf_pos_err_m.max(1.0))),
+            series(records, |r| r.ekf_pos_err_m.max(1.0), max_points),
             &GREEN,
         ))?
         .label("Simple EKF")
@@ -205,7 +562,15 @@ fn plot_position_error(records: &[SimRecord], path: &Path) -> anyhow::Result<()>
 
     chart
         .draw_series(LineSeries::new(
-            records.iter().map(|r| (r.time_s, r.dsfb_pos_err_m.max(1.0))),
+            series(records, |r| r.ekf_inflated_pos_err_m.max(1.0), max_points),
+            &MAGENTA,
+        ))?
+        .label("EKF, blackout inflation")
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 25, y)], MAGENTA.stroke_width(3)));
+
+    chart
+        .draw_series(LineSeries::new(
+            series(records, |r| r.dsfb_pos_err_m.max(1.0), max_points),
             &BLUE,
         ))?
         .label("DSFB")
@@ -222,12 +587,118 @@ fn plot_position_error(records: &[SimRecord], path: &Path) -> anyhow::Result<()>
     Ok(())
 }
 
-fn plot_trust(records: &[SimRecord], path: &Path) -> anyhow::Result<()> {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)?;
+/// Position error of each method from shortly before blackout entry to
+/// shortly after exit, with the blackout shaded and each method's divergence
+/// rate and exit velocity error in the legend.
+fn plot_blackout_divergence<DB>(
+    root: DrawingArea<DB, Shift>,
+    records: &[SimRecord],
+    summary: &Summary,
+    max_points: usize,
+) -> anyhow::Result<()>
+where
+    DB: DrawingBackend,
+    DB::ErrorType: 'static,
+{
+    root.fill(&WHITE)?;
+
+    let last_time = records.last().map(|r| r.time_s).unwrap_or(1.0);
+    let start = summary.blackout_start_s.unwrap_or(0.0);
+    let end = summary.blackout_end_s.unwrap_or(last_time);
+    let pad = 0.25 * (end - start).max(1.0);
+    let (t0, t1) = ((start - pad).max(0.0), (end + pad).min(last_time));
+    let window: Vec<SimRecord> = records
+        .iter()
+        .filter(|r| r.time_s >= t0 && r.time_s <= t1)
+        .cloned()
+        .collect();
+
+    let methods: [(&str, &MethodMetrics, RecordValue, RGBColor); 4] = [
+        (
+            "Pure Inertial",
+            &summary.inertial,
+            |r| r.inertial_pos_err_m,
+            RED,
+        ),
+        ("Simple EKF", &summary.ekf, |r| r.ekf_pos_err_m, GREEN),
+        (
+            "EKF, blackout inflation",
+            &summary.ekf_inflated,
+            |r| r.ekf_inflated_pos_err_m,
+            MAGENTA,
+        ),
+        ("DSFB", &summary.dsfb, |r| r.dsfb_pos_err_m, BLUE),
+    ];
+
+    let max_err = window
+        .iter()
+        .flat_map(|r| methods.iter().map(move |m| (m.2)(r)))
+        .filter(|e| e.is_finite())
+        .fold(1.0_f64, f64::max)
+        * 1.1;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(
+            "Position Error Divergence During Blackout",
+            ("sans-serif", 34).into_font(),
+        )
+        .margin(20)
+        .x_label_area_size(50)
+        .y_label_area_size(80)
+        .build_cartesian_2d(t0..t1.max(t0 + 1.0), 0.0..max_err)?;
+
+    chart
+        .configure_mesh()
+        .x_desc("Time [s]")
+        .y_desc("Position Error [m]")
+        .draw()?;
+
+    chart.draw_series(std::iter::once(Rectangle::new(
+        [(start, 0.0), (end, max_err)],
+        BLACK.mix(0.08).filled(),
+    )))?;
+    chart.draw_series(std::iter::once(Text::new(
+        format!("Blackout ({:.0} s)", summary.blackout_duration_s),
+        (start, max_err * 0.03),
+        ("sans-serif", 20).into_font(),
+    )))?;
+
+    for (name, metrics, pos_fn, color) in methods {
+        let label = match &metrics.blackout {
+            Some(b) => format!(
+                "{name}: {:.2} m/s accrued, {:.2} m/s vel. err. at exit",
+                b.position_divergence_rate_mps, b.velocity_error_at_exit_mps
+            ),
+            None => name.to_string(),
+        };
+        chart
+            .draw_series(LineSeries::new(series(&window, pos_fn, max_points), &color))?
+            .label(label)
+            .legend(move |(x, y)| {
+                PathElement::new(vec![(x, y), (x + 25, y)], color.stroke_width(3))
+            });
     }
 
-    let root = BitMapBackend::new(path, (1280, 720)).into_drawing_area();
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::UpperLeft)
+        .border_style(BLACK)
+        .background_style(WHITE.mix(0.7))
+        .draw()?;
+
+    root.present()?;
+    Ok(())
+}
+
+fn plot_trust<DB>(
+    root: DrawingArea<DB, Shift>,
+    records: &[SimRecord],
+    max_points: usize,
+) -> anyhow::Result<()>
+where
+    DB: DrawingBackend,
+    DB::ErrorType: 'static,
+{
     root.fill(&WHITE)?;
 
     let max_time = records.last().map(|r| r.time_s).unwrap_or(1.0);
@@ -247,7 +718,7 @@ fn plot_trust(records: &[SimRecord], path: &Path) -> anyhow::Result<()> {
 
     chart
         .draw_series(LineSeries::new(
-            records.iter().map(|r| (r.time_s, r.dsfb_trust_imu0)),
+            series(records, |r| r.dsfb_trust_imu0, max_points),
             &BLUE,
         ))?
         .label("IMU-0")
@@ -255,7 +726,7 @@ fn plot_trust(records: &[SimRecord], path: &Path) -> anyhow::Result<()> {
 
     chart
         .draw_series(LineSeries::new(
-            records.iter().map(|r| (r.time_s, r.dsfb_trust_imu1)),
+            series(records, |r| r.dsfb_trust_imu1, max_points),
             &RED,
         ))?
         .label("IMU-1")
@@ -263,7 +734,7 @@ fn plot_trust(records: &[SimRecord], path: &Path) -> anyhow::Result<()> {
 
     chart
         .draw_series(LineSeries::new(
-            records.iter().map(|r| (r.time_s, r.dsfb_trust_imu2)),
+            series(records, |r| r.dsfb_trust_imu2, max_points),
             &GREEN,
         ))?
         .label("IMU-2")
@@ -1,6 +1,9 @@
 use std::f64::consts::PI;
 
 use nalgebra::{Matrix3, UnitQuaternion, Vector3};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::Serialize;
 
 use crate::config::SimConfig;
 
@@ -10,6 +13,25 @@ const R_AIR: f64 = 287.05;
 const GAMMA_AIR: f64 = 1.4;
 const SIGMA_SB: f64 = 5.670_374_419e-8;
 
+/// Altitude at which the run ends when the landing phase is disabled [m].
+pub const REENTRY_CUTOFF_ALTITUDE_M: f64 = 18_000.0;
+
+/// Broadside drag coefficient of the belly-flop fall.
+const BELLY_FLOP_CD: f64 = 1.2;
+/// Largest non-gravitational load of the belly-flop fall [m/s^2].
+const BELLY_FLOP_MAX_LOAD_MPS2: f64 = 4.0 * G0;
+/// Velocity relaxation time of the belly-flop fall [s].
+const BELLY_FLOP_TAU_S: f64 = 5.0;
+/// Altitude e-folding time of the commanded belly-flop sink rate [s].
+const BELLY_FLOP_SINK_TIME_S: f64 = 60.0;
+/// Tail-first drag coefficient and area fraction used once the flip starts.
+const TAIL_FIRST_CD: f64 = 0.9;
+const TAIL_FIRST_AREA_FRACTION: f64 = 0.2;
+/// Attitude slew limit outside the flip [rad/s].
+const GUIDED_MAX_SLEW_RPS: f64 = 0.35;
+/// Horizontal-velocity damping gain of the landing burn [1/s].
+const LANDING_BURN_LATERAL_GAIN: f64 = 0.6;
+
 #[derive(Debug, Clone)]
 pub struct VehicleParams {
     pub dry_mass_kg: f64,
@@ -84,21 +106,150 @@ pub struct AeroSample {
 pub struct TruthStepSample {
     pub atmosphere: AtmosphereSample,
     pub aero: AeroSample,
+    /// Total non-gravitational specific force: aero plus engine thrust
+    pub specific_force_b_mps2: Vector3<f64>,
+    pub thrust_n: f64,
     pub angular_accel_b_rps2: Vector3<f64>,
     pub heat_flux_w_m2: f64,
     pub blackout: bool,
 }
 
-#[derive(Debug, Clone, Copy)]
+/// One scheduled heat-shield tile loss.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TileLossEvent {
+    pub onset_s: f64,
+    /// Multiplier on the nominal asymmetric aero terms
+    pub severity: f64,
+}
+
+/// Flight phase of the descent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DescentPhase {
+    /// Aerodynamically controlled entry down to the re-entry cutoff
+    Entry,
+    /// Broadside fall from the blackout lower bound to the flip
+    BellyFlop,
+    /// Rotation to vertical with the engines lit
+    Flip,
+    /// Throttled descent to touchdown
+    LandingBurn,
+    Landed,
+}
+
+/// Times of the terminal-descent phase transitions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct LandingTimeline {
+    pub belly_flop_start_s: Option<f64>,
+    pub flip_start_s: Option<f64>,
+    pub landing_burn_start_s: Option<f64>,
+    pub touchdown_s: Option<f64>,
+    pub touchdown_speed_mps: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
 pub struct ReentryEventState {
+    /// Whether any tile-loss event has started
     pub tile_loss_active: bool,
+    /// Summed severity of the tile-loss events started so far
+    pub tile_loss_severity: f64,
+    /// Tile-loss schedule, sorted by onset
+    pub tile_loss_events: Vec<TileLossEvent>,
+    pub descent_phase: DescentPhase,
+    pub landing: LandingTimeline,
+    /// Attitude at the start of the flip
+    flip_start_q_bn: UnitQuaternion<f64>,
 }
 
 impl Default for ReentryEventState {
+    /// The nominal schedule: one full-severity tile loss at 320 s.
     fn default() -> Self {
+        Self::from_config(&SimConfig::default())
+    }
+}
+
+impl ReentryEventState {
+    /// Draws the tile-loss schedule of `cfg.tile_loss`.
+    pub fn from_config(cfg: &SimConfig) -> Self {
+        let tile_loss = &cfg.tile_loss;
+        let seed = tile_loss.effective_seed(cfg.seed);
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+        let mut tile_loss_events: Vec<TileLossEvent> = (0..tile_loss.count)
+            .map(|_| TileLossEvent {
+                onset_s: tile_loss.onset_s + tile_loss.onset_spread_s * rng.gen::<f64>(),
+                severity: tile_loss.severity_min
+                    + (tile_loss.severity_max - tile_loss.severity_min) * rng.gen::<f64>(),
+            })
+            .collect();
+        tile_loss_events.sort_by(|a, b| a.onset_s.total_cmp(&b.onset_s));
+
         Self {
             tile_loss_active: false,
+            tile_loss_severity: 0.0,
+            tile_loss_events,
+            descent_phase: DescentPhase::Entry,
+            landing: LandingTimeline::default(),
+            flip_start_q_bn: UnitQuaternion::identity(),
+        }
+    }
+
+    /// Activates every event whose onset is at or before `t_s`.
+    pub fn advance(&mut self, t_s: f64) {
+        let mut started = self
+            .tile_loss_events
+            .iter()
+            .filter(|event| t_s >= event.onset_s)
+            .peekable();
+        self.tile_loss_active = started.peek().is_some();
+        self.tile_loss_severity = started.map(|event| event.severity).sum();
+    }
+
+    /// Moves the descent into the belly-flop, flip and landing-burn phases
+    /// once their start conditions are met. A no-op while the landing phase
+    /// is disabled.
+    fn advance_descent(&mut self, cfg: &SimConfig, state: &TruthState, t_s: f64) {
+        if !cfg.landing.enabled {
+            return;
         }
+        match self.descent_phase {
+            DescentPhase::Entry if state.altitude_m() < cfg.blackout_lower_m => {
+                self.descent_phase = DescentPhase::BellyFlop;
+                self.landing.belly_flop_start_s = Some(t_s);
+            }
+            DescentPhase::BellyFlop if state.altitude_m() <= cfg.landing.flip_altitude_m => {
+                self.descent_phase = DescentPhase::Flip;
+                self.landing.flip_start_s = Some(t_s);
+                self.flip_start_q_bn = state.q_bn;
+            }
+            DescentPhase::Flip => {
+                let flip_start_s = self.landing.flip_start_s.unwrap_or(t_s);
+                if t_s - flip_start_s >= cfg.landing.flip_duration_s {
+                    self.descent_phase = DescentPhase::LandingBurn;
+                    self.landing.landing_burn_start_s = Some(t_s);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether attitude and translation are commanded rather than flown
+    /// through the entry aero model.
+    fn guided_phase(&self) -> bool {
+        matches!(
+            self.descent_phase,
+            DescentPhase::BellyFlop | DescentPhase::Flip | DescentPhase::LandingBurn
+        )
+    }
+}
+
+/// Whether the run has reached its end: touchdown when the landing phase is
+/// enabled, the re-entry cutoff altitude otherwise.
+pub fn descent_complete(cfg: &SimConfig, state: &TruthState, events: &ReentryEventState) -> bool {
+    if cfg.landing.enabled {
+        events.descent_phase == DescentPhase::Landed
+    } else {
+        state.altitude_m() <= REENTRY_CUTOFF_ALTITUDE_M
     }
 }
 
@@ -204,11 +355,12 @@ fn aerodynamic_sample(
     let transient_roll = smooth_pulse(t_s, 274.0, 12.0, 0.17);
     let transient_yaw = smooth_pulse(t_s, 283.0, 15.0, -0.12);
 
-    let asym_side = if events.tile_loss_active { 0.085 } else { 0.0 };
-    let asym_roll = if events.tile_loss_active { 0.065 } else { 0.0 };
-    let asym_yaw = if events.tile_loss_active { -0.045 } else { 0.0 };
+    let asym_side = 0.085 * events.tile_loss_severity;
+    let asym_roll = 0.065 * events.tile_loss_severity;
+    let asym_yaw = -0.045 * events.tile_loss_severity;
 
-    let cd = (0.92 + 0.75 * alpha.sin().abs() + 0.02 * (mach - 6.0).max(0.0).min(10.0)).clamp(0.5, 2.4);
+    let cd =
+        (0.92 + 0.75 * alpha.sin().abs() + 0.02 * (mach - 6.0).max(0.0).min(10.0)).clamp(0.5, 2.4);
     let cl = (1.45 * alpha.sin() + 0.22 * pitch_cmd).clamp(-1.2, 1.9);
     let cy = (-0.50 * beta + 0.10 * yaw_cmd + asym_side + 0.03 * transient_yaw).clamp(-0.7, 0.7);
 
@@ -216,18 +368,15 @@ fn aerodynamic_sample(
     let q_hat = state.omega_b_rps.y * params.ref_length_m / (2.0 * speed);
     let r_hat = state.omega_b_rps.z * params.ref_span_m / (2.0 * speed);
 
-    let c_roll = (-0.18 * beta - 0.62 * p_hat + 0.22 * bank_cmd + asym_roll + transient_roll).clamp(-0.65, 0.65);
-    let c_pitch = (-0.48 * (alpha - target_alpha) - 0.58 * q_hat + 0.48 * pitch_cmd + transient_pitch)
-        .clamp(-0.75, 0.75);
-    let c_yaw = (-0.24 * beta - 0.54 * r_hat + 0.42 * yaw_cmd + asym_yaw + transient_yaw).clamp(-0.65, 0.65);
-
-    let force_b = q_dyn
-        * params.ref_area_m2
-        * Vector3::new(
-            -cd,
-            cy,
-            cl,
-        );
+    let c_roll = (-0.18 * beta - 0.62 * p_hat + 0.22 * bank_cmd + asym_roll + transient_roll)
+        .clamp(-0.65, 0.65);
+    let c_pitch =
+        (-0.48 * (alpha - target_alpha) - 0.58 * q_hat + 0.48 * pitch_cmd + transient_pitch)
+            .clamp(-0.75, 0.75);
+    let c_yaw = (-0.24 * beta - 0.54 * r_hat + 0.42 * yaw_cmd + asym_yaw + transient_yaw)
+        .clamp(-0.65, 0.65);
+
+    let force_b = q_dyn * params.ref_area_m2 * Vector3::new(-cd, cy, cl);
     let mut moment_b = Vector3::new(
         q_dyn * params.ref_area_m2 * params.ref_span_m * c_roll,
         q_dyn * params.ref_area_m2 * params.ref_length_m * c_pitch,
@@ -249,6 +398,160 @@ fn aerodynamic_sample(
     }
 }
 
+/// Rotation of `q_bn` that carries its body x axis onto `target_n` with no
+/// roll about that axis.
+fn nose_toward(q_bn: UnitQuaternion<f64>, target_n: &Vector3<f64>) -> UnitQuaternion<f64> {
+    let nose_n = q_bn.transform_vector(&Vector3::x());
+    let rotation = UnitQuaternion::rotation_between(&nose_n, target_n)
+        .unwrap_or_else(|| UnitQuaternion::from_axis_angle(&Vector3::y_axis(), PI));
+    rotation * q_bn
+}
+
+/// Terminal fall speed of the vehicle broadside to the flow.
+fn belly_flop_terminal_speed(
+    mass_kg: f64,
+    params: &VehicleParams,
+    density_kg_m3: f64,
+    g: f64,
+) -> f64 {
+    (2.0 * mass_kg * g / (density_kg_m3 * BELLY_FLOP_CD * params.ref_area_m2)).sqrt()
+}
+
+/// Nav-frame specific force of the belly-flop fall.
+///
+/// The entry model leaves the blackout band at near-orbital speed, which no
+/// drag-only fall bleeds off before the ground. Like the blackout guidance
+/// shaping, the belly-flop instead relaxes the velocity toward a vertical sink
+/// that decays with altitude down to the sea-level terminal speed, within a
+/// bounded aerodynamic load that never pushes the vehicle down.
+fn belly_flop_specific_force_n(
+    state: &TruthState,
+    params: &VehicleParams,
+    atmosphere: AtmosphereSample,
+    g: f64,
+) -> Vector3<f64> {
+    let mass = state.mass_kg.max(params.dry_mass_kg);
+    let h = state.altitude_m();
+    let v_term = belly_flop_terminal_speed(mass, params, atmosphere.density_kg_m3, g);
+    let v_term_sea_level =
+        belly_flop_terminal_speed(mass, params, atmosphere_sample(0.0).density_kg_m3, g);
+    let sink = v_term.min((h / BELLY_FLOP_SINK_TIME_S).max(v_term_sea_level));
+
+    let accel = (Vector3::new(0.0, 0.0, -sink) - state.vel_n_mps) / BELLY_FLOP_TAU_S;
+    let vertical = (accel.z + g).clamp(0.0, BELLY_FLOP_MAX_LOAD_MPS2);
+    let mut horizontal = Vector3::new(accel.x, accel.y, 0.0);
+    let max_horizontal = (BELLY_FLOP_MAX_LOAD_MPS2.powi(2) - vertical.powi(2))
+        .max(0.0)
+        .sqrt();
+    if horizontal.norm() > max_horizontal {
+        horizontal *= max_horizontal / horizontal.norm();
+    }
+    horizontal + Vector3::new(0.0, 0.0, vertical)
+}
+
+/// Tail-first drag once the flip starts, in the nav frame.
+fn tail_first_drag_n(
+    state: &TruthState,
+    params: &VehicleParams,
+    atmosphere: AtmosphereSample,
+) -> Vector3<f64> {
+    let v_n = state.vel_n_mps;
+    let drag_n = -0.5
+        * atmosphere.density_kg_m3
+        * v_n.norm()
+        * v_n
+        * TAIL_FIRST_CD
+        * TAIL_FIRST_AREA_FRACTION
+        * params.ref_area_m2;
+    drag_n / state.mass_kg.max(params.dry_mass_kg)
+}
+
+/// Aero sample of the guided phases. The entry aero model does not cover
+/// them, so moments are dropped and attitude is commanded directly.
+fn guided_aero_sample(
+    state: &TruthState,
+    atmosphere: AtmosphereSample,
+    specific_force_n: Vector3<f64>,
+) -> AeroSample {
+    let speed = state.vel_n_mps.norm();
+    let v_b = state.q_bn.inverse_transform_vector(&state.vel_n_mps);
+
+    AeroSample {
+        specific_force_b_mps2: state.q_bn.inverse_transform_vector(&specific_force_n),
+        moment_b_nm: Vector3::zeros(),
+        dynamic_pressure_pa: 0.5 * atmosphere.density_kg_m3 * speed * speed,
+        mach: speed / atmosphere.sound_speed_mps.max(1.0),
+        alpha_deg: v_b.z.atan2(v_b.x).to_degrees(),
+        beta_deg: (v_b.y / speed.max(1.0))
+            .clamp(-1.0, 1.0)
+            .asin()
+            .to_degrees(),
+    }
+}
+
+/// Thrust magnitude [N] and commanded nose direction of the guided phases.
+///
+/// The belly-flop holds the nose level along its current heading. During
+/// the flip the engines hold minimum throttle while the nose follows a
+/// smoothstep rotation to vertical. The landing burn commands the constant
+/// deceleration that nulls the sink rate at the ground, `g + vz^2 / 2h`, plus
+/// damping of the horizontal velocity, with the thrust tilted at most
+/// `max_tilt_deg` from vertical.
+fn guided_command(
+    cfg: &SimConfig,
+    state: &TruthState,
+    events: &ReentryEventState,
+    g: f64,
+    t_s: f64,
+) -> (f64, Vector3<f64>) {
+    let landing = &cfg.landing;
+    let min_thrust = landing.min_throttle * landing.max_thrust_n;
+
+    match events.descent_phase {
+        DescentPhase::BellyFlop => {
+            let nose_n = state.q_bn.transform_vector(&Vector3::x());
+            let level = Vector3::new(nose_n.x, nose_n.y, 0.0);
+            let level = if level.norm() > 1.0e-6 {
+                level.normalize()
+            } else {
+                Vector3::x()
+            };
+            (0.0, level)
+        }
+        DescentPhase::Flip => {
+            let flip_start_s = events.landing.flip_start_s.unwrap_or(t_s);
+            let tau = ((t_s - flip_start_s) / landing.flip_duration_s).clamp(0.0, 1.0);
+            let smooth = tau * tau * (3.0 - 2.0 * tau);
+            let q_vertical = nose_toward(events.flip_start_q_bn, &Vector3::z());
+            let q_cmd = events.flip_start_q_bn.slerp(&q_vertical, smooth);
+            (min_thrust, q_cmd.transform_vector(&Vector3::x()))
+        }
+        DescentPhase::LandingBurn => {
+            let h = state.altitude_m().max(1.0);
+            let vz = state.vel_n_mps.z;
+            let a_up = if vz < 0.0 {
+                g + vz * vz / (2.0 * h)
+            } else {
+                0.0
+            };
+            let max_lateral = a_up.max(g) * landing.max_tilt_deg.to_radians().tan();
+            let mut a_lateral = -LANDING_BURN_LATERAL_GAIN
+                * Vector3::new(state.vel_n_mps.x, state.vel_n_mps.y, 0.0);
+            if a_lateral.norm() > max_lateral {
+                a_lateral *= max_lateral / a_lateral.norm();
+            }
+            let a_cmd = a_lateral + Vector3::new(0.0, 0.0, a_up.max(g));
+            let thrust = if a_up > 0.0 {
+                (state.mass_kg * a_cmd.norm()).clamp(min_thrust, landing.max_thrust_n)
+            } else {
+                min_thrust
+            };
+            (thrust, a_cmd.normalize())
+        }
+        DescentPhase::Entry | DescentPhase::Landed => (0.0, Vector3::x()),
+    }
+}
+
 pub fn truth_step(
     state: &mut TruthState,
     params: &VehicleParams,
@@ -257,16 +560,39 @@ pub fn truth_step(
     dt_s: f64,
     events: &mut ReentryEventState,
 ) -> TruthStepSample {
-    if t_s >= 320.0 {
-        events.tile_loss_active = true;
-    }
+    events.advance(t_s);
+    events.advance_descent(cfg, state, t_s);
+    let guided = events.guided_phase();
 
     let atmosphere = atmosphere_sample(state.altitude_m());
-    let aero = aerodynamic_sample(state, params, atmosphere, t_s, events);
-
     let g = gravity_mps2(state.altitude_m());
+    let aero = match events.descent_phase {
+        DescentPhase::BellyFlop => guided_aero_sample(
+            state,
+            atmosphere,
+            belly_flop_specific_force_n(state, params, atmosphere, g),
+        ),
+        DescentPhase::Flip | DescentPhase::LandingBurn => guided_aero_sample(
+            state,
+            atmosphere,
+            tail_first_drag_n(state, params, atmosphere),
+        ),
+        DescentPhase::Entry | DescentPhase::Landed => {
+            aerodynamic_sample(state, params, atmosphere, t_s, events)
+        }
+    };
+
+    let (thrust_n, nose_cmd_n) = if guided {
+        guided_command(cfg, state, events, g, t_s)
+    } else {
+        (0.0, Vector3::x())
+    };
+    // Engines fire along body +x (tail-first), i.e. out of the nose axis.
+    let specific_force_b_mps2 = aero.specific_force_b_mps2
+        + Vector3::x() * thrust_n / state.mass_kg.max(params.dry_mass_kg);
+
     let gravity_n = Vector3::new(0.0, 0.0, -g);
-    let acc_n = state.q_bn.transform_vector(&aero.specific_force_b_mps2) + gravity_n;
+    let acc_n = state.q_bn.transform_vector(&specific_force_b_mps2) + gravity_n;
 
     state.vel_n_mps += acc_n * dt_s;
 
@@ -284,17 +610,48 @@ pub fn truth_step(
     state.pos_n_m += state.vel_n_mps * dt_s;
     state.pos_n_m.z = state.pos_n_m.z.max(0.0);
 
-    let coriolis = state
-        .omega_b_rps
-        .cross(&(params.inertia_kgm2 * state.omega_b_rps));
-    let omega_dot = params.inertia_inv_kgm2 * (aero.moment_b_nm - coriolis);
-    state.omega_b_rps += omega_dot * dt_s;
-    state.omega_b_rps.x = state.omega_b_rps.x.clamp(-0.45, 0.45);
-    state.omega_b_rps.y = state.omega_b_rps.y.clamp(-0.50, 0.50);
-    state.omega_b_rps.z = state.omega_b_rps.z.clamp(-0.45, 0.45);
+    let omega_dot = if guided {
+        // Attitude follows the flip profile or slews toward the commanded nose
+        // direction; the body rate is whatever that motion implies.
+        let q_cmd = nose_toward(state.q_bn, &nose_cmd_n);
+        let q_next = if events.descent_phase != DescentPhase::Flip {
+            let angle = state.q_bn.angle_to(&q_cmd);
+            let max_step = GUIDED_MAX_SLEW_RPS * dt_s;
+            if angle > max_step {
+                state.q_bn.slerp(&q_cmd, max_step / angle)
+            } else {
+                q_cmd
+            }
+        } else {
+            q_cmd
+        };
+        let omega_next = (state.q_bn.inverse() * q_next).scaled_axis() / dt_s;
+        let omega_dot = (omega_next - state.omega_b_rps) / dt_s;
+        state.omega_b_rps = omega_next;
+        state.q_bn = q_next;
+        omega_dot
+    } else {
+        let coriolis = state
+            .omega_b_rps
+            .cross(&(params.inertia_kgm2 * state.omega_b_rps));
+        let omega_dot = params.inertia_inv_kgm2 * (aero.moment_b_nm - coriolis);
+        state.omega_b_rps += omega_dot * dt_s;
+        state.omega_b_rps.x = state.omega_b_rps.x.clamp(-0.45, 0.45);
+        state.omega_b_rps.y = state.omega_b_rps.y.clamp(-0.50, 0.50);
+        state.omega_b_rps.z = state.omega_b_rps.z.clamp(-0.45, 0.45);
+
+        let dq = UnitQuaternion::from_scaled_axis(state.omega_b_rps * dt_s);
+        state.q_bn *= dq;
+        omega_dot
+    };
 
-    let dq = UnitQuaternion::from_scaled_axis(state.omega_b_rps * dt_s);
-    state.q_bn *= dq;
+    if guided && state.pos_n_m.z <= 0.0 {
+        events.descent_phase = DescentPhase::Landed;
+        events.landing.touchdown_s = Some(t_s + dt_s);
+        events.landing.touchdown_speed_mps = Some(state.vel_n_mps.norm());
+        state.vel_n_mps = Vector3::zeros();
+        state.omega_b_rps = Vector3::zeros();
+    }
 
     // Sutton-Graves-like convective stagnation heating estimate.
     let speed = state.vel_n_mps.norm();
@@ -313,11 +670,14 @@ pub fn truth_step(
     let mass_dot = -1.1e-7 * heat_flux * params.ref_area_m2;
     state.mass_kg = (state.mass_kg + mass_dot * dt_s).max(params.dry_mass_kg);
 
-    let blackout = state.altitude_m() <= cfg.blackout_upper_m && state.altitude_m() >= cfg.blackout_lower_m;
+    let blackout =
+        state.altitude_m() <= cfg.blackout_upper_m && state.altitude_m() >= cfg.blackout_lower_m;
 
     TruthStepSample {
         atmosphere,
         aero,
+        specific_force_b_mps2,
+        thrust_n,
         angular_accel_b_rps2: omega_dot,
         heat_flux_w_m2: heat_flux,
         blackout,
@@ -0,0 +1,191 @@
+//! Re-running a finished run from the configuration recorded in its run directory.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde_json::Value;
+
+use crate::config::{DerivedValues, ResolvedConfig, SimConfig};
+use crate::output::{Summary, CONFIG_FILE, SUMMARY_FILE};
+use crate::run_simulation;
+
+/// Summary fields that are inputs or paths rather than results, and so are
+/// not compared
+const UNCOMPARED_SUMMARY_FIELDS: [&str; 2] = ["config", "outputs"];
+
+/// Outcome of [`replay_run`].
+#[derive(Debug, Clone)]
+pub struct ReplayReport {
+    /// Run directory that was replayed
+    pub source_dir: PathBuf,
+    /// Summary of the new run
+    pub summary: Summary,
+    /// Values outside the tolerance; empty when the replay reproduces the run
+    pub mismatches: Vec<SummaryMismatch>,
+}
+
+impl ReplayReport {
+    pub fn matches(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// One value that differs between the stored run and its replay.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SummaryMismatch {
+    /// Dotted JSON path, e.g. `dsfb.blackout.rmse_position_m`
+    pub path: String,
+    pub stored: Value,
+    pub replayed: Value,
+}
+
+/// Loads the configuration `run_dir` was flown with.
+///
+/// Reads `config.json`; run directories written before it existed fall back
+/// to the `config` recorded in `starship_summary.json`.
+pub fn load_run_config(run_dir: &Path) -> anyhow::Result<SimConfig> {
+    Ok(load_recorded_config(run_dir)?.0)
+}
+
+fn load_recorded_config(run_dir: &Path) -> anyhow::Result<(SimConfig, Option<DerivedValues>)> {
+    let config_path = run_dir.join(CONFIG_FILE);
+    if config_path.exists() {
+        let resolved: ResolvedConfig = read_json(&config_path)?;
+        return Ok((resolved.config, Some(resolved.derived)));
+    }
+
+    let summary: Value = read_json(&run_dir.join(SUMMARY_FILE))?;
+    let config = summary.get("config").cloned().with_context(|| {
+        format!(
+            "no {CONFIG_FILE} and no config in {SUMMARY_FILE} of {}",
+            run_dir.display()
+        )
+    })?;
+    Ok((serde_json::from_value(config)?, None))
+}
+
+/// Re-runs the configuration recorded in `run_dir` into a fresh run directory
+/// under `output_dir` and compares the new summary with the stored one.
+///
+/// Numbers match when they differ by at most `tolerance` times their
+/// magnitude (absolutely, for magnitudes below 1); all other values must be
+/// equal. The summary's `config` and `outputs` are not compared, nor are
+/// fields the stored summary predates. When `config.json` records derived
+/// values, they are recomputed and compared under `derived.*` as well.
+pub fn replay_run(
+    run_dir: &Path,
+    output_dir: &Path,
+    tolerance: f64,
+) -> anyhow::Result<ReplayReport> {
+    anyhow::ensure!(
+        tolerance.is_finite() && tolerance >= 0.0,
+        "replay tolerance must be finite and >= 0"
+    );
+    let (config, derived) = load_recorded_config(run_dir)?;
+    config
+        .validate()
+        .with_context(|| format!("invalid recorded config in {}", run_dir.display()))?;
+    let stored: Value = read_json(&run_dir.join(SUMMARY_FILE))?;
+
+    let summary = run_simulation(&config, output_dir)?;
+
+    let mut mismatches = Vec::new();
+    if let Some(derived) = derived {
+        compare_values(
+            "derived",
+            &serde_json::to_value(&derived)?,
+            &serde_json::to_value(&config.resolved().derived)?,
+            tolerance,
+            &mut mismatches,
+        );
+    }
+    mismatches.extend(compare_summaries(
+        &stored,
+        &serde_json::to_value(&summary)?,
+        tolerance,
+    ));
+
+    Ok(ReplayReport {
+        source_dir: run_dir.to_path_buf(),
+        summary,
+        mismatches,
+    })
+}
+
+/// Compares two serialized summaries field by field, skipping `config` and
+/// `outputs`.
+pub fn compare_summaries(stored: &Value, replayed: &Value, tolerance: f64) -> Vec<SummaryMismatch> {
+    let compared = |summary: &Value| {
+        let mut summary = summary.clone();
+        if let Value::Object(fields) = &mut summary {
+            for field in UNCOMPARED_SUMMARY_FIELDS {
+                fields.remove(field);
+            }
+        }
+        summary
+    };
+    let mut mismatches = Vec::new();
+    compare_values(
+        "",
+        &compared(stored),
+        &compared(replayed),
+        tolerance,
+        &mut mismatches,
+    );
+    mismatches
+}
+
+fn compare_values(
+    path: &str,
+    stored: &Value,
+    replayed: &Value,
+    tolerance: f64,
+    mismatches: &mut Vec<SummaryMismatch>,
+) {
+    let child = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{path}.{key}")
+        }
+    };
+    let matches = match (stored, replayed) {
+        (Value::Number(a), Value::Number(b)) => match (a.as_f64(), b.as_f64()) {
+            (Some(a), Some(b)) => (a - b).abs() <= tolerance * a.abs().max(b.abs()).max(1.0),
+            _ => a == b,
+        },
+        (Value::Array(a), Value::Array(b)) if a.len() == b.len() => {
+            for (idx, (a, b)) in a.iter().zip(b).enumerate() {
+                compare_values(&child(&idx.to_string()), a, b, tolerance, mismatches);
+            }
+            true
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            for key in a.keys() {
+                compare_values(
+                    &child(key),
+                    a.get(key).unwrap_or(&Value::Null),
+                    b.get(key).unwrap_or(&Value::Null),
+                    tolerance,
+                    mismatches,
+                );
+            }
+            true
+        }
+        (a, b) => a == b,
+    };
+    if !matches {
+        mismatches.push(SummaryMismatch {
+            path: path.to_string(),
+            stored: stored.clone(),
+            replayed: replayed.clone(),
+        });
+    }
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> anyhow::Result<T> {
+    let raw =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    serde_json::from_str(&raw).with_context(|| format!("failed to parse {}", path.display()))
+}
@@ -0,0 +1,147 @@
+//! Post-run physics sanity checks on the truth trajectory.
+//!
+//! A configuration can fly to completion while the truth model leaves the
+//! regime it is meant for (dynamic pressure far beyond any airframe, a heat
+//! shield pinned at the thermal model's clamp, a vehicle climbing out of an
+//! unguided entry). Estimator results from such a run are not meaningful, so
+//! every run checks its truth samples against [`SanityConfig`] and lists the
+//! violations in the summary.
+
+use serde::Serialize;
+
+use crate::config::SanityConfig;
+
+/// One physics quantity checked on every truth sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SanityCheck {
+    /// Dynamic pressure above `max_dynamic_pressure_pa`
+    DynamicPressure,
+    /// Heat flux above `max_heat_flux_w_m2`
+    HeatFlux,
+    /// Heat-shield temperature outside `[min_heat_shield_temp_k, max_heat_shield_temp_k]`
+    HeatShieldTemperature,
+    /// Climb of more than `max_altitude_rise_m` in an unguided segment
+    AltitudeRise,
+}
+
+/// All samples of a run that failed one check.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SanityViolation {
+    pub check: SanityCheck,
+    /// Number of failing samples
+    pub samples: usize,
+    pub first_time_s: f64,
+    pub last_time_s: f64,
+    /// Value furthest outside the bound; NaN if only non-finite values failed
+    pub worst_value: f64,
+    /// The bound that `worst_value` violates
+    pub limit: f64,
+}
+
+/// Accumulates sanity violations over the samples of one run.
+#[derive(Debug, Clone)]
+pub struct SanityMonitor {
+    config: SanityConfig,
+    violations: Vec<SanityViolation>,
+    /// Lowest altitude of the current unguided segment
+    segment_min_altitude_m: Option<f64>,
+}
+
+impl SanityMonitor {
+    pub fn new(config: &SanityConfig) -> Self {
+        Self {
+            config: config.clone(),
+            violations: Vec::new(),
+            segment_min_altitude_m: None,
+        }
+    }
+
+    /// Checks one truth sample. `guided` marks samples whose trajectory is
+    /// shaped by guidance (blackout band, belly-flop, flip, landing burn), for
+    /// which the altitude-rise check is skipped.
+    pub fn observe(
+        &mut self,
+        time_s: f64,
+        altitude_m: f64,
+        dynamic_pressure_pa: f64,
+        heat_flux_w_m2: f64,
+        heat_shield_temp_k: f64,
+        guided: bool,
+    ) {
+        let cfg = &self.config;
+        let (q_max, flux_max) = (cfg.max_dynamic_pressure_pa, cfg.max_heat_flux_w_m2);
+        let (temp_min, temp_max) = (cfg.min_heat_shield_temp_k, cfg.max_heat_shield_temp_k);
+        let rise_max = cfg.max_altitude_rise_m;
+
+        self.check_max(
+            SanityCheck::DynamicPressure,
+            time_s,
+            dynamic_pressure_pa,
+            q_max,
+        );
+        self.check_max(SanityCheck::HeatFlux, time_s, heat_flux_w_m2, flux_max);
+        if heat_shield_temp_k < temp_min {
+            self.record(
+                SanityCheck::HeatShieldTemperature,
+                time_s,
+                heat_shield_temp_k,
+                temp_min,
+            );
+        } else {
+            self.check_max(
+                SanityCheck::HeatShieldTemperature,
+                time_s,
+                heat_shield_temp_k,
+                temp_max,
+            );
+        }
+
+        if guided {
+            self.segment_min_altitude_m = None;
+            return;
+        }
+        let min_altitude = self
+            .segment_min_altitude_m
+            .map_or(altitude_m, |m| m.min(altitude_m));
+        self.segment_min_altitude_m = Some(min_altitude);
+        let rise = altitude_m - min_altitude;
+        if rise > rise_max || !altitude_m.is_finite() {
+            self.record(SanityCheck::AltitudeRise, time_s, rise, rise_max);
+        }
+    }
+
+    /// Violations in order of their first occurrence.
+    pub fn finish(self) -> Vec<SanityViolation> {
+        self.violations
+    }
+
+    fn check_max(&mut self, check: SanityCheck, time_s: f64, value: f64, limit: f64) {
+        if value.is_nan() || value > limit {
+            self.record(check, time_s, value, limit);
+        }
+    }
+
+    fn record(&mut self, check: SanityCheck, time_s: f64, value: f64, limit: f64) {
+        let value = if value.is_finite() { value } else { f64::NAN };
+        match self.violations.iter_mut().find(|v| v.check == check) {
+            Some(violation) => {
+                violation.samples += 1;
+                violation.last_time_s = time_s;
+                let worse = (value - limit).abs() > (violation.worst_value - violation.limit).abs();
+                if violation.worst_value.is_nan() || worse {
+                    violation.worst_value = value;
+                    violation.limit = limit;
+                }
+            }
+            None => self.violations.push(SanityViolation {
+                check,
+                samples: 1,
+                first_time_s: time_s,
+                last_time_s: time_s,
+                worst_value: value,
+                limit,
+            }),
+        }
+    }
+}
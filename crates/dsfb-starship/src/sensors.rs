@@ -5,6 +5,8 @@ use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 use rand_distr::StandardNormal;
 
+use crate::config::{FusionJitterConfig, ImuLimitsConfig, JitterDistribution, SimConfig};
+use crate::output::FusionJitterMetrics;
 use crate::physics::ReentryEventState;
 
 #[derive(Debug, Clone, Copy)]
@@ -23,11 +25,61 @@ struct ImuChannel {
     gyro_noise_std: f64,
     accel_thermal_coeff: Vector3<f64>,
     gyro_thermal_coeff: Vector3<f64>,
+    /// Fractional noise growth per kelvin above the 320 K reference
+    thermal_noise_sensitivity: f64,
+    /// Bias growth per unit of accumulated thermal dose [K s]
+    accel_thermal_dose_coeff: Vector3<f64>,
+    gyro_thermal_dose_coeff: Vector3<f64>,
+    /// Full-scale range per axis [m/s^2]
+    accel_range_mps2: f64,
+    /// Full-scale range per axis [rad/s]
+    gyro_range_rps: f64,
+    /// Output resolution [m/s^2]; 0 disables quantization
+    accel_lsb_mps2: f64,
+    /// Output resolution [rad/s]; 0 disables quantization
+    gyro_lsb_rps: f64,
+}
+
+impl ImuChannel {
+    /// Quantizes and saturates a raw reading; the flag is set when any axis
+    /// hit its range.
+    fn digitize(
+        &self,
+        accel_b_mps2: Vector3<f64>,
+        gyro_b_rps: Vector3<f64>,
+    ) -> (ImuMeasurement, bool) {
+        let (accel_b_mps2, accel_saturated) =
+            digitize_axes(accel_b_mps2, self.accel_lsb_mps2, self.accel_range_mps2);
+        let (gyro_b_rps, gyro_saturated) =
+            digitize_axes(gyro_b_rps, self.gyro_lsb_rps, self.gyro_range_rps);
+        (
+            ImuMeasurement {
+                accel_b_mps2,
+                gyro_b_rps,
+            },
+            accel_saturated || gyro_saturated,
+        )
+    }
+}
+
+fn digitize_axes(value: Vector3<f64>, lsb: f64, range: f64) -> (Vector3<f64>, bool) {
+    let quantized = if lsb > 0.0 {
+        value.map(|v| (v / lsb).round() * lsb)
+    } else {
+        value
+    };
+    let saturated = quantized.iter().any(|v| v.abs() > range);
+    (quantized.map(|v| v.clamp(-range, range)), saturated)
 }
 
 pub struct ImuArray {
     channels: Vec<ImuChannel>,
     rng: ChaCha8Rng,
+    thermal_degradation: f64,
+    thermal_dose_k_s: f64,
+    last_t_s: Option<f64>,
+    /// Per channel, the number of readings with at least one saturated axis
+    saturated_counts: Vec<usize>,
 }
 
 impl ImuArray {
@@ -37,6 +89,10 @@ impl ImuArray {
 
         for idx in 0..count {
             let channel_scale = 1.0 + 0.11 * idx as f64;
+            // Higher-index units sit closer to the heat shield and degrade faster;
+            // mounting orientation alternates the sign of the thermal bias.
+            let thermal_scale = 1.0 + 0.8 * idx as f64;
+            let thermal_sign = if idx % 2 == 0 { -1.0 } else { 1.0 };
             let accel_bias0 = Vector3::new(
                 0.03 * channel_scale,
                 -0.02 * channel_scale,
@@ -68,10 +124,57 @@ impl ImuArray {
                 gyro_noise_std: 0.0012 + 0.0003 * idx as f64,
                 accel_thermal_coeff: Vector3::new(4.0e-4, -2.5e-4, 6.0e-4),
                 gyro_thermal_coeff: Vector3::new(4.0e-6, -2.2e-6, 3.0e-6),
+                thermal_noise_sensitivity: 0.04 * thermal_scale,
+                accel_thermal_dose_coeff: Vector3::new(1.5e-4, -1.0e-4, 2.0e-4)
+                    * (thermal_sign * thermal_scale),
+                gyro_thermal_dose_coeff: Vector3::new(1.5e-6, -1.2e-6, 1.0e-6)
+                    * (thermal_sign * thermal_scale),
+                accel_range_mps2: f64::INFINITY,
+                gyro_range_rps: f64::INFINITY,
+                accel_lsb_mps2: 0.0,
+                gyro_lsb_rps: 0.0,
             });
         }
 
-        Self { channels, rng }
+        Self {
+            channels,
+            rng,
+            thermal_degradation: 1.0,
+            thermal_dose_k_s: 0.0,
+            last_t_s: None,
+            saturated_counts: vec![0; count],
+        }
+    }
+
+    /// The IMU array of a run of `cfg`: its seed, unit count, thermal
+    /// degradation, and output limits.
+    pub fn from_config(cfg: &SimConfig) -> Self {
+        Self::new(cfg.seed, cfg.imu_count)
+            .with_thermal_degradation(cfg.imu_thermal_degradation)
+            .with_limits(&cfg.imu_limits)
+    }
+
+    /// Scales the heating-driven noise and bias growth; `0.0` disables it.
+    pub fn with_thermal_degradation(mut self, gain: f64) -> Self {
+        self.thermal_degradation = gain;
+        self
+    }
+
+    /// Applies the same output range and resolution to every channel;
+    /// without it the outputs are unbounded and continuous.
+    pub fn with_limits(mut self, limits: &ImuLimitsConfig) -> Self {
+        for channel in &mut self.channels {
+            channel.accel_range_mps2 = limits.accel_range_mps2;
+            channel.gyro_range_rps = limits.gyro_range_rps;
+            channel.accel_lsb_mps2 = limits.accel_lsb_mps2;
+            channel.gyro_lsb_rps = limits.gyro_lsb_rps;
+        }
+        self
+    }
+
+    /// Per channel, how many readings so far had at least one saturated axis.
+    pub fn saturated_counts(&self) -> &[usize] {
+        &self.saturated_counts
     }
 
     pub fn len(&self) -> usize {
@@ -87,35 +190,51 @@ impl ImuArray {
         events: &ReentryEventState,
     ) -> Vec<ImuMeasurement> {
         let mut out = Vec::with_capacity(self.channels.len());
+        let thermal_delta = (heat_shield_temp_k - 320.0).max(0.0);
+
+        // Accumulated heating drives a slow, persistent bias growth.
+        let dt_s = self.last_t_s.map_or(0.0, |last| (t_s - last).max(0.0));
+        self.thermal_dose_k_s += thermal_delta * dt_s;
+        self.last_t_s = Some(t_s);
+        let dose = self.thermal_degradation * self.thermal_dose_k_s;
 
         for idx in 0..self.channels.len() {
             let channel = self.channels[idx].clone();
-            let thermal_delta = (heat_shield_temp_k - 320.0).max(0.0);
+            let noise_scale =
+                1.0 + self.thermal_degradation * channel.thermal_noise_sensitivity * thermal_delta;
 
             let accel_bias = channel.accel_bias0
                 + channel.accel_drift_rate * t_s
-                + channel.accel_thermal_coeff * thermal_delta;
+                + channel.accel_thermal_coeff * thermal_delta
+                + channel.accel_thermal_dose_coeff * dose;
             let gyro_bias = channel.gyro_bias0
                 + channel.gyro_drift_rate * t_s
-                + channel.gyro_thermal_coeff * thermal_delta;
+                + channel.gyro_thermal_coeff * thermal_delta
+                + channel.gyro_thermal_dose_coeff * dose;
 
+            let accel_std = channel.accel_noise_std * noise_scale;
+            let gyro_std = channel.gyro_noise_std * noise_scale;
             let accel_noise = Vector3::new(
-                self.gaussian(channel.accel_noise_std),
-                self.gaussian(channel.accel_noise_std),
-                self.gaussian(channel.accel_noise_std),
+                self.gaussian(accel_std),
+                self.gaussian(accel_std),
+                self.gaussian(accel_std),
             );
             let gyro_noise = Vector3::new(
-                self.gaussian(channel.gyro_noise_std),
-                self.gaussian(channel.gyro_noise_std),
-                self.gaussian(channel.gyro_noise_std),
+                self.gaussian(gyro_std),
+                self.gaussian(gyro_std),
+                self.gaussian(gyro_std),
             );
 
             let (accel_fault, gyro_fault) = fault_terms(idx, t_s, events);
 
-            out.push(ImuMeasurement {
-                accel_b_mps2: true_specific_force_b_mps2 + accel_bias + accel_noise + accel_fault,
-                gyro_b_rps: true_gyro_b_rps + gyro_bias + gyro_noise + gyro_fault,
-            });
+            let (measurement, saturated) = channel.digitize(
+                true_specific_force_b_mps2 + accel_bias + accel_noise + accel_fault,
+                true_gyro_b_rps + gyro_bias + gyro_noise + gyro_fault,
+            );
+            if saturated {
+                self.saturated_counts[idx] += 1;
+            }
+            out.push(measurement);
         }
 
         out
@@ -165,3 +284,158 @@ fn fault_terms(idx: usize, t_s: f64, events: &ReentryEventState) -> (Vector3<f64
 
     (accel_fault, gyro_fault)
 }
+
+#[derive(Debug, Clone, Copy)]
+pub struct GnssFix {
+    pub pos_n_m: Vector3<f64>,
+    pub vel_n_mps: Vector3<f64>,
+}
+
+#[derive(Debug, Clone)]
+struct GnssReceiver {
+    pos_noise_std: Vector3<f64>,
+    vel_noise_std: Vector3<f64>,
+}
+
+pub struct GnssArray {
+    receivers: Vec<GnssReceiver>,
+    rng: ChaCha8Rng,
+}
+
+impl GnssArray {
+    pub fn new(seed: u64, count: usize) -> Self {
+        let rng = ChaCha8Rng::seed_from_u64(seed ^ 0xCAB00D1E_u64);
+        let receivers = (0..count)
+            .map(|idx| {
+                // Independent receivers/constellations with progressively poorer geometry.
+                let scale = 1.0 + 0.3 * idx as f64;
+                GnssReceiver {
+                    pos_noise_std: Vector3::new(5.5, 5.5, 7.0) * scale,
+                    vel_noise_std: Vector3::new(0.75, 0.75, 0.90) * scale,
+                }
+            })
+            .collect();
+
+        Self { receivers, rng }
+    }
+
+    pub fn measure(
+        &mut self,
+        true_pos_n_m: Vector3<f64>,
+        true_vel_n_mps: Vector3<f64>,
+        t_s: f64,
+    ) -> Vec<GnssFix> {
+        let mut out = Vec::with_capacity(self.receivers.len());
+
+        for idx in 0..self.receivers.len() {
+            let receiver = self.receivers[idx].clone();
+            let pos_noise = Vector3::new(
+                self.gaussian(receiver.pos_noise_std.x),
+                self.gaussian(receiver.pos_noise_std.y),
+                self.gaussian(receiver.pos_noise_std.z),
+            );
+            let vel_noise = Vector3::new(
+                self.gaussian(receiver.vel_noise_std.x),
+                self.gaussian(receiver.vel_noise_std.y),
+                self.gaussian(receiver.vel_noise_std.z),
+            );
+            let (pos_spoof, vel_spoof) = gnss_spoof_terms(idx, t_s);
+
+            out.push(GnssFix {
+                pos_n_m: true_pos_n_m + pos_noise + pos_spoof,
+                vel_n_mps: true_vel_n_mps + vel_noise + vel_spoof,
+            });
+        }
+
+        out
+    }
+
+    fn gaussian(&mut self, sigma: f64) -> f64 {
+        let z: f64 = self.rng.sample(StandardNormal);
+        sigma * z
+    }
+}
+
+fn gnss_spoof_terms(idx: usize, t_s: f64) -> (Vector3<f64>, Vector3<f64>) {
+    // Receiver 1 is captured by a slow, self-consistent ramp spoof: the position
+    // offset grows at a constant rate and the velocity carries that same rate.
+    const SPOOF_START_S: f64 = 10.0;
+
+    if idx != 1 || t_s < SPOOF_START_S {
+        return (Vector3::zeros(), Vector3::zeros());
+    }
+
+    let ramp_rate_mps = Vector3::new(2.4, -1.5, 0.8);
+    (ramp_rate_mps * (t_s - SPOOF_START_S), ramp_rate_mps)
+}
+
+/// Release times of the flight computer's fusion task.
+///
+/// Draws one release offset per update from the configured distribution and
+/// reports the interval the task measures since its previous release. With
+/// jitter disabled every interval is exactly `dt` and no draws are made.
+pub struct FusionClock {
+    config: FusionJitterConfig,
+    dt_s: f64,
+    rng: ChaCha8Rng,
+    prev_offset_s: f64,
+    updates: usize,
+    min_interval_s: f64,
+    max_interval_s: f64,
+    sum_sq_deviation_s2: f64,
+}
+
+impl FusionClock {
+    pub fn new(config: &FusionJitterConfig, sim_seed: u64, dt_s: f64) -> Self {
+        Self {
+            config: config.clone(),
+            dt_s,
+            rng: ChaCha8Rng::seed_from_u64(config.effective_seed(sim_seed)),
+            prev_offset_s: 0.0,
+            updates: 0,
+            min_interval_s: f64::INFINITY,
+            max_interval_s: 0.0,
+            sum_sq_deviation_s2: 0.0,
+        }
+    }
+
+    /// Interval [s] between the previous fusion update and the next one.
+    pub fn next_interval(&mut self) -> f64 {
+        let interval = if self.config.enabled() {
+            let offset = self.draw_offset();
+            let interval = self.dt_s + offset - self.prev_offset_s;
+            self.prev_offset_s = offset;
+            interval
+        } else {
+            self.dt_s
+        };
+
+        self.updates += 1;
+        self.min_interval_s = self.min_interval_s.min(interval);
+        self.max_interval_s = self.max_interval_s.max(interval);
+        self.sum_sq_deviation_s2 += (interval - self.dt_s).powi(2);
+        interval
+    }
+
+    /// Realized interval statistics; `None` when jitter is disabled.
+    pub fn metrics(&self) -> Option<FusionJitterMetrics> {
+        (self.config.enabled() && self.updates > 0).then(|| FusionJitterMetrics {
+            updates: self.updates,
+            min_interval_s: self.min_interval_s,
+            max_interval_s: self.max_interval_s,
+            rms_interval_deviation_s: (self.sum_sq_deviation_s2 / self.updates as f64).sqrt(),
+        })
+    }
+
+    fn draw_offset(&mut self) -> f64 {
+        let magnitude = self.config.magnitude_s;
+        match self.config.distribution {
+            JitterDistribution::None => 0.0,
+            JitterDistribution::Uniform => magnitude * (2.0 * self.rng.gen::<f64>() - 1.0),
+            JitterDistribution::Gaussian => {
+                let z: f64 = self.rng.sample(StandardNormal);
+                magnitude * z.clamp(-3.0, 3.0)
+            }
+        }
+    }
+}
@@ -0,0 +1,333 @@
+//! End-to-end run of the re-entry simulation over 20 steps, checking every
+//! output file, the telemetry schema, and that the summary metrics agree with
+//! the telemetry they were computed from.
+
+use std::fs;
+use std::path::PathBuf;
+
+use dsfb_starship::config::{JitterDistribution, SimConfig};
+use dsfb_starship::replay::replay_run;
+use dsfb_starship::run_simulation;
+use dsfb_starship::sanity::SanityCheck;
+use serde_json::Value;
+
+const STEPS: usize = 20;
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dsfb-starship-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn tiny_config() -> SimConfig {
+    let mut cfg = SimConfig::default();
+    cfg.t_final = STEPS as f64 * cfg.dt;
+    cfg
+}
+
+fn column(header: &csv::StringRecord, name: &str) -> usize {
+    header
+        .iter()
+        .position(|h| h == name)
+        .unwrap_or_else(|| panic!("telemetry lacks column {name}"))
+}
+
+fn rmse(values: &[f64]) -> f64 {
+    (values.iter().map(|v| v * v).sum::<f64>() / values.len() as f64).sqrt()
+}
+
+#[test]
+fn simulation_writes_every_output_consistent_with_its_summary() {
+    let scratch = scratch_dir("pipeline");
+    let cfg = tiny_config();
+    let summary = run_simulation(&cfg, &scratch).expect("simulation runs");
+    let outputs = &summary.outputs;
+
+    assert!(outputs.output_dir.starts_with(&scratch));
+    for path in [
+        &outputs.csv_path,
+        &outputs.summary_path,
+        &outputs.config_path,
+        &outputs.plot_altitude_path,
+        &outputs.plot_error_path,
+        &outputs.plot_trust_path,
+    ] {
+        assert!(path.is_file(), "missing {}", path.display());
+        assert_eq!(path.parent(), Some(outputs.output_dir.as_path()));
+    }
+    assert_eq!(
+        outputs.plot_blackout_path.is_some(),
+        summary.blackout_start_s.is_some()
+    );
+
+    let mut reader = csv::Reader::from_path(&outputs.csv_path).expect("telemetry opens");
+    let header = reader.headers().expect("telemetry header").clone();
+    let rows: Vec<csv::StringRecord> = reader.records().map(|r| r.expect("record")).collect();
+    assert_eq!(rows.len(), summary.output_samples);
+    assert_eq!(summary.samples, cfg.steps());
+    assert_eq!(summary.output_samples, summary.samples);
+
+    let value = |row: &csv::StringRecord, name: &str| -> f64 {
+        row[column(&header, name)].parse().expect("numeric cell")
+    };
+    for method in ["inertial", "ekf", "ekf_inflated", "dsfb"] {
+        for suffix in ["pos_err_m", "vel_err_mps", "att_err_deg"] {
+            column(&header, &format!("{method}_{suffix}"));
+        }
+    }
+
+    // Normalized IMU trust weights stay a convex combination.
+    for row in &rows {
+        let total: f64 = (0..3)
+            .map(|k| value(row, &format!("dsfb_trust_imu{k}")))
+            .sum();
+        assert!((total - 1.0).abs() < 1e-6, "IMU trust sums to {total}");
+    }
+
+    let pos_err: Vec<f64> = rows.iter().map(|r| value(r, "dsfb_pos_err_m")).collect();
+    let vel_err: Vec<f64> = rows.iter().map(|r| value(r, "dsfb_vel_err_mps")).collect();
+    assert!((rmse(&pos_err) - summary.dsfb.rmse_position_m).abs() < 1e-6);
+    assert!((rmse(&vel_err) - summary.dsfb.rmse_velocity_mps).abs() < 1e-6);
+    assert!((pos_err[pos_err.len() - 1] - summary.dsfb.final_position_error_m).abs() < 1e-6);
+
+    let stored: Value =
+        serde_json::from_str(&fs::read_to_string(&outputs.summary_path).unwrap()).unwrap();
+    for key in [
+        "inertial",
+        "ekf",
+        "ekf_inflated",
+        "dsfb",
+        "thermal",
+        "outputs",
+    ] {
+        assert!(stored.get(key).is_some(), "summary lacks {key}");
+    }
+    assert_eq!(stored["samples"], summary.samples);
+    let config: Value =
+        serde_json::from_str(&fs::read_to_string(&outputs.config_path).unwrap()).unwrap();
+    assert_eq!(config["t_final"], cfg.t_final);
+    assert_eq!(config["derived"]["steps"], cfg.steps());
+
+    let report =
+        replay_run(&outputs.output_dir, &scratch.join("replay"), 1e-9).expect("replay runs");
+    assert!(
+        report.matches(),
+        "replay mismatches: {:?}",
+        report.mismatches
+    );
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn fusion_jitter_is_seeded_and_reported() {
+    let scratch = scratch_dir("jitter");
+    let mut cfg = tiny_config();
+    let nominal = run_simulation(&cfg, &scratch.join("nominal")).expect("simulation runs");
+    assert!(nominal.fusion_jitter.is_none());
+
+    cfg.fusion_jitter.distribution = JitterDistribution::Uniform;
+    cfg.fusion_jitter.magnitude_s = 0.25 * cfg.dt;
+    let jittered = run_simulation(&cfg, &scratch.join("jittered")).expect("simulation runs");
+    let jitter = jittered.fusion_jitter.as_ref().expect("jitter metrics");
+    assert_eq!(jitter.updates, jittered.samples);
+    assert!(jitter.min_interval_s >= 0.5 * cfg.dt && jitter.max_interval_s <= 1.5 * cfg.dt);
+    assert!(jitter.rms_interval_deviation_s > 0.0);
+    assert_ne!(jittered.dsfb.rmse_position_m, nominal.dsfb.rmse_position_m);
+
+    let report = replay_run(&jittered.outputs.output_dir, &scratch.join("replay"), 1e-9)
+        .expect("replay runs");
+    assert!(
+        report.matches(),
+        "replay mismatches: {:?}",
+        report.mismatches
+    );
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn sanity_checks_report_runs_outside_the_envelope() {
+    let scratch = scratch_dir("sanity");
+    let mut cfg = tiny_config();
+    let nominal = run_simulation(&cfg, &scratch.join("nominal")).expect("simulation runs");
+    assert!(nominal.sanity_violations.is_empty());
+
+    cfg.sanity.max_heat_flux_w_m2 = 1.0;
+    cfg.sanity.min_heat_shield_temp_k = 400.0;
+    let tight = run_simulation(&cfg, &scratch.join("tight")).expect("simulation runs");
+    let checks: Vec<SanityCheck> = tight.sanity_violations.iter().map(|v| v.check).collect();
+    assert_eq!(
+        checks,
+        vec![SanityCheck::HeatFlux, SanityCheck::HeatShieldTemperature]
+    );
+    for violation in &tight.sanity_violations {
+        assert_eq!(violation.samples, tight.samples);
+        assert_eq!(violation.first_time_s, 0.0);
+    }
+    let temp = &tight.sanity_violations[1];
+    assert_eq!(temp.limit, 400.0);
+    assert!(temp.worst_value < 400.0);
+
+    let stored: Value =
+        serde_json::from_str(&fs::read_to_string(&tight.outputs.summary_path).unwrap()).unwrap();
+    assert_eq!(stored["sanity_violations"][0]["check"], "heat_flux");
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn config_dir_rejects_overrides_that_invalidate_a_config() {
+    let scratch = scratch_dir("config-dir-overrides");
+    let configs = scratch.join("configs");
+    fs::create_dir_all(&configs).expect("config dir");
+    let raw = serde_json::to_string(&tiny_config()).expect("config serializes");
+    fs::write(configs.join("tiny.json"), raw).expect("config written");
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_dsfb-starship"))
+        .arg("--configs")
+        .arg(&configs)
+        .arg("--rho")
+        .arg("1.5")
+        .arg("--output")
+        .arg(scratch.join("out"))
+        .output()
+        .expect("binary runs");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("invalid config tiny after overrides"),
+        "{stderr}"
+    );
+    assert!(stderr.contains("rho must be in (0, 1)"), "{stderr}");
+    assert!(!scratch.join("out").exists());
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn inflated_ekf_trusts_gnss_more_after_inflated_propagation() {
+    use dsfb_starship::estimators::{NavState, SimpleEkf};
+    use nalgebra::{UnitQuaternion, Vector3};
+
+    let nav = NavState {
+        pos_n_m: Vector3::new(0.0, 0.0, 50_000.0),
+        vel_n_mps: Vector3::new(1_000.0, 0.0, -100.0),
+        q_bn: UnitQuaternion::identity(),
+        omega_b_rps: Vector3::zeros(),
+    };
+    let fix_after = |scale: f64| {
+        let mut ekf = SimpleEkf::new(nav.clone());
+        ekf.set_process_noise_scale(scale);
+        for _ in 0..10 {
+            ekf.propagate(Vector3::zeros(), Vector3::zeros(), 0.1);
+        }
+        let predicted = ekf.nav.pos_n_m;
+        let offset = Vector3::new(100.0, 0.0, 0.0);
+        ekf.update_gnss(predicted + offset, ekf.nav.vel_n_mps);
+        (ekf.nav.pos_n_m - predicted).x / offset.x
+    };
+
+    // The fraction of a GNSS innovation taken grows with the inflation, and a
+    // scale of 1 is the nominal filter
+    let nominal = fix_after(1.0);
+    let inflated = fix_after(20.0);
+    assert!(nominal > 0.0 && nominal < 1.0, "{nominal}");
+    assert!(
+        inflated > nominal && inflated < 1.0,
+        "{inflated} vs {nominal}"
+    );
+    assert_eq!(fix_after(1.0), nominal);
+}
+
+#[test]
+fn unit_blackout_inflation_reproduces_the_ekf() {
+    let scratch = scratch_dir("ekf-inflation");
+    let mut cfg = tiny_config();
+    cfg.ekf_blackout_q_inflation = 1.0;
+    let summary = run_simulation(&cfg, &scratch).expect("simulation runs");
+    assert_eq!(
+        summary.ekf_inflated.rmse_position_m,
+        summary.ekf.rmse_position_m
+    );
+    assert_eq!(
+        summary.ekf_inflated.rmse_velocity_mps,
+        summary.ekf.rmse_velocity_mps
+    );
+
+    cfg.ekf_blackout_q_inflation = 0.5;
+    let err = cfg.validate().unwrap_err();
+    assert!(
+        err.to_string().contains("ekf_blackout_q_inflation"),
+        "{err}"
+    );
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn gnss_trust_columns_follow_the_receiver_count() {
+    let scratch = scratch_dir("gnss-columns");
+    for receivers in [1, 4] {
+        let mut cfg = tiny_config();
+        cfg.gnss_count = receivers;
+        cfg.export_attitude = true;
+        let summary =
+            run_simulation(&cfg, &scratch.join(receivers.to_string())).expect("simulation runs");
+
+        let mut reader =
+            csv::Reader::from_path(&summary.outputs.csv_path).expect("telemetry opens");
+        let header = reader.headers().expect("telemetry header").clone();
+        let gnss: Vec<&str> = header
+            .iter()
+            .filter(|h| h.starts_with("dsfb_trust_gnss"))
+            .collect();
+        let expected: Vec<String> = (0..receivers)
+            .map(|k| format!("dsfb_trust_gnss{k}"))
+            .collect();
+        assert_eq!(gnss, expected);
+        assert_eq!(
+            column(&header, "dsfb_trust_gnss0"),
+            column(&header, "dsfb_raw_trust_imu2") + 1
+        );
+        column(&header, "dsfb_yaw_deg");
+        for row in reader.records() {
+            assert_eq!(row.expect("record").len(), header.len());
+        }
+    }
+
+    let _ = fs::remove_dir_all(&scratch);
+}
+
+#[test]
+fn thermal_metrics_cover_every_configured_imu() {
+    let scratch = scratch_dir("imu-count");
+    for imus in [2, 4] {
+        let mut cfg = tiny_config();
+        cfg.imu_count = imus;
+        let summary =
+            run_simulation(&cfg, &scratch.join(imus.to_string())).expect("simulation runs");
+        assert_eq!(summary.thermal.trust_temp_correlation_imu.len(), imus);
+        assert_eq!(summary.thermal.trust_cold_imu.len(), imus);
+        assert_eq!(summary.thermal.trust_at_peak_temp_imu.len(), imus);
+        assert!(summary
+            .thermal
+            .trust_at_peak_temp_imu
+            .iter()
+            .all(|&trust| trust > 0.0 && trust <= 1.0));
+
+        let mut reader =
+            csv::Reader::from_path(&summary.outputs.csv_path).expect("telemetry opens");
+        let header = reader.headers().expect("telemetry header").clone();
+        let raw: Vec<&str> = header
+            .iter()
+            .filter(|h| h.starts_with("dsfb_raw_trust_imu"))
+            .collect();
+        let expected: Vec<String> = (0..imus)
+            .map(|k| format!("dsfb_raw_trust_imu{k}"))
+            .collect();
+        assert_eq!(raw, expected);
+    }
+
+    let _ = fs::remove_dir_all(&scratch);
+}
@@ -69,6 +69,7 @@ fn main() -> std::io::Result<()> {
         impulse_start: 300,
         impulse_duration: 100,
         impulse_amplitude: 1.0,
+        faults: Vec::new(),
         seed: 42,
     };
 
@@ -0,0 +1,231 @@
+//! Redundant-Sensor Voting Supervisor Example
+//!
+//! Three redundant sensors measure the same oscillating signal. A DSFB
+//! observer fuses them continuously; on top of it, a discrete supervisor turns
+//! each channel's trust into a health state with hysteresis and picks the
+//! output source an actuator would consume:
+//!
+//! - `Fused` while at least two channels are admitted
+//! - `Single(k)` when only channel `k` is left
+//! - `Hold` when none is, keeping the last output
+//!
+//! Channel B takes a bias fault between 3 s and 6 s, and channel C drops out
+//! between 5 s and 7 s. Both are excluded while faulty, leaving channel A
+//! alone for a while, and are re-admitted once their trust has recovered.
+//!
+//! The supervisor votes on relative trust, each channel's weight over the
+//! largest weight, rather than on the weights themselves. A normalized weight
+//! rises whenever another channel degrades, so a faulty channel could look
+//! healthy again just because a second one failed; the ratio to the best
+//! channel does not move in that case. Absolute trust does not work either:
+//! a biased channel pulls the fused state, raising every channel's residual.
+//!
+//! ```text
+//! cargo run -p dsfb --example redundancy_supervisor
+//! ```
+
+use dsfb::{DsfbObserver, DsfbParams, DsfbState};
+use rand::SeedableRng;
+use rand_distr::{Distribution, Normal};
+
+const CHANNEL_NAMES: [&str; 3] = ["A", "B", "C"];
+const DT: f64 = 0.01;
+const DURATION_S: f64 = 12.0;
+const NOISE_STD: f64 = 0.02;
+
+/// A channel is excluded after its trust stays below this for `EXCLUDE_STEPS`
+const EXCLUDE_TRUST: f64 = 0.3;
+const EXCLUDE_STEPS: usize = 10;
+/// An excluded channel is re-admitted after its trust stays above this for `READMIT_STEPS`
+const READMIT_TRUST: f64 = 0.7;
+const READMIT_STEPS: usize = 50;
+
+/// Discrete health of one channel as seen by the supervisor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChannelHealth {
+    /// Admitted, trust above the exclusion level
+    Healthy,
+    /// Admitted, but the trust is below the exclusion level
+    Suspect,
+    /// Not admitted to the output vote
+    Excluded,
+}
+
+/// Output source selected by the supervisor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputSource {
+    Fused,
+    Single(usize),
+    Hold,
+}
+
+/// Per-channel health state machine with separate exclusion and
+/// re-admission thresholds, so a weight hovering near one level does not
+/// toggle the decision every step.
+struct ChannelMonitor {
+    health: ChannelHealth,
+    /// Consecutive steps the current transition condition has held
+    run_length: usize,
+}
+
+impl ChannelMonitor {
+    fn new() -> Self {
+        Self {
+            health: ChannelHealth::Healthy,
+            run_length: 0,
+        }
+    }
+
+    /// Update with the channel's trust; returns the new health when it changed.
+    fn update(&mut self, trust: f64) -> Option<ChannelHealth> {
+        let previous = self.health;
+        match self.health {
+            ChannelHealth::Healthy | ChannelHealth::Suspect => {
+                if trust < EXCLUDE_TRUST {
+                    self.run_length += 1;
+                    self.health = if self.run_length >= EXCLUDE_STEPS {
+                        self.run_length = 0;
+                        ChannelHealth::Excluded
+                    } else {
+                        ChannelHealth::Suspect
+                    };
+                } else {
+                    self.run_length = 0;
+                    self.health = ChannelHealth::Healthy;
+                }
+            }
+            ChannelHealth::Excluded => {
+                if trust > READMIT_TRUST {
+                    self.run_length += 1;
+                    if self.run_length >= READMIT_STEPS {
+                        self.run_length = 0;
+                        self.health = ChannelHealth::Healthy;
+                    }
+                } else {
+                    self.run_length = 0;
+                }
+            }
+        }
+        (self.health != previous).then_some(self.health)
+    }
+
+    fn admitted(&self) -> bool {
+        self.health != ChannelHealth::Excluded
+    }
+}
+
+/// Picks the output source from the set of admitted channels.
+fn select_source(monitors: &[ChannelMonitor]) -> OutputSource {
+    let admitted: Vec<usize> = (0..monitors.len())
+        .filter(|&k| monitors[k].admitted())
+        .collect();
+    match admitted.as_slice() {
+        [] => OutputSource::Hold,
+        [k] => OutputSource::Single(*k),
+        _ => OutputSource::Fused,
+    }
+}
+
+/// Weight of channel `k` relative to the most trusted channel, in `[0, 1]`.
+fn relative_trust(observer: &DsfbObserver, k: usize) -> f64 {
+    let best = (0..CHANNEL_NAMES.len())
+        .map(|j| observer.trust_weight(j))
+        .fold(0.0, f64::max);
+    if best > 0.0 {
+        observer.trust_weight(k) / best
+    } else {
+        0.0
+    }
+}
+
+fn truth(t: f64) -> f64 {
+    (1.2 * t).sin()
+}
+
+/// Sensor reading of channel `k` at time `t`, or `None` while it is silent.
+fn measure(k: usize, t: f64, noise: f64) -> Option<f64> {
+    let value = truth(t) + noise;
+    match k {
+        1 if (3.0..6.0).contains(&t) => Some(value + 0.8),
+        2 if (5.0..7.0).contains(&t) => None,
+        _ => Some(value),
+    }
+}
+
+fn source_name(source: OutputSource) -> String {
+    match source {
+        OutputSource::Fused => "fused".to_string(),
+        OutputSource::Single(k) => format!("channel {}", CHANNEL_NAMES[k]),
+        OutputSource::Hold => "hold".to_string(),
+    }
+}
+
+fn main() {
+    println!("Running DSFB redundancy supervisor example...\n");
+
+    let mut observer = DsfbObserver::new(DsfbParams::default(), CHANNEL_NAMES.len());
+    observer.init(DsfbState::new(truth(0.0), 1.2, 0.0));
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+    let noise = Normal::new(0.0, NOISE_STD).expect("valid noise distribution");
+
+    let mut monitors: Vec<ChannelMonitor> = CHANNEL_NAMES
+        .iter()
+        .map(|_| ChannelMonitor::new())
+        .collect();
+    let mut source = OutputSource::Fused;
+    let mut output = truth(0.0);
+    let mut sum_sq_error = 0.0;
+
+    let steps = (DURATION_S / DT).round() as usize;
+    for step in 0..steps {
+        let t = (step + 1) as f64 * DT;
+        let readings: Vec<Option<f64>> = (0..CHANNEL_NAMES.len())
+            .map(|k| measure(k, t, noise.sample(&mut rng)))
+            .collect();
+
+        let fused = observer.step_multirate(&readings, DT).phi;
+
+        for (k, monitor) in monitors.iter_mut().enumerate() {
+            let trust = relative_trust(&observer, k);
+            if let Some(health) = monitor.update(trust) {
+                println!(
+                    "t={t:6.2}s  channel {} -> {health:?} (trust {trust:.3})",
+                    CHANNEL_NAMES[k]
+                );
+            }
+        }
+
+        let selected = select_source(&monitors);
+        if selected != source {
+            println!(
+                "t={t:6.2}s  switch-over: {} -> {}",
+                source_name(source),
+                source_name(selected)
+            );
+            source = selected;
+        }
+
+        output = match source {
+            OutputSource::Fused => fused,
+            OutputSource::Single(k) => readings[k].unwrap_or(output),
+            OutputSource::Hold => output,
+        };
+        sum_sq_error += (output - truth(t)).powi(2);
+    }
+
+    println!("\nFinal channel state:");
+    for (k, monitor) in monitors.iter().enumerate() {
+        println!(
+            "  {}: {:?}, trust {:.3}, weight {:.3}",
+            CHANNEL_NAMES[k],
+            monitor.health,
+            relative_trust(&observer, k),
+            observer.trust_weight(k)
+        );
+    }
+    println!(
+        "Supervised output RMS error: {:.4}",
+        (sum_sq_error / steps as f64).sqrt()
+    );
+}
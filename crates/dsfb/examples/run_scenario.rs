@@ -0,0 +1,67 @@
+//! Scenario Runner Example
+//!
+//! Runs one or more JSON scenario files and checks their metrics against the
+//! stored tolerances. Exits with a non-zero status if any check fails.
+//!
+//! ```text
+//! cargo run --example run_scenario -- scenarios/drift_impulse.json
+//! ```
+
+use dsfb::scenario::Scenario;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let paths: Vec<String> = std::env::args().skip(1).collect();
+    if paths.is_empty() {
+        eprintln!("usage: run_scenario <scenario.json>...");
+        return ExitCode::from(2);
+    }
+
+    let mut all_passed = true;
+    for path in &paths {
+        let scenario = match Scenario::load(path) {
+            Ok(scenario) => scenario,
+            Err(err) => {
+                eprintln!("error: {err}");
+                return ExitCode::from(2);
+            }
+        };
+
+        println!("Scenario: {} ({path})", scenario.name);
+        if !scenario.description.is_empty() {
+            println!("  {}", scenario.description);
+        }
+
+        let report = scenario.run();
+        let m = &report.metrics;
+        println!("  rms_mean:      {:.6}", m.rms_mean);
+        println!("  rms_freqonly:  {:.6}", m.rms_freqonly);
+        println!("  rms_dsfb:      {:.6}", m.rms_dsfb);
+        println!("  peak_err_dsfb: {:.6}", m.peak_err_dsfb);
+        println!("  min_w2:        {:.6}", m.min_w2);
+
+        for check in &report.checks {
+            println!(
+                "  [{}] {} = {:.6} (expected {:.6} ± {:.6})",
+                if check.passed { "PASS" } else { "FAIL" },
+                check.metric,
+                check.actual,
+                check.tolerance.expected,
+                check.tolerance.tolerance
+            );
+        }
+        if report.checks.is_empty() {
+            println!("  (no expectations configured)");
+        }
+        println!();
+
+        all_passed &= report.passed();
+    }
+
+    if all_passed {
+        ExitCode::SUCCESS
+    } else {
+        eprintln!("one or more scenario checks failed");
+        ExitCode::FAILURE
+    }
+}
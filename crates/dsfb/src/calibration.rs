@@ -0,0 +1,155 @@
+//! Startup calibration of per-channel trust softness
+//!
+//! `sigma0` sets how quickly a channel loses trust as its residual envelope
+//! grows, and a good value depends on each sensor's noise level. A
+//! [`Sigma0Calibration`] collects every channel's residuals over the first
+//! steps of a run and estimates the channel's `sigma0` as the scaled median
+//! absolute deviation (MAD) of those residuals. The MAD is barely moved by a
+//! few outliers or by a constant offset, so a faulty sample during startup
+//! does not inflate the estimate.
+
+use crate::scalar::Real;
+
+/// Ratio of the standard deviation to the MAD for Gaussian noise
+pub const MAD_TO_SIGMA: f64 = 1.4826;
+
+/// Collects per-channel residuals and estimates each channel's `sigma0`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sigma0Calibration<T: Real = f64> {
+    /// Steps to collect before estimating
+    samples: usize,
+    /// Steps recorded so far
+    steps: usize,
+    /// Residuals collected for each channel
+    residuals: Vec<Vec<T>>,
+    /// Estimated `sigma0` of each channel, once calibration is complete
+    sigma0: Option<Vec<T>>,
+}
+
+impl<T: Real> Sigma0Calibration<T> {
+    /// Calibrate `channels` channels from their residuals over `samples` steps
+    ///
+    /// # Panics
+    ///
+    /// If `samples` is zero.
+    pub fn new(channels: usize, samples: usize) -> Self {
+        assert!(samples > 0, "Sigma0 calibration needs at least one sample");
+        Self {
+            samples,
+            steps: 0,
+            residuals: vec![Vec::with_capacity(samples); channels],
+            sigma0: None,
+        }
+    }
+
+    /// Number of steps collected before estimating
+    pub fn samples(&self) -> usize {
+        self.samples
+    }
+
+    /// Whether every channel's `sigma0` has been estimated
+    pub fn is_complete(&self) -> bool {
+        self.sigma0.is_some()
+    }
+
+    /// Estimated `sigma0` of each channel, once calibration is complete
+    pub fn sigma0(&self) -> Option<&[T]> {
+        self.sigma0.as_deref()
+    }
+
+    /// Record one step's residuals and return the estimates if this step
+    /// completes calibration
+    ///
+    /// `None` skips a channel for this step, e.g. when it has no new sample.
+    /// A channel with no recorded residuals, or whose residuals have zero
+    /// MAD, gets `fallback`. Once calibration is complete further steps are
+    /// ignored.
+    pub fn record(
+        &mut self,
+        residuals: impl IntoIterator<Item = Option<T>>,
+        fallback: T,
+    ) -> Option<&[T]> {
+        if self.is_complete() {
+            return None;
+        }
+
+        for (collected, residual) in self.residuals.iter_mut().zip(residuals) {
+            if let Some(r) = residual {
+                collected.push(r);
+            }
+        }
+        self.steps += 1;
+        if self.steps < self.samples {
+            return None;
+        }
+
+        let sigma0 = self
+            .residuals
+            .iter()
+            .map(|collected| match mad_sigma(collected) {
+                Some(sigma) if sigma > T::ZERO => sigma,
+                _ => fallback,
+            })
+            .collect();
+        self.residuals = Vec::new();
+        self.sigma0 = Some(sigma0);
+        self.sigma0()
+    }
+}
+
+/// Standard deviation estimated from the median absolute deviation of
+/// `values`, or `None` if `values` is empty
+pub fn mad_sigma<T: Real>(values: &[T]) -> Option<T> {
+    let center = median(values.to_vec())?;
+    let deviations = values.iter().map(|&v| (v - center).abs()).collect();
+    median(deviations).map(|mad| T::from_f64(MAD_TO_SIGMA) * mad)
+}
+
+fn median<T: Real>(mut values: Vec<T>) -> Option<T> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal));
+    let mid = values.len() / 2;
+    Some(if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / T::from_f64(2.0)
+    } else {
+        values[mid]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mad_sigma_ignores_outliers_and_offset() {
+        let values = [0.9, 1.1, 0.9, 1.1, 0.9, 1.1, 25.0];
+        let sigma = mad_sigma(&values).unwrap();
+        assert!((sigma - MAD_TO_SIGMA * 0.2).abs() < 1e-12);
+        assert_eq!(mad_sigma::<f64>(&[]), None);
+    }
+
+    #[test]
+    fn test_calibration_completes_after_samples() {
+        let mut calibration = Sigma0Calibration::new(3, 4);
+        for n in 0..3 {
+            let sign = if n % 2 == 0 { 1.0 } else { -1.0 };
+            assert!(calibration
+                .record([Some(0.1 * sign), Some(0.0), None], 0.5)
+                .is_none());
+        }
+        let sigma0 = calibration
+            .record([Some(-0.1), Some(0.0), None], 0.5)
+            .unwrap()
+            .to_vec();
+
+        assert!((sigma0[0] - MAD_TO_SIGMA * 0.1).abs() < 1e-12);
+        // Zero MAD and no samples both fall back
+        assert_eq!(sigma0[1], 0.5);
+        assert_eq!(sigma0[2], 0.5);
+        assert!(calibration.is_complete());
+        assert!(calibration.record([Some(9.0); 3], 0.5).is_none());
+        assert_eq!(calibration.sigma0(), Some(sigma0.as_slice()));
+    }
+}
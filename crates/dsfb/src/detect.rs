@@ -0,0 +1,193 @@
+//! Change-point detection on channel residual streams
+//!
+//! Implements a two-sided CUSUM test on each channel's signed residuals. The
+//! EMA envelope needs roughly `1 / (1 - rho)` steps to reflect a small
+//! persistent bias shift; CUSUM accumulates the evidence and flags the shift
+//! once it exceeds a threshold, with an estimate of its onset and magnitude.
+
+use crate::scalar::Real;
+
+/// Parameters of the two-sided CUSUM change detector
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CusumParams<T: Real = f64> {
+    /// Allowance `k`: residual deviations below it do not accumulate,
+    /// typically half the smallest shift worth detecting
+    pub allowance: T,
+    /// Decision threshold `h` on the cumulative sums
+    pub threshold: T,
+    /// Re-initialize the channel envelope to the post-change residual level
+    /// on detection, instead of letting the EMA converge to it
+    pub reinit_envelope: bool,
+}
+
+impl<T: Real> CusumParams<T> {
+    /// Create CUSUM parameters that report change points and re-initialize envelopes
+    pub fn new(allowance: T, threshold: T) -> Self {
+        Self {
+            allowance,
+            threshold,
+            reinit_envelope: true,
+        }
+    }
+
+    /// Set whether a detection re-initializes the channel envelope
+    pub fn with_reinit_envelope(mut self, reinit_envelope: bool) -> Self {
+        self.reinit_envelope = reinit_envelope;
+        self
+    }
+}
+
+/// A change in the mean of one channel's residuals
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChangePoint<T: Real = f64> {
+    /// Channel index
+    pub channel: usize,
+    /// Estimated step of the change onset (0-based observer step count)
+    pub onset_step: usize,
+    /// Step at which the change was detected
+    pub detected_step: usize,
+    /// Estimated shift of the residual mean (signed)
+    pub shift: T,
+    /// Residual mean after the change, the detector's new reference
+    pub level: T,
+}
+
+/// Two-sided CUSUM detector for one residual stream
+///
+/// The cumulative sums are `g+ = max(0, g+ + (r - mu) - k)` and
+/// `g- = max(0, g- - (r - mu) - k)`, with `mu` the reference mean. When either
+/// exceeds the threshold the shift is estimated as `k + g / n`, where `n` is
+/// the number of steps since that sum was last zero, `mu` moves to the new
+/// level, and both sums restart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CusumDetector<T: Real = f64> {
+    params: CusumParams<T>,
+    reference: T,
+    g_pos: T,
+    g_neg: T,
+    run_pos: usize,
+    run_neg: usize,
+}
+
+impl<T: Real> CusumDetector<T> {
+    /// Create a detector with zero reference mean
+    pub fn new(params: CusumParams<T>) -> Self {
+        Self {
+            params,
+            reference: T::ZERO,
+            g_pos: T::ZERO,
+            g_neg: T::ZERO,
+            run_pos: 0,
+            run_neg: 0,
+        }
+    }
+
+    /// Feed one residual; returns `(run_length, shift)` when a change is detected
+    ///
+    /// `run_length` counts the steps, including this one, since the change onset.
+    pub fn update(&mut self, residual: T) -> Option<(usize, T)> {
+        let deviation = residual - self.reference;
+        let k = self.params.allowance;
+
+        self.g_pos = accumulate(self.g_pos + deviation - k, &mut self.run_pos);
+        self.g_neg = accumulate(self.g_neg - deviation - k, &mut self.run_neg);
+
+        let detection = if self.g_pos > self.params.threshold {
+            Some((self.run_pos, k + self.g_pos / T::from_usize(self.run_pos)))
+        } else if self.g_neg > self.params.threshold {
+            Some((
+                self.run_neg,
+                -(k + self.g_neg / T::from_usize(self.run_neg)),
+            ))
+        } else {
+            None
+        };
+
+        if let Some((_, shift)) = detection {
+            self.reference += shift;
+            self.restart();
+        }
+        detection
+    }
+
+    /// Clear the cumulative sums and set the reference mean
+    pub fn reset(&mut self, reference: T) {
+        self.reference = reference;
+        self.restart();
+    }
+
+    /// Current reference mean of the residuals
+    pub fn reference(&self) -> T {
+        self.reference
+    }
+
+    /// Current upper and lower cumulative sums `(g+, g-)`
+    pub fn statistics(&self) -> (T, T) {
+        (self.g_pos, self.g_neg)
+    }
+
+    /// Detector parameters
+    pub fn params(&self) -> &CusumParams<T> {
+        &self.params
+    }
+
+    fn restart(&mut self) {
+        self.g_pos = T::ZERO;
+        self.g_neg = T::ZERO;
+        self.run_pos = 0;
+        self.run_neg = 0;
+    }
+}
+
+/// Clamp a cumulative sum at zero and track how long it has been positive
+fn accumulate<T: Real>(sum: T, run: &mut usize) -> T {
+    if sum > T::ZERO {
+        *run += 1;
+        sum
+    } else {
+        *run = 0;
+        T::ZERO
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cusum_ignores_deviation_below_allowance() {
+        let mut detector = CusumDetector::new(CusumParams::new(0.1, 0.5));
+        for k in 0..1000 {
+            let r = if k % 2 == 0 { 0.09 } else { -0.09 };
+            assert_eq!(detector.update(r), None);
+        }
+    }
+
+    #[test]
+    fn test_cusum_detects_step_and_estimates_shift() {
+        let mut detector = CusumDetector::new(CusumParams::new(0.05, 1.0));
+        for _ in 0..50 {
+            assert_eq!(detector.update(0.0), None);
+        }
+
+        let mut detection = None;
+        let mut steps = 0;
+        while detection.is_none() {
+            detection = detector.update(0.3);
+            steps += 1;
+        }
+
+        let (run_length, shift) = detection.unwrap();
+        assert_eq!(run_length, steps);
+        assert!((shift - 0.3).abs() < 1e-12);
+        assert!((detector.reference() - 0.3).abs() < 1e-12);
+
+        // The new level is the reference, so the persistent shift is not re-flagged
+        for _ in 0..200 {
+            assert_eq!(detector.update(0.3), None);
+        }
+        let (_, back) = (0..100).find_map(|_| detector.update(0.0)).unwrap();
+        assert!((back + 0.3).abs() < 1e-12);
+    }
+}
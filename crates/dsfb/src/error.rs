@@ -0,0 +1,94 @@
+//! Errors of the fallible observer API
+//!
+//! [`DsfbObserver::try_step`](crate::DsfbObserver::try_step) and the other
+//! `try_` methods report invalid input as a [`DsfbError`] instead of
+//! panicking, for supervisory loops that must not abort. The panicking
+//! methods wrap them and panic with the error's message.
+//!
+//! With the `std` feature a `DsfbError` converts into the workspace-wide
+//! [`dsfb_error::Error`], the error type of the other DSFB libraries.
+
+use core::fmt;
+
+/// Invalid input to a [`DsfbObserver`](crate::DsfbObserver)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DsfbError {
+    /// A step got a different number of measurements than the observer has
+    /// channels
+    MeasurementCountMismatch {
+        /// Number of channels of the observer
+        expected: usize,
+        /// Number of measurements passed
+        got: usize,
+    },
+    /// A channel index is not below the observer's channel count
+    ChannelOutOfRange {
+        /// Requested channel
+        channel: usize,
+        /// Number of channels of the observer
+        channels: usize,
+    },
+}
+
+impl fmt::Display for DsfbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            DsfbError::MeasurementCountMismatch { expected, got } => {
+                write!(
+                    f,
+                    "Measurement count mismatch: expected {expected}, got {got}"
+                )
+            }
+            DsfbError::ChannelOutOfRange { channel, channels } => write!(
+                f,
+                "Channel index out of range: {channel} (observer has {channels} channels)"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DsfbError {}
+
+#[cfg(feature = "std")]
+impl From<DsfbError> for dsfb_error::Error {
+    fn from(error: DsfbError) -> Self {
+        match error {
+            DsfbError::MeasurementCountMismatch { expected, got } => {
+                dsfb_error::Error::LengthMismatch {
+                    context: "measurements",
+                    expected,
+                    got,
+                }
+            }
+            DsfbError::ChannelOutOfRange { channel, channels } => {
+                dsfb_error::Error::IndexOutOfRange {
+                    context: "channel",
+                    index: channel,
+                    len: channels,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_converts_to_workspace_error() {
+        let error = dsfb_error::Error::from(DsfbError::ChannelOutOfRange {
+            channel: 4,
+            channels: 3,
+        });
+        assert!(matches!(
+            error,
+            dsfb_error::Error::IndexOutOfRange {
+                context: "channel",
+                index: 4,
+                len: 3
+            }
+        ));
+    }
+}
@@ -0,0 +1,287 @@
+//! Fixed-capacity DSFB observer
+//!
+//! [`DsfbObserverFixed`] keeps its `N` channels in arrays, so it needs
+//! neither `std` nor an allocator and fits on a flight microcontroller. It
+//! runs the core observer step (prediction, trust update, and correction),
+//! including staleness decay, the weight floor, the trust shape, and the rho
+//! policy of [`DsfbParams`], and gives the same estimates as a
+//! [`DsfbObserver`](crate::DsfbObserver) with the same parameters. Change
+//! detection, saturation limits, `sigma0` calibration, channel groups,
+//! custom rho schedules, and step diagnostics are only available on the
+//! heap-backed observer.
+
+use crate::error::DsfbError;
+use crate::params::DsfbParams;
+use crate::rho::RhoSchedule;
+use crate::scalar::Real;
+use crate::state::DsfbState;
+use crate::trust::{calculate_multirate_trust_weights_into, fused_variance, TrustStats};
+
+/// DSFB observer over a fixed number `N` of channels
+///
+/// Generic over the scalar type like [`DsfbObserver`](crate::DsfbObserver);
+/// `f32` suits targets without double-precision hardware.
+#[derive(Debug, Clone)]
+pub struct DsfbObserverFixed<const N: usize, T: Real = f64> {
+    /// Observer parameters
+    params: DsfbParams<T>,
+    /// Current state estimate
+    state: DsfbState<T>,
+    /// EMA residuals for each channel
+    ema_residuals: [T; N],
+    /// Trust statistics for each channel
+    trust_stats: [TrustStats<T>; N],
+    /// Variance estimate of the fused channel measurement
+    fused_variance: T,
+    /// Last fresh sample of each channel, held while the channel is stale
+    held_measurements: [Option<T>; N],
+    /// Consecutive stale steps of each channel
+    staleness: [usize; N],
+    /// Trust multiplier of each channel: `staleness_decay^staleness`
+    staleness_factors: [T; N],
+    /// Number of completed steps
+    steps: usize,
+}
+
+impl<const N: usize, T: Real> DsfbObserverFixed<N, T> {
+    /// Create a new fixed-capacity DSFB observer
+    pub fn new(params: DsfbParams<T>) -> Self {
+        Self {
+            state: DsfbState::zero(),
+            ema_residuals: [T::ZERO; N],
+            trust_stats: core::array::from_fn(|_| TrustStats::new()),
+            // Uniform weights over empty envelopes: sigma0^2 / channels
+            fused_variance: params.sigma0 * params.sigma0 / T::from_usize(N.max(1)),
+            held_measurements: [None; N],
+            staleness: [0; N],
+            staleness_factors: [T::ONE; N],
+            steps: 0,
+            params,
+        }
+    }
+
+    /// Initialize the state
+    pub fn init(&mut self, initial_state: DsfbState<T>) {
+        self.state = initial_state;
+    }
+
+    /// Return the observer to its freshly constructed condition
+    ///
+    /// The state is zeroed, and every channel's envelope, trust statistics,
+    /// held sample, and staleness are cleared, as is the step count.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.params);
+    }
+
+    /// Perform one step with a sample from every channel
+    ///
+    /// See [`DsfbObserver::step`](crate::DsfbObserver::step).
+    pub fn step(&mut self, measurements: &[T; N], dt: T) -> DsfbState<T> {
+        self.step_multirate(&measurements.map(Some), dt)
+    }
+
+    /// Perform one step from a slice, or report
+    /// [`DsfbError::MeasurementCountMismatch`] if it does not hold exactly
+    /// `N` measurements
+    ///
+    /// The observer is unchanged on error.
+    pub fn try_step(&mut self, measurements: &[T], dt: T) -> Result<DsfbState<T>, DsfbError> {
+        let measurements: &[T; N] =
+            measurements
+                .try_into()
+                .map_err(|_| DsfbError::MeasurementCountMismatch {
+                    expected: N,
+                    got: measurements.len(),
+                })?;
+        Ok(self.step(measurements, dt))
+    }
+
+    /// Perform one step with channels that may not have a new sample
+    ///
+    /// `None` marks a stale channel; see
+    /// [`DsfbObserver::step_multirate`](crate::DsfbObserver::step_multirate).
+    pub fn step_multirate(&mut self, measurements: &[Option<T>; N], dt: T) -> DsfbState<T> {
+        let mut fresh = [false; N];
+        for (k, &measurement) in measurements.iter().enumerate() {
+            match measurement {
+                Some(y) => {
+                    self.held_measurements[k] = Some(y);
+                    self.staleness[k] = 0;
+                    self.staleness_factors[k] = T::ONE;
+                    fresh[k] = true;
+                }
+                None if self.held_measurements[k].is_some() => {
+                    self.staleness[k] = self.staleness[k].saturating_add(1);
+                    self.staleness_factors[k] *= self.params.staleness_decay;
+                }
+                None => self.staleness_factors[k] = T::ZERO,
+            }
+        }
+
+        // Predict under the constant-slew model
+        let phi = self.state.phi + self.state.omega * dt;
+        let omega = self.state.omega + self.state.alpha * dt;
+        let alpha = self.state.alpha;
+
+        // Residuals against the held samples: r_k = y_k - phi^-
+        let residuals: [T; N] =
+            core::array::from_fn(|k| self.held_measurements[k].map_or(T::ZERO, |y| y - phi));
+
+        // Forgetting factor of each channel's envelope update
+        let rho: [T; N] = core::array::from_fn(|k| {
+            self.params.rho_policy.rho(
+                self.params.rho,
+                self.steps,
+                residuals[k].abs(),
+                self.ema_residuals[k],
+            )
+        });
+
+        let mut weights = [T::ZERO; N];
+        calculate_multirate_trust_weights_into(
+            &self.params.trust_shape,
+            &residuals,
+            &mut self.ema_residuals,
+            &fresh,
+            &self.staleness_factors,
+            &rho,
+            &[self.params.sigma0; N],
+            self.params.min_weight,
+            &mut weights,
+        );
+
+        // Store trust stats and per-channel drift estimates
+        for (k, stats) in self.trust_stats.iter_mut().enumerate() {
+            stats.residual_ema = self.ema_residuals[k];
+            stats.weight = weights[k];
+            if fresh[k] {
+                stats.update_drift(residuals[k], self.params.rho, dt);
+            }
+        }
+
+        // Fused variance: sum_k w_k^2 * (sigma0 + s_k)^2
+        self.fused_variance = fused_variance(&weights, &self.ema_residuals, self.params.sigma0);
+
+        // Aggregate residual: R = sum_k w_k * r_k
+        let aggregate_residual: T = residuals
+            .iter()
+            .zip(weights.iter())
+            .map(|(&r, &w)| w * r)
+            .sum();
+
+        self.state = DsfbState::new(
+            phi + self.params.k_phi * aggregate_residual,
+            omega + self.params.k_omega * aggregate_residual,
+            alpha + self.params.k_alpha * aggregate_residual,
+        );
+        self.steps += 1;
+        self.state
+    }
+
+    /// Get the current state
+    pub fn state(&self) -> DsfbState<T> {
+        self.state
+    }
+
+    /// Get the observer parameters
+    pub fn params(&self) -> &DsfbParams<T> {
+        &self.params
+    }
+
+    /// Get trust statistics for all channels
+    pub fn trust_stats(&self) -> &[TrustStats<T>; N] {
+        &self.trust_stats
+    }
+
+    /// Get trust weight for a specific channel
+    ///
+    /// # Panics
+    ///
+    /// If `channel` is out of range; see [`Self::try_trust_weight`].
+    pub fn trust_weight(&self, channel: usize) -> T {
+        self.try_trust_weight(channel)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Get trust weight for a specific channel, or
+    /// [`DsfbError::ChannelOutOfRange`]
+    pub fn try_trust_weight(&self, channel: usize) -> Result<T, DsfbError> {
+        self.trust_stats
+            .get(channel)
+            .map(|stats| stats.weight)
+            .ok_or(DsfbError::ChannelOutOfRange {
+                channel,
+                channels: N,
+            })
+    }
+
+    /// Get the number of consecutive stale steps of a specific channel
+    pub fn channel_staleness(&self, channel: usize) -> usize {
+        self.staleness[channel]
+    }
+
+    /// Get the variance estimate of the trust-weighted fused measurement
+    pub fn fused_uncertainty(&self) -> T {
+        self.fused_variance
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::observer::DsfbObserver;
+    use crate::rho::RhoPolicy;
+    use crate::trust::TrustShape;
+
+    #[test]
+    fn test_fixed_observer_matches_heap_observer() {
+        let params = DsfbParams::new(0.5, 0.1, 0.01, 0.9, 0.1)
+            .with_min_weight(0.05)
+            .with_rho_policy(RhoPolicy::Warmup)
+            .with_trust_shape(TrustShape::Exponential);
+        let mut fixed = DsfbObserverFixed::<3>::new(params);
+        let mut heap = DsfbObserver::new(params, 3);
+        let initial = DsfbState::new(0.0, 0.2, 0.0);
+        fixed.init(initial);
+        heap.init(initial);
+
+        let dt = 0.05;
+        for step in 0..200 {
+            let t = step as f64 * dt;
+            let truth = 0.2 * t;
+            let fault = if step >= 80 { 1.5 } else { 0.0 };
+            let samples = [truth + 0.01, truth - 0.01, truth + fault];
+            if step % 3 == 0 {
+                assert_eq!(fixed.step(&samples, dt), heap.step(&samples, dt));
+            } else {
+                // The slow third channel repeats between its updates
+                let samples = [Some(samples[0]), Some(samples[1]), None];
+                assert_eq!(
+                    fixed.step_multirate(&samples, dt),
+                    heap.step_multirate(&samples, dt)
+                );
+            }
+            assert_eq!(fixed.trust_stats().as_slice(), heap.trust_stats());
+            assert_eq!(fixed.fused_uncertainty(), heap.fused_uncertainty());
+        }
+        assert!(fixed.trust_weight(2) < 0.1);
+    }
+
+    #[test]
+    fn test_fixed_try_step_rejects_wrong_count() {
+        let mut observer = DsfbObserverFixed::<2, f32>::new(DsfbParams::default_params());
+        assert_eq!(
+            observer.try_step(&[1.0, 2.0, 3.0], 0.1),
+            Err(DsfbError::MeasurementCountMismatch {
+                expected: 2,
+                got: 3
+            })
+        );
+        assert_eq!(observer.state(), DsfbState::zero());
+        assert!(observer.try_step(&[1.0, 1.0], 0.1).is_ok());
+        assert!(matches!(
+            observer.try_trust_weight(2),
+            Err(DsfbError::ChannelOutOfRange { .. })
+        ));
+    }
+}
@@ -0,0 +1,153 @@
+//! Hierarchical group trust
+//!
+//! Sensors that share a mount, a power rail, or a clock tend to fail
+//! together, and per-channel trust cannot tell a disturbed cluster from
+//! independent channels that happen to agree. [`ChannelGroups`] maps every
+//! channel to a group and keeps one residual envelope per group: the EMA of
+//! the mean absolute residual over the group's fresh channels. A group's raw
+//! trust follows from its envelope through the same
+//! [`TrustFunction`] as channel trust, and a channel's composed raw trust is
+//! its own times its group's, before normalization across all channels.
+
+use crate::scalar::Real;
+use crate::trust::TrustFunction;
+
+/// Channel-to-group mapping with one residual envelope per group
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelGroups<T: Real = f64> {
+    /// Group of each channel
+    mapping: Vec<usize>,
+    /// Channels of each group
+    members: Vec<Vec<usize>>,
+    /// EMA of the mean absolute residual of each group
+    envelopes: Vec<T>,
+    /// Raw (unnormalized) trust of each group
+    trust: Vec<T>,
+}
+
+impl<T: Real> ChannelGroups<T> {
+    /// Put channel `k` in group `mapping[k]`
+    ///
+    /// Groups are numbered from zero; the group count is the largest entry
+    /// plus one. A group without channels keeps a zero envelope and has no
+    /// effect.
+    pub fn new(mapping: Vec<usize>) -> Self {
+        let count = mapping.iter().max().map_or(0, |&g| g + 1);
+        let mut members = vec![Vec::new(); count];
+        for (k, &g) in mapping.iter().enumerate() {
+            members[g].push(k);
+        }
+        Self {
+            mapping,
+            members,
+            envelopes: vec![T::ZERO; count],
+            trust: vec![T::ONE; count],
+        }
+    }
+
+    /// Number of groups
+    pub fn count(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Group of every channel
+    pub fn mapping(&self) -> &[usize] {
+        &self.mapping
+    }
+
+    /// Group of a specific channel
+    pub fn group_of(&self, channel: usize) -> usize {
+        self.mapping[channel]
+    }
+
+    /// Channels of a specific group
+    pub fn members(&self, group: usize) -> &[usize] {
+        &self.members[group]
+    }
+
+    /// Residual envelope of a specific group
+    pub fn envelope(&self, group: usize) -> T {
+        self.envelopes[group]
+    }
+
+    /// Raw trust of a specific group after the latest update
+    pub fn trust(&self, group: usize) -> T {
+        self.trust[group]
+    }
+
+    /// Update the group envelopes and trust from one step's residuals
+    ///
+    /// Only channels flagged in `fresh` contribute; a group without a fresh
+    /// channel keeps its envelope. Each group's trust uses the mean `sigma0`
+    /// of its channels. Returns the group trust of every channel, the factor
+    /// that multiplies its own raw trust.
+    pub fn update(
+        &mut self,
+        trust_fn: &impl TrustFunction<T>,
+        residuals: &[T],
+        fresh: &[bool],
+        rho: T,
+        sigma0: &[T],
+    ) -> Vec<T> {
+        for (g, members) in self.members.iter().enumerate() {
+            let fresh_members: Vec<usize> = members.iter().copied().filter(|&k| fresh[k]).collect();
+            if !fresh_members.is_empty() {
+                // s_g = rho*s_g + (1-rho)*mean_k |r_k|
+                let mean = fresh_members.iter().map(|&k| residuals[k].abs()).sum::<T>()
+                    / T::from_usize(fresh_members.len());
+                self.envelopes[g] = rho * self.envelopes[g] + (T::ONE - rho) * mean;
+            }
+            if !members.is_empty() {
+                let group_sigma0 =
+                    members.iter().map(|&k| sigma0[k]).sum::<T>() / T::from_usize(members.len());
+                self.trust[g] = trust_fn.raw_trust(self.envelopes[g], group_sigma0);
+            }
+        }
+
+        self.mapping.iter().map(|&g| self.trust[g]).collect()
+    }
+
+    /// Clear every group envelope, keeping the mapping
+    pub fn reset(&mut self) {
+        self.envelopes.fill(T::ZERO);
+        self.trust.fill(T::ONE);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trust::TrustShape;
+
+    #[test]
+    fn test_group_envelope_tracks_mean_of_fresh_members() {
+        let mut groups = ChannelGroups::new(vec![0, 0, 1, 1]);
+        assert_eq!(groups.count(), 2);
+        assert_eq!(groups.members(1), &[2, 3]);
+
+        let factors = groups.update(
+            &TrustShape::Reciprocal,
+            &[1.0, -3.0, 0.5, 9.0],
+            &[true, true, true, false],
+            0.5,
+            &[0.1; 4],
+        );
+
+        assert!((groups.envelope(0) - 1.0).abs() < 1e-12);
+        assert!((groups.envelope(1) - 0.25).abs() < 1e-12);
+        assert!((groups.trust(0) - 1.0 / 1.1).abs() < 1e-12);
+        assert_eq!(
+            factors,
+            vec![
+                groups.trust(0),
+                groups.trust(0),
+                groups.trust(1),
+                groups.trust(1)
+            ]
+        );
+
+        groups.reset();
+        assert_eq!(groups.envelope(0), 0.0);
+        assert_eq!(groups.mapping(), &[0, 0, 1, 1]);
+    }
+}
@@ -0,0 +1,113 @@
+//! CSV export of per-step trust statistics
+//!
+//! [`TrustCsvWriter`] streams one row per step and channel to any
+//! [`std::io::Write`] with the fixed schema [`TRUST_CSV_HEADER`], so trust
+//! traces from different tools can be compared column for column.
+
+use crate::observer::DsfbStepDiagnostics;
+use crate::scalar::Real;
+use crate::trust::TrustStats;
+use std::io::{self, Write};
+
+/// Column names written as the first line of every trust CSV
+pub const TRUST_CSV_HEADER: [&str; 5] = ["step", "channel", "weight", "ema_residual", "residual"];
+
+/// Streams per-step trust statistics as CSV rows
+///
+/// Values are written in their shortest round-trip decimal form, so a trace
+/// read back reproduces the observer's numbers exactly.
+pub struct TrustCsvWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> TrustCsvWriter<W> {
+    /// Wrap `inner` and write the header line
+    pub fn new(mut inner: W) -> io::Result<Self> {
+        writeln!(inner, "{}", TRUST_CSV_HEADER.join(","))?;
+        Ok(Self { inner })
+    }
+
+    /// Write one row per channel from a step's diagnostics
+    pub fn write_step<T: Real>(
+        &mut self,
+        step: usize,
+        diagnostics: &DsfbStepDiagnostics<T>,
+    ) -> io::Result<()> {
+        self.write_stats(step, &diagnostics.trust_stats, &diagnostics.residuals)
+    }
+
+    /// Write one row per channel from trust statistics and the step's raw residuals
+    ///
+    /// Panics if `residuals` and `trust_stats` differ in length.
+    pub fn write_stats<T: Real>(
+        &mut self,
+        step: usize,
+        trust_stats: &[TrustStats<T>],
+        residuals: &[T],
+    ) -> io::Result<()> {
+        assert_eq!(
+            trust_stats.len(),
+            residuals.len(),
+            "one residual per channel required"
+        );
+        for (channel, (stats, residual)) in trust_stats.iter().zip(residuals).enumerate() {
+            writeln!(
+                self.inner,
+                "{step},{channel},{},{},{}",
+                stats.weight.to_f64(),
+                stats.residual_ema.to_f64(),
+                residual.to_f64()
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Flush the underlying writer
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    /// Return the underlying writer
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DsfbObserver, DsfbParams, DsfbState};
+
+    #[test]
+    fn test_trust_csv_rows_match_diagnostics() {
+        let mut observer = DsfbObserver::new(DsfbParams::default(), 2);
+        observer.init(DsfbState::new(0.0, 0.0, 0.0));
+        let mut writer = TrustCsvWriter::new(Vec::new()).unwrap();
+
+        let mut steps = Vec::new();
+        for step in 0..3 {
+            let diagnostics = observer.step_with_diagnostics(&[0.1, 0.5], 0.01);
+            writer.write_step(step, &diagnostics).unwrap();
+            steps.push(diagnostics);
+        }
+
+        let csv = String::from_utf8(writer.into_inner()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "step,channel,weight,ema_residual,residual");
+        assert_eq!(lines.len(), 1 + 3 * 2);
+
+        let fields: Vec<&str> = lines[6].split(',').collect();
+        let last = &steps[2];
+        assert_eq!(fields[0], "2");
+        assert_eq!(fields[1], "1");
+        assert_eq!(
+            fields[2].parse::<f64>().unwrap(),
+            last.trust_stats[1].weight
+        );
+        assert_eq!(
+            fields[3].parse::<f64>().unwrap(),
+            last.trust_stats[1].residual_ema
+        );
+        assert_eq!(fields[4].parse::<f64>().unwrap(), last.residuals[1]);
+    }
+}
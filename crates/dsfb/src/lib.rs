@@ -3,15 +3,62 @@
 //! A trust-adaptive nonlinear state estimation algorithm for tracking
 //! position (phi), velocity/drift (omega), and acceleration/slew (alpha)
 //! across multiple measurement channels with adaptive trust weighting.
+//!
+//! Without the default `std` feature the crate is `no_std`: the heap-backed
+//! observers and the simulation are left out, and [`DsfbObserverFixed`]
+//! runs the core observer over a fixed number of channels.
+
+#![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "std")]
+pub mod calibration;
+pub mod detect;
+pub mod error;
+pub mod fixed;
+#[cfg(feature = "std")]
+pub mod group;
+#[cfg(feature = "io")]
+pub mod io;
+#[cfg(feature = "std")]
+pub mod measurement;
+#[cfg(feature = "nalgebra")]
+pub mod nd;
+#[cfg(feature = "std")]
 pub mod observer;
 pub mod params;
+#[cfg(feature = "python")]
+mod python;
+pub mod rho;
+pub mod saturation;
+pub mod scalar;
+#[cfg(feature = "serde")]
+pub mod scenario;
+#[cfg(feature = "std")]
 pub mod sim;
 pub mod state;
 pub mod trust;
+pub mod units;
 
 // Re-export main types
+#[cfg(feature = "std")]
+pub use calibration::Sigma0Calibration;
+pub use detect::{ChangePoint, CusumDetector, CusumParams};
+pub use error::DsfbError;
+pub use fixed::DsfbObserverFixed;
+#[cfg(feature = "std")]
+pub use group::ChannelGroups;
+#[cfg(feature = "io")]
+pub use io::{TrustCsvWriter, TRUST_CSV_HEADER};
+#[cfg(feature = "std")]
+pub use measurement::{DsfbObserverGeneric, MeasurementModel};
+#[cfg(feature = "nalgebra")]
+pub use nd::{DsfbObserverNd, DsfbStateNd, DsfbStepDiagnosticsNd};
+#[cfg(feature = "std")]
 pub use observer::{DsfbObserver, DsfbStepDiagnostics};
 pub use params::DsfbParams;
+pub use rho::{RhoPolicy, RhoSchedule};
+pub use saturation::{Rail, SaturationLimits};
+pub use scalar::Real;
 pub use state::DsfbState;
-pub use trust::TrustStats;
+pub use trust::{TrustFunction, TrustShape, TrustStats};
+pub use units::{Phase, Rate, Slew};
@@ -0,0 +1,316 @@
+//! Per-channel measurement functions
+//!
+//! [`DsfbObserver`] assumes every channel observes `phi` directly. The
+//! [`DsfbObserverGeneric`] variant accepts a measurement function `h_k(phi)`
+//! per channel, with an optional analytic Jacobian `dh_k/dphi`, so channels
+//! observing e.g. `sin(phi)` or a scaled `phi` are fused with residuals in
+//! their own units.
+
+use core::fmt;
+
+use crate::detect::CusumParams;
+use crate::observer::{DsfbObserver, DsfbStepDiagnostics};
+use crate::params::DsfbParams;
+use crate::saturation::SaturationLimits;
+use crate::scalar::Real;
+use crate::state::DsfbState;
+use crate::trust::TrustStats;
+
+/// Scalar function of the predicted position `phi`
+pub type ScalarFn<T> = Box<dyn Fn(T) -> T + Send + Sync>;
+
+/// Measurement model for a single channel
+pub enum MeasurementModel<T: Real = f64> {
+    /// `h(phi) = phi`
+    Identity,
+    /// User-supplied `h(phi)` with an optional Jacobian `dh/dphi`.
+    ///
+    /// Without a Jacobian, a central finite difference is used.
+    Function {
+        h: ScalarFn<T>,
+        jacobian: Option<ScalarFn<T>>,
+    },
+}
+
+impl<T: Real> MeasurementModel<T> {
+    /// Identity measurement `h(phi) = phi`
+    pub fn identity() -> Self {
+        Self::Identity
+    }
+
+    /// Measurement function with a finite-difference Jacobian
+    pub fn function(h: impl Fn(T) -> T + Send + Sync + 'static) -> Self {
+        Self::Function {
+            h: Box::new(h),
+            jacobian: None,
+        }
+    }
+
+    /// Measurement function with an analytic Jacobian
+    pub fn with_jacobian(
+        h: impl Fn(T) -> T + Send + Sync + 'static,
+        jacobian: impl Fn(T) -> T + Send + Sync + 'static,
+    ) -> Self {
+        Self::Function {
+            h: Box::new(h),
+            jacobian: Some(Box::new(jacobian)),
+        }
+    }
+
+    /// Linear measurement `h(phi) = gain * phi`
+    pub fn scaled(gain: T) -> Self
+    where
+        T: Send + Sync,
+    {
+        Self::with_jacobian(move |phi| gain * phi, move |_| gain)
+    }
+
+    /// Whether this is the identity model
+    pub fn is_identity(&self) -> bool {
+        matches!(self, Self::Identity)
+    }
+
+    /// Evaluate `h(phi)`
+    pub fn evaluate(&self, phi: T) -> T {
+        match self {
+            Self::Identity => phi,
+            Self::Function { h, .. } => h(phi),
+        }
+    }
+
+    /// Evaluate `dh/dphi` at `phi`
+    pub fn jacobian(&self, phi: T) -> T {
+        match self {
+            Self::Identity => T::ONE,
+            Self::Function {
+                jacobian: Some(jacobian),
+                ..
+            } => jacobian(phi),
+            Self::Function { h, jacobian: None } => {
+                // Step of sqrt(eps) relative to |phi| balances truncation and rounding error
+                let scale = if phi.abs() > T::ONE {
+                    phi.abs()
+                } else {
+                    T::ONE
+                };
+                let step = T::EPSILON.sqrt() * scale;
+                (h(phi + step) - h(phi - step)) / (step + step)
+            }
+        }
+    }
+}
+
+impl<T: Real> fmt::Debug for MeasurementModel<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Identity => f.write_str("Identity"),
+            Self::Function { jacobian, .. } => f
+                .debug_struct("Function")
+                .field("analytic_jacobian", &jacobian.is_some())
+                .finish(),
+        }
+    }
+}
+
+/// DSFB observer with per-channel measurement functions
+///
+/// Residuals are `r_k = y_k - h_k(phi^-)` and drive the trust weights as in
+/// [`DsfbObserver`]. The correction maps them back to `phi` with a weighted
+/// Gauss-Newton step, `R = sum_k w_k H_k r_k / sum_k w_k H_k^2`, which reduces
+/// to the identity update when every `H_k = 1`. When every channel is the
+/// identity the observer takes the [`DsfbObserver`] path unchanged.
+pub struct DsfbObserverGeneric<T: Real = f64> {
+    observer: DsfbObserver<T>,
+    models: Vec<MeasurementModel<T>>,
+    all_identity: bool,
+}
+
+impl<T: Real> DsfbObserverGeneric<T> {
+    /// Create a new observer with one measurement model per channel
+    pub fn new(params: DsfbParams<T>, models: Vec<MeasurementModel<T>>) -> Self {
+        let all_identity = models.iter().all(MeasurementModel::is_identity);
+        Self {
+            observer: DsfbObserver::new(params, models.len()),
+            models,
+            all_identity,
+        }
+    }
+
+    /// Run a CUSUM change detector on each channel's residual stream
+    /// (see [`DsfbObserver::with_change_detector`])
+    pub fn with_change_detector(mut self, params: CusumParams<T>) -> Self {
+        self.observer = self.observer.with_change_detector(params);
+        self
+    }
+
+    /// Treat samples at known sensor saturation limits as one-sided information
+    /// (see [`DsfbObserver::with_saturation_limits`]); limits are in each
+    /// channel's measurement units
+    pub fn with_saturation_limits(mut self, limits: Vec<Option<SaturationLimits<T>>>) -> Self {
+        self.observer = self.observer.with_saturation_limits(limits);
+        self
+    }
+
+    /// Compose each channel's trust with the trust of its group
+    /// (see [`DsfbObserver::with_groups`])
+    pub fn with_groups(mut self, mapping: Vec<usize>) -> Self {
+        self.observer = self.observer.with_groups(mapping);
+        self
+    }
+
+    /// Initialize the state
+    pub fn init(&mut self, initial_state: DsfbState<T>) {
+        self.observer.init(initial_state);
+    }
+
+    /// Return the observer to its freshly constructed condition
+    /// (see [`DsfbObserver::reset`])
+    pub fn reset(&mut self) {
+        self.observer.reset();
+    }
+
+    /// Re-seed the observer at `state` (see [`DsfbObserver::reinit_with`])
+    pub fn reinit_with(&mut self, state: DsfbState<T>, keep_envelopes: bool) {
+        self.observer.reinit_with(state, keep_envelopes);
+    }
+
+    /// Perform one step and return the corrected state estimate
+    pub fn step(&mut self, measurements: &[T], dt: T) -> DsfbState<T> {
+        self.step_with_diagnostics(measurements, dt).state
+    }
+
+    /// Perform one step and return diagnostics
+    ///
+    /// Residuals in the diagnostics are in each channel's measurement units.
+    pub fn step_with_diagnostics(&mut self, measurements: &[T], dt: T) -> DsfbStepDiagnostics<T> {
+        if self.all_identity {
+            return self.observer.step_with_diagnostics(measurements, dt);
+        }
+
+        assert_eq!(
+            measurements.len(),
+            self.models.len(),
+            "Measurement count mismatch"
+        );
+
+        let predicted = self.observer.predict(dt);
+        let residuals: Vec<T> = measurements
+            .iter()
+            .zip(&self.models)
+            .map(|(&y, model)| y - model.evaluate(predicted.phi))
+            .collect();
+        self.observer
+            .flag_saturated(measurements.iter().copied().map(Some));
+        let jacobians: Vec<T> = self
+            .models
+            .iter()
+            .map(|model| model.jacobian(predicted.phi))
+            .collect();
+
+        self.observer
+            .correct(predicted, residuals, Some(&jacobians), None, None, dt)
+    }
+
+    /// Measurement models, one per channel
+    pub fn models(&self) -> &[MeasurementModel<T>] {
+        &self.models
+    }
+
+    /// Underlying observer, for trust and uncertainty accessors
+    pub fn observer(&self) -> &DsfbObserver<T> {
+        &self.observer
+    }
+
+    /// Get the current state
+    pub fn state(&self) -> DsfbState<T> {
+        self.observer.state()
+    }
+
+    /// Get trust statistics for all channels
+    pub fn trust_stats(&self) -> &[TrustStats<T>] {
+        self.observer.trust_stats()
+    }
+
+    /// Get trust weight for a specific channel
+    pub fn trust_weight(&self, channel: usize) -> T {
+        self.observer.trust_weight(channel)
+    }
+
+    /// Clear the residual envelope of one channel (see [`DsfbObserver::reset_channel`])
+    pub fn reset_channel(&mut self, channel: usize) {
+        self.observer.reset_channel(channel);
+    }
+
+    /// Re-initialize one channel's envelope (see [`DsfbObserver::reinit_envelope`])
+    pub fn reinit_envelope(&mut self, channel: usize, s0: T) {
+        self.observer.reinit_envelope(channel, s0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity_models_match_observer() {
+        let params = DsfbParams::new(0.5, 0.1, 0.01, 0.9, 0.1);
+        let mut observer = DsfbObserver::new(params, 2);
+        let mut generic = DsfbObserverGeneric::new(
+            params,
+            vec![MeasurementModel::identity(), MeasurementModel::identity()],
+        );
+
+        for k in 0..50 {
+            let y = [0.1 * k as f64, 0.1 * k as f64 + 0.05];
+            observer.step(&y, 0.1);
+            generic.step(&y, 0.1);
+        }
+
+        assert_eq!(observer.state(), generic.state());
+    }
+
+    #[test]
+    fn test_unit_jacobian_matches_identity_update() {
+        let params = DsfbParams::new(0.5, 0.1, 0.01, 0.9, 0.1);
+        let mut observer = DsfbObserver::new(params, 2);
+        let mut generic = DsfbObserverGeneric::new(
+            params,
+            vec![MeasurementModel::identity(), MeasurementModel::scaled(1.0)],
+        );
+
+        for _ in 0..20 {
+            observer.step(&[1.0, 1.2], 0.1);
+            generic.step(&[1.0, 1.2], 0.1);
+        }
+
+        assert!((observer.state().phi - generic.state().phi).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_finite_difference_jacobian() {
+        let model = MeasurementModel::<f64>::function(f64::sin);
+        assert!((model.jacobian(0.3) - 0.3_f64.cos()).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_nonlinear_channels_track_phi() {
+        let params = DsfbParams::new(0.5, 0.1, 0.01, 0.9, 0.1);
+        let mut observer = DsfbObserverGeneric::new(
+            params,
+            vec![
+                MeasurementModel::identity(),
+                MeasurementModel::scaled(2.0),
+                MeasurementModel::with_jacobian(f64::sin, f64::cos),
+            ],
+        );
+
+        let phi = 0.4;
+        for _ in 0..2000 {
+            observer.step(&[phi, 2.0 * phi, phi.sin()], 0.1);
+        }
+
+        assert!((observer.state().phi - phi).abs() < 1e-6);
+        let weights: f64 = (0..3).map(|k| observer.trust_weight(k)).sum();
+        assert!((weights - 1.0).abs() < 1e-10);
+    }
+}
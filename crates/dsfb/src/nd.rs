@@ -0,0 +1,315 @@
+//! N-dimensional DSFB observer
+//!
+//! [`DsfbObserver`](crate::DsfbObserver) tracks one scalar `phi`/`omega`/`alpha`
+//! triplet, so fusing a multi-axis signal (e.g. the three axes of redundant
+//! IMUs) takes one observer per axis, each with its own trust weights.
+//! [`DsfbObserverNd`] tracks vector-valued states over [`DVector`] with one
+//! trust weight per channel shared by all axes: a channel's envelope follows
+//! the Euclidean norm of its residual vector, so a fault on any axis costs the
+//! channel trust on every axis.
+
+use nalgebra::DVector;
+
+use crate::params::DsfbParams;
+use crate::rho::scheduled_rho;
+use crate::scalar::Real;
+use crate::trust::{calculate_trust_weights_with, fused_variance};
+
+/// Vector-valued state of the N-dimensional DSFB observer
+#[derive(Debug, Clone, PartialEq)]
+pub struct DsfbStateNd<T: Real = f64> {
+    /// Position/phase of each axis
+    pub phi: DVector<T>,
+    /// Velocity/frequency (drift) of each axis
+    pub omega: DVector<T>,
+    /// Acceleration/slew of each axis
+    pub alpha: DVector<T>,
+}
+
+impl<T: Real> DsfbStateNd<T> {
+    /// Create a new state
+    ///
+    /// # Panics
+    ///
+    /// If the three components differ in dimension.
+    pub fn new(phi: DVector<T>, omega: DVector<T>, alpha: DVector<T>) -> Self {
+        assert!(
+            omega.len() == phi.len() && alpha.len() == phi.len(),
+            "State dimension mismatch"
+        );
+        Self { phi, omega, alpha }
+    }
+
+    /// Create a zero state of dimension `dim`
+    pub fn zero(dim: usize) -> Self {
+        let zero = DVector::from_element(dim, T::ZERO);
+        Self {
+            phi: zero.clone(),
+            omega: zero.clone(),
+            alpha: zero,
+        }
+    }
+
+    /// Number of axes
+    pub fn dim(&self) -> usize {
+        self.phi.len()
+    }
+}
+
+/// Diagnostics captured for a single N-dimensional observer step
+#[derive(Debug, Clone)]
+pub struct DsfbStepDiagnosticsNd<T: Real = f64> {
+    /// Per-channel residual vectors against the predicted state
+    pub residuals: Vec<DVector<T>>,
+    /// Euclidean norm of each channel's residual, the input to its envelope
+    pub residual_norms: Vec<T>,
+    /// Weighted residual used for the correction step
+    pub aggregate_residual: DVector<T>,
+    /// Normalized trust weight of each channel after the step
+    pub weights: Vec<T>,
+    /// Variance estimate of the fused measurement, per axis
+    pub fused_variance: T,
+    /// Corrected state estimate after the step
+    pub state: DsfbStateNd<T>,
+}
+
+/// DSFB observer over vector-valued measurements with shared channel trust
+///
+/// Each channel measures the full `phi` vector. With `dim == 1` the observer
+/// behaves like [`DsfbObserver`](crate::DsfbObserver).
+pub struct DsfbObserverNd<T: Real = f64> {
+    /// Observer parameters
+    params: DsfbParams<T>,
+    /// Number of measurement channels
+    channels: usize,
+    /// Current state estimate
+    state: DsfbStateNd<T>,
+    /// EMA of each channel's residual norm
+    ema_residuals: Vec<T>,
+    /// Normalized trust weight of each channel
+    weights: Vec<T>,
+    /// Variance estimate of the fused measurement, per axis
+    fused_variance: T,
+    /// Number of completed steps
+    steps: usize,
+}
+
+impl<T: Real> DsfbObserverNd<T> {
+    /// Create an observer for `channels` channels of `dim`-dimensional samples
+    pub fn new(params: DsfbParams<T>, channels: usize, dim: usize) -> Self {
+        Self {
+            channels,
+            state: DsfbStateNd::zero(dim),
+            ema_residuals: vec![T::ZERO; channels],
+            weights: vec![T::ONE / T::from_usize(channels.max(1)); channels],
+            // Uniform weights over empty envelopes: sigma0^2 / channels
+            fused_variance: params.sigma0 * params.sigma0 / T::from_usize(channels.max(1)),
+            steps: 0,
+            params,
+        }
+    }
+
+    /// Initialize the state
+    ///
+    /// # Panics
+    ///
+    /// If `initial_state` has a different dimension than the observer.
+    pub fn init(&mut self, initial_state: DsfbStateNd<T>) {
+        assert_eq!(
+            initial_state.dim(),
+            self.state.dim(),
+            "State dimension mismatch"
+        );
+        self.state = initial_state;
+    }
+
+    /// Return the observer to its freshly constructed condition
+    pub fn reset(&mut self) {
+        *self = Self::new(self.params, self.channels, self.state.dim());
+    }
+
+    /// Perform one step of the DSFB algorithm
+    ///
+    /// `measurements` holds one `dim`-dimensional sample per channel.
+    ///
+    /// # Panics
+    ///
+    /// If there is not one sample per channel or a sample has the wrong dimension.
+    pub fn step(&mut self, measurements: &[DVector<T>], dt: T) -> DsfbStateNd<T> {
+        self.step_with_diagnostics(measurements, dt).state
+    }
+
+    /// Perform one step of the DSFB algorithm and return diagnostics
+    pub fn step_with_diagnostics(
+        &mut self,
+        measurements: &[DVector<T>],
+        dt: T,
+    ) -> DsfbStepDiagnosticsNd<T> {
+        assert_eq!(
+            measurements.len(),
+            self.channels,
+            "Measurement count mismatch"
+        );
+        let dim = self.state.dim();
+        assert!(
+            measurements.iter().all(|y| y.len() == dim),
+            "Measurement dimension mismatch"
+        );
+
+        let predicted = self.predict(dt);
+
+        // Residuals r_k = y_k - phi^- and their norms ||r_k||
+        let residuals: Vec<DVector<T>> = measurements
+            .iter()
+            .map(|y| y.zip_map(&predicted.phi, |y, phi| y - phi))
+            .collect();
+        let residual_norms: Vec<T> = residuals.iter().map(norm).collect();
+
+        // Shared trust: s_k = rho_k*s_k + (1-rho_k)*||r_k||, w_k ~ f(s_k)
+        let rho = scheduled_rho(
+            &self.params.rho_policy,
+            self.params.rho,
+            self.steps,
+            &residual_norms,
+            &self.ema_residuals,
+        );
+        self.weights = calculate_trust_weights_with(
+            &self.params.trust_shape,
+            &residual_norms,
+            &mut self.ema_residuals,
+            &rho,
+            self.params.sigma0,
+            self.params.min_weight,
+        );
+        self.fused_variance =
+            fused_variance(&self.weights, &self.ema_residuals, self.params.sigma0);
+
+        // Aggregate residual: R = sum_k w_k * r_k
+        let mut aggregate_residual = DVector::from_element(dim, T::ZERO);
+        for (r, &w) in residuals.iter().zip(&self.weights) {
+            aggregate_residual = aggregate_residual.zip_map(r, |acc, r| acc + w * r);
+        }
+
+        let gain = |x: &DVector<T>, k: T| x.zip_map(&aggregate_residual, |x, r| x + k * r);
+        self.state = DsfbStateNd {
+            phi: gain(&predicted.phi, self.params.k_phi),
+            omega: gain(&predicted.omega, self.params.k_omega),
+            alpha: gain(&predicted.alpha, self.params.k_alpha),
+        };
+        self.steps += 1;
+
+        DsfbStepDiagnosticsNd {
+            residuals,
+            residual_norms,
+            aggregate_residual,
+            weights: self.weights.clone(),
+            fused_variance: self.fused_variance,
+            state: self.state.clone(),
+        }
+    }
+
+    /// Predicted state for the next step under the constant-slew model
+    pub fn predict(&self, dt: T) -> DsfbStateNd<T> {
+        let state = &self.state;
+        DsfbStateNd {
+            phi: state
+                .phi
+                .zip_map(&state.omega, |phi, omega| phi + omega * dt),
+            omega: state
+                .omega
+                .zip_map(&state.alpha, |omega, alpha| omega + alpha * dt),
+            alpha: state.alpha.clone(),
+        }
+    }
+
+    /// Get the current state
+    pub fn state(&self) -> &DsfbStateNd<T> {
+        &self.state
+    }
+
+    /// Number of axes
+    pub fn dim(&self) -> usize {
+        self.state.dim()
+    }
+
+    /// Get the normalized trust weights of all channels
+    pub fn trust_weights(&self) -> &[T] {
+        &self.weights
+    }
+
+    /// Get trust weight for a specific channel
+    pub fn trust_weight(&self, channel: usize) -> T {
+        self.weights[channel]
+    }
+
+    /// Get the EMA of a specific channel's residual norm
+    pub fn ema_residual(&self, channel: usize) -> T {
+        self.ema_residuals[channel]
+    }
+
+    /// Get the per-axis variance estimate of the trust-weighted fused measurement
+    pub fn fused_uncertainty(&self) -> T {
+        self.fused_variance
+    }
+}
+
+/// Euclidean norm
+fn norm<T: Real>(v: &DVector<T>) -> T {
+    v.iter().map(|&x| x * x).sum::<T>().sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DsfbObserver, DsfbState};
+
+    #[test]
+    fn test_one_axis_matches_scalar_observer() {
+        let params = DsfbParams::new(0.5, 0.1, 0.01, 0.9, 0.1).with_min_weight(0.05);
+        let mut scalar = DsfbObserver::new(params, 3);
+        let mut nd = DsfbObserverNd::new(params, 3, 1);
+        scalar.init(DsfbState::new(0.2, 0.5, 0.0));
+        nd.init(DsfbStateNd::new(
+            DVector::from_element(1, 0.2),
+            DVector::from_element(1, 0.5),
+            DVector::from_element(1, 0.0),
+        ));
+
+        for n in 0..50 {
+            let t = n as f64 * 0.1;
+            let y = [t, t + 0.05, if n > 20 { t + 2.0 } else { t }];
+            let scalar_state = scalar.step(&y, 0.1);
+            let samples: Vec<DVector<f64>> =
+                y.iter().map(|&y| DVector::from_element(1, y)).collect();
+            let nd_state = nd.step(&samples, 0.1);
+
+            assert!((scalar_state.phi - nd_state.phi[0]).abs() < 1e-12);
+            assert!((scalar_state.omega - nd_state.omega[0]).abs() < 1e-12);
+            for k in 0..3 {
+                assert!((scalar.trust_weight(k) - nd.trust_weight(k)).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fault_on_one_axis_costs_trust_on_all() {
+        let mut observer = DsfbObserverNd::new(DsfbParams::default(), 3, 3);
+        let truth = DVector::from_vec(vec![1.0, -2.0, 0.5]);
+        observer.init(DsfbStateNd::new(
+            truth.clone(),
+            DVector::zeros(3),
+            DVector::zeros(3),
+        ));
+
+        let mut faulty = truth.clone();
+        faulty[2] += 3.0;
+        for _ in 0..40 {
+            observer.step(&[truth.clone(), truth.clone(), faulty.clone()], 0.01);
+        }
+
+        assert!(observer.trust_weight(2) < 0.25 * observer.trust_weight(0));
+        // The healthy axes of the faulty channel do not pull the estimate either
+        assert!((observer.state().phi[2] - truth[2]).abs() < 0.5);
+        assert!((observer.state().phi[0] - truth[0]).abs() < 1e-9);
+    }
+}
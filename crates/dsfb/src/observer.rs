@@ -2,54 +2,267 @@
 //!
 //! Implements the Drift-Slew Fusion Bootstrap algorithm
 
+use crate::calibration::Sigma0Calibration;
+use crate::detect::{ChangePoint, CusumDetector, CusumParams};
+use crate::error::DsfbError;
+use crate::group::ChannelGroups;
 use crate::params::DsfbParams;
+use crate::rho::{scheduled_rho, RhoSchedule};
+use crate::saturation::{Rail, SaturationLimits};
+use crate::scalar::Real;
 use crate::state::DsfbState;
-use crate::trust::{calculate_trust_weights, TrustStats};
+use crate::trust::{
+    apply_weight_floor, calculate_multirate_trust_weights_with, fused_variance_per_channel,
+    TrustStats,
+};
+use crate::units::Phase;
 
 /// Diagnostics captured for a single DSFB observer step.
 #[derive(Debug, Clone)]
-pub struct DsfbStepDiagnostics {
+pub struct DsfbStepDiagnostics<T: Real = f64> {
     /// Per-channel measurement residuals against the predicted state.
-    pub residuals: Vec<f64>,
+    pub residuals: Vec<T>,
     /// Weighted residual used for the correction step.
-    pub aggregate_residual: f64,
+    pub aggregate_residual: T,
     /// Trust statistics after the step update.
-    pub trust_stats: Vec<TrustStats>,
+    pub trust_stats: Vec<TrustStats<T>>,
+    /// Variance estimate of the fused channel measurement.
+    pub fused_variance: T,
     /// Corrected state estimate after the step update.
-    pub state: DsfbState,
+    pub state: DsfbState<T>,
+    /// Change points detected during this step (empty without a change detector).
+    pub change_points: Vec<ChangePoint<T>>,
+    /// Rail each channel's sample is pinned at (all `None` without saturation limits).
+    pub saturated: Vec<Option<Rail>>,
 }
 
 /// DSFB Observer
-pub struct DsfbObserver {
+///
+/// Generic over the scalar type; `f64` is the default and `f32` is available
+/// for targets without double-precision hardware.
+pub struct DsfbObserver<T: Real = f64> {
     /// Observer parameters
-    params: DsfbParams,
+    params: DsfbParams<T>,
     /// Number of measurement channels
     channels: usize,
     /// Current state estimate
-    state: DsfbState,
+    state: DsfbState<T>,
     /// EMA residuals for each channel
-    ema_residuals: Vec<f64>,
+    ema_residuals: Vec<T>,
     /// Trust statistics for each channel
-    trust_stats: Vec<TrustStats>,
+    trust_stats: Vec<TrustStats<T>>,
+    /// Variance estimate of the fused channel measurement
+    fused_variance: T,
+    /// Last fresh sample of each channel, held while the channel is stale
+    held_measurements: Vec<Option<T>>,
+    /// Consecutive stale steps of each channel
+    staleness: Vec<usize>,
+    /// Trust multiplier of each channel: `staleness_decay^staleness`
+    staleness_factors: Vec<T>,
+    /// Optional per-channel change detectors on the residual streams
+    detectors: Option<Vec<CusumDetector<T>>>,
+    /// Most recent change point of each channel
+    last_change_points: Vec<Option<ChangePoint<T>>>,
+    /// Optional known saturation limits of each channel
+    saturation_limits: Option<Vec<Option<SaturationLimits<T>>>>,
+    /// Rail each channel's current sample is pinned at
+    saturated: Vec<Option<Rail>>,
+    /// Time of the state estimate, once set by [`DsfbObserver::step_at`]
+    time: Option<T>,
+    /// Time of each channel's last arrival through [`DsfbObserver::step_at`]
+    last_arrivals: Vec<Option<T>>,
+    /// Trust softness of each channel: `params.sigma0` until calibrated
+    sigma0: Vec<T>,
+    /// Optional startup calibration of `sigma0`
+    calibration: Option<Sigma0Calibration<T>>,
+    /// Optional channel groups for hierarchical trust
+    groups: Option<ChannelGroups<T>>,
+    /// Optional custom rho schedule, replacing `params.rho_policy`
+    rho_schedule: Option<Box<dyn RhoSchedule<T> + Send + Sync>>,
+    /// Number of completed steps
+    steps: usize,
 }
 
-impl DsfbObserver {
+impl<T: Real> DsfbObserver<T> {
     /// Create a new DSFB observer
-    pub fn new(params: DsfbParams, channels: usize) -> Self {
+    pub fn new(params: DsfbParams<T>, channels: usize) -> Self {
         Self {
-            params,
             channels,
             state: DsfbState::zero(),
-            ema_residuals: vec![0.0; channels],
+            ema_residuals: vec![T::ZERO; channels],
             trust_stats: vec![TrustStats::new(); channels],
+            // Uniform weights over empty envelopes: sigma0^2 / channels
+            fused_variance: params.sigma0 * params.sigma0 / T::from_usize(channels.max(1)),
+            held_measurements: vec![None; channels],
+            staleness: vec![0; channels],
+            staleness_factors: vec![T::ONE; channels],
+            detectors: None,
+            last_change_points: vec![None; channels],
+            saturation_limits: None,
+            saturated: vec![None; channels],
+            time: None,
+            last_arrivals: vec![None; channels],
+            sigma0: vec![params.sigma0; channels],
+            calibration: None,
+            groups: None,
+            rho_schedule: None,
+            steps: 0,
+            params,
         }
     }
 
+    /// Run a CUSUM change detector on each channel's residual stream
+    ///
+    /// Detected change points are reported in the step diagnostics and through
+    /// [`Self::last_change_point`]. With `reinit_envelope` set, a detection
+    /// re-initializes the channel's envelope to the absolute post-change
+    /// residual level and its bias to the post-change level, so trust reacts
+    /// at once to a small persistent shift the EMA would take
+    /// `~1 / (1 - rho)` steps to absorb.
+    pub fn with_change_detector(mut self, params: CusumParams<T>) -> Self {
+        self.detectors = Some(vec![CusumDetector::new(params); self.channels]);
+        self
+    }
+
+    /// Treat samples at known sensor saturation limits as one-sided information
+    ///
+    /// `limits` holds one entry per channel; `None` leaves a channel
+    /// unlimited. A sample at or beyond a limit only pulls the estimate toward
+    /// the saturated side, and its trust residual is raised to the limits'
+    /// residual floor (see [`SaturationLimits::one_sided`]), so a channel
+    /// pinned at its rail cannot gain trust because the prediction happens to
+    /// sit near the rail. Diagnostics and change detection still see the raw
+    /// residuals.
+    ///
+    /// # Panics
+    ///
+    /// If `limits` does not have one entry per channel.
+    pub fn with_saturation_limits(mut self, limits: Vec<Option<SaturationLimits<T>>>) -> Self {
+        assert_eq!(
+            limits.len(),
+            self.channels,
+            "Saturation limit count mismatch"
+        );
+        self.saturation_limits = Some(limits);
+        self
+    }
+
+    /// Estimate each channel's `sigma0` from its first `samples` steps
+    ///
+    /// Until calibration is complete every usable channel gets equal weight
+    /// (scaled by any staleness decay or quality hints), while the envelopes
+    /// are updated as usual. After `samples` steps each channel's `sigma0` is
+    /// set to the scaled median absolute deviation of its fresh, unsaturated
+    /// residuals (see [`Sigma0Calibration`]), and adaptive weighting starts
+    /// with the next step. A channel without such residuals, or whose
+    /// residuals have zero spread, keeps `params.sigma0`.
+    ///
+    /// # Panics
+    ///
+    /// If `samples` is zero.
+    pub fn with_sigma0_calibration(mut self, samples: usize) -> Self {
+        self.calibration = Some(Sigma0Calibration::new(self.channels, samples));
+        self
+    }
+
+    /// Compose each channel's trust with the trust of its group
+    ///
+    /// Channel `k` belongs to group `mapping[k]`. Each group keeps a residual
+    /// envelope, the EMA (with `rho`) of the mean absolute trust residual over
+    /// its fresh channels, and its raw trust follows from that envelope through
+    /// `params.trust_shape` with the mean `sigma0` of its channels. A
+    /// channel's raw trust is multiplied by its group's before normalization,
+    /// so a cluster of correlated sensors that is disturbed together loses
+    /// weight as a whole even while its members agree with each other. See
+    /// [`ChannelGroups`].
+    ///
+    /// # Panics
+    ///
+    /// If `mapping` does not have one entry per channel.
+    pub fn with_groups(mut self, mapping: Vec<usize>) -> Self {
+        assert_eq!(
+            mapping.len(),
+            self.channels,
+            "Group mapping length mismatch"
+        );
+        self.groups = Some(ChannelGroups::new(mapping));
+        self
+    }
+
+    /// Schedule each channel's envelope forgetting factor with a custom policy
+    ///
+    /// Replaces `params.rho_policy` (see [`RhoSchedule`]). Group envelopes and
+    /// bias/drift estimates keep using `params.rho`.
+    pub fn with_rho_schedule(
+        mut self,
+        schedule: impl RhoSchedule<T> + Send + Sync + 'static,
+    ) -> Self {
+        self.rho_schedule = Some(Box::new(schedule));
+        self
+    }
+
     /// Initialize the state
-    pub fn init(&mut self, initial_state: DsfbState) {
+    pub fn init(&mut self, initial_state: DsfbState<T>) {
         self.state = initial_state;
     }
 
+    /// Return the observer to its freshly constructed condition
+    ///
+    /// The state is zeroed, and every channel's envelope, trust statistics,
+    /// held sample, staleness, and change-detector history are cleared, as are
+    /// the step count, the clock of [`Self::step_at`], and the group
+    /// envelopes. Parameters, the channel count, saturation limits, the group
+    /// mapping, a custom rho schedule, and whether change detection is
+    /// enabled are kept. A `sigma0`
+    /// calibration starts over, and `params.sigma0` applies until it completes
+    /// again.
+    pub fn reset(&mut self) {
+        let detector_params = self
+            .detectors
+            .as_ref()
+            .and_then(|detectors| detectors.first())
+            .map(|detector| *detector.params());
+        let mut fresh = Self::new(self.params, self.channels);
+        if let Some(params) = detector_params {
+            fresh = fresh.with_change_detector(params);
+        }
+        if let Some(calibration) = &self.calibration {
+            fresh = fresh.with_sigma0_calibration(calibration.samples());
+        }
+        fresh.saturation_limits = self.saturation_limits.take();
+        fresh.groups = self.groups.take().map(|mut groups| {
+            groups.reset();
+            groups
+        });
+        fresh.rho_schedule = self.rho_schedule.take();
+        *self = fresh;
+    }
+
+    /// Re-seed the observer at `state`
+    ///
+    /// For recovering from a diverged or non-finite estimate. With
+    /// `keep_envelopes`, each channel's envelope, trust weight, bias and drift
+    /// estimates, and change detector survive, as do the group envelopes, so
+    /// channels that had lost trust do not regain it just because the state
+    /// was re-seeded. Otherwise the
+    /// observer is [`reset`](Self::reset) before the state is set. Either way,
+    /// samples held for stale channels before the re-seed are dropped, so in
+    /// [`Self::step_multirate`] a channel carries weight again only once it
+    /// reports. With `keep_envelopes` the clock of [`Self::step_at`] keeps
+    /// running, `state` is taken to be at the current time, and calibrated
+    /// `sigma0` values are kept.
+    pub fn reinit_with(&mut self, state: DsfbState<T>, keep_envelopes: bool) {
+        if keep_envelopes {
+            self.held_measurements.fill(None);
+            self.last_arrivals.fill(None);
+            self.staleness.fill(0);
+            self.staleness_factors.fill(T::ONE);
+        } else {
+            self.reset();
+        }
+        self.state = state;
+    }
+
     /// Perform one step of the DSFB algorithm
     ///
     /// # Arguments
@@ -58,97 +271,654 @@ impl DsfbObserver {
     ///
     /// # Returns
     /// The corrected state estimate
-    pub fn step(&mut self, measurements: &[f64], dt: f64) -> DsfbState {
+    ///
+    /// # Panics
+    ///
+    /// If there is not one measurement per channel; see [`Self::try_step`].
+    pub fn step(&mut self, measurements: &[T], dt: T) -> DsfbState<T> {
         self.step_with_diagnostics(measurements, dt).state
     }
 
+    /// Perform one step of the DSFB algorithm, or report invalid input
+    ///
+    /// Like [`Self::step`], but returns
+    /// [`DsfbError::MeasurementCountMismatch`] instead of panicking. The
+    /// observer is unchanged on error.
+    pub fn try_step(&mut self, measurements: &[T], dt: T) -> Result<DsfbState<T>, DsfbError> {
+        self.try_step_with_diagnostics(measurements, dt)
+            .map(|diagnostics| diagnostics.state)
+    }
+
+    /// Perform one step with typed phase measurements (see [`Self::step`])
+    pub fn step_phases(&mut self, measurements: &[Phase<T>], dt: T) -> DsfbState<T> {
+        let raw: Vec<T> = measurements.iter().map(|y| y.0).collect();
+        self.step(&raw, dt)
+    }
+
     /// Perform one step of the DSFB algorithm and return diagnostics.
-    pub fn step_with_diagnostics(&mut self, measurements: &[f64], dt: f64) -> DsfbStepDiagnostics {
+    pub fn step_with_diagnostics(&mut self, measurements: &[T], dt: T) -> DsfbStepDiagnostics<T> {
+        self.try_step_with_diagnostics(measurements, dt)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Perform one step and return diagnostics, or report invalid input
+    /// (see [`Self::try_step`])
+    pub fn try_step_with_diagnostics(
+        &mut self,
+        measurements: &[T],
+        dt: T,
+    ) -> Result<DsfbStepDiagnostics<T>, DsfbError> {
+        if measurements.len() != self.channels {
+            return Err(DsfbError::MeasurementCountMismatch {
+                expected: self.channels,
+                got: measurements.len(),
+            });
+        }
+        Ok(self.step_fresh(measurements, None, dt))
+    }
+
+    /// Perform one step with external per-channel quality hints
+    ///
+    /// `quality[k]` in `[0, 1]` (e.g. from built-in-test status or a
+    /// signal-strength indicator) multiplies channel `k`'s data-driven raw
+    /// trust before normalization, so system-level knowledge modulates the
+    /// trust without replacing it: the envelopes are updated from the
+    /// residuals as usual, and a channel with hint `1` is treated exactly as
+    /// in [`Self::step`]. A channel with hint `0` gets zero weight and is
+    /// exempt from the weight floor; if every hint is `0` all weights are
+    /// zero and the state follows the prediction. Hints apply to this step only.
+    ///
+    /// # Panics
+    ///
+    /// If `quality` does not have one entry per channel or an entry lies
+    /// outside `[0, 1]`.
+    pub fn step_with_quality(&mut self, measurements: &[T], quality: &[T], dt: T) -> DsfbState<T> {
+        self.step_with_quality_diagnostics(measurements, quality, dt)
+            .state
+    }
+
+    /// Perform one step with quality hints (see [`Self::step_with_quality`])
+    /// and return diagnostics.
+    pub fn step_with_quality_diagnostics(
+        &mut self,
+        measurements: &[T],
+        quality: &[T],
+        dt: T,
+    ) -> DsfbStepDiagnostics<T> {
+        assert_eq!(quality.len(), self.channels, "Quality hint count mismatch");
+        assert!(
+            quality.iter().all(|&q| q >= T::ZERO && q <= T::ONE),
+            "Quality hints must lie in [0, 1]"
+        );
+        self.step_fresh(measurements, Some(quality), dt)
+    }
+
+    /// One step in which every channel has a new sample.
+    fn step_fresh(
+        &mut self,
+        measurements: &[T],
+        quality: Option<&[T]>,
+        dt: T,
+    ) -> DsfbStepDiagnostics<T> {
         assert_eq!(
             measurements.len(),
             self.channels,
             "Measurement count mismatch"
         );
 
-        // Predict step
-        let phi_pred = self.state.phi + self.state.omega * dt;
-        let omega_pred = self.state.omega + self.state.alpha * dt;
-        let alpha_pred = self.state.alpha;
+        // Every channel is fresh
+        for (k, &y) in measurements.iter().enumerate() {
+            self.held_measurements[k] = Some(y);
+            self.staleness[k] = 0;
+            self.staleness_factors[k] = T::ONE;
+        }
+
+        let predicted = self.predict(dt);
 
         // Measurement function h_k(phi^-) = phi^- (identity)
-        let h_pred = phi_pred;
+        let h_pred = predicted.phi;
 
         // Compute residuals: r_k = y_k - h_k(phi^-)
-        let residuals: Vec<f64> = measurements.iter().map(|&y| y - h_pred).collect();
+        let residuals: Vec<T> = measurements.iter().map(|&y| y - h_pred).collect();
+        self.flag_saturated(measurements.iter().copied().map(Some));
+
+        self.correct(predicted, residuals, None, None, quality, dt)
+    }
+
+    /// Perform one step with channels that may not have a new sample
+    ///
+    /// `None` marks a stale channel, e.g. a slow sensor repeating its last
+    /// value between updates. A stale channel's residual is taken against its
+    /// last fresh sample, its EMA residual and bias/drift estimates are not
+    /// updated, and its raw trust is multiplied by `staleness_decay` for every
+    /// consecutive stale step. Channels that have never reported get zero
+    /// weight.
+    pub fn step_multirate(&mut self, measurements: &[Option<T>], dt: T) -> DsfbState<T> {
+        self.step_multirate_with_diagnostics(measurements, dt).state
+    }
+
+    /// Perform one multi-rate step (see [`Self::step_multirate`]) and return diagnostics.
+    pub fn step_multirate_with_diagnostics(
+        &mut self,
+        measurements: &[Option<T>],
+        dt: T,
+    ) -> DsfbStepDiagnostics<T> {
+        assert_eq!(
+            measurements.len(),
+            self.channels,
+            "Measurement count mismatch"
+        );
+
+        let mut fresh = vec![false; self.channels];
+        for (k, &measurement) in measurements.iter().enumerate() {
+            match measurement {
+                Some(y) => {
+                    self.held_measurements[k] = Some(y);
+                    self.staleness[k] = 0;
+                    self.staleness_factors[k] = T::ONE;
+                    fresh[k] = true;
+                }
+                None if self.held_measurements[k].is_some() => {
+                    self.staleness[k] = self.staleness[k].saturating_add(1);
+                    self.staleness_factors[k] *= self.params.staleness_decay;
+                }
+                None => self.staleness_factors[k] = T::ZERO,
+            }
+        }
+
+        let predicted = self.predict(dt);
+        let h_pred = predicted.phi;
+        let residuals: Vec<T> = self
+            .held_measurements
+            .iter()
+            .map(|held| held.map_or(T::ZERO, |y| y - h_pred))
+            .collect();
+        let held = self.held_measurements.clone();
+        self.flag_saturated(held);
+
+        self.correct(predicted, residuals, None, Some(&fresh), None, dt)
+    }
+
+    /// Fuse one time-stamped sample of one channel
+    ///
+    /// For asynchronous, irregularly sampled telemetry. The state is
+    /// propagated from the previous arrival to `t` and corrected with the
+    /// new sample as in [`Self::step_multirate`], with every other channel
+    /// stale. The first arrival starts the clock: the state set by
+    /// [`Self::init`] is taken to be at that time and is corrected with the
+    /// sample through a zero-length (`dt = 0`) step. Samples of several
+    /// channels with the same timestamp should go through
+    /// [`Self::step_arrivals`] together, since separate zero-length steps
+    /// would apply the correction once per channel.
+    ///
+    /// Staleness counts steps, not elapsed time: a stale channel's trust
+    /// decays by `staleness_decay` at every arrival of any other channel.
+    ///
+    /// # Panics
+    ///
+    /// If `channel` is out of range or `t` precedes the previous arrival.
+    pub fn step_at(&mut self, t: T, channel: usize, value: T) -> DsfbState<T> {
+        self.step_arrivals(t, &[(channel, value)]).state
+    }
+
+    /// Fuse the samples `(channel, value)` that arrived together at time `t`
+    /// (see [`Self::step_at`]) and return diagnostics
+    ///
+    /// Channels without a sample in `arrivals` are stale. If a channel appears
+    /// more than once, its last sample is used.
+    ///
+    /// # Panics
+    ///
+    /// If a channel index is out of range or `t` precedes the previous arrival.
+    pub fn step_arrivals(&mut self, t: T, arrivals: &[(usize, T)]) -> DsfbStepDiagnostics<T> {
+        let dt = match self.time {
+            Some(last) => {
+                assert!(t >= last, "Arrival times must be non-decreasing");
+                t - last
+            }
+            None => T::ZERO,
+        };
+
+        let mut measurements = vec![None; self.channels];
+        for &(channel, value) in arrivals {
+            assert!(channel < self.channels, "Channel index out of range");
+            measurements[channel] = Some(value);
+            self.last_arrivals[channel] = Some(t);
+        }
+
+        let diagnostics = self.step_multirate_with_diagnostics(&measurements, dt);
+        self.time = Some(t);
+        diagnostics
+    }
+
+    /// State extrapolated to time `t` under the constant-slew model, without
+    /// changing the observer
+    ///
+    /// For reading the estimate between arrivals. Before the first
+    /// [`Self::step_at`] there is no clock and the current state is returned.
+    pub fn predict_at(&self, t: T) -> DsfbState<T> {
+        match self.time {
+            Some(time) => self.predict(t - time),
+            None => self.state,
+        }
+    }
+
+    /// Time of the state estimate, advanced by every step once
+    /// [`Self::step_at`] has started the clock
+    pub fn time(&self) -> Option<T> {
+        self.time
+    }
+
+    /// Time of a channel's last sample through [`Self::step_at`]
+    pub fn channel_last_arrival(&self, channel: usize) -> Option<T> {
+        self.last_arrivals[channel]
+    }
+
+    /// Predicted state for the next step under the constant-slew model.
+    pub(crate) fn predict(&self, dt: T) -> DsfbState<T> {
+        DsfbState::new(
+            self.state.phi + self.state.omega * dt,
+            self.state.omega + self.state.alpha * dt,
+            self.state.alpha,
+        )
+    }
+
+    /// Record the rail each channel's sample is pinned at; `None` samples are
+    /// never saturated.
+    pub(crate) fn flag_saturated(&mut self, samples: impl IntoIterator<Item = Option<T>>) {
+        let Some(limits) = &self.saturation_limits else {
+            return;
+        };
+        for ((rail, limits), sample) in self.saturated.iter_mut().zip(limits).zip(samples) {
+            *rail = limits
+                .as_ref()
+                .zip(sample)
+                .and_then(|(limits, y)| limits.rail(y));
+        }
+    }
+
+    /// Trust update and correction from measurement-space residuals.
+    ///
+    /// `jacobians` holds `dh_k/dphi` at the predicted state; `None` means every
+    /// channel is the identity. `fresh` flags the channels with a new sample;
+    /// `None` means every channel is fresh. `quality` multiplies each channel's
+    /// raw trust (see [`Self::step_with_quality`]). Channels flagged by
+    /// [`Self::flag_saturated`] use one-sided correction and trust residuals.
+    pub(crate) fn correct(
+        &mut self,
+        predicted: DsfbState<T>,
+        residuals: Vec<T>,
+        jacobians: Option<&[T]>,
+        fresh: Option<&[bool]>,
+        quality: Option<&[T]>,
+        dt: T,
+    ) -> DsfbStepDiagnostics<T> {
+        let change_points = self.detect_changes(&residuals, fresh);
+
+        // One-sided residuals for saturated samples
+        let mut correction_residuals = residuals.clone();
+        let mut trust_residuals = residuals.clone();
+        if let Some(limits) = &self.saturation_limits {
+            for (k, rail) in self.saturated.iter().enumerate() {
+                if let (Some(rail), Some(limits)) = (rail, &limits[k]) {
+                    (correction_residuals[k], trust_residuals[k]) =
+                        limits.one_sided(*rail, residuals[k]);
+                }
+            }
+        }
+
+        // Raw-trust multipliers: staleness decay, times any quality hints
+        let factors: Vec<T> = match (fresh, quality) {
+            (None, None) => vec![T::ONE; self.channels],
+            (Some(_), None) => self.staleness_factors.clone(),
+            (_, Some(quality)) => self
+                .staleness_factors
+                .iter()
+                .zip(quality)
+                .map(|(&staleness, &q)| staleness * q)
+                .collect(),
+        };
+        let all_fresh = vec![true; self.channels];
+        let fresh_flags = fresh.unwrap_or(&all_fresh);
+
+        // Group trust multiplies each member's raw trust
+        let trust_factors: Vec<T> = match &mut self.groups {
+            Some(groups) => groups
+                .update(
+                    &self.params.trust_shape,
+                    &trust_residuals,
+                    fresh_flags,
+                    self.params.rho,
+                    &self.sigma0,
+                )
+                .iter()
+                .zip(&factors)
+                .map(|(&group_trust, &f)| group_trust * f)
+                .collect(),
+            None => factors.clone(),
+        };
+
+        // Forgetting factor of each channel's envelope update
+        let rho = match &self.rho_schedule {
+            Some(schedule) => scheduled_rho(
+                schedule.as_ref(),
+                self.params.rho,
+                self.steps,
+                &trust_residuals,
+                &self.ema_residuals,
+            ),
+            None => scheduled_rho(
+                &self.params.rho_policy,
+                self.params.rho,
+                self.steps,
+                &trust_residuals,
+                &self.ema_residuals,
+            ),
+        };
 
         // Calculate trust weights
-        let weights = calculate_trust_weights(
-            &residuals,
+        let mut weights = calculate_multirate_trust_weights_with(
+            &self.params.trust_shape,
+            &trust_residuals,
             &mut self.ema_residuals,
-            self.params.rho,
-            self.params.sigma0,
+            fresh_flags,
+            &trust_factors,
+            &rho,
+            &self.sigma0,
+            self.params.min_weight,
         );
+        if self.is_calibrating() {
+            weights = calibration_weights(&factors, self.params.min_weight);
+        }
+
+        // Collect calibration residuals from fresh, unsaturated samples
+        if let Some(calibration) = &mut self.calibration {
+            let samples = (0..self.channels)
+                .map(|k| (fresh_flags[k] && self.saturated[k].is_none()).then_some(residuals[k]));
+            if let Some(sigma0) = calibration.record(samples, self.params.sigma0) {
+                self.sigma0.copy_from_slice(sigma0);
+            }
+        }
 
-        // Store trust stats
+        // Store trust stats and per-channel drift estimates
         for (k, &weight) in weights.iter().enumerate().take(self.channels) {
             self.trust_stats[k].residual_ema = self.ema_residuals[k];
             self.trust_stats[k].weight = weight;
+            if fresh.map_or(true, |fresh| fresh[k]) {
+                self.trust_stats[k].update_drift(correction_residuals[k], self.params.rho, dt);
+            }
         }
 
-        // Aggregate residual: R = sum_k w_k * r_k
-        let aggregate_residual: f64 = residuals
-            .iter()
-            .zip(weights.iter())
-            .map(|(&r, &w)| w * r)
-            .sum();
+        // Fused variance: sum_k w_k^2 * (sigma0_k + s_k)^2
+        self.fused_variance =
+            fused_variance_per_channel(&weights, &self.ema_residuals, &self.sigma0);
+
+        let aggregate_residual: T = match jacobians {
+            // Aggregate residual: R = sum_k w_k * r_k
+            None => correction_residuals
+                .iter()
+                .zip(weights.iter())
+                .map(|(&r, &w)| w * r)
+                .sum(),
+            // Weighted Gauss-Newton step in phi: R = sum_k w_k H_k r_k / sum_k w_k H_k^2
+            Some(jacobians) => {
+                let mut numerator = T::ZERO;
+                let mut denominator = T::ZERO;
+                for ((&r, &w), &h) in correction_residuals
+                    .iter()
+                    .zip(weights.iter())
+                    .zip(jacobians)
+                {
+                    numerator += w * h * r;
+                    denominator += w * h * h;
+                }
+                if denominator > T::EPSILON {
+                    numerator / denominator
+                } else {
+                    T::ZERO
+                }
+            }
+        };
 
         // Correct step
-        let phi = phi_pred + self.params.k_phi * aggregate_residual;
-        let omega = omega_pred + self.params.k_omega * aggregate_residual;
-        let alpha = alpha_pred + self.params.k_alpha * aggregate_residual;
+        let phi = predicted.phi + self.params.k_phi * aggregate_residual;
+        let omega = predicted.omega + self.params.k_omega * aggregate_residual;
+        let alpha = predicted.alpha + self.params.k_alpha * aggregate_residual;
 
         self.state = DsfbState::new(phi, omega, alpha);
+        self.steps += 1;
+        if let Some(time) = &mut self.time {
+            *time += dt;
+        }
         DsfbStepDiagnostics {
             residuals,
             aggregate_residual,
             trust_stats: self.trust_stats.clone(),
+            fused_variance: self.fused_variance,
             state: self.state,
+            change_points,
+            saturated: self.saturated.clone(),
+        }
+    }
+
+    /// Feed fresh residuals to the change detectors and apply envelope resets.
+    ///
+    /// Runs before the trust update, so a re-initialized envelope already
+    /// includes the detecting residual.
+    fn detect_changes(&mut self, residuals: &[T], fresh: Option<&[bool]>) -> Vec<ChangePoint<T>> {
+        let Some(detectors) = self.detectors.as_mut() else {
+            return Vec::new();
+        };
+
+        let mut change_points = Vec::new();
+        for (k, detector) in detectors.iter_mut().enumerate() {
+            if !fresh.map_or(true, |fresh| fresh[k]) {
+                continue;
+            }
+            let Some((run_length, shift)) = detector.update(residuals[k]) else {
+                continue;
+            };
+
+            let level = detector.reference();
+            let change = ChangePoint {
+                channel: k,
+                onset_step: (self.steps + 1).saturating_sub(run_length),
+                detected_step: self.steps,
+                shift,
+                level,
+            };
+            if detector.params().reinit_envelope {
+                self.ema_residuals[k] = level.abs();
+                let stats = &mut self.trust_stats[k];
+                stats.residual_ema = level.abs();
+                stats.bias = level;
+                stats.drift_rate = T::ZERO;
+            }
+            self.last_change_points[k] = Some(change);
+            change_points.push(change);
+        }
+
+        change_points
+    }
+
+    /// Clear the residual envelope and bias/drift estimates of one channel
+    ///
+    /// Intended for a supervisor to call after maintenance or re-calibration of
+    /// a single channel; the state and the other channels are untouched. The
+    /// channel's weight is recomputed from the cleared envelope on the next step.
+    pub fn reset_channel(&mut self, channel: usize) {
+        self.reinit_envelope(channel, T::ZERO);
+    }
+
+    /// Re-initialize the residual envelope of one channel to `s0`
+    ///
+    /// The bias and drift-rate estimates are cleared as well. A non-zero `s0`
+    /// lets a re-calibrated channel re-enter with reduced trust that decays
+    /// toward its measured envelope.
+    pub fn reinit_envelope(&mut self, channel: usize, s0: T) {
+        assert!(channel < self.channels, "Channel index out of range");
+        self.ema_residuals[channel] = s0;
+        let stats = &mut self.trust_stats[channel];
+        stats.residual_ema = s0;
+        stats.bias = T::ZERO;
+        stats.drift_rate = T::ZERO;
+        if let Some(detectors) = self.detectors.as_mut() {
+            detectors[channel].reset(T::ZERO);
         }
     }
 
     /// Get the current state
-    pub fn state(&self) -> DsfbState {
+    pub fn state(&self) -> DsfbState<T> {
         self.state
     }
 
     /// Get trust statistics for all channels
-    pub fn trust_stats(&self) -> &[TrustStats] {
+    pub fn trust_stats(&self) -> &[TrustStats<T>] {
         &self.trust_stats
     }
 
     /// Get trust weight for a specific channel
-    pub fn trust_weight(&self, channel: usize) -> f64 {
-        self.trust_stats[channel].weight
+    ///
+    /// # Panics
+    ///
+    /// If `channel` is out of range; see [`Self::try_trust_weight`].
+    pub fn trust_weight(&self, channel: usize) -> T {
+        self.try_trust_weight(channel)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Get trust weight for a specific channel, or
+    /// [`DsfbError::ChannelOutOfRange`]
+    pub fn try_trust_weight(&self, channel: usize) -> Result<T, DsfbError> {
+        self.trust_stats
+            .get(channel)
+            .map(|stats| stats.weight)
+            .ok_or(DsfbError::ChannelOutOfRange {
+                channel,
+                channels: self.channels,
+            })
     }
 
     /// Get EMA residual for a specific channel
-    pub fn ema_residual(&self, channel: usize) -> f64 {
+    pub fn ema_residual(&self, channel: usize) -> T {
         self.trust_stats[channel].residual_ema
     }
+
+    /// Get the bias estimate of a specific channel relative to the fused state
+    pub fn channel_bias(&self, channel: usize) -> T {
+        self.trust_stats[channel].bias
+    }
+
+    /// Get the drift-rate estimate of a specific channel relative to the fused state
+    pub fn channel_drift_rate(&self, channel: usize) -> T {
+        self.trust_stats[channel].drift_rate
+    }
+
+    /// Get the number of consecutive stale steps of a specific channel
+    pub fn channel_staleness(&self, channel: usize) -> usize {
+        self.staleness[channel]
+    }
+
+    /// Get the rail the latest sample of a specific channel is pinned at
+    ///
+    /// Always `None` unless the observer was built with
+    /// [`Self::with_saturation_limits`].
+    pub fn channel_saturation(&self, channel: usize) -> Option<Rail> {
+        self.saturated[channel]
+    }
+
+    /// Get the most recent change point detected on a specific channel
+    ///
+    /// Always `None` unless the observer was built with
+    /// [`Self::with_change_detector`].
+    pub fn last_change_point(&self, channel: usize) -> Option<ChangePoint<T>> {
+        self.last_change_points[channel]
+    }
+
+    /// Get the channel groups and their envelopes, if group trust is enabled
+    ///
+    /// Always `None` unless the observer was built with [`Self::with_groups`].
+    pub fn groups(&self) -> Option<&ChannelGroups<T>> {
+        self.groups.as_ref()
+    }
+
+    /// Get the change detector of a specific channel, if detection is enabled
+    pub fn change_detector(&self, channel: usize) -> Option<&CusumDetector<T>> {
+        self.detectors.as_ref().map(|detectors| &detectors[channel])
+    }
+
+    /// Whether the observer is still collecting residuals for its `sigma0`
+    /// calibration
+    ///
+    /// Always `false` unless the observer was built with
+    /// [`Self::with_sigma0_calibration`].
+    pub fn is_calibrating(&self) -> bool {
+        self.calibration
+            .as_ref()
+            .is_some_and(|calibration| !calibration.is_complete())
+    }
+
+    /// Get the calibrated `sigma0` of every channel, once calibration is complete
+    pub fn calibrated_sigma0(&self) -> Option<&[T]> {
+        self.calibration
+            .as_ref()
+            .and_then(Sigma0Calibration::sigma0)
+    }
+
+    /// Get the trust softness currently used for a specific channel
+    pub fn channel_sigma0(&self, channel: usize) -> T {
+        self.sigma0[channel]
+    }
+
+    /// Get the variance estimate of the trust-weighted fused measurement
+    ///
+    /// Per-channel variances come from the residual envelopes and are
+    /// propagated through the current trust weights, so the value grows when
+    /// all channels are disturbed and shrinks as trusted channels agree.
+    pub fn fused_uncertainty(&self) -> T {
+        self.fused_variance
+    }
+}
+
+/// Weights while `sigma0` is being calibrated: proportional to the raw-trust
+/// multipliers alone, so every usable channel starts out equal.
+fn calibration_weights<T: Real>(factors: &[T], min_weight: T) -> Vec<T> {
+    let sum: T = factors.iter().copied().sum();
+    let mut weights: Vec<T> = factors
+        .iter()
+        .map(|&f| if sum > T::ZERO { f / sum } else { T::ZERO })
+        .collect();
+    let usable: Vec<bool> = factors.iter().map(|&f| f > T::ZERO).collect();
+    apply_weight_floor(&mut weights, min_weight, &usable);
+    weights
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rho::RhoPolicy;
+    use crate::units::{Rate, Slew};
 
     #[test]
     fn test_observer_creation() {
-        let params = DsfbParams::default();
+        let params: DsfbParams = DsfbParams::default();
         let observer = DsfbObserver::new(params, 2);
         assert_eq!(observer.channels, 2);
         assert_eq!(observer.state.phi, 0.0);
     }
 
+    #[test]
+    fn test_step_phases_matches_untyped_step() {
+        let mut typed = DsfbObserver::new(DsfbParams::default(), 2);
+        let mut raw = DsfbObserver::new(DsfbParams::default(), 2);
+        typed.init(DsfbState::from_units(Phase(0.0), Rate(0.5), Slew(0.0)));
+        raw.init(DsfbState::new(0.0, 0.5, 0.0));
+
+        let state = typed.step_phases(&[Phase(1.0), Phase(1.05)], 0.01);
+        assert_eq!(state, raw.step(&[1.0, 1.05], 0.01));
+        assert_eq!(state.rate(), Rate(state.omega));
+    }
+
     #[test]
     fn test_observer_step_no_residual() {
         let params = DsfbParams::new(0.5, 0.1, 0.01, 0.9, 0.1);
@@ -163,6 +933,58 @@ mod tests {
         assert!(state.phi > 1.0);
     }
 
+    #[test]
+    fn test_quality_hints_scale_raw_trust() {
+        let params = DsfbParams::new(0.5, 0.1, 0.01, 0.9, 0.1);
+        let measurements = [1.0, 1.05, 0.98];
+        let mut plain = DsfbObserver::new(params, 3);
+        let mut full = DsfbObserver::new(params, 3);
+        let mut hinted = DsfbObserver::new(params, 3);
+
+        let plain_step = plain.step_with_diagnostics(&measurements, 0.1);
+        let full_step = full.step_with_quality_diagnostics(&measurements, &[1.0, 1.0, 1.0], 0.1);
+        assert_eq!(full_step.state, plain_step.state);
+        assert_eq!(full.trust_stats(), plain.trust_stats());
+
+        let hinted_step =
+            hinted.step_with_quality_diagnostics(&measurements, &[0.5, 1.0, 0.0], 0.1);
+        // Envelopes still follow the residuals; only the weights change
+        assert_eq!(hinted.ema_residual(0), plain.ema_residual(0));
+        assert_eq!(hinted.trust_weight(2), 0.0);
+        let plain_ratio = plain.trust_weight(0) / plain.trust_weight(1);
+        let hinted_ratio = hinted.trust_weight(0) / hinted.trust_weight(1);
+        assert!((hinted_ratio - 0.5 * plain_ratio).abs() < 1e-12);
+        let sum: f64 = hinted_step.trust_stats.iter().map(|s| s.weight).sum();
+        assert!((sum - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_saturated_channel_is_one_sided() {
+        let params = DsfbParams::new(0.5, 0.1, 0.01, 0.9, 0.1);
+        let limits = SaturationLimits::symmetric(1.0).with_residual_floor(0.5);
+        let mut plain = DsfbObserver::new(params, 2);
+        let mut aware =
+            DsfbObserver::new(params, 2).with_saturation_limits(vec![Some(limits), None]);
+        plain.init(DsfbState::new(1.05, 0.0, 0.0));
+        aware.init(DsfbState::new(1.05, 0.0, 0.0));
+
+        // Channel 0 is pinned at +1 while the prediction sits just beyond the rail
+        let measurements = [1.0, 1.2];
+        let plain_step = plain.step_with_diagnostics(&measurements, 0.1);
+        let aware_step = aware.step_with_diagnostics(&measurements, 0.1);
+
+        assert_eq!(aware_step.saturated, vec![Some(Rail::Upper), None]);
+        assert_eq!(aware.channel_saturation(0), Some(Rail::Upper));
+        assert_eq!(plain_step.saturated, vec![None, None]);
+        assert_eq!(aware_step.residuals, plain_step.residuals);
+        // The rail reading no longer looks like the best channel...
+        assert!(plain.trust_weight(0) > plain.trust_weight(1));
+        assert!(aware.trust_weight(0) < aware.trust_weight(1));
+        // ...and it does not drag the estimate back toward the rail
+        assert!(aware_step.state.phi > plain_step.state.phi);
+        assert!(aware_step.state.phi > 1.05);
+    }
+
     #[test]
     fn test_observer_trust_weights_sum() {
         let params = DsfbParams::default();
@@ -174,4 +996,434 @@ mod tests {
         let sum: f64 = (0..3).map(|i| observer.trust_weight(i)).sum();
         assert!((sum - 1.0).abs() < 1e-10);
     }
+
+    #[test]
+    fn test_fused_uncertainty_grows_with_common_disturbance() {
+        let params = DsfbParams::new(0.5, 0.1, 0.01, 0.9, 0.1);
+        let mut observer = DsfbObserver::new(params, 2);
+        let prior = observer.fused_uncertainty();
+        assert!((prior - 0.005).abs() < 1e-12);
+
+        observer.step(&[0.0, 0.0], 0.1);
+        let quiet = observer.fused_uncertainty();
+        let diagnostics = observer.step_with_diagnostics(&[2.0, 2.0], 0.1);
+
+        assert_eq!(diagnostics.fused_variance, observer.fused_uncertainty());
+        assert!(observer.fused_uncertainty() > quiet);
+    }
+
+    #[test]
+    fn test_channel_bias_separates_offset_channel() {
+        let params = DsfbParams::new(0.5, 0.1, 0.01, 0.9, 0.1);
+        let mut observer = DsfbObserver::new(params, 3);
+        observer.init(DsfbState::new(1.0, 0.0, 0.0));
+
+        for _ in 0..500 {
+            observer.step(&[1.0, 1.0, 1.3], 0.1);
+        }
+
+        let relative_bias = observer.channel_bias(2) - observer.channel_bias(0);
+        assert!((relative_bias - 0.3).abs() < 1e-6);
+        assert!(observer.channel_drift_rate(2).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_reset_channel_restores_trust() {
+        let params = DsfbParams::new(0.5, 0.1, 0.01, 0.9, 0.1);
+        let mut observer = DsfbObserver::new(params, 3);
+        observer.init(DsfbState::new(1.0, 0.0, 0.0));
+
+        for _ in 0..50 {
+            observer.step(&[1.0, 1.0, 3.0], 0.1);
+        }
+        let faulted_weight = observer.trust_weight(2);
+        let healthy_ema = observer.ema_residual(0);
+
+        observer.reset_channel(2);
+        assert_eq!(observer.ema_residual(2), 0.0);
+        assert_eq!(observer.channel_bias(2), 0.0);
+        assert_eq!(observer.ema_residual(0), healthy_ema);
+
+        let state = observer.state();
+        observer.step(&[state.phi, state.phi, state.phi], 0.1);
+        assert!(observer.trust_weight(2) > faulted_weight);
+    }
+
+    #[test]
+    fn test_reset_matches_fresh_observer() {
+        let params = DsfbParams::new(0.5, 0.1, 0.01, 0.9, 0.1);
+        let cusum = CusumParams::new(0.05, 0.5);
+        let mut observer = DsfbObserver::new(params, 2).with_change_detector(cusum);
+        observer.init(DsfbState::new(1.0, 0.2, 0.0));
+        for _ in 0..20 {
+            observer.step(&[1.0, 2.0], 0.1);
+        }
+
+        observer.reset();
+        let mut fresh = DsfbObserver::new(params, 2).with_change_detector(cusum);
+        assert_eq!(observer.state(), DsfbState::zero());
+        assert_eq!(observer.trust_stats(), fresh.trust_stats());
+        assert_eq!(observer.fused_uncertainty(), fresh.fused_uncertainty());
+        assert_eq!(observer.change_detector(1), fresh.change_detector(1));
+        assert_eq!(
+            observer.step(&[0.3, 0.5], 0.1),
+            fresh.step(&[0.3, 0.5], 0.1)
+        );
+    }
+
+    #[test]
+    fn test_reinit_with_keeps_or_clears_envelopes() {
+        let params = DsfbParams::new(0.5, 0.1, 0.01, 0.9, 0.1);
+        let mut observer = DsfbObserver::new(params, 2);
+        observer.init(DsfbState::new(1.0, 0.0, 0.0));
+        for _ in 0..30 {
+            observer.step(&[1.0, 3.0], 0.1);
+        }
+        let faulted_ema = observer.ema_residual(1);
+        let faulted_weight = observer.trust_weight(1);
+
+        let seed = DsfbState::new(2.0, 0.0, 0.0);
+        observer.reinit_with(seed, true);
+        assert_eq!(observer.state(), seed);
+        assert_eq!(observer.ema_residual(1), faulted_ema);
+        assert_eq!(observer.trust_weight(1), faulted_weight);
+
+        observer.reinit_with(seed, false);
+        assert_eq!(observer.state(), seed);
+        assert_eq!(observer.ema_residual(1), 0.0);
+        assert_eq!(observer.trust_weight(1), 1.0);
+    }
+
+    #[test]
+    fn test_stale_channel_does_not_gain_trust() {
+        let params = DsfbParams::new(0.5, 0.1, 0.01, 0.9, 0.1).with_staleness_decay(0.8);
+        let mut observer = DsfbObserver::new(params, 2);
+        observer.init(DsfbState::new(1.0, 0.0, 0.0));
+
+        for _ in 0..20 {
+            observer.step(&[1.0, 1.2], 0.1);
+        }
+        let slow_ema = observer.ema_residual(1);
+        let slow_bias = observer.channel_bias(1);
+
+        let mut previous = observer.trust_weight(1);
+        for step in 1..=5 {
+            observer.step_multirate(&[Some(1.0), None], 0.1);
+            assert_eq!(observer.channel_staleness(1), step);
+            assert_eq!(observer.ema_residual(1), slow_ema);
+            assert_eq!(observer.channel_bias(1), slow_bias);
+            assert!(observer.trust_weight(1) < previous);
+            previous = observer.trust_weight(1);
+        }
+
+        observer.step_multirate(&[Some(1.0), Some(1.2)], 0.1);
+        assert_eq!(observer.channel_staleness(1), 0);
+        assert!(observer.trust_weight(1) > previous);
+    }
+
+    #[test]
+    fn test_multirate_all_fresh_matches_step() {
+        let params = DsfbParams::new(0.5, 0.1, 0.01, 0.9, 0.1);
+        let mut observer = DsfbObserver::new(params, 2);
+        let mut multirate = DsfbObserver::new(params, 2);
+
+        for k in 0..30 {
+            let y = [0.1 * k as f64, 0.1 * k as f64 + 0.05];
+            observer.step(&y, 0.1);
+            multirate.step_multirate(&[Some(y[0]), Some(y[1])], 0.1);
+        }
+
+        assert_eq!(observer.state(), multirate.state());
+        assert_eq!(observer.trust_stats(), multirate.trust_stats());
+    }
+
+    #[test]
+    fn test_step_at_matches_multirate_with_elapsed_dt() {
+        let params = DsfbParams::new(0.5, 0.1, 0.01, 0.9, 0.1);
+        let mut streaming = DsfbObserver::new(params, 2);
+        let mut multirate = DsfbObserver::new(params, 2);
+
+        streaming.step_at(1.0, 0, 0.2);
+        streaming.step_at(1.1, 1, 0.3);
+        streaming.step_arrivals(1.25, &[(0, 0.35), (1, 0.4)]);
+        streaming.step_at(1.7, 1, 0.6);
+
+        multirate.step_multirate(&[Some(0.2), None], 0.0);
+        multirate.step_multirate(&[None, Some(0.3)], 0.1);
+        multirate.step_multirate(&[Some(0.35), Some(0.4)], 0.15);
+        multirate.step_multirate(&[None, Some(0.6)], 0.45);
+
+        let (a, b) = (streaming.state(), multirate.state());
+        assert!((a.phi - b.phi).abs() < 1e-12);
+        assert!((a.omega - b.omega).abs() < 1e-12);
+        assert!((a.alpha - b.alpha).abs() < 1e-12);
+        assert_eq!(streaming.time(), Some(1.7));
+        assert_eq!(streaming.channel_last_arrival(0), Some(1.25));
+        assert_eq!(streaming.channel_staleness(0), 1);
+
+        let ahead = streaming.predict_at(2.0);
+        assert!((ahead.phi - (a.phi + a.omega * 0.3)).abs() < 1e-12);
+        assert_eq!(streaming.state(), a);
+    }
+
+    #[test]
+    #[should_panic(expected = "non-decreasing")]
+    fn test_step_at_rejects_out_of_order_arrival() {
+        let mut observer = DsfbObserver::new(DsfbParams::default(), 2);
+        observer.step_at(2.0, 0, 0.1);
+        observer.step_at(1.5, 1, 0.1);
+    }
+
+    #[test]
+    fn test_unreported_channel_has_zero_weight() {
+        let params = DsfbParams::new(0.5, 0.1, 0.01, 0.9, 0.1);
+        let mut observer = DsfbObserver::new(params, 2);
+
+        observer.step_multirate(&[Some(1.0), None], 0.1);
+        assert_eq!(observer.trust_weight(1), 0.0);
+        assert_eq!(observer.trust_weight(0), 1.0);
+    }
+
+    #[test]
+    fn test_change_detector_flags_small_bias_shift() {
+        let params = DsfbParams::new(0.5, 0.1, 0.01, 0.99, 0.1);
+        let cusum = CusumParams::new(0.02, 0.5);
+        let mut observer = DsfbObserver::new(params, 3).with_change_detector(cusum);
+        let mut plain = DsfbObserver::new(params, 3);
+
+        for _ in 0..100 {
+            let diagnostics = observer.step_with_diagnostics(&[0.0, 0.0, 0.0], 0.1);
+            assert!(diagnostics.change_points.is_empty());
+            plain.step(&[0.0, 0.0, 0.0], 0.1);
+        }
+
+        let mut detected = None;
+        for _ in 0..100 {
+            let diagnostics = observer.step_with_diagnostics(&[0.0, 0.0, 0.15], 0.1);
+            plain.step(&[0.0, 0.0, 0.15], 0.1);
+            if let Some(change) = diagnostics.change_points.first() {
+                detected = Some(*change);
+                break;
+            }
+        }
+
+        let change = detected.expect("bias shift should be detected");
+        assert_eq!(change.channel, 2);
+        assert!(change.onset_step >= 98 && change.onset_step <= 101);
+        assert!(change.shift > 0.1);
+        assert_eq!(observer.last_change_point(2), Some(change));
+        assert_eq!(observer.last_change_point(0), None);
+
+        // The envelope jumped to the new residual level; the plain EMA lags
+        assert!(observer.ema_residual(2) > 0.1);
+        assert!(observer.ema_residual(2) > 5.0 * plain.ema_residual(2));
+        assert!(observer.trust_weight(2) < plain.trust_weight(2));
+    }
+
+    #[test]
+    fn test_change_detector_report_only_keeps_envelope() {
+        let params = DsfbParams::new(0.5, 0.1, 0.01, 0.99, 0.1);
+        let cusum = CusumParams::new(0.02, 0.5).with_reinit_envelope(false);
+        let mut observer = DsfbObserver::new(params, 2).with_change_detector(cusum);
+        let mut plain = DsfbObserver::new(params, 2);
+
+        for _ in 0..60 {
+            observer.step(&[0.0, 0.2], 0.1);
+            plain.step(&[0.0, 0.2], 0.1);
+        }
+
+        assert!(observer.last_change_point(1).is_some());
+        assert_eq!(observer.trust_stats(), plain.trust_stats());
+        assert_eq!(observer.state(), plain.state());
+    }
+
+    #[test]
+    fn test_reinit_envelope_sets_channel_envelope() {
+        let params = DsfbParams::new(0.5, 0.1, 0.01, 0.9, 0.1);
+        let mut observer = DsfbObserver::new(params, 2);
+
+        observer.reinit_envelope(1, 0.4);
+        assert_eq!(observer.ema_residual(1), 0.4);
+        assert_eq!(observer.ema_residual(0), 0.0);
+
+        observer.step(&[0.0, 0.0], 0.1);
+        assert!(observer.trust_weight(1) < observer.trust_weight(0));
+    }
+
+    /// Two channels around zero: a quiet one and one with 20x the noise.
+    fn noisy_pair(n: usize) -> [f64; 2] {
+        let quiet = if n % 2 == 0 { 0.01 } else { -0.01 };
+        let loud = [0.2, -0.2, 0.1][n % 3];
+        [quiet, loud]
+    }
+
+    #[test]
+    fn test_sigma0_calibration_estimates_channel_noise() {
+        let mut observer = DsfbObserver::new(DsfbParams::default(), 2).with_sigma0_calibration(30);
+        assert!(observer.is_calibrating());
+
+        for n in 0..30 {
+            observer.step(&noisy_pair(n), 0.1);
+            // Adaptive weighting is off while calibrating
+            assert_eq!(observer.trust_weight(0), 0.5);
+            assert_eq!(observer.trust_weight(1), 0.5);
+        }
+
+        assert!(!observer.is_calibrating());
+        let sigma0 = observer.calibrated_sigma0().unwrap().to_vec();
+        // Residuals are taken against the fused prediction, so the quiet
+        // channel also sees the state error the loud one causes
+        assert!(sigma0[1] > 2.0 * sigma0[0]);
+        assert_eq!(observer.channel_sigma0(0), sigma0[0]);
+        assert_eq!(observer.channel_sigma0(1), sigma0[1]);
+
+        observer.step(&noisy_pair(30), 0.1);
+        assert!(observer.trust_weight(0) > observer.trust_weight(1));
+    }
+
+    #[test]
+    fn test_group_trust_suppresses_correlated_cluster() {
+        let params = DsfbParams::default();
+        let mut flat = DsfbObserver::new(params, 5);
+        let mut grouped = DsfbObserver::new(params, 5).with_groups(vec![0, 0, 1, 1, 1]);
+
+        // Channels 0 and 1 share a mount and drift off together
+        for n in 0..200 {
+            let bias = if n >= 50 { 0.5 } else { 0.0 };
+            let y = [bias, bias, 0.0, 0.0, 0.0];
+            flat.step(&y, 0.01);
+            grouped.step(&y, 0.01);
+        }
+
+        let groups = grouped.groups().unwrap();
+        assert!(groups.envelope(0) > groups.envelope(1));
+        assert!(grouped.trust_weight(0) < flat.trust_weight(0));
+        assert!(grouped.state().phi.abs() < flat.state().phi.abs());
+
+        grouped.reset();
+        assert_eq!(grouped.groups().unwrap().mapping(), &[0, 0, 1, 1, 1]);
+        assert_eq!(grouped.groups().unwrap().envelope(0), 0.0);
+    }
+
+    #[test]
+    fn test_single_group_matches_ungrouped_observer() {
+        let params = DsfbParams::default().with_min_weight(0.05);
+        let mut flat = DsfbObserver::new(params, 3);
+        let mut grouped = DsfbObserver::new(params, 3).with_groups(vec![0; 3]);
+
+        for n in 0..50 {
+            let t = n as f64 * 0.1;
+            let y = [t, t + 0.05, if n > 20 { t + 2.0 } else { t }];
+            let a = flat.step(&y, 0.1);
+            let b = grouped.step(&y, 0.1);
+            assert!((a.phi - b.phi).abs() < 1e-12);
+            for k in 0..3 {
+                assert!((flat.trust_weight(k) - grouped.trust_weight(k)).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_innovation_rho_reacts_faster_to_fault() {
+        let params = DsfbParams {
+            rho: 0.99,
+            ..DsfbParams::default()
+        };
+        let mut fixed = DsfbObserver::new(params, 3);
+        let mut adaptive = DsfbObserver::new(
+            params.with_rho_policy(RhoPolicy::Innovation {
+                ratio: 4.0,
+                fast_rho: 0.5,
+            }),
+            3,
+        );
+
+        for n in 0..60 {
+            let fault = if n >= 50 { 1.0 } else { 0.0 };
+            let y = [0.01, -0.01, fault];
+            fixed.step(&y, 0.01);
+            adaptive.step(&y, 0.01);
+        }
+
+        assert!(adaptive.trust_weight(2) < 0.5 * fixed.trust_weight(2));
+    }
+
+    #[test]
+    fn test_custom_rho_schedule_replaces_policy() {
+        struct Constant(f64);
+        impl RhoSchedule<f64> for Constant {
+            fn rho(&self, _base: f64, _step: usize, _residual: f64, _envelope: f64) -> f64 {
+                self.0
+            }
+        }
+
+        let params = DsfbParams {
+            rho: 0.7,
+            ..DsfbParams::default()
+        };
+        let mut reference = DsfbObserver::new(params, 3);
+        let mut custom =
+            DsfbObserver::new(DsfbParams::default(), 3).with_rho_schedule(Constant(0.7));
+        for n in 0..20 {
+            let y = [0.0, 0.1, n as f64 * 0.05];
+            reference.step(&y, 0.1);
+            custom.step(&y, 0.1);
+            for k in 0..3 {
+                assert!((reference.ema_residual(k) - custom.ema_residual(k)).abs() < 1e-12);
+            }
+        }
+
+        custom.reset();
+        custom.step(&[0.0, 0.0, 1.0], 0.1);
+        assert!((custom.ema_residual(2) - 0.3).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_try_api_reports_invalid_input() {
+        let mut observer: DsfbObserver = DsfbObserver::new(DsfbParams::default(), 2);
+        assert_eq!(
+            observer.try_step(&[1.0, 2.0, 3.0], 0.1),
+            Err(DsfbError::MeasurementCountMismatch {
+                expected: 2,
+                got: 3
+            })
+        );
+        assert_eq!(observer.state(), DsfbState::zero());
+        assert_eq!(
+            observer.try_trust_weight(2),
+            Err(DsfbError::ChannelOutOfRange {
+                channel: 2,
+                channels: 2
+            })
+        );
+
+        let state = observer.try_step(&[1.0, 1.0], 0.1).unwrap();
+        assert_eq!(state, observer.state());
+        assert_eq!(observer.try_trust_weight(1), Ok(0.5));
+    }
+
+    #[test]
+    #[should_panic(expected = "Measurement count mismatch: expected 2, got 1")]
+    fn test_step_panics_on_measurement_count_mismatch() {
+        let mut observer: DsfbObserver = DsfbObserver::new(DsfbParams::default(), 2);
+        observer.step(&[1.0], 0.1);
+    }
+
+    #[test]
+    fn test_reset_restarts_sigma0_calibration() {
+        let params: DsfbParams = DsfbParams::default();
+        let mut observer = DsfbObserver::new(params, 2).with_sigma0_calibration(4);
+        for n in 0..4 {
+            observer.step(&noisy_pair(n), 0.1);
+        }
+        assert!(observer.calibrated_sigma0().is_some());
+
+        observer.reinit_with(DsfbState::zero(), true);
+        assert!(observer.calibrated_sigma0().is_some());
+
+        observer.reset();
+        assert!(observer.is_calibrating());
+        assert_eq!(observer.calibrated_sigma0(), None);
+        assert_eq!(observer.channel_sigma0(1), params.sigma0);
+    }
 }
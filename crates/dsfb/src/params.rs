@@ -2,46 +2,115 @@
 //!
 //! Parameters for the DSFB observer algorithm
 
+use crate::rho::RhoPolicy;
+use crate::scalar::Real;
+use crate::trust::TrustShape;
+
+/// Default per-step trust decay for stale channels
+pub const DEFAULT_STALENESS_DECAY: f64 = 0.5;
+
 /// Parameters for the DSFB observer
+///
+/// Build them with [`new`](Self::new) or [`default_params`](Self::default_params)
+/// and the `with_*` methods; the struct is `#[non_exhaustive]`, so fields can
+/// be read and assigned but not listed in a struct literal outside this crate.
+///
+/// With the `serde` feature, omitted fields deserialize to [`default_params`](Self::default_params).
 #[derive(Debug, Clone, Copy, PartialEq)]
-pub struct DsfbParams {
+#[non_exhaustive]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct DsfbParams<T: Real = f64> {
     /// Gain for phi correction
-    pub k_phi: f64,
+    pub k_phi: T,
     /// Gain for omega correction
-    pub k_omega: f64,
+    pub k_omega: T,
     /// Gain for alpha correction
-    pub k_alpha: f64,
+    pub k_alpha: T,
     /// EMA smoothing factor (0 < rho < 1)
-    pub rho: f64,
+    ///
+    /// The base value of the [`rho_policy`](Self::rho_policy) schedule.
+    pub rho: T,
     /// Trust softness parameter
-    pub sigma0: f64,
+    pub sigma0: T,
+    /// Per-step multiplier on the trust of a stale channel (0 <= decay <= 1)
+    ///
+    /// Only used by [`DsfbObserver::step_multirate`](crate::DsfbObserver::step_multirate).
+    pub staleness_decay: T,
+    /// Floor on each channel's normalized trust weight (0 disables)
+    ///
+    /// See [`apply_weight_floor`](crate::trust::apply_weight_floor).
+    pub min_weight: T,
+    /// Shape of a channel's raw trust as its residual envelope grows
+    ///
+    /// See [`TrustShape`]; the default is `1 / (sigma0 + s_k)`.
+    pub trust_shape: TrustShape<T>,
+    /// How each channel's envelope forgetting factor follows from `rho`
+    ///
+    /// See [`RhoPolicy`]; the default uses `rho` at every step.
+    pub rho_policy: RhoPolicy<T>,
 }
 
-impl DsfbParams {
+impl<T: Real> DsfbParams<T> {
     /// Create new DSFB parameters
-    pub fn new(k_phi: f64, k_omega: f64, k_alpha: f64, rho: f64, sigma0: f64) -> Self {
+    pub fn new(k_phi: T, k_omega: T, k_alpha: T, rho: T, sigma0: T) -> Self {
         Self {
             k_phi,
             k_omega,
             k_alpha,
             rho,
             sigma0,
+            staleness_decay: T::from_f64(DEFAULT_STALENESS_DECAY),
+            min_weight: T::ZERO,
+            trust_shape: TrustShape::Reciprocal,
+            rho_policy: RhoPolicy::Fixed,
         }
     }
 
+    /// Set the per-step trust decay applied to stale channels
+    pub fn with_staleness_decay(mut self, staleness_decay: T) -> Self {
+        self.staleness_decay = staleness_decay;
+        self
+    }
+
+    /// Set the floor on each channel's normalized trust weight
+    pub fn with_min_weight(mut self, min_weight: T) -> Self {
+        self.min_weight = min_weight;
+        self
+    }
+
+    /// Set the shape of each channel's raw trust
+    pub fn with_trust_shape(mut self, trust_shape: TrustShape<T>) -> Self {
+        self.trust_shape = trust_shape;
+        self
+    }
+
+    /// Set how the envelope forgetting factor is scheduled
+    pub fn with_rho_policy(mut self, rho_policy: RhoPolicy<T>) -> Self {
+        self.rho_policy = rho_policy;
+        self
+    }
+
     /// Create default parameters suitable for basic simulation
     pub fn default_params() -> Self {
         Self {
-            k_phi: 0.5,
-            k_omega: 0.1,
-            k_alpha: 0.01,
-            rho: 0.95,
-            sigma0: 0.1,
+            k_phi: T::from_f64(0.5),
+            k_omega: T::from_f64(0.1),
+            k_alpha: T::from_f64(0.01),
+            rho: T::from_f64(0.95),
+            sigma0: T::from_f64(0.1),
+            staleness_decay: T::from_f64(DEFAULT_STALENESS_DECAY),
+            min_weight: T::ZERO,
+            trust_shape: TrustShape::Reciprocal,
+            rho_policy: RhoPolicy::Fixed,
         }
     }
 }
 
-impl Default for DsfbParams {
+impl<T: Real> Default for DsfbParams<T> {
     fn default() -> Self {
         Self::default_params()
     }
@@ -0,0 +1,272 @@
+//! Python bindings (`python` feature)
+//!
+//! A `dsfb` extension module over the `f64` observer, so Python prototypes
+//! run the canonical implementation rather than a re-implementation. It
+//! exposes [`DsfbParams`] and [`DsfbObserver`] as classes of the same names
+//! and the drift-impulse simulation as `run_simulation`. Invalid input raises
+//! `ValueError` (or `IndexError` for a channel index) instead of panicking.
+
+#![allow(clippy::useless_conversion)] // False positive from PyO3-generated PyResult signature.
+
+use std::collections::BTreeMap;
+
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::prelude::*;
+
+use crate::error::DsfbError;
+use crate::observer::DsfbObserver;
+use crate::params::DsfbParams;
+use crate::sim::{run_simulation, SimConfig};
+use crate::state::DsfbState;
+
+fn py_err(err: DsfbError) -> PyErr {
+    match err {
+        DsfbError::ChannelOutOfRange { .. } => PyIndexError::new_err(err.to_string()),
+        DsfbError::MeasurementCountMismatch { .. } => PyValueError::new_err(err.to_string()),
+    }
+}
+
+/// Observer parameters; omitted arguments take the defaults of
+/// [`DsfbParams::default_params`]
+#[pyclass(name = "DsfbParams")]
+#[derive(Debug, Clone, Copy)]
+pub struct PyDsfbParams {
+    params: DsfbParams,
+}
+
+#[pymethods]
+impl PyDsfbParams {
+    #[new]
+    #[pyo3(signature = (k_phi=None, k_omega=None, k_alpha=None, rho=None, sigma0=None, staleness_decay=None, min_weight=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn py_new(
+        k_phi: Option<f64>,
+        k_omega: Option<f64>,
+        k_alpha: Option<f64>,
+        rho: Option<f64>,
+        sigma0: Option<f64>,
+        staleness_decay: Option<f64>,
+        min_weight: Option<f64>,
+    ) -> PyResult<Self> {
+        let defaults = DsfbParams::default_params();
+        let params = DsfbParams {
+            k_phi: k_phi.unwrap_or(defaults.k_phi),
+            k_omega: k_omega.unwrap_or(defaults.k_omega),
+            k_alpha: k_alpha.unwrap_or(defaults.k_alpha),
+            rho: rho.unwrap_or(defaults.rho),
+            sigma0: sigma0.unwrap_or(defaults.sigma0),
+            staleness_decay: staleness_decay.unwrap_or(defaults.staleness_decay),
+            min_weight: min_weight.unwrap_or(defaults.min_weight),
+            ..defaults
+        };
+        if !(0.0..=1.0).contains(&params.rho) {
+            return Err(PyValueError::new_err("rho must lie in [0, 1]"));
+        }
+        if !(0.0..).contains(&params.sigma0) {
+            return Err(PyValueError::new_err("sigma0 must be non-negative"));
+        }
+        if !(0.0..=1.0).contains(&params.staleness_decay) {
+            return Err(PyValueError::new_err("staleness_decay must lie in [0, 1]"));
+        }
+        Ok(Self { params })
+    }
+
+    #[getter]
+    fn k_phi(&self) -> f64 {
+        self.params.k_phi
+    }
+
+    #[getter]
+    fn k_omega(&self) -> f64 {
+        self.params.k_omega
+    }
+
+    #[getter]
+    fn k_alpha(&self) -> f64 {
+        self.params.k_alpha
+    }
+
+    #[getter]
+    fn rho(&self) -> f64 {
+        self.params.rho
+    }
+
+    #[getter]
+    fn sigma0(&self) -> f64 {
+        self.params.sigma0
+    }
+
+    #[getter]
+    fn staleness_decay(&self) -> f64 {
+        self.params.staleness_decay
+    }
+
+    #[getter]
+    fn min_weight(&self) -> f64 {
+        self.params.min_weight
+    }
+
+    fn __repr__(&self) -> String {
+        let p = &self.params;
+        format!(
+            "DsfbParams(k_phi={}, k_omega={}, k_alpha={}, rho={}, sigma0={}, staleness_decay={}, min_weight={})",
+            p.k_phi, p.k_omega, p.k_alpha, p.rho, p.sigma0, p.staleness_decay, p.min_weight
+        )
+    }
+}
+
+/// The DSFB observer; states are `(phi, omega, alpha)` tuples
+#[pyclass(name = "DsfbObserver")]
+pub struct PyDsfbObserver {
+    observer: DsfbObserver,
+}
+
+#[pymethods]
+impl PyDsfbObserver {
+    #[new]
+    #[pyo3(signature = (channels, params=None))]
+    fn py_new(channels: usize, params: Option<PyDsfbParams>) -> PyResult<Self> {
+        if channels == 0 {
+            return Err(PyValueError::new_err("channels must be positive"));
+        }
+        let params = params.map_or_else(DsfbParams::default_params, |p| p.params);
+        Ok(Self {
+            observer: DsfbObserver::new(params, channels),
+        })
+    }
+
+    /// Set the state estimate
+    fn init(&mut self, phi: f64, omega: f64, alpha: f64) {
+        self.observer.init(DsfbState::new(phi, omega, alpha));
+    }
+
+    /// One step with a sample from every channel; returns the corrected state
+    fn step(&mut self, measurements: Vec<f64>, dt: f64) -> PyResult<(f64, f64, f64)> {
+        let state = self.observer.try_step(&measurements, dt).map_err(py_err)?;
+        Ok((state.phi, state.omega, state.alpha))
+    }
+
+    /// One step in which `None` marks a channel without a new sample
+    fn step_multirate(
+        &mut self,
+        measurements: Vec<Option<f64>>,
+        dt: f64,
+    ) -> PyResult<(f64, f64, f64)> {
+        if measurements.len() != self.observer.trust_stats().len() {
+            return Err(py_err(DsfbError::MeasurementCountMismatch {
+                expected: self.observer.trust_stats().len(),
+                got: measurements.len(),
+            }));
+        }
+        let state = self.observer.step_multirate(&measurements, dt);
+        Ok((state.phi, state.omega, state.alpha))
+    }
+
+    /// Return the observer to its freshly constructed condition
+    fn reset(&mut self) {
+        self.observer.reset();
+    }
+
+    #[getter]
+    fn state(&self) -> (f64, f64, f64) {
+        let state = self.observer.state();
+        (state.phi, state.omega, state.alpha)
+    }
+
+    #[getter]
+    fn channels(&self) -> usize {
+        self.observer.trust_stats().len()
+    }
+
+    /// Normalized trust weight of every channel
+    fn trust_weights(&self) -> Vec<f64> {
+        self.observer
+            .trust_stats()
+            .iter()
+            .map(|stats| stats.weight)
+            .collect()
+    }
+
+    fn trust_weight(&self, channel: usize) -> PyResult<f64> {
+        self.observer.try_trust_weight(channel).map_err(py_err)
+    }
+
+    /// Residual envelope (EMA of absolute residuals) of every channel
+    fn ema_residuals(&self) -> Vec<f64> {
+        self.observer
+            .trust_stats()
+            .iter()
+            .map(|stats| stats.residual_ema)
+            .collect()
+    }
+
+    /// Variance estimate of the trust-weighted fused measurement
+    fn fused_uncertainty(&self) -> f64 {
+        self.observer.fused_uncertainty()
+    }
+}
+
+/// Run the drift-impulse simulation and return its columns by name
+///
+/// Omitted arguments take the defaults of [`SimConfig`]; `params` defaults
+/// to [`DsfbParams::default_params`].
+#[pyfunction(name = "run_simulation")]
+#[pyo3(signature = (params=None, steps=None, dt=None, sigma_noise=None, sigma_alpha=None, drift_beta=None, impulse_start=None, impulse_duration=None, impulse_amplitude=None, seed=None))]
+#[allow(clippy::too_many_arguments)]
+fn py_run_simulation(
+    params: Option<PyDsfbParams>,
+    steps: Option<usize>,
+    dt: Option<f64>,
+    sigma_noise: Option<f64>,
+    sigma_alpha: Option<f64>,
+    drift_beta: Option<f64>,
+    impulse_start: Option<usize>,
+    impulse_duration: Option<usize>,
+    impulse_amplitude: Option<f64>,
+    seed: Option<u64>,
+) -> PyResult<BTreeMap<&'static str, Vec<f64>>> {
+    let defaults = SimConfig::default();
+    let config = SimConfig {
+        steps: steps.unwrap_or(defaults.steps),
+        dt: dt.unwrap_or(defaults.dt),
+        sigma_noise: sigma_noise.unwrap_or(defaults.sigma_noise),
+        sigma_alpha: sigma_alpha.unwrap_or(defaults.sigma_alpha),
+        drift_beta: drift_beta.unwrap_or(defaults.drift_beta),
+        impulse_start: impulse_start.unwrap_or(defaults.impulse_start),
+        impulse_duration: impulse_duration.unwrap_or(defaults.impulse_duration),
+        impulse_amplitude: impulse_amplitude.unwrap_or(defaults.impulse_amplitude),
+        seed: seed.unwrap_or(defaults.seed),
+        ..defaults
+    };
+    if !(0.0..).contains(&config.sigma_noise) || !(0.0..).contains(&config.sigma_alpha) {
+        return Err(PyValueError::new_err(
+            "sigma_noise and sigma_alpha must be non-negative",
+        ));
+    }
+    let params = params.map_or_else(DsfbParams::default_params, |p| p.params);
+
+    let steps = run_simulation(config, params);
+    let column = |f: fn(&crate::sim::SimStep) -> f64| steps.iter().map(f).collect::<Vec<f64>>();
+    Ok(BTreeMap::from([
+        ("t", column(|s| s.t)),
+        ("phi_true", column(|s| s.phi_true)),
+        ("y1", column(|s| s.y1)),
+        ("y2", column(|s| s.y2)),
+        ("phi_mean", column(|s| s.phi_mean)),
+        ("phi_freqonly", column(|s| s.phi_freqonly)),
+        ("phi_dsfb", column(|s| s.phi_dsfb)),
+        ("err_mean", column(|s| s.err_mean)),
+        ("err_freqonly", column(|s| s.err_freqonly)),
+        ("err_dsfb", column(|s| s.err_dsfb)),
+        ("w2", column(|s| s.w2)),
+        ("s2", column(|s| s.s2)),
+    ]))
+}
+
+#[pymodule]
+fn dsfb(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyDsfbParams>()?;
+    m.add_class::<PyDsfbObserver>()?;
+    m.add_function(wrap_pyfunction!(py_run_simulation, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,117 @@
+//! Forgetting factor scheduling
+//!
+//! Each channel's residual envelope is the EMA `s_k = rho*s_k + (1-rho)*|r_k|`.
+//! A rho close to one rejects noise but takes `~1 / (1 - rho)` steps to
+//! react to a fault; a smaller rho reacts quickly but lets single noisy
+//! samples move the trust weights. A [`RhoSchedule`] picks rho per channel
+//! and per step, so the envelope can forget quickly when a residual spikes
+//! or while the observer is starting up, and slowly in steady state.
+
+use crate::scalar::Real;
+
+/// Forgetting factor of a channel's envelope update
+pub trait RhoSchedule<T: Real> {
+    /// Rho for one channel's envelope update
+    ///
+    /// `base` is [`DsfbParams::rho`](crate::DsfbParams::rho), `step` the
+    /// number of observer steps completed before this one, `residual` the
+    /// channel's absolute trust residual, and `envelope` its envelope before
+    /// the update. The result should lie in `[0, 1]`.
+    fn rho(&self, base: T, step: usize, residual: T, envelope: T) -> T;
+}
+
+/// Built-in rho schedules, selected through
+/// [`DsfbParams::rho_policy`](crate::DsfbParams::rho_policy)
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "kind", rename_all = "snake_case")
+)]
+pub enum RhoPolicy<T: Real = f64> {
+    /// `rho` at every step
+    #[default]
+    Fixed,
+    /// `min(rho, n / (n + 1))` at step `n`: a running mean of the residuals
+    /// until the EMA's own memory is the shorter one, so the envelopes do not
+    /// start out biased toward zero
+    Warmup,
+    /// `min(rho, fast_rho)` while `|r_k| > ratio * s_k`, `rho` otherwise: a
+    /// residual well above the channel's envelope is absorbed at once
+    Innovation {
+        /// Residual-to-envelope ratio above which the channel forgets fast
+        ratio: T,
+        /// Rho used while the residual exceeds the ratio
+        fast_rho: T,
+    },
+}
+
+impl<T: Real> RhoSchedule<T> for RhoPolicy<T> {
+    fn rho(&self, base: T, step: usize, residual: T, envelope: T) -> T {
+        match *self {
+            RhoPolicy::Fixed => base,
+            RhoPolicy::Warmup => {
+                let running_mean = T::from_usize(step) / T::from_usize(step + 1);
+                min(base, running_mean)
+            }
+            RhoPolicy::Innovation { ratio, fast_rho } => {
+                if residual > ratio * envelope {
+                    min(base, fast_rho)
+                } else {
+                    base
+                }
+            }
+        }
+    }
+}
+
+fn min<T: Real>(a: T, b: T) -> T {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+/// Rho of every channel for one step
+///
+/// `residuals` are the signed trust residuals and `envelopes` the envelopes
+/// before the update.
+#[cfg(feature = "std")]
+pub fn scheduled_rho<T: Real>(
+    schedule: &(impl RhoSchedule<T> + ?Sized),
+    base: T,
+    step: usize,
+    residuals: &[T],
+    envelopes: &[T],
+) -> Vec<T> {
+    residuals
+        .iter()
+        .zip(envelopes)
+        .map(|(&r, &s)| schedule.rho(base, step, r.abs(), s))
+        .collect()
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warmup_is_running_mean_until_base() {
+        let rho: Vec<f64> = (0..5)
+            .map(|step| RhoPolicy::Warmup.rho(0.7, step, 1.0, 0.0))
+            .collect();
+        assert_eq!(rho, vec![0.0, 0.5, 2.0 / 3.0, 0.7, 0.7]);
+        assert_eq!(RhoPolicy::Fixed.rho(0.7, 0, 1.0, 0.0), 0.7);
+    }
+
+    #[test]
+    fn test_innovation_forgets_fast_on_spike() {
+        let policy = RhoPolicy::Innovation {
+            ratio: 3.0,
+            fast_rho: 0.5,
+        };
+        let rho = scheduled_rho(&policy, 0.95, 10, &[0.2, -0.4, 0.2], &[0.1, 0.1, 0.1]);
+        assert_eq!(rho, vec![0.95, 0.5, 0.95]);
+    }
+}
@@ -0,0 +1,147 @@
+//! Saturation-aware residuals for clipped measurements
+//!
+//! A sensor pinned at its rail only reports that the true value lies at or
+//! beyond the rail. Taking the rail reading at face value gives a residual
+//! that is misleadingly small whenever the prediction is near the rail too,
+//! so a saturated channel would keep, or even gain, trust. With
+//! [`SaturationLimits`] a reading at or beyond a limit is treated as
+//! one-sided information instead: it only pulls the estimate toward the
+//! saturated side, and its trust residual is never smaller than a floor.
+
+use crate::scalar::Real;
+
+/// The limit a saturated measurement is pinned at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rail {
+    /// At or below the lower limit; the true value is at most the reading
+    Lower,
+    /// At or above the upper limit; the true value is at least the reading
+    Upper,
+}
+
+/// Known saturation limits of one channel, in measurement units
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SaturationLimits<T: Real = f64> {
+    /// Lowest value the sensor can report
+    pub lower: T,
+    /// Highest value the sensor can report
+    pub upper: T,
+    /// Smallest trust residual magnitude of a saturated sample
+    pub residual_floor: T,
+}
+
+impl<T: Real> SaturationLimits<T> {
+    /// Create limits with no residual floor
+    ///
+    /// # Panics
+    ///
+    /// If `lower` is not below `upper`.
+    pub fn new(lower: T, upper: T) -> Self {
+        assert!(lower < upper, "Saturation limits require lower < upper");
+        Self {
+            lower,
+            upper,
+            residual_floor: T::ZERO,
+        }
+    }
+
+    /// Create limits at `-range` and `+range`
+    pub fn symmetric(range: T) -> Self {
+        Self::new(-range, range)
+    }
+
+    /// Set the smallest trust residual magnitude of a saturated sample
+    ///
+    /// A rail reading is consistent with any true value beyond the rail, so
+    /// its residual says little about the channel's health. The floor keeps a
+    /// saturated channel's envelope from shrinking below it.
+    ///
+    /// # Panics
+    ///
+    /// If `residual_floor` is negative.
+    pub fn with_residual_floor(mut self, residual_floor: T) -> Self {
+        assert!(
+            residual_floor >= T::ZERO,
+            "Saturation residual floor must be non-negative"
+        );
+        self.residual_floor = residual_floor;
+        self
+    }
+
+    /// Rail the sample `y` is pinned at, if any
+    pub fn rail(&self, y: T) -> Option<Rail> {
+        if y >= self.upper {
+            Some(Rail::Upper)
+        } else if y <= self.lower {
+            Some(Rail::Lower)
+        } else {
+            None
+        }
+    }
+
+    /// Correction and trust residuals of a sample pinned at `rail`, given its
+    /// raw residual `y - h(phi^-)`
+    ///
+    /// The correction residual keeps only the part that pushes the prediction
+    /// toward the saturated side and is zero when the prediction already lies
+    /// beyond the rail. The trust residual has the same sign as the rail and a
+    /// magnitude of at least [`Self::residual_floor`].
+    pub fn one_sided(&self, rail: Rail, residual: T) -> (T, T) {
+        match rail {
+            Rail::Upper => {
+                let correction = if residual > T::ZERO {
+                    residual
+                } else {
+                    T::ZERO
+                };
+                let trust = if correction > self.residual_floor {
+                    correction
+                } else {
+                    self.residual_floor
+                };
+                (correction, trust)
+            }
+            Rail::Lower => {
+                let correction = if residual < T::ZERO {
+                    residual
+                } else {
+                    T::ZERO
+                };
+                let trust = if correction < -self.residual_floor {
+                    correction
+                } else {
+                    -self.residual_floor
+                };
+                (correction, trust)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rail_classification() {
+        let limits = SaturationLimits::symmetric(2.0);
+        assert_eq!(limits.rail(2.0), Some(Rail::Upper));
+        assert_eq!(limits.rail(3.0), Some(Rail::Upper));
+        assert_eq!(limits.rail(-2.0), Some(Rail::Lower));
+        assert_eq!(limits.rail(1.99), None);
+    }
+
+    #[test]
+    fn test_one_sided_residuals() {
+        let limits = SaturationLimits::new(-1.0, 1.0).with_residual_floor(0.5);
+
+        // Prediction below the upper rail: pull up, at least by the floor for trust
+        assert_eq!(limits.one_sided(Rail::Upper, 0.1), (0.1, 0.5));
+        assert_eq!(limits.one_sided(Rail::Upper, 0.8), (0.8, 0.8));
+        // Prediction beyond the rail is consistent with the reading
+        assert_eq!(limits.one_sided(Rail::Upper, -0.3), (0.0, 0.5));
+        assert_eq!(limits.one_sided(Rail::Lower, 0.3), (0.0, -0.5));
+        assert_eq!(limits.one_sided(Rail::Lower, -0.7), (-0.7, -0.7));
+    }
+}
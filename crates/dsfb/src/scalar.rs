@@ -0,0 +1,106 @@
+//! Scalar abstraction for the DSFB observer and trust math
+//!
+//! The observer, parameters, state, and trust weights are generic over
+//! [`Real`], which is implemented for `f64` (the default) and `f32` for
+//! targets without a double-precision FPU. Without the `std` feature the
+//! transcendental functions come from `libm`.
+
+use core::fmt::Debug;
+use core::iter::Sum;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// Floating-point scalar used by the DSFB observer and trust math
+pub trait Real:
+    Copy
+    + Debug
+    + Default
+    + PartialEq
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+    + Sum
+    + 'static
+{
+    /// Additive identity
+    const ZERO: Self;
+    /// Multiplicative identity
+    const ONE: Self;
+    /// Machine epsilon
+    const EPSILON: Self;
+
+    /// Convert from `f64`, rounding to the nearest representable value
+    fn from_f64(value: f64) -> Self;
+    /// Widen to `f64`
+    fn to_f64(self) -> f64;
+    /// Convert a count (e.g. a channel count) to a scalar
+    fn from_usize(value: usize) -> Self;
+    /// Absolute value
+    fn abs(self) -> Self;
+    /// Square root
+    fn sqrt(self) -> Self;
+    /// Exponential function
+    fn exp(self) -> Self;
+    /// Whether the value is neither infinite nor NaN
+    fn is_finite(self) -> bool;
+}
+
+macro_rules! impl_real {
+    ($t:ty, $abs:path, $sqrt:path, $exp:path) => {
+        impl Real for $t {
+            const ZERO: Self = 0.0;
+            const ONE: Self = 1.0;
+            const EPSILON: Self = <$t>::EPSILON;
+
+            #[inline]
+            fn from_f64(value: f64) -> Self {
+                value as $t
+            }
+
+            #[inline]
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            #[inline]
+            fn from_usize(value: usize) -> Self {
+                value as $t
+            }
+
+            #[inline]
+            fn abs(self) -> Self {
+                $abs(self)
+            }
+
+            #[inline]
+            fn sqrt(self) -> Self {
+                $sqrt(self)
+            }
+
+            #[inline]
+            fn exp(self) -> Self {
+                $exp(self)
+            }
+
+            #[inline]
+            fn is_finite(self) -> bool {
+                <$t>::is_finite(self)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "std")]
+impl_real!(f32, f32::abs, f32::sqrt, f32::exp);
+#[cfg(feature = "std")]
+impl_real!(f64, f64::abs, f64::sqrt, f64::exp);
+#[cfg(not(feature = "std"))]
+impl_real!(f32, libm::fabsf, libm::sqrtf, libm::expf);
+#[cfg(not(feature = "std"))]
+impl_real!(f64, libm::fabs, libm::sqrt, libm::exp);
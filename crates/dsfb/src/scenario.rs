@@ -0,0 +1,262 @@
+//! Shareable simulation scenarios
+//!
+//! A [`Scenario`] bundles a [`SimConfig`], the [`DsfbParams`] to run it with,
+//! and tolerances on the resulting metrics, so regression cases can be stored
+//! as JSON files (see `scenarios/`) and replayed with the `run_scenario` example.
+
+use crate::params::DsfbParams;
+use crate::sim::{rms_error, run_simulation, SimConfig, SimStep, SIM_CHANNELS};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::Path;
+
+/// Expected value of a metric with an absolute tolerance
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MetricTolerance {
+    pub expected: f64,
+    pub tolerance: f64,
+}
+
+impl MetricTolerance {
+    pub fn new(expected: f64, tolerance: f64) -> Self {
+        Self {
+            expected,
+            tolerance,
+        }
+    }
+
+    /// Whether `actual` lies within `expected ± tolerance`
+    pub fn accepts(&self, actual: f64) -> bool {
+        (actual - self.expected).abs() <= self.tolerance
+    }
+}
+
+/// Tolerances on [`ScenarioMetrics`]; omitted metrics are not checked
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScenarioExpectations {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rms_mean: Option<MetricTolerance>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rms_freqonly: Option<MetricTolerance>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rms_dsfb: Option<MetricTolerance>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peak_err_dsfb: Option<MetricTolerance>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_w2: Option<MetricTolerance>,
+}
+
+/// Summary metrics of one scenario run
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioMetrics {
+    /// RMS error of the plain channel mean
+    pub rms_mean: f64,
+    /// RMS error of the frequency-only observer
+    pub rms_freqonly: f64,
+    /// RMS error of the DSFB observer
+    pub rms_dsfb: f64,
+    /// Largest absolute DSFB error over the run
+    pub peak_err_dsfb: f64,
+    /// Smallest trust weight assigned to channel 2
+    pub min_w2: f64,
+}
+
+/// Outcome of checking one metric against its tolerance
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetricCheck {
+    pub metric: &'static str,
+    pub actual: f64,
+    pub tolerance: MetricTolerance,
+    pub passed: bool,
+}
+
+/// Metrics of a scenario run and the result of every configured check
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioReport {
+    pub metrics: ScenarioMetrics,
+    pub checks: Vec<MetricCheck>,
+}
+
+impl ScenarioReport {
+    /// Whether every configured check passed
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.passed)
+    }
+}
+
+/// A named, self-contained simulation test case
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(default)]
+    pub config: SimConfig,
+    #[serde(default)]
+    pub params: DsfbParams,
+    #[serde(default)]
+    pub expected: ScenarioExpectations,
+}
+
+impl Scenario {
+    /// Parse and validate a scenario from JSON
+    pub fn from_json(json: &str) -> io::Result<Self> {
+        let scenario: Self = serde_json::from_str(json)?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    /// Load and validate a scenario from a JSON file
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)?;
+        Self::from_json(&json)
+            .map_err(|err| io::Error::new(err.kind(), format!("{}: {err}", path.display())))
+    }
+
+    /// Pretty-printed JSON representation
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("scenario serialization cannot fail")
+    }
+
+    /// Reject configurations the simulation harness would panic on
+    pub fn validate(&self) -> io::Result<()> {
+        let cfg = &self.config;
+        let invalid = |msg: String| Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+
+        if cfg.steps == 0 {
+            return invalid("config.steps must be > 0".to_string());
+        }
+        if !(cfg.dt.is_finite() && cfg.dt > 0.0) {
+            return invalid(format!("config.dt must be finite and > 0, got {}", cfg.dt));
+        }
+        let non_negative = |v: f64| v.is_finite() && v >= 0.0;
+        if !non_negative(cfg.sigma_noise) || !non_negative(cfg.sigma_alpha) {
+            return invalid("config.sigma_noise and config.sigma_alpha must be >= 0".to_string());
+        }
+        if let Some(event) = cfg
+            .fault_events()
+            .iter()
+            .find(|event| event.channel >= SIM_CHANNELS)
+        {
+            return invalid(format!(
+                "fault channel {} out of range 0..{SIM_CHANNELS}",
+                event.channel
+            ));
+        }
+        Ok(())
+    }
+
+    /// Run the simulation and compute its summary metrics
+    pub fn metrics(&self) -> ScenarioMetrics {
+        let results = run_simulation(self.config.clone(), self.params);
+        let errors = |f: fn(&SimStep) -> f64| -> Vec<f64> { results.iter().map(f).collect() };
+
+        ScenarioMetrics {
+            rms_mean: rms_error(&errors(|s| s.err_mean)),
+            rms_freqonly: rms_error(&errors(|s| s.err_freqonly)),
+            rms_dsfb: rms_error(&errors(|s| s.err_dsfb)),
+            peak_err_dsfb: results.iter().map(|s| s.err_dsfb).fold(0.0, f64::max),
+            min_w2: results.iter().map(|s| s.w2).fold(f64::INFINITY, f64::min),
+        }
+    }
+
+    /// Run the simulation and check its metrics against the expectations
+    pub fn run(&self) -> ScenarioReport {
+        let metrics = self.metrics();
+        let exp = &self.expected;
+        let checks = [
+            ("rms_mean", exp.rms_mean, metrics.rms_mean),
+            ("rms_freqonly", exp.rms_freqonly, metrics.rms_freqonly),
+            ("rms_dsfb", exp.rms_dsfb, metrics.rms_dsfb),
+            ("peak_err_dsfb", exp.peak_err_dsfb, metrics.peak_err_dsfb),
+            ("min_w2", exp.min_w2, metrics.min_w2),
+        ]
+        .into_iter()
+        .filter_map(|(metric, tolerance, actual)| {
+            tolerance.map(|tolerance| MetricCheck {
+                metric,
+                actual,
+                tolerance,
+                passed: tolerance.accepts(actual),
+            })
+        })
+        .collect();
+
+        ScenarioReport { metrics, checks }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rho::RhoPolicy;
+    use crate::sim::FaultEvent;
+    use crate::trust::TrustShape;
+
+    #[test]
+    fn test_scenario_json_round_trip() {
+        let scenario = Scenario {
+            name: "round_trip".to_string(),
+            description: String::new(),
+            config: SimConfig {
+                steps: 50,
+                faults: vec![FaultEvent::drift_rate_change(0, 10, 20, 0.3)],
+                ..Default::default()
+            },
+            params: DsfbParams::default()
+                .with_staleness_decay(0.25)
+                .with_trust_shape(TrustShape::Logistic { center: 0.3 })
+                .with_rho_policy(RhoPolicy::Innovation {
+                    ratio: 4.0,
+                    fast_rho: 0.5,
+                }),
+            expected: ScenarioExpectations {
+                rms_dsfb: Some(MetricTolerance::new(0.1, 0.05)),
+                ..Default::default()
+            },
+        };
+
+        let parsed = Scenario::from_json(&scenario.to_json()).unwrap();
+        assert_eq!(parsed, scenario);
+    }
+
+    #[test]
+    fn test_partial_scenario_uses_defaults() {
+        let scenario =
+            Scenario::from_json(r#"{ "name": "partial", "config": { "steps": 20 } }"#).unwrap();
+        assert_eq!(scenario.config.steps, 20);
+        assert_eq!(scenario.config.dt, SimConfig::default().dt);
+        assert_eq!(scenario.params, DsfbParams::default());
+        assert!(scenario.run().checks.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_scenarios_rejected() {
+        let bad_channel = r#"{ "name": "x", "config": { "faults": [
+            { "channel": 5, "kind": "bias", "start": 0, "duration": 1, "magnitude": 1.0 }
+        ] } }"#;
+        let err = Scenario::from_json(bad_channel).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let typo =
+            r#"{ "name": "x", "expected": { "rms_dsfbb": { "expected": 0, "tolerance": 1 } } }"#;
+        assert!(Scenario::from_json(typo).is_err());
+    }
+
+    #[test]
+    fn test_shipped_scenarios_pass() {
+        for json in [
+            include_str!("../scenarios/drift_impulse.json"),
+            include_str!("../scenarios/multi_fault.json"),
+        ] {
+            let scenario = Scenario::from_json(json).unwrap();
+            let report = scenario.run();
+            assert!(!report.checks.is_empty(), "{} has no checks", scenario.name);
+            assert!(report.passed(), "{}: {:?}", scenario.name, report.checks);
+        }
+    }
+}
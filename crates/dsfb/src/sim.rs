@@ -59,8 +59,113 @@ impl FreqOnlyObserver {
     }
 }
 
+/// Number of measurement channels in the drift-impulse harness
+pub const SIM_CHANNELS: usize = 2;
+
+/// Kind of fault injected into a measurement channel
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum FaultKind {
+    /// Transient additive offset of `magnitude` while the event is active
+    Impulse,
+    /// Sustained additive sensor bias of `magnitude` while the event is active
+    Bias,
+    /// Additional drift of `magnitude` per unit time while the event is active;
+    /// the accumulated offset is held after the event ends
+    DriftRateChange,
+}
+
+/// A fault applied to one channel over a window of simulation steps
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FaultEvent {
+    /// Channel index (0-based)
+    pub channel: usize,
+    pub kind: FaultKind,
+    /// First affected step
+    pub start: usize,
+    /// Number of affected steps
+    pub duration: usize,
+    pub magnitude: f64,
+}
+
+impl FaultEvent {
+    pub fn new(
+        channel: usize,
+        kind: FaultKind,
+        start: usize,
+        duration: usize,
+        magnitude: f64,
+    ) -> Self {
+        Self {
+            channel,
+            kind,
+            start,
+            duration,
+            magnitude,
+        }
+    }
+
+    pub fn impulse(channel: usize, start: usize, duration: usize, magnitude: f64) -> Self {
+        Self::new(channel, FaultKind::Impulse, start, duration, magnitude)
+    }
+
+    pub fn bias(channel: usize, start: usize, duration: usize, magnitude: f64) -> Self {
+        Self::new(channel, FaultKind::Bias, start, duration, magnitude)
+    }
+
+    pub fn drift_rate_change(channel: usize, start: usize, duration: usize, rate: f64) -> Self {
+        Self::new(channel, FaultKind::DriftRateChange, start, duration, rate)
+    }
+
+    /// One past the last affected step
+    pub fn end(&self) -> usize {
+        self.start.saturating_add(self.duration)
+    }
+
+    /// Whether the event is active at `step`
+    pub fn is_active(&self, step: usize) -> bool {
+        step >= self.start && step < self.end()
+    }
+
+    /// Additive measurement offset contributed by this event at `step`
+    pub fn offset(&self, step: usize, dt: f64) -> f64 {
+        match self.kind {
+            FaultKind::Impulse | FaultKind::Bias => {
+                if self.is_active(step) {
+                    self.magnitude
+                } else {
+                    0.0
+                }
+            }
+            FaultKind::DriftRateChange => {
+                if step < self.start {
+                    0.0
+                } else {
+                    let elapsed = step.min(self.end()) - self.start;
+                    self.magnitude * elapsed as f64 * dt
+                }
+            }
+        }
+    }
+}
+
 /// Simulation configuration
-#[derive(Clone)]
+///
+/// The legacy `impulse_*` fields describe a single impulse on channel 2;
+/// `faults` adds any number of further events on any channel.
+///
+/// With the `serde` feature, omitted fields deserialize to their defaults.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct SimConfig {
     pub dt: f64,
     pub steps: usize,
@@ -70,9 +175,28 @@ pub struct SimConfig {
     pub impulse_start: usize,
     pub impulse_duration: usize,
     pub impulse_amplitude: f64,
+    pub faults: Vec<FaultEvent>,
     pub seed: u64,
 }
 
+impl SimConfig {
+    /// All fault events for the run: the legacy channel-2 impulse (if any)
+    /// followed by the entries in `faults`.
+    pub fn fault_events(&self) -> Vec<FaultEvent> {
+        let mut events = Vec::with_capacity(self.faults.len() + 1);
+        if self.impulse_duration > 0 && self.impulse_amplitude != 0.0 {
+            events.push(FaultEvent::impulse(
+                1,
+                self.impulse_start,
+                self.impulse_duration,
+                self.impulse_amplitude,
+            ));
+        }
+        events.extend(self.faults.iter().cloned());
+        events
+    }
+}
+
 impl Default for SimConfig {
     fn default() -> Self {
         Self {
@@ -84,6 +208,7 @@ impl Default for SimConfig {
             impulse_start: 300,
             impulse_duration: 100,
             impulse_amplitude: 1.0,
+            faults: Vec::new(),
             seed: 42,
         }
     }
@@ -162,11 +287,20 @@ pub fn run_simulation_trace(
     let noise_dist = Normal::new(0.0, config.sigma_noise).unwrap();
     let alpha_dist = Normal::new(0.0, config.sigma_alpha).unwrap();
 
+    let faults = config.fault_events();
+    for event in &faults {
+        assert!(
+            event.channel < SIM_CHANNELS,
+            "fault channel {} out of range 0..{SIM_CHANNELS}",
+            event.channel
+        );
+    }
+
     // Initialize true state
     let mut true_state = TrueState::new(0.0, 0.5, 0.0);
 
     // Initialize observers
-    let mut dsfb = DsfbObserver::new(dsfb_params, 2);
+    let mut dsfb = DsfbObserver::new(dsfb_params, SIM_CHANNELS);
     dsfb.init(DsfbState::new(0.0, 0.5, 0.0));
 
     let mut freqonly = FreqOnlyObserver::new(0.5, 0.1);
@@ -180,14 +314,18 @@ pub fn run_simulation_trace(
         let noise1 = noise_dist.sample(&mut rng);
         let noise2 = noise_dist.sample(&mut rng);
 
-        let y1 = true_state.phi + noise1;
+        let mut y1 = true_state.phi + noise1;
 
         // Channel 2 has drift
         let mut y2 = true_state.phi + config.drift_beta * t + noise2;
 
-        // Add impulse
-        if step >= config.impulse_start && step < config.impulse_start + config.impulse_duration {
-            y2 += config.impulse_amplitude;
+        // Add fault events
+        for event in &faults {
+            let offset = event.offset(step, config.dt);
+            match event.channel {
+                0 => y1 += offset,
+                _ => y2 += offset,
+            }
         }
 
         // Mean fusion
@@ -293,6 +431,104 @@ mod tests {
         assert_eq!(trace[0].residuals.len(), 2);
     }
 
+    #[test]
+    fn test_f32_observer_tracks_f64_on_drift_impulse() {
+        let config = SimConfig::default();
+        let dt = config.dt;
+        let trace = run_simulation_trace(config, DsfbParams::default());
+
+        let mut observer = DsfbObserver::<f32>::new(DsfbParams::default(), 2);
+        observer.init(DsfbState::new(0.0, 0.5, 0.0));
+
+        let mut max_phi_diff = 0.0f64;
+        let mut max_weight_diff = 0.0f64;
+        for step in &trace {
+            let measurements: Vec<f32> = step.measurements.iter().map(|&y| y as f32).collect();
+            let state = observer.step(&measurements, dt as f32);
+            max_phi_diff = max_phi_diff.max((f64::from(state.phi) - step.dsfb_state.phi).abs());
+            for (k, stats) in step.trust_stats.iter().enumerate() {
+                let w = f64::from(observer.trust_weight(k));
+                max_weight_diff = max_weight_diff.max((w - stats.weight).abs());
+            }
+        }
+
+        assert!(max_phi_diff < 1e-5, "phi divergence {max_phi_diff}");
+        assert!(
+            max_weight_diff < 1e-5,
+            "weight divergence {max_weight_diff}"
+        );
+    }
+
+    #[test]
+    fn test_fault_event_offsets() {
+        let impulse = FaultEvent::impulse(0, 10, 5, 2.0);
+        assert_eq!(impulse.offset(9, 0.1), 0.0);
+        assert_eq!(impulse.offset(10, 0.1), 2.0);
+        assert_eq!(impulse.offset(15, 0.1), 0.0);
+
+        let drift = FaultEvent::drift_rate_change(1, 10, 5, 2.0);
+        assert_eq!(drift.offset(10, 0.1), 0.0);
+        assert!((drift.offset(12, 0.1) - 0.4).abs() < 1e-12);
+        assert!((drift.offset(40, 0.1) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_legacy_impulse_matches_fault_event() {
+        let legacy = SimConfig {
+            steps: 200,
+            impulse_start: 50,
+            impulse_duration: 20,
+            ..Default::default()
+        };
+        let explicit = SimConfig {
+            impulse_amplitude: 0.0,
+            faults: vec![FaultEvent::impulse(1, 50, 20, legacy.impulse_amplitude)],
+            ..legacy.clone()
+        };
+
+        let a = run_simulation_trace(legacy, DsfbParams::default());
+        let b = run_simulation_trace(explicit, DsfbParams::default());
+        for (x, y) in a.iter().zip(b.iter()) {
+            assert_eq!(x.measurements, y.measurements);
+        }
+    }
+
+    #[test]
+    fn test_multi_fault_sequence() {
+        let config = SimConfig {
+            steps: 200,
+            sigma_noise: 0.0,
+            sigma_alpha: 0.0,
+            drift_beta: 0.0,
+            impulse_amplitude: 0.0,
+            faults: vec![
+                FaultEvent::impulse(0, 20, 10, 1.0),
+                FaultEvent::bias(1, 60, 40, -0.5),
+                FaultEvent::impulse(1, 80, 5, 2.0),
+            ],
+            ..Default::default()
+        };
+        let trace = run_simulation_trace(config, DsfbParams::default());
+
+        let offset = |step: usize, ch: usize| trace[step].measurements[ch] - trace[step].phi_true;
+        assert!((offset(25, 0) - 1.0).abs() < 1e-12);
+        assert!(offset(35, 0).abs() < 1e-12);
+        assert!((offset(70, 1) + 0.5).abs() < 1e-12);
+        assert!((offset(82, 1) - 1.5).abs() < 1e-12);
+        assert!(offset(150, 1).abs() < 1e-12);
+    }
+
+    #[test]
+    #[should_panic(expected = "fault channel")]
+    fn test_fault_channel_out_of_range_panics() {
+        let config = SimConfig {
+            steps: 10,
+            faults: vec![FaultEvent::bias(SIM_CHANNELS, 0, 5, 1.0)],
+            ..Default::default()
+        };
+        run_simulation_trace(config, DsfbParams::default());
+    }
+
     #[test]
     fn test_rms_error() {
         let errors = vec![0.1, 0.2, 0.3];
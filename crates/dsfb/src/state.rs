@@ -5,34 +5,57 @@
 //! - omega: velocity/frequency (drift)
 //! - alpha: acceleration/slew
 
+use crate::scalar::Real;
+use crate::units::{Phase, Rate, Slew};
+
 /// State of the DSFB observer
 #[derive(Debug, Clone, Copy, PartialEq)]
-pub struct DsfbState {
+pub struct DsfbState<T: Real = f64> {
     /// Position/phase
-    pub phi: f64,
+    pub phi: T,
     /// Velocity/frequency (drift)
-    pub omega: f64,
+    pub omega: T,
     /// Acceleration/slew
-    pub alpha: f64,
+    pub alpha: T,
 }
 
-impl DsfbState {
+impl<T: Real> DsfbState<T> {
     /// Create a new DSFB state
-    pub fn new(phi: f64, omega: f64, alpha: f64) -> Self {
+    pub fn new(phi: T, omega: T, alpha: T) -> Self {
         Self { phi, omega, alpha }
     }
 
+    /// Create a state from typed components
+    pub fn from_units(phi: Phase<T>, omega: Rate<T>, alpha: Slew<T>) -> Self {
+        Self::new(phi.0, omega.0, alpha.0)
+    }
+
+    /// Position/phase as a typed quantity
+    pub fn phase(&self) -> Phase<T> {
+        Phase(self.phi)
+    }
+
+    /// Velocity/frequency (drift) as a typed quantity
+    pub fn rate(&self) -> Rate<T> {
+        Rate(self.omega)
+    }
+
+    /// Acceleration/slew as a typed quantity
+    pub fn slew(&self) -> Slew<T> {
+        Slew(self.alpha)
+    }
+
     /// Create a zero state
     pub fn zero() -> Self {
         Self {
-            phi: 0.0,
-            omega: 0.0,
-            alpha: 0.0,
+            phi: T::ZERO,
+            omega: T::ZERO,
+            alpha: T::ZERO,
         }
     }
 }
 
-impl Default for DsfbState {
+impl<T: Real> Default for DsfbState<T> {
     fn default() -> Self {
         Self::zero()
     }
@@ -2,68 +2,579 @@
 //!
 //! Implements the trust-adaptive mechanism using EMA residuals
 
+use crate::scalar::Real;
+
+/// Raw trust of a channel as a function of its residual envelope
+///
+/// Raw trust is normalized across channels afterwards, so only the relative
+/// shape matters. Implementations should be non-negative and non-increasing
+/// in `envelope`.
+pub trait TrustFunction<T: Real> {
+    /// Raw (unnormalized) trust of a channel with EMA residual `envelope` and
+    /// trust softness `sigma0`
+    fn raw_trust(&self, envelope: T, sigma0: T) -> T;
+}
+
+/// Built-in trust shapes, selected through
+/// [`DsfbParams::trust_shape`](crate::DsfbParams::trust_shape)
+///
+/// `sigma0` sets the residual scale of every shape; shapes other than
+/// [`TrustShape::Reciprocal`] require it to be positive.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "kind", rename_all = "snake_case")
+)]
+pub enum TrustShape<T: Real = f64> {
+    /// `1 / (sigma0 + s)`: heavy-tailed, a faulty channel keeps some trust
+    #[default]
+    Reciprocal,
+    /// `exp(-s / sigma0)`: trust falls off faster than any power of `s`
+    Exponential,
+    /// `1 / (1 + exp((s - center) / sigma0))`: near full trust below `center`,
+    /// near zero above it, with a transition of width `sigma0`
+    Logistic {
+        /// Envelope at which raw trust is one half
+        center: T,
+    },
+    /// `1` while `s <= threshold`, `0` above it
+    HardThreshold {
+        /// Largest envelope that keeps full trust
+        threshold: T,
+    },
+}
+
+impl<T: Real> TrustFunction<T> for TrustShape<T> {
+    fn raw_trust(&self, envelope: T, sigma0: T) -> T {
+        match *self {
+            TrustShape::Reciprocal => T::ONE / (sigma0 + envelope),
+            TrustShape::Exponential => (-envelope / sigma0).exp(),
+            TrustShape::Logistic { center } => {
+                T::ONE / (T::ONE + ((envelope - center) / sigma0).exp())
+            }
+            TrustShape::HardThreshold { threshold } => {
+                if envelope <= threshold {
+                    T::ONE
+                } else {
+                    T::ZERO
+                }
+            }
+        }
+    }
+}
+
 /// Trust statistics for a single channel
 #[derive(Debug, Clone, PartialEq)]
-pub struct TrustStats {
+pub struct TrustStats<T: Real = f64> {
     /// EMA of absolute residuals
-    pub residual_ema: f64,
+    pub residual_ema: T,
     /// Trust weight (normalized)
-    pub weight: f64,
+    pub weight: T,
+    /// EMA of signed residuals: the channel's bias relative to the fused state
+    pub bias: T,
+    /// EMA of the rate of change of `bias` (per unit time)
+    pub drift_rate: T,
 }
 
-impl TrustStats {
+impl<T: Real> TrustStats<T> {
     /// Create new trust statistics
     pub fn new() -> Self {
         Self {
-            residual_ema: 0.0,
-            weight: 1.0,
+            residual_ema: T::ZERO,
+            weight: T::ONE,
+            bias: T::ZERO,
+            drift_rate: T::ZERO,
+        }
+    }
+
+    /// Update the bias and drift-rate estimates from a signed residual
+    ///
+    /// Uses the same forgetting factor as the residual envelope:
+    /// `b_k = rho*b_k + (1-rho)*r_k`, and `d_k` is the EMA of `(b_k - b_k^-) / dt`.
+    pub fn update_drift(&mut self, residual: T, rho: T, dt: T) {
+        let previous = self.bias;
+        self.bias = rho * self.bias + (T::ONE - rho) * residual;
+        if dt > T::ZERO {
+            let rate = (self.bias - previous) / dt;
+            self.drift_rate = rho * self.drift_rate + (T::ONE - rho) * rate;
         }
     }
 }
 
-impl Default for TrustStats {
+impl<T: Real> Default for TrustStats<T> {
     fn default() -> Self {
         Self::new()
     }
 }
 
 /// Calculate trust weights from residuals
-pub fn calculate_trust_weights(
-    residuals: &[f64],
-    ema_residuals: &mut [f64],
-    rho: f64,
-    sigma0: f64,
-) -> Vec<f64> {
+#[cfg(feature = "std")]
+pub fn calculate_trust_weights<T: Real>(
+    residuals: &[T],
+    ema_residuals: &mut [T],
+    rho: T,
+    sigma0: T,
+) -> Vec<T> {
+    calculate_trust_weights_with_floor(residuals, ema_residuals, rho, sigma0, T::ZERO)
+}
+
+/// [`calculate_trust_weights`] with every weight raised to at least
+/// `min_weight` after normalization (see [`apply_weight_floor`])
+///
+/// The uniform fallback already satisfies any feasible floor, so the floor
+/// never changes it.
+#[cfg(feature = "std")]
+pub fn calculate_trust_weights_with_floor<T: Real>(
+    residuals: &[T],
+    ema_residuals: &mut [T],
+    rho: T,
+    sigma0: T,
+    min_weight: T,
+) -> Vec<T> {
+    trust_weights(
+        &TrustShape::Reciprocal,
+        residuals,
+        ema_residuals,
+        |_| rho,
+        sigma0,
+        min_weight,
+    )
+}
+
+/// [`calculate_trust_weights_with_floor`] with raw trust from `trust_fn`
+/// instead of `1 / (sigma0 + s_k)` and a separate forgetting factor `rho[k]`
+/// for each channel, e.g. from a [`RhoSchedule`](crate::rho::RhoSchedule)
+#[cfg(feature = "std")]
+pub fn calculate_trust_weights_with<T: Real>(
+    trust_fn: &impl TrustFunction<T>,
+    residuals: &[T],
+    ema_residuals: &mut [T],
+    rho: &[T],
+    sigma0: T,
+    min_weight: T,
+) -> Vec<T> {
+    trust_weights(
+        trust_fn,
+        residuals,
+        ema_residuals,
+        |k| rho[k],
+        sigma0,
+        min_weight,
+    )
+}
+
+/// Shared body of the single-rate trust weight functions, with the
+/// forgetting factor of channel `k` given by `rho(k)`
+#[cfg(feature = "std")]
+fn trust_weights<T: Real>(
+    trust_fn: &impl TrustFunction<T>,
+    residuals: &[T],
+    ema_residuals: &mut [T],
+    rho: impl Fn(usize) -> T,
+    sigma0: T,
+    min_weight: T,
+) -> Vec<T> {
     let n = residuals.len();
-    let mut raw_weights = vec![0.0; n];
+    let mut raw_weights = vec![T::ZERO; n];
 
     // Update EMA and calculate raw trust weights
     for k in 0..n {
-        // Update EMA: s_k = rho*s_k + (1-rho)*|r_k|
-        ema_residuals[k] = rho * ema_residuals[k] + (1.0 - rho) * residuals[k].abs();
+        // Update EMA: s_k = rho_k*s_k + (1-rho_k)*|r_k|
+        let rho_k = rho(k);
+        ema_residuals[k] = rho_k * ema_residuals[k] + (T::ONE - rho_k) * residuals[k].abs();
 
-        // Trust softness: wtilde_k = 1 / (sigma0 + s_k)
-        raw_weights[k] = 1.0 / (sigma0 + ema_residuals[k]);
+        // Raw trust: wtilde_k = f(s_k), 1 / (sigma0 + s_k) by default
+        raw_weights[k] = trust_fn.raw_trust(ema_residuals[k], sigma0);
     }
 
     // Normalize weights: w_k = wtilde_k / sum_j wtilde_j
-    let sum: f64 = raw_weights.iter().sum();
-    if sum > 0.0 {
+    let sum: T = raw_weights.iter().copied().sum();
+    if sum > T::ZERO {
         for w in raw_weights.iter_mut() {
             *w /= sum;
         }
     } else {
         // Fallback to uniform weights
-        let uniform = 1.0 / n as f64;
+        let uniform = T::ONE / T::from_usize(n);
         for w in raw_weights.iter_mut() {
             *w = uniform;
         }
     }
 
+    floor_weights(&mut raw_weights, min_weight, |_| true);
     raw_weights
 }
 
-#[cfg(test)]
+/// Calculate trust weights for many observers at once
+///
+/// `residuals`, `ema_residuals`, and `weights` are channel-major: with
+/// `observers = residuals.len() / channels`, entry `k * observers + o` belongs
+/// to channel `k` of observer `o`. Each observer gets exactly the weights and
+/// envelope update [`calculate_trust_weights_with_floor`] would give it, but the EMA
+/// update and normalization run as flat loops over contiguous rows, without
+/// per-observer allocation or bounds checks, so they autovectorize. Raw
+/// trust is always the default `1 / (sigma0 + s_k)`.
+///
+/// # Panics
+///
+/// If `channels` is zero, the lengths of the three slices differ, or they are
+/// not a multiple of `channels`.
+#[cfg(feature = "std")]
+pub fn calculate_trust_weights_batch<T: Real>(
+    residuals: &[T],
+    ema_residuals: &mut [T],
+    weights: &mut [T],
+    channels: usize,
+    rho: T,
+    sigma0: T,
+    min_weight: T,
+) {
+    assert!(channels > 0, "channels must be positive");
+    assert_eq!(
+        residuals.len() % channels,
+        0,
+        "residual count must be a multiple of channels"
+    );
+    assert_eq!(ema_residuals.len(), residuals.len(), "ema_residuals length");
+    assert_eq!(weights.len(), residuals.len(), "weights length");
+
+    let observers = residuals.len() / channels;
+    if observers == 0 {
+        return;
+    }
+
+    for ((&r, s), w) in residuals
+        .iter()
+        .zip(ema_residuals.iter_mut())
+        .zip(weights.iter_mut())
+    {
+        *s = rho * *s + (T::ONE - rho) * r.abs();
+        *w = T::ONE / (sigma0 + *s);
+    }
+
+    let mut sums = vec![T::ZERO; observers];
+    for row in weights.chunks_exact(observers) {
+        for (sum, &w) in sums.iter_mut().zip(row) {
+            *sum += w;
+        }
+    }
+
+    let uniform = T::ONE / T::from_usize(channels);
+    for row in weights.chunks_exact_mut(observers) {
+        for (w, &sum) in row.iter_mut().zip(&sums) {
+            if sum > T::ZERO {
+                *w /= sum;
+            } else {
+                *w = uniform;
+            }
+        }
+    }
+
+    if min_weight > T::ZERO {
+        let mut column = vec![T::ZERO; channels];
+        for o in 0..observers {
+            for (k, c) in column.iter_mut().enumerate() {
+                *c = weights[k * observers + o];
+            }
+            floor_weights(&mut column, min_weight, |_| true);
+            for (k, &c) in column.iter().enumerate() {
+                weights[k * observers + o] = c;
+            }
+        }
+    }
+}
+
+/// Calculate trust weights when some channels carry stale samples
+///
+/// Only channels with `fresh[k]` update their EMA residual; stale channels keep
+/// their envelope and have their raw trust scaled by `staleness_factors[k]`
+/// (`decay^n` after `n` stale steps, `0` for a channel that has never
+/// reported). If no channel has usable trust, all weights are zero.
+#[cfg(feature = "std")]
+pub fn calculate_multirate_trust_weights<T: Real>(
+    residuals: &[T],
+    ema_residuals: &mut [T],
+    fresh: &[bool],
+    staleness_factors: &[T],
+    rho: T,
+    sigma0: T,
+) -> Vec<T> {
+    calculate_multirate_trust_weights_with_floor(
+        residuals,
+        ema_residuals,
+        fresh,
+        staleness_factors,
+        rho,
+        sigma0,
+        T::ZERO,
+    )
+}
+
+/// [`calculate_multirate_trust_weights`] with a weight floor
+///
+/// `min_weight` floors the channels with a non-zero staleness factor; a
+/// channel that has never reported stays at zero.
+#[cfg(feature = "std")]
+pub fn calculate_multirate_trust_weights_with_floor<T: Real>(
+    residuals: &[T],
+    ema_residuals: &mut [T],
+    fresh: &[bool],
+    staleness_factors: &[T],
+    rho: T,
+    sigma0: T,
+    min_weight: T,
+) -> Vec<T> {
+    let mut weights = vec![T::ZERO; residuals.len()];
+    multirate_trust_weights_into(
+        &TrustShape::Reciprocal,
+        residuals,
+        ema_residuals,
+        fresh,
+        staleness_factors,
+        |_| rho,
+        |_| sigma0,
+        min_weight,
+        &mut weights,
+    );
+    weights
+}
+
+/// [`calculate_multirate_trust_weights_with_floor`] with a separate trust softness
+/// `sigma0[k]` for each channel, e.g. from a
+/// [`Sigma0Calibration`](crate::calibration::Sigma0Calibration)
+#[cfg(feature = "std")]
+pub fn calculate_multirate_trust_weights_per_channel<T: Real>(
+    residuals: &[T],
+    ema_residuals: &mut [T],
+    fresh: &[bool],
+    staleness_factors: &[T],
+    rho: T,
+    sigma0: &[T],
+    min_weight: T,
+) -> Vec<T> {
+    let mut weights = vec![T::ZERO; residuals.len()];
+    multirate_trust_weights_into(
+        &TrustShape::Reciprocal,
+        residuals,
+        ema_residuals,
+        fresh,
+        staleness_factors,
+        |_| rho,
+        |k| sigma0[k],
+        min_weight,
+        &mut weights,
+    );
+    weights
+}
+
+/// [`calculate_multirate_trust_weights_per_channel`] with raw trust from
+/// `trust_fn` instead of `1 / (sigma0_k + s_k)` and a separate forgetting
+/// factor `rho[k]` for each channel
+#[cfg(feature = "std")]
+#[allow(clippy::too_many_arguments)]
+pub fn calculate_multirate_trust_weights_with<T: Real>(
+    trust_fn: &impl TrustFunction<T>,
+    residuals: &[T],
+    ema_residuals: &mut [T],
+    fresh: &[bool],
+    staleness_factors: &[T],
+    rho: &[T],
+    sigma0: &[T],
+    min_weight: T,
+) -> Vec<T> {
+    let mut weights = vec![T::ZERO; residuals.len()];
+    calculate_multirate_trust_weights_into(
+        trust_fn,
+        residuals,
+        ema_residuals,
+        fresh,
+        staleness_factors,
+        rho,
+        sigma0,
+        min_weight,
+        &mut weights,
+    );
+    weights
+}
+
+/// [`calculate_multirate_trust_weights_with`] writing into `weights`, which
+/// must have one entry per channel, instead of allocating
+///
+/// Available without `std`; [`DsfbObserverFixed`](crate::DsfbObserverFixed)
+/// runs its trust update through it.
+#[allow(clippy::too_many_arguments)]
+pub fn calculate_multirate_trust_weights_into<T: Real>(
+    trust_fn: &impl TrustFunction<T>,
+    residuals: &[T],
+    ema_residuals: &mut [T],
+    fresh: &[bool],
+    staleness_factors: &[T],
+    rho: &[T],
+    sigma0: &[T],
+    min_weight: T,
+    weights: &mut [T],
+) {
+    multirate_trust_weights_into(
+        trust_fn,
+        residuals,
+        ema_residuals,
+        fresh,
+        staleness_factors,
+        |k| rho[k],
+        |k| sigma0[k],
+        min_weight,
+        weights,
+    );
+}
+
+/// Shared body of the multi-rate trust weight functions, with the forgetting
+/// factor and trust softness of channel `k` given by `rho(k)` and `sigma0(k)`
+#[allow(clippy::too_many_arguments)]
+fn multirate_trust_weights_into<T: Real>(
+    trust_fn: &impl TrustFunction<T>,
+    residuals: &[T],
+    ema_residuals: &mut [T],
+    fresh: &[bool],
+    staleness_factors: &[T],
+    rho: impl Fn(usize) -> T,
+    sigma0: impl Fn(usize) -> T,
+    min_weight: T,
+    weights: &mut [T],
+) {
+    let n = residuals.len();
+
+    for k in 0..n {
+        if fresh[k] {
+            let rho_k = rho(k);
+            ema_residuals[k] = rho_k * ema_residuals[k] + (T::ONE - rho_k) * residuals[k].abs();
+        }
+        weights[k] = staleness_factors[k] * trust_fn.raw_trust(ema_residuals[k], sigma0(k));
+    }
+
+    let sum: T = weights.iter().copied().sum();
+    if sum > T::ZERO {
+        for w in weights.iter_mut() {
+            *w /= sum;
+        }
+    } else {
+        // Fallback to uniform weights over the channels that may be used
+        let usable = staleness_factors.iter().filter(|&&f| f > T::ZERO).count();
+        for (w, &f) in weights.iter_mut().zip(staleness_factors) {
+            *w = if f > T::ZERO {
+                T::ONE / T::from_usize(usable)
+            } else {
+                T::ZERO
+            };
+        }
+    }
+
+    floor_weights(weights, min_weight, |k| staleness_factors[k] > T::ZERO);
+}
+
+/// Raise normalized weights to a floor and renormalize the rest
+///
+/// Every `eligible` channel ends with a weight of at least `min_weight`. The
+/// mass left after the floored channels is shared by the others in proportion
+/// to their current weights, repeating until no further channel falls below
+/// the floor, so the weights still sum to one. A floor of zero leaves the
+/// weights unchanged; a floor of at least `1 / eligible_count` cannot be met
+/// and gives uniform weights over the eligible channels. Ineligible channels
+/// must already have zero weight and keep it.
+pub fn apply_weight_floor<T: Real>(weights: &mut [T], min_weight: T, eligible: &[bool]) {
+    floor_weights(weights, min_weight, |k| eligible[k]);
+}
+
+/// [`apply_weight_floor`] with eligibility given per channel index
+///
+/// The floored set only grows while the renormalization scale shrinks, so a
+/// channel is floored exactly when its weight times the smallest scale seen
+/// so far falls below `min_weight`; that keeps the loop free of allocation.
+fn floor_weights<T: Real>(weights: &mut [T], min_weight: T, eligible: impl Fn(usize) -> bool) {
+    let count = (0..weights.len()).filter(|&k| eligible(k)).count();
+    if min_weight <= T::ZERO || count == 0 {
+        return;
+    }
+
+    if min_weight * T::from_usize(count) >= T::ONE {
+        let uniform = T::ONE / T::from_usize(count);
+        for (k, w) in weights.iter_mut().enumerate() {
+            *w = if eligible(k) { uniform } else { T::ZERO };
+        }
+        return;
+    }
+
+    let mut floor_scale: Option<T> = None;
+    let floored = |w: T, floor_scale: Option<T>| floor_scale.is_some_and(|s| w * s < min_weight);
+    loop {
+        let floored_count = (0..weights.len())
+            .filter(|&k| eligible(k) && floored(weights[k], floor_scale))
+            .count();
+        let free_mass = T::ONE - min_weight * T::from_usize(floored_count);
+        let free_sum: T = (0..weights.len())
+            .filter(|&k| eligible(k) && !floored(weights[k], floor_scale))
+            .map(|k| weights[k])
+            .sum();
+        if free_sum <= T::ZERO {
+            break;
+        }
+
+        let scale = free_mass / free_sum;
+        let changed = (0..weights.len()).any(|k| {
+            eligible(k) && !floored(weights[k], floor_scale) && weights[k] * scale < min_weight
+        });
+
+        if changed {
+            floor_scale = Some(match floor_scale {
+                Some(s) if s < scale => s,
+                _ => scale,
+            });
+        } else {
+            for (k, w) in weights.iter_mut().enumerate() {
+                if !eligible(k) {
+                    continue;
+                }
+                *w = if floored(*w, floor_scale) {
+                    min_weight
+                } else {
+                    *w * scale
+                };
+            }
+            break;
+        }
+    }
+}
+
+/// Variance of the trust-weighted sum of channels
+///
+/// Each channel's standard deviation is taken from its residual envelope as
+/// `sigma0 + s_k`, the same quantity that sets its raw trust. Channels are
+/// treated as independent, so the fused variance is `sum_k w_k^2 (sigma0 + s_k)^2`.
+pub fn fused_variance<T: Real>(weights: &[T], ema_residuals: &[T], sigma0: T) -> T {
+    weights
+        .iter()
+        .zip(ema_residuals.iter())
+        .map(|(&w, &s)| {
+            let sigma = sigma0 + s;
+            w * w * sigma * sigma
+        })
+        .sum()
+}
+
+/// [`fused_variance`] with a separate `sigma0[k]` for each channel
+pub fn fused_variance_per_channel<T: Real>(weights: &[T], ema_residuals: &[T], sigma0: &[T]) -> T {
+    weights
+        .iter()
+        .zip(ema_residuals.iter())
+        .zip(sigma0)
+        .map(|((&w, &s), &sigma0)| {
+            let sigma = sigma0 + s;
+            w * w * sigma * sigma
+        })
+        .sum()
+}
+
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
 
@@ -88,4 +599,206 @@ mod tests {
         let sum: f64 = weights.iter().sum();
         assert!((sum - 1.0).abs() < 1e-10);
     }
+
+    #[test]
+    fn test_trust_shapes_fall_with_envelope() {
+        let shapes = [
+            TrustShape::Reciprocal,
+            TrustShape::Exponential,
+            TrustShape::Logistic { center: 0.3 },
+            TrustShape::HardThreshold { threshold: 0.3 },
+        ];
+        for shape in shapes {
+            let trust: Vec<f64> = [0.0, 0.1, 0.3, 1.0, 10.0]
+                .iter()
+                .map(|&s| shape.raw_trust(s, 0.1))
+                .collect();
+            assert!(trust.windows(2).all(|w| w[1] <= w[0]), "{shape:?}");
+            assert!(trust.iter().all(|&w| w >= 0.0), "{shape:?}");
+        }
+
+        assert!((TrustShape::Logistic { center: 0.3 }.raw_trust(0.3, 0.1) - 0.5).abs() < 1e-12);
+        assert_eq!(
+            TrustShape::HardThreshold { threshold: 0.3 }.raw_trust(0.3, 0.1),
+            1.0
+        );
+        assert_eq!(
+            TrustShape::HardThreshold { threshold: 0.3 }.raw_trust(0.31, 0.1),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_exponential_shape_suppresses_outlier_harder() {
+        let residuals = vec![0.05, 0.05, 0.5];
+        let reciprocal = calculate_trust_weights(&residuals, &mut [0.0; 3], 0.5, 0.1);
+        let exponential = calculate_trust_weights_with(
+            &TrustShape::Exponential,
+            &residuals,
+            &mut [0.0; 3],
+            &[0.5; 3],
+            0.1,
+            0.0,
+        );
+
+        assert!(exponential[2] < 0.5 * reciprocal[2]);
+        assert!((exponential.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_hard_threshold_excludes_and_falls_back() {
+        let shape = TrustShape::HardThreshold { threshold: 0.2 };
+        let weights = calculate_trust_weights_with(
+            &shape,
+            &[0.0, 0.1, 1.0],
+            &mut [0.0; 3],
+            &[0.5; 3],
+            0.1,
+            0.0,
+        );
+        assert_eq!(weights, vec![0.5, 0.5, 0.0]);
+
+        // Every channel above the threshold leaves only the uniform fallback
+        let weights =
+            calculate_trust_weights_with(&shape, &[1.0; 3], &mut [0.0; 3], &[0.5; 3], 0.1, 0.0);
+        assert!(weights.iter().all(|&w| (w - 1.0 / 3.0).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_batch_weights_match_scalar_path() {
+        // Three channels for four observers, one of which hits the fallback
+        let per_observer = [
+            [0.1, 1.0, 0.5],
+            [0.0, 0.0, 0.0],
+            [2.0, 0.3, 0.05],
+            [f64::INFINITY; 3],
+        ];
+        let observers = per_observer.len();
+        let mut residuals = vec![0.0; 3 * observers];
+        for (o, row) in per_observer.iter().enumerate() {
+            for (k, &r) in row.iter().enumerate() {
+                residuals[k * observers + o] = r;
+            }
+        }
+
+        for min_weight in [0.0, 0.2] {
+            let mut ema_residuals = vec![0.05; 3 * observers];
+            let mut weights = vec![0.0; 3 * observers];
+            calculate_trust_weights_batch(
+                &residuals,
+                &mut ema_residuals,
+                &mut weights,
+                3,
+                0.9,
+                0.1,
+                min_weight,
+            );
+
+            for (o, row) in per_observer.iter().enumerate() {
+                let mut ema = vec![0.05; 3];
+                let expected =
+                    calculate_trust_weights_with_floor(row, &mut ema, 0.9, 0.1, min_weight);
+                for k in 0..3 {
+                    assert_eq!(weights[k * observers + o], expected[k]);
+                    assert_eq!(ema_residuals[k * observers + o], ema[k]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_multirate_weights_freeze_stale_envelope() {
+        let residuals = vec![0.0, 0.0];
+        let mut ema_residuals = vec![0.2, 0.2];
+        let weights = calculate_multirate_trust_weights(
+            &residuals,
+            &mut ema_residuals,
+            &[true, false],
+            &[1.0, 0.5],
+            0.9,
+            0.1,
+        );
+
+        assert!((ema_residuals[0] - 0.18).abs() < 1e-12);
+        assert_eq!(ema_residuals[1], 0.2);
+        assert!(weights[1] < weights[0]);
+        assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_weight_floor_renormalizes_other_channels() {
+        let residuals = vec![0.0, 0.0, 10.0];
+        let mut ema_residuals = vec![0.0, 0.1, 10.0];
+        let unfloored = calculate_trust_weights(&residuals, &mut ema_residuals.clone(), 0.9, 0.1);
+        let weights =
+            calculate_trust_weights_with_floor(&residuals, &mut ema_residuals, 0.9, 0.1, 0.1);
+
+        assert!(unfloored[2] < 0.1);
+        assert!((weights[2] - 0.1).abs() < 1e-12);
+        assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        // The unfloored channels keep their relative trust
+        assert!((weights[0] / weights[1] - unfloored[0] / unfloored[1]).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_weight_floor_cascades_and_saturates() {
+        let mut weights = vec![0.9, 0.06, 0.04];
+        apply_weight_floor(&mut weights, 0.08, &[true; 3]);
+        assert!((weights[1] - 0.08).abs() < 1e-12);
+        assert!((weights[2] - 0.08).abs() < 1e-12);
+        assert!((weights[0] - 0.84).abs() < 1e-12);
+
+        // An infeasible floor degrades to uniform weights
+        let mut weights = vec![0.9, 0.06, 0.04];
+        apply_weight_floor(&mut weights, 0.5, &[true; 3]);
+        assert!(weights.iter().all(|&w| (w - 1.0 / 3.0).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_weight_floor_leaves_uniform_fallback_unchanged() {
+        // Infinite residuals zero every raw weight and trigger the fallback
+        let residuals = vec![f64::INFINITY; 3];
+        let mut ema_residuals = vec![0.0; 3];
+        let weights =
+            calculate_trust_weights_with_floor(&residuals, &mut ema_residuals, 0.9, 0.1, 0.2);
+        assert!(weights.iter().all(|&w| (w - 1.0 / 3.0).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_multirate_weight_floor_skips_unreported_channels() {
+        let weights = calculate_multirate_trust_weights_with_floor(
+            &[0.0, 0.0, 0.0],
+            &mut [0.0, 5.0, 0.0],
+            &[true, true, false],
+            &[1.0, 1.0, 0.0],
+            0.9,
+            0.1,
+            0.2,
+        );
+        assert!((weights[1] - 0.2).abs() < 1e-12);
+        assert!((weights[0] - 0.8).abs() < 1e-12);
+        assert_eq!(weights[2], 0.0);
+    }
+
+    #[test]
+    fn test_fused_variance_uniform_channels() {
+        // Equal envelopes with uniform weights shrink variance by 1/n.
+        let weights = vec![0.25; 4];
+        let ema_residuals = vec![0.1; 4];
+        let variance = fused_variance(&weights, &ema_residuals, 0.1);
+
+        assert!((variance - 0.04 / 4.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_drift_rate_tracks_ramp_residual() {
+        let mut stats = TrustStats::new();
+        let dt = 0.1;
+        for step in 0..400 {
+            stats.update_drift(0.5 * step as f64 * dt, 0.9, dt);
+        }
+
+        assert!((stats.drift_rate - 0.5).abs() < 1e-6);
+        assert!(stats.bias > 0.0);
+    }
 }
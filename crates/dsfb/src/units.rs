@@ -0,0 +1,149 @@
+//! Typed wrappers for the three DSFB state quantities
+//!
+//! The observer math works on bare scalars, so nothing stops a rate-valued
+//! threshold from being applied to a phase-valued channel. [`Phase`],
+//! [`Rate`], and [`Slew`] tag a value with the state component it belongs to;
+//! quantities of the same kind add and subtract, any of them scales by a
+//! plain scalar, and moving between kinds requires an explicit time step.
+//! The wrappers are `#[repr(transparent)]` and the untyped API is unchanged.
+
+use core::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+use crate::scalar::Real;
+
+macro_rules! quantity {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+        #[repr(transparent)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[cfg_attr(feature = "serde", serde(transparent))]
+        pub struct $name<T: Real = f64>(pub T);
+
+        impl<T: Real> $name<T> {
+            /// Wrap a raw scalar
+            pub fn new(value: T) -> Self {
+                Self(value)
+            }
+
+            /// The raw scalar
+            pub fn value(self) -> T {
+                self.0
+            }
+
+            /// Absolute value
+            pub fn abs(self) -> Self {
+                Self(self.0.abs())
+            }
+        }
+
+        impl<T: Real> Add for $name<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self(self.0 + rhs.0)
+            }
+        }
+
+        impl<T: Real> Sub for $name<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self(self.0 - rhs.0)
+            }
+        }
+
+        impl<T: Real> AddAssign for $name<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                self.0 += rhs.0;
+            }
+        }
+
+        impl<T: Real> SubAssign for $name<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                self.0 -= rhs.0;
+            }
+        }
+
+        impl<T: Real> Neg for $name<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self(-self.0)
+            }
+        }
+
+        impl<T: Real> Mul<T> for $name<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                Self(self.0 * rhs)
+            }
+        }
+
+        impl<T: Real> Div<T> for $name<T> {
+            type Output = Self;
+
+            fn div(self, rhs: T) -> Self {
+                Self(self.0 / rhs)
+            }
+        }
+    };
+}
+
+quantity!(
+    /// Position/phase, the observed quantity and the unit of channel residuals
+    Phase
+);
+quantity!(
+    /// Velocity/frequency (drift), phase per unit time
+    Rate
+);
+quantity!(
+    /// Acceleration/slew, rate per unit time
+    Slew
+);
+
+impl<T: Real> Rate<T> {
+    /// Phase accumulated over `dt` at this rate
+    pub fn integrate(self, dt: T) -> Phase<T> {
+        Phase(self.0 * dt)
+    }
+}
+
+impl<T: Real> Slew<T> {
+    /// Rate accumulated over `dt` at this slew
+    pub fn integrate(self, dt: T) -> Rate<T> {
+        Rate(self.0 * dt)
+    }
+}
+
+impl<T: Real> Phase<T> {
+    /// Average rate of a phase change over `dt`
+    pub fn per(self, dt: T) -> Rate<T> {
+        Rate(self.0 / dt)
+    }
+}
+
+impl<T: Real> Rate<T> {
+    /// Average slew of a rate change over `dt`
+    pub fn per(self, dt: T) -> Slew<T> {
+        Slew(self.0 / dt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integration_moves_between_kinds() {
+        let dt = 0.5;
+        let phase: Phase = Rate(2.0).integrate(dt) + Phase(1.0);
+        assert_eq!(phase, Phase(2.0));
+        assert_eq!(Slew(4.0).integrate(dt), Rate(2.0));
+        assert_eq!(Phase(1.0).per(dt), Rate(2.0));
+        assert_eq!(Rate(1.0).per(dt), Slew(2.0));
+        assert_eq!((-Phase(3.0) * 2.0).abs(), Phase(6.0));
+    }
+}